@@ -4,6 +4,7 @@ use std::path::Path;
 use std::sync::Arc;
 
 use anyhow::Result;
+use async_trait::async_trait;
 use clap::{AppSettings, Arg, Command};
 use tokio::sync::Notify;
 use tokio::time::Duration;
@@ -19,12 +20,27 @@ use webrtc::peer_connection::configuration::RTCConfiguration;
 use webrtc::peer_connection::peer_connection_state::RTCPeerConnectionState;
 use webrtc::peer_connection::sdp::session_description::RTCSessionDescription;
 use webrtc::rtp_transceiver::rtp_codec::RTCRtpCodecCapability;
+use webrtc::rtp_transceiver::rtp_transform::{RTCEncodedFrame, RTCRtpTransform};
 use webrtc::track::track_local::track_local_static_sample::TrackLocalStaticSample;
 use webrtc::track::track_local::TrackLocal;
 use webrtc::Error;
 
 const CIPHER_KEY: u8 = 0xAA;
 
+/// XorCipher encrypts every encoded frame before it is packetized
+struct XorCipher;
+
+#[async_trait]
+impl RTCRtpTransform for XorCipher {
+    async fn transform(
+        &self,
+        mut frame: RTCEncodedFrame,
+    ) -> webrtc::error::Result<Option<RTCEncodedFrame>> {
+        frame.data = frame.data.iter().map(|b| b ^ CIPHER_KEY).collect();
+        Ok(Some(frame))
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let mut app = Command::new("insertable-streams")
@@ -135,6 +151,9 @@ async fn main() -> Result<()> {
         .add_track(Arc::clone(&video_track) as Arc<dyn TrackLocal + Send + Sync>)
         .await?;
 
+    // Encrypt video using XOR Cipher
+    rtp_sender.set_transform(Some(Arc::new(XorCipher)));
+
     // Read incoming RTCP packets
     // Before these packets are returned they are processed by interceptors. For things
     // like NACK this needs to be called.
@@ -165,7 +184,7 @@ async fn main() -> Result<()> {
             ((1000 * header.timebase_numerator) / header.timebase_denominator) as u64,
         );
         loop {
-            let mut frame = match ivf.parse_next_frame() {
+            let frame = match ivf.parse_next_frame() {
                 Ok((frame, _)) => frame,
                 Err(err) => {
                     println!("All video frames parsed and sent: {err}");
@@ -173,11 +192,6 @@ async fn main() -> Result<()> {
                 }
             };
 
-            // Encrypt video using XOR Cipher
-            for b in &mut frame[..] {
-                *b ^= CIPHER_KEY;
-            }
-
             tokio::time::sleep(sleep_time).await;
 
             video_track
@@ -3,6 +3,7 @@ use std::time::Duration;
 
 use util::vnet::net::*;
 
+use super::agent_selection_policy::*;
 use super::*;
 use crate::error::*;
 use crate::mdns::*;
@@ -155,6 +156,16 @@ pub struct AgentConfig {
 
     /// Include loopback addresses in the candidate list.
     pub include_loopback: bool,
+
+    /// Enables the renomination ICE option. The controlling agent keeps checking new candidate
+    /// pairs after one has been selected and nominates a better one, tagging every nomination with
+    /// an increasing NOMINATION attribute. The controlled agent switches to the pair carrying the
+    /// highest nomination value. Both agents need to enable it.
+    pub renomination: bool,
+
+    /// Decides when the controlling agent renominates. Defaults to
+    /// [`DefaultPairSelectionPolicy`] if unset. Only used when `renomination` is enabled.
+    pub pair_selection_policy: Option<Arc<dyn PairSelectionPolicy + Send + Sync>>,
 }
 
 impl AgentConfig {
@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::net::{Ipv4Addr, Ipv6Addr};
 use std::str::FromStr;
 use std::sync::Arc;
@@ -232,7 +233,7 @@ impl Agent {
             return;
         }
 
-        let ips = local_interfaces(
+        let networks = local_networks(
             &net,
             &interface_filter,
             &ip_filter,
@@ -240,7 +241,14 @@ impl Agent {
             include_loopback,
         )
         .await;
-        for ip in ips {
+        // The local preference of the host candidates must be unique.
+        let mut local_preferences = HashSet::new();
+        for (ip, adapter) in networks {
+            let mut local_preference = adapter.local_preference(&ip);
+            while !local_preferences.insert(local_preference) && local_preference > 1 {
+                local_preference -= 1;
+            }
+
             let mut mapped_ip = ip;
 
             if mdns_mode != MulticastDnsMode::QueryAndGather && ext_ip_mapper.is_some() {
@@ -326,6 +334,9 @@ impl Agent {
                         port,
                         component: COMPONENT_RTP,
                         conn: Some(conn),
+                        local_preference,
+                        network_id: adapter.id,
+                        network_cost: adapter.adapter_type.network_cost(),
                         ..CandidateBaseConfig::default()
                     },
                     ..CandidateHostConfig::default()
@@ -1,8 +1,9 @@
-use portable_atomic::{AtomicBool, AtomicU64};
+use portable_atomic::{AtomicBool, AtomicU32, AtomicU64};
 
 use arc_swap::ArcSwapOption;
 use util::sync::Mutex as SyncMutex;
 
+use super::agent_selection_policy::*;
 use super::agent_transport::*;
 use super::*;
 use crate::candidate::candidate_base::CandidateBaseConfig;
@@ -47,8 +48,15 @@ pub struct AgentInternal {
 
     pub(crate) start_time: SyncMutex<Instant>,
     pub(crate) nominated_pair: Mutex<Option<Arc<CandidatePair>>>,
+    // The last NOMINATION value sent when controlling, or accepted when controlled
+    pub(crate) nomination: AtomicU32,
+    // Cleared if the remote agent doesn't support renomination
+    pub(crate) renomination: AtomicBool,
 
     pub(crate) connection_state: AtomicU8, //ConnectionState,
+    pub(crate) gathering_state: Arc<AtomicU8>, //GatheringState,
+    // Set once the remote agent signalled it has no more candidates to trickle
+    pub(crate) remote_end_of_candidates: AtomicBool,
 
     pub(crate) started_ch_tx: Mutex<Option<broadcast::Sender<()>>>,
 
@@ -65,6 +73,7 @@ pub struct AgentInternal {
 
     // the following variables won't be changed after init_with_defaults()
     pub(crate) insecure_skip_verify: bool,
+    pub(crate) pair_selection_policy: Arc<dyn PairSelectionPolicy + Send + Sync>,
     pub(crate) max_binding_requests: u16,
     pub(crate) host_acceptance_min_wait: Duration,
     pub(crate) srflx_acceptance_min_wait: Duration,
@@ -118,10 +127,18 @@ impl AgentInternal {
 
             start_time: SyncMutex::new(Instant::now()),
             nominated_pair: Mutex::new(None),
+            nomination: AtomicU32::new(0),
 
             connection_state: AtomicU8::new(ConnectionState::New as u8),
+            gathering_state: Arc::new(AtomicU8::new(0)), //GatheringState::New,
+            remote_end_of_candidates: AtomicBool::new(false),
 
             insecure_skip_verify: config.insecure_skip_verify,
+            renomination: AtomicBool::new(config.renomination),
+            pair_selection_policy: config
+                .pair_selection_policy
+                .clone()
+                .unwrap_or_else(|| Arc::new(DefaultPairSelectionPolicy::default())),
 
             started_ch_tx: Mutex::new(Some(started_ch_tx)),
 
@@ -231,6 +248,17 @@ impl AgentInternal {
                 *last_connection_state = self.connection_state.load(Ordering::SeqCst).into();
                 return;
             }
+
+            // Once both agents are done gathering no candidate pair can show up anymore, so
+            // ICE has failed as soon as all the checks did (RFC 8838 Section 8)
+            if self.remote_end_of_candidates.load(Ordering::SeqCst)
+                && self.gathering_state.load(Ordering::SeqCst) == GatheringState::Complete as u8
+                && self.all_candidate_pairs_failed().await
+            {
+                self.update_connection_state(ConnectionState::Failed).await;
+                *last_connection_state = self.connection_state.load(Ordering::SeqCst).into();
+                return;
+            }
         }
 
         self.contact_candidates().await;
@@ -337,7 +365,12 @@ impl AgentInternal {
 
         if let Some(p) = p {
             p.nominated.store(true, Ordering::SeqCst);
-            self.agent_conn.selected_pair.store(Some(p));
+            if let Some(previous) = self.agent_conn.selected_pair.swap(Some(Arc::clone(&p))) {
+                // Renomination replaced the previously selected pair
+                if !Arc::ptr_eq(&previous, &p) {
+                    previous.nominated.store(false, Ordering::SeqCst);
+                }
+            }
 
             self.update_connection_state(ConnectionState::Connected)
                 .await;
@@ -369,6 +402,7 @@ impl AgentInternal {
         )> = vec![];
 
         {
+            let selected_pair = self.agent_conn.get_selected_pair();
             let mut checklist = self.agent_conn.checklist.lock().await;
             if checklist.is_empty() {
                 log::warn!(
@@ -381,6 +415,14 @@ impl AgentInternal {
                 if p_state == CandidatePairState::Waiting as u8 {
                     p.state
                         .store(CandidatePairState::InProgress as u8, Ordering::SeqCst);
+                } else if p_state == CandidatePairState::Succeeded as u8
+                    && self.renomination.load(Ordering::SeqCst)
+                {
+                    // Keep checking succeeded pairs so that their round trip time stays current
+                    // for the pair selection policy, the selected one is kept alive separately
+                    if selected_pair.as_ref().is_some_and(|selected| selected == p) {
+                        continue;
+                    }
                 } else if p_state != CandidatePairState::InProgress as u8 {
                     continue;
                 }
@@ -435,6 +477,16 @@ impl AgentInternal {
         None
     }
 
+    /// Returns true if the checklist has pairs and the connectivity checks of all of them
+    /// failed.
+    async fn all_candidate_pairs_failed(&self) -> bool {
+        let checklist = self.agent_conn.checklist.lock().await;
+        !checklist.is_empty()
+            && checklist
+                .iter()
+                .all(|p| p.state.load(Ordering::SeqCst) == CandidatePairState::Failed as u8)
+    }
+
     /// Checks if the selected pair is (still) valid.
     /// Note: the caller should hold the agent lock.
     pub(crate) async fn validate_selected_pair(&self) -> bool {
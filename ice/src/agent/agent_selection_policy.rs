@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use crate::candidate::*;
+
+/// The RTT improvement a pair must show before [`DefaultPairSelectionPolicy`] switches to it.
+pub const DEFAULT_MIN_RTT_IMPROVEMENT: Duration = Duration::from_millis(30);
+
+/// Decides whether the controlling agent should renominate away from the selected candidate pair.
+///
+/// The policy is only consulted when renomination is enabled (see `AgentConfig::renomination`).
+/// Both pairs passed to it have succeeded their connectivity checks and passed the acceptance
+/// wait for their candidate types.
+pub trait PairSelectionPolicy {
+    /// Returns true if `candidate` should replace `selected` as the selected candidate pair.
+    fn should_switch(&self, selected: &CandidatePair, candidate: &CandidatePair) -> bool;
+}
+
+/// Prefers pairs with a lower network cost and, among pairs of equal cost, a clearly lower RTT.
+///
+/// The network cost of a pair is first the number of its candidates that are relayed, so a direct
+/// path that comes up after a relay pair was nominated replaces it, then the sum of the network
+/// costs of its candidates, so a pair over Wi-Fi replaces one over cellular data.
+#[derive(Debug, Clone, Copy)]
+pub struct DefaultPairSelectionPolicy {
+    /// How much lower the RTT of an equal-cost pair must be before switching to it.
+    pub min_rtt_improvement: Duration,
+}
+
+impl Default for DefaultPairSelectionPolicy {
+    fn default() -> Self {
+        Self {
+            min_rtt_improvement: DEFAULT_MIN_RTT_IMPROVEMENT,
+        }
+    }
+}
+
+impl DefaultPairSelectionPolicy {
+    fn network_cost(pair: &CandidatePair) -> (u8, u32) {
+        (
+            u8::from(pair.local.candidate_type() == CandidateType::Relay)
+                + u8::from(pair.remote.candidate_type() == CandidateType::Relay),
+            u32::from(pair.local.network_cost()) + u32::from(pair.remote.network_cost()),
+        )
+    }
+}
+
+impl PairSelectionPolicy for DefaultPairSelectionPolicy {
+    fn should_switch(&self, selected: &CandidatePair, candidate: &CandidatePair) -> bool {
+        let (selected_cost, candidate_cost) =
+            (Self::network_cost(selected), Self::network_cost(candidate));
+        if candidate_cost != selected_cost {
+            return candidate_cost < selected_cost;
+        }
+
+        match (
+            selected.current_round_trip_time(),
+            candidate.current_round_trip_time(),
+        ) {
+            (Some(selected_rtt), Some(candidate_rtt)) => {
+                candidate_rtt + self.min_rtt_improvement < selected_rtt
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::agent_selection_policy::*;
+use crate::candidate::candidate_base::*;
+use crate::candidate::candidate_host::*;
+use crate::candidate::candidate_relay::*;
+use crate::candidate::*;
+use crate::error::Result;
+use crate::network_type::AdapterType;
+
+fn host_candidate(address: &str, port: u16) -> Result<Arc<dyn Candidate + Send + Sync>> {
+    host_candidate_with_cost(address, port, 0)
+}
+
+fn host_candidate_with_cost(
+    address: &str,
+    port: u16,
+    network_cost: u16,
+) -> Result<Arc<dyn Candidate + Send + Sync>> {
+    let config = CandidateHostConfig {
+        base_config: CandidateBaseConfig {
+            network: "udp".to_owned(),
+            address: address.to_owned(),
+            port,
+            component: 1,
+            network_cost,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    Ok(Arc::new(config.new_candidate_host()?))
+}
+
+fn relay_candidate(address: &str, port: u16) -> Result<Arc<dyn Candidate + Send + Sync>> {
+    let config = CandidateRelayConfig {
+        base_config: CandidateBaseConfig {
+            network: "udp".to_owned(),
+            address: address.to_owned(),
+            port,
+            component: 1,
+            ..Default::default()
+        },
+        rel_addr: "4.3.2.1".to_owned(),
+        rel_port: 43210,
+        ..Default::default()
+    };
+    Ok(Arc::new(config.new_candidate_relay()?))
+}
+
+fn pair(
+    local: Arc<dyn Candidate + Send + Sync>,
+    remote: Arc<dyn Candidate + Send + Sync>,
+    rtt: Option<Duration>,
+) -> CandidatePair {
+    let p = CandidatePair::new(local, remote, true);
+    if let Some(rtt) = rtt {
+        p.update_round_trip_time(rtt);
+    }
+    p
+}
+
+#[test]
+fn test_default_policy_prefers_direct_pair() -> Result<()> {
+    let policy = DefaultPairSelectionPolicy::default();
+
+    let relayed = pair(
+        host_candidate("192.168.1.1", 1000)?,
+        relay_candidate("1.2.3.4", 2000)?,
+        Some(Duration::from_millis(20)),
+    );
+    let direct = pair(
+        host_candidate("192.168.1.1", 1000)?,
+        host_candidate("192.168.1.2", 3000)?,
+        Some(Duration::from_millis(80)),
+    );
+
+    assert!(
+        policy.should_switch(&relayed, &direct),
+        "a direct pair should replace a relayed one regardless of RTT"
+    );
+    assert!(
+        !policy.should_switch(&direct, &relayed),
+        "a relayed pair should never replace a direct one"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_default_policy_rtt_threshold() -> Result<()> {
+    let policy = DefaultPairSelectionPolicy {
+        min_rtt_improvement: Duration::from_millis(30),
+    };
+
+    let selected = pair(
+        host_candidate("192.168.1.1", 1000)?,
+        host_candidate("192.168.1.2", 2000)?,
+        Some(Duration::from_millis(100)),
+    );
+    let slightly_better = pair(
+        host_candidate("192.168.1.1", 1000)?,
+        host_candidate("192.168.1.3", 3000)?,
+        Some(Duration::from_millis(80)),
+    );
+    let much_better = pair(
+        host_candidate("192.168.1.1", 1000)?,
+        host_candidate("192.168.1.4", 4000)?,
+        Some(Duration::from_millis(20)),
+    );
+    let unmeasured = pair(
+        host_candidate("192.168.1.1", 1000)?,
+        host_candidate("192.168.1.5", 5000)?,
+        None,
+    );
+
+    assert!(!policy.should_switch(&selected, &slightly_better));
+    assert!(policy.should_switch(&selected, &much_better));
+    assert!(!policy.should_switch(&selected, &unmeasured));
+
+    Ok(())
+}
+
+#[test]
+fn test_default_policy_prefers_cheaper_network() -> Result<()> {
+    let policy = DefaultPairSelectionPolicy::default();
+
+    let cellular = pair(
+        host_candidate_with_cost("10.0.0.1", 1000, AdapterType::Cellular.network_cost())?,
+        host_candidate("192.168.1.2", 2000)?,
+        Some(Duration::from_millis(20)),
+    );
+    let wifi = pair(
+        host_candidate_with_cost("192.168.1.1", 1000, AdapterType::Wifi.network_cost())?,
+        host_candidate("192.168.1.2", 2000)?,
+        Some(Duration::from_millis(80)),
+    );
+    let relayed_wifi = pair(
+        host_candidate_with_cost("192.168.1.1", 1000, AdapterType::Wifi.network_cost())?,
+        relay_candidate("1.2.3.4", 3000)?,
+        Some(Duration::from_millis(20)),
+    );
+
+    assert!(policy.should_switch(&cellular, &wifi));
+    assert!(!policy.should_switch(&wifi, &cellular));
+    assert!(
+        policy.should_switch(&relayed_wifi, &cellular),
+        "relaying costs more than any network"
+    );
+
+    Ok(())
+}
@@ -14,6 +14,7 @@ use tokio::time::{Duration, Instant};
 use crate::agent::agent_internal::*;
 use crate::candidate::*;
 use crate::control::*;
+use crate::nomination::*;
 use crate::priority::*;
 use crate::use_candidate::*;
 
@@ -120,19 +121,26 @@ impl AgentInternal {
                     let ufrag_pwd = self.ufrag_pwd.lock().await;
                     let username =
                         ufrag_pwd.remote_ufrag.clone() + ":" + ufrag_pwd.local_ufrag.as_str();
-                    let mut msg = Message::new();
-                    let result = msg.build(&[
+                    let mut setters: Vec<Box<dyn Setter>> = vec![
                         Box::new(BINDING_REQUEST),
                         Box::new(TransactionId::new()),
                         Box::new(Username::new(ATTR_USERNAME, username)),
                         Box::<UseCandidateAttr>::default(),
                         Box::new(AttrControlling(self.tie_breaker.load(Ordering::SeqCst))),
                         Box::new(PriorityAttr(pair.local.priority())),
-                        Box::new(MessageIntegrity::new_short_term_integrity(
-                            ufrag_pwd.remote_pwd.clone(),
-                        )),
-                        Box::new(FINGERPRINT),
-                    ]);
+                    ];
+                    if self.renomination.load(Ordering::SeqCst) {
+                        setters.push(Box::new(NominationAttr(
+                            self.nomination.load(Ordering::SeqCst),
+                        )));
+                    }
+                    setters.push(Box::new(MessageIntegrity::new_short_term_integrity(
+                        ufrag_pwd.remote_pwd.clone(),
+                    )));
+                    setters.push(Box::new(FINGERPRINT));
+
+                    let mut msg = Message::new();
+                    let result = msg.build(&setters);
                     (msg, result)
                 };
 
@@ -159,6 +167,71 @@ impl AgentInternal {
         }
     }
 
+    /// Marks `p` as the pair to nominate, bumping the NOMINATION value if renomination is enabled.
+    async fn set_nominated_pair(&self, p: Arc<CandidatePair>) {
+        let mut nominated_pair = self.nominated_pair.lock().await;
+        if self.renomination.load(Ordering::SeqCst) {
+            self.nomination.fetch_add(1, Ordering::SeqCst);
+        }
+        *nominated_pair = Some(p);
+    }
+
+    /// Nominates a better pair than the selected one, as judged by the pair selection policy.
+    /// Only used by the controlling agent when renomination is enabled.
+    async fn renominate_pair(&self) {
+        let selected_pair = match self.agent_conn.get_selected_pair() {
+            Some(p) => p,
+            None => return,
+        };
+
+        let nominated_pair = {
+            let nominated_pair = self.nominated_pair.lock().await;
+            nominated_pair.clone()
+        };
+        if let Some(nominated_pair) = nominated_pair {
+            if nominated_pair != selected_pair {
+                // A renomination is in flight, keep sending it until it is answered
+                if nominated_pair.state() == CandidatePairState::Failed {
+                    let mut nominated = self.nominated_pair.lock().await;
+                    *nominated = Some(Arc::clone(&selected_pair));
+                } else {
+                    self.nominate_pair().await;
+                }
+                return;
+            }
+        }
+
+        let mut best = Arc::clone(&selected_pair);
+        {
+            let checklist = self.agent_conn.checklist.lock().await;
+            for p in &*checklist {
+                if p.state() != CandidatePairState::Succeeded
+                    || **p == *best
+                    || !self.is_nominatable(&p.local)
+                    || !self.is_nominatable(&p.remote)
+                {
+                    continue;
+                }
+                if self.pair_selection_policy.should_switch(&best, p) {
+                    best = Arc::clone(p);
+                }
+            }
+        }
+
+        if best != selected_pair {
+            log::debug!(
+                "[{}]: renominating from ({}, {}) to ({}, {})",
+                self.get_name(),
+                selected_pair.local,
+                selected_pair.remote,
+                best.local,
+                best.remote
+            );
+            self.set_nominated_pair(best).await;
+            self.nominate_pair().await;
+        }
+    }
+
     pub(crate) async fn start(&self) {
         if self.is_controlling.load(Ordering::SeqCst) {
             ControllingSelector::start(self).await;
@@ -222,6 +295,7 @@ impl ControllingSelector for AgentInternal {
             let mut nominated_pair = self.nominated_pair.lock().await;
             *nominated_pair = None;
         }
+        self.nomination.store(0, Ordering::SeqCst);
         *self.start_time.lock() = Instant::now();
     }
 
@@ -241,6 +315,12 @@ impl ControllingSelector for AgentInternal {
             if self.validate_selected_pair().await {
                 log::trace!("[{}]: checking keepalive", self.get_name());
                 self.check_keepalive().await;
+
+                if self.renomination.load(Ordering::SeqCst) {
+                    // Keep checking pairs that came up after the selection, they may be better
+                    self.ping_all_candidates().await;
+                    self.renominate_pair().await;
+                }
             }
         } else if nominated_pair_is_some {
             self.nominate_pair().await;
@@ -260,10 +340,7 @@ impl ControllingSelector for AgentInternal {
                         p.remote.to_string()
                     );
                     p.nominated.store(true, Ordering::SeqCst);
-                    {
-                        let mut nominated_pair = self.nominated_pair.lock().await;
-                        *nominated_pair = Some(p);
-                    }
+                    self.set_nominated_pair(p).await;
                 }
 
                 self.nominate_pair().await;
@@ -325,11 +402,14 @@ impl ControllingSelector for AgentInternal {
                 remote,
                 local
             );
-            let selected_pair_is_none = self.agent_conn.get_selected_pair().is_none();
+            let selected_pair = self.agent_conn.get_selected_pair();
+            let selected_pair_is_none = selected_pair.is_none();
 
             if let Some(p) = self.find_pair(local, remote).await {
                 p.state
                     .store(CandidatePairState::Succeeded as u8, Ordering::SeqCst);
+                p.binding_request_count.store(0, Ordering::SeqCst);
+                p.update_round_trip_time(pending_request.timestamp.elapsed());
                 log::trace!(
                     "Found valid candidate pair: {}, p.state: {}, isUseCandidate: {}, {}",
                     p,
@@ -337,8 +417,18 @@ impl ControllingSelector for AgentInternal {
                     pending_request.is_use_candidate,
                     selected_pair_is_none
                 );
-                if pending_request.is_use_candidate && selected_pair_is_none {
-                    self.set_selected_pair(Some(Arc::clone(&p))).await;
+                if pending_request.is_use_candidate {
+                    let is_renomination = self.renomination.load(Ordering::SeqCst)
+                        && selected_pair.is_some_and(|selected| selected != p)
+                        && self
+                            .nominated_pair
+                            .lock()
+                            .await
+                            .as_ref()
+                            .is_some_and(|nominated| *nominated == p);
+                    if selected_pair_is_none || is_renomination {
+                        self.set_selected_pair(Some(Arc::clone(&p))).await;
+                    }
                 }
             } else {
                 // This shouldn't happen
@@ -390,10 +480,7 @@ impl ControllingSelector for AgentInternal {
                     {
                         log::trace!("The candidate ({}, {}) is the best candidate available, marking it as nominated",
                             p.local, p.remote);
-                        {
-                            let mut nominated_pair = self.nominated_pair.lock().await;
-                            *nominated_pair = Some(p);
-                        }
+                        self.set_nominated_pair(p).await;
                         self.nominate_pair().await;
                     }
                 } else {
@@ -407,6 +494,43 @@ impl ControllingSelector for AgentInternal {
     }
 }
 
+impl AgentInternal {
+    /// Returns true if the controlled agent should act on a USE-CANDIDATE for `p`.
+    ///
+    /// Without renomination only the first nomination counts. With renomination a nomination
+    /// carrying a NOMINATION value higher than any seen before replaces the selected pair.
+    fn accept_nomination(&self, m: &Message, p: &Arc<CandidatePair>) -> bool {
+        let selected_pair = self.agent_conn.get_selected_pair();
+        if !self.renomination.load(Ordering::SeqCst) {
+            return selected_pair.is_none();
+        }
+
+        let mut nomination = NominationAttr::default();
+        if nomination.get_from(m).is_err() {
+            // The remote does not renominate, fall back to regular nomination
+            return selected_pair.is_none();
+        }
+
+        if selected_pair.is_some_and(|selected| selected == *p) {
+            return false;
+        }
+
+        let accepted = self.nomination.load(Ordering::SeqCst);
+        if nomination.0 <= accepted {
+            log::trace!(
+                "[{}]: ignoring stale nomination {} for {}, already accepted {}",
+                self.get_name(),
+                nomination.0,
+                p,
+                accepted
+            );
+            return false;
+        }
+        self.nomination.store(nomination.0, Ordering::SeqCst);
+        true
+    }
+}
+
 #[async_trait]
 impl ControlledSelector for AgentInternal {
     async fn start(&self) {}
@@ -487,9 +611,15 @@ impl ControlledSelector for AgentInternal {
             if let Some(p) = self.find_pair(local, remote).await {
                 p.state
                     .store(CandidatePairState::Succeeded as u8, Ordering::SeqCst);
+                p.binding_request_count.store(0, Ordering::SeqCst);
+                p.update_round_trip_time(pending_request.timestamp.elapsed());
                 log::trace!("Found valid candidate pair: {}", p);
 
-                if p.nominate_on_binding_success.load(Ordering::SeqCst)
+                if self.renomination.load(Ordering::SeqCst) {
+                    if p.nominate_on_binding_success.swap(false, Ordering::SeqCst) {
+                        self.set_selected_pair(Some(Arc::clone(&p))).await;
+                    }
+                } else if p.nominate_on_binding_success.load(Ordering::SeqCst)
                     && self.agent_conn.get_selected_pair().is_none()
                 {
                     self.set_selected_pair(Some(Arc::clone(&p))).await;
@@ -519,7 +649,7 @@ impl ControlledSelector for AgentInternal {
 
         if let Some(p) = self.find_pair(local, remote).await {
             let use_candidate = m.contains(ATTR_USE_CANDIDATE);
-            if use_candidate {
+            if use_candidate && self.accept_nomination(m, &p) {
                 // https://tools.ietf.org/html/rfc8445#section-7.3.1.5
 
                 if p.state.load(Ordering::SeqCst) == CandidatePairState::Succeeded as u8 {
@@ -527,9 +657,7 @@ impl ControlledSelector for AgentInternal {
                     // previously sent by this pair produced a successful response and
                     // generated a valid pair (Section 7.2.5.3.2).  The agent sets the
                     // nominated flag value of the valid pair to true.
-                    if self.agent_conn.get_selected_pair().is_none() {
-                        self.set_selected_pair(Some(Arc::clone(&p))).await;
-                    }
+                    self.set_selected_pair(Some(Arc::clone(&p))).await;
                 } else {
                     // If the received Binding request triggered a new check to be
                     // enqueued in the triggered-check queue (Section 7.3.1.4), once the
@@ -18,6 +18,7 @@ use crate::candidate::candidate_peer_reflexive::*;
 use crate::candidate::candidate_relay::*;
 use crate::candidate::candidate_server_reflexive::*;
 use crate::control::AttrControlling;
+use crate::nomination::NominationAttr;
 use crate::priority::PriorityAttr;
 use crate::use_candidate::UseCandidateAttr;
 
@@ -382,6 +383,127 @@ async fn test_handle_peer_reflexive_unknown_remote() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_controlled_renomination() -> Result<()> {
+    let a = Agent::new(AgentConfig {
+        renomination: true,
+        ..Default::default()
+    })
+    .await?;
+
+    let local: Arc<dyn Candidate + Send + Sync> = Arc::new(
+        CandidateHostConfig {
+            base_config: CandidateBaseConfig {
+                network: "udp".to_owned(),
+                address: "192.168.0.2".to_owned(),
+                port: 777,
+                component: 1,
+                conn: Some(Arc::new(MockConn {})),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+        .new_candidate_host()?,
+    );
+
+    let mut remotes: Vec<Arc<dyn Candidate + Send + Sync>> = vec![];
+    for (address, port) in [("172.17.0.3", 999), ("172.17.0.4", 888)] {
+        let remote: Arc<dyn Candidate + Send + Sync> = Arc::new(
+            CandidateHostConfig {
+                base_config: CandidateBaseConfig {
+                    network: "udp".to_owned(),
+                    address: address.to_owned(),
+                    port,
+                    component: 1,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+            .new_candidate_host()?,
+        );
+        a.internal.add_remote_candidate(&remote).await;
+        a.internal.add_pair(local.clone(), remote.clone()).await;
+        if let Some(p) = a.internal.find_pair(&local, &remote).await {
+            p.state
+                .store(CandidatePairState::Succeeded as u8, Ordering::SeqCst);
+        }
+        remotes.push(remote);
+    }
+
+    let (username, local_pwd, tie_breaker) = {
+        let ufrag_pwd = a.internal.ufrag_pwd.lock().await;
+        (
+            ufrag_pwd.local_ufrag.to_owned() + ":" + ufrag_pwd.remote_ufrag.as_str(),
+            ufrag_pwd.local_pwd.clone(),
+            a.internal.tie_breaker.load(Ordering::SeqCst),
+        )
+    };
+
+    let nominate = |remote: &Arc<dyn Candidate + Send + Sync>, nomination: u32| {
+        let mut msg = Message::new();
+        msg.build(&[
+            Box::new(BINDING_REQUEST),
+            Box::new(TransactionId::new()),
+            Box::new(Username::new(ATTR_USERNAME, username.clone())),
+            Box::new(UseCandidateAttr::new()),
+            Box::new(AttrControlling(tie_breaker)),
+            Box::new(PriorityAttr(local.priority())),
+            Box::new(NominationAttr(nomination)),
+            Box::new(MessageIntegrity::new_short_term_integrity(
+                local_pwd.clone(),
+            )),
+            Box::new(FINGERPRINT),
+        ])
+        .map(|_| (msg, remote.addr()))
+    };
+
+    let (mut msg, addr) = nominate(&remotes[0], 1)?;
+    a.internal.handle_inbound(&mut msg, &local, addr).await;
+    let selected = a
+        .get_selected_candidate_pair()
+        .expect("pair should be selected");
+    assert!(selected.remote.equal(&*remotes[0]), "first nomination");
+
+    let (mut msg, addr) = nominate(&remotes[1], 2)?;
+    a.internal.handle_inbound(&mut msg, &local, addr).await;
+    let selected = a
+        .get_selected_candidate_pair()
+        .expect("pair should be selected");
+    assert!(
+        selected.remote.equal(&*remotes[1]),
+        "higher nomination wins"
+    );
+
+    let (mut msg, addr) = nominate(&remotes[0], 1)?;
+    a.internal.handle_inbound(&mut msg, &local, addr).await;
+    let selected = a
+        .get_selected_candidate_pair()
+        .expect("pair should be selected");
+    assert!(
+        selected.remote.equal(&*remotes[1]),
+        "stale nomination must be ignored"
+    );
+
+    // Succeeded pairs other than the selected one are still checked
+    a.internal.ping_all_candidates().await;
+    for (remote, expected) in remotes.iter().zip([1, 0]) {
+        let p = a
+            .internal
+            .find_pair(&local, remote)
+            .await
+            .expect("pair should exist");
+        assert_eq!(
+            p.binding_request_count.load(Ordering::SeqCst),
+            expected,
+            "{p}"
+        );
+        assert_eq!(p.state(), CandidatePairState::Succeeded);
+    }
+
+    a.close().await?;
+    Ok(())
+}
+
 //use std::io::Write;
 
 // Assert that Agent on startup sends message, and doesn't wait for connectivityTicker to fire
@@ -1770,6 +1892,99 @@ async fn test_connection_state_connecting_to_failed() -> Result<()> {
     Ok(())
 }
 
+// Assert that once the remote agent signalled the end of its candidates, the ICE Agent fails as
+// soon as all the candidate pairs did instead of waiting for the failed timeout
+#[tokio::test]
+async fn test_remote_end_of_candidates_fails_early() -> Result<()> {
+    let thirty_seconds = Duration::from_secs(30);
+
+    let agent = Arc::new(
+        Agent::new(AgentConfig {
+            network_types: vec![NetworkType::Udp4],
+            disconnected_timeout: Some(thirty_seconds),
+            failed_timeout: Some(thirty_seconds),
+            max_binding_requests: Some(2),
+            check_interval: Duration::from_millis(50),
+            ..Default::default()
+        })
+        .await?,
+    );
+
+    let (failed_tx, mut failed_rx) = mpsc::channel::<()>(1);
+    let failed_tx = Arc::new(Mutex::new(Some(failed_tx)));
+    agent.on_connection_state_change(Box::new(move |c: ConnectionState| {
+        let failed_tx_clone = Arc::clone(&failed_tx);
+        Box::pin(async move {
+            if c == ConnectionState::Failed {
+                let mut tx = failed_tx_clone.lock().await;
+                tx.take();
+            }
+        })
+    }));
+
+    let (gathered_tx, mut gathered_rx) = mpsc::channel::<()>(1);
+    let gathered_tx = Arc::new(Mutex::new(Some(gathered_tx)));
+    agent.on_candidate(Box::new(
+        move |c: Option<Arc<dyn Candidate + Send + Sync>>| {
+            let gathered_tx_clone = Arc::clone(&gathered_tx);
+            Box::pin(async move {
+                if c.is_none() {
+                    let mut tx = gathered_tx_clone.lock().await;
+                    tx.take();
+                }
+            })
+        },
+    ));
+    agent.gather_candidates()?;
+    let _ = gathered_rx.recv().await;
+
+    let local_candidates = agent.get_local_candidates().await?;
+    let Some(local) = local_candidates.first() else {
+        // No interface to gather on
+        agent.close().await?;
+        return Ok(());
+    };
+
+    // A remote candidate which never answers the connectivity checks
+    let silent = tokio::net::UdpSocket::bind(SocketAddr::new(local.addr().ip(), 0)).await?;
+    let remote: Arc<dyn Candidate + Send + Sync> = Arc::new(
+        CandidateHostConfig {
+            base_config: CandidateBaseConfig {
+                network: "udp".to_owned(),
+                address: local.addr().ip().to_string(),
+                port: silent.local_addr()?.port(),
+                component: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+        .new_candidate_host()?,
+    );
+
+    let agent_clone = Arc::clone(&agent);
+    tokio::spawn(async move {
+        let (_cancel_tx, cancel_rx) = mpsc::channel(1);
+        let result = agent_clone
+            .dial(cancel_rx, "InvalidFrag".to_owned(), "InvalidPwd".to_owned())
+            .await;
+        assert!(result.is_err());
+    });
+
+    agent.add_remote_candidate(&remote)?;
+    agent.add_remote_end_of_candidates();
+
+    assert!(
+        tokio::time::timeout(Duration::from_secs(10), failed_rx.recv())
+            .await
+            .is_ok(),
+        "the agent should fail before the failed timeout"
+    );
+
+    agent.close().await?;
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_agent_restart_during_gather() -> Result<()> {
     //"Restart During Gather"
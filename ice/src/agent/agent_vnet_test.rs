@@ -206,8 +206,9 @@ impl TestAuthHandler {
     }
 }
 
+#[async_trait]
 impl turn::auth::AuthHandler for TestAuthHandler {
-    fn auth_handle(
+    async fn auth_handle(
         &self,
         username: &str,
         _realm: &str,
@@ -239,11 +240,20 @@ pub(crate) async fn add_vnet_stun(wan_net: Arc<net::Net>) -> Result<turn::server
                     net: wan_net,
                 },
             ),
+            nat_discovery: None,
         }],
         realm: "webrtc.rs".to_owned(),
         auth_handler: Arc::new(TestAuthHandler::new()),
         channel_bind_timeout: Duration::from_secs(0),
         alloc_close_notify: None,
+        peer_address_policy: turn::server::config::PeerAddressPolicy {
+            allow_internal: true,
+            ..Default::default()
+        },
+        quota: turn::server::config::QuotaConfig::default(),
+        relay_handler: None,
+        store: None,
+        drain_timeout: Duration::from_secs(0),
     })
     .await?;
 
@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod agent_gather_test;
 #[cfg(test)]
+mod agent_selection_policy_test;
+#[cfg(test)]
 mod agent_test;
 #[cfg(test)]
 mod agent_transport_test;
@@ -10,6 +12,7 @@ pub(crate) mod agent_vnet_test;
 pub mod agent_config;
 pub mod agent_gather;
 pub(crate) mod agent_internal;
+pub mod agent_selection_policy;
 pub mod agent_selector;
 pub mod agent_stats;
 pub mod agent_transport;
@@ -197,6 +200,7 @@ impl Agent {
             Arc::new(Net::new(None))
         };
 
+        let gathering_state = Arc::clone(&ai.gathering_state);
         let agent = Self {
             udp_network: config.udp_network,
             internal: Arc::new(ai),
@@ -208,7 +212,7 @@ impl Agent {
             mdns_conn,
             net,
             ext_ip_mapper: Arc::new(ext_ip_mapper),
-            gathering_state: Arc::new(AtomicU8::new(0)), //GatheringState::New,
+            gathering_state,
             candidate_types,
             urls: config.urls.clone(),
             network_types: config.network_types.clone(),
@@ -326,6 +330,14 @@ impl Agent {
         Ok(res)
     }
 
+    /// Signals that the remote agent has no more candidates to trickle, so that the connection
+    /// fails as soon as all the candidate pairs did, instead of waiting for the failed timeout.
+    pub fn add_remote_end_of_candidates(&self) {
+        self.internal
+            .remote_end_of_candidates
+            .store(true, Ordering::SeqCst);
+    }
+
     /// Returns the local user credentials.
     pub async fn get_local_user_credentials(&self) -> (String, String) {
         let ufrag_pwd = self.internal.ufrag_pwd.lock().await;
@@ -360,6 +372,12 @@ impl Agent {
         self.internal.agent_conn.get_selected_pair()
     }
 
+    /// Turns off renomination enabled with [`AgentConfig::renomination`], for when the remote
+    /// agent turns out not to support it. Call it before [`Agent::dial`] or [`Agent::accept`].
+    pub fn disable_renomination(&self) {
+        self.internal.renomination.store(false, Ordering::SeqCst);
+    }
+
     /// Sets the credentials of the remote agent.
     pub async fn set_remote_credentials(
         &self,
@@ -414,6 +432,9 @@ impl Agent {
             ufrag_pwd.remote_ufrag = String::new();
             ufrag_pwd.remote_pwd = String::new();
         }
+        self.internal
+            .remote_end_of_candidates
+            .store(false, Ordering::SeqCst);
         {
             let mut pending_binding_requests = self.internal.pending_binding_requests.lock().await;
             *pending_binding_requests = vec![];
@@ -26,7 +26,13 @@ pub struct CandidateBaseConfig {
     pub port: u16,
     pub component: u16,
     pub priority: u32,
+    /// The local preference used to compute the priority, 0 to use the default one.
+    pub local_preference: u16,
     pub foundation: String,
+    /// Identifies the network the candidate was gathered on (`network-id`), 0 if unknown.
+    pub network_id: u16,
+    /// The cost of the network the candidate was gathered on (`network-cost`).
+    pub network_cost: u16,
     pub conn: Option<Arc<dyn util::Conn + Send + Sync>>,
     pub initialized_ch: Option<broadcast::Receiver<()>>,
 }
@@ -52,6 +58,10 @@ pub struct CandidateBase {
 
     pub(crate) foundation_override: String,
     pub(crate) priority_override: u32,
+    pub(crate) local_preference_override: u16,
+
+    pub(crate) network_id: u16,
+    pub(crate) network_cost: u16,
 
     //CandidateHost
     pub(crate) network: String,
@@ -82,6 +92,9 @@ impl Default for CandidateBase {
 
             foundation_override: String::new(),
             priority_override: 0,
+            local_preference_override: 0,
+            network_id: 0,
+            network_cost: 0,
             network: String::new(),
             relay_client: None,
         }
@@ -189,6 +202,16 @@ impl Candidate for CandidateBase {
             + (256 - u32::from(self.component()))
     }
 
+    /// Returns the identifier of the network the candidate was gathered on.
+    fn network_id(&self) -> u16 {
+        self.network_id
+    }
+
+    /// Returns the cost of the network the candidate was gathered on.
+    fn network_cost(&self) -> u16 {
+        self.network_cost
+    }
+
     /// Returns `Option<CandidateRelatedAddress>`.
     fn related_address(&self) -> Option<CandidateRelatedAddress> {
         self.related_address.as_ref().cloned()
@@ -228,6 +251,14 @@ impl Candidate for CandidateBase {
             .as_str();
         }
 
+        if self.network_id != 0 {
+            val += format!(" network-id {}", self.network_id).as_str();
+        }
+
+        if self.network_cost != 0 {
+            val += format!(" network-cost {}", self.network_cost).as_str();
+        }
+
         val
     }
 
@@ -360,7 +391,11 @@ impl CandidateBase {
             // other-pref is the preference for the particular IP address from which
             // the candidate was obtained.  When there is only a single IP address,
             // this value SHOULD be set to the maximum allowed value (8191).
-            let other_pref: u16 = 8191;
+            let other_pref: u16 = if self.local_preference_override != 0 {
+                self.local_preference_override >> 3
+            } else {
+                8191
+            };
 
             let direction_pref: u16 = match self.candidate_type() {
                 CandidateType::Host | CandidateType::Relay => match self.tcp_type() {
@@ -381,6 +416,8 @@ impl CandidateBase {
             };
 
             (1 << 13) * direction_pref + other_pref
+        } else if self.local_preference_override != 0 {
+            self.local_preference_override
         } else {
             DEFAULT_LOCAL_PREFERENCE
         }
@@ -421,32 +458,41 @@ pub fn unmarshal_candidate(raw: &str) -> Result<impl Candidate> {
     let mut rel_addr = String::new();
     let mut rel_port = 0;
     let mut tcp_type = TcpType::Unspecified;
+    let mut network_id = 0;
+    let mut network_cost = 0;
+
+    // The extensions are key/value pairs following the candidate type.
+    let mut extensions = split[8..].iter();
+    while let Some(key) = extensions.next() {
+        let Some(value) = extensions.next() else {
+            return Err(Error::Other(format!(
+                "{:?}: no value for {}",
+                Error::ErrParseExtension,
+                key
+            )));
+        };
 
-    if split.len() > 8 {
-        let split2 = &split[8..];
-
-        if split2[0] == "raddr" {
-            if split2.len() < 4 {
-                return Err(Error::Other(format!(
-                    "{:?}: incorrect length",
-                    Error::ErrParseRelatedAddr
-                )));
-            }
-
-            // RelatedAddress
-            split2[1].clone_into(&mut rel_addr);
-
-            // RelatedPort
-            rel_port = split2[3].parse()?;
-        } else if split2[0] == "tcptype" {
-            if split2.len() < 2 {
-                return Err(Error::Other(format!(
-                    "{:?}: incorrect length",
-                    Error::ErrParseType
-                )));
+        match *key {
+            "raddr" => {
+                // RelatedAddress
+                (*value).clone_into(&mut rel_addr);
+
+                // RelatedPort
+                match (extensions.next(), extensions.next()) {
+                    (Some(&"rport"), Some(port)) => rel_port = port.parse()?,
+                    _ => {
+                        return Err(Error::Other(format!(
+                            "{:?}: incorrect length",
+                            Error::ErrParseRelatedAddr
+                        )))
+                    }
+                }
             }
-
-            tcp_type = TcpType::from(split2[1]);
+            "tcptype" => tcp_type = TcpType::from(*value),
+            "network-id" => network_id = value.parse()?,
+            "network-cost" => network_cost = value.parse()?,
+            // Other extensions, such as generation or ufrag, are ignored.
+            _ => {}
         }
     }
 
@@ -460,6 +506,8 @@ pub fn unmarshal_candidate(raw: &str) -> Result<impl Candidate> {
                     component,
                     priority,
                     foundation,
+                    network_id,
+                    network_cost,
                     ..CandidateBaseConfig::default()
                 },
                 tcp_type,
@@ -475,6 +523,8 @@ pub fn unmarshal_candidate(raw: &str) -> Result<impl Candidate> {
                     component,
                     priority,
                     foundation,
+                    network_id,
+                    network_cost,
                     ..CandidateBaseConfig::default()
                 },
                 rel_addr,
@@ -491,6 +541,8 @@ pub fn unmarshal_candidate(raw: &str) -> Result<impl Candidate> {
                     component,
                     priority,
                     foundation,
+                    network_id,
+                    network_cost,
                     ..CandidateBaseConfig::default()
                 },
                 rel_addr,
@@ -508,6 +560,8 @@ pub fn unmarshal_candidate(raw: &str) -> Result<impl Candidate> {
                     component,
                     priority,
                     foundation,
+                    network_id,
+                    network_cost,
                     ..CandidateBaseConfig::default()
                 },
                 rel_addr,
@@ -13,8 +13,9 @@ use crate::url::{ProtoType, SchemeType, Url};
 
 pub(crate) struct OptimisticAuthHandler;
 
+#[async_trait]
 impl AuthHandler for OptimisticAuthHandler {
-    fn auth_handle(
+    async fn auth_handle(
         &self,
         _username: &str,
         _realm: &str,
@@ -59,9 +60,18 @@ async fn test_relay_only_connection() -> Result<(), Error> {
                 address: "127.0.0.1".to_owned(),
                 net: Arc::new(util::vnet::net::Net::new(None)),
             }),
+            nat_discovery: None,
         }],
         channel_bind_timeout: Duration::from_secs(0),
         alloc_close_notify: None,
+        peer_address_policy: turn::server::config::PeerAddressPolicy {
+            allow_internal: true,
+            ..Default::default()
+        },
+        quota: turn::server::config::QuotaConfig::default(),
+        relay_handler: None,
+        store: None,
+        drain_timeout: Duration::from_secs(0),
     })
     .await?;
 
@@ -40,9 +40,18 @@ async fn test_server_reflexive_only_connection() -> Result<()> {
                 address: "127.0.0.1".to_owned(),
                 net: Arc::new(util::vnet::net::Net::new(None)),
             }),
+            nat_discovery: None,
         }],
         channel_bind_timeout: Duration::from_secs(0),
         alloc_close_notify: None,
+        peer_address_policy: turn::server::config::PeerAddressPolicy {
+            allow_internal: true,
+            ..Default::default()
+        },
+        quota: turn::server::config::QuotaConfig::default(),
+        relay_handler: None,
+        store: None,
+        drain_timeout: Duration::from_secs(0),
     })
     .await?;
 
@@ -13,6 +13,15 @@ fn test_candidate_priority() -> Result<()> {
             },
             2130706431,
         ),
+        (
+            CandidateBase {
+                candidate_type: CandidateType::Host,
+                component: AtomicU16::new(COMPONENT_RTP),
+                local_preference_override: 0xc823,
+                ..Default::default()
+            },
+            2127045631,
+        ),
         (
             CandidateBase {
                 candidate_type: CandidateType::Host,
@@ -374,8 +383,22 @@ fn test_candidate_marshal() -> Result<()> {
             }),
             "1380287402 1 udp 2130706431 e2494022-4d9a-4c1e-a750-cc48d4f8d6ee.local 60542 typ host",
         ),
+        (
+            Some(CandidateBase{
+                    network_type:   AtomicU8::new(NetworkType::Udp4 as u8),
+                    candidate_type: CandidateType::Host,
+                    address:       "10.0.75.1".to_owned(),
+                    port:          53634,
+                    network_id:    2,
+                    network_cost:  10,
+                ..Default::default()
+            }),
+            "4273957277 1 udp 2130706431 10.0.75.1 53634 typ host network-id 2 network-cost 10",
+        ),
         // Invalid candidates
         (None, ""),
+        (None, "4273957277 1 udp 2130706431 10.0.75.1 53634 typ host network-id"),
+        (None, "4273957277 1 udp 2130706431 10.0.75.1 53634 typ host network-cost INVALID"),
         (None, "1938809241"),
         (None, "1986380506 99999999 udp 2122063615 10.0.75.1 53634 typ host generation 0 network-id 2"),
         (None, "1986380506 1 udp 99999999999 10.0.75.1 53634 typ host"),
@@ -409,3 +432,27 @@ fn test_candidate_marshal() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_candidate_unmarshal_extensions() -> Result<()> {
+    let candidate = unmarshal_candidate(
+        "1986380506 1 udp 2122063615 10.0.75.1 53634 typ host generation 0 ufrag abcd network-id 2 network-cost 900",
+    )?;
+    assert_eq!(candidate.network_id(), 2);
+    assert_eq!(candidate.network_cost(), 900);
+
+    let candidate = unmarshal_candidate(
+        "4207374051 1 udp 1685790463 191.228.238.68 53991 typ srflx raddr 192.168.0.1 rport 53991 generation 0 network-id 3 network-cost 10",
+    )?;
+    assert_eq!(
+        candidate.related_address(),
+        Some(CandidateRelatedAddress {
+            address: "192.168.0.1".to_owned(),
+            port: 53991,
+        })
+    );
+    assert_eq!(candidate.network_id(), 3);
+    assert_eq!(candidate.network_cost(), 10);
+
+    Ok(())
+}
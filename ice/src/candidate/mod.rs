@@ -17,11 +17,11 @@ use std::fmt;
 use std::net::{IpAddr, SocketAddr};
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 use async_trait::async_trait;
 use candidate_base::*;
-use portable_atomic::{AtomicBool, AtomicU16, AtomicU8};
+use portable_atomic::{AtomicBool, AtomicU16, AtomicU64, AtomicU8};
 use serde::{Deserialize, Serialize};
 use tokio::sync::{broadcast, Mutex};
 
@@ -67,6 +67,17 @@ pub trait Candidate: fmt::Display {
 
     fn priority(&self) -> u32;
 
+    /// Identifies the network the candidate was gathered on, 0 if unknown.
+    fn network_id(&self) -> u16 {
+        0
+    }
+
+    /// The cost of the network the candidate was gathered on, the higher the
+    /// more expensive. Phones use it to prefer Wi-Fi over cellular data.
+    fn network_cost(&self) -> u16 {
+        0
+    }
+
     /// A transport address related to candidate,
     /// which is useful for diagnostics and other purposes.
     fn related_address(&self) -> Option<CandidateRelatedAddress>;
@@ -235,6 +246,7 @@ pub struct CandidatePair {
     pub(crate) state: AtomicU8, // convert it to CandidatePairState,
     pub(crate) nominated: AtomicBool,
     pub(crate) nominate_on_binding_success: AtomicBool,
+    pub(crate) current_round_trip_time: AtomicU64, // nanoseconds, 0 until measured
 }
 
 impl Default for CandidatePair {
@@ -247,6 +259,7 @@ impl Default for CandidatePair {
             binding_request_count: AtomicU16::new(0),
             nominated: AtomicBool::new(false),
             nominate_on_binding_success: AtomicBool::new(false),
+            current_round_trip_time: AtomicU64::new(0),
         }
     }
 }
@@ -300,6 +313,7 @@ impl CandidatePair {
             binding_request_count: AtomicU16::new(0),
             nominated: AtomicBool::new(false),
             nominate_on_binding_success: AtomicBool::new(false),
+            current_round_trip_time: AtomicU64::new(0),
         }
     }
 
@@ -322,6 +336,26 @@ impl CandidatePair {
             + u64::from(g > d)
     }
 
+    /// Returns the latest round trip time measured by a connectivity check on this pair, or `None`
+    /// if no check has succeeded yet.
+    pub fn current_round_trip_time(&self) -> Option<Duration> {
+        match self.current_round_trip_time.load(Ordering::SeqCst) {
+            0 => None,
+            rtt => Some(Duration::from_nanos(rtt)),
+        }
+    }
+
+    pub(crate) fn update_round_trip_time(&self, rtt: Duration) {
+        // Clamp to 1ns so that a measured pair is never mistaken for an unmeasured one.
+        let rtt = u64::try_from(rtt.as_nanos()).unwrap_or(u64::MAX).max(1);
+        self.current_round_trip_time.store(rtt, Ordering::SeqCst);
+    }
+
+    /// Returns the state of the pair's connectivity checks.
+    pub fn state(&self) -> CandidatePairState {
+        self.state.load(Ordering::SeqCst).into()
+    }
+
     pub async fn write(&self, b: &[u8]) -> Result<usize> {
         self.local.write_to(b, &*self.remote).await
     }
@@ -160,6 +160,8 @@ pub enum Error {
     ErrParseRelatedAddr,
     #[error("could not parse type")]
     ErrParseType,
+    #[error("could not parse candidate extension")]
+    ErrParseExtension,
     #[error("unknown candidate type")]
     ErrUnknownCandidateType,
     #[error("failed to get XOR-MAPPED-ADDRESS response")]
@@ -8,6 +8,7 @@ mod error;
 pub mod external_ip_mapper;
 pub mod mdns;
 pub mod network_type;
+pub mod nomination;
 pub mod priority;
 pub mod rand;
 pub mod state;
@@ -5,6 +5,7 @@ use std::fmt;
 use std::net::IpAddr;
 
 use serde::{Deserialize, Serialize};
+pub use util::ifaces::AdapterType;
 
 use crate::error::*;
 
@@ -126,6 +127,67 @@ impl NetworkType {
     }
 }
 
+/// Describes the network adapter a local address was found on.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct NetworkAdapter {
+    /// Identifies the adapter among the local ones, advertised in the
+    /// `network-id` candidate extension.
+    pub id: u16,
+    pub adapter_type: AdapterType,
+}
+
+impl NetworkAdapter {
+    /// Returns the preference of the adapter when several are available,
+    /// from 0 (lowest preference) to 255. Wired and Wi-Fi networks are
+    /// preferred over cellular data, and VPN tunnels are avoided.
+    #[must_use]
+    pub const fn preference(self) -> u8 {
+        match self.adapter_type {
+            AdapterType::Ethernet => 250,
+            AdapterType::Wifi => 200,
+            AdapterType::Unknown => 150,
+            AdapterType::Cellular => 100,
+            AdapterType::Vpn => 50,
+            AdapterType::Loopback => 10,
+        }
+    }
+
+    /// Computes the local preference of a candidate gathered on the adapter
+    /// with the address `ip`, following RFC 8421 Section 4: the adapter
+    /// preference comes first, then the precedence of the address family.
+    #[must_use]
+    pub fn local_preference(self, ip: &IpAddr) -> u16 {
+        (u16::from(self.preference()) << 8) | u16::from(address_preference(ip))
+    }
+}
+
+/// Returns the precedence of the address from the default policy table of
+/// RFC 6724 Section 2.1, which puts IPv6 ahead of IPv4 as RFC 8421 recommends.
+pub(crate) fn address_preference(ip: &IpAddr) -> u8 {
+    match ip {
+        IpAddr::V4(_) => 35,
+        IpAddr::V6(ip) if ip.is_loopback() => 50,
+        IpAddr::V6(ip) => {
+            let segments = ip.segments();
+            if segments[0] == 0x2002 {
+                // 6to4
+                30
+            } else if segments[0] == 0x2001 && segments[1] == 0 {
+                // Teredo
+                5
+            } else if segments[0] & 0xfe00 == 0xfc00 {
+                // Unique local
+                3
+            } else if segments[0] & 0xffc0 == 0xfe80 {
+                // Link-local addresses only reach the local link.
+                1
+            } else {
+                40
+            }
+        }
+    }
+}
+
 /// Determines the type of network based on the short network string and an IP address.
 pub(crate) fn determine_network_type(network: &str, ip: &IpAddr) -> Result<NetworkType> {
     let ipv4 = ip.is_ipv4();
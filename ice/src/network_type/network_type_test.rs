@@ -93,3 +93,28 @@ fn test_network_type_to_string() {
         assert_eq!(network_type.to_string(), expected_string);
     }
 }
+
+#[test]
+fn test_network_adapter_local_preference() {
+    let ipv4: IpAddr = "192.168.0.1".parse().unwrap();
+    let ipv6: IpAddr = "2001:db8::1".parse().unwrap();
+    let link_local: IpAddr = "fe80::a3:6ff:fec4:5454".parse().unwrap();
+
+    let adapter = |adapter_type| NetworkAdapter {
+        id: 1,
+        adapter_type,
+    };
+
+    let ethernet = adapter(AdapterType::Ethernet);
+    let wifi = adapter(AdapterType::Wifi);
+    let cellular = adapter(AdapterType::Cellular);
+    let vpn = adapter(AdapterType::Vpn);
+
+    assert!(ethernet.local_preference(&ipv4) > wifi.local_preference(&ipv6));
+    assert!(wifi.local_preference(&ipv4) > cellular.local_preference(&ipv6));
+    assert!(cellular.local_preference(&ipv4) > vpn.local_preference(&ipv6));
+
+    // IPv6 is preferred on the same adapter, unless it only reaches the link.
+    assert!(wifi.local_preference(&ipv6) > wifi.local_preference(&ipv4));
+    assert!(wifi.local_preference(&ipv4) > wifi.local_preference(&link_local));
+}
@@ -0,0 +1,36 @@
+#[cfg(test)]
+mod nomination_test;
+
+use stun::attributes::ATTR_NOMINATION;
+use stun::checks::*;
+use stun::message::*;
+
+/// Represents NOMINATION attribute.
+///
+/// The controlling agent includes it next to USE-CANDIDATE when renomination is enabled, so the
+/// controlled agent can tell the latest nomination apart from retransmissions of older ones.
+#[derive(Default, PartialEq, Eq, Debug, Copy, Clone)]
+pub struct NominationAttr(pub u32);
+
+const NOMINATION_SIZE: usize = 4; // 32 bit
+
+impl Setter for NominationAttr {
+    /// Adds NOMINATION attribute to message.
+    fn add_to(&self, m: &mut Message) -> Result<(), stun::Error> {
+        m.add(ATTR_NOMINATION, &self.0.to_be_bytes());
+        Ok(())
+    }
+}
+
+impl Getter for NominationAttr {
+    /// Decodes NOMINATION attribute from message.
+    fn get_from(&mut self, m: &Message) -> Result<(), stun::Error> {
+        let v = m.get(ATTR_NOMINATION)?;
+
+        check_size(ATTR_NOMINATION, v.len(), NOMINATION_SIZE)?;
+
+        self.0 = u32::from_be_bytes([v[0], v[1], v[2], v[3]]);
+
+        Ok(())
+    }
+}
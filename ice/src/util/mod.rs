@@ -101,7 +101,28 @@ pub async fn local_interfaces(
     network_types: &[NetworkType],
     include_loopback: bool,
 ) -> HashSet<IpAddr> {
-    let mut ips = HashSet::new();
+    local_networks(
+        vnet,
+        interface_filter,
+        ip_filter,
+        network_types,
+        include_loopback,
+    )
+    .await
+    .into_iter()
+    .map(|(ip, _)| ip)
+    .collect()
+}
+
+/// Returns the local addresses along with the network adapter they were found on.
+pub async fn local_networks(
+    vnet: &Arc<Net>,
+    interface_filter: &Option<InterfaceFilterFn>,
+    ip_filter: &Option<IpFilterFn>,
+    network_types: &[NetworkType],
+    include_loopback: bool,
+) -> Vec<(IpAddr, NetworkAdapter)> {
+    let mut ips: Vec<(IpAddr, NetworkAdapter)> = vec![];
     let interfaces = vnet.get_interfaces().await;
 
     let (mut ipv4requested, mut ipv6requested) = (false, false);
@@ -114,13 +135,18 @@ pub async fn local_interfaces(
         }
     }
 
-    for iface in interfaces {
+    for (index, iface) in interfaces.iter().enumerate() {
         if let Some(filter) = interface_filter {
             if !filter(iface.name()) {
                 continue;
             }
         }
 
+        let adapter = NetworkAdapter {
+            id: u16::try_from(index + 1).unwrap_or(u16::MAX),
+            adapter_type: AdapterType::from_name(iface.name()),
+        };
+
         for ipnet in iface.addrs() {
             let ipaddr = ipnet.addr();
 
@@ -130,8 +156,9 @@ pub async fn local_interfaces(
                     .as_ref()
                     .map(|filter| filter(ipaddr))
                     .unwrap_or(true)
+                && !ips.iter().any(|(ip, _)| *ip == ipaddr)
             {
-                ips.insert(ipaddr);
+                ips.push((ipaddr, adapter));
             }
         }
     }
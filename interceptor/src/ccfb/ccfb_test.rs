@@ -0,0 +1,126 @@
+use rtcp::transport_feedbacks::congestion_control_feedback::ARRIVAL_TIME_OFFSET_MAX;
+
+use super::*;
+
+fn as_report(pkts: &[Box<dyn rtcp::packet::Packet + Send + Sync>]) -> &CcFeedbackReport {
+    assert_eq!(pkts.len(), 1);
+    pkts[0]
+        .as_any()
+        .downcast_ref::<CcFeedbackReport>()
+        .expect("CcFeedbackReport")
+}
+
+#[test]
+fn test_recorder_empty() {
+    let mut r = Recorder::new(1);
+    assert!(r.build_feedback_packet(0, 0).is_empty());
+}
+
+#[test]
+fn test_recorder_reports_losses() {
+    let mut r = Recorder::new(5000);
+    r.record(1, 10, 0, Ecn::NotEct);
+    r.record(1, 11, 1_000, Ecn::Ect0);
+    r.record(1, 13, 500_000, Ecn::Ce);
+
+    let pkts = r.build_feedback_packet(1_000_000, 0x1234);
+    let report = as_report(&pkts);
+    assert_eq!(report.sender_ssrc, 5000);
+    assert_eq!(report.report_timestamp, 0x1234);
+    assert_eq!(
+        report.report_blocks,
+        vec![CcFeedbackReportBlock {
+            media_ssrc: 1,
+            begin_sequence: 10,
+            metric_blocks: vec![
+                CcFeedbackMetricBlock {
+                    received: true,
+                    ecn: Ecn::NotEct,
+                    arrival_time_offset: 1024,
+                },
+                CcFeedbackMetricBlock {
+                    received: true,
+                    ecn: Ecn::Ect0,
+                    arrival_time_offset: 1022,
+                },
+                CcFeedbackMetricBlock::default(),
+                CcFeedbackMetricBlock {
+                    received: true,
+                    ecn: Ecn::Ce,
+                    arrival_time_offset: 512,
+                },
+            ],
+        }]
+    );
+
+    // packets already reported are not reported again, losses in between are
+    r.record(1, 16, 1_000_000, Ecn::NotEct);
+    let pkts = r.build_feedback_packet(1_000_000, 0);
+    let report = as_report(&pkts);
+    assert_eq!(report.report_blocks[0].begin_sequence, 14);
+    assert_eq!(
+        report.report_blocks[0]
+            .metric_blocks
+            .iter()
+            .map(|m| m.received)
+            .collect::<Vec<_>>(),
+        vec![false, false, true]
+    );
+
+    assert!(r.build_feedback_packet(2_000_000, 0).is_empty());
+}
+
+#[test]
+fn test_recorder_sequence_number_wrap() {
+    let mut r = Recorder::new(1);
+    r.record(1, 65534, 0, Ecn::NotEct);
+    r.record(1, 1, 0, Ecn::NotEct);
+    r.record(1, 65535, 0, Ecn::NotEct);
+
+    let pkts = r.build_feedback_packet(0, 0);
+    let report = as_report(&pkts);
+    assert_eq!(report.report_blocks[0].begin_sequence, 65534);
+    assert_eq!(
+        report.report_blocks[0]
+            .metric_blocks
+            .iter()
+            .map(|m| m.received)
+            .collect::<Vec<_>>(),
+        vec![true, true, false, true]
+    );
+}
+
+#[test]
+fn test_recorder_multiple_streams() {
+    let mut r = Recorder::new(1);
+    r.record(7, 100, 0, Ecn::NotEct);
+    r.record(3, 200, 0, Ecn::NotEct);
+
+    let pkts = r.build_feedback_packet(0, 0);
+    let report = as_report(&pkts);
+    assert_eq!(
+        report
+            .report_blocks
+            .iter()
+            .map(|b| (b.media_ssrc, b.begin_sequence, b.metric_blocks.len()))
+            .collect::<Vec<_>>(),
+        vec![(3, 200, 1), (7, 100, 1)]
+    );
+}
+
+#[test]
+fn test_recorder_limits() {
+    let mut r = Recorder::new(1);
+    r.record(1, 0, 0, Ecn::NotEct);
+    r.record(1, 20000, 0, Ecn::NotEct);
+
+    let pkts = r.build_feedback_packet(100_000_000, 0);
+    let report = as_report(&pkts);
+    let block = &report.report_blocks[0];
+    assert_eq!(block.metric_blocks.len(), MAX_REPORTED_PACKETS);
+    assert_eq!(block.begin_sequence as usize, 20001 - MAX_REPORTED_PACKETS);
+    assert_eq!(
+        block.metric_blocks.last().map(|m| m.arrival_time_offset),
+        Some(ARRIVAL_TIME_OFFSET_MAX)
+    );
+}
@@ -0,0 +1,171 @@
+#[cfg(test)]
+mod ccfb_test;
+
+pub mod receiver;
+pub mod sender;
+
+use std::collections::{BTreeMap, HashMap};
+
+use rtcp::transport_feedbacks::congestion_control_feedback::{
+    CcFeedbackMetricBlock, CcFeedbackReport, CcFeedbackReportBlock, Ecn, ARRIVAL_TIME_OFFSET_MAX,
+    MAX_REPORTED_PACKETS,
+};
+
+use crate::stream_info::StreamInfo;
+
+pub(crate) const TYPE_RTCP_FB_ACK: &str = "ack";
+pub(crate) const RTCP_FB_PARAMETER_CCFB: &str = "ccfb";
+
+/// Returns true if RFC 8888 congestion control feedback was negotiated for the stream.
+fn stream_support_ccfb(info: &StreamInfo) -> bool {
+    info.rtcp_feedback
+        .iter()
+        .any(|fb| fb.typ == TYPE_RTCP_FB_ACK && fb.parameter == RTCP_FB_PARAMETER_CCFB)
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+struct PktInfo {
+    arrival_time: i64,
+    ecn: Ecn,
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
+struct StreamRecord {
+    cycles: u32,
+    last_sequence_number: Option<u16>,
+    // extended sequence number following the last reported one
+    next_begin: Option<u32>,
+    received_packets: BTreeMap<u32, PktInfo>,
+}
+
+impl StreamRecord {
+    fn extend(&mut self, sequence_number: u16) -> u32 {
+        let last = match self.last_sequence_number {
+            Some(last) => last,
+            None => {
+                self.last_sequence_number = Some(sequence_number);
+                return sequence_number as u32;
+            }
+        };
+
+        if sequence_number < 0x0fff && last > 0xf000 {
+            self.cycles += 1 << 16;
+        } else if sequence_number > 0xf000 && last < 0x0fff && self.cycles > 0 {
+            // reordered packet from the previous cycle
+            return (self.cycles - (1 << 16)) | sequence_number as u32;
+        }
+        if sequence_number.wrapping_sub(last) < 0x8000 {
+            self.last_sequence_number = Some(sequence_number);
+        }
+
+        self.cycles | sequence_number as u32
+    }
+}
+
+/// Recorder records incoming RTP packets and their arrival times and creates
+/// congestion control feedback reports as specified in
+/// <https://www.rfc-editor.org/rfc/rfc8888.html>
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Recorder {
+    sender_ssrc: u32,
+    streams: HashMap<u32, StreamRecord>,
+}
+
+impl Recorder {
+    /// new creates a new Recorder which uses the given sender_ssrc in the created
+    /// feedback packets.
+    pub fn new(sender_ssrc: u32) -> Self {
+        Recorder {
+            sender_ssrc,
+            ..Default::default()
+        }
+    }
+
+    /// record marks a packet with media_ssrc and sequence_number as received at arrival_time
+    /// (in microseconds) with the given ECN marking.
+    pub fn record(&mut self, media_ssrc: u32, sequence_number: u16, arrival_time: i64, ecn: Ecn) {
+        let stream = self.streams.entry(media_ssrc).or_default();
+        let sequence_number = stream.extend(sequence_number);
+        stream
+            .received_packets
+            .entry(sequence_number)
+            .or_insert(PktInfo { arrival_time, ecn });
+    }
+
+    /// build_feedback_packet creates a new RTCP packet containing a report block for every stream
+    /// that received packets since the last report. `now` is the current time in microseconds on
+    /// the same clock as the recorded arrival times, and `report_timestamp` the middle 32 bits of
+    /// the current NTP time.
+    pub fn build_feedback_packet(
+        &mut self,
+        now: i64,
+        report_timestamp: u32,
+    ) -> Vec<Box<dyn rtcp::packet::Packet + Send + Sync>> {
+        let mut ssrcs: Vec<u32> = self
+            .streams
+            .iter()
+            .filter(|(_, s)| !s.received_packets.is_empty())
+            .map(|(ssrc, _)| *ssrc)
+            .collect();
+        if ssrcs.is_empty() {
+            return vec![];
+        }
+        ssrcs.sort_unstable();
+
+        let mut report_blocks = Vec::with_capacity(ssrcs.len());
+        for ssrc in ssrcs {
+            let stream = match self.streams.get_mut(&ssrc) {
+                Some(stream) => stream,
+                None => continue,
+            };
+            let (first, last) = match (
+                stream.received_packets.keys().next(),
+                stream.received_packets.keys().next_back(),
+            ) {
+                (Some(first), Some(last)) => (*first, *last),
+                _ => continue,
+            };
+
+            // Report packets lost since the last report as well
+            let mut begin = match stream.next_begin {
+                Some(next_begin) if next_begin < first => next_begin,
+                _ => first,
+            };
+            if last - begin >= MAX_REPORTED_PACKETS as u32 {
+                begin = last + 1 - MAX_REPORTED_PACKETS as u32;
+            }
+
+            let metric_blocks = (begin..=last)
+                .map(|seq| match stream.received_packets.get(&seq) {
+                    Some(pkt) => CcFeedbackMetricBlock {
+                        received: true,
+                        ecn: pkt.ecn,
+                        arrival_time_offset: arrival_time_offset(now - pkt.arrival_time),
+                    },
+                    None => CcFeedbackMetricBlock::default(),
+                })
+                .collect();
+
+            report_blocks.push(CcFeedbackReportBlock {
+                media_ssrc: ssrc,
+                begin_sequence: (begin & 0xffff) as u16,
+                metric_blocks,
+            });
+
+            stream.next_begin = Some(last + 1);
+            stream.received_packets.clear();
+        }
+
+        vec![Box::new(CcFeedbackReport {
+            sender_ssrc: self.sender_ssrc,
+            report_blocks,
+            report_timestamp,
+        })]
+    }
+}
+
+/// Converts a duration in microseconds to an arrival time offset in 1/1024 seconds.
+fn arrival_time_offset(delta_us: i64) -> u16 {
+    let ato = delta_us.max(0) * 1024 / 1_000_000;
+    ato.min(ARRIVAL_TIME_OFFSET_MAX as i64) as u16
+}
@@ -0,0 +1,120 @@
+use rtcp::transport_feedbacks::congestion_control_feedback::CcFeedbackReport;
+
+use super::*;
+use crate::ccfb::{RTCP_FB_PARAMETER_CCFB, TYPE_RTCP_FB_ACK};
+use crate::mock::mock_stream::MockStream;
+use crate::stream_info::RTCPFeedback;
+
+fn ccfb_stream_info(ssrc: u32) -> StreamInfo {
+    StreamInfo {
+        ssrc,
+        rtcp_feedback: vec![RTCPFeedback {
+            typ: TYPE_RTCP_FB_ACK.to_owned(),
+            parameter: RTCP_FB_PARAMETER_CCFB.to_owned(),
+        }],
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_ccfb_receiver_interceptor_before_any_packets() -> Result<()> {
+    let icpr = Receiver::builder().build("")?;
+    let stream = MockStream::new(&ccfb_stream_info(1), icpr).await;
+
+    tokio::select! {
+        pkts = stream.written_rtcp() => {
+            assert!(pkts.map(|p| p.is_empty()).unwrap_or(true), "Should not have sent an RTCP packet before receiving the first RTP packets")
+        }
+        _ = tokio::time::sleep(Duration::from_millis(300)) => {
+            // All good
+        }
+    }
+
+    stream.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ccfb_receiver_interceptor_after_rtp_packets() -> Result<()> {
+    let icpr = Receiver::builder()
+        .with_interval(Duration::from_millis(50))
+        .build("")?;
+    let stream = MockStream::new(&ccfb_stream_info(1), icpr).await;
+
+    for seq in [0u16, 1, 2, 4] {
+        stream
+            .receive_rtp(rtp::packet::Packet {
+                header: rtp::header::Header {
+                    ssrc: 1,
+                    sequence_number: seq,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .await;
+        stream.read_rtp().await;
+    }
+
+    let pkts = stream.written_rtcp().await.expect("feedback");
+    assert_eq!(pkts.len(), 1);
+    let report = pkts[0]
+        .as_any()
+        .downcast_ref::<CcFeedbackReport>()
+        .expect("CcFeedbackReport");
+    assert_eq!(report.report_blocks.len(), 1);
+    let block = &report.report_blocks[0];
+    assert_eq!(block.media_ssrc, 1);
+    assert_eq!(block.begin_sequence, 0);
+    assert_eq!(
+        block
+            .metric_blocks
+            .iter()
+            .map(|m| m.received)
+            .collect::<Vec<_>>(),
+        vec![true, true, true, false, true]
+    );
+
+    stream.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ccfb_receiver_interceptor_not_negotiated() -> Result<()> {
+    let icpr = Receiver::builder()
+        .with_interval(Duration::from_millis(50))
+        .build("")?;
+    let stream = MockStream::new(
+        &StreamInfo {
+            ssrc: 1,
+            ..Default::default()
+        },
+        icpr,
+    )
+    .await;
+
+    stream
+        .receive_rtp(rtp::packet::Packet {
+            header: rtp::header::Header {
+                ssrc: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    stream.read_rtp().await;
+
+    tokio::select! {
+        pkts = stream.written_rtcp() => {
+            assert!(pkts.map(|p| p.is_empty()).unwrap_or(true), "Should not send feedback for a stream without ccfb")
+        }
+        _ = tokio::time::sleep(Duration::from_millis(200)) => {
+            // All good
+        }
+    }
+
+    stream.close().await?;
+
+    Ok(())
+}
@@ -0,0 +1,257 @@
+mod sender_stream;
+#[cfg(test)]
+mod sender_test;
+
+use std::time::{Duration, Instant};
+
+use rtcp::transport_feedbacks::congestion_control_feedback::{
+    CcFeedbackReport, Ecn, ARRIVAL_TIME_OFFSET_UNAVAILABLE,
+};
+use sender_stream::SenderStream;
+use tokio::sync::Mutex;
+
+use crate::ccfb::stream_support_ccfb;
+use crate::*;
+
+/// PacketResult is the outcome of a single sent RTP packet as reported by the remote peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PacketResult {
+    pub ssrc: u32,
+    pub sequence_number: u16,
+    /// When the packet was handed to the next writer.
+    pub departure_time: Instant,
+    /// Size of the marshaled RTP packet in bytes.
+    pub size: usize,
+    /// How long before the report timestamp the packet arrived. None if the packet was lost.
+    /// A reported offset that was too large to be represented is returned as Duration::MAX.
+    pub arrival_time_offset: Option<Duration>,
+    pub ecn: Ecn,
+}
+
+impl PacketResult {
+    /// received returns true if the packet was reported as received.
+    pub fn received(&self) -> bool {
+        self.arrival_time_offset.is_some()
+    }
+}
+
+/// FeedbackReport is a congestion control feedback report matched against the sent packets.
+/// Reported packets the sender has no record of (e.g. sent too long ago) are skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedbackReport {
+    /// The middle 32 bits of the NTP time at which the remote peer generated the report.
+    pub report_timestamp: u32,
+    pub results: Vec<PacketResult>,
+}
+
+pub type OnFeedbackFn = Arc<dyn Fn(FeedbackReport) + Send + Sync>;
+
+/// Sequence numbers are 16 bits, so remembering more packets per stream is useless.
+const MAX_LOG2_SIZE: u8 = 15;
+
+/// SenderBuilder can be used to configure Sender Interceptor
+#[derive(Default)]
+pub struct SenderBuilder {
+    log2_size: Option<u8>,
+    on_feedback: Option<OnFeedbackFn>,
+}
+
+impl SenderBuilder {
+    /// with_log2_size sets the number of sent packets remembered per stream.
+    /// Size must be one of: 1, 2, 4, 8, 16, 32, 64, 128, 256, 512, 1024, 2048, 4096, 8192, 16384, 32768,
+    /// building the interceptor fails otherwise.
+    pub fn with_log2_size(mut self, log2_size: u8) -> SenderBuilder {
+        self.log2_size = Some(log2_size);
+        self
+    }
+
+    /// with_on_feedback sets the handler called with every received feedback report.
+    pub fn with_on_feedback(mut self, f: OnFeedbackFn) -> SenderBuilder {
+        self.on_feedback = Some(f);
+        self
+    }
+}
+
+impl InterceptorBuilder for SenderBuilder {
+    fn build(&self, _id: &str) -> Result<Arc<dyn Interceptor + Send + Sync>> {
+        let log2_size = self.log2_size.unwrap_or(13); // 8192 = 1 << 13
+        if log2_size > MAX_LOG2_SIZE {
+            return Err(Error::ErrInvalidSize);
+        }
+
+        Ok(Arc::new(Sender {
+            internal: Arc::new(SenderInternal {
+                log2_size,
+                on_feedback: self.on_feedback.clone(),
+                streams: Mutex::new(HashMap::new()),
+            }),
+        }))
+    }
+}
+
+pub(super) struct SenderInternal {
+    log2_size: u8,
+    on_feedback: Option<OnFeedbackFn>,
+    streams: Mutex<HashMap<u32, Arc<SenderStream>>>,
+}
+
+impl SenderInternal {
+    async fn handle_feedback(&self, report: &CcFeedbackReport) {
+        let on_feedback = match &self.on_feedback {
+            Some(on_feedback) => on_feedback,
+            None => return,
+        };
+
+        let mut results = vec![];
+        for block in &report.report_blocks {
+            let stream = {
+                let streams = self.streams.lock().await;
+                match streams.get(&block.media_ssrc) {
+                    Some(stream) => Arc::clone(stream),
+                    None => continue,
+                }
+            };
+
+            let history = stream.history.lock().await;
+            for (i, metric) in block.metric_blocks.iter().enumerate() {
+                let sequence_number = block.begin_sequence.wrapping_add(i as u16);
+                let sent = match history.get(sequence_number) {
+                    Some(sent) => sent,
+                    None => continue,
+                };
+
+                let arrival_time_offset = if !metric.received {
+                    None
+                } else if metric.arrival_time_offset == ARRIVAL_TIME_OFFSET_UNAVAILABLE {
+                    Some(Duration::MAX)
+                } else {
+                    Some(Duration::from_micros(
+                        metric.arrival_time_offset as u64 * 1_000_000 / 1024,
+                    ))
+                };
+
+                results.push(PacketResult {
+                    ssrc: block.media_ssrc,
+                    sequence_number,
+                    departure_time: sent.departure_time,
+                    size: sent.size,
+                    arrival_time_offset,
+                    ecn: metric.ecn,
+                });
+            }
+        }
+
+        on_feedback(FeedbackReport {
+            report_timestamp: report.report_timestamp,
+            results,
+        });
+    }
+}
+
+pub struct SenderRtcpReader {
+    parent_rtcp_reader: Arc<dyn RTCPReader + Send + Sync>,
+    internal: Arc<SenderInternal>,
+}
+
+#[async_trait]
+impl RTCPReader for SenderRtcpReader {
+    async fn read(
+        &self,
+        buf: &mut [u8],
+        a: &Attributes,
+    ) -> Result<(Vec<Box<dyn rtcp::packet::Packet + Send + Sync>>, Attributes)> {
+        let (pkts, attr) = { self.parent_rtcp_reader.read(buf, a).await? };
+        for p in &pkts {
+            if let Some(report) = p.as_any().downcast_ref::<CcFeedbackReport>() {
+                self.internal.handle_feedback(report).await;
+            }
+        }
+
+        Ok((pkts, attr))
+    }
+}
+
+/// Sender records the departure time and size of outgoing RTP packets and matches them against
+/// incoming congestion control feedback reports as specified in:
+/// <https://www.rfc-editor.org/rfc/rfc8888.html>
+///
+/// The matched reports are passed to the handler set with [`SenderBuilder::with_on_feedback`],
+/// which is where a congestion controller would be driven from.
+pub struct Sender {
+    internal: Arc<SenderInternal>,
+}
+
+impl Sender {
+    /// builder returns a new SenderBuilder.
+    pub fn builder() -> SenderBuilder {
+        SenderBuilder::default()
+    }
+}
+
+#[async_trait]
+impl Interceptor for Sender {
+    /// bind_rtcp_reader lets you modify any incoming RTCP packets. It is called once per sender/receiver, however this might
+    /// change in the future. The returned method will be called once per packet batch.
+    async fn bind_rtcp_reader(
+        &self,
+        reader: Arc<dyn RTCPReader + Send + Sync>,
+    ) -> Arc<dyn RTCPReader + Send + Sync> {
+        Arc::new(SenderRtcpReader {
+            internal: Arc::clone(&self.internal),
+            parent_rtcp_reader: reader,
+        }) as Arc<dyn RTCPReader + Send + Sync>
+    }
+
+    /// bind_rtcp_writer lets you modify any outgoing RTCP packets. It is called once per PeerConnection. The returned method
+    /// will be called once per packet batch.
+    async fn bind_rtcp_writer(
+        &self,
+        writer: Arc<dyn RTCPWriter + Send + Sync>,
+    ) -> Arc<dyn RTCPWriter + Send + Sync> {
+        writer
+    }
+
+    /// bind_local_stream lets you modify any outgoing RTP packets. It is called once for per LocalStream. The returned method
+    /// will be called once per rtp packet.
+    async fn bind_local_stream(
+        &self,
+        info: &StreamInfo,
+        writer: Arc<dyn RTPWriter + Send + Sync>,
+    ) -> Arc<dyn RTPWriter + Send + Sync> {
+        if !stream_support_ccfb(info) {
+            return writer;
+        }
+
+        let stream = Arc::new(SenderStream::new(writer, self.internal.log2_size));
+        {
+            let mut streams = self.internal.streams.lock().await;
+            streams.insert(info.ssrc, Arc::clone(&stream));
+        }
+
+        stream
+    }
+
+    /// unbind_local_stream is called when the Stream is removed. It can be used to clean up any data related to that track.
+    async fn unbind_local_stream(&self, info: &StreamInfo) {
+        let mut streams = self.internal.streams.lock().await;
+        streams.remove(&info.ssrc);
+    }
+
+    /// bind_remote_stream lets you modify any incoming RTP packets. It is called once for per RemoteStream. The returned method
+    /// will be called once per rtp packet.
+    async fn bind_remote_stream(
+        &self,
+        _info: &StreamInfo,
+        reader: Arc<dyn RTPReader + Send + Sync>,
+    ) -> Arc<dyn RTPReader + Send + Sync> {
+        reader
+    }
+
+    /// unbind_remote_stream is called when the Stream is removed. It can be used to clean up any data related to that track.
+    async fn unbind_remote_stream(&self, _info: &StreamInfo) {}
+
+    /// close closes the Interceptor, cleaning up any data if necessary.
+    async fn close(&self) -> Result<()> {
+        Ok(())
+    }
+}
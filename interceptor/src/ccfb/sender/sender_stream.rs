@@ -0,0 +1,67 @@
+use util::MarshalSize;
+
+use super::*;
+
+#[derive(Debug, Clone, Copy)]
+pub(super) struct SentPacket {
+    sequence_number: u16,
+    pub(super) departure_time: Instant,
+    pub(super) size: usize,
+}
+
+/// SentHistory remembers the last 2^log2_size packets sent on a stream.
+pub(super) struct SentHistory {
+    packets: Vec<Option<SentPacket>>,
+}
+
+impl SentHistory {
+    fn new(log2_size: u8) -> Self {
+        SentHistory {
+            packets: vec![None; 1 << log2_size],
+        }
+    }
+
+    fn add(&mut self, packet: SentPacket) {
+        let idx = packet.sequence_number as usize % self.packets.len();
+        self.packets[idx] = Some(packet);
+    }
+
+    pub(super) fn get(&self, sequence_number: u16) -> Option<&SentPacket> {
+        let idx = sequence_number as usize % self.packets.len();
+        self.packets[idx]
+            .as_ref()
+            .filter(|p| p.sequence_number == sequence_number)
+    }
+}
+
+pub(super) struct SenderStream {
+    next_rtp_writer: Arc<dyn RTPWriter + Send + Sync>,
+    pub(super) history: Mutex<SentHistory>,
+}
+
+impl SenderStream {
+    pub(super) fn new(next_rtp_writer: Arc<dyn RTPWriter + Send + Sync>, log2_size: u8) -> Self {
+        SenderStream {
+            next_rtp_writer,
+            history: Mutex::new(SentHistory::new(log2_size)),
+        }
+    }
+}
+
+/// RTPWriter is used by Interceptor.bind_local_stream.
+#[async_trait]
+impl RTPWriter for SenderStream {
+    /// write a rtp packet
+    async fn write(&self, pkt: &rtp::packet::Packet, a: &Attributes) -> Result<usize> {
+        {
+            let mut history = self.history.lock().await;
+            history.add(SentPacket {
+                sequence_number: pkt.header.sequence_number,
+                departure_time: Instant::now(),
+                size: pkt.marshal_size(),
+            });
+        }
+
+        self.next_rtp_writer.write(pkt, a).await
+    }
+}
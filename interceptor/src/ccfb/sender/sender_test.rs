@@ -0,0 +1,117 @@
+use rtcp::transport_feedbacks::congestion_control_feedback::{
+    CcFeedbackMetricBlock, CcFeedbackReportBlock,
+};
+use tokio::sync::mpsc;
+
+use super::*;
+use crate::ccfb::{RTCP_FB_PARAMETER_CCFB, TYPE_RTCP_FB_ACK};
+use crate::mock::mock_stream::MockStream;
+use crate::stream_info::RTCPFeedback;
+use crate::test::timeout_or_fail;
+
+#[test]
+fn test_ccfb_sender_builder_log2_size() {
+    assert!(Sender::builder().with_log2_size(15).build("").is_ok());
+    assert_eq!(
+        Sender::builder().with_log2_size(16).build("").err(),
+        Some(Error::ErrInvalidSize)
+    );
+    assert_eq!(
+        Sender::builder().with_log2_size(u8::MAX).build("").err(),
+        Some(Error::ErrInvalidSize)
+    );
+}
+
+#[tokio::test]
+async fn test_ccfb_sender_interceptor() -> Result<()> {
+    let (feedback_tx, mut feedback_rx) = mpsc::unbounded_channel();
+    let icpr = Sender::builder()
+        .with_log2_size(3)
+        .with_on_feedback(Arc::new(move |report| {
+            let _ = feedback_tx.send(report);
+        }))
+        .build("")?;
+
+    let stream = MockStream::new(
+        &StreamInfo {
+            ssrc: 1,
+            rtcp_feedback: vec![RTCPFeedback {
+                typ: TYPE_RTCP_FB_ACK.to_owned(),
+                parameter: RTCP_FB_PARAMETER_CCFB.to_owned(),
+            }],
+            ..Default::default()
+        },
+        icpr,
+    )
+    .await;
+
+    for seq_num in [10u16, 11, 12] {
+        stream
+            .write_rtp(&rtp::packet::Packet {
+                header: rtp::header::Header {
+                    ssrc: 1,
+                    sequence_number: seq_num,
+                    ..Default::default()
+                },
+                payload: vec![0u8; 100].into(),
+            })
+            .await?;
+
+        let p = timeout_or_fail(Duration::from_millis(10), stream.written_rtp())
+            .await
+            .expect("A packet");
+        assert_eq!(p.header.sequence_number, seq_num);
+    }
+
+    stream
+        .receive_rtcp(vec![Box::new(CcFeedbackReport {
+            sender_ssrc: 2,
+            report_blocks: vec![CcFeedbackReportBlock {
+                media_ssrc: 1,
+                begin_sequence: 9,
+                metric_blocks: vec![
+                    // 9 was never sent
+                    CcFeedbackMetricBlock {
+                        received: true,
+                        ecn: Ecn::NotEct,
+                        arrival_time_offset: 0,
+                    },
+                    CcFeedbackMetricBlock {
+                        received: true,
+                        ecn: Ecn::Ect0,
+                        arrival_time_offset: 512,
+                    },
+                    CcFeedbackMetricBlock::default(),
+                    CcFeedbackMetricBlock {
+                        received: true,
+                        ecn: Ecn::NotEct,
+                        arrival_time_offset: ARRIVAL_TIME_OFFSET_UNAVAILABLE,
+                    },
+                ],
+            }],
+            report_timestamp: 42,
+        })])
+        .await;
+    stream.read_rtcp().await;
+
+    let report = timeout_or_fail(Duration::from_millis(10), feedback_rx.recv())
+        .await
+        .expect("A feedback report");
+    assert_eq!(report.report_timestamp, 42);
+    assert_eq!(
+        report
+            .results
+            .iter()
+            .map(|r| (r.sequence_number, r.size, r.arrival_time_offset, r.ecn))
+            .collect::<Vec<_>>(),
+        vec![
+            (10, 112, Some(Duration::from_millis(500)), Ecn::Ect0),
+            (11, 112, None, Ecn::NotEct),
+            (12, 112, Some(Duration::MAX), Ecn::NotEct),
+        ]
+    );
+
+    stream.close().await?;
+
+    Ok(())
+}
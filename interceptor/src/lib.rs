@@ -10,6 +10,7 @@ use async_trait::async_trait;
 use error::Result;
 use stream_info::StreamInfo;
 
+pub mod ccfb;
 pub mod chain;
 mod error;
 pub mod mock;
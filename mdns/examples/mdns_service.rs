@@ -0,0 +1,135 @@
+use std::io::Write;
+use std::net::SocketAddr;
+use std::str::FromStr;
+
+use clap::{App, AppSettings, Arg};
+use mdns::config::*;
+use mdns::conn::*;
+use mdns::service::*;
+use mdns::Error;
+use tokio::sync::mpsc;
+use webrtc_mdns as mdns;
+
+// Registers a service instance and browses for the instances of the same
+// service type, run it on several hosts of the link:
+// cargo run --color=always --package webrtc-mdns --example mdns_service -- --instance "Living Room"
+
+// For interop with dns-sd:
+// dns-sd -B _webrtc-rs._udp
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    env_logger::Builder::new()
+        .format(|buf, record| {
+            writeln!(
+                buf,
+                "{}:{} [{}] {} - {}",
+                record.file().unwrap_or("unknown"),
+                record.line().unwrap_or(0),
+                record.level(),
+                chrono::Local::now().format("%H:%M:%S.%6f"),
+                record.args()
+            )
+        })
+        .filter(None, log::LevelFilter::Info)
+        .init();
+
+    let mut app = App::new("mDNS Service")
+        .version("0.1.0")
+        .author("Rain Liu <yuliu@webrtc.rs>")
+        .about("An example of DNS-SD service registration and browsing")
+        .setting(AppSettings::DeriveDisplayOrder)
+        .setting(AppSettings::SubcommandsNegateReqs)
+        .arg(
+            Arg::with_name("FULLHELP")
+                .help("Prints more detailed help information")
+                .long("fullhelp"),
+        )
+        .arg(
+            Arg::with_name("server")
+                .required_unless("FULLHELP")
+                .takes_value(true)
+                .default_value("0.0.0.0:5353")
+                .long("server")
+                .help("mDNS Server name."),
+        )
+        .arg(
+            Arg::with_name("instance")
+                .long("instance")
+                .takes_value(true)
+                .default_value("webrtc-rs")
+                .help("Service instance name"),
+        )
+        .arg(
+            Arg::with_name("service")
+                .long("service")
+                .takes_value(true)
+                .default_value("_webrtc-rs._udp")
+                .help("Service type"),
+        )
+        .arg(
+            Arg::with_name("host-name")
+                .long("host-name")
+                .takes_value(true)
+                .default_value("webrtc-rs-test.local")
+                .help("Host name"),
+        )
+        .arg(
+            Arg::with_name("port")
+                .long("port")
+                .takes_value(true)
+                .default_value("8080")
+                .help("Service port"),
+        );
+
+    let matches = app.clone().get_matches();
+
+    if matches.is_present("FULLHELP") {
+        app.print_long_help().unwrap();
+        std::process::exit(0);
+    }
+
+    let server = matches.value_of("server").unwrap();
+    let service_type = matches.value_of("service").unwrap();
+    let host_name = matches.value_of("host-name").unwrap();
+
+    let server = DnsConn::server(
+        SocketAddr::from_str(server)?,
+        Config {
+            local_names: vec![host_name.to_owned()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let service = server
+        .register(ServiceInfo {
+            instance: matches.value_of("instance").unwrap().to_owned(),
+            service: service_type.to_owned(),
+            host_name: host_name.to_owned(),
+            port: matches.value_of("port").unwrap().parse().unwrap(),
+            ..Default::default()
+        })
+        .await?;
+    println!("registered {service}");
+
+    let (events_tx, mut events_rx) = mpsc::channel(16);
+    let (close_tx, close_rx) = mpsc::channel(1);
+    tokio::spawn(async move {
+        while let Some(event) = events_rx.recv().await {
+            match event {
+                ServiceEvent::Resolved(service) => println!("resolved {service}"),
+                ServiceEvent::Removed(service) => println!("removed {service}"),
+            }
+        }
+    });
+    tokio::spawn(async move {
+        println!("Press ctlr-c to stop");
+        tokio::signal::ctrl_c().await.unwrap();
+        let _ = close_tx.send(()).await;
+    });
+
+    server.browse(service_type, events_tx, close_rx).await?;
+    server.close().await?;
+    Ok(())
+}
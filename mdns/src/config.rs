@@ -10,5 +10,9 @@ pub struct Config {
     // local_names are the names that we will generate answers for
     // when we get questions
     pub local_names: Vec<String>,
+
+    // disable_ipv6 stops listening and sending on the IPv6 mDNS group, which
+    // is otherwise joined on every interface with an IPv6 link-local address
+    pub disable_ipv6: bool,
     //LoggerFactory logging.LoggerFactory
 }
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+
+use tokio::time::{Duration, Instant};
+
+use super::is_link_local;
+use crate::message::resource::ResourceHeader;
+
+struct CacheEntry {
+    answer: ResourceHeader,
+    addr: SocketAddr,
+    expires: Instant,
+}
+
+// Cache keeps the A and AAAA answers seen on the link until their TTL
+// expires, so that queries for known names are answered right away
+// (RFC 6762 Section 10).
+#[derive(Default)]
+pub(crate) struct Cache {
+    entries: HashMap<String, Vec<CacheEntry>>,
+}
+
+impl Cache {
+    // insert records that `answer` resolves to `addr`, a TTL of 0 removes it.
+    pub(crate) fn insert(&mut self, answer: ResourceHeader, addr: SocketAddr) {
+        let name = answer.name.data.to_ascii_lowercase();
+        let entries = self.entries.entry(name.clone()).or_default();
+        entries.retain(|e| e.addr.ip() != addr.ip());
+        if answer.ttl > 0 {
+            let expires = Instant::now() + Duration::from_secs(answer.ttl as u64);
+            entries.push(CacheEntry {
+                answer,
+                addr,
+                expires,
+            });
+        }
+        if entries.is_empty() {
+            self.entries.remove(&name);
+        }
+    }
+
+    // get returns the most recent unexpired answer for the name, preferring
+    // IPv4 addresses, then IPv6 addresses that are not link-local.
+    pub(crate) fn get(&mut self, name: &str) -> Option<(ResourceHeader, SocketAddr)> {
+        let now = Instant::now();
+        self.entries.retain(|_, entries| {
+            entries.retain(|e| e.expires > now);
+            !entries.is_empty()
+        });
+
+        self.entries
+            .get(&name.to_ascii_lowercase())
+            .and_then(|entries| {
+                entries.iter().rev().min_by_key(|e| match e.addr.ip() {
+                    ip if ip.is_ipv4() => 0,
+                    ip if !is_link_local(&ip) => 1,
+                    _ => 2,
+                })
+            })
+            .map(|e| (e.answer.clone(), e.addr))
+    }
+}
@@ -1,10 +1,31 @@
 #[cfg(test)]
 mod test {
+    use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV6};
+
     use tokio::time::timeout;
 
     use crate::config::Config;
     use crate::conn::*;
 
+    fn new_server() -> Result<DnsConn> {
+        DnsConn::server(
+            SocketAddr::new(Ipv4Addr::new(0, 0, 0, 0).into(), 5353),
+            Config::default(),
+        )
+    }
+
+    fn test_service(instance: &str, service: &str) -> ServiceInfo {
+        ServiceInfo {
+            instance: instance.to_owned(),
+            service: service.to_owned(),
+            host_name: "webrtc-rs-test.local".to_owned(),
+            port: 5000,
+            txt: vec!["version=1".to_owned()],
+            addrs: vec![IpAddr::V4(Ipv4Addr::new(192, 0, 2, 10))],
+            ..Default::default()
+        }
+    }
+
     #[tokio::test]
     async fn test_multiple_close() -> Result<()> {
         let server_a = DnsConn::server(
@@ -38,10 +59,189 @@ mod test {
             .unwrap();
 
         let res = server_a.query("invalid-host", b).await;
-        assert!(res.is_err(), "server_a.query expects timeout!");
+        assert_eq!(
+            res.unwrap_err(),
+            Error::ErrConnectionClosed,
+            "server_a.query expects timeout!"
+        );
+        assert!(
+            server_a.queries.lock().await.is_empty(),
+            "canceled query must be removed"
+        );
+
+        server_a.close().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_register_and_browse() -> Result<()> {
+        let server_a = new_server()?;
+        let server_b = new_server()?;
+
+        let service = server_a
+            .register(test_service("browse", "_wrtcbrowse._udp"))
+            .await?;
+        assert_eq!(service.instance, "browse");
+
+        let (events_tx, mut events_rx) = mpsc::channel(4);
+        let (close_tx, close_rx) = mpsc::channel(1);
+        let browse = tokio::spawn(async move {
+            let result = server_b
+                .browse("_wrtcbrowse._udp", events_tx, close_rx)
+                .await;
+            server_b.close().await?;
+            result
+        });
+
+        let event = timeout(Duration::from_secs(5), events_rx.recv())
+            .await
+            .expect("service must be resolved");
+        let Some(ServiceEvent::Resolved(resolved)) = event else {
+            panic!("expected resolved service, got {event:?}");
+        };
+        assert_eq!(resolved.instance, "browse");
+        assert_eq!(resolved.host_name, "webrtc-rs-test.local");
+        assert_eq!(resolved.port, 5000);
+        assert_eq!(resolved.txt, vec!["version=1".to_owned()]);
+        assert_eq!(resolved.addrs, service.addrs);
+
+        server_a.unregister(&service).await?;
+        assert_eq!(
+            server_a.unregister(&service).await,
+            Err(Error::ErrServiceNotRegistered)
+        );
+
+        let event = timeout(Duration::from_secs(5), events_rx.recv())
+            .await
+            .expect("service must be removed");
+        assert!(
+            matches!(event, Some(ServiceEvent::Removed(ref removed)) if removed.instance == "browse"),
+            "expected removed service, got {event:?}"
+        );
+
+        close_tx.send(()).await.unwrap();
+        browse.await.unwrap()?;
+        server_a.close().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_register_name_conflict() -> Result<()> {
+        let server_a = new_server()?;
+        let server_b = new_server()?;
+
+        let service = server_a
+            .register(test_service("conflict", "_wrtcconflict._udp"))
+            .await?;
+        assert_eq!(service.instance, "conflict");
+
+        let renamed = server_b
+            .register(test_service("conflict", "_wrtcconflict._udp"))
+            .await?;
+        assert_eq!(renamed.instance, "conflict (2)");
+
+        // Conflicts between the services of one conn are resolved locally.
+        let renamed = server_a
+            .register(test_service("conflict", "_wrtcconflict._udp"))
+            .await?;
+        assert_eq!(renamed.instance, "conflict (3)");
+
+        assert_eq!(
+            server_a
+                .register(test_service("invalid.name", "_wrtcconflict._udp"))
+                .await,
+            Err(Error::ErrInvalidService)
+        );
 
         server_a.close().await?;
+        server_b.close().await?;
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_query_cache() -> Result<()> {
+        let server_a = DnsConn::server(
+            SocketAddr::new(Ipv4Addr::new(0, 0, 0, 0).into(), 5353),
+            Config {
+                local_names: vec!["webrtc-rs-cache-test.local".to_owned()],
+                ..Default::default()
+            },
+        )?;
+        let server_b = new_server()?;
+
+        // Concurrent queries for the same name are all answered.
+        let (_close_a, close_rx_a) = mpsc::channel(1);
+        let (_close_b, close_rx_b) = mpsc::channel(1);
+        let (res_a, res_b) = timeout(Duration::from_secs(5), async {
+            tokio::join!(
+                server_b.query("webrtc-rs-cache-test.local", close_rx_a),
+                server_b.query("webrtc-rs-cache-test.local", close_rx_b),
+            )
+        })
+        .await
+        .expect("queries must be answered");
+        let (answer, addr) = res_a?;
+        res_b?;
+        assert_eq!(answer.name.data, "webrtc-rs-cache-test.local.");
+        assert!(answer.typ == DnsType::A || answer.typ == DnsType::Aaaa);
+        assert_eq!(addr.is_ipv4(), answer.typ == DnsType::A);
+
+        // Once the responder is gone, the name is still resolved from the
+        // cache.
+        server_a.close().await?;
+        let (close_tx, close_rx) = mpsc::channel(1);
+        close_tx.send(()).await.unwrap();
+        let (cached, _) = timeout(
+            Duration::from_millis(100),
+            server_b.query("webrtc-rs-cache-test.local", close_rx),
+        )
+        .await
+        .expect("cached answer must be returned right away")?;
+        assert_eq!(cached.name, answer.name);
+
+        server_b.close().await?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cache_preferred_address() {
+        let answer = |typ| ResourceHeader {
+            name: Name {
+                data: "webrtc-rs-host.local.".to_owned(),
+            },
+            typ,
+            ttl: 120,
+            ..Default::default()
+        };
+        let link_local = SocketAddr::V6(SocketAddrV6::new(
+            Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1),
+            5353,
+            0,
+            2,
+        ));
+        let global: SocketAddr = "[2001:db8::1]:5353".parse().unwrap();
+        let ipv4: SocketAddr = "192.0.2.1:5353".parse().unwrap();
+
+        let mut cache = cache::Cache::default();
+        cache.insert(answer(DnsType::Aaaa), link_local);
+        let (_, addr) = cache.get("webrtc-rs-host.local.").unwrap();
+        assert_eq!(
+            addr, link_local,
+            "the scope of a link-local address is kept"
+        );
+
+        cache.insert(answer(DnsType::Aaaa), global);
+        cache.insert(answer(DnsType::Aaaa), link_local);
+        assert_eq!(cache.get("webrtc-rs-host.local.").unwrap().1, global);
+
+        cache.insert(answer(DnsType::A), ipv4);
+        cache.insert(answer(DnsType::Aaaa), global);
+        let (answer, addr) = cache.get("webrtc-rs-host.local.").unwrap();
+        assert_eq!(answer.typ, DnsType::A);
+        assert_eq!(addr, ipv4);
+    }
 }
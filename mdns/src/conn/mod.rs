@@ -1,12 +1,15 @@
+mod cache;
+mod multicast;
+
 use core::sync::atomic;
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::net::{IpAddr, SocketAddr, SocketAddrV6};
 use std::sync::Arc;
 use std::time::Duration;
 
-use socket2::SockAddr;
-use tokio::net::{ToSocketAddrs, UdpSocket};
+use cache::*;
+use multicast::*;
+use tokio::net::UdpSocket;
 use tokio::sync::{mpsc, Mutex};
-use util::ifaces;
 
 use crate::config::*;
 use crate::error::*;
@@ -15,8 +18,10 @@ use crate::message::name::*;
 use crate::message::parser::*;
 use crate::message::question::*;
 use crate::message::resource::a::*;
+use crate::message::resource::aaaa::*;
 use crate::message::resource::*;
 use crate::message::*;
+use crate::service::*;
 
 mod conn_test;
 
@@ -26,14 +31,26 @@ const INBOUND_BUFFER_SIZE: usize = 65535;
 const DEFAULT_QUERY_INTERVAL: Duration = Duration::from_secs(1);
 const MAX_MESSAGE_RECORDS: usize = 3;
 const RESPONSE_TTL: u32 = 120;
+const LISTENER_BUFFER_SIZE: usize = 16;
+const PROBE_COUNT: usize = 3;
+const PROBE_INTERVAL: Duration = Duration::from_millis(250);
+const MAX_PROBE_ATTEMPTS: usize = 15;
+const ANNOUNCE_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_BROWSE_INTERVAL: Duration = Duration::from_secs(3600);
 
 // Conn represents a mDNS Server
 pub struct DnsConn {
-    socket: Arc<UdpSocket>,
-    dst_addr: SocketAddr,
+    multicast: Arc<Multicast>,
 
     query_interval: Duration,
     queries: Arc<Mutex<Vec<Query>>>,
+    cache: Arc<Mutex<Cache>>,
+
+    // services are the registered DNS-SD services this conn answers for.
+    services: Arc<Mutex<Vec<ServiceInfo>>>,
+    // listeners receive the responses seen on the link, for probing and
+    // browsing.
+    listeners: Arc<Mutex<Vec<mpsc::Sender<Vec<u8>>>>>,
 
     is_server_closed: Arc<atomic::AtomicBool>,
     close_server: mpsc::Sender<()>,
@@ -52,50 +69,8 @@ struct QueryResult {
 impl DnsConn {
     /// server establishes a mDNS connection over an existing connection
     pub fn server(addr: SocketAddr, config: Config) -> Result<Self> {
-        let socket = socket2::Socket::new(
-            socket2::Domain::IPV4,
-            socket2::Type::DGRAM,
-            Some(socket2::Protocol::UDP),
-        )?;
-
-        #[cfg(feature = "reuse_port")]
-        #[cfg(target_family = "unix")]
-        socket.set_reuse_port(true)?;
-
-        socket.set_reuse_address(true)?;
-        socket.set_broadcast(true)?;
-        socket.set_nonblocking(true)?;
-
-        socket.bind(&SockAddr::from(addr))?;
-        {
-            let mut join_error_count = 0;
-            let interfaces = match ifaces::ifaces() {
-                Ok(e) => e,
-                Err(e) => {
-                    log::error!("Error getting interfaces: {:?}", e);
-                    return Err(Error::Other(e.to_string()));
-                }
-            };
-
-            for interface in &interfaces {
-                if let Some(SocketAddr::V4(e)) = interface.addr {
-                    if let Err(e) = socket.join_multicast_v4(&Ipv4Addr::new(224, 0, 0, 251), e.ip())
-                    {
-                        log::trace!("Error connecting multicast, error: {:?}", e);
-                        join_error_count += 1;
-                        continue;
-                    }
-
-                    log::trace!("Connected to interface address {:?}", e);
-                }
-            }
-
-            if join_error_count >= interfaces.len() {
-                return Err(Error::ErrJoiningMulticastGroup);
-            }
-        }
-
-        let socket = UdpSocket::from_std(socket.into())?;
+        let dst_addr: SocketAddr = DEFAULT_DEST_ADDR.parse()?;
+        let multicast = Multicast::new(addr, dst_addr, !config.disable_ipv6)?;
 
         let local_names = config
             .local_names
@@ -103,8 +78,6 @@ impl DnsConn {
             .map(|l| l.to_string() + ".")
             .collect();
 
-        let dst_addr: SocketAddr = DEFAULT_DEST_ADDR.parse()?;
-
         let is_server_closed = Arc::new(atomic::AtomicBool::new(false));
 
         let (close_server_send, close_server_rcv) = mpsc::channel(1);
@@ -117,23 +90,30 @@ impl DnsConn {
             },
 
             queries: Arc::new(Mutex::new(vec![])),
-            socket: Arc::new(socket),
-            dst_addr,
+            cache: Arc::new(Mutex::new(Cache::default())),
+            services: Arc::new(Mutex::new(vec![])),
+            listeners: Arc::new(Mutex::new(vec![])),
+            multicast: Arc::new(multicast),
             is_server_closed: Arc::clone(&is_server_closed),
             close_server: close_server_send,
         };
 
         let queries = c.queries.clone();
-        let socket = Arc::clone(&c.socket);
+        let cache = Arc::clone(&c.cache);
+        let services = Arc::clone(&c.services);
+        let listeners = Arc::clone(&c.listeners);
+        let multicast = Arc::clone(&c.multicast);
 
         tokio::spawn(async move {
             DnsConn::start(
                 close_server_rcv,
                 is_server_closed,
-                socket,
+                multicast,
                 local_names,
-                dst_addr,
                 queries,
+                cache,
+                services,
+                listeners,
             )
             .await
         });
@@ -148,6 +128,13 @@ impl DnsConn {
             return Err(Error::ErrConnectionClosed);
         }
 
+        let services = std::mem::take(&mut *self.services.lock().await);
+        for service in &services {
+            if let Err(err) = announce(&self.multicast, service, 0).await {
+                log::warn!("Failed to send goodbye for {}: {}", service, err);
+            }
+        }
+
         log::trace!("Sending close command to server");
         match self.close_server.send(()).await {
             Ok(_) => {
@@ -162,7 +149,13 @@ impl DnsConn {
     }
 
     /// Query sends mDNS Queries for the following name until
-    /// either there's a close signal or we get a result
+    /// either there's a close signal or we get a result.
+    ///
+    /// The A or AAAA answer is returned with the address it resolves to, and
+    /// the port of the responder. A answers are preferred, then AAAA answers
+    /// that are not link-local; a link-local address carries the scope of
+    /// the interface it was received on. Answers are cached until their TTL
+    /// expires, a cached answer is returned without querying.
     pub async fn query(
         &self,
         name: &str,
@@ -173,10 +166,16 @@ impl DnsConn {
         }
 
         let name_with_suffix = name.to_owned() + ".";
+        if let Some(res) = self.cache.lock().await.get(&name_with_suffix) {
+            log::trace!("Found {} in cache", name_with_suffix);
+            return Ok(res);
+        }
 
         let (query_tx, mut query_rx) = mpsc::channel(1);
         {
             let mut queries = self.queries.lock().await;
+            // Drops the queries whose caller went away.
+            queries.retain(|q| !q.query_result_chan.is_closed());
             queries.push(Query {
                 name_with_suffix: name_with_suffix.clone(),
                 query_result_chan: query_tx,
@@ -195,6 +194,9 @@ impl DnsConn {
 
                 _ = close_query_signal.recv() => {
                     log::info!("Query close signal received.");
+                    drop(query_rx);
+                    let mut queries = self.queries.lock().await;
+                    queries.retain(|q| !q.query_result_chan.is_closed());
                     return Err(Error::ErrConnectionClosed)
                 },
 
@@ -208,50 +210,251 @@ impl DnsConn {
         }
     }
 
-    async fn send_question(&self, name: &str) {
-        let packed_name = match Name::new(name) {
-            Ok(pn) => pn,
-            Err(err) => {
-                log::warn!("Failed to construct mDNS packet: {}", err);
+    /// register probes the link for the name of the service instance, then
+    /// announces the service and answers the questions about it until it is
+    /// unregistered or the conn is closed.
+    ///
+    /// If another host already uses the instance name, " (2)", " (3)"... is
+    /// appended to it (RFC 6762 Section 9). The registered service is returned.
+    pub async fn register(&self, mut service: ServiceInfo) -> Result<ServiceInfo> {
+        if self.is_server_closed.load(atomic::Ordering::SeqCst) {
+            return Err(Error::ErrConnectionClosed);
+        }
+        service.validate()?;
+
+        let (listener_tx, mut listener_rx) = mpsc::channel(LISTENER_BUFFER_SIZE);
+        self.listeners.lock().await.push(listener_tx);
+
+        let instance = service.instance.clone();
+        let mut registered = false;
+        for attempt in 1..=MAX_PROBE_ATTEMPTS {
+            if attempt > 1 {
+                service.instance = format!("{instance} ({attempt})");
+            }
+            if self.is_registered(&service).await {
+                continue;
+            }
+            if self.probe(&service, &mut listener_rx).await? {
+                registered = true;
+                break;
+            }
+            log::debug!("Name conflict for {}", service.instance_name());
+        }
+        if !registered {
+            return Err(Error::ErrServiceNameConflict);
+        }
+
+        self.services.lock().await.push(service.clone());
+        announce(&self.multicast, &service, SERVICE_RECORD_TTL).await?;
+
+        // The announcement is repeated once (RFC 6762 Section 8.3).
+        let multicast = Arc::clone(&self.multicast);
+        let services = Arc::clone(&self.services);
+        let announced = service.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(ANNOUNCE_INTERVAL).await;
+            if !services.lock().await.contains(&announced) {
                 return;
             }
+            if let Err(err) = announce(&multicast, &announced, SERVICE_RECORD_TTL).await {
+                log::warn!("Failed to announce {}: {}", announced, err);
+            }
+        });
+
+        Ok(service)
+    }
+
+    /// unregister stops answering for the service, and sends its goodbye
+    /// records so that the other hosts flush them.
+    pub async fn unregister(&self, service: &ServiceInfo) -> Result<()> {
+        if self.is_server_closed.load(atomic::Ordering::SeqCst) {
+            return Err(Error::ErrConnectionClosed);
+        }
+
+        let removed = {
+            let instance_name = service.instance_name();
+            let mut services = self.services.lock().await;
+            match services
+                .iter()
+                .position(|s| s.instance_name().eq_ignore_ascii_case(&instance_name))
+            {
+                Some(i) => services.remove(i),
+                None => return Err(Error::ErrServiceNotRegistered),
+            }
         };
 
-        let raw_query = {
-            let mut msg = Message {
+        announce(&self.multicast, &removed, 0).await
+    }
+
+    /// browse sends PTR queries for the service type, such as "_webrtc._udp",
+    /// and reports the instances found on the link to `events_tx` until
+    /// there's a close signal.
+    pub async fn browse(
+        &self,
+        service: &str,
+        events_tx: mpsc::Sender<ServiceEvent>,
+        mut close_browse_signal: mpsc::Receiver<()>,
+    ) -> Result<()> {
+        if self.is_server_closed.load(atomic::Ordering::SeqCst) {
+            return Err(Error::ErrConnectionClosed);
+        }
+
+        let (listener_tx, mut listener_rx) = mpsc::channel(LISTENER_BUFFER_SIZE);
+        self.listeners.lock().await.push(listener_tx);
+
+        let mut browser = Browser::new(service);
+        let ptr_question = Question {
+            name: Name::new(&browser.service_name())?,
+            typ: DnsType::Ptr,
+            class: DNSCLASS_INET,
+        };
+
+        // Queries are repeated with an increasing interval (RFC 6762 Section 5.2).
+        let mut interval = self.query_interval;
+        let mut next_query = tokio::time::Instant::now();
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep_until(next_query) => {
+                    log::trace!("Sending browse query");
+                    self.send_questions(vec![ptr_question.clone()]).await;
+                    next_query += interval;
+                    interval = (interval * 2).min(MAX_BROWSE_INTERVAL);
+                },
+
+                _ = close_browse_signal.recv() => {
+                    log::info!("Browse close signal received.");
+                    return Ok(());
+                },
+
+                packet = listener_rx.recv() => {
+                    let Some(packet) = packet else {
+                        return Err(Error::ErrConnectionClosed);
+                    };
+                    let records = match parse_records(&packet) {
+                        Ok(records) => records,
+                        Err(err) => {
+                            log::warn!("Failed to parse mDNS packet {}", err);
+                            continue;
+                        }
+                    };
+
+                    let (events, questions) = browser.update(&records);
+                    if !questions.is_empty() {
+                        self.send_questions(questions).await;
+                    }
+                    for event in events {
+                        if events_tx.send(event).await.is_err() {
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    async fn is_registered(&self, service: &ServiceInfo) -> bool {
+        let instance_name = service.instance_name();
+        let services = self.services.lock().await;
+        services
+            .iter()
+            .any(|s| s.instance_name().eq_ignore_ascii_case(&instance_name))
+    }
+
+    /// probe sends the probe queries for the service instance name, returns
+    /// false if another host answers for it (RFC 6762 Section 8.1).
+    async fn probe(
+        &self,
+        service: &ServiceInfo,
+        listener_rx: &mut mpsc::Receiver<Vec<u8>>,
+    ) -> Result<bool> {
+        let instance_name = service.instance_name();
+        // Responses received before probing are about other names.
+        while listener_rx.try_recv().is_ok() {}
+
+        for _ in 0..PROBE_COUNT {
+            let msg = Message {
                 header: Header::default(),
                 questions: vec![Question {
-                    typ: DnsType::A,
-                    class: DNSCLASS_INET,
-                    name: packed_name,
+                    name: Name::new(&instance_name)?,
+                    typ: DnsType::All,
+                    class: DnsClass(DNSCLASS_INET.0 | CLASS_UNICAST_RESPONSE),
                 }],
+                authorities: vec![
+                    service.srv_record(HOST_RECORD_TTL)?,
+                    service.txt_record(SERVICE_RECORD_TTL)?,
+                ],
                 ..Default::default()
             };
+            send_message(&self.multicast, msg, None).await?;
 
-            match msg.pack() {
-                Ok(v) => v,
-                Err(err) => {
-                    log::error!("Failed to construct mDNS packet {}", err);
-                    return;
+            let deadline = tokio::time::Instant::now() + PROBE_INTERVAL;
+            while let Ok(packet) = tokio::time::timeout_at(deadline, listener_rx.recv()).await {
+                let Some(packet) = packet else {
+                    return Err(Error::ErrConnectionClosed);
+                };
+                let Ok(records) = parse_records(&packet) else {
+                    continue;
+                };
+                if records
+                    .iter()
+                    .any(|r| r.header.name.data.eq_ignore_ascii_case(&instance_name))
+                {
+                    return Ok(false);
                 }
             }
+        }
+
+        Ok(true)
+    }
+
+    async fn send_question(&self, name: &str) {
+        let packed_name = match Name::new(name) {
+            Ok(pn) => pn,
+            Err(err) => {
+                log::warn!("Failed to construct mDNS packet: {}", err);
+                return;
+            }
         };
 
-        log::trace!("{:?} sending {:?}...", self.socket.local_addr(), raw_query);
-        if let Err(err) = self.socket.send_to(&raw_query, self.dst_addr).await {
+        self.send_questions(vec![
+            Question {
+                typ: DnsType::A,
+                class: DNSCLASS_INET,
+                name: packed_name.clone(),
+            },
+            Question {
+                typ: DnsType::Aaaa,
+                class: DNSCLASS_INET,
+                name: packed_name,
+            },
+        ])
+        .await
+    }
+
+    async fn send_questions(&self, questions: Vec<Question>) {
+        let msg = Message {
+            header: Header::default(),
+            questions,
+            ..Default::default()
+        };
+
+        if let Err(err) = send_message(&self.multicast, msg, None).await {
             log::error!("Failed to send mDNS packet {}", err);
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn start(
         mut closed_rx: mpsc::Receiver<()>,
         close_server: Arc<atomic::AtomicBool>,
-        socket: Arc<UdpSocket>,
+        multicast: Arc<Multicast>,
         local_names: Vec<String>,
-        dst_addr: SocketAddr,
         queries: Arc<Mutex<Vec<Query>>>,
+        cache: Arc<Mutex<Cache>>,
+        services: Arc<Mutex<Vec<ServiceInfo>>>,
+        listeners: Arc<Mutex<Vec<mpsc::Sender<Vec<u8>>>>>,
     ) -> Result<()> {
-        log::info!("Looping and listening {:?}", socket.local_addr());
+        log::info!("Looping and listening {:?}", multicast.local_addr());
 
         let mut b = vec![0u8; INBOUND_BUFFER_SIZE];
         let (mut n, mut src);
@@ -261,11 +464,12 @@ impl DnsConn {
                 _ = closed_rx.recv() => {
                     log::info!("Closing server connection");
                     close_server.store(true, atomic::Ordering::SeqCst);
+                    listeners.lock().await.clear();
 
                     return Ok(());
                 }
 
-                result = socket.recv_from(&mut b) => {
+                result = multicast.recv_from(&mut b) => {
                     match result{
                         Ok((len, addr)) => {
                             n = len;
@@ -282,25 +486,47 @@ impl DnsConn {
             }
 
             let mut p = Parser::default();
-            if let Err(err) = p.start(&b[..n]) {
-                log::error!("Failed to parse mDNS packet {}", err);
-                continue;
+            let header = match p.start(&b[..n]) {
+                Ok(header) => header,
+                Err(err) => {
+                    log::error!("Failed to parse mDNS packet {}", err);
+                    continue;
+                }
+            };
+
+            if header.response {
+                let mut listeners = listeners.lock().await;
+                listeners.retain(|l| !l.is_closed());
+                for listener in listeners.iter() {
+                    let _ = listener.try_send(b[..n].to_vec());
+                }
             }
 
-            run(&mut p, &socket, &local_names, src, dst_addr, &queries).await
+            run(
+                &mut p,
+                &multicast,
+                &local_names,
+                src,
+                &queries,
+                &cache,
+                &services,
+            )
+            .await
         }
     }
 }
 
 async fn run(
     p: &mut Parser<'_>,
-    socket: &Arc<UdpSocket>,
+    multicast: &Multicast,
     local_names: &[String],
     src: SocketAddr,
-    dst_addr: SocketAddr,
     queries: &Arc<Mutex<Vec<Query>>>,
+    cache: &Arc<Mutex<Cache>>,
+    services: &Arc<Mutex<Vec<ServiceInfo>>>,
 ) {
-    let mut interface_addr = None;
+    let services = services.lock().await.clone();
+    let mut interface = None;
     for _ in 0..=MAX_MESSAGE_RECORDS {
         let q = match p.question() {
             Ok(q) => q,
@@ -315,38 +541,42 @@ async fn run(
             }
         };
 
-        for local_name in local_names {
-            if *local_name == q.name.data {
-                let interface_addr = match interface_addr {
-                    Some(addr) => addr,
-                    None => match get_interface_addr_for_ip(src).await {
-                        Ok(addr) => {
-                            interface_addr.replace(addr);
-                            addr
-                        }
-                        Err(e) => {
-                            log::warn!(
-                                "Failed to get local interface to communicate with {}: {:?}",
-                                &src,
-                                e
-                            );
-                            continue;
-                        }
-                    },
-                };
+        let is_local_name = local_names.contains(&q.name.data);
+        if !is_local_name && services.is_empty() {
+            continue;
+        }
 
-                log::trace!(
-                    "Found local name: {} to send answer, IP {}, interface addr {}",
-                    local_name,
-                    src.ip(),
-                    interface_addr
-                );
-                if let Err(e) =
-                    send_answer(socket, &interface_addr, &q.name.data, src.ip(), dst_addr).await
-                {
-                    log::error!("Error sending answer to client: {:?}", e);
+        let interface = match &interface {
+            Some(interface) => interface,
+            None => match get_interface_addr_for_ip(src).await {
+                Ok(addr) => interface.insert(interface_for(addr.ip())),
+                Err(e) => {
+                    log::warn!(
+                        "Failed to get local interface to communicate with {}: {:?}",
+                        &src,
+                        e
+                    );
                     continue;
-                };
+                }
+            },
+        };
+
+        if is_local_name {
+            log::trace!(
+                "Found local name: {} to send answer, IP {}, interface addrs {:?}",
+                q.name.data,
+                src.ip(),
+                interface.addrs
+            );
+            if let Err(e) = send_answer(multicast, interface, &q.name.data, q.typ, src.ip()).await {
+                log::error!("Error sending answer to client: {:?}", e);
+                continue;
+            };
+        }
+
+        if !services.is_empty() {
+            if let Err(e) = send_service_answers(multicast, interface, &services, &q).await {
+                log::error!("Error sending service answer to client: {:?}", e);
             }
         }
     }
@@ -354,6 +584,9 @@ async fn run(
     // There might be more than MAX_MESSAGE_RECORDS questions, so skip the rest
     let _ = p.skip_all_questions();
 
+    // All the answers are cached first, so that the preferred address of a
+    // name is returned whatever the order of the answers.
+    let mut answered = vec![];
     for _ in 0..=MAX_MESSAGE_RECORDS {
         let a = match p.answer_header() {
             Ok(a) => a,
@@ -361,22 +594,62 @@ async fn run(
                 if Error::ErrSectionDone != err {
                     log::warn!("Failed to parse mDNS packet {}", err);
                 }
-                return;
+                break;
             }
         };
 
         if a.typ != DnsType::A && a.typ != DnsType::Aaaa {
+            if let Err(err) = p.skip_answer() {
+                log::warn!("Failed to parse mDNS packet {}", err);
+                break;
+            }
             continue;
         }
 
+        let ip = match p.resource_body() {
+            Ok(body) => {
+                if let Some(a) = body.as_any().downcast_ref::<AResource>() {
+                    IpAddr::from(a.a)
+                } else if let Some(aaaa) = body.as_any().downcast_ref::<AaaaResource>() {
+                    IpAddr::from(aaaa.aaaa)
+                } else {
+                    continue;
+                }
+            }
+            Err(err) => {
+                log::warn!("Failed to parse mDNS packet {}", err);
+                break;
+            }
+        };
+        // The answer may be for another address family than the packet. A
+        // link-local address is only usable on the interface it was received
+        // on.
+        let addr = match (ip, src) {
+            (IpAddr::V6(ip), SocketAddr::V6(src)) if is_link_local(&IpAddr::V6(ip)) => {
+                SocketAddr::V6(SocketAddrV6::new(ip, src.port(), 0, src.scope_id()))
+            }
+            _ => SocketAddr::new(ip, src.port()),
+        };
+
+        cache.lock().await.insert(a.clone(), addr);
+        // A TTL of 0 is a goodbye (RFC 6762 Section 10.1).
+        if a.ttl > 0 && !answered.contains(&a.name.data) {
+            answered.push(a.name.data);
+        }
+    }
+
+    for name in answered {
+        let Some((answer, addr)) = cache.lock().await.get(&name) else {
+            continue;
+        };
         let mut qs = queries.lock().await;
         for j in (0..qs.len()).rev() {
-            if qs[j].name_with_suffix == a.name.data {
+            if qs[j].name_with_suffix == name {
                 let _ = qs[j]
                     .query_result_chan
                     .send(QueryResult {
-                        answer: a.clone(),
-                        addr: src,
+                        answer: answer.clone(),
+                        addr,
                     })
                     .await;
                 qs.remove(j);
@@ -385,52 +658,177 @@ async fn run(
     }
 }
 
+/// is_link_local reports whether the address is an IPv6 link-local address,
+/// fe80::/10, which can't be used without the scope of its interface.
+pub(crate) fn is_link_local(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V6(ip) => ip.segments()[0] & 0xffc0 == 0xfe80,
+        IpAddr::V4(_) => false,
+    }
+}
+
 async fn send_answer(
-    socket: &Arc<UdpSocket>,
-    interface_addr: &SocketAddr,
+    multicast: &Multicast,
+    interface: &Interface,
     name: &str,
+    typ: DnsType,
     dst: IpAddr,
-    dst_addr: SocketAddr,
 ) -> Result<()> {
-    let raw_answer = {
-        let mut msg = Message {
-            header: Header {
-                response: true,
-                authoritative: true,
+    let mut answers = vec![];
+    for ip in &interface.addrs {
+        let (rtyp, body): (DnsType, Box<dyn ResourceBody>) = match ip {
+            IpAddr::V4(ip) if typ != DnsType::Aaaa => {
+                (DnsType::A, Box::new(AResource { a: ip.octets() }))
+            }
+            IpAddr::V6(ip) if typ == DnsType::Aaaa || typ == DnsType::All => {
+                (DnsType::Aaaa, Box::new(AaaaResource { aaaa: ip.octets() }))
+            }
+            _ => continue,
+        };
+        answers.push(Resource {
+            header: ResourceHeader {
+                typ: rtyp,
+                class: DNSCLASS_INET,
+                name: Name::new(name)?,
+                ttl: RESPONSE_TTL,
                 ..Default::default()
             },
+            body: Some(body),
+        });
+    }
+    if answers.is_empty() {
+        return Ok(());
+    }
 
-            answers: vec![Resource {
-                header: ResourceHeader {
-                    typ: DnsType::A,
-                    class: DNSCLASS_INET,
-                    name: Name::new(name)?,
-                    ttl: RESPONSE_TTL,
-                    ..Default::default()
-                },
-                body: Some(Box::new(AResource {
-                    a: match interface_addr.ip() {
-                        IpAddr::V4(ip) => ip.octets(),
-                        IpAddr::V6(_) => {
-                            return Err(Error::Other("Unexpected IpV6 addr".to_owned()))
-                        }
-                    },
-                })),
-            }],
+    let msg = Message {
+        header: Header {
+            response: true,
+            authoritative: true,
             ..Default::default()
-        };
+        },
+        answers,
+        ..Default::default()
+    };
+    send_message(multicast, msg, Some(interface)).await?;
+    log::trace!("Sent answer to IP {}", dst);
+
+    Ok(())
+}
 
-        msg.pack()?
+async fn send_service_answers(
+    multicast: &Multicast,
+    interface: &Interface,
+    services: &[ServiceInfo],
+    q: &Question,
+) -> Result<()> {
+    let (answers, additionals) = service_answers(services, q, &interface.addrs)?;
+    if answers.is_empty() {
+        return Ok(());
+    }
+
+    let msg = Message {
+        header: Header {
+            response: true,
+            authoritative: true,
+            ..Default::default()
+        },
+        answers,
+        additionals,
+        ..Default::default()
     };
+    send_message(multicast, msg, Some(interface)).await?;
+    log::trace!("Sent service answer for {}", q.name);
 
-    socket.send_to(&raw_answer, dst_addr).await?;
-    log::trace!("Sent answer to IP {}", dst);
+    Ok(())
+}
+
+// announce sends all the records of the service on every interface, with a
+// TTL of 0 for goodbye packets (RFC 6762 Sections 8.3 and 10.1).
+async fn announce(multicast: &Multicast, service: &ServiceInfo, ttl: u32) -> Result<()> {
+    let interfaces = multicast_interfaces();
+    if interfaces.is_empty() {
+        let msg = announcement(service, &[], ttl)?;
+        return send_message(multicast, msg, None).await;
+    }
 
+    for interface in &interfaces {
+        let msg = announcement(service, &interface.addrs, ttl)?;
+        send_message(multicast, msg, Some(interface)).await?;
+    }
     Ok(())
 }
 
-async fn get_interface_addr_for_ip(addr: impl ToSocketAddrs) -> std::io::Result<SocketAddr> {
-    let socket = UdpSocket::bind("0.0.0.0:0").await?;
+fn announcement(service: &ServiceInfo, addrs: &[IpAddr], ttl: u32) -> Result<Message> {
+    Ok(Message {
+        header: Header {
+            response: true,
+            authoritative: true,
+            ..Default::default()
+        },
+        answers: service.records(addrs, ttl)?,
+        ..Default::default()
+    })
+}
+
+async fn send_message(
+    multicast: &Multicast,
+    mut msg: Message,
+    interface: Option<&Interface>,
+) -> Result<()> {
+    let raw = msg.pack()?;
+    log::trace!("{:?} sending {:?}...", multicast.local_addr(), raw);
+    multicast.send(&raw, interface).await
+}
+
+// parse_records returns the answers and additional records of a response,
+// skipping the records of unsupported types.
+fn parse_records(b: &[u8]) -> Result<Vec<Resource>> {
+    let mut p = Parser::default();
+    p.start(b)?;
+    p.skip_all_questions()?;
+
+    let mut records = vec![];
+    loop {
+        let header = match p.answer_header() {
+            Ok(header) => header,
+            Err(Error::ErrSectionDone) => break,
+            Err(err) => return Err(err),
+        };
+        match p.resource_body() {
+            Ok(body) => records.push(Resource {
+                header,
+                body: Some(body),
+            }),
+            Err(Error::ErrNilResourceBody) => p.skip_answer()?,
+            Err(err) => return Err(err),
+        }
+    }
+    p.skip_all_authorities()?;
+    loop {
+        let header = match p.additional_header() {
+            Ok(header) => header,
+            Err(Error::ErrSectionDone) => break,
+            Err(err) => return Err(err),
+        };
+        match p.resource_body() {
+            Ok(body) => records.push(Resource {
+                header,
+                body: Some(body),
+            }),
+            Err(Error::ErrNilResourceBody) => p.skip_additional()?,
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(records)
+}
+
+async fn get_interface_addr_for_ip(addr: SocketAddr) -> std::io::Result<SocketAddr> {
+    let socket = if addr.is_ipv6() {
+        UdpSocket::bind("[::]:0").await?
+    } else {
+        UdpSocket::bind("0.0.0.0:0").await?
+    };
     socket.connect(addr).await?;
     socket.local_addr()
 }
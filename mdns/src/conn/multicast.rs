@@ -0,0 +1,280 @@
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV6};
+
+use socket2::{SockAddr, SockRef};
+use tokio::net::UdpSocket;
+use tokio::sync::Mutex;
+use util::ifaces;
+
+use crate::error::*;
+
+pub(crate) const MDNS_GROUP_V4: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+pub(crate) const MDNS_GROUP_V6: Ipv6Addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 0xfb);
+
+// Interface is a network interface with its addresses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Interface {
+    pub(crate) name: String,
+    pub(crate) addrs: Vec<IpAddr>,
+    // index is the IPv6 scope of the interface, known from its link-local
+    // addresses.
+    pub(crate) index: Option<u32>,
+}
+
+// interfaces returns the interfaces of the host, grouping their addresses.
+pub(crate) fn interfaces() -> Vec<Interface> {
+    let addrs = match ifaces::ifaces() {
+        Ok(addrs) => addrs,
+        Err(e) => {
+            log::warn!("Error getting interfaces: {:?}", e);
+            return vec![];
+        }
+    };
+
+    let mut interfaces: Vec<Interface> = vec![];
+    for addr in addrs {
+        let Some(sock_addr) = addr.addr else {
+            continue;
+        };
+        let interface = match interfaces.iter_mut().find(|i| i.name == addr.name) {
+            Some(interface) => interface,
+            None => {
+                interfaces.push(Interface {
+                    name: addr.name.clone(),
+                    addrs: vec![],
+                    index: None,
+                });
+                interfaces.last_mut().unwrap()
+            }
+        };
+        if let SocketAddr::V6(v6) = sock_addr {
+            if v6.scope_id() != 0 {
+                interface.index = Some(v6.scope_id());
+            }
+        }
+        if !interface.addrs.contains(&sock_addr.ip()) {
+            interface.addrs.push(sock_addr.ip());
+        }
+    }
+    interfaces
+}
+
+// interface_for returns the interface which has the address `ip`, listing
+// `ip` first.
+pub(crate) fn interface_for(ip: IpAddr) -> Interface {
+    let mut interface = interfaces()
+        .into_iter()
+        .find(|i| i.addrs.contains(&ip))
+        .unwrap_or_else(|| Interface {
+            name: String::new(),
+            addrs: vec![ip],
+            index: None,
+        });
+    interface.addrs.retain(|addr| *addr != ip);
+    interface.addrs.insert(0, ip);
+    interface
+}
+
+// multicast_interfaces returns the interfaces mDNS packets are sent on.
+pub(crate) fn multicast_interfaces() -> Vec<Interface> {
+    interfaces()
+        .into_iter()
+        .filter(|i| i.addrs.iter().all(|addr| !addr.is_loopback()))
+        .collect()
+}
+
+// Multicast holds the IPv4 and IPv6 sockets of a DnsConn, which are joined to
+// the mDNS groups on every interface.
+pub(crate) struct Multicast {
+    socket_v4: UdpSocket,
+    socket_v6: Option<UdpSocket>,
+    dst_addr: SocketAddr,
+    // send_lock serializes the sends, as the outgoing interface is an option
+    // of the sockets.
+    send_lock: Mutex<()>,
+}
+
+impl Multicast {
+    // new binds the IPv4 socket to `addr`, and the IPv6 one to the same port
+    // if `ipv6` is set. IPv6 is disabled if the IPv6 group can't be joined.
+    pub(crate) fn new(addr: SocketAddr, dst_addr: SocketAddr, ipv6: bool) -> Result<Self> {
+        let interfaces = match ifaces::ifaces() {
+            Ok(e) => e,
+            Err(e) => {
+                log::error!("Error getting interfaces: {:?}", e);
+                return Err(Error::Other(e.to_string()));
+            }
+        };
+
+        let socket = new_socket(socket2::Domain::IPV4, addr)?;
+        let mut join_error_count = 0;
+        for interface in &interfaces {
+            if let Some(SocketAddr::V4(e)) = interface.addr {
+                if let Err(e) = socket.join_multicast_v4(&MDNS_GROUP_V4, e.ip()) {
+                    log::trace!("Error connecting multicast, error: {:?}", e);
+                    join_error_count += 1;
+                    continue;
+                }
+
+                log::trace!("Connected to interface address {:?}", e);
+            }
+        }
+
+        if join_error_count >= interfaces.len() {
+            return Err(Error::ErrJoiningMulticastGroup);
+        }
+
+        let socket_v6 = if ipv6 {
+            match new_socket_v6(addr.port()) {
+                Ok(socket) => Some(UdpSocket::from_std(socket.into())?),
+                Err(e) => {
+                    log::debug!("IPv6 mDNS is disabled: {:?}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        Ok(Multicast {
+            socket_v4: UdpSocket::from_std(socket.into())?,
+            socket_v6,
+            dst_addr,
+            send_lock: Mutex::new(()),
+        })
+    }
+
+    pub(crate) fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket_v4.local_addr()
+    }
+
+    // recv_from receives a packet from any of the sockets.
+    pub(crate) async fn recv_from(&self, b: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        loop {
+            let socket = tokio::select! {
+                result = self.socket_v4.readable() => {
+                    result?;
+                    &self.socket_v4
+                }
+                result = readable(self.socket_v6.as_ref()) => {
+                    result?;
+                    self.socket_v6.as_ref().unwrap()
+                }
+            };
+
+            match socket.try_recv_from(b) {
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+                result => return result,
+            }
+        }
+    }
+
+    // send sends the packet to the mDNS groups on `interface`, or on every
+    // interface if unset.
+    pub(crate) async fn send(&self, raw: &[u8], interface: Option<&Interface>) -> Result<()> {
+        let interfaces = match interface {
+            Some(interface) => vec![interface.clone()],
+            None => multicast_interfaces(),
+        };
+
+        let _guard = self.send_lock.lock().await;
+        let mut sent = false;
+        let mut last_err = None;
+        for interface in &interfaces {
+            if let Some(IpAddr::V4(ip)) = interface.addrs.iter().find(|addr| addr.is_ipv4()) {
+                match self.send_v4(raw, *ip).await {
+                    Ok(()) => sent = true,
+                    Err(e) => {
+                        log::trace!("Failed to send on {}: {:?}", interface.name, e);
+                        last_err = Some(e);
+                    }
+                }
+            }
+            if let (Some(socket), Some(index)) = (&self.socket_v6, interface.index) {
+                match self.send_v6(socket, raw, index).await {
+                    Ok(()) => sent = true,
+                    Err(e) => {
+                        log::trace!("Failed to send on {}: {:?}", interface.name, e);
+                        last_err = Some(e);
+                    }
+                }
+            }
+        }
+
+        if sent {
+            Ok(())
+        } else if interface.is_none() || last_err.is_none() {
+            // Without a multicast interface the routing table decides.
+            self.socket_v4.send_to(raw, self.dst_addr).await?;
+            Ok(())
+        } else {
+            Err(last_err.unwrap().into())
+        }
+    }
+
+    async fn send_v4(&self, raw: &[u8], ip: Ipv4Addr) -> io::Result<()> {
+        SockRef::from(&self.socket_v4).set_multicast_if_v4(&ip)?;
+        self.socket_v4.send_to(raw, self.dst_addr).await?;
+        Ok(())
+    }
+
+    async fn send_v6(&self, socket: &UdpSocket, raw: &[u8], index: u32) -> io::Result<()> {
+        SockRef::from(socket).set_multicast_if_v6(index)?;
+        let dst = SocketAddrV6::new(MDNS_GROUP_V6, self.dst_addr.port(), 0, index);
+        socket.send_to(raw, dst).await?;
+        Ok(())
+    }
+}
+
+async fn readable(socket: Option<&UdpSocket>) -> io::Result<()> {
+    match socket {
+        Some(socket) => socket.readable().await,
+        None => std::future::pending().await,
+    }
+}
+
+fn new_socket(domain: socket2::Domain, addr: SocketAddr) -> Result<socket2::Socket> {
+    let socket = socket2::Socket::new(domain, socket2::Type::DGRAM, Some(socket2::Protocol::UDP))?;
+
+    #[cfg(feature = "reuse_port")]
+    #[cfg(target_family = "unix")]
+    socket.set_reuse_port(true)?;
+
+    socket.set_reuse_address(true)?;
+    if domain == socket2::Domain::IPV4 {
+        socket.set_broadcast(true)?;
+    } else {
+        socket.set_only_v6(true)?;
+    }
+    socket.set_nonblocking(true)?;
+
+    socket.bind(&SockAddr::from(addr))?;
+    Ok(socket)
+}
+
+fn new_socket_v6(port: u16) -> Result<socket2::Socket> {
+    let socket = new_socket(
+        socket2::Domain::IPV6,
+        SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), port),
+    )?;
+
+    let mut joined = false;
+    for interface in interfaces() {
+        if let Some(index) = interface.index {
+            match socket.join_multicast_v6(&MDNS_GROUP_V6, index) {
+                Ok(()) => joined = true,
+                Err(e) => log::trace!(
+                    "Error joining {} on {}: {:?}",
+                    MDNS_GROUP_V6,
+                    interface.name,
+                    e
+                ),
+            }
+        }
+    }
+    if !joined {
+        return Err(Error::ErrJoiningMulticastGroup);
+    }
+
+    Ok(socket)
+}
@@ -16,6 +16,12 @@ pub enum Error {
     ErrContextElapsed,
     #[error("mDNS: config must not be nil")]
     ErrNilConfig,
+    #[error("mDNS: invalid service instance, type or host name")]
+    ErrInvalidService,
+    #[error("mDNS: service instance name conflict could not be resolved")]
+    ErrServiceNameConflict,
+    #[error("mDNS: service is not registered")]
+    ErrServiceNotRegistered,
     #[error("parsing/packing of this type isn't available yet")]
     ErrNotStarted,
     #[error("parsing/packing of this section has completed")]
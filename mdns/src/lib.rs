@@ -5,5 +5,6 @@ pub mod config;
 pub mod conn;
 mod error;
 pub mod message;
+pub mod service;
 
 pub use error::Error;
@@ -18,6 +18,10 @@ impl ResourceBody for AResource {
         DnsType::A
     }
 
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     // pack appends the wire format of the AResource to msg.
     fn pack(
         &self,
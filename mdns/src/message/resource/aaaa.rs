@@ -18,6 +18,10 @@ impl ResourceBody for AaaaResource {
         DnsType::Aaaa
     }
 
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     // pack appends the wire format of the AAAAResource to msg.
     fn pack(
         &self,
@@ -18,6 +18,10 @@ impl ResourceBody for CnameResource {
         DnsType::Cname
     }
 
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     // pack appends the wire format of the cnameresource to msg.
     fn pack(
         &self,
@@ -9,6 +9,7 @@ pub mod soa;
 pub mod srv;
 pub mod txt;
 
+use std::any::Any;
 use std::collections::HashMap;
 use std::fmt;
 
@@ -231,11 +232,15 @@ impl ResourceHeader {
 }
 
 // A ResourceBody is a DNS resource record minus the header.
-pub trait ResourceBody: fmt::Display + fmt::Debug {
+pub trait ResourceBody: fmt::Display + fmt::Debug + Send + Sync {
     // real_type returns the actual type of the Resource. This is used to
     // fill in the header Type field.
     fn real_type(&self) -> DnsType;
 
+    // as_any returns the ResourceBody as Any, so it can be downcast to its
+    // concrete type.
+    fn as_any(&self) -> &dyn Any;
+
     // pack packs a Resource except for its header.
     fn pack(
         &self,
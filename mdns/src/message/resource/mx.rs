@@ -25,6 +25,10 @@ impl ResourceBody for MxResource {
         DnsType::Mx
     }
 
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     // pack appends the wire format of the MXResource to msg.
     fn pack(
         &self,
@@ -19,6 +19,10 @@ impl ResourceBody for NsResource {
         DnsType::Ns
     }
 
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     // pack appends the wire format of the NSResource to msg.
     fn pack(
         &self,
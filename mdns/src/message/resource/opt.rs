@@ -43,6 +43,10 @@ impl ResourceBody for OptResource {
         DnsType::Opt
     }
 
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     fn pack(
         &self,
         mut msg: Vec<u8>,
@@ -19,6 +19,10 @@ impl ResourceBody for PtrResource {
         DnsType::Ptr
     }
 
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     // pack appends the wire format of the PTRResource to msg.
     fn pack(
         &self,
@@ -40,6 +40,10 @@ impl ResourceBody for SoaResource {
         DnsType::Soa
     }
 
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     // pack appends the wire format of the SOAResource to msg.
     fn pack(
         &self,
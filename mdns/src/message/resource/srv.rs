@@ -27,6 +27,10 @@ impl ResourceBody for SrvResource {
         DnsType::Srv
     }
 
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     // pack appends the wire format of the SRVResource to msg.
     fn pack(
         &self,
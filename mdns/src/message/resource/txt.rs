@@ -23,6 +23,10 @@ impl ResourceBody for TxtResource {
         DnsType::Txt
     }
 
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
     // pack appends the wire format of the TXTResource to msg.
     fn pack(
         &self,
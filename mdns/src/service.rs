@@ -0,0 +1,436 @@
+#[cfg(test)]
+mod service_test;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use crate::error::*;
+use crate::message::name::*;
+use crate::message::question::*;
+use crate::message::resource::a::*;
+use crate::message::resource::aaaa::*;
+use crate::message::resource::ptr::*;
+use crate::message::resource::srv::*;
+use crate::message::resource::txt::*;
+use crate::message::resource::*;
+use crate::message::*;
+
+pub const DEFAULT_DOMAIN: &str = "local";
+
+// SERVICES_NAME is the name used to enumerate the service types advertised
+// on the link (RFC 6763 Section 9).
+pub const SERVICES_NAME: &str = "_services._dns-sd._udp.local.";
+
+// Records that are unique to the host publishing them set the cache-flush
+// bit of their class (RFC 6762 Section 10.2).
+const CLASS_CACHE_FLUSH: u16 = 0x8000;
+
+// Questions asking for a unicast response set the same bit of their class
+// (RFC 6762 Section 5.4).
+pub(crate) const CLASS_UNICAST_RESPONSE: u16 = 0x8000;
+
+// TTLs recommended by RFC 6762 Section 10 for records containing a host name
+// and for the other records.
+pub(crate) const HOST_RECORD_TTL: u32 = 120;
+pub(crate) const SERVICE_RECORD_TTL: u32 = 4500;
+
+// ServiceInfo describes a DNS-SD service instance (RFC 6763), either
+// registered on a DnsConn or found by browsing.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct ServiceInfo {
+    // instance is the user-visible name of the service instance, such as
+    // "Living Room Camera". It must not contain dots.
+    pub instance: String,
+
+    // service is the service type, such as "_webrtc._udp".
+    pub service: String,
+
+    // domain is the domain of the service, DEFAULT_DOMAIN if empty.
+    pub domain: String,
+
+    // host_name is the host providing the service, such as "camera.local".
+    pub host_name: String,
+
+    pub port: u16,
+
+    // txt are the key=value strings of the TXT record.
+    pub txt: Vec<String>,
+
+    // addrs are the addresses of host_name. When registering, the addresses
+    // of the interface a question was received on are published if empty.
+    pub addrs: Vec<IpAddr>,
+}
+
+impl fmt::Display for ServiceInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at {}:{} {:?}",
+            self.instance_name(),
+            self.host_name,
+            self.port,
+            self.addrs
+        )
+    }
+}
+
+impl ServiceInfo {
+    // service_name returns the fully qualified name of the service type,
+    // such as "_webrtc._udp.local.".
+    pub fn service_name(&self) -> String {
+        let domain = if self.domain.is_empty() {
+            DEFAULT_DOMAIN
+        } else {
+            self.domain.trim_end_matches('.')
+        };
+        format!("{}.{}.", self.service, domain)
+    }
+
+    // instance_name returns the fully qualified name of the service instance,
+    // such as "Living Room Camera._webrtc._udp.local.".
+    pub fn instance_name(&self) -> String {
+        format!("{}.{}", self.instance, self.service_name())
+    }
+
+    pub(crate) fn host_fqdn(&self) -> String {
+        fqdn(&self.host_name)
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        let labels: Vec<&str> = self.service.split('.').collect();
+        if self.instance.is_empty()
+            || self.instance.contains('.')
+            || self.host_name.is_empty()
+            || labels.len() != 2
+            || !labels[0].starts_with('_')
+            || (labels[1] != "_tcp" && labels[1] != "_udp")
+        {
+            return Err(Error::ErrInvalidService);
+        }
+        Ok(())
+    }
+
+    // ptr_record returns the record pointing the service type to the instance.
+    pub(crate) fn ptr_record(&self, ttl: u32) -> Result<Resource> {
+        Ok(Resource {
+            header: ResourceHeader {
+                name: Name::new(&self.service_name())?,
+                typ: DnsType::Ptr,
+                class: DNSCLASS_INET,
+                ttl,
+                ..Default::default()
+            },
+            body: Some(Box::new(PtrResource {
+                ptr: Name::new(&self.instance_name())?,
+            })),
+        })
+    }
+
+    // services_record returns the record enumerating the service type.
+    pub(crate) fn services_record(&self, ttl: u32) -> Result<Resource> {
+        Ok(Resource {
+            header: ResourceHeader {
+                name: Name::new(SERVICES_NAME)?,
+                typ: DnsType::Ptr,
+                class: DNSCLASS_INET,
+                ttl,
+                ..Default::default()
+            },
+            body: Some(Box::new(PtrResource {
+                ptr: Name::new(&self.service_name())?,
+            })),
+        })
+    }
+
+    pub(crate) fn srv_record(&self, ttl: u32) -> Result<Resource> {
+        Ok(Resource {
+            header: unique_header(&self.instance_name(), DnsType::Srv, ttl)?,
+            body: Some(Box::new(SrvResource {
+                priority: 0,
+                weight: 0,
+                port: self.port,
+                target: Name::new(&self.host_fqdn())?,
+            })),
+        })
+    }
+
+    pub(crate) fn txt_record(&self, ttl: u32) -> Result<Resource> {
+        // A TXT record holds at least one string (RFC 6763 Section 6.1).
+        let txt = if self.txt.is_empty() {
+            vec![String::new()]
+        } else {
+            self.txt.clone()
+        };
+        Ok(Resource {
+            header: unique_header(&self.instance_name(), DnsType::Txt, ttl)?,
+            body: Some(Box::new(TxtResource { txt })),
+        })
+    }
+
+    // addr_records returns the A and AAAA records of the host, with `addrs`
+    // if the service has no addresses of its own.
+    pub(crate) fn addr_records(&self, addrs: &[IpAddr], ttl: u32) -> Result<Vec<Resource>> {
+        let addrs = if self.addrs.is_empty() {
+            addrs
+        } else {
+            &self.addrs
+        };
+        addrs
+            .iter()
+            .map(|addr| addr_record(&self.host_fqdn(), *addr, ttl))
+            .collect()
+    }
+
+    // records returns all the records of the service, which are announced
+    // once it is registered.
+    pub(crate) fn records(&self, addrs: &[IpAddr], ttl: u32) -> Result<Vec<Resource>> {
+        let host_ttl = ttl.min(HOST_RECORD_TTL);
+        let mut records = vec![
+            self.ptr_record(ttl)?,
+            self.srv_record(host_ttl)?,
+            self.txt_record(ttl)?,
+        ];
+        records.extend(self.addr_records(addrs, host_ttl)?);
+        Ok(records)
+    }
+}
+
+// service_answers returns the answers and the additional records to the
+// question `q` about the registered `services`, whose host addresses are
+// `addrs` unless they have their own.
+pub(crate) fn service_answers(
+    services: &[ServiceInfo],
+    q: &Question,
+    addrs: &[IpAddr],
+) -> Result<(Vec<Resource>, Vec<Resource>)> {
+    let name = &q.name.data;
+    let any = q.typ == DnsType::All;
+    let (mut answers, mut additionals) = (vec![], vec![]);
+
+    if name.eq_ignore_ascii_case(SERVICES_NAME) && (any || q.typ == DnsType::Ptr) {
+        let mut service_names = vec![];
+        for service in services {
+            let service_name = service.service_name();
+            if !service_names.contains(&service_name) {
+                answers.push(service.services_record(SERVICE_RECORD_TTL)?);
+                service_names.push(service_name);
+            }
+        }
+    }
+
+    for service in services {
+        if name.eq_ignore_ascii_case(&service.service_name()) {
+            if any || q.typ == DnsType::Ptr {
+                answers.push(service.ptr_record(SERVICE_RECORD_TTL)?);
+                additionals.push(service.srv_record(HOST_RECORD_TTL)?);
+                additionals.push(service.txt_record(SERVICE_RECORD_TTL)?);
+                additionals.extend(service.addr_records(addrs, HOST_RECORD_TTL)?);
+            }
+        } else if name.eq_ignore_ascii_case(&service.instance_name()) {
+            if any || q.typ == DnsType::Srv {
+                answers.push(service.srv_record(HOST_RECORD_TTL)?);
+                additionals.extend(service.addr_records(addrs, HOST_RECORD_TTL)?);
+            }
+            if any || q.typ == DnsType::Txt {
+                answers.push(service.txt_record(SERVICE_RECORD_TTL)?);
+            }
+        } else if name.eq_ignore_ascii_case(&service.host_fqdn()) {
+            answers.extend(
+                service
+                    .addr_records(addrs, HOST_RECORD_TTL)?
+                    .into_iter()
+                    .filter(|r| any || r.header.typ == q.typ),
+            );
+        }
+    }
+
+    Ok((answers, additionals))
+}
+
+// ServiceEvent is reported while browsing for services.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServiceEvent {
+    // Resolved is reported when the host, port and addresses of an instance
+    // are known, and again whenever they change.
+    Resolved(ServiceInfo),
+
+    // Removed is reported when an instance sends its goodbye records.
+    Removed(ServiceInfo),
+}
+
+pub(crate) fn fqdn(name: &str) -> String {
+    if name.ends_with('.') {
+        name.to_owned()
+    } else {
+        name.to_owned() + "."
+    }
+}
+
+pub(crate) fn addr_record(name: &str, addr: IpAddr, ttl: u32) -> Result<Resource> {
+    let (typ, body): (DnsType, Box<dyn ResourceBody>) = match addr {
+        IpAddr::V4(ip) => (DnsType::A, Box::new(AResource { a: ip.octets() })),
+        IpAddr::V6(ip) => (DnsType::Aaaa, Box::new(AaaaResource { aaaa: ip.octets() })),
+    };
+    Ok(Resource {
+        header: unique_header(name, typ, ttl)?,
+        body: Some(body),
+    })
+}
+
+fn unique_header(name: &str, typ: DnsType, ttl: u32) -> Result<ResourceHeader> {
+    Ok(ResourceHeader {
+        name: Name::new(name)?,
+        typ,
+        class: DnsClass(DNSCLASS_INET.0 | CLASS_CACHE_FLUSH),
+        ttl,
+        ..Default::default()
+    })
+}
+
+struct BrowsedInstance {
+    info: ServiceInfo,
+    has_srv: bool,
+    reported: Option<ServiceInfo>,
+    queried: bool,
+    addrs_queried: bool,
+}
+
+// Browser keeps the records received while browsing for a service type, and
+// tells which instances are resolved.
+pub(crate) struct Browser {
+    template: ServiceInfo,
+    instances: HashMap<String, BrowsedInstance>,
+    addrs: HashMap<String, Vec<IpAddr>>,
+}
+
+impl Browser {
+    pub(crate) fn new(service: &str) -> Self {
+        Browser {
+            template: ServiceInfo {
+                service: service.to_owned(),
+                domain: DEFAULT_DOMAIN.to_owned(),
+                ..Default::default()
+            },
+            instances: HashMap::new(),
+            addrs: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn service_name(&self) -> String {
+        self.template.service_name()
+    }
+
+    // update applies the records of a response, returns the events to report
+    // and the questions resolving the instances which are not yet resolved.
+    pub(crate) fn update(&mut self, records: &[Resource]) -> (Vec<ServiceEvent>, Vec<Question>) {
+        let mut events = vec![];
+        let service_name = self.service_name();
+
+        for r in records {
+            let Some(body) = &r.body else {
+                continue;
+            };
+            let name = r.header.name.data.to_ascii_lowercase();
+
+            if let Some(ptr) = body.as_any().downcast_ref::<PtrResource>() {
+                if !name.eq_ignore_ascii_case(&service_name) {
+                    continue;
+                }
+                let instance_name = ptr.ptr.data.to_ascii_lowercase();
+                if r.header.ttl == 0 {
+                    // Goodbye (RFC 6762 Section 10.1).
+                    if let Some(instance) = self.instances.remove(&instance_name) {
+                        if let Some(reported) = instance.reported {
+                            events.push(ServiceEvent::Removed(reported));
+                        }
+                    }
+                    continue;
+                }
+                let suffix = format!(".{service_name}").to_ascii_lowercase();
+                if !instance_name.ends_with(&suffix) || instance_name.len() == suffix.len() {
+                    continue;
+                }
+                let instance = ptr.ptr.data[..instance_name.len() - suffix.len()].to_owned();
+                self.instances
+                    .entry(instance_name)
+                    .or_insert_with(|| BrowsedInstance {
+                        info: ServiceInfo {
+                            instance,
+                            ..self.template.clone()
+                        },
+                        has_srv: false,
+                        reported: None,
+                        queried: false,
+                        addrs_queried: false,
+                    });
+            } else if let Some(srv) = body.as_any().downcast_ref::<SrvResource>() {
+                if let Some(instance) = self.instances.get_mut(&name) {
+                    instance.info.host_name = srv.target.data.trim_end_matches('.').to_owned();
+                    instance.info.port = srv.port;
+                    instance.has_srv = true;
+                }
+            } else if let Some(txt) = body.as_any().downcast_ref::<TxtResource>() {
+                if let Some(instance) = self.instances.get_mut(&name) {
+                    instance.info.txt = txt.txt.iter().filter(|s| !s.is_empty()).cloned().collect();
+                }
+            } else {
+                let addr = if let Some(a) = body.as_any().downcast_ref::<AResource>() {
+                    IpAddr::V4(Ipv4Addr::from(a.a))
+                } else if let Some(aaaa) = body.as_any().downcast_ref::<AaaaResource>() {
+                    IpAddr::V6(Ipv6Addr::from(aaaa.aaaa))
+                } else {
+                    continue;
+                };
+                let addrs = self.addrs.entry(name).or_default();
+                if r.header.ttl == 0 {
+                    addrs.retain(|a| *a != addr);
+                } else if !addrs.contains(&addr) {
+                    addrs.push(addr);
+                }
+            }
+        }
+
+        let mut questions = vec![];
+        for (instance_name, instance) in &mut self.instances {
+            if !instance.has_srv {
+                if !instance.queried {
+                    instance.queried = true;
+                    if let Ok(name) = Name::new(instance_name) {
+                        questions.push(Question {
+                            name,
+                            typ: DnsType::All,
+                            class: DNSCLASS_INET,
+                        });
+                    }
+                }
+                continue;
+            }
+
+            let host = instance.info.host_fqdn().to_ascii_lowercase();
+            instance.info.addrs = self.addrs.get(&host).cloned().unwrap_or_default();
+            if instance.info.addrs.is_empty() {
+                if !instance.addrs_queried {
+                    instance.addrs_queried = true;
+                    if let Ok(name) = Name::new(&host) {
+                        for typ in [DnsType::A, DnsType::Aaaa] {
+                            questions.push(Question {
+                                name: name.clone(),
+                                typ,
+                                class: DNSCLASS_INET,
+                            });
+                        }
+                    }
+                }
+                continue;
+            }
+
+            if instance.reported.as_ref() != Some(&instance.info) {
+                instance.reported = Some(instance.info.clone());
+                events.push(ServiceEvent::Resolved(instance.info.clone()));
+            }
+        }
+
+        (events, questions)
+    }
+}
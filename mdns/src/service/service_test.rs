@@ -0,0 +1,167 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use super::*;
+
+fn test_service() -> ServiceInfo {
+    ServiceInfo {
+        instance: "Living Room".to_owned(),
+        service: "_webrtc._udp".to_owned(),
+        host_name: "camera.local".to_owned(),
+        port: 8443,
+        txt: vec!["path=/".to_owned()],
+        addrs: vec![
+            IpAddr::V4(Ipv4Addr::new(192, 168, 1, 2)),
+            IpAddr::V6(Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0, 2)),
+        ],
+        ..Default::default()
+    }
+}
+
+fn question(name: &str, typ: DnsType) -> Question {
+    Question {
+        name: Name::new(name).unwrap(),
+        typ,
+        class: DNSCLASS_INET,
+    }
+}
+
+fn types(records: &[Resource]) -> Vec<DnsType> {
+    records.iter().map(|r| r.header.typ).collect()
+}
+
+#[test]
+fn test_service_names() -> Result<()> {
+    let mut service = test_service();
+    assert_eq!(service.service_name(), "_webrtc._udp.local.");
+    assert_eq!(service.instance_name(), "Living Room._webrtc._udp.local.");
+    assert_eq!(service.host_fqdn(), "camera.local.");
+    service.validate()?;
+
+    service.domain = "example.com.".to_owned();
+    assert_eq!(service.service_name(), "_webrtc._udp.example.com.");
+
+    for invalid in [
+        ServiceInfo {
+            instance: "a.b".to_owned(),
+            ..test_service()
+        },
+        ServiceInfo {
+            service: "_webrtc".to_owned(),
+            ..test_service()
+        },
+        ServiceInfo {
+            service: "_webrtc._sctp".to_owned(),
+            ..test_service()
+        },
+        ServiceInfo {
+            host_name: String::new(),
+            ..test_service()
+        },
+    ] {
+        assert_eq!(invalid.validate(), Err(Error::ErrInvalidService));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_service_answers() -> Result<()> {
+    let services = vec![test_service()];
+    let addrs = [IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))];
+
+    let (answers, additionals) = service_answers(
+        &services,
+        &question("_webrtc._udp.local.", DnsType::Ptr),
+        &addrs,
+    )?;
+    assert_eq!(types(&answers), vec![DnsType::Ptr]);
+    assert_eq!(
+        types(&additionals),
+        vec![DnsType::Srv, DnsType::Txt, DnsType::A, DnsType::Aaaa]
+    );
+
+    let (answers, additionals) =
+        service_answers(&services, &question(SERVICES_NAME, DnsType::Ptr), &addrs)?;
+    assert_eq!(types(&answers), vec![DnsType::Ptr]);
+    assert!(additionals.is_empty());
+
+    let (answers, _) = service_answers(
+        &services,
+        &question("living room._webrtc._udp.local.", DnsType::All),
+        &addrs,
+    )?;
+    assert_eq!(types(&answers), vec![DnsType::Srv, DnsType::Txt]);
+
+    let (answers, _) =
+        service_answers(&services, &question("camera.local.", DnsType::Aaaa), &addrs)?;
+    assert_eq!(types(&answers), vec![DnsType::Aaaa]);
+
+    // The interface addresses are used without addresses of the service.
+    let services = vec![ServiceInfo {
+        addrs: vec![],
+        ..test_service()
+    }];
+    let (answers, _) =
+        service_answers(&services, &question("camera.local.", DnsType::All), &addrs)?;
+    assert_eq!(types(&answers), vec![DnsType::A]);
+
+    let (answers, _) = service_answers(
+        &services,
+        &question("_other._udp.local.", DnsType::Ptr),
+        &addrs,
+    )?;
+    assert!(answers.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_browser_update() -> Result<()> {
+    let service = test_service();
+    let mut browser = Browser::new("_webrtc._udp");
+
+    // Unrelated service types are ignored.
+    let other = ServiceInfo {
+        service: "_other._tcp".to_owned(),
+        host_name: "other.local".to_owned(),
+        ..test_service()
+    };
+    let (events, questions) = browser.update(&other.records(&[], SERVICE_RECORD_TTL)?);
+    assert!(events.is_empty());
+    assert!(questions.is_empty());
+
+    // A PTR record alone asks for the SRV and TXT records of the instance.
+    let (events, questions) = browser.update(&[service.ptr_record(SERVICE_RECORD_TTL)?]);
+    assert!(events.is_empty());
+    assert_eq!(questions.len(), 1);
+    assert_eq!(questions[0].name.data, "living room._webrtc._udp.local.");
+    assert_eq!(questions[0].typ, DnsType::All);
+
+    // Without addresses, they are asked for.
+    let (events, questions) = browser.update(&[
+        service.srv_record(HOST_RECORD_TTL)?,
+        service.txt_record(SERVICE_RECORD_TTL)?,
+    ]);
+    assert!(events.is_empty());
+    assert_eq!(
+        questions.iter().map(|q| q.typ).collect::<Vec<_>>(),
+        vec![DnsType::A, DnsType::Aaaa]
+    );
+
+    let resolved = ServiceInfo {
+        domain: DEFAULT_DOMAIN.to_owned(),
+        ..service.clone()
+    };
+    let (events, _) = browser.update(&service.addr_records(&[], HOST_RECORD_TTL)?);
+    assert_eq!(events, vec![ServiceEvent::Resolved(resolved.clone())]);
+
+    // Repeated records aren't reported again.
+    let (events, questions) = browser.update(&service.records(&[], SERVICE_RECORD_TTL)?);
+    assert!(events.is_empty());
+    assert!(questions.is_empty());
+
+    let (events, _) = browser.update(&service.records(&[], 0)?);
+    assert_eq!(events, vec![ServiceEvent::Removed(resolved)]);
+
+    Ok(())
+}
@@ -0,0 +1,249 @@
+use bytes::{BufMut, Bytes, BytesMut};
+
+use crate::audio::level::{audio_level, MIN_AUDIO_LEVEL};
+use crate::audio::Sample;
+use crate::error::{Error, Result};
+
+/// Maximum number of reflection coefficients in a comfort noise payload.
+pub const MAX_COMFORT_NOISE_ORDER: usize = 254;
+
+/// Comfort noise payload, as defined in [RFC 3389].
+///
+/// It describes the background noise during silence with its level and, optionally, the
+/// reflection coefficients of an all-pole filter approximating its spectrum. Receivers
+/// generate noise from it until the next packet with audio.
+///
+/// ```text
+///  0                   1                   2                   3
+///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |0|   level     |      N1       |      N2       |      ...      |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// ```
+///
+/// [RFC 3389]: https://tools.ietf.org/html/rfc3389
+#[derive(Eq, PartialEq, Clone, Default, Debug)]
+pub struct ComfortNoise {
+    /// Noise level in -dBov, from 0 to 127.
+    pub level: u8,
+    /// Quantized reflection coefficients, see [`ComfortNoise::reflection_coefficients`].
+    pub quantized_coefficients: Vec<u8>,
+}
+
+impl ComfortNoise {
+    /// Describes the noise in the given PCM samples with reflection coefficients up to `order`.
+    /// An order of 0 only carries the level, for white noise.
+    pub fn from_pcm<T>(samples: &[T], order: usize) -> Self
+    where
+        T: Copy,
+        Sample<T>: From<T>,
+        Sample<f32>: From<Sample<T>>,
+    {
+        let level = audio_level(samples);
+        let order = order.min(MAX_COMFORT_NOISE_ORDER);
+        if order == 0 || level == MIN_AUDIO_LEVEL {
+            return Self {
+                level,
+                quantized_coefficients: vec![],
+            };
+        }
+
+        let samples: Vec<f64> = samples
+            .iter()
+            .map(|&s| f32::from(Sample::<f32>::from(Sample::from(s))) as f64)
+            .collect();
+        let autocorrelation: Vec<f64> = (0..=order.min(samples.len() - 1))
+            .map(|lag| {
+                samples[lag..]
+                    .iter()
+                    .zip(&samples)
+                    .map(|(a, b)| a * b)
+                    .sum()
+            })
+            .collect();
+
+        Self {
+            level,
+            quantized_coefficients: reflection_coefficients(&autocorrelation)
+                .into_iter()
+                .map(quantize_coefficient)
+                .collect(),
+        }
+    }
+
+    /// Returns the reflection coefficients, between -1 and 1. Coefficient `q` is quantized as
+    /// `(q - 127) / 128`.
+    pub fn reflection_coefficients(&self) -> Vec<f32> {
+        self.quantized_coefficients
+            .iter()
+            .map(|&q| (q as f32 - 127.0) / 128.0)
+            .collect()
+    }
+
+    /// Parses a comfort noise payload.
+    pub fn unmarshal(payload: &[u8]) -> Result<Self> {
+        let (&level, coefficients) = payload
+            .split_first()
+            .ok_or(Error::ErrComfortNoisePayloadEmpty)?;
+        if level > MIN_AUDIO_LEVEL {
+            return Err(Error::ErrComfortNoiseLevelInvalid);
+        }
+        if coefficients.len() > MAX_COMFORT_NOISE_ORDER {
+            return Err(Error::ErrComfortNoiseOrderTooHigh);
+        }
+
+        Ok(Self {
+            level,
+            quantized_coefficients: coefficients.to_vec(),
+        })
+    }
+
+    /// Serializes the comfort noise payload.
+    pub fn marshal(&self) -> Result<Bytes> {
+        if self.level > MIN_AUDIO_LEVEL {
+            return Err(Error::ErrComfortNoiseLevelInvalid);
+        }
+        if self.quantized_coefficients.len() > MAX_COMFORT_NOISE_ORDER {
+            return Err(Error::ErrComfortNoiseOrderTooHigh);
+        }
+
+        let mut payload = BytesMut::with_capacity(1 + self.quantized_coefficients.len());
+        payload.put_u8(self.level);
+        payload.put_slice(&self.quantized_coefficients);
+        Ok(payload.freeze())
+    }
+}
+
+/// Computes the reflection coefficients of the all-pole model of a signal from its
+/// autocorrelation, with the Levinson-Durbin recursion.
+fn reflection_coefficients(autocorrelation: &[f64]) -> Vec<f64> {
+    let order = autocorrelation.len().saturating_sub(1);
+    let mut coefficients = vec![0.0; order];
+    let mut predictor = vec![0.0; order + 1];
+    predictor[0] = 1.0;
+    let mut error = autocorrelation[0];
+
+    for i in 1..=order {
+        if error <= f64::EPSILON * autocorrelation[0] {
+            break;
+        }
+        let acc: f64 = (0..i).map(|j| predictor[j] * autocorrelation[i - j]).sum();
+        let k = (-acc / error).clamp(-1.0, 1.0);
+        coefficients[i - 1] = k;
+
+        let previous = predictor.clone();
+        for j in 1..i {
+            predictor[j] = previous[j] + k * previous[i - j];
+        }
+        predictor[i] = k;
+        error *= 1.0 - k * k;
+    }
+
+    coefficients
+}
+
+fn quantize_coefficient(k: f64) -> u8 {
+    (k * 128.0 + 127.0).round().clamp(0.0, 254.0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn noise(len: usize) -> Vec<f32> {
+        // Deterministic pseudo random noise
+        let mut state = 0x1234_5678u32;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state as f32 / u32::MAX as f32 - 0.5) * 0.02
+            })
+            .collect()
+    }
+
+    #[test]
+    fn comfort_noise_marshal_unmarshal() {
+        let cn = ComfortNoise {
+            level: 60,
+            quantized_coefficients: vec![127, 0, 254],
+        };
+        let payload = cn.marshal().unwrap();
+        assert_eq!(payload, Bytes::from_static(&[60, 127, 0, 254]));
+        assert_eq!(ComfortNoise::unmarshal(&payload).unwrap(), cn);
+        assert_eq!(
+            cn.reflection_coefficients(),
+            vec![0.0, -127.0 / 128.0, 127.0 / 128.0]
+        );
+
+        // Level only
+        assert_eq!(
+            ComfortNoise::unmarshal(&[70]).unwrap(),
+            ComfortNoise {
+                level: 70,
+                quantized_coefficients: vec![],
+            }
+        );
+
+        assert_eq!(
+            ComfortNoise::unmarshal(&[]),
+            Err(Error::ErrComfortNoisePayloadEmpty)
+        );
+        assert_eq!(
+            ComfortNoise::unmarshal(&[128]),
+            Err(Error::ErrComfortNoiseLevelInvalid)
+        );
+        assert_eq!(
+            ComfortNoise {
+                level: 128,
+                quantized_coefficients: vec![],
+            }
+            .marshal(),
+            Err(Error::ErrComfortNoiseLevelInvalid)
+        );
+        assert_eq!(
+            ComfortNoise::unmarshal(&[60; MAX_COMFORT_NOISE_ORDER + 2]),
+            Err(Error::ErrComfortNoiseOrderTooHigh)
+        );
+    }
+
+    #[test]
+    fn comfort_noise_from_pcm() {
+        // White noise has a flat spectrum
+        let white = noise(960);
+        let cn = ComfortNoise::from_pcm(&white, 4);
+        assert_eq!(cn.level, audio_level(&white));
+        assert_eq!(cn.quantized_coefficients.len(), 4);
+        for k in cn.reflection_coefficients() {
+            assert!(k.abs() < 0.15, "{k}");
+        }
+
+        // Low pass filtered noise is strongly correlated with the previous sample
+        let mut previous = 0.0;
+        let low_pass: Vec<f32> = white
+            .iter()
+            .map(|s| {
+                previous = 0.9 * previous + s;
+                previous
+            })
+            .collect();
+        let cn = ComfortNoise::from_pcm(&low_pass, 4);
+        let coefficients = cn.reflection_coefficients();
+        assert!((coefficients[0] + 0.9).abs() < 0.1, "{coefficients:?}");
+        for k in &coefficients[1..] {
+            assert!(k.abs() < 0.15, "{k}");
+        }
+
+        // Level only
+        let cn = ComfortNoise::from_pcm(&white, 0);
+        assert!(cn.quantized_coefficients.is_empty());
+        assert_eq!(
+            ComfortNoise::from_pcm(&[0i16; 160], 4),
+            ComfortNoise {
+                level: MIN_AUDIO_LEVEL,
+                quantized_coefficients: vec![],
+            }
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+
+/// Discontinuous transmission (DTX) settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DtxConfig {
+    /// Interval at which a frame is still sent during silence, so that receivers keep
+    /// generating comfort noise and don't consider the stream as stopped. Opus encoders use
+    /// 400ms.
+    pub keep_alive_interval: Duration,
+}
+
+impl Default for DtxConfig {
+    fn default() -> Self {
+        Self {
+            keep_alive_interval: Duration::from_millis(400),
+        }
+    }
+}
+
+/// What to do with an audio frame during discontinuous transmission.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DtxAction {
+    /// Send the frame as is.
+    Send,
+    /// Send a frame describing the silence instead, e.g. an [`opus_dtx_frame`] or a
+    /// [`ComfortNoise`](crate::audio::comfort_noise::ComfortNoise) payload.
+    SendSilence,
+    /// Don't send the frame. The RTP timestamp still advances by its duration.
+    Skip,
+}
+
+/// Decides which frames to send with discontinuous transmission, based on their voice
+/// activity.
+///
+/// Frames with voice are always sent. The first frame of a silence period is sent as well, so
+/// that receivers get the transition, and frames are skipped after that, apart from one every
+/// keep alive interval.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DtxController {
+    config: DtxConfig,
+    /// Time since the last frame sent during silence, or None while there is voice
+    since_sent: Option<Duration>,
+}
+
+impl DtxController {
+    pub fn new(config: DtxConfig) -> Self {
+        Self {
+            config,
+            since_sent: None,
+        }
+    }
+
+    /// Get the DTX settings.
+    pub fn config(&self) -> &DtxConfig {
+        &self.config
+    }
+
+    /// Returns whether the controller is in a silence period.
+    pub fn is_silent(&self) -> bool {
+        self.since_sent.is_some()
+    }
+
+    /// Processes a frame of `duration`, with voice activity `voice` as reported by a
+    /// [`VoiceActivityDetector`](crate::audio::level::VoiceActivityDetector).
+    pub fn process(&mut self, voice: bool, duration: Duration) -> DtxAction {
+        if voice {
+            self.since_sent = None;
+            return DtxAction::Send;
+        }
+
+        match &mut self.since_sent {
+            None => {
+                self.since_sent = Some(Duration::ZERO);
+                DtxAction::Send
+            }
+            Some(since_sent) => {
+                *since_sent += duration;
+                if *since_sent >= self.config.keep_alive_interval {
+                    *since_sent = Duration::ZERO;
+                    DtxAction::SendSilence
+                } else {
+                    DtxAction::Skip
+                }
+            }
+        }
+    }
+}
+
+/// Returns the frame an Opus encoder sends during silence with DTX enabled, for the mode of
+/// the given Opus packet: its TOC byte alone, describing a single frame of 0 bytes. Decoders
+/// fill it with comfort noise. Returns None for an empty packet.
+pub fn opus_dtx_frame(packet: &[u8]) -> Option<Bytes> {
+    // Keep the configuration and stereo flag, with frame count code 0
+    let toc = packet.first()? & 0xFC;
+    Some(Bytes::copy_from_slice(&[toc]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dtx_controller_process() {
+        let frame = Duration::from_millis(20);
+        let mut dtx = DtxController::new(DtxConfig {
+            keep_alive_interval: Duration::from_millis(60),
+        });
+        assert_eq!(dtx.config().keep_alive_interval, Duration::from_millis(60));
+
+        assert_eq!(dtx.process(true, frame), DtxAction::Send);
+        assert!(!dtx.is_silent());
+
+        // The first silent frame is sent, then one every keep alive interval
+        let actions: Vec<DtxAction> = (0..8).map(|_| dtx.process(false, frame)).collect();
+        assert_eq!(
+            actions,
+            vec![
+                DtxAction::Send,
+                DtxAction::Skip,
+                DtxAction::Skip,
+                DtxAction::SendSilence,
+                DtxAction::Skip,
+                DtxAction::Skip,
+                DtxAction::SendSilence,
+                DtxAction::Skip,
+            ]
+        );
+        assert!(dtx.is_silent());
+
+        assert_eq!(dtx.process(true, frame), DtxAction::Send);
+        assert!(!dtx.is_silent());
+        assert_eq!(dtx.process(false, frame), DtxAction::Send);
+        assert_eq!(dtx.process(false, frame), DtxAction::Skip);
+    }
+
+    #[test]
+    fn opus_dtx_frame_from_packet() {
+        // SILK wideband 20ms stereo, two frames
+        assert_eq!(
+            opus_dtx_frame(&[0x4D, 0x01, 0x02]),
+            Some(Bytes::from_static(&[0x4C]))
+        );
+        assert_eq!(opus_dtx_frame(&[0xFC]), Some(Bytes::from_static(&[0xFC])));
+        assert_eq!(opus_dtx_frame(&[]), None);
+    }
+}
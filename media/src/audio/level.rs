@@ -0,0 +1,155 @@
+use std::time::Duration;
+
+use crate::audio::Sample;
+
+/// Audio level of digital silence, in -dBov.
+pub const MIN_AUDIO_LEVEL: u8 = 127;
+
+/// Returns the level of the given PCM samples in -dBov, as carried by the RTP audio level
+/// extension ([RFC 6464]) and comfort noise payloads ([RFC 3389]): 0 is the loudest signal
+/// possible and 127 is silence.
+///
+/// The level is the RMS of the samples relative to the overload point of the format, i.e. a
+/// full scale square wave. Samples of all channels are taken into account.
+///
+/// [RFC 6464]: https://tools.ietf.org/html/rfc6464
+/// [RFC 3389]: https://tools.ietf.org/html/rfc3389
+pub fn audio_level<T>(samples: &[T]) -> u8
+where
+    T: Copy,
+    Sample<T>: From<T>,
+    Sample<f32>: From<Sample<T>>,
+{
+    if samples.is_empty() {
+        return MIN_AUDIO_LEVEL;
+    }
+
+    let energy: f64 = samples
+        .iter()
+        .map(|&s| {
+            let s = f32::from(Sample::<f32>::from(Sample::from(s))) as f64;
+            s * s
+        })
+        .sum();
+    let mean_square = energy / samples.len() as f64;
+    if mean_square <= 0.0 {
+        return MIN_AUDIO_LEVEL;
+    }
+
+    let dbov = 10.0 * mean_square.log10();
+    (-dbov).round().clamp(0.0, MIN_AUDIO_LEVEL as f64) as u8
+}
+
+/// Energy based voice activity detector.
+///
+/// Audio is considered to contain voice while its level is above a threshold, and for a
+/// hangover period after it drops below, so that the end of words isn't cut.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoiceActivityDetector {
+    threshold: u8,
+    hangover: Duration,
+    since_voice: Option<Duration>,
+}
+
+impl Default for VoiceActivityDetector {
+    fn default() -> Self {
+        Self::new(50, Duration::from_millis(200))
+    }
+}
+
+impl VoiceActivityDetector {
+    /// Creates a detector reporting voice for audio levels of `threshold` -dBov or louder.
+    pub fn new(threshold: u8, hangover: Duration) -> Self {
+        Self {
+            threshold,
+            hangover,
+            since_voice: None,
+        }
+    }
+
+    /// Get the level in -dBov at or above which audio is considered voice.
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Get the time voice is still reported for after the level drops below the threshold.
+    pub fn hangover(&self) -> Duration {
+        self.hangover
+    }
+
+    /// Processes a frame of `duration` with the given audio level, as returned by
+    /// [`audio_level`], and returns whether it contains voice.
+    pub fn process(&mut self, level: u8, duration: Duration) -> bool {
+        if level <= self.threshold {
+            self.since_voice = Some(Duration::ZERO);
+            return true;
+        }
+
+        match &mut self.since_voice {
+            Some(since_voice) => {
+                *since_voice += duration;
+                if *since_voice <= self.hangover {
+                    true
+                } else {
+                    self.since_voice = None;
+                    false
+                }
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(amplitude: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| amplitude * (i as f32 * 2.0 * std::f32::consts::PI / 48.0).sin())
+            .collect()
+    }
+
+    #[test]
+    fn audio_level_of_pcm() {
+        assert_eq!(audio_level::<i16>(&[]), MIN_AUDIO_LEVEL);
+        assert_eq!(audio_level(&[0i16; 480]), MIN_AUDIO_LEVEL);
+        assert_eq!(audio_level(&[i16::MAX, i16::MIN].repeat(240)), 0);
+        assert_eq!(audio_level(&[1.0f32, -1.0].repeat(240)), 0);
+
+        // A full scale sine is 3 dB below a full scale square wave
+        assert_eq!(audio_level(&sine(1.0, 480)), 3);
+        assert_eq!(audio_level(&sine(0.1, 480)), 23);
+
+        let samples: Vec<i16> = sine(0.01, 480)
+            .into_iter()
+            .map(|s| (s * i16::MAX as f32) as i16)
+            .collect();
+        assert_eq!(audio_level(&samples), 43);
+
+        // Quieter than the format can represent
+        assert_eq!(audio_level(&sine(1e-9, 480)), MIN_AUDIO_LEVEL);
+    }
+
+    #[test]
+    fn voice_activity_detector_hangover() {
+        let frame = Duration::from_millis(20);
+        let mut vad = VoiceActivityDetector::new(40, Duration::from_millis(60));
+
+        assert!(!vad.process(MIN_AUDIO_LEVEL, frame));
+        assert!(!vad.process(41, frame));
+        assert!(vad.process(40, frame));
+        assert!(vad.process(10, frame));
+
+        // Voice is still reported during the hangover
+        assert!(vad.process(MIN_AUDIO_LEVEL, frame));
+        assert!(vad.process(MIN_AUDIO_LEVEL, frame));
+        assert!(vad.process(MIN_AUDIO_LEVEL, frame));
+        assert!(!vad.process(MIN_AUDIO_LEVEL, frame));
+        assert!(!vad.process(MIN_AUDIO_LEVEL, frame));
+
+        // And restarts with voice
+        assert!(vad.process(30, frame));
+        assert!(vad.process(MIN_AUDIO_LEVEL, frame));
+    }
+}
@@ -1,4 +1,7 @@
 pub mod buffer;
+pub mod comfort_noise;
+pub mod dtx;
+pub mod level;
 mod sample;
 
 pub use sample::Sample;
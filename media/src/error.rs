@@ -40,6 +40,13 @@ pub enum Error {
 
     #[error("data is not a H264 bitstream")]
     ErrDataIsNotH264Stream,
+
+    #[error("comfort noise payload is empty")]
+    ErrComfortNoisePayloadEmpty,
+    #[error("comfort noise level must be between 0 and 127")]
+    ErrComfortNoiseLevelInvalid,
+    #[error("too many comfort noise reflection coefficients")]
+    ErrComfortNoiseOrderTooHigh,
     #[error("Io EOF")]
     ErrIoEOF,
 
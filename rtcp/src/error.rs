@@ -96,6 +96,12 @@ pub enum Error {
     BadStructMemberType,
     #[error("Cannot read into non-pointer")]
     BadReadParameter,
+    /// Measured overhead does not fit into 9 bits.
+    #[error("Measured overhead must be < 512")]
+    InvalidOverhead,
+    /// Temporal layer ID does not fit into 3 bits.
+    #[error("Temporal layer ID must be < 8")]
+    InvalidTemporalLayerId,
 
     #[error("{0}")]
     Util(#[from] util::Error),
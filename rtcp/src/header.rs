@@ -35,6 +35,24 @@ pub const FORMAT_REMB: u8 = 15;
 /// Transport and Payload specific feedback messages overload the count field to act as a message type. those are listed here.
 /// https://tools.ietf.org/html/draft-holmer-rmcat-transport-wide-cc-extensions-01#page-5
 pub const FORMAT_TCC: u8 = 15;
+/// Transport and Payload specific feedback messages overload the count field to act as a message type. those are listed here.
+/// https://www.rfc-editor.org/rfc/rfc8888.html#section-3.1
+pub const FORMAT_CCFB: u8 = 11;
+/// Transport and Payload specific feedback messages overload the count field to act as a message type. those are listed here.
+/// https://www.rfc-editor.org/rfc/rfc5104.html#section-4.2
+pub const FORMAT_TMMBR: u8 = 3;
+/// Transport and Payload specific feedback messages overload the count field to act as a message type. those are listed here.
+/// https://www.rfc-editor.org/rfc/rfc5104.html#section-4.2
+pub const FORMAT_TMMBN: u8 = 4;
+/// Transport and Payload specific feedback messages overload the count field to act as a message type. those are listed here.
+/// https://www.rfc-editor.org/rfc/rfc4585.html#section-6.3.3
+pub const FORMAT_RPSI: u8 = 3;
+/// Transport and Payload specific feedback messages overload the count field to act as a message type. those are listed here.
+/// https://www.rfc-editor.org/rfc/rfc9627.html#section-3
+pub const FORMAT_LRR: u8 = 10;
+/// Transport and Payload specific feedback messages overload the count field to act as a message type. those are listed here.
+/// https://www.rfc-editor.org/rfc/rfc4585.html#section-6.4
+pub const FORMAT_AFB: u8 = 15;
 
 impl std::fmt::Display for PacketType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -8,15 +8,20 @@ use crate::error::{Error, Result};
 use crate::extended_report::ExtendedReport;
 use crate::goodbye::*;
 use crate::header::*;
+use crate::payload_feedbacks::application_layer_feedback::*;
 use crate::payload_feedbacks::full_intra_request::*;
+use crate::payload_feedbacks::layer_refresh_request::*;
 use crate::payload_feedbacks::picture_loss_indication::*;
 use crate::payload_feedbacks::receiver_estimated_maximum_bitrate::*;
+use crate::payload_feedbacks::reference_picture_selection_indication::*;
 use crate::payload_feedbacks::slice_loss_indication::*;
 use crate::raw_packet::*;
 use crate::receiver_report::*;
 use crate::sender_report::*;
 use crate::source_description::*;
+use crate::transport_feedbacks::congestion_control_feedback::*;
 use crate::transport_feedbacks::rapid_resynchronization_request::*;
+use crate::transport_feedbacks::temporary_maximum_media_stream_bitrate::*;
 use crate::transport_feedbacks::transport_layer_cc::*;
 use crate::transport_feedbacks::transport_layer_nack::*;
 
@@ -104,13 +109,32 @@ where
             FORMAT_TLN => Box::new(TransportLayerNack::unmarshal(&mut in_packet)?),
             FORMAT_RRR => Box::new(RapidResynchronizationRequest::unmarshal(&mut in_packet)?),
             FORMAT_TCC => Box::new(TransportLayerCc::unmarshal(&mut in_packet)?),
+            FORMAT_CCFB => Box::new(CcFeedbackReport::unmarshal(&mut in_packet)?),
+            FORMAT_TMMBR => Box::new(TemporaryMaximumMediaStreamBitrateRequest::unmarshal(
+                &mut in_packet,
+            )?),
+            FORMAT_TMMBN => Box::new(TemporaryMaximumMediaStreamBitrateNotification::unmarshal(
+                &mut in_packet,
+            )?),
             _ => Box::new(RawPacket::unmarshal(&mut in_packet)?),
         },
         PacketType::PayloadSpecificFeedback => match h.count {
             FORMAT_PLI => Box::new(PictureLossIndication::unmarshal(&mut in_packet)?),
             FORMAT_SLI => Box::new(SliceLossIndication::unmarshal(&mut in_packet)?),
-            FORMAT_REMB => Box::new(ReceiverEstimatedMaximumBitrate::unmarshal(&mut in_packet)?),
+            FORMAT_AFB => {
+                // REMB is the only application layer feedback we know how to parse
+                let mut in_packet = in_packet.copy_to_bytes(in_packet.remaining());
+                if in_packet.get(HEADER_LENGTH + 8..HEADER_LENGTH + 12) == Some(b"REMB") {
+                    Box::new(ReceiverEstimatedMaximumBitrate::unmarshal(&mut in_packet)?)
+                } else {
+                    Box::new(ApplicationLayerFeedback::unmarshal(&mut in_packet)?)
+                }
+            }
             FORMAT_FIR => Box::new(FullIntraRequest::unmarshal(&mut in_packet)?),
+            FORMAT_RPSI => Box::new(ReferencePictureSelectionIndication::unmarshal(
+                &mut in_packet,
+            )?),
+            FORMAT_LRR => Box::new(LayerRefreshRequest::unmarshal(&mut in_packet)?),
             _ => Box::new(RawPacket::unmarshal(&mut in_packet)?),
         },
         PacketType::ExtendedReport => Box::new(ExtendedReport::unmarshal(&mut in_packet)?),
@@ -0,0 +1,118 @@
+use super::*;
+use crate::payload_feedbacks::receiver_estimated_maximum_bitrate::ReceiverEstimatedMaximumBitrate;
+
+#[test]
+fn test_application_layer_feedback_unmarshal() {
+    let tests = vec![
+        (
+            "valid",
+            Bytes::from_static(&[
+                0x8f, 0xce, 0x00, 0x03, // v=2, p=0, FMT=15, PSFB, len=3
+                0x90, 0x2f, 0x9e, 0x2e, // sender=0x902f9e2e
+                0x4b, 0xc4, 0xfc, 0xb4, // media=0x4bc4fcb4
+                0x01, 0x02, 0x03, 0x04, // data
+            ]),
+            ApplicationLayerFeedback {
+                sender_ssrc: 0x902f9e2e,
+                media_ssrc: 0x4bc4fcb4,
+                data: Bytes::from_static(&[0x01, 0x02, 0x03, 0x04]),
+            },
+            None,
+        ),
+        (
+            "packet too short",
+            Bytes::from_static(&[0x00, 0x00, 0x00, 0x00]),
+            ApplicationLayerFeedback::default(),
+            Some(Error::PacketTooShort),
+        ),
+        (
+            "wrong type",
+            Bytes::from_static(&[
+                0x8f, 0xcd, 0x00, 0x02, // v=2, p=0, FMT=15, RTPFB, len=2
+                0x90, 0x2f, 0x9e, 0x2e, // sender=0x902f9e2e
+                0x4b, 0xc4, 0xfc, 0xb4, // media=0x4bc4fcb4
+            ]),
+            ApplicationLayerFeedback::default(),
+            Some(Error::WrongType),
+        ),
+    ];
+
+    for (name, mut data, want, want_error) in tests {
+        let got = ApplicationLayerFeedback::unmarshal(&mut data);
+
+        assert_eq!(
+            got.is_err(),
+            want_error.is_some(),
+            "Unmarshal {name}: err = {got:?}, want {want_error:?}"
+        );
+
+        if let Some(err) = want_error {
+            let got_err = got.err().unwrap();
+            assert_eq!(
+                err, got_err,
+                "Unmarshal {name}: err = {got_err:?}, want {err:?}",
+            );
+        } else {
+            let actual = got.unwrap();
+            assert_eq!(
+                actual, want,
+                "Unmarshal {name}: got {actual:?}, want {want:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_application_layer_feedback_round_trip() {
+    let want = ApplicationLayerFeedback {
+        sender_ssrc: 1,
+        media_ssrc: 2,
+        data: Bytes::from_static(b"GOOG"),
+    };
+    let mut data = want.marshal().expect("Marshal");
+    let actual = ApplicationLayerFeedback::unmarshal(&mut data).expect("Unmarshal");
+    assert_eq!(actual, want);
+
+    // data is zero padded to a 32-bit boundary
+    let unaligned = ApplicationLayerFeedback {
+        data: Bytes::from_static(b"abc"),
+        ..Default::default()
+    };
+    let mut data = unaligned.marshal().expect("Marshal");
+    assert_eq!(data.len(), 16);
+    let actual = ApplicationLayerFeedback::unmarshal(&mut data).expect("Unmarshal");
+    assert_eq!(actual.data, Bytes::from_static(b"abc\0"));
+}
+
+#[test]
+fn test_application_layer_feedback_unmarshal_packet() {
+    let afb = ApplicationLayerFeedback {
+        sender_ssrc: 1,
+        media_ssrc: 2,
+        data: Bytes::from_static(b"GOOG"),
+    };
+    let remb = ReceiverEstimatedMaximumBitrate {
+        sender_ssrc: 1,
+        bitrate: 8927168.0,
+        ssrcs: vec![1215622422],
+    };
+
+    let mut data = bytes::BytesMut::new();
+    data.extend_from_slice(&afb.marshal().expect("Marshal"));
+    data.extend_from_slice(&remb.marshal().expect("Marshal"));
+    let packets = crate::packet::unmarshal(&mut data.freeze()).expect("Unmarshal");
+
+    assert_eq!(packets.len(), 2);
+    assert_eq!(
+        packets[0]
+            .as_any()
+            .downcast_ref::<ApplicationLayerFeedback>(),
+        Some(&afb)
+    );
+    assert_eq!(
+        packets[1]
+            .as_any()
+            .downcast_ref::<ReceiverEstimatedMaximumBitrate>(),
+        Some(&remb)
+    );
+}
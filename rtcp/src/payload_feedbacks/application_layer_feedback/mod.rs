@@ -0,0 +1,146 @@
+#[cfg(test)]
+mod application_layer_feedback_test;
+
+use std::any::Any;
+use std::fmt;
+
+use bytes::{Buf, BufMut, Bytes};
+use util::marshal::{Marshal, MarshalSize, Unmarshal};
+
+use crate::error::Error;
+use crate::header::*;
+use crate::packet::*;
+use crate::util::*;
+
+type Result<T> = std::result::Result<T, util::Error>;
+
+const AFB_OFFSET: usize = 8;
+
+/// The ApplicationLayerFeedback packet carries feedback defined by the application rather
+/// than by RTP/RTCP. See RFC 4585 Section 6.4.
+///
+/// REMB is an application layer feedback message as well, [`crate::packet::unmarshal`]
+/// returns it as a [`ReceiverEstimatedMaximumBitrate`](crate::payload_feedbacks::receiver_estimated_maximum_bitrate::ReceiverEstimatedMaximumBitrate)
+/// and everything else as an ApplicationLayerFeedback.
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct ApplicationLayerFeedback {
+    /// SSRC of sender
+    pub sender_ssrc: u32,
+    /// SSRC of the media source
+    pub media_ssrc: u32,
+    /// The application data. It is zero padded to a multiple of 4 bytes on the wire,
+    /// so a received message may end with padding.
+    pub data: Bytes,
+}
+
+impl fmt::Display for ApplicationLayerFeedback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ApplicationLayerFeedback {:x} {:x} {:?}",
+            self.sender_ssrc, self.media_ssrc, self.data,
+        )
+    }
+}
+
+impl Packet for ApplicationLayerFeedback {
+    /// Header returns the Header associated with this packet.
+    fn header(&self) -> Header {
+        Header {
+            padding: false,
+            count: FORMAT_AFB,
+            packet_type: PacketType::PayloadSpecificFeedback,
+            length: ((self.marshal_size() / 4) - 1) as u16,
+        }
+    }
+
+    /// destination_ssrc returns an array of SSRC values that this packet refers to.
+    fn destination_ssrc(&self) -> Vec<u32> {
+        vec![self.media_ssrc]
+    }
+
+    fn raw_size(&self) -> usize {
+        HEADER_LENGTH + AFB_OFFSET + self.data.len() + get_padding_size(self.data.len())
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + Sync) {
+        self
+    }
+
+    fn equal(&self, other: &(dyn Packet + Send + Sync)) -> bool {
+        other.as_any().downcast_ref::<ApplicationLayerFeedback>() == Some(self)
+    }
+
+    fn cloned(&self) -> Box<dyn Packet + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+impl MarshalSize for ApplicationLayerFeedback {
+    fn marshal_size(&self) -> usize {
+        self.raw_size()
+    }
+}
+
+impl Marshal for ApplicationLayerFeedback {
+    /// Marshal encodes the ApplicationLayerFeedback
+    fn marshal_to(&self, mut buf: &mut [u8]) -> Result<usize> {
+        if buf.remaining_mut() < self.marshal_size() {
+            return Err(Error::BufferTooShort.into());
+        }
+
+        let h = self.header();
+        let n = h.marshal_to(buf)?;
+        buf = &mut buf[n..];
+
+        buf.put_u32(self.sender_ssrc);
+        buf.put_u32(self.media_ssrc);
+        buf.put_slice(&self.data);
+        for _ in 0..get_padding_size(self.data.len()) {
+            buf.put_u8(0);
+        }
+
+        Ok(self.marshal_size())
+    }
+}
+
+impl Unmarshal for ApplicationLayerFeedback {
+    /// Unmarshal decodes the ApplicationLayerFeedback
+    fn unmarshal<B>(raw_packet: &mut B) -> Result<Self>
+    where
+        Self: Sized,
+        B: Buf,
+    {
+        let raw_packet_len = raw_packet.remaining();
+        if raw_packet_len < (HEADER_LENGTH + AFB_OFFSET) {
+            return Err(Error::PacketTooShort.into());
+        }
+
+        let h = Header::unmarshal(raw_packet)?;
+
+        let total_length = HEADER_LENGTH + (4 * h.length) as usize;
+        if raw_packet_len < total_length || total_length < HEADER_LENGTH + AFB_OFFSET {
+            return Err(Error::PacketTooShort.into());
+        }
+
+        if h.packet_type != PacketType::PayloadSpecificFeedback || h.count != FORMAT_AFB {
+            return Err(Error::WrongType.into());
+        }
+
+        let sender_ssrc = raw_packet.get_u32();
+        let media_ssrc = raw_packet.get_u32();
+        let data = raw_packet.copy_to_bytes(total_length - HEADER_LENGTH - AFB_OFFSET);
+
+        if
+        /*h.padding &&*/
+        raw_packet.has_remaining() {
+            raw_packet.advance(raw_packet.remaining());
+        }
+
+        Ok(ApplicationLayerFeedback {
+            sender_ssrc,
+            media_ssrc,
+            data,
+        })
+    }
+}
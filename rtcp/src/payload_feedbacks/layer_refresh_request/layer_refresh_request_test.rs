@@ -0,0 +1,128 @@
+use bytes::Bytes;
+
+use super::*;
+
+#[test]
+fn test_layer_refresh_request_unmarshal() {
+    let tests = vec![
+        (
+            "valid",
+            Bytes::from_static(&[
+                0x8a, 0xce, 0x00, 0x08, // v=2, p=0, FMT=10, PSFB, len=8
+                0x90, 0x2f, 0x9e, 0x2e, // sender=0x902f9e2e
+                0x00, 0x00, 0x00, 0x00, // media=0
+                0x4b, 0xc4, 0xfc, 0xb4, // ssrc=0x4bc4fcb4
+                0x05, 0xe0, 0x00, 0x00, // seq=5, C=1, PT=96
+                0x02, 0x01, 0x01, 0x00, // TTID=2 TLID=1, CTID=1 CLID=0
+                0x12, 0x34, 0x56, 0x78, // ssrc=0x12345678
+                0x06, 0x61, 0x00, 0x00, // seq=6, C=0, PT=97
+                0x01, 0x00, 0x00, 0x00, // TTID=1 TLID=0
+            ]),
+            LayerRefreshRequest {
+                sender_ssrc: 0x902f9e2e,
+                media_ssrc: 0,
+                entries: vec![
+                    LrrEntry {
+                        ssrc: 0x4bc4fcb4,
+                        sequence_number: 5,
+                        payload_type: 96,
+                        target_layer: LayerId {
+                            temporal_id: 2,
+                            layer_id: 1,
+                        },
+                        current_layer: Some(LayerId {
+                            temporal_id: 1,
+                            layer_id: 0,
+                        }),
+                    },
+                    LrrEntry {
+                        ssrc: 0x12345678,
+                        sequence_number: 6,
+                        payload_type: 97,
+                        target_layer: LayerId {
+                            temporal_id: 1,
+                            layer_id: 0,
+                        },
+                        current_layer: None,
+                    },
+                ],
+            },
+            None,
+        ),
+        (
+            "packet too short",
+            Bytes::from_static(&[0x00, 0x00, 0x00, 0x00]),
+            LayerRefreshRequest::default(),
+            Some(Error::PacketTooShort),
+        ),
+        (
+            "wrong fmt",
+            Bytes::from_static(&[
+                0x84, 0xce, 0x00, 0x02, // v=2, p=0, FMT=4, PSFB, len=2
+                0x90, 0x2f, 0x9e, 0x2e, // sender=0x902f9e2e
+                0x00, 0x00, 0x00, 0x00, // media=0
+            ]),
+            LayerRefreshRequest::default(),
+            Some(Error::WrongType),
+        ),
+    ];
+
+    for (name, mut data, want, want_error) in tests {
+        let got = LayerRefreshRequest::unmarshal(&mut data);
+
+        assert_eq!(
+            got.is_err(),
+            want_error.is_some(),
+            "Unmarshal {name}: err = {got:?}, want {want_error:?}"
+        );
+
+        if let Some(err) = want_error {
+            let got_err = got.err().unwrap();
+            assert_eq!(
+                err, got_err,
+                "Unmarshal {name}: err = {got_err:?}, want {err:?}",
+            );
+        } else {
+            let actual = got.unwrap();
+            assert_eq!(
+                actual, want,
+                "Unmarshal {name}: got {actual:?}, want {want:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_layer_refresh_request_round_trip() {
+    let want = LayerRefreshRequest {
+        sender_ssrc: 1,
+        media_ssrc: 0,
+        entries: vec![LrrEntry {
+            ssrc: 2,
+            sequence_number: 255,
+            payload_type: 127,
+            target_layer: LayerId {
+                temporal_id: 7,
+                layer_id: 255,
+            },
+            current_layer: Some(LayerId {
+                temporal_id: 3,
+                layer_id: 2,
+            }),
+        }],
+    };
+
+    let mut data = want.marshal().expect("Marshal");
+    let packets = crate::packet::unmarshal(&mut data).expect("Unmarshal");
+    assert_eq!(packets.len(), 1);
+    assert_eq!(
+        packets[0].as_any().downcast_ref::<LayerRefreshRequest>(),
+        Some(&want)
+    );
+    assert_eq!(packets[0].destination_ssrc(), vec![2]);
+
+    let mut invalid = want;
+    invalid.entries[0].target_layer.temporal_id = 8;
+    let err = invalid.marshal().expect_err("Marshal");
+    assert_eq!(Error::InvalidTemporalLayerId, err);
+}
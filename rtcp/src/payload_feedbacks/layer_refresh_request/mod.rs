@@ -0,0 +1,218 @@
+#[cfg(test)]
+mod layer_refresh_request_test;
+
+use std::any::Any;
+use std::fmt;
+
+use bytes::{Buf, BufMut};
+use util::marshal::{Marshal, MarshalSize, Unmarshal};
+
+use crate::error::Error;
+use crate::header::*;
+use crate::packet::*;
+
+type Result<T> = std::result::Result<T, util::Error>;
+
+const LRR_OFFSET: usize = 8;
+const LRR_ENTRY_LENGTH: usize = 12;
+const TEMPORAL_ID_MAX: u8 = 0x7;
+
+/// A LayerId identifies a layer of a scalable stream by its temporal and spatial/quality layer.
+#[derive(Debug, PartialEq, Eq, Default, Clone, Copy)]
+pub struct LayerId {
+    /// Temporal layer ID, at most 7
+    pub temporal_id: u8,
+    /// Spatial or quality layer ID
+    pub layer_id: u8,
+}
+
+impl LayerId {
+    fn marshal(&self) -> Result<u16> {
+        if self.temporal_id > TEMPORAL_ID_MAX {
+            return Err(Error::InvalidTemporalLayerId.into());
+        }
+        Ok(((self.temporal_id as u16) << 8) | self.layer_id as u16)
+    }
+
+    fn unmarshal(v: u16) -> Self {
+        LayerId {
+            temporal_id: (v >> 8) as u8 & TEMPORAL_ID_MAX,
+            layer_id: v as u8,
+        }
+    }
+}
+
+/// A LrrEntry requests the refresh of a single layer of a media sender, as carried by
+/// LayerRefreshRequest.
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct LrrEntry {
+    /// SSRC of the media sender that is requested to refresh
+    pub ssrc: u32,
+    /// Command sequence number, incremented for every new request
+    pub sequence_number: u8,
+    /// The RTP payload type the layer IDs are to be interpreted with
+    pub payload_type: u8,
+    /// The layer that is requested to be refreshed
+    pub target_layer: LayerId,
+    /// The layer the requester currently decodes. If unset, all layers up to
+    /// and including the target layer are requested to be refreshed.
+    pub current_layer: Option<LayerId>,
+}
+
+/// The LayerRefreshRequest packet asks the encoder to refresh a layer of a
+/// scalable stream without sending a full intra frame. See RFC 9627 Section 3.
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct LayerRefreshRequest {
+    /// SSRC of sender
+    pub sender_ssrc: u32,
+    /// SSRC of the media source, not used and should be 0
+    pub media_ssrc: u32,
+    pub entries: Vec<LrrEntry>,
+}
+
+impl fmt::Display for LayerRefreshRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = format!("LayerRefreshRequest {:x}", self.sender_ssrc);
+        for e in &self.entries {
+            out += format!(
+                " ({:x} {} {:?} {:?})",
+                e.ssrc, e.sequence_number, e.target_layer, e.current_layer
+            )
+            .as_str();
+        }
+        write!(f, "{out}")
+    }
+}
+
+impl Packet for LayerRefreshRequest {
+    /// Header returns the Header associated with this packet.
+    fn header(&self) -> Header {
+        Header {
+            padding: false,
+            count: FORMAT_LRR,
+            packet_type: PacketType::PayloadSpecificFeedback,
+            length: ((self.marshal_size() / 4) - 1) as u16,
+        }
+    }
+
+    /// destination_ssrc returns an array of SSRC values that this packet refers to.
+    fn destination_ssrc(&self) -> Vec<u32> {
+        self.entries.iter().map(|e| e.ssrc).collect()
+    }
+
+    fn raw_size(&self) -> usize {
+        HEADER_LENGTH + LRR_OFFSET + self.entries.len() * LRR_ENTRY_LENGTH
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + Sync) {
+        self
+    }
+
+    fn equal(&self, other: &(dyn Packet + Send + Sync)) -> bool {
+        other.as_any().downcast_ref::<LayerRefreshRequest>() == Some(self)
+    }
+
+    fn cloned(&self) -> Box<dyn Packet + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+impl MarshalSize for LayerRefreshRequest {
+    fn marshal_size(&self) -> usize {
+        self.raw_size()
+    }
+}
+
+impl Marshal for LayerRefreshRequest {
+    /// Marshal encodes the LayerRefreshRequest
+    fn marshal_to(&self, mut buf: &mut [u8]) -> Result<usize> {
+        if buf.remaining_mut() < self.marshal_size() {
+            return Err(Error::BufferTooShort.into());
+        }
+
+        let h = self.header();
+        let n = h.marshal_to(buf)?;
+        buf = &mut buf[n..];
+
+        buf.put_u32(self.sender_ssrc);
+        buf.put_u32(self.media_ssrc);
+
+        for e in &self.entries {
+            if e.payload_type > 0x7F {
+                return Err(Error::WrongPayloadType.into());
+            }
+            buf.put_u32(e.ssrc);
+            buf.put_u8(e.sequence_number);
+            buf.put_u8((u8::from(e.current_layer.is_some()) << 7) | e.payload_type);
+            buf.put_u16(0);
+            buf.put_u16(e.target_layer.marshal()?);
+            buf.put_u16(e.current_layer.unwrap_or_default().marshal()?);
+        }
+
+        Ok(self.marshal_size())
+    }
+}
+
+impl Unmarshal for LayerRefreshRequest {
+    /// Unmarshal decodes the LayerRefreshRequest
+    fn unmarshal<B>(raw_packet: &mut B) -> Result<Self>
+    where
+        Self: Sized,
+        B: Buf,
+    {
+        let raw_packet_len = raw_packet.remaining();
+        if raw_packet_len < (HEADER_LENGTH + LRR_OFFSET) {
+            return Err(Error::PacketTooShort.into());
+        }
+
+        let h = Header::unmarshal(raw_packet)?;
+
+        let total_length = HEADER_LENGTH + (4 * h.length) as usize;
+        if raw_packet_len < total_length || total_length < HEADER_LENGTH + LRR_OFFSET {
+            return Err(Error::PacketTooShort.into());
+        }
+
+        if h.packet_type != PacketType::PayloadSpecificFeedback || h.count != FORMAT_LRR {
+            return Err(Error::WrongType.into());
+        }
+
+        let sender_ssrc = raw_packet.get_u32();
+        let media_ssrc = raw_packet.get_u32();
+
+        let mut i = HEADER_LENGTH + LRR_OFFSET;
+        let mut entries = vec![];
+        while i + LRR_ENTRY_LENGTH <= total_length {
+            let ssrc = raw_packet.get_u32();
+            let sequence_number = raw_packet.get_u8();
+            let b = raw_packet.get_u8();
+            raw_packet.get_u16();
+            let target_layer = LayerId::unmarshal(raw_packet.get_u16());
+            let current_layer = LayerId::unmarshal(raw_packet.get_u16());
+            entries.push(LrrEntry {
+                ssrc,
+                sequence_number,
+                payload_type: b & 0x7F,
+                target_layer,
+                current_layer: if b & 0x80 != 0 {
+                    Some(current_layer)
+                } else {
+                    None
+                },
+            });
+
+            i += LRR_ENTRY_LENGTH;
+        }
+
+        if
+        /*h.padding &&*/
+        raw_packet.has_remaining() {
+            raw_packet.advance(raw_packet.remaining());
+        }
+
+        Ok(LayerRefreshRequest {
+            sender_ssrc,
+            media_ssrc,
+            entries,
+        })
+    }
+}
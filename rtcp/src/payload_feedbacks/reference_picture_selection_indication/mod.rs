@@ -0,0 +1,180 @@
+#[cfg(test)]
+mod reference_picture_selection_indication_test;
+
+use std::any::Any;
+use std::fmt;
+
+use bytes::{Buf, BufMut, Bytes};
+use util::marshal::{Marshal, MarshalSize, Unmarshal};
+
+use crate::error::Error;
+use crate::header::*;
+use crate::packet::*;
+use crate::util::*;
+
+type Result<T> = std::result::Result<T, util::Error>;
+
+const RPSI_OFFSET: usize = 8;
+// padding bits and payload type
+const RPSI_FCI_HEADER_LENGTH: usize = 2;
+
+/// The ReferencePictureSelectionIndication packet tells the encoder which reference picture
+/// to use for further encoding. See RFC 4585 Section 6.3.3.
+///
+/// The bit string is defined by the codec and carried opaquely. It is always sent as whole
+/// bytes, a received bit string that ends in the middle of a byte includes that last byte.
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct ReferencePictureSelectionIndication {
+    /// SSRC of sender
+    pub sender_ssrc: u32,
+    /// SSRC of the media source
+    pub media_ssrc: u32,
+    /// The RTP payload type the bit string is to be interpreted with
+    pub payload_type: u8,
+    /// The codec specific native RPSI bit string
+    pub bit_string: Bytes,
+}
+
+impl ReferencePictureSelectionIndication {
+    fn fci_padding(&self) -> usize {
+        get_padding_size(RPSI_FCI_HEADER_LENGTH + self.bit_string.len())
+    }
+}
+
+impl fmt::Display for ReferencePictureSelectionIndication {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ReferencePictureSelectionIndication {:x} {:x} pt {} {:?}",
+            self.sender_ssrc, self.media_ssrc, self.payload_type, self.bit_string,
+        )
+    }
+}
+
+impl Packet for ReferencePictureSelectionIndication {
+    /// Header returns the Header associated with this packet.
+    fn header(&self) -> Header {
+        Header {
+            padding: false,
+            count: FORMAT_RPSI,
+            packet_type: PacketType::PayloadSpecificFeedback,
+            length: ((self.marshal_size() / 4) - 1) as u16,
+        }
+    }
+
+    /// destination_ssrc returns an array of SSRC values that this packet refers to.
+    fn destination_ssrc(&self) -> Vec<u32> {
+        vec![self.media_ssrc]
+    }
+
+    fn raw_size(&self) -> usize {
+        HEADER_LENGTH
+            + RPSI_OFFSET
+            + RPSI_FCI_HEADER_LENGTH
+            + self.bit_string.len()
+            + self.fci_padding()
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + Sync) {
+        self
+    }
+
+    fn equal(&self, other: &(dyn Packet + Send + Sync)) -> bool {
+        other
+            .as_any()
+            .downcast_ref::<ReferencePictureSelectionIndication>()
+            == Some(self)
+    }
+
+    fn cloned(&self) -> Box<dyn Packet + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+impl MarshalSize for ReferencePictureSelectionIndication {
+    fn marshal_size(&self) -> usize {
+        self.raw_size()
+    }
+}
+
+impl Marshal for ReferencePictureSelectionIndication {
+    /// Marshal encodes the ReferencePictureSelectionIndication
+    fn marshal_to(&self, mut buf: &mut [u8]) -> Result<usize> {
+        if self.payload_type > 0x7F {
+            return Err(Error::WrongPayloadType.into());
+        }
+        if buf.remaining_mut() < self.marshal_size() {
+            return Err(Error::BufferTooShort.into());
+        }
+
+        let h = self.header();
+        let n = h.marshal_to(buf)?;
+        buf = &mut buf[n..];
+
+        buf.put_u32(self.sender_ssrc);
+        buf.put_u32(self.media_ssrc);
+
+        let padding = self.fci_padding();
+        buf.put_u8((padding * 8) as u8);
+        buf.put_u8(self.payload_type);
+        buf.put_slice(&self.bit_string);
+        for _ in 0..padding {
+            buf.put_u8(0);
+        }
+
+        Ok(self.marshal_size())
+    }
+}
+
+impl Unmarshal for ReferencePictureSelectionIndication {
+    /// Unmarshal decodes the ReferencePictureSelectionIndication
+    fn unmarshal<B>(raw_packet: &mut B) -> Result<Self>
+    where
+        Self: Sized,
+        B: Buf,
+    {
+        let raw_packet_len = raw_packet.remaining();
+        if raw_packet_len < (HEADER_LENGTH + RPSI_OFFSET + 4) {
+            return Err(Error::PacketTooShort.into());
+        }
+
+        let h = Header::unmarshal(raw_packet)?;
+
+        let total_length = HEADER_LENGTH + (4 * h.length) as usize;
+        if raw_packet_len < total_length || total_length < HEADER_LENGTH + RPSI_OFFSET + 4 {
+            return Err(Error::PacketTooShort.into());
+        }
+
+        if h.packet_type != PacketType::PayloadSpecificFeedback || h.count != FORMAT_RPSI {
+            return Err(Error::WrongType.into());
+        }
+
+        let sender_ssrc = raw_packet.get_u32();
+        let media_ssrc = raw_packet.get_u32();
+
+        let padding_bits = raw_packet.get_u8() as usize;
+        let payload_type = raw_packet.get_u8() & 0x7F;
+
+        let fci_length = total_length - HEADER_LENGTH - RPSI_OFFSET - RPSI_FCI_HEADER_LENGTH;
+        // a partially used last byte is kept as part of the bit string
+        let padding = padding_bits / 8;
+        if padding > fci_length {
+            return Err(Error::WrongPadding.into());
+        }
+        let bit_string = raw_packet.copy_to_bytes(fci_length - padding);
+        raw_packet.advance(padding);
+
+        if
+        /*h.padding &&*/
+        raw_packet.has_remaining() {
+            raw_packet.advance(raw_packet.remaining());
+        }
+
+        Ok(ReferencePictureSelectionIndication {
+            sender_ssrc,
+            media_ssrc,
+            payload_type,
+            bit_string,
+        })
+    }
+}
@@ -0,0 +1,128 @@
+use super::*;
+
+#[test]
+fn test_reference_picture_selection_indication_unmarshal() {
+    let tests = vec![
+        (
+            "valid",
+            Bytes::from_static(&[
+                0x83, 0xce, 0x00, 0x03, // v=2, p=0, FMT=3, PSFB, len=3
+                0x90, 0x2f, 0x9e, 0x2e, // sender=0x902f9e2e
+                0x4b, 0xc4, 0xfc, 0xb4, // media=0x4bc4fcb4
+                0x08, 0x60, 0x85, 0x00, // PB=8, PT=96, bit string=0x85
+            ]),
+            ReferencePictureSelectionIndication {
+                sender_ssrc: 0x902f9e2e,
+                media_ssrc: 0x4bc4fcb4,
+                payload_type: 96,
+                bit_string: Bytes::from_static(&[0x85]),
+            },
+            None,
+        ),
+        (
+            "partial byte",
+            Bytes::from_static(&[
+                0x83, 0xce, 0x00, 0x03, // v=2, p=0, FMT=3, PSFB, len=3
+                0x90, 0x2f, 0x9e, 0x2e, // sender=0x902f9e2e
+                0x4b, 0xc4, 0xfc, 0xb4, // media=0x4bc4fcb4
+                0x04, 0x60, 0x85, 0x10, // PB=4, PT=96, bit string=0x851
+            ]),
+            ReferencePictureSelectionIndication {
+                sender_ssrc: 0x902f9e2e,
+                media_ssrc: 0x4bc4fcb4,
+                payload_type: 96,
+                bit_string: Bytes::from_static(&[0x85, 0x10]),
+            },
+            None,
+        ),
+        (
+            "invalid padding",
+            Bytes::from_static(&[
+                0x83, 0xce, 0x00, 0x03, // v=2, p=0, FMT=3, PSFB, len=3
+                0x90, 0x2f, 0x9e, 0x2e, // sender=0x902f9e2e
+                0x4b, 0xc4, 0xfc, 0xb4, // media=0x4bc4fcb4
+                0x20, 0x60, 0x85, 0x00, // PB=32, PT=96
+            ]),
+            ReferencePictureSelectionIndication::default(),
+            Some(Error::WrongPadding),
+        ),
+        (
+            "packet too short",
+            Bytes::from_static(&[
+                0x83, 0xce, 0x00, 0x02, // v=2, p=0, FMT=3, PSFB, len=2
+                0x90, 0x2f, 0x9e, 0x2e, // sender=0x902f9e2e
+                0x4b, 0xc4, 0xfc, 0xb4, // media=0x4bc4fcb4
+            ]),
+            ReferencePictureSelectionIndication::default(),
+            Some(Error::PacketTooShort),
+        ),
+        (
+            "wrong type",
+            Bytes::from_static(&[
+                0x83, 0xcd, 0x00, 0x03, // v=2, p=0, FMT=3, RTPFB, len=3
+                0x90, 0x2f, 0x9e, 0x2e, // sender=0x902f9e2e
+                0x4b, 0xc4, 0xfc, 0xb4, // media=0x4bc4fcb4
+                0x08, 0x60, 0x85, 0x00, // PB=8, PT=96, bit string=0x85
+            ]),
+            ReferencePictureSelectionIndication::default(),
+            Some(Error::WrongType),
+        ),
+    ];
+
+    for (name, mut data, want, want_error) in tests {
+        let got = ReferencePictureSelectionIndication::unmarshal(&mut data);
+
+        assert_eq!(
+            got.is_err(),
+            want_error.is_some(),
+            "Unmarshal {name}: err = {got:?}, want {want_error:?}"
+        );
+
+        if let Some(err) = want_error {
+            let got_err = got.err().unwrap();
+            assert_eq!(
+                err, got_err,
+                "Unmarshal {name}: err = {got_err:?}, want {err:?}",
+            );
+        } else {
+            let actual = got.unwrap();
+            assert_eq!(
+                actual, want,
+                "Unmarshal {name}: got {actual:?}, want {want:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_reference_picture_selection_indication_round_trip() {
+    for len in 0..6 {
+        let want = ReferencePictureSelectionIndication {
+            sender_ssrc: 1,
+            media_ssrc: 2,
+            payload_type: 100,
+            bit_string: Bytes::from(vec![0xAB; len]),
+        };
+
+        let mut data = want.marshal().expect("Marshal");
+        assert_eq!(data.len() % 4, 0);
+        assert_eq!(data.len(), want.marshal_size());
+
+        let packets = crate::packet::unmarshal(&mut data).expect("Unmarshal");
+        assert_eq!(packets.len(), 1);
+        assert_eq!(
+            packets[0]
+                .as_any()
+                .downcast_ref::<ReferencePictureSelectionIndication>(),
+            Some(&want),
+            "round trip with {len} bytes"
+        );
+    }
+
+    let invalid = ReferencePictureSelectionIndication {
+        payload_type: 0x80,
+        ..Default::default()
+    };
+    let err = invalid.marshal().expect_err("Marshal");
+    assert_eq!(Error::WrongPayloadType, err);
+}
@@ -0,0 +1,161 @@
+use bytes::Bytes;
+
+use super::*;
+
+fn example_report() -> CcFeedbackReport {
+    CcFeedbackReport {
+        sender_ssrc: 0x902f9e2e,
+        report_blocks: vec![CcFeedbackReportBlock {
+            media_ssrc: 0x4bc4fcb4,
+            begin_sequence: 0xfffe,
+            metric_blocks: vec![
+                CcFeedbackMetricBlock {
+                    received: true,
+                    ecn: Ecn::Ect0,
+                    arrival_time_offset: 0x10,
+                },
+                CcFeedbackMetricBlock::default(),
+                CcFeedbackMetricBlock {
+                    received: true,
+                    ecn: Ecn::Ce,
+                    arrival_time_offset: ARRIVAL_TIME_OFFSET_UNAVAILABLE,
+                },
+            ],
+        }],
+        report_timestamp: 0x12345678,
+    }
+}
+
+#[test]
+fn test_cc_feedback_report_unmarshal() {
+    let tests = vec![
+        (
+            "valid",
+            Bytes::from_static(&[
+                0x8b, 0xcd, 0x0, 0x6, // v=2, p=0, fmt=11, RTPFB, len=6
+                0x90, 0x2f, 0x9e, 0x2e, // sender=0x902f9e2e
+                0x4b, 0xc4, 0xfc, 0xb4, // media=0x4bc4fcb4
+                0xff, 0xfe, 0x0, 0x3, // begin_seq=0xfffe, num_reports=3
+                0xc0, 0x10, 0x0, 0x0, // received ECT(0) ato=16, lost
+                0xff, 0xff, 0x0, 0x0, // received CE ato=unavailable, padding
+                0x12, 0x34, 0x56, 0x78, // report timestamp
+            ]),
+            example_report(),
+            None,
+        ),
+        (
+            "no report blocks",
+            Bytes::from_static(&[
+                0x8b, 0xcd, 0x0, 0x2, // v=2, p=0, fmt=11, RTPFB, len=2
+                0x90, 0x2f, 0x9e, 0x2e, // sender=0x902f9e2e
+                0x12, 0x34, 0x56, 0x78, // report timestamp
+            ]),
+            CcFeedbackReport {
+                sender_ssrc: 0x902f9e2e,
+                report_blocks: vec![],
+                report_timestamp: 0x12345678,
+            },
+            None,
+        ),
+        (
+            "truncated metric blocks",
+            Bytes::from_static(&[
+                0x8b, 0xcd, 0x0, 0x4, // v=2, p=0, fmt=11, RTPFB, len=4
+                0x90, 0x2f, 0x9e, 0x2e, // sender=0x902f9e2e
+                0x4b, 0xc4, 0xfc, 0xb4, // media=0x4bc4fcb4
+                0x0, 0x1, 0x0, 0x8, // begin_seq=1, num_reports=8
+                0x12, 0x34, 0x56, 0x78, // report timestamp
+            ]),
+            CcFeedbackReport::default(),
+            Some(Error::PacketTooShort),
+        ),
+        (
+            "length too short",
+            Bytes::from_static(&[
+                0x8b, 0xcd, 0x0, 0x1, // v=2, p=0, fmt=11, RTPFB, len=1
+                0x90, 0x2f, 0x9e, 0x2e, // sender=0x902f9e2e
+                0x12, 0x34, 0x56, 0x78, // report timestamp
+            ]),
+            CcFeedbackReport::default(),
+            Some(Error::PacketTooShort),
+        ),
+        (
+            "wrong type",
+            Bytes::from_static(&[
+                0x81, 0xcd, 0x0, 0x2, // v=2, p=0, fmt=1, RTPFB, len=2
+                0x90, 0x2f, 0x9e, 0x2e, // sender=0x902f9e2e
+                0x12, 0x34, 0x56, 0x78,
+            ]),
+            CcFeedbackReport::default(),
+            Some(Error::WrongType),
+        ),
+        (
+            "nil",
+            Bytes::from_static(&[]),
+            CcFeedbackReport::default(),
+            Some(Error::PacketTooShort),
+        ),
+    ];
+
+    for (name, mut data, want, want_error) in tests {
+        let got = CcFeedbackReport::unmarshal(&mut data);
+
+        assert_eq!(
+            got.is_err(),
+            want_error.is_some(),
+            "Unmarshal {name}: err = {got:?}, want {want_error:?}"
+        );
+
+        if let Some(err) = want_error {
+            let got_err = got.err().unwrap();
+            assert_eq!(
+                err, got_err,
+                "Unmarshal {name}: err = {got_err:?}, want {err:?}",
+            );
+        } else {
+            let actual = got.unwrap();
+            assert_eq!(
+                actual, want,
+                "Unmarshal {name}: got {actual:?}, want {want:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_cc_feedback_report_roundtrip() {
+    let mut second_block = CcFeedbackReportBlock {
+        media_ssrc: 0x12345678,
+        begin_sequence: 100,
+        metric_blocks: vec![],
+    };
+    for i in 0..4 {
+        second_block.metric_blocks.push(CcFeedbackMetricBlock {
+            received: i != 2,
+            ecn: if i != 2 { Ecn::Ect1 } else { Ecn::NotEct },
+            arrival_time_offset: if i != 2 { 1024 - i } else { 0 },
+        });
+    }
+
+    let mut want = example_report();
+    want.report_blocks.push(second_block);
+
+    let mut data = want.marshal().expect("Marshal");
+    assert_eq!(data.len(), want.marshal_size());
+
+    let actual = CcFeedbackReport::unmarshal(&mut data).expect("Unmarshal");
+    assert_eq!(actual, want, "round trip: got {actual:?}, want {want:?}");
+    assert_eq!(actual.destination_ssrc(), vec![0x4bc4fcb4, 0x12345678]);
+}
+
+#[test]
+fn test_cc_feedback_report_unmarshal_packet() {
+    let mut data = example_report().marshal().expect("Marshal");
+    let packets = crate::packet::unmarshal(&mut data).expect("Unmarshal");
+
+    assert_eq!(packets.len(), 1);
+    assert_eq!(
+        packets[0].as_any().downcast_ref::<CcFeedbackReport>(),
+        Some(&example_report())
+    );
+}
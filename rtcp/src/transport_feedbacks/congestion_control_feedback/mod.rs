@@ -0,0 +1,310 @@
+#[cfg(test)]
+mod congestion_control_feedback_test;
+
+use std::any::Any;
+use std::fmt;
+
+use bytes::{Buf, BufMut};
+use util::marshal::{Marshal, MarshalSize, Unmarshal};
+
+use crate::error::Error;
+use crate::header::*;
+use crate::packet::*;
+use crate::util::*;
+
+/// Length of the fixed part of a report block: media SSRC, begin_seq and num_reports.
+const REPORT_BLOCK_OFFSET: usize = 8;
+/// Length of a metric block.
+const METRIC_BLOCK_LENGTH: usize = 2;
+/// Length of the report timestamp field.
+const REPORT_TIMESTAMP_LENGTH: usize = 4;
+/// The maximum number of metric blocks in a single report block.
+pub const MAX_REPORTED_PACKETS: usize = 16384;
+
+/// The arrival time offset value meaning "the packet arrived, but too long before the report
+/// timestamp to be represented".
+pub const ARRIVAL_TIME_OFFSET_UNAVAILABLE: u16 = 0x1FFF;
+/// The largest arrival time offset that can be represented, the offset is at least that large.
+pub const ARRIVAL_TIME_OFFSET_MAX: u16 = 0x1FFE;
+
+/// Ecn is the ECN marking of a received packet as defined in RFC 3168.
+#[derive(Debug, PartialEq, Eq, Default, Clone, Copy)]
+#[repr(u8)]
+pub enum Ecn {
+    /// Non ECN-Capable Transport.
+    #[default]
+    NotEct = 0,
+    /// ECN Capable Transport, ECT(1).
+    Ect1 = 1,
+    /// ECN Capable Transport, ECT(0).
+    Ect0 = 2,
+    /// Congestion Experienced.
+    Ce = 3,
+}
+
+impl From<u8> for Ecn {
+    fn from(v: u8) -> Self {
+        match v & 0x3 {
+            1 => Ecn::Ect1,
+            2 => Ecn::Ect0,
+            3 => Ecn::Ce,
+            _ => Ecn::NotEct,
+        }
+    }
+}
+
+/// CcFeedbackMetricBlock reports the reception of a single RTP packet.
+#[derive(Debug, PartialEq, Eq, Default, Clone, Copy)]
+pub struct CcFeedbackMetricBlock {
+    /// Whether the packet was received.
+    pub received: bool,
+    /// The ECN marking of the packet, only meaningful if `received` is set.
+    pub ecn: Ecn,
+    /// The arrival time of the packet relative to the report timestamp, in 1/1024 seconds.
+    /// Only meaningful if `received` is set.
+    pub arrival_time_offset: u16,
+}
+
+impl CcFeedbackMetricBlock {
+    fn marshal(&self) -> u16 {
+        if !self.received {
+            return 0;
+        }
+        (1 << 15) | ((self.ecn as u16) << 13) | (self.arrival_time_offset & 0x1FFF)
+    }
+
+    fn unmarshal(v: u16) -> Self {
+        CcFeedbackMetricBlock {
+            received: v & (1 << 15) != 0,
+            ecn: Ecn::from((v >> 13) as u8),
+            arrival_time_offset: v & 0x1FFF,
+        }
+    }
+}
+
+/// CcFeedbackReportBlock reports the reception of a range of RTP packets from a single SSRC.
+/// The i-th metric block refers to sequence number `begin_sequence + i`.
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct CcFeedbackReportBlock {
+    /// SSRC of the RTP stream being reported on.
+    pub media_ssrc: u32,
+    /// Sequence number of the first reported packet.
+    pub begin_sequence: u16,
+    pub metric_blocks: Vec<CcFeedbackMetricBlock>,
+}
+
+impl CcFeedbackReportBlock {
+    fn raw_size(&self) -> usize {
+        let l = self.metric_blocks.len() * METRIC_BLOCK_LENGTH;
+        REPORT_BLOCK_OFFSET + l + get_padding_size(l)
+    }
+}
+
+/// The CcFeedbackReport packet carries congestion control feedback for one or more RTP streams.
+/// ## Specifications
+///
+/// * [RFC 8888 §3.1]
+///
+/// [RFC 8888 §3.1]: https://www.rfc-editor.org/rfc/rfc8888.html#section-3.1
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct CcFeedbackReport {
+    /// SSRC of sender
+    pub sender_ssrc: u32,
+    pub report_blocks: Vec<CcFeedbackReportBlock>,
+    /// The middle 32 bits of the NTP timestamp at which the report was generated.
+    pub report_timestamp: u32,
+}
+
+impl fmt::Display for CcFeedbackReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = format!("CcFeedbackReport from {:x}\n", self.sender_ssrc);
+        out += format!("\tReport Timestamp {}\n", self.report_timestamp).as_str();
+        for block in &self.report_blocks {
+            out += format!(
+                "\tMedia Ssrc {:x} Begin Seq {} Reports {}\n",
+                block.media_ssrc,
+                block.begin_sequence,
+                block.metric_blocks.len()
+            )
+            .as_str();
+            for (i, metric) in block.metric_blocks.iter().enumerate() {
+                if metric.received {
+                    out += format!(
+                        "\t\t{}\treceived ecn {:?} ato {}\n",
+                        block.begin_sequence.wrapping_add(i as u16),
+                        metric.ecn,
+                        metric.arrival_time_offset
+                    )
+                    .as_str();
+                }
+            }
+        }
+        write!(f, "{out}")
+    }
+}
+
+impl Packet for CcFeedbackReport {
+    /// returns the Header associated with this packet.
+    fn header(&self) -> Header {
+        Header {
+            padding: get_padding_size(self.raw_size()) != 0,
+            count: FORMAT_CCFB,
+            packet_type: PacketType::TransportSpecificFeedback,
+            length: ((self.marshal_size() / 4) - 1) as u16,
+        }
+    }
+
+    /// destination_ssrc returns an array of SSRC values that this packet refers to.
+    fn destination_ssrc(&self) -> Vec<u32> {
+        self.report_blocks.iter().map(|b| b.media_ssrc).collect()
+    }
+
+    fn raw_size(&self) -> usize {
+        HEADER_LENGTH
+            + SSRC_LENGTH
+            + self
+                .report_blocks
+                .iter()
+                .map(|b| b.raw_size())
+                .sum::<usize>()
+            + REPORT_TIMESTAMP_LENGTH
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + Sync) {
+        self
+    }
+
+    fn equal(&self, other: &(dyn Packet + Send + Sync)) -> bool {
+        other.as_any().downcast_ref::<CcFeedbackReport>() == Some(self)
+    }
+
+    fn cloned(&self) -> Box<dyn Packet + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+impl MarshalSize for CcFeedbackReport {
+    fn marshal_size(&self) -> usize {
+        let l = self.raw_size();
+        // align to 32-bit boundary
+        l + get_padding_size(l)
+    }
+}
+
+impl Marshal for CcFeedbackReport {
+    /// Marshal encodes the packet in binary.
+    fn marshal_to(&self, mut buf: &mut [u8]) -> Result<usize, util::Error> {
+        if self
+            .report_blocks
+            .iter()
+            .any(|b| b.metric_blocks.len() > MAX_REPORTED_PACKETS)
+        {
+            return Err(Error::TooManyReports.into());
+        }
+        if self.marshal_size() / 4 - 1 > u16::MAX as usize {
+            return Err(Error::TooManyReports.into());
+        }
+        if buf.remaining_mut() < self.marshal_size() {
+            return Err(Error::BufferTooShort.into());
+        }
+
+        let h = self.header();
+        let n = h.marshal_to(buf)?;
+        buf = &mut buf[n..];
+
+        buf.put_u32(self.sender_ssrc);
+
+        for block in &self.report_blocks {
+            buf.put_u32(block.media_ssrc);
+            buf.put_u16(block.begin_sequence);
+            buf.put_u16(block.metric_blocks.len() as u16);
+            for metric in &block.metric_blocks {
+                buf.put_u16(metric.marshal());
+            }
+            if block.metric_blocks.len() % 2 != 0 {
+                buf.put_u16(0);
+            }
+        }
+
+        buf.put_u32(self.report_timestamp);
+
+        Ok(self.marshal_size())
+    }
+}
+
+impl Unmarshal for CcFeedbackReport {
+    /// Unmarshal decodes the CcFeedbackReport from binary
+    fn unmarshal<B>(raw_packet: &mut B) -> Result<Self, util::Error>
+    where
+        Self: Sized,
+        B: Buf,
+    {
+        let raw_packet_len = raw_packet.remaining();
+        if raw_packet_len < (HEADER_LENGTH + SSRC_LENGTH + REPORT_TIMESTAMP_LENGTH) {
+            return Err(Error::PacketTooShort.into());
+        }
+
+        let h = Header::unmarshal(raw_packet)?;
+
+        let total_length = HEADER_LENGTH + (4 * h.length) as usize;
+        if raw_packet_len < total_length
+            || total_length < HEADER_LENGTH + SSRC_LENGTH + REPORT_TIMESTAMP_LENGTH
+        {
+            return Err(Error::PacketTooShort.into());
+        }
+
+        if h.packet_type != PacketType::TransportSpecificFeedback || h.count != FORMAT_CCFB {
+            return Err(Error::WrongType.into());
+        }
+
+        let sender_ssrc = raw_packet.get_u32();
+
+        let mut remaining = total_length - HEADER_LENGTH - SSRC_LENGTH - REPORT_TIMESTAMP_LENGTH;
+        let mut report_blocks = vec![];
+        while remaining > 0 {
+            if remaining < REPORT_BLOCK_OFFSET {
+                return Err(Error::PacketTooShort.into());
+            }
+            let media_ssrc = raw_packet.get_u32();
+            let begin_sequence = raw_packet.get_u16();
+            let num_reports = raw_packet.get_u16() as usize;
+            remaining -= REPORT_BLOCK_OFFSET;
+
+            if num_reports > MAX_REPORTED_PACKETS {
+                return Err(Error::TooManyReports.into());
+            }
+            let metrics_length = num_reports * METRIC_BLOCK_LENGTH;
+            let padded_length = metrics_length + get_padding_size(metrics_length);
+            if remaining < padded_length {
+                return Err(Error::PacketTooShort.into());
+            }
+
+            let mut metric_blocks = Vec::with_capacity(num_reports);
+            for _ in 0..num_reports {
+                metric_blocks.push(CcFeedbackMetricBlock::unmarshal(raw_packet.get_u16()));
+            }
+            raw_packet.advance(padded_length - metrics_length);
+            remaining -= padded_length;
+
+            report_blocks.push(CcFeedbackReportBlock {
+                media_ssrc,
+                begin_sequence,
+                metric_blocks,
+            });
+        }
+
+        let report_timestamp = raw_packet.get_u32();
+
+        if
+        /*h.padding &&*/
+        raw_packet.has_remaining() {
+            raw_packet.advance(raw_packet.remaining());
+        }
+
+        Ok(CcFeedbackReport {
+            sender_ssrc,
+            report_blocks,
+            report_timestamp,
+        })
+    }
+}
@@ -1,3 +1,5 @@
+pub mod congestion_control_feedback;
 pub mod rapid_resynchronization_request;
+pub mod temporary_maximum_media_stream_bitrate;
 pub mod transport_layer_cc;
 pub mod transport_layer_nack;
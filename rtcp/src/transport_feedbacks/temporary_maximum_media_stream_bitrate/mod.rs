@@ -0,0 +1,323 @@
+#[cfg(test)]
+mod temporary_maximum_media_stream_bitrate_test;
+
+use std::any::Any;
+use std::fmt;
+
+use bytes::{Buf, BufMut};
+use util::marshal::{Marshal, MarshalSize, Unmarshal};
+
+use crate::error::Error;
+use crate::header::*;
+use crate::packet::*;
+
+type Result<T> = std::result::Result<T, util::Error>;
+
+const TMMB_OFFSET: usize = 8;
+const TMMB_ENTRY_LENGTH: usize = 8;
+const MANTISSA_MAX: u64 = 0x1FFFF;
+/// The largest measured overhead that can be carried in a TMMBR/TMMBN entry.
+pub const OVERHEAD_MAX: u16 = 0x1FF;
+
+/// A TmmbEntry is a bitrate limit for a single media sender, as carried by
+/// TMMBR and TMMBN packets. See RFC 5104 Section 4.2.1.1.
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct TmmbEntry {
+    /// SSRC of the media sender the limit applies to
+    pub ssrc: u32,
+    /// Maximum total media bit rate in bits per second
+    pub bitrate: u64,
+    /// Measured per-packet overhead in bytes
+    pub overhead: u16,
+}
+
+impl TmmbEntry {
+    fn marshal_to(&self, buf: &mut &mut [u8]) -> Result<()> {
+        if self.overhead > OVERHEAD_MAX {
+            return Err(Error::InvalidOverhead.into());
+        }
+
+        // find the smallest exponent for which the mantissa fits into 17 bits
+        let mut exp = 0u32;
+        let mut mantissa = self.bitrate;
+        while mantissa > MANTISSA_MAX {
+            mantissa >>= 1;
+            exp += 1;
+        }
+
+        buf.put_u32(self.ssrc);
+        buf.put_u32((exp << 26) | ((mantissa as u32) << 9) | self.overhead as u32);
+        Ok(())
+    }
+
+    fn unmarshal<B: Buf>(raw_packet: &mut B) -> Self {
+        let ssrc = raw_packet.get_u32();
+        let v = raw_packet.get_u32();
+        let exp = v >> 26;
+        let mantissa = ((v >> 9) as u64) & MANTISSA_MAX;
+        TmmbEntry {
+            ssrc,
+            bitrate: ((mantissa as u128) << exp).min(u64::MAX as u128) as u64,
+            overhead: (v & OVERHEAD_MAX as u32) as u16,
+        }
+    }
+}
+
+fn unmarshal_entries<B: Buf>(raw_packet: &mut B, format: u8) -> Result<(u32, u32, Vec<TmmbEntry>)> {
+    let raw_packet_len = raw_packet.remaining();
+    if raw_packet_len < (HEADER_LENGTH + TMMB_OFFSET) {
+        return Err(Error::PacketTooShort.into());
+    }
+
+    let h = Header::unmarshal(raw_packet)?;
+
+    let total_length = HEADER_LENGTH + (4 * h.length) as usize;
+    if raw_packet_len < total_length || total_length < HEADER_LENGTH + TMMB_OFFSET {
+        return Err(Error::PacketTooShort.into());
+    }
+
+    if h.packet_type != PacketType::TransportSpecificFeedback || h.count != format {
+        return Err(Error::WrongType.into());
+    }
+
+    let sender_ssrc = raw_packet.get_u32();
+    let media_ssrc = raw_packet.get_u32();
+
+    let mut i = HEADER_LENGTH + TMMB_OFFSET;
+    let mut entries = vec![];
+    while i + TMMB_ENTRY_LENGTH <= total_length {
+        entries.push(TmmbEntry::unmarshal(raw_packet));
+        i += TMMB_ENTRY_LENGTH;
+    }
+
+    if
+    /*h.padding &&*/
+    raw_packet.has_remaining() {
+        raw_packet.advance(raw_packet.remaining());
+    }
+
+    Ok((sender_ssrc, media_ssrc, entries))
+}
+
+fn marshal_entries(
+    mut buf: &mut [u8],
+    h: Header,
+    sender_ssrc: u32,
+    media_ssrc: u32,
+    entries: &[TmmbEntry],
+) -> Result<()> {
+    let n = h.marshal_to(buf)?;
+    buf = &mut buf[n..];
+
+    buf.put_u32(sender_ssrc);
+    buf.put_u32(media_ssrc);
+    for entry in entries {
+        entry.marshal_to(&mut buf)?;
+    }
+    Ok(())
+}
+
+/// The TemporaryMaximumMediaStreamBitrateRequest (TMMBR) packet asks the media senders
+/// to limit their bitrate. See RFC 5104 Section 4.2.1.
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct TemporaryMaximumMediaStreamBitrateRequest {
+    /// SSRC of sender
+    pub sender_ssrc: u32,
+    /// SSRC of the media source, not used and should be 0
+    pub media_ssrc: u32,
+    pub entries: Vec<TmmbEntry>,
+}
+
+impl fmt::Display for TemporaryMaximumMediaStreamBitrateRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = format!(
+            "TemporaryMaximumMediaStreamBitrateRequest {:x}",
+            self.sender_ssrc
+        );
+        for e in &self.entries {
+            out += format!(" ({:x} {} {})", e.ssrc, e.bitrate, e.overhead).as_str();
+        }
+        write!(f, "{out}")
+    }
+}
+
+impl Packet for TemporaryMaximumMediaStreamBitrateRequest {
+    /// Header returns the Header associated with this packet.
+    fn header(&self) -> Header {
+        Header {
+            padding: false,
+            count: FORMAT_TMMBR,
+            packet_type: PacketType::TransportSpecificFeedback,
+            length: ((self.marshal_size() / 4) - 1) as u16,
+        }
+    }
+
+    /// destination_ssrc returns an array of SSRC values that this packet refers to.
+    fn destination_ssrc(&self) -> Vec<u32> {
+        self.entries.iter().map(|e| e.ssrc).collect()
+    }
+
+    fn raw_size(&self) -> usize {
+        HEADER_LENGTH + TMMB_OFFSET + self.entries.len() * TMMB_ENTRY_LENGTH
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + Sync) {
+        self
+    }
+
+    fn equal(&self, other: &(dyn Packet + Send + Sync)) -> bool {
+        other
+            .as_any()
+            .downcast_ref::<TemporaryMaximumMediaStreamBitrateRequest>()
+            == Some(self)
+    }
+
+    fn cloned(&self) -> Box<dyn Packet + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+impl MarshalSize for TemporaryMaximumMediaStreamBitrateRequest {
+    fn marshal_size(&self) -> usize {
+        self.raw_size()
+    }
+}
+
+impl Marshal for TemporaryMaximumMediaStreamBitrateRequest {
+    /// Marshal encodes the TemporaryMaximumMediaStreamBitrateRequest
+    fn marshal_to(&self, buf: &mut [u8]) -> Result<usize> {
+        if buf.remaining_mut() < self.marshal_size() {
+            return Err(Error::BufferTooShort.into());
+        }
+
+        marshal_entries(
+            buf,
+            self.header(),
+            self.sender_ssrc,
+            self.media_ssrc,
+            &self.entries,
+        )?;
+
+        Ok(self.marshal_size())
+    }
+}
+
+impl Unmarshal for TemporaryMaximumMediaStreamBitrateRequest {
+    /// Unmarshal decodes the TemporaryMaximumMediaStreamBitrateRequest
+    fn unmarshal<B>(raw_packet: &mut B) -> Result<Self>
+    where
+        Self: Sized,
+        B: Buf,
+    {
+        let (sender_ssrc, media_ssrc, entries) = unmarshal_entries(raw_packet, FORMAT_TMMBR)?;
+
+        Ok(TemporaryMaximumMediaStreamBitrateRequest {
+            sender_ssrc,
+            media_ssrc,
+            entries,
+        })
+    }
+}
+
+/// The TemporaryMaximumMediaStreamBitrateNotification (TMMBN) packet acknowledges a TMMBR
+/// and lists the limits the media sender currently applies. See RFC 5104 Section 4.2.2.
+#[derive(Debug, PartialEq, Eq, Default, Clone)]
+pub struct TemporaryMaximumMediaStreamBitrateNotification {
+    /// SSRC of sender
+    pub sender_ssrc: u32,
+    /// SSRC of the media source, not used and should be 0
+    pub media_ssrc: u32,
+    /// The bounding set of limits, empty if no limit applies
+    pub entries: Vec<TmmbEntry>,
+}
+
+impl fmt::Display for TemporaryMaximumMediaStreamBitrateNotification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = format!(
+            "TemporaryMaximumMediaStreamBitrateNotification {:x}",
+            self.sender_ssrc
+        );
+        for e in &self.entries {
+            out += format!(" ({:x} {} {})", e.ssrc, e.bitrate, e.overhead).as_str();
+        }
+        write!(f, "{out}")
+    }
+}
+
+impl Packet for TemporaryMaximumMediaStreamBitrateNotification {
+    /// Header returns the Header associated with this packet.
+    fn header(&self) -> Header {
+        Header {
+            padding: false,
+            count: FORMAT_TMMBN,
+            packet_type: PacketType::TransportSpecificFeedback,
+            length: ((self.marshal_size() / 4) - 1) as u16,
+        }
+    }
+
+    /// destination_ssrc returns an array of SSRC values that this packet refers to.
+    fn destination_ssrc(&self) -> Vec<u32> {
+        self.entries.iter().map(|e| e.ssrc).collect()
+    }
+
+    fn raw_size(&self) -> usize {
+        HEADER_LENGTH + TMMB_OFFSET + self.entries.len() * TMMB_ENTRY_LENGTH
+    }
+
+    fn as_any(&self) -> &(dyn Any + Send + Sync) {
+        self
+    }
+
+    fn equal(&self, other: &(dyn Packet + Send + Sync)) -> bool {
+        other
+            .as_any()
+            .downcast_ref::<TemporaryMaximumMediaStreamBitrateNotification>()
+            == Some(self)
+    }
+
+    fn cloned(&self) -> Box<dyn Packet + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+impl MarshalSize for TemporaryMaximumMediaStreamBitrateNotification {
+    fn marshal_size(&self) -> usize {
+        self.raw_size()
+    }
+}
+
+impl Marshal for TemporaryMaximumMediaStreamBitrateNotification {
+    /// Marshal encodes the TemporaryMaximumMediaStreamBitrateNotification
+    fn marshal_to(&self, buf: &mut [u8]) -> Result<usize> {
+        if buf.remaining_mut() < self.marshal_size() {
+            return Err(Error::BufferTooShort.into());
+        }
+
+        marshal_entries(
+            buf,
+            self.header(),
+            self.sender_ssrc,
+            self.media_ssrc,
+            &self.entries,
+        )?;
+
+        Ok(self.marshal_size())
+    }
+}
+
+impl Unmarshal for TemporaryMaximumMediaStreamBitrateNotification {
+    /// Unmarshal decodes the TemporaryMaximumMediaStreamBitrateNotification
+    fn unmarshal<B>(raw_packet: &mut B) -> Result<Self>
+    where
+        Self: Sized,
+        B: Buf,
+    {
+        let (sender_ssrc, media_ssrc, entries) = unmarshal_entries(raw_packet, FORMAT_TMMBN)?;
+
+        Ok(TemporaryMaximumMediaStreamBitrateNotification {
+            sender_ssrc,
+            media_ssrc,
+            entries,
+        })
+    }
+}
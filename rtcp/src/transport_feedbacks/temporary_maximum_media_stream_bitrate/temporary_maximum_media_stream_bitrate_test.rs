@@ -0,0 +1,184 @@
+use bytes::Bytes;
+
+use super::*;
+
+#[test]
+fn test_tmmbr_unmarshal() {
+    let tests = vec![
+        (
+            "valid",
+            Bytes::from_static(&[
+                0x83, 0xcd, 0x00, 0x04, // v=2, p=0, FMT=3, RTPFB, len=4
+                0x90, 0x2f, 0x9e, 0x2e, // sender=0x902f9e2e
+                0x00, 0x00, 0x00, 0x00, // media=0
+                0x4b, 0xc4, 0xfc, 0xb4, // ssrc=0x4bc4fcb4
+                0x2a, 0xae, 0xa0, 0x28, // exp=10, mantissa=0x15750, overhead=40
+            ]),
+            TemporaryMaximumMediaStreamBitrateRequest {
+                sender_ssrc: 0x902f9e2e,
+                media_ssrc: 0,
+                entries: vec![TmmbEntry {
+                    ssrc: 0x4bc4fcb4,
+                    bitrate: 0x15750 << 10,
+                    overhead: 40,
+                }],
+            },
+            None,
+        ),
+        (
+            "packet too short",
+            Bytes::from_static(&[0x00, 0x00, 0x00, 0x00]),
+            TemporaryMaximumMediaStreamBitrateRequest::default(),
+            Some(Error::PacketTooShort),
+        ),
+        (
+            "wrong fmt",
+            Bytes::from_static(&[
+                0x84, 0xcd, 0x00, 0x02, // v=2, p=0, FMT=4, RTPFB, len=2
+                0x90, 0x2f, 0x9e, 0x2e, // sender=0x902f9e2e
+                0x00, 0x00, 0x00, 0x00, // media=0
+            ]),
+            TemporaryMaximumMediaStreamBitrateRequest::default(),
+            Some(Error::WrongType),
+        ),
+    ];
+
+    for (name, mut data, want, want_error) in tests {
+        let got = TemporaryMaximumMediaStreamBitrateRequest::unmarshal(&mut data);
+
+        assert_eq!(
+            got.is_err(),
+            want_error.is_some(),
+            "Unmarshal {name}: err = {got:?}, want {want_error:?}"
+        );
+
+        if let Some(err) = want_error {
+            let got_err = got.err().unwrap();
+            assert_eq!(
+                err, got_err,
+                "Unmarshal {name}: err = {got_err:?}, want {err:?}",
+            );
+        } else {
+            let actual = got.unwrap();
+            assert_eq!(
+                actual, want,
+                "Unmarshal {name}: got {actual:?}, want {want:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_tmmb_round_trip() {
+    let entries = vec![
+        TmmbEntry {
+            ssrc: 1,
+            bitrate: 0,
+            overhead: 0,
+        },
+        TmmbEntry {
+            ssrc: 2,
+            bitrate: 0x1FFFF,
+            overhead: OVERHEAD_MAX,
+        },
+        TmmbEntry {
+            ssrc: 3,
+            bitrate: 1_000_000 & !0x7,
+            overhead: 28,
+        },
+    ];
+
+    let want = TemporaryMaximumMediaStreamBitrateRequest {
+        sender_ssrc: 5000,
+        media_ssrc: 0,
+        entries: entries.clone(),
+    };
+    let mut data = want.marshal().expect("Marshal");
+    let actual =
+        TemporaryMaximumMediaStreamBitrateRequest::unmarshal(&mut data).expect("Unmarshal");
+    assert_eq!(actual, want);
+    assert_eq!(actual.destination_ssrc(), vec![1, 2, 3]);
+
+    let want = TemporaryMaximumMediaStreamBitrateNotification {
+        sender_ssrc: 6000,
+        media_ssrc: 0,
+        entries,
+    };
+    let mut data = want.marshal().expect("Marshal");
+    let actual =
+        TemporaryMaximumMediaStreamBitrateNotification::unmarshal(&mut data).expect("Unmarshal");
+    assert_eq!(actual, want);
+
+    // an empty notification means no limit applies
+    let want = TemporaryMaximumMediaStreamBitrateNotification::default();
+    let mut data = want.marshal().expect("Marshal");
+    assert_eq!(data.len(), 12);
+    let actual =
+        TemporaryMaximumMediaStreamBitrateNotification::unmarshal(&mut data).expect("Unmarshal");
+    assert_eq!(actual, want);
+}
+
+#[test]
+fn test_tmmb_bitrate_precision() {
+    // bitrates that need more than 17 bits lose their lowest bits
+    let want = TemporaryMaximumMediaStreamBitrateRequest {
+        entries: vec![TmmbEntry {
+            ssrc: 1,
+            bitrate: 1_000_001,
+            overhead: 0,
+        }],
+        ..Default::default()
+    };
+    let mut data = want.marshal().expect("Marshal");
+    let actual =
+        TemporaryMaximumMediaStreamBitrateRequest::unmarshal(&mut data).expect("Unmarshal");
+    assert_eq!(actual.entries[0].bitrate, 1_000_000);
+
+    let invalid = TemporaryMaximumMediaStreamBitrateRequest {
+        entries: vec![TmmbEntry {
+            ssrc: 1,
+            bitrate: 0,
+            overhead: OVERHEAD_MAX + 1,
+        }],
+        ..Default::default()
+    };
+    let err = invalid.marshal().expect_err("Marshal");
+    assert_eq!(Error::InvalidOverhead, err);
+}
+
+#[test]
+fn test_tmmb_unmarshal_packet() {
+    let request = TemporaryMaximumMediaStreamBitrateRequest {
+        sender_ssrc: 1,
+        media_ssrc: 0,
+        entries: vec![TmmbEntry {
+            ssrc: 2,
+            bitrate: 256_000,
+            overhead: 40,
+        }],
+    };
+    let notification = TemporaryMaximumMediaStreamBitrateNotification {
+        sender_ssrc: 2,
+        media_ssrc: 0,
+        entries: request.entries.clone(),
+    };
+
+    let mut data = bytes::BytesMut::new();
+    data.extend_from_slice(&request.marshal().expect("Marshal"));
+    data.extend_from_slice(&notification.marshal().expect("Marshal"));
+    let packets = crate::packet::unmarshal(&mut data.freeze()).expect("Unmarshal");
+
+    assert_eq!(packets.len(), 2);
+    assert_eq!(
+        packets[0]
+            .as_any()
+            .downcast_ref::<TemporaryMaximumMediaStreamBitrateRequest>(),
+        Some(&request)
+    );
+    assert_eq!(
+        packets[1]
+            .as_any()
+            .downcast_ref::<TemporaryMaximumMediaStreamBitrateNotification>(),
+        Some(&notification)
+    );
+}
@@ -56,6 +56,10 @@ pub enum Error {
     AudioLevelOverflow,
     #[error("playout delay overflow")]
     PlayoutDelayOverflow,
+    #[error("inband comfort noise level overflow")]
+    InbandCnLevelOverflow,
+    #[error("invalid video layers allocation")]
+    VideoLayersAllocationInvalid,
     #[error("payload is not large enough")]
     PayloadIsNotLargeEnough,
     #[error("STAP-A declared size({0}) is larger than buffer({1})")]
@@ -0,0 +1,98 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use bytes::{Bytes, BytesMut};
+
+use super::*;
+use crate::error::Result;
+
+#[test]
+fn test_abs_capture_time_extension_too_small() -> Result<()> {
+    let mut buf = &vec![0u8; 7][..];
+    let result = AbsCaptureTimeExtension::unmarshal(&mut buf);
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_abs_capture_time_extension_round_trip() -> Result<()> {
+    let tests = vec![
+        (
+            Bytes::from_static(&[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08]),
+            AbsCaptureTimeExtension {
+                timestamp: 0x0102030405060708,
+                estimated_capture_clock_offset: None,
+            },
+        ),
+        (
+            Bytes::from_static(&[
+                0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0xFF, 0xFF, 0xFF, 0xFF, 0x80, 0x00,
+                0x00, 0x00,
+            ]),
+            AbsCaptureTimeExtension {
+                timestamp: 0x0102030405060708,
+                estimated_capture_clock_offset: Some(-0x80000000),
+            },
+        ),
+    ];
+
+    for (raw, expected) in tests {
+        let buf = &mut raw.clone();
+        let a = AbsCaptureTimeExtension::unmarshal(buf)?;
+        assert_eq!(a, expected);
+
+        let mut dst = BytesMut::with_capacity(a.marshal_size());
+        dst.resize(a.marshal_size(), 0);
+        a.marshal_to(&mut dst)?;
+        assert_eq!(raw, dst.freeze());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_abs_capture_time_extension_capture_time() -> Result<()> {
+    let capture_time = UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+    let a = AbsCaptureTimeExtension::new(capture_time);
+
+    let diff = |a: SystemTime, b: SystemTime| {
+        a.duration_since(b)
+            .unwrap_or_else(|e| e.duration())
+            .as_nanos()
+    };
+    assert!(diff(a.capture_time().unwrap(), capture_time) < 1_000);
+    assert!(diff(a.estimated_capture_time().unwrap(), capture_time) < 1_000);
+
+    // The capturer clock is 1.5 seconds behind the sender clock
+    let ahead = AbsCaptureTimeExtension {
+        estimated_capture_clock_offset: Some(0x1_8000_0000),
+        ..a
+    };
+    assert!(
+        diff(
+            ahead.estimated_capture_time().unwrap(),
+            capture_time + Duration::from_millis(1500)
+        ) < 1_000
+    );
+
+    let behind = AbsCaptureTimeExtension {
+        estimated_capture_clock_offset: Some(-0x1_8000_0000),
+        ..a
+    };
+    assert!(
+        diff(
+            behind.estimated_capture_time().unwrap(),
+            capture_time - Duration::from_millis(1500)
+        ) < 1_000
+    );
+
+    // NTP times before the Unix epoch can't be represented
+    let before_epoch = AbsCaptureTimeExtension {
+        timestamp: 0,
+        estimated_capture_clock_offset: Some(0x1_8000_0000),
+    };
+    assert_eq!(before_epoch.capture_time(), None);
+    assert_eq!(before_epoch.estimated_capture_time(), None);
+
+    Ok(())
+}
@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod abs_capture_time_extension_test;
+
+use std::time::{Duration, SystemTime};
+
+use bytes::{Buf, BufMut};
+use util::marshal::{Marshal, MarshalSize, Unmarshal};
+
+use super::abs_send_time_extension::{ntp2unix, unix2ntp, NTP_UNIX_EPOCH_OFFSET};
+use crate::error::Error;
+
+pub const ABS_CAPTURE_TIME_EXTENSION_SIZE: usize = 8;
+pub const ABS_CAPTURE_TIME_EXTENDED_EXTENSION_SIZE: usize = 16;
+
+/// AbsCaptureTimeExtension is a extension payload format in
+/// http://www.webrtc.org/experiments/rtp-hdrext/abs-capture-time
+/// 0                   1                   2                   3
+/// 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |  ID   | len=15|     absolute capture timestamp (bit 0-23)     |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |             absolute capture timestamp (bit 24-55)            |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |  ... (56-63)  |   estimated capture clock offset (bit 0-23)   |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |           estimated capture clock offset (bit 24-55)          |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |  ... (56-63)  |
+/// +-+-+-+-+-+-+-+-+
+///
+/// The timestamp is the NTP time (UQ32.32) of the capture of the first
+/// sample of the frame, in the clock of the original capturer. The optional
+/// estimated capture clock offset (Q32.32) is the estimated offset between
+/// that clock and the clock of the sender, updated by every hop so that the
+/// last receiver can map the capture time to its own clock.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct AbsCaptureTimeExtension {
+    pub timestamp: u64,
+    pub estimated_capture_clock_offset: Option<i64>,
+}
+
+impl Unmarshal for AbsCaptureTimeExtension {
+    /// Unmarshal parses the passed byte slice and stores the result in the members.
+    fn unmarshal<B>(raw_packet: &mut B) -> Result<Self, util::Error>
+    where
+        Self: Sized,
+        B: Buf,
+    {
+        if raw_packet.remaining() < ABS_CAPTURE_TIME_EXTENSION_SIZE {
+            return Err(Error::ErrBufferTooSmall.into());
+        }
+
+        let timestamp = raw_packet.get_u64();
+        let estimated_capture_clock_offset = if raw_packet.remaining()
+            >= ABS_CAPTURE_TIME_EXTENDED_EXTENSION_SIZE - ABS_CAPTURE_TIME_EXTENSION_SIZE
+        {
+            Some(raw_packet.get_i64())
+        } else {
+            None
+        };
+
+        Ok(AbsCaptureTimeExtension {
+            timestamp,
+            estimated_capture_clock_offset,
+        })
+    }
+}
+
+impl MarshalSize for AbsCaptureTimeExtension {
+    /// MarshalSize returns the size of the AbsCaptureTimeExtension once marshaled.
+    fn marshal_size(&self) -> usize {
+        if self.estimated_capture_clock_offset.is_some() {
+            ABS_CAPTURE_TIME_EXTENDED_EXTENSION_SIZE
+        } else {
+            ABS_CAPTURE_TIME_EXTENSION_SIZE
+        }
+    }
+}
+
+impl Marshal for AbsCaptureTimeExtension {
+    /// MarshalTo serializes the members to buffer.
+    fn marshal_to(&self, mut buf: &mut [u8]) -> Result<usize, util::Error> {
+        if buf.remaining_mut() < self.marshal_size() {
+            return Err(Error::ErrBufferTooSmall.into());
+        }
+
+        buf.put_u64(self.timestamp);
+        if let Some(offset) = self.estimated_capture_clock_offset {
+            buf.put_i64(offset);
+        }
+
+        Ok(self.marshal_size())
+    }
+}
+
+impl AbsCaptureTimeExtension {
+    /// new makes a new AbsCaptureTimeExtension from the capture time, without clock offset.
+    pub fn new(capture_time: SystemTime) -> Self {
+        AbsCaptureTimeExtension {
+            timestamp: unix2ntp(capture_time),
+            estimated_capture_clock_offset: None,
+        }
+    }
+
+    /// capture_time returns the capture time in the clock of the original capturer, or None if
+    /// the timestamp is before the Unix epoch.
+    pub fn capture_time(&self) -> Option<SystemTime> {
+        (self.timestamp >> 32 >= NTP_UNIX_EPOCH_OFFSET).then(|| ntp2unix(self.timestamp))
+    }
+
+    /// estimated_capture_time returns the capture time in the clock of the sender of the
+    /// packet, applying the estimated capture clock offset if any. Returns None if the
+    /// timestamp is before the Unix epoch.
+    pub fn estimated_capture_time(&self) -> Option<SystemTime> {
+        let capture_time = self.capture_time()?;
+        let estimated_capture_time = match self.estimated_capture_clock_offset {
+            Some(offset) if offset >= 0 => capture_time
+                .checked_add(q32_32_to_duration(offset))
+                .unwrap_or(capture_time),
+            Some(offset) => capture_time
+                .checked_sub(q32_32_to_duration(offset.saturating_neg()))
+                .unwrap_or(capture_time),
+            None => capture_time,
+        };
+        Some(estimated_capture_time)
+    }
+}
+
+fn q32_32_to_duration(v: i64) -> Duration {
+    let v = v as u64;
+    let s = v >> 32;
+    let f = ((v & 0xFFFFFFFF) * 1_000_000_000) >> 32;
+    Duration::new(s, f as u32)
+}
@@ -65,6 +65,9 @@ fn test_ntp_conversion() -> Result<()> {
         }
     }
 
+    // NTP times before the Unix epoch are clamped to it
+    assert_eq!(ntp2unix(0), UNIX_EPOCH);
+
     Ok(())
 }
 
@@ -81,13 +81,16 @@ impl AbsSendTimeExtension {
     }
 }
 
+/// Offset in seconds between the NTP epoch (1900) and the Unix epoch (1970)
+pub(crate) const NTP_UNIX_EPOCH_OFFSET: u64 = 0x83AA7E80;
+
 pub fn unix2ntp(st: SystemTime) -> u64 {
     let u = st
         .duration_since(UNIX_EPOCH)
         .unwrap_or_else(|_| Duration::from_secs(0))
         .as_nanos() as u64;
     let mut s = u / 1_000_000_000;
-    s += 0x83AA7E80; //offset in seconds between unix epoch and ntp epoch
+    s += NTP_UNIX_EPOCH_OFFSET;
     let mut f = u % 1_000_000_000;
     f <<= 32;
     f /= 1_000_000_000;
@@ -96,12 +99,15 @@ pub fn unix2ntp(st: SystemTime) -> u64 {
     s | f
 }
 
+/// ntp2unix converts a NTP time to a system time, NTP times before the Unix epoch are clamped
+/// to it.
 pub fn ntp2unix(t: u64) -> SystemTime {
-    let mut s = t >> 32;
+    let Some(s) = (t >> 32).checked_sub(NTP_UNIX_EPOCH_OFFSET) else {
+        return UNIX_EPOCH;
+    };
     let mut f = t & 0xFFFFFFFF;
     f *= 1_000_000_000;
     f >>= 32;
-    s -= 0x83AA7E80;
     let u = s * 1_000_000_000 + f;
 
     UNIX_EPOCH
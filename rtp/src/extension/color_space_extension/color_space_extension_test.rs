@@ -0,0 +1,96 @@
+use bytes::{Bytes, BytesMut};
+
+use super::*;
+use crate::error::Result;
+
+#[test]
+fn test_color_space_extension_too_small() -> Result<()> {
+    let mut buf = &vec![0u8; 3][..];
+    let result = ColorSpaceExtension::unmarshal(&mut buf);
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_color_space_extension_round_trip() -> Result<()> {
+    let tests = vec![
+        (
+            // BT.709, limited range, chroma siting left/top
+            Bytes::from_static(&[1, 1, 1, 0b0001_0101]),
+            ColorSpaceExtension {
+                primaries: 1,
+                transfer: 1,
+                matrix: 1,
+                range: 1,
+                chroma_siting_horizontal: 1,
+                chroma_siting_vertical: 1,
+                hdr_metadata: None,
+            },
+        ),
+        (
+            // BT.2020 PQ with HDR metadata
+            Bytes::from_static(&[
+                9,
+                16,
+                9,
+                0b0010_1000,
+                0x03,
+                0xE8,
+                0x00,
+                0x32,
+                0x86,
+                0xC4,
+                0x3A,
+                0x98,
+                0x21,
+                0x34,
+                0x9B,
+                0xAA,
+                0x19,
+                0x64,
+                0x08,
+                0xFC,
+                0x3D,
+                0x13,
+                0x40,
+                0x42,
+                0x03,
+                0xE8,
+                0x01,
+                0x90,
+            ]),
+            ColorSpaceExtension {
+                primaries: 9,
+                transfer: 16,
+                matrix: 9,
+                range: 2,
+                chroma_siting_horizontal: 2,
+                chroma_siting_vertical: 0,
+                hdr_metadata: Some(HdrMetadata {
+                    luminance_max: 1000,
+                    luminance_min: 50,
+                    primary_r: Chromaticity { x: 34500, y: 15000 },
+                    primary_g: Chromaticity { x: 8500, y: 39850 },
+                    primary_b: Chromaticity { x: 6500, y: 2300 },
+                    white_point: Chromaticity { x: 15635, y: 16450 },
+                    max_content_light_level: 1000,
+                    max_frame_average_light_level: 400,
+                }),
+            },
+        ),
+    ];
+
+    for (raw, expected) in tests {
+        let buf = &mut raw.clone();
+        let a = ColorSpaceExtension::unmarshal(buf)?;
+        assert_eq!(a, expected);
+
+        let mut dst = BytesMut::with_capacity(a.marshal_size());
+        dst.resize(a.marshal_size(), 0);
+        a.marshal_to(&mut dst)?;
+        assert_eq!(raw, dst.freeze());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,162 @@
+#[cfg(test)]
+mod color_space_extension_test;
+
+use bytes::{Buf, BufMut};
+use util::marshal::{Marshal, MarshalSize, Unmarshal};
+
+use crate::error::Error;
+
+pub const COLOR_SPACE_EXTENSION_SIZE: usize = 4;
+pub const COLOR_SPACE_EXTENSION_WITH_HDR_METADATA_SIZE: usize = 28;
+
+/// Chromaticity is a point of the CIE 1931 color space, in units of 0.00002.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct Chromaticity {
+    pub x: u16,
+    pub y: u16,
+}
+
+/// HdrMetadata is the static HDR metadata of SMPTE ST 2086 and CTA-861.3.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct HdrMetadata {
+    /// Maximum luminance of the mastering display, in nits.
+    pub luminance_max: u16,
+    /// Minimum luminance of the mastering display, in units of 0.0001 nits.
+    pub luminance_min: u16,
+    pub primary_r: Chromaticity,
+    pub primary_g: Chromaticity,
+    pub primary_b: Chromaticity,
+    pub white_point: Chromaticity,
+    /// Maximum content light level, in nits.
+    pub max_content_light_level: u16,
+    /// Maximum frame average light level, in nits.
+    pub max_frame_average_light_level: u16,
+}
+
+/// ColorSpaceExtension is a extension payload format in
+/// http://www.webrtc.org/experiments/rtp-hdrext/color-space
+/// The primaries, transfer and matrix are the code points of ITU-T H.273,
+/// optionally followed by the HDR metadata of the frame.
+/// 0                   1                   2                   3
+/// 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |   primaries   |   transfer    |    matrix     |0 0|rng|hor|ver|
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |         luminance max         |         luminance min         |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |  primary r/g/b and white point chromaticities (x, y) ...      |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |    max content light level    | max frame average light level |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct ColorSpaceExtension {
+    pub primaries: u8,
+    pub transfer: u8,
+    pub matrix: u8,
+    /// 0: invalid, 1: limited, 2: full, 3: derived.
+    pub range: u8,
+    /// 0: unspecified, 1: collocated with luma, 2: half.
+    pub chroma_siting_horizontal: u8,
+    /// 0: unspecified, 1: collocated with luma, 2: half.
+    pub chroma_siting_vertical: u8,
+    pub hdr_metadata: Option<HdrMetadata>,
+}
+
+fn get_chromaticity<B: Buf>(buf: &mut B) -> Chromaticity {
+    Chromaticity {
+        x: buf.get_u16(),
+        y: buf.get_u16(),
+    }
+}
+
+fn put_chromaticity(buf: &mut &mut [u8], c: &Chromaticity) {
+    buf.put_u16(c.x);
+    buf.put_u16(c.y);
+}
+
+impl Unmarshal for ColorSpaceExtension {
+    /// Unmarshal parses the passed byte slice and stores the result in the members.
+    fn unmarshal<B>(raw_packet: &mut B) -> Result<Self, util::Error>
+    where
+        Self: Sized,
+        B: Buf,
+    {
+        if raw_packet.remaining() < COLOR_SPACE_EXTENSION_SIZE {
+            return Err(Error::ErrBufferTooSmall.into());
+        }
+
+        let primaries = raw_packet.get_u8();
+        let transfer = raw_packet.get_u8();
+        let matrix = raw_packet.get_u8();
+        let b = raw_packet.get_u8();
+
+        let hdr_metadata = if raw_packet.remaining()
+            >= COLOR_SPACE_EXTENSION_WITH_HDR_METADATA_SIZE - COLOR_SPACE_EXTENSION_SIZE
+        {
+            Some(HdrMetadata {
+                luminance_max: raw_packet.get_u16(),
+                luminance_min: raw_packet.get_u16(),
+                primary_r: get_chromaticity(raw_packet),
+                primary_g: get_chromaticity(raw_packet),
+                primary_b: get_chromaticity(raw_packet),
+                white_point: get_chromaticity(raw_packet),
+                max_content_light_level: raw_packet.get_u16(),
+                max_frame_average_light_level: raw_packet.get_u16(),
+            })
+        } else {
+            None
+        };
+
+        Ok(ColorSpaceExtension {
+            primaries,
+            transfer,
+            matrix,
+            range: (b >> 4) & 0b11,
+            chroma_siting_horizontal: (b >> 2) & 0b11,
+            chroma_siting_vertical: b & 0b11,
+            hdr_metadata,
+        })
+    }
+}
+
+impl MarshalSize for ColorSpaceExtension {
+    /// MarshalSize returns the size of the ColorSpaceExtension once marshaled.
+    fn marshal_size(&self) -> usize {
+        if self.hdr_metadata.is_some() {
+            COLOR_SPACE_EXTENSION_WITH_HDR_METADATA_SIZE
+        } else {
+            COLOR_SPACE_EXTENSION_SIZE
+        }
+    }
+}
+
+impl Marshal for ColorSpaceExtension {
+    /// MarshalTo serializes the members to buffer.
+    fn marshal_to(&self, mut buf: &mut [u8]) -> Result<usize, util::Error> {
+        if buf.remaining_mut() < self.marshal_size() {
+            return Err(Error::ErrBufferTooSmall.into());
+        }
+
+        buf.put_u8(self.primaries);
+        buf.put_u8(self.transfer);
+        buf.put_u8(self.matrix);
+        buf.put_u8(
+            (self.range & 0b11) << 4
+                | (self.chroma_siting_horizontal & 0b11) << 2
+                | self.chroma_siting_vertical & 0b11,
+        );
+
+        if let Some(hdr) = &self.hdr_metadata {
+            buf.put_u16(hdr.luminance_max);
+            buf.put_u16(hdr.luminance_min);
+            put_chromaticity(&mut buf, &hdr.primary_r);
+            put_chromaticity(&mut buf, &hdr.primary_g);
+            put_chromaticity(&mut buf, &hdr.primary_b);
+            put_chromaticity(&mut buf, &hdr.white_point);
+            buf.put_u16(hdr.max_content_light_level);
+            buf.put_u16(hdr.max_frame_average_light_level);
+        }
+
+        Ok(self.marshal_size())
+    }
+}
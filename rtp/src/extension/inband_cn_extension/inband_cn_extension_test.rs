@@ -0,0 +1,37 @@
+use bytes::{Bytes, BytesMut};
+
+use super::*;
+use crate::error::Result;
+
+#[test]
+fn test_inband_cn_extension() -> Result<()> {
+    let mut buf = &vec![0u8; 0][..];
+    assert!(InbandCnExtension::unmarshal(&mut buf).is_err());
+
+    let tests = vec![
+        (
+            Bytes::from_static(&[0x32]),
+            InbandCnExtension { level: Some(50) },
+        ),
+        (
+            Bytes::from_static(&[0x80]),
+            InbandCnExtension { level: None },
+        ),
+    ];
+    for (raw, expected) in tests {
+        let a = InbandCnExtension::unmarshal(&mut raw.clone())?;
+        assert_eq!(a, expected);
+
+        let mut dst = BytesMut::with_capacity(a.marshal_size());
+        dst.resize(a.marshal_size(), 0);
+        a.marshal_to(&mut dst)?;
+        assert_eq!(raw, dst.freeze());
+    }
+
+    let a = InbandCnExtension { level: Some(128) };
+    let mut dst = BytesMut::with_capacity(a.marshal_size());
+    dst.resize(a.marshal_size(), 0);
+    assert!(a.marshal_to(&mut dst).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod inband_cn_extension_test;
+
+use bytes::{Buf, BufMut};
+use util::marshal::{Marshal, MarshalSize, Unmarshal};
+
+use crate::error::Error;
+
+pub const INBAND_CN_EXTENSION_SIZE: usize = 1;
+
+/// InbandCnExtension is a extension payload format in
+/// http://www.webrtc.org/experiments/rtp-hdrext/inband-cn
+/// It signals that the audio frame carries comfort noise, of the given level
+/// in -dBov (0 to 127) unless N is set.
+/// 0                   1
+/// 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |  ID   | len=0 |N|    level    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct InbandCnExtension {
+    pub level: Option<u8>,
+}
+
+impl Unmarshal for InbandCnExtension {
+    /// Unmarshal parses the passed byte slice and stores the result in the members.
+    fn unmarshal<B>(raw_packet: &mut B) -> Result<Self, util::Error>
+    where
+        Self: Sized,
+        B: Buf,
+    {
+        if raw_packet.remaining() < INBAND_CN_EXTENSION_SIZE {
+            return Err(Error::ErrBufferTooSmall.into());
+        }
+
+        let b = raw_packet.get_u8();
+        let level = if b & 0x80 != 0 { None } else { Some(b) };
+
+        Ok(InbandCnExtension { level })
+    }
+}
+
+impl MarshalSize for InbandCnExtension {
+    /// MarshalSize returns the size of the InbandCnExtension once marshaled.
+    fn marshal_size(&self) -> usize {
+        INBAND_CN_EXTENSION_SIZE
+    }
+}
+
+impl Marshal for InbandCnExtension {
+    /// MarshalTo serializes the members to buffer.
+    fn marshal_to(&self, mut buf: &mut [u8]) -> Result<usize, util::Error> {
+        if buf.remaining_mut() < INBAND_CN_EXTENSION_SIZE {
+            return Err(Error::ErrBufferTooSmall.into());
+        }
+
+        match self.level {
+            Some(level) if level > 127 => return Err(Error::InbandCnLevelOverflow.into()),
+            Some(level) => buf.put_u8(level),
+            None => buf.put_u8(0x80),
+        }
+
+        Ok(INBAND_CN_EXTENSION_SIZE)
+    }
+}
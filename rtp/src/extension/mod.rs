@@ -3,11 +3,17 @@ use std::fmt;
 
 use util::{Marshal, MarshalSize};
 
+pub mod abs_capture_time_extension;
 pub mod abs_send_time_extension;
 pub mod audio_level_extension;
+pub mod color_space_extension;
+pub mod inband_cn_extension;
 pub mod playout_delay_extension;
 pub mod transport_cc_extension;
+pub mod video_content_type_extension;
+pub mod video_layers_allocation_extension;
 pub mod video_orientation_extension;
+pub mod video_timing_extension;
 
 /// A generic RTP header extension.
 pub enum HeaderExtension {
@@ -16,6 +22,12 @@ pub enum HeaderExtension {
     PlayoutDelay(playout_delay_extension::PlayoutDelayExtension),
     TransportCc(transport_cc_extension::TransportCcExtension),
     VideoOrientation(video_orientation_extension::VideoOrientationExtension),
+    AbsCaptureTime(abs_capture_time_extension::AbsCaptureTimeExtension),
+    VideoLayersAllocation(video_layers_allocation_extension::VideoLayersAllocationExtension),
+    ColorSpace(color_space_extension::ColorSpaceExtension),
+    VideoContentType(video_content_type_extension::VideoContentTypeExtension),
+    VideoTiming(video_timing_extension::VideoTimingExtension),
+    InbandCn(inband_cn_extension::InbandCnExtension),
 
     /// A custom extension
     Custom {
@@ -36,6 +48,18 @@ impl HeaderExtension {
                 "http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01".into()
             }
             VideoOrientation(_) => "urn:3gpp:video-orientation".into(),
+            AbsCaptureTime(_) => {
+                "http://www.webrtc.org/experiments/rtp-hdrext/abs-capture-time".into()
+            }
+            VideoLayersAllocation(_) => {
+                "http://www.webrtc.org/experiments/rtp-hdrext/video-layers-allocation00".into()
+            }
+            ColorSpace(_) => "http://www.webrtc.org/experiments/rtp-hdrext/color-space".into(),
+            VideoContentType(_) => {
+                "http://www.webrtc.org/experiments/rtp-hdrext/video-content-type".into()
+            }
+            VideoTiming(_) => "http://www.webrtc.org/experiments/rtp-hdrext/video-timing".into(),
+            InbandCn(_) => "http://www.webrtc.org/experiments/rtp-hdrext/inband-cn".into(),
             Custom { uri, .. } => uri.clone(),
         }
     }
@@ -46,7 +70,14 @@ impl HeaderExtension {
             (AbsSendTime(_), AbsSendTime(_)) => true,
             (AudioLevel(_), AudioLevel(_)) => true,
             (TransportCc(_), TransportCc(_)) => true,
+            (PlayoutDelay(_), PlayoutDelay(_)) => true,
             (VideoOrientation(_), VideoOrientation(_)) => true,
+            (AbsCaptureTime(_), AbsCaptureTime(_)) => true,
+            (VideoLayersAllocation(_), VideoLayersAllocation(_)) => true,
+            (ColorSpace(_), ColorSpace(_)) => true,
+            (VideoContentType(_), VideoContentType(_)) => true,
+            (VideoTiming(_), VideoTiming(_)) => true,
+            (InbandCn(_), InbandCn(_)) => true,
             (Custom { uri, .. }, Custom { uri: other_uri, .. }) => uri == other_uri,
             _ => false,
         }
@@ -62,6 +93,12 @@ impl MarshalSize for HeaderExtension {
             PlayoutDelay(ext) => ext.marshal_size(),
             TransportCc(ext) => ext.marshal_size(),
             VideoOrientation(ext) => ext.marshal_size(),
+            AbsCaptureTime(ext) => ext.marshal_size(),
+            VideoLayersAllocation(ext) => ext.marshal_size(),
+            ColorSpace(ext) => ext.marshal_size(),
+            VideoContentType(ext) => ext.marshal_size(),
+            VideoTiming(ext) => ext.marshal_size(),
+            InbandCn(ext) => ext.marshal_size(),
             Custom { extension: ext, .. } => ext.marshal_size(),
         }
     }
@@ -76,6 +113,12 @@ impl Marshal for HeaderExtension {
             PlayoutDelay(ext) => ext.marshal_to(buf),
             TransportCc(ext) => ext.marshal_to(buf),
             VideoOrientation(ext) => ext.marshal_to(buf),
+            AbsCaptureTime(ext) => ext.marshal_to(buf),
+            VideoLayersAllocation(ext) => ext.marshal_to(buf),
+            ColorSpace(ext) => ext.marshal_to(buf),
+            VideoContentType(ext) => ext.marshal_to(buf),
+            VideoTiming(ext) => ext.marshal_to(buf),
+            InbandCn(ext) => ext.marshal_to(buf),
             Custom { extension: ext, .. } => ext.marshal_to(buf),
         }
     }
@@ -91,6 +134,14 @@ impl fmt::Debug for HeaderExtension {
             PlayoutDelay(ext) => f.debug_tuple("PlayoutDelay").field(ext).finish(),
             TransportCc(ext) => f.debug_tuple("TransportCc").field(ext).finish(),
             VideoOrientation(ext) => f.debug_tuple("VideoOrientation").field(ext).finish(),
+            AbsCaptureTime(ext) => f.debug_tuple("AbsCaptureTime").field(ext).finish(),
+            VideoLayersAllocation(ext) => {
+                f.debug_tuple("VideoLayersAllocation").field(ext).finish()
+            }
+            ColorSpace(ext) => f.debug_tuple("ColorSpace").field(ext).finish(),
+            VideoContentType(ext) => f.debug_tuple("VideoContentType").field(ext).finish(),
+            VideoTiming(ext) => f.debug_tuple("VideoTiming").field(ext).finish(),
+            InbandCn(ext) => f.debug_tuple("InbandCn").field(ext).finish(),
             Custom { uri, extension: _ } => f.debug_struct("Custom").field("uri", uri).finish(),
         }
     }
@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod video_content_type_extension_test;
+
+use std::convert::{TryFrom, TryInto};
+
+use bytes::{Buf, BufMut};
+use util::marshal::{Marshal, MarshalSize, Unmarshal};
+
+use crate::error::Error;
+
+pub const VIDEO_CONTENT_TYPE_EXTENSION_SIZE: usize = 1;
+
+/// VideoContentTypeExtension is a extension payload format in
+/// http://www.webrtc.org/experiments/rtp-hdrext/video-content-type
+/// 0                   1
+/// 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |  ID   | len=0 | content type  |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct VideoContentTypeExtension {
+    pub content_type: VideoContentType,
+}
+
+#[derive(Default, PartialEq, Eq, Debug, Copy, Clone)]
+pub enum VideoContentType {
+    #[default]
+    Unspecified = 0,
+    Screenshare = 1,
+}
+
+impl Unmarshal for VideoContentTypeExtension {
+    /// Unmarshal parses the passed byte slice and stores the result in the members.
+    fn unmarshal<B>(raw_packet: &mut B) -> Result<Self, util::Error>
+    where
+        Self: Sized,
+        B: Buf,
+    {
+        if raw_packet.remaining() < VIDEO_CONTENT_TYPE_EXTENSION_SIZE {
+            return Err(Error::ErrBufferTooSmall.into());
+        }
+
+        Ok(VideoContentTypeExtension {
+            content_type: raw_packet.get_u8().try_into()?,
+        })
+    }
+}
+
+impl MarshalSize for VideoContentTypeExtension {
+    /// MarshalSize returns the size of the VideoContentTypeExtension once marshaled.
+    fn marshal_size(&self) -> usize {
+        VIDEO_CONTENT_TYPE_EXTENSION_SIZE
+    }
+}
+
+impl Marshal for VideoContentTypeExtension {
+    /// MarshalTo serializes the members to buffer.
+    fn marshal_to(&self, mut buf: &mut [u8]) -> Result<usize, util::Error> {
+        if buf.remaining_mut() < VIDEO_CONTENT_TYPE_EXTENSION_SIZE {
+            return Err(Error::ErrBufferTooSmall.into());
+        }
+
+        buf.put_u8(self.content_type as u8);
+
+        Ok(VIDEO_CONTENT_TYPE_EXTENSION_SIZE)
+    }
+}
+
+impl TryFrom<u8> for VideoContentType {
+    type Error = util::Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(VideoContentType::Unspecified),
+            1 => Ok(VideoContentType::Screenshare),
+            _ => Err(util::Error::Other(format!(
+                "Unhandled video content type: {value}"
+            ))),
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use bytes::{Bytes, BytesMut};
+
+use super::*;
+use crate::error::Result;
+
+#[test]
+fn test_video_content_type_extension() -> Result<()> {
+    let mut buf = &vec![0u8; 0][..];
+    assert!(VideoContentTypeExtension::unmarshal(&mut buf).is_err());
+
+    let mut buf = &[2u8][..];
+    assert!(VideoContentTypeExtension::unmarshal(&mut buf).is_err());
+
+    let raw = Bytes::from_static(&[1]);
+    let a1 = VideoContentTypeExtension::unmarshal(&mut raw.clone())?;
+    let a2 = VideoContentTypeExtension {
+        content_type: VideoContentType::Screenshare,
+    };
+    assert_eq!(a1, a2);
+
+    let mut dst = BytesMut::with_capacity(a2.marshal_size());
+    dst.resize(a2.marshal_size(), 0);
+    a2.marshal_to(&mut dst)?;
+    assert_eq!(raw, dst.freeze());
+
+    Ok(())
+}
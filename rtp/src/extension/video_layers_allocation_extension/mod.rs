@@ -0,0 +1,340 @@
+#[cfg(test)]
+mod video_layers_allocation_extension_test;
+
+use bytes::{Buf, BufMut};
+use util::marshal::{Marshal, MarshalSize, Unmarshal};
+
+use crate::error::Error;
+
+pub const VIDEO_LAYERS_ALLOCATION_MAX_RTP_STREAMS: usize = 4;
+pub const VIDEO_LAYERS_ALLOCATION_MAX_SPATIAL_LAYERS: usize = 4;
+pub const VIDEO_LAYERS_ALLOCATION_MAX_TEMPORAL_LAYERS: usize = 4;
+
+const RESOLUTION_AND_FRAME_RATE_SIZE: usize = 5;
+
+/// SpatialLayer is an active spatial layer of a VideoLayersAllocationExtension.
+#[derive(PartialEq, Eq, Debug, Default, Clone)]
+pub struct SpatialLayer {
+    /// Index of the RTP stream the layer is sent on, for simulcast.
+    pub rtp_stream_index: u8,
+    pub spatial_id: u8,
+    /// Cumulative target bitrate of every temporal layer, in kbps.
+    pub target_bitrates_kbps: Vec<u32>,
+    /// Resolution and maximum frame rate, only meaningful when
+    /// resolution_and_frame_rate_is_valid is set on the extension.
+    pub width: u16,
+    pub height: u16,
+    pub frame_rate_fps: u8,
+}
+
+/// VideoLayersAllocationExtension is a extension payload format in
+/// http://www.webrtc.org/experiments/rtp-hdrext/video-layers-allocation00
+/// It describes the simulcast streams and spatial and temporal layers the
+/// sender currently produces, with their target bitrate and resolution.
+///                            +-+-+-+-+-+-+-+-+
+///                            |RID| NS| sl_bm |
+///                            +-+-+-+-+-+-+-+-+
+/// Spatial layer bitmask      |sl0_bm |sl1_bm |
+///   up to 2 bytes            |---------------|
+///   when sl_bm == 0          |sl2_bm |sl3_bm |
+///                            +-+-+-+-+-+-+-+-+
+/// Number of temporal layers  |#tl|#tl|#tl|#tl|
+/// per spatial layer          |   |   |   |   |
+///                            +-+-+-+-+-+-+-+-+
+///  Target bitrate in kbps    |               |
+///   per temporal layer       :      ...      :
+///    leb128 encoded          |               |
+///                            +-+-+-+-+-+-+-+-+
+/// Resolution and framerate   |               |
+/// 5 bytes per spatial layer  + width-1 for   +
+///      (optional)            | rid=0, sid=0  |
+///                            +---------------+
+///                            |               |
+///                            + height-1 for  +
+///                            | rid=0, sid=0  |
+///                            +---------------+
+///                            | max framerate |
+///                            +-+-+-+-+-+-+-+-+
+///                            :      ...      :
+///                            +-+-+-+-+-+-+-+-+
+///
+/// A single zero byte signals that no layer is active.
+#[derive(PartialEq, Eq, Debug, Default, Clone)]
+pub struct VideoLayersAllocationExtension {
+    /// Index of the RTP stream carrying the extension.
+    pub rtp_stream_index: u8,
+    pub resolution_and_frame_rate_is_valid: bool,
+    /// Active layers, ordered by RTP stream index and then spatial id.
+    pub active_spatial_layers: Vec<SpatialLayer>,
+}
+
+impl VideoLayersAllocationExtension {
+    fn validate(&self) -> Result<(), Error> {
+        if self.rtp_stream_index as usize >= VIDEO_LAYERS_ALLOCATION_MAX_RTP_STREAMS {
+            return Err(Error::VideoLayersAllocationInvalid);
+        }
+
+        let mut previous: Option<(u8, u8)> = None;
+        for layer in &self.active_spatial_layers {
+            if layer.rtp_stream_index as usize >= VIDEO_LAYERS_ALLOCATION_MAX_RTP_STREAMS
+                || layer.spatial_id as usize >= VIDEO_LAYERS_ALLOCATION_MAX_SPATIAL_LAYERS
+                || layer.target_bitrates_kbps.is_empty()
+                || layer.target_bitrates_kbps.len() > VIDEO_LAYERS_ALLOCATION_MAX_TEMPORAL_LAYERS
+                || self.resolution_and_frame_rate_is_valid
+                    && (layer.width == 0 || layer.height == 0)
+            {
+                return Err(Error::VideoLayersAllocationInvalid);
+            }
+
+            let current = (layer.rtp_stream_index, layer.spatial_id);
+            if previous.is_some_and(|p| p >= current) {
+                return Err(Error::VideoLayersAllocationInvalid);
+            }
+            previous = Some(current);
+        }
+
+        Ok(())
+    }
+
+    /// num_rtp_streams returns the number of RTP streams, including the one carrying the
+    /// extension even if none of its layers is active. Out of range indexes, rejected by
+    /// validate, are capped so that sizing an invalid extension doesn't panic.
+    fn num_rtp_streams(&self) -> usize {
+        self.active_spatial_layers
+            .iter()
+            .map(|l| l.rtp_stream_index as usize + 1)
+            .max()
+            .unwrap_or(0)
+            .max(self.rtp_stream_index as usize + 1)
+            .min(VIDEO_LAYERS_ALLOCATION_MAX_RTP_STREAMS)
+    }
+
+    /// spatial_layer_bitmasks returns the bitmask of active spatial layers of every RTP stream.
+    /// Out of range layers, rejected by validate, are ignored.
+    fn spatial_layer_bitmasks(&self) -> [u8; VIDEO_LAYERS_ALLOCATION_MAX_RTP_STREAMS] {
+        let mut bitmasks = [0u8; VIDEO_LAYERS_ALLOCATION_MAX_RTP_STREAMS];
+        for layer in &self.active_spatial_layers {
+            if let Some(bitmask) = bitmasks.get_mut(layer.rtp_stream_index as usize) {
+                *bitmask |= 1u8.checked_shl(layer.spatial_id as u32).unwrap_or(0);
+            }
+        }
+        bitmasks
+    }
+
+    /// shared_bitmask returns the spatial layer bitmask when it is the same for every RTP stream.
+    fn shared_bitmask(&self) -> Option<u8> {
+        let bitmasks = self.spatial_layer_bitmasks();
+        let bitmasks = &bitmasks[..self.num_rtp_streams()];
+        if bitmasks.iter().all(|b| *b == bitmasks[0]) {
+            Some(bitmasks[0])
+        } else {
+            None
+        }
+    }
+}
+
+impl Unmarshal for VideoLayersAllocationExtension {
+    /// Unmarshal parses the passed byte slice and stores the result in the members.
+    fn unmarshal<B>(raw_packet: &mut B) -> Result<Self, util::Error>
+    where
+        Self: Sized,
+        B: Buf,
+    {
+        if raw_packet.remaining() < 1 {
+            return Err(Error::ErrBufferTooSmall.into());
+        }
+
+        let b = raw_packet.get_u8();
+        if b == 0 && !raw_packet.has_remaining() {
+            return Ok(VideoLayersAllocationExtension::default());
+        }
+
+        let rtp_stream_index = b >> 6;
+        let num_rtp_streams = ((b >> 4) & 0b11) as usize + 1;
+        let shared_bitmask = b & 0b1111;
+        if rtp_stream_index as usize >= num_rtp_streams {
+            return Err(Error::VideoLayersAllocationInvalid.into());
+        }
+
+        let mut bitmasks = [shared_bitmask; VIDEO_LAYERS_ALLOCATION_MAX_RTP_STREAMS];
+        if shared_bitmask == 0 {
+            let size = num_rtp_streams.div_ceil(2);
+            if raw_packet.remaining() < size {
+                return Err(Error::ErrBufferTooSmall.into());
+            }
+            for i in 0..size {
+                let b = raw_packet.get_u8();
+                bitmasks[2 * i] = b >> 4;
+                bitmasks[2 * i + 1] = b & 0b1111;
+            }
+        }
+
+        let mut active_spatial_layers = vec![];
+        for (rtp_stream_index, bitmask) in bitmasks[..num_rtp_streams].iter().enumerate() {
+            for spatial_id in 0..VIDEO_LAYERS_ALLOCATION_MAX_SPATIAL_LAYERS {
+                if bitmask & (1 << spatial_id) != 0 {
+                    active_spatial_layers.push(SpatialLayer {
+                        rtp_stream_index: rtp_stream_index as u8,
+                        spatial_id: spatial_id as u8,
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+
+        // Number of temporal layers, 2 bits per active spatial layer
+        let mut num_temporal_layers = Vec::with_capacity(active_spatial_layers.len());
+        for chunk in 0..active_spatial_layers.len().div_ceil(4) {
+            if !raw_packet.has_remaining() {
+                return Err(Error::ErrBufferTooSmall.into());
+            }
+            let b = raw_packet.get_u8();
+            for i in 0..4 {
+                if chunk * 4 + i < active_spatial_layers.len() {
+                    num_temporal_layers.push(((b >> (6 - 2 * i)) & 0b11) as usize + 1);
+                }
+            }
+        }
+
+        for (layer, num_temporal_layers) in
+            active_spatial_layers.iter_mut().zip(num_temporal_layers)
+        {
+            for _ in 0..num_temporal_layers {
+                layer.target_bitrates_kbps.push(read_leb128(raw_packet)?);
+            }
+        }
+
+        let resolution_and_frame_rate_is_valid = !active_spatial_layers.is_empty()
+            && raw_packet.remaining()
+                == active_spatial_layers.len() * RESOLUTION_AND_FRAME_RATE_SIZE;
+        if resolution_and_frame_rate_is_valid {
+            for layer in &mut active_spatial_layers {
+                layer.width = raw_packet.get_u16().saturating_add(1);
+                layer.height = raw_packet.get_u16().saturating_add(1);
+                layer.frame_rate_fps = raw_packet.get_u8();
+            }
+        } else {
+            raw_packet.advance(raw_packet.remaining());
+        }
+
+        Ok(VideoLayersAllocationExtension {
+            rtp_stream_index,
+            resolution_and_frame_rate_is_valid,
+            active_spatial_layers,
+        })
+    }
+}
+
+impl MarshalSize for VideoLayersAllocationExtension {
+    /// MarshalSize returns the size of the VideoLayersAllocationExtension once marshaled.
+    fn marshal_size(&self) -> usize {
+        if self.active_spatial_layers.is_empty() {
+            return 1;
+        }
+
+        let mut size = 1;
+        if self.shared_bitmask().is_none() {
+            size += self.num_rtp_streams().div_ceil(2);
+        }
+        size += self.active_spatial_layers.len().div_ceil(4);
+        for layer in &self.active_spatial_layers {
+            size += layer
+                .target_bitrates_kbps
+                .iter()
+                .map(|b| leb128_size(*b))
+                .sum::<usize>();
+        }
+        if self.resolution_and_frame_rate_is_valid {
+            size += self.active_spatial_layers.len() * RESOLUTION_AND_FRAME_RATE_SIZE;
+        }
+
+        size
+    }
+}
+
+impl Marshal for VideoLayersAllocationExtension {
+    /// MarshalTo serializes the members to buffer.
+    fn marshal_to(&self, mut buf: &mut [u8]) -> Result<usize, util::Error> {
+        self.validate()?;
+
+        let size = self.marshal_size();
+        if buf.remaining_mut() < size {
+            return Err(Error::ErrBufferTooSmall.into());
+        }
+
+        if self.active_spatial_layers.is_empty() {
+            buf.put_u8(0);
+            return Ok(size);
+        }
+
+        let num_rtp_streams = self.num_rtp_streams();
+        let shared_bitmask = self.shared_bitmask();
+        buf.put_u8(
+            (self.rtp_stream_index << 6)
+                | (((num_rtp_streams - 1) as u8) << 4)
+                | shared_bitmask.unwrap_or(0),
+        );
+        if shared_bitmask.is_none() {
+            let bitmasks = self.spatial_layer_bitmasks();
+            for pair in bitmasks[..num_rtp_streams].chunks(2) {
+                buf.put_u8(pair[0] << 4 | pair.get(1).copied().unwrap_or(0));
+            }
+        }
+
+        for chunk in self.active_spatial_layers.chunks(4) {
+            let mut b = 0u8;
+            for (i, layer) in chunk.iter().enumerate() {
+                b |= ((layer.target_bitrates_kbps.len() - 1) as u8) << (6 - 2 * i);
+            }
+            buf.put_u8(b);
+        }
+
+        for layer in &self.active_spatial_layers {
+            for bitrate in &layer.target_bitrates_kbps {
+                write_leb128(&mut buf, *bitrate);
+            }
+        }
+
+        if self.resolution_and_frame_rate_is_valid {
+            for layer in &self.active_spatial_layers {
+                buf.put_u16(layer.width - 1);
+                buf.put_u16(layer.height - 1);
+                buf.put_u8(layer.frame_rate_fps);
+            }
+        }
+
+        Ok(size)
+    }
+}
+
+fn leb128_size(mut value: u32) -> usize {
+    let mut size = 1;
+    while value >= 0x80 {
+        size += 1;
+        value >>= 7;
+    }
+    size
+}
+
+fn write_leb128(buf: &mut &mut [u8], mut value: u32) {
+    while value >= 0x80 {
+        buf.put_u8(0x80 | (value & 0x7F) as u8);
+        value >>= 7;
+    }
+    buf.put_u8(value as u8);
+}
+
+fn read_leb128<B: Buf>(buf: &mut B) -> Result<u32, util::Error> {
+    let mut value = 0u64;
+    for i in 0..5 {
+        if !buf.has_remaining() {
+            return Err(Error::ErrBufferTooSmall.into());
+        }
+        let b = buf.get_u8();
+        value |= ((b & 0x7F) as u64) << (7 * i);
+        if b & 0x80 == 0 {
+            return u32::try_from(value).map_err(|_| Error::VideoLayersAllocationInvalid.into());
+        }
+    }
+    Err(Error::VideoLayersAllocationInvalid.into())
+}
@@ -0,0 +1,172 @@
+use bytes::{Bytes, BytesMut};
+
+use super::*;
+use crate::error::Result;
+
+fn round_trip(a: &VideoLayersAllocationExtension) -> Result<Bytes> {
+    let mut dst = BytesMut::with_capacity(a.marshal_size());
+    dst.resize(a.marshal_size(), 0);
+    let n = a.marshal_to(&mut dst)?;
+    assert_eq!(n, a.marshal_size());
+    let raw = dst.freeze();
+
+    let b = VideoLayersAllocationExtension::unmarshal(&mut raw.clone())?;
+    assert_eq!(&b, a);
+
+    Ok(raw)
+}
+
+fn layer(rtp_stream_index: u8, spatial_id: u8, target_bitrates_kbps: Vec<u32>) -> SpatialLayer {
+    SpatialLayer {
+        rtp_stream_index,
+        spatial_id,
+        target_bitrates_kbps,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_video_layers_allocation_extension_no_active_layers() -> Result<()> {
+    let a = VideoLayersAllocationExtension::default();
+    assert_eq!(round_trip(&a)?, Bytes::from_static(&[0]));
+
+    Ok(())
+}
+
+#[test]
+fn test_video_layers_allocation_extension_single_stream() -> Result<()> {
+    let a = VideoLayersAllocationExtension {
+        rtp_stream_index: 0,
+        resolution_and_frame_rate_is_valid: false,
+        active_spatial_layers: vec![layer(0, 0, vec![25, 50]), layer(0, 1, vec![100, 200])],
+    };
+    // RID=0 NS=0 sl_bm=0b0011, #tl=1,1, bitrates 25 50 100 200(leb128)
+    assert_eq!(
+        round_trip(&a)?,
+        Bytes::from_static(&[0b0000_0011, 0b0101_0000, 25, 50, 100, 0xC8, 0x01])
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_video_layers_allocation_extension_simulcast() -> Result<()> {
+    // Same spatial layers for every stream use the shared bitmask
+    let a = VideoLayersAllocationExtension {
+        rtp_stream_index: 1,
+        resolution_and_frame_rate_is_valid: true,
+        active_spatial_layers: vec![
+            SpatialLayer {
+                width: 320,
+                height: 180,
+                frame_rate_fps: 15,
+                ..layer(0, 0, vec![150])
+            },
+            SpatialLayer {
+                width: 640,
+                height: 360,
+                frame_rate_fps: 30,
+                ..layer(1, 0, vec![300, 500])
+            },
+            SpatialLayer {
+                width: 1280,
+                height: 720,
+                frame_rate_fps: 30,
+                ..layer(2, 0, vec![1000, 1500, 2500])
+            },
+        ],
+    };
+    let raw = round_trip(&a)?;
+    assert_eq!(raw[0], 0b0110_0001);
+    assert_eq!(raw[1], 0b0001_1000);
+
+    // Different spatial layers per stream use one bitmask per stream
+    let a = VideoLayersAllocationExtension {
+        rtp_stream_index: 0,
+        resolution_and_frame_rate_is_valid: false,
+        active_spatial_layers: vec![
+            layer(0, 0, vec![100]),
+            layer(1, 0, vec![200]),
+            layer(1, 1, vec![400]),
+        ],
+    };
+    let raw = round_trip(&a)?;
+    assert_eq!(raw[0], 0b0001_0000);
+    assert_eq!(raw[1], 0b0001_0011);
+
+    // The stream carrying the extension may have no active layer
+    let a = VideoLayersAllocationExtension {
+        rtp_stream_index: 2,
+        resolution_and_frame_rate_is_valid: false,
+        active_spatial_layers: vec![layer(0, 0, vec![100]), layer(1, 0, vec![200])],
+    };
+    round_trip(&a)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_video_layers_allocation_extension_invalid() -> Result<()> {
+    let invalid = vec![
+        VideoLayersAllocationExtension {
+            active_spatial_layers: vec![layer(0, 0, vec![])],
+            ..Default::default()
+        },
+        VideoLayersAllocationExtension {
+            active_spatial_layers: vec![layer(0, 0, vec![1, 2, 3, 4, 5])],
+            ..Default::default()
+        },
+        VideoLayersAllocationExtension {
+            active_spatial_layers: vec![layer(0, 4, vec![100])],
+            ..Default::default()
+        },
+        VideoLayersAllocationExtension {
+            active_spatial_layers: vec![layer(1, 0, vec![100]), layer(0, 0, vec![100])],
+            ..Default::default()
+        },
+        VideoLayersAllocationExtension {
+            resolution_and_frame_rate_is_valid: true,
+            active_spatial_layers: vec![layer(0, 0, vec![100])],
+            ..Default::default()
+        },
+    ];
+    for a in invalid {
+        let mut dst = BytesMut::with_capacity(a.marshal_size());
+        dst.resize(a.marshal_size(), 0);
+        assert!(a.marshal_to(&mut dst).is_err(), "{a:?}");
+    }
+
+    // Layers out of range of the bitmasks must be rejected by marshal, not panic
+    let out_of_range = vec![
+        VideoLayersAllocationExtension {
+            active_spatial_layers: vec![layer(4, 0, vec![100])],
+            ..Default::default()
+        },
+        VideoLayersAllocationExtension {
+            active_spatial_layers: vec![layer(0, 8, vec![100])],
+            ..Default::default()
+        },
+        VideoLayersAllocationExtension {
+            active_spatial_layers: vec![layer(255, 255, vec![100])],
+            ..Default::default()
+        },
+        VideoLayersAllocationExtension {
+            rtp_stream_index: 255,
+            active_spatial_layers: vec![layer(0, 0, vec![100])],
+            ..Default::default()
+        },
+    ];
+    for a in out_of_range {
+        assert!(a.marshal().is_err(), "{a:?}");
+    }
+
+    // RID larger than the number of streams
+    let mut buf = &[0b0100_0001u8, 0, 100][..];
+    assert!(VideoLayersAllocationExtension::unmarshal(&mut buf).is_err());
+
+    // Truncated bitrate
+    let mut buf = &[0b0000_0001u8, 0, 0xC8][..];
+    assert!(VideoLayersAllocationExtension::unmarshal(&mut buf).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use bytes::{Bytes, BytesMut};
+
+use super::*;
+use crate::error::Result;
+
+#[test]
+fn test_video_timing_extension() -> Result<()> {
+    let mut buf = &vec![0u8; 11][..];
+    assert!(VideoTimingExtension::unmarshal(&mut buf).is_err());
+
+    let raw = Bytes::from_static(&[
+        VIDEO_TIMING_FLAG_TRIGGERED_BY_SIZE,
+        0x00,
+        0x01,
+        0x00,
+        0x05,
+        0x00,
+        0x06,
+        0x00,
+        0x0A,
+        0x01,
+        0x00,
+        0x00,
+        0x00,
+    ]);
+    let a1 = VideoTimingExtension::unmarshal(&mut raw.clone())?;
+    let a2 = VideoTimingExtension {
+        flags: VIDEO_TIMING_FLAG_TRIGGERED_BY_SIZE,
+        encode_start_delta_ms: 1,
+        encode_finish_delta_ms: 5,
+        packetization_finish_delta_ms: 6,
+        pacer_exit_delta_ms: 10,
+        network_timestamp_delta_ms: 256,
+        network2_timestamp_delta_ms: 0,
+    };
+    assert_eq!(a1, a2);
+
+    let mut dst = BytesMut::with_capacity(a2.marshal_size());
+    dst.resize(a2.marshal_size(), 0);
+    a2.marshal_to(&mut dst)?;
+    assert_eq!(raw, dst.freeze());
+
+    // The legacy format has no flags
+    let a3 = VideoTimingExtension::unmarshal(&mut raw.slice(1..))?;
+    assert_eq!(a3, VideoTimingExtension { flags: 0, ..a2 });
+
+    Ok(())
+}
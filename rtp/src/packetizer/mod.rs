@@ -31,6 +31,11 @@ pub trait Packetizer: fmt::Debug {
     fn enable_abs_send_time(&mut self, value: u8);
     fn packetize(&mut self, payload: &Bytes, samples: u32) -> Result<Vec<Packet>>;
     fn skip_samples(&mut self, skipped_samples: u32);
+    /// timestamp returns the RTP timestamp of the next packetized payload, or 0 if the
+    /// packetizer doesn't expose it
+    fn timestamp(&self) -> u32 {
+        0
+    }
     fn clone_to(&self) -> Box<dyn Packetizer + Send + Sync>;
 }
 
@@ -159,6 +164,10 @@ impl Packetizer for PacketizerImpl {
         self.timestamp = self.timestamp.wrapping_add(skipped_samples);
     }
 
+    fn timestamp(&self) -> u32 {
+        self.timestamp
+    }
+
     fn clone_to(&self) -> Box<dyn Packetizer + Send + Sync> {
         Box::new(self.clone())
     }
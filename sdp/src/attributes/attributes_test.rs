@@ -0,0 +1,275 @@
+use super::*;
+use crate::description::media::MediaDescription;
+use crate::description::session::SessionDescription;
+
+fn assert_round_trip<T: AttributeValue + fmt::Debug>(values: &[&str]) -> Result<()> {
+    for value in values {
+        let parsed = T::parse(value)?;
+        assert_eq!(&parsed.to_string(), value, "{parsed:?}");
+    }
+    Ok(())
+}
+
+fn assert_syntax_error<T: AttributeValue + fmt::Debug>(tests: &[(&str, usize)]) {
+    for (value, position) in tests {
+        match T::parse(value) {
+            Err(Error::SyntaxError { s, p }) => {
+                assert_eq!(&s, value);
+                assert_eq!(p, *position, "{value}");
+            }
+            other => panic!("{value}: expected a syntax error, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_candidate() -> Result<()> {
+    assert_round_trip::<Candidate>(&[
+        "1 1 udp 2130706431 192.168.1.2 53165 typ host",
+        "2 1 udp 1694498815 203.0.113.7 61665 typ srflx raddr 192.168.1.2 rport 53165",
+        "3 1 tcp 1518280447 192.168.1.2 9 typ host tcptype active generation 0 network-id 1",
+    ])?;
+
+    let candidate = Candidate::parse(
+        "2 1 udp 1694498815 203.0.113.7 61665 typ srflx raddr 192.168.1.2 rport 53165 network-cost 10",
+    )?;
+    assert_eq!(candidate.component, 1);
+    assert_eq!(candidate.priority, 1694498815);
+    assert_eq!(candidate.typ, "srflx");
+    assert_eq!(candidate.related_address.as_deref(), Some("192.168.1.2"));
+    assert_eq!(candidate.related_port, Some(53165));
+    assert_eq!(
+        candidate.extensions,
+        vec![("network-cost".to_owned(), "10".to_owned())]
+    );
+
+    assert_syntax_error::<Candidate>(&[
+        ("1 1 udp", 7),
+        ("1 x udp 2130706431 192.168.1.2 53165 typ host", 2),
+        ("1 1 udp 2130706431 192.168.1.2 99999 typ host", 31),
+        ("1 1 udp 2130706431 192.168.1.2 53165 type host", 37),
+        (
+            "1 1 udp 2130706431 192.168.1.2 53165 typ host generation",
+            56,
+        ),
+    ]);
+
+    Ok(())
+}
+
+#[test]
+fn test_rtpmap() -> Result<()> {
+    assert_round_trip::<RtpMap>(&["96 VP8/90000", "111 opus/48000/2"])?;
+
+    let rtpmap = RtpMap::parse("111 opus/48000/2")?;
+    assert_eq!(
+        rtpmap,
+        RtpMap {
+            payload_type: 111,
+            encoding_name: "opus".to_owned(),
+            clock_rate: 48000,
+            encoding_parameters: Some("2".to_owned()),
+        }
+    );
+
+    assert_syntax_error::<RtpMap>(&[
+        ("256 VP8/90000", 0),
+        ("96", 2),
+        ("96 VP8", 6),
+        ("96 VP8/fast", 7),
+        ("96 VP8/90000 extra", 13),
+    ]);
+
+    Ok(())
+}
+
+#[test]
+fn test_fmtp() -> Result<()> {
+    assert_round_trip::<Fmtp>(&["111 minptime=10;useinbandfec=1", "97 apt=96", "101 0-15"])?;
+
+    // Parameters separated by whitespace are kept, and normalized on output.
+    let fmtp = Fmtp::parse("96 profile-level-id=42e01f; packetization-mode=1")?;
+    assert_eq!(fmtp.payload_type(), Some(96));
+    assert_eq!(fmtp.parameter("profile-level-id"), Some("42e01f"));
+    assert_eq!(fmtp.parameter("packetization-mode"), Some("1"));
+    assert_eq!(fmtp.parameter("level-asymmetry-allowed"), None);
+    assert_eq!(
+        fmtp.to_string(),
+        "96 profile-level-id=42e01f;packetization-mode=1"
+    );
+
+    assert_syntax_error::<Fmtp>(&[("", 0), ("96 ", 3)]);
+
+    Ok(())
+}
+
+#[test]
+fn test_rtcp_fb() -> Result<()> {
+    assert_round_trip::<RtcpFb>(&["96 nack", "96 nack pli", "* transport-cc"])?;
+
+    let rtcp_fb = RtcpFb::parse("96 ccm fir")?;
+    assert_eq!(rtcp_fb.payload_type(), Some(96));
+    assert_eq!(rtcp_fb.feedback(), "ccm fir");
+    assert_eq!(RtcpFb::parse("* goog-remb")?.payload_type(), None);
+
+    assert_syntax_error::<RtcpFb>(&[("x nack", 0), ("96", 2)]);
+
+    Ok(())
+}
+
+#[test]
+fn test_ssrc_group_msid_group() -> Result<()> {
+    assert_round_trip::<SsrcGroup>(&["FID 1234 5678", "SIM 1 2 3"])?;
+    assert_round_trip::<Msid>(&["stream track", "stream"])?;
+    assert_round_trip::<Group>(&["BUNDLE 0 1 2", "LS"])?;
+
+    assert_eq!(SsrcGroup::parse("FID 1234 5678")?.ssrcs, vec![1234, 5678]);
+    assert_eq!(
+        Group::parse("BUNDLE audio video")?.tags,
+        vec!["audio".to_owned(), "video".to_owned()]
+    );
+
+    assert_syntax_error::<SsrcGroup>(&[("FID 1234 -1", 9)]);
+    assert_syntax_error::<Msid>(&[("", 0), ("stream track extra", 13)]);
+
+    Ok(())
+}
+
+#[test]
+fn test_rid() -> Result<()> {
+    assert_round_trip::<Rid>(&[
+        "h send",
+        "h send pt=96,97",
+        "f recv pt=96;max-width=1280;max-height=720",
+        "q send max-fps=15",
+    ])?;
+
+    let rid = Rid::parse("f recv pt=96,97;max-width=1280")?;
+    assert_eq!(rid.direction, RidDirection::Recv);
+    assert_eq!(rid.payload_types, vec![96, 97]);
+    assert_eq!(
+        rid.restrictions,
+        vec![("max-width".to_owned(), Some("1280".to_owned()))]
+    );
+
+    assert_syntax_error::<Rid>(&[("h", 1), ("h both", 2), ("h send pt=x", 10)]);
+
+    Ok(())
+}
+
+#[test]
+fn test_simulcast() -> Result<()> {
+    assert_round_trip::<Simulcast>(&["send h;m;l", "recv 1,2;3", "send ~h;m recv l"])?;
+
+    let simulcast = Simulcast::parse("send h,~m;l")?;
+    assert_eq!(
+        simulcast.send,
+        vec![
+            vec![
+                SimulcastStream {
+                    rid: "h".to_owned(),
+                    paused: false,
+                },
+                SimulcastStream {
+                    rid: "m".to_owned(),
+                    paused: true,
+                },
+            ],
+            vec![SimulcastStream {
+                rid: "l".to_owned(),
+                paused: false,
+            }],
+        ]
+    );
+    assert!(simulcast.recv.is_empty());
+
+    assert_syntax_error::<Simulcast>(&[
+        ("", 0),
+        ("send", 4),
+        ("send h;;l", 7),
+        ("both h", 0),
+        ("send h send l", 7),
+    ]);
+
+    Ok(())
+}
+
+#[test]
+fn test_syntax_error_position_in_chars() {
+    // Positions are counted in characters so that the error display lines up.
+    assert_syntax_error::<SsrcGroup>(&[("é 1234 x", 7)]);
+}
+
+#[test]
+fn test_typed_attribute() -> Result<()> {
+    let tests = vec![
+        Attribute::new("rtpmap".to_owned(), Some("96 VP8/90000".to_owned())),
+        Attribute::new("fmtp".to_owned(), Some("97 apt=96".to_owned())),
+        Attribute::new("rtcp-fb".to_owned(), Some("96 nack pli".to_owned())),
+        Attribute::new("simulcast".to_owned(), Some("send h;l".to_owned())),
+        Attribute::new("rid".to_owned(), Some("h send".to_owned())),
+        Attribute::new("sendrecv".to_owned(), None),
+        Attribute::new(
+            "x-unknown".to_owned(),
+            Some("  kept   exactly as is ".to_owned()),
+        ),
+    ];
+
+    for attribute in tests {
+        let typed = TypedAttribute::parse(&attribute)?;
+        assert_eq!(Attribute::from(typed), attribute);
+    }
+
+    assert!(matches!(
+        TypedAttribute::parse(&Attribute::new("rtpmap".to_owned(), None)),
+        Err(Error::SdpInvalidSyntax(_))
+    ));
+    assert!(matches!(
+        TypedAttribute::parse(&Attribute::new(
+            "x-unknown".to_owned(),
+            Some("anything".to_owned())
+        ))?,
+        TypedAttribute::Other(_)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_description_typed_attributes() -> Result<()> {
+    let media = MediaDescription::new_jsep_media_description("video".to_owned(), vec![])
+        .with_typed_attribute(&RtpMap::parse("96 VP8/90000")?)
+        .with_typed_attribute(&RtpMap::parse("97 rtx/90000")?)
+        .with_typed_attribute(&Fmtp::parse("97 apt=96")?)
+        .with_property_attribute("rtcp-mux".to_owned());
+
+    let rtpmaps = media.attributes_of::<RtpMap>()?;
+    assert_eq!(rtpmaps.len(), 2);
+    assert_eq!(rtpmaps[1].encoding_name, "rtx");
+    assert_eq!(
+        media.attributes_of::<Fmtp>()?[0].parameter("apt"),
+        Some("96")
+    );
+    assert!(media.attributes_of::<Rid>()?.is_empty());
+
+    let typed = media.typed_attributes()?;
+    assert_eq!(typed.len(), 4);
+    assert!(matches!(typed[3], TypedAttribute::Other(_)));
+
+    let session = SessionDescription::default().with_typed_attribute(&Group {
+        semantics: "BUNDLE".to_owned(),
+        tags: vec!["0".to_owned(), "1".to_owned()],
+    });
+    assert_eq!(
+        session.attribute(ATTR_KEY_GROUP).map(String::as_str),
+        Some("BUNDLE 0 1")
+    );
+    assert_eq!(session.attributes_of::<Group>()?[0].tags.len(), 2);
+
+    let malformed =
+        media.with_value_attribute(ATTR_KEY_RTPMAP.to_owned(), "x VP9/90000".to_owned());
+    assert!(malformed.attributes_of::<RtpMap>().is_err());
+    assert!(malformed.typed_attributes().is_err());
+
+    Ok(())
+}
@@ -0,0 +1,694 @@
+#[cfg(test)]
+mod attributes_test;
+
+use std::fmt;
+
+use crate::description::common::Attribute;
+use crate::description::session::*;
+use crate::error::{Error, Result};
+use crate::lexer::Fields;
+
+/// AttributeValue is an attribute whose value has a typed representation,
+/// parsed from and serialized to the value of its "a=" line.
+pub trait AttributeValue: fmt::Display + Sized {
+    /// The key of the attribute, as in "a=<key>:<value>".
+    const KEY: &'static str;
+
+    /// parse parses the value of the attribute, errors point at the offending
+    /// position of the value.
+    fn parse(value: &str) -> Result<Self>;
+
+    /// to_attribute converts this value to an Attribute.
+    fn to_attribute(&self) -> Attribute {
+        Attribute::new(Self::KEY.to_owned(), Some(self.to_string()))
+    }
+}
+
+/// TypedAttribute is an attribute parsed according to its key, attributes
+/// without a typed representation are kept as is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypedAttribute {
+    Candidate(Candidate),
+    RtpMap(RtpMap),
+    Fmtp(Fmtp),
+    RtcpFb(RtcpFb),
+    SsrcGroup(SsrcGroup),
+    Simulcast(Simulcast),
+    Rid(Rid),
+    Msid(Msid),
+    Group(Group),
+    Other(Attribute),
+}
+
+impl TypedAttribute {
+    /// parse parses the attribute according to its key.
+    pub fn parse(attribute: &Attribute) -> Result<Self> {
+        fn value<T: AttributeValue>(attribute: &Attribute) -> Result<T> {
+            match &attribute.value {
+                Some(value) => T::parse(value),
+                None => Err(Error::SdpInvalidSyntax(format!("`a={}`", attribute.key))),
+            }
+        }
+
+        Ok(match attribute.key.as_str() {
+            Candidate::KEY => TypedAttribute::Candidate(value(attribute)?),
+            RtpMap::KEY => TypedAttribute::RtpMap(value(attribute)?),
+            Fmtp::KEY => TypedAttribute::Fmtp(value(attribute)?),
+            RtcpFb::KEY => TypedAttribute::RtcpFb(value(attribute)?),
+            SsrcGroup::KEY => TypedAttribute::SsrcGroup(value(attribute)?),
+            Simulcast::KEY => TypedAttribute::Simulcast(value(attribute)?),
+            Rid::KEY => TypedAttribute::Rid(value(attribute)?),
+            Msid::KEY => TypedAttribute::Msid(value(attribute)?),
+            Group::KEY => TypedAttribute::Group(value(attribute)?),
+            _ => TypedAttribute::Other(attribute.clone()),
+        })
+    }
+
+    /// to_attribute converts this value back to an Attribute.
+    pub fn to_attribute(&self) -> Attribute {
+        match self {
+            TypedAttribute::Candidate(v) => v.to_attribute(),
+            TypedAttribute::RtpMap(v) => v.to_attribute(),
+            TypedAttribute::Fmtp(v) => v.to_attribute(),
+            TypedAttribute::RtcpFb(v) => v.to_attribute(),
+            TypedAttribute::SsrcGroup(v) => v.to_attribute(),
+            TypedAttribute::Simulcast(v) => v.to_attribute(),
+            TypedAttribute::Rid(v) => v.to_attribute(),
+            TypedAttribute::Msid(v) => v.to_attribute(),
+            TypedAttribute::Group(v) => v.to_attribute(),
+            TypedAttribute::Other(a) => a.clone(),
+        }
+    }
+}
+
+impl From<TypedAttribute> for Attribute {
+    fn from(attribute: TypedAttribute) -> Self {
+        match attribute {
+            TypedAttribute::Other(a) => a,
+            _ => attribute.to_attribute(),
+        }
+    }
+}
+
+// parse_parameters parses a list of `key[=value]` separated by `;`.
+fn parse_parameters(value: &str) -> Vec<(String, Option<String>)> {
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|p| match p.split_once('=') {
+            Some((key, value)) => (key.trim().to_owned(), Some(value.trim().to_owned())),
+            None => (p.to_owned(), None),
+        })
+        .collect()
+}
+
+fn write_parameters(
+    f: &mut fmt::Formatter<'_>,
+    parameters: &[(String, Option<String>)],
+) -> fmt::Result {
+    for (i, (key, value)) in parameters.iter().enumerate() {
+        if i > 0 {
+            write!(f, ";")?;
+        }
+        write!(f, "{key}")?;
+        if let Some(value) = value {
+            write!(f, "={value}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Candidate describes the "a=candidate" attribute of an ICE candidate
+/// (RFC 8839 Section 5.1).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub foundation: String,
+    pub component: u16,
+    pub transport: String,
+    pub priority: u32,
+    pub address: String,
+    pub port: u16,
+    pub typ: String,
+    pub related_address: Option<String>,
+    pub related_port: Option<u16>,
+    /// Extensions such as tcptype, generation or network-id, in order.
+    pub extensions: Vec<(String, String)>,
+}
+
+impl fmt::Display for Candidate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {} typ {}",
+            self.foundation,
+            self.component,
+            self.transport,
+            self.priority,
+            self.address,
+            self.port,
+            self.typ
+        )?;
+        if let Some(related_address) = &self.related_address {
+            write!(f, " raddr {related_address}")?;
+        }
+        if let Some(related_port) = &self.related_port {
+            write!(f, " rport {related_port}")?;
+        }
+        for (key, value) in &self.extensions {
+            write!(f, " {key} {value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl AttributeValue for Candidate {
+    const KEY: &'static str = ATTR_KEY_CANDIDATE;
+
+    fn parse(value: &str) -> Result<Self> {
+        let mut fields = Fields::new(value);
+
+        let (foundation, _) = fields.expect_field()?;
+        let (component, p) = fields.expect_field()?;
+        let component = fields.parse(component, p)?;
+        let (transport, _) = fields.expect_field()?;
+        let (priority, p) = fields.expect_field()?;
+        let priority = fields.parse(priority, p)?;
+        let (address, _) = fields.expect_field()?;
+        let (port, p) = fields.expect_field()?;
+        let port = fields.parse(port, p)?;
+        let (typ, p) = fields.expect_field()?;
+        if typ != "typ" {
+            return Err(fields.error(p));
+        }
+        let (typ, _) = fields.expect_field()?;
+
+        let mut candidate = Candidate {
+            foundation: foundation.to_owned(),
+            component,
+            transport: transport.to_owned(),
+            priority,
+            address: address.to_owned(),
+            port,
+            typ: typ.to_owned(),
+            ..Default::default()
+        };
+
+        while let Some((key, p)) = fields.next_field() {
+            let Some((value, value_p)) = fields.next_field() else {
+                return Err(fields.error(p + key.len()));
+            };
+            match key {
+                "raddr" if candidate.extensions.is_empty() => {
+                    candidate.related_address = Some(value.to_owned());
+                }
+                "rport" if candidate.extensions.is_empty() => {
+                    candidate.related_port = Some(fields.parse(value, value_p)?);
+                }
+                _ => candidate
+                    .extensions
+                    .push((key.to_owned(), value.to_owned())),
+            }
+        }
+
+        Ok(candidate)
+    }
+}
+
+/// RtpMap describes the "a=rtpmap" attribute mapping a payload type to an
+/// encoding (RFC 8866 Section 6.6).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RtpMap {
+    pub payload_type: u8,
+    pub encoding_name: String,
+    pub clock_rate: u32,
+    pub encoding_parameters: Option<String>,
+}
+
+impl fmt::Display for RtpMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}/{}",
+            self.payload_type, self.encoding_name, self.clock_rate
+        )?;
+        if let Some(encoding_parameters) = &self.encoding_parameters {
+            write!(f, "/{encoding_parameters}")?;
+        }
+        Ok(())
+    }
+}
+
+impl AttributeValue for RtpMap {
+    const KEY: &'static str = ATTR_KEY_RTPMAP;
+
+    fn parse(value: &str) -> Result<Self> {
+        let mut fields = Fields::new(value);
+
+        let (payload_type, p) = fields.expect_field()?;
+        let payload_type = fields.parse(payload_type, p)?;
+        let (encoding, p) = fields.expect_field()?;
+        if let Some((_, p)) = fields.next_field() {
+            return Err(fields.error(p));
+        }
+
+        let mut split = encoding.splitn(3, '/');
+        let encoding_name = split.next().unwrap_or_default();
+        let clock_rate = split
+            .next()
+            .ok_or_else(|| fields.error(p + encoding.len()))?;
+        let clock_rate = fields.parse(clock_rate, p + encoding_name.len() + 1)?;
+
+        Ok(RtpMap {
+            payload_type,
+            encoding_name: encoding_name.to_owned(),
+            clock_rate,
+            encoding_parameters: split.next().map(str::to_owned),
+        })
+    }
+}
+
+/// Fmtp describes the "a=fmtp" attribute carrying the format specific
+/// parameters (RFC 8866 Section 6.15).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Fmtp {
+    /// The format, a payload type for RTP media.
+    pub format: String,
+    /// The `key[=value]` parameters, in order.
+    pub parameters: Vec<(String, Option<String>)>,
+}
+
+impl Fmtp {
+    /// payload_type returns the format as an RTP payload type.
+    pub fn payload_type(&self) -> Option<u8> {
+        self.format.parse().ok()
+    }
+
+    /// parameter returns the value of the parameter named key.
+    pub fn parameter(&self, key: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(k, _)| k == key)
+            .and_then(|(_, v)| v.as_deref())
+    }
+
+    /// parameters_string returns the parameters as they appear in the attribute.
+    pub fn parameters_string(&self) -> String {
+        struct Parameters<'a>(&'a [(String, Option<String>)]);
+        impl fmt::Display for Parameters<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write_parameters(f, self.0)
+            }
+        }
+        Parameters(&self.parameters).to_string()
+    }
+}
+
+impl fmt::Display for Fmtp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.format)?;
+        write_parameters(f, &self.parameters)
+    }
+}
+
+impl AttributeValue for Fmtp {
+    const KEY: &'static str = ATTR_KEY_FMTP;
+
+    fn parse(value: &str) -> Result<Self> {
+        let mut fields = Fields::new(value);
+
+        let (format, _) = fields.expect_field()?;
+        // Parameters may be separated by whitespace after the `;`.
+        let (parameters, _) = fields.rest().ok_or_else(|| fields.error(value.len()))?;
+
+        Ok(Fmtp {
+            format: format.to_owned(),
+            parameters: parse_parameters(parameters),
+        })
+    }
+}
+
+/// RtcpFb describes the "a=rtcp-fb" attribute of RTCP feedback capabilities
+/// (RFC 4585 Section 4.2).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RtcpFb {
+    /// The format the feedback applies to, `*` for all of them.
+    pub format: String,
+    pub typ: String,
+    pub parameter: Option<String>,
+}
+
+impl RtcpFb {
+    /// payload_type returns the format as an RTP payload type, None for `*`.
+    pub fn payload_type(&self) -> Option<u8> {
+        self.format.parse().ok()
+    }
+
+    /// feedback returns the type and parameter of the feedback, like "nack pli".
+    pub fn feedback(&self) -> String {
+        match &self.parameter {
+            Some(parameter) => format!("{} {}", self.typ, parameter),
+            None => self.typ.clone(),
+        }
+    }
+}
+
+impl fmt::Display for RtcpFb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.format, self.feedback())
+    }
+}
+
+impl AttributeValue for RtcpFb {
+    const KEY: &'static str = ATTR_KEY_RTCPFB;
+
+    fn parse(value: &str) -> Result<Self> {
+        let mut fields = Fields::new(value);
+
+        let (format, p) = fields.expect_field()?;
+        if format != "*" {
+            fields.parse::<u8>(format, p)?;
+        }
+        let (typ, _) = fields.expect_field()?;
+
+        Ok(RtcpFb {
+            format: format.to_owned(),
+            typ: typ.to_owned(),
+            parameter: fields.rest().map(|(rest, _)| rest.to_owned()),
+        })
+    }
+}
+
+/// SsrcGroup describes the "a=ssrc-group" attribute grouping SSRCs, like the
+/// RTX one of a stream (RFC 5576 Section 4.2).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SsrcGroup {
+    pub semantics: String,
+    pub ssrcs: Vec<u32>,
+}
+
+impl fmt::Display for SsrcGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.semantics)?;
+        for ssrc in &self.ssrcs {
+            write!(f, " {ssrc}")?;
+        }
+        Ok(())
+    }
+}
+
+impl AttributeValue for SsrcGroup {
+    const KEY: &'static str = ATTR_KEY_SSRCGROUP;
+
+    fn parse(value: &str) -> Result<Self> {
+        let mut fields = Fields::new(value);
+
+        let (semantics, _) = fields.expect_field()?;
+        let mut ssrcs = vec![];
+        while let Some((ssrc, p)) = fields.next_field() {
+            ssrcs.push(fields.parse(ssrc, p)?);
+        }
+
+        Ok(SsrcGroup {
+            semantics: semantics.to_owned(),
+            ssrcs,
+        })
+    }
+}
+
+/// Msid describes the "a=msid" attribute associating a media description to
+/// a media stream and track (RFC 8830 Section 2).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Msid {
+    pub stream_id: String,
+    pub track_id: Option<String>,
+}
+
+impl fmt::Display for Msid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.stream_id)?;
+        if let Some(track_id) = &self.track_id {
+            write!(f, " {track_id}")?;
+        }
+        Ok(())
+    }
+}
+
+impl AttributeValue for Msid {
+    const KEY: &'static str = ATTR_KEY_MSID;
+
+    fn parse(value: &str) -> Result<Self> {
+        let mut fields = Fields::new(value);
+
+        let (stream_id, _) = fields.expect_field()?;
+        let track_id = fields.next_field().map(|(track_id, _)| track_id.to_owned());
+        if let Some((_, p)) = fields.next_field() {
+            return Err(fields.error(p));
+        }
+
+        Ok(Msid {
+            stream_id: stream_id.to_owned(),
+            track_id,
+        })
+    }
+}
+
+/// Group describes the "a=group" attribute grouping media descriptions, like
+/// the BUNDLE group (RFC 5888 Section 5).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Group {
+    pub semantics: String,
+    /// The identification tags (mids) of the grouped media descriptions.
+    pub tags: Vec<String>,
+}
+
+impl fmt::Display for Group {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.semantics)?;
+        for tag in &self.tags {
+            write!(f, " {tag}")?;
+        }
+        Ok(())
+    }
+}
+
+impl AttributeValue for Group {
+    const KEY: &'static str = ATTR_KEY_GROUP;
+
+    fn parse(value: &str) -> Result<Self> {
+        let mut fields = Fields::new(value);
+
+        let (semantics, _) = fields.expect_field()?;
+        let mut tags = vec![];
+        while let Some((tag, _)) = fields.next_field() {
+            tags.push(tag.to_owned());
+        }
+
+        Ok(Group {
+            semantics: semantics.to_owned(),
+            tags,
+        })
+    }
+}
+
+/// RidDirection is the direction of the RTP stream a rid applies to.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum RidDirection {
+    #[default]
+    Send,
+    Recv,
+}
+
+impl fmt::Display for RidDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RidDirection::Send => write!(f, "send"),
+            RidDirection::Recv => write!(f, "recv"),
+        }
+    }
+}
+
+/// Rid describes the "a=rid" attribute identifying an RTP stream and its
+/// restrictions (RFC 8851 Section 10).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Rid {
+    pub id: String,
+    pub direction: RidDirection,
+    /// The payload types the stream is restricted to, from `pt=`.
+    pub payload_types: Vec<u8>,
+    /// The other `key[=value]` restrictions, like max-width, in order.
+    pub restrictions: Vec<(String, Option<String>)>,
+}
+
+impl fmt::Display for Rid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.id, self.direction)?;
+        if self.payload_types.is_empty() && self.restrictions.is_empty() {
+            return Ok(());
+        }
+
+        write!(f, " ")?;
+        if !self.payload_types.is_empty() {
+            let payload_types: Vec<String> =
+                self.payload_types.iter().map(|pt| pt.to_string()).collect();
+            write!(f, "pt={}", payload_types.join(","))?;
+            if !self.restrictions.is_empty() {
+                write!(f, ";")?;
+            }
+        }
+        write_parameters(f, &self.restrictions)
+    }
+}
+
+impl AttributeValue for Rid {
+    const KEY: &'static str = ATTR_KEY_RID;
+
+    fn parse(value: &str) -> Result<Self> {
+        let mut fields = Fields::new(value);
+
+        let (id, _) = fields.expect_field()?;
+        let (direction, p) = fields.expect_field()?;
+        let direction = match direction {
+            "send" => RidDirection::Send,
+            "recv" => RidDirection::Recv,
+            _ => return Err(fields.error(p)),
+        };
+
+        let mut rid = Rid {
+            id: id.to_owned(),
+            direction,
+            ..Default::default()
+        };
+
+        if let Some((restrictions, p)) = fields.rest() {
+            for (key, value) in parse_parameters(restrictions) {
+                if key != "pt" {
+                    rid.restrictions.push((key, value));
+                    continue;
+                }
+
+                let formats = value.unwrap_or_default();
+                // Point at the format list in the value.
+                let p = p + restrictions.find("pt=").unwrap_or(0) + 3;
+                for format in formats.split(',') {
+                    rid.payload_types.push(fields.parse(format.trim(), p)?);
+                }
+            }
+        }
+
+        Ok(rid)
+    }
+}
+
+/// SimulcastStream is a rid listed in a simulcast attribute.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SimulcastStream {
+    pub rid: String,
+    pub paused: bool,
+}
+
+impl fmt::Display for SimulcastStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.paused {
+            write!(f, "~")?;
+        }
+        write!(f, "{}", self.rid)
+    }
+}
+
+/// Simulcast describes the "a=simulcast" attribute listing the simulcast
+/// streams in each direction, every stream being a list of alternative rids
+/// (RFC 8853 Section 5.1).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Simulcast {
+    pub send: Vec<Vec<SimulcastStream>>,
+    pub recv: Vec<Vec<SimulcastStream>>,
+}
+
+impl Simulcast {
+    fn write_streams(f: &mut fmt::Formatter<'_>, streams: &[Vec<SimulcastStream>]) -> fmt::Result {
+        for (i, alternatives) in streams.iter().enumerate() {
+            if i > 0 {
+                write!(f, ";")?;
+            }
+            for (j, stream) in alternatives.iter().enumerate() {
+                if j > 0 {
+                    write!(f, ",")?;
+                }
+                write!(f, "{stream}")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_streams(
+        fields: &Fields<'_>,
+        value: &str,
+        p: usize,
+    ) -> Result<Vec<Vec<SimulcastStream>>> {
+        let mut streams = vec![];
+        let mut offset = 0;
+        for alternatives in value.split(';') {
+            let mut list = vec![];
+            for rid in alternatives.split(',') {
+                let (paused, id) = match rid.strip_prefix('~') {
+                    Some(id) => (true, id),
+                    None => (false, rid),
+                };
+                if id.is_empty() {
+                    return Err(fields.error(p + offset));
+                }
+                list.push(SimulcastStream {
+                    rid: id.to_owned(),
+                    paused,
+                });
+                offset += rid.len() + 1;
+            }
+            streams.push(list);
+        }
+        Ok(streams)
+    }
+}
+
+impl fmt::Display for Simulcast {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.send.is_empty() {
+            write!(f, "send ")?;
+            Self::write_streams(f, &self.send)?;
+            if !self.recv.is_empty() {
+                write!(f, " ")?;
+            }
+        }
+        if !self.recv.is_empty() {
+            write!(f, "recv ")?;
+            Self::write_streams(f, &self.recv)?;
+        }
+        Ok(())
+    }
+}
+
+impl AttributeValue for Simulcast {
+    const KEY: &'static str = ATTR_KEY_SIMULCAST;
+
+    fn parse(value: &str) -> Result<Self> {
+        let mut fields = Fields::new(value);
+        let mut simulcast = Simulcast::default();
+
+        let mut seen = false;
+        while let Some((direction, p)) = fields.next_field() {
+            let (streams, streams_p) = fields.expect_field()?;
+            let streams = Self::parse_streams(&fields, streams, streams_p)?;
+            match direction {
+                "send" if simulcast.send.is_empty() => simulcast.send = streams,
+                "recv" if simulcast.recv.is_empty() => simulcast.recv = streams,
+                _ => return Err(fields.error(p)),
+            }
+            seen = true;
+        }
+        if !seen {
+            return Err(fields.error(0));
+        }
+
+        Ok(simulcast)
+    }
+}
@@ -3,7 +3,9 @@ use std::fmt;
 
 use url::Url;
 
+use crate::attributes::{AttributeValue, TypedAttribute};
 use crate::description::common::*;
+use crate::error::Result;
 use crate::extmap::*;
 
 /// Constants for extmap key
@@ -80,6 +82,22 @@ impl MediaDescription {
         None
     }
 
+    /// typed_attributes returns every attribute parsed according to its key,
+    /// failing on the first malformed one.
+    pub fn typed_attributes(&self) -> Result<Vec<TypedAttribute>> {
+        self.attributes.iter().map(TypedAttribute::parse).collect()
+    }
+
+    /// attributes_of returns the values of the attributes of type T,
+    /// failing on the first malformed one.
+    pub fn attributes_of<T: AttributeValue>(&self) -> Result<Vec<T>> {
+        self.attributes
+            .iter()
+            .filter(|a| a.key == T::KEY)
+            .map(|a| T::parse(a.value.as_deref().unwrap_or_default()))
+            .collect()
+    }
+
     /// new_jsep_media_description creates a new MediaName with
     /// some settings that are required by the JSEP spec.
     pub fn new_jsep_media_description(codec_type: String, _codec_prefs: Vec<&str>) -> Self {
@@ -126,6 +144,12 @@ impl MediaDescription {
         self
     }
 
+    /// with_typed_attribute adds a typed attribute 'a=key:value' to the media description
+    pub fn with_typed_attribute<T: AttributeValue>(mut self, value: &T) -> Self {
+        self.attributes.push(value.to_attribute());
+        self
+    }
+
     /// with_fingerprint adds a fingerprint to the media description
     pub fn with_fingerprint(self, algorithm: String, value: String) -> Self {
         self.with_value_attribute("fingerprint".to_owned(), algorithm + " " + &value)
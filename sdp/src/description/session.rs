@@ -7,6 +7,7 @@ use url::Url;
 
 use super::common::*;
 use super::media::*;
+use crate::attributes::{AttributeValue, TypedAttribute};
 use crate::error::{Error, Result};
 use crate::lexer::*;
 use crate::util::*;
@@ -23,6 +24,7 @@ pub const ATTR_KEY_MSID_SEMANTIC: &str = "msid-semantic";
 pub const ATTR_KEY_CONNECTION_SETUP: &str = "setup";
 pub const ATTR_KEY_MID: &str = "mid";
 pub const ATTR_KEY_ICELITE: &str = "ice-lite";
+pub const ATTR_KEY_ICE_OPTIONS: &str = "ice-options";
 pub const ATTR_KEY_RTCPMUX: &str = "rtcp-mux";
 pub const ATTR_KEY_RTCPRSIZE: &str = "rtcp-rsize";
 pub const ATTR_KEY_INACTIVE: &str = "inactive";
@@ -31,6 +33,12 @@ pub const ATTR_KEY_SEND_ONLY: &str = "sendonly";
 pub const ATTR_KEY_SEND_RECV: &str = "sendrecv";
 pub const ATTR_KEY_EXT_MAP: &str = "extmap";
 pub const ATTR_KEY_EXTMAP_ALLOW_MIXED: &str = "extmap-allow-mixed";
+pub const ATTR_KEY_CRYPTO: &str = "crypto";
+pub const ATTR_KEY_RTPMAP: &str = "rtpmap";
+pub const ATTR_KEY_FMTP: &str = "fmtp";
+pub const ATTR_KEY_RTCPFB: &str = "rtcp-fb";
+pub const ATTR_KEY_RID: &str = "rid";
+pub const ATTR_KEY_SIMULCAST: &str = "simulcast";
 
 /// Constants for semantic tokens used in JSEP
 pub const SEMANTIC_TOKEN_LIP_SYNCHRONIZATION: &str = "LS";
@@ -348,6 +356,12 @@ impl SessionDescription {
         self
     }
 
+    /// with_typed_attribute adds a typed attribute 'a=key:value' to the session description
+    pub fn with_typed_attribute<T: AttributeValue>(mut self, value: &T) -> Self {
+        self.attributes.push(value.to_attribute());
+        self
+    }
+
     /// WithFingerprint adds a fingerprint to the session description
     pub fn with_fingerprint(self, algorithm: String, value: String) -> Self {
         self.with_value_attribute("fingerprint".to_string(), algorithm + " " + value.as_str())
@@ -363,19 +377,72 @@ impl SessionDescription {
         let mut codecs: HashMap<u8, Codec> = HashMap::new();
 
         for m in &self.media_descriptions {
+            // feedback that applies to every payload type of the media description (RFC 4585 §4.2)
+            let mut wildcard_rtcp_feedback = vec![];
             for a in &m.attributes {
+                // match on the text of the attribute so that attributes whose
+                // value was folded into the key are recognized too
                 let attr = a.to_string();
-                if attr.starts_with("rtpmap:") {
-                    if let Ok(codec) = parse_rtpmap(&attr) {
-                        merge_codecs(codec, &mut codecs);
+                let Some((key, value)) = attr.split_once(':') else {
+                    continue;
+                };
+                let attr = Attribute::new(key.to_owned(), Some(value.to_owned()));
+                match TypedAttribute::parse(&attr) {
+                    Ok(TypedAttribute::RtpMap(rtpmap)) => merge_codecs(
+                        Codec {
+                            payload_type: rtpmap.payload_type,
+                            name: rtpmap.encoding_name,
+                            clock_rate: rtpmap.clock_rate,
+                            encoding_parameters: rtpmap.encoding_parameters.unwrap_or_default(),
+                            ..Default::default()
+                        },
+                        &mut codecs,
+                    ),
+                    Ok(TypedAttribute::Fmtp(fmtp)) => {
+                        if let Some(payload_type) = fmtp.payload_type() {
+                            merge_codecs(
+                                Codec {
+                                    payload_type,
+                                    fmtp: fmtp.parameters_string(),
+                                    ..Default::default()
+                                },
+                                &mut codecs,
+                            );
+                        }
                     }
-                } else if attr.starts_with("fmtp:") {
-                    if let Ok(codec) = parse_fmtp(&attr) {
-                        merge_codecs(codec, &mut codecs);
+                    Ok(TypedAttribute::RtcpFb(rtcp_fb)) => match rtcp_fb.payload_type() {
+                        Some(payload_type) => merge_codecs(
+                            Codec {
+                                payload_type,
+                                rtcp_feedback: vec![rtcp_fb.feedback()],
+                                ..Default::default()
+                            },
+                            &mut codecs,
+                        ),
+                        None => wildcard_rtcp_feedback.push(rtcp_fb.feedback()),
+                    },
+                    // an rtpmap without a clock rate isn't valid, but used to be
+                    // accepted with a zero clock rate
+                    Err(_) if key == ATTR_KEY_RTPMAP => {
+                        if let Some(codec) = parse_rtpmap_without_clock_rate(value) {
+                            merge_codecs(codec, &mut codecs);
+                        }
                     }
-                } else if attr.starts_with("rtcp-fb:") {
-                    if let Ok(codec) = parse_rtcp_fb(&attr) {
-                        merge_codecs(codec, &mut codecs);
+                    _ => {}
+                }
+            }
+
+            if !wildcard_rtcp_feedback.is_empty() {
+                for format in &m.media_name.formats {
+                    if let Ok(payload_type) = format.parse::<u8>() {
+                        merge_codecs(
+                            Codec {
+                                payload_type,
+                                rtcp_feedback: wildcard_rtcp_feedback.clone(),
+                                ..Default::default()
+                            },
+                            &mut codecs,
+                        );
                     }
                 }
             }
@@ -424,6 +491,22 @@ impl SessionDescription {
         None
     }
 
+    /// typed_attributes returns every attribute parsed according to its key,
+    /// failing on the first malformed one.
+    pub fn typed_attributes(&self) -> Result<Vec<TypedAttribute>> {
+        self.attributes.iter().map(TypedAttribute::parse).collect()
+    }
+
+    /// attributes_of returns the values of the attributes of type T,
+    /// failing on the first malformed one.
+    pub fn attributes_of<T: AttributeValue>(&self) -> Result<Vec<T>> {
+        self.attributes
+            .iter()
+            .filter(|a| a.key == T::KEY)
+            .map(|a| T::parse(a.value.as_deref().unwrap_or_default()))
+            .collect()
+    }
+
     /// Marshal takes a SDP struct to text
     ///
     /// <https://tools.ietf.org/html/rfc4566#section-5>
@@ -25,6 +25,15 @@ pub const SDES_REPAIR_RTP_STREAM_ID_URI: &str =
 
 pub const AUDIO_LEVEL_URI: &str = "urn:ietf:params:rtp-hdrext:ssrc-audio-level";
 pub const VIDEO_ORIENTATION_URI: &str = "urn:3gpp:video-orientation";
+pub const ABS_CAPTURE_TIME_URI: &str =
+    "http://www.webrtc.org/experiments/rtp-hdrext/abs-capture-time";
+pub const VIDEO_LAYERS_ALLOCATION_URI: &str =
+    "http://www.webrtc.org/experiments/rtp-hdrext/video-layers-allocation00";
+pub const COLOR_SPACE_URI: &str = "http://www.webrtc.org/experiments/rtp-hdrext/color-space";
+pub const VIDEO_CONTENT_TYPE_URI: &str =
+    "http://www.webrtc.org/experiments/rtp-hdrext/video-content-type";
+pub const VIDEO_TIMING_URI: &str = "http://www.webrtc.org/experiments/rtp-hdrext/video-timing";
+pub const INBAND_CN_URI: &str = "http://www.webrtc.org/experiments/rtp-hdrext/inband-cn";
 
 /// ExtMap represents the activation of a single RTP header extension
 #[derive(Debug, Clone, Default)]
@@ -95,3 +95,66 @@ pub fn write_key_slice_of_values<W: fmt::Write, V: Display>(
 
     Ok(())
 }
+
+/// Fields splits an attribute value on whitespace, keeping the position of
+/// every field so that errors point at the offending one.
+pub(crate) struct Fields<'a> {
+    s: &'a str,
+    p: usize,
+}
+
+impl<'a> Fields<'a> {
+    pub(crate) fn new(s: &'a str) -> Self {
+        Fields { s, p: 0 }
+    }
+
+    /// next_field returns the next field and its position, or None at the end
+    /// of the value.
+    pub(crate) fn next_field(&mut self) -> Option<(&'a str, usize)> {
+        let rest = &self.s[self.p..];
+        let start = self.p + (rest.len() - rest.trim_start().len());
+        if start == self.s.len() {
+            self.p = start;
+            return None;
+        }
+
+        let len = self.s[start..]
+            .find(char::is_whitespace)
+            .unwrap_or(self.s.len() - start);
+        self.p = start + len;
+        Some((&self.s[start..self.p], start))
+    }
+
+    /// expect_field returns the next field and its position, failing at the
+    /// end of the value.
+    pub(crate) fn expect_field(&mut self) -> Result<(&'a str, usize)> {
+        self.next_field().ok_or_else(|| self.error(self.s.len()))
+    }
+
+    /// rest returns the remaining of the value and its position, or None if
+    /// there is nothing left.
+    pub(crate) fn rest(&mut self) -> Option<(&'a str, usize)> {
+        let rest = &self.s[self.p..];
+        let start = self.p + (rest.len() - rest.trim_start().len());
+        self.p = self.s.len();
+        let rest = self.s[start..].trim_end();
+        if rest.is_empty() {
+            None
+        } else {
+            Some((rest, start))
+        }
+    }
+
+    /// parse parses the field at position p.
+    pub(crate) fn parse<T: std::str::FromStr>(&self, field: &str, p: usize) -> Result<T> {
+        field.parse::<T>().map_err(|_| self.error(p))
+    }
+
+    /// error returns a syntax error at the byte position p of the value.
+    pub(crate) fn error(&self, p: usize) -> Error {
+        Error::SyntaxError {
+            s: self.s.to_owned(),
+            p: self.s[..p.min(self.s.len())].chars().count(),
+        }
+    }
+}
@@ -4,8 +4,6 @@ mod util_test;
 use std::collections::HashMap;
 use std::fmt;
 
-use super::error::{Error, Result};
-
 pub const ATTRIBUTE_KEY: &str = "a=";
 
 /// ConnectionRole indicates which of the end points should initiate the connection establishment
@@ -111,83 +109,6 @@ impl fmt::Display for Codec {
     }
 }
 
-pub(crate) fn parse_rtpmap(rtpmap: &str) -> Result<Codec> {
-    // a=rtpmap:<payload type> <encoding name>/<clock rate>[/<encoding parameters>]
-    let split: Vec<&str> = rtpmap.split_whitespace().collect();
-    if split.len() != 2 {
-        return Err(Error::MissingWhitespace);
-    }
-
-    let pt_split: Vec<&str> = split[0].split(':').collect();
-    if pt_split.len() != 2 {
-        return Err(Error::MissingColon);
-    }
-    let payload_type = pt_split[1].parse::<u8>()?;
-
-    let split: Vec<&str> = split[1].split('/').collect();
-    let name = split[0].to_string();
-    let parts = split.len();
-    let clock_rate = if parts > 1 {
-        split[1].parse::<u32>()?
-    } else {
-        0
-    };
-    let encoding_parameters = if parts > 2 {
-        split[2].to_string()
-    } else {
-        "".to_string()
-    };
-
-    Ok(Codec {
-        payload_type,
-        name,
-        clock_rate,
-        encoding_parameters,
-        ..Default::default()
-    })
-}
-
-pub(crate) fn parse_fmtp(fmtp: &str) -> Result<Codec> {
-    // a=fmtp:<format> <format specific parameters>
-    let split: Vec<&str> = fmtp.split_whitespace().collect();
-    if split.len() != 2 {
-        return Err(Error::MissingWhitespace);
-    }
-
-    let fmtp = split[1].to_string();
-
-    let split: Vec<&str> = split[0].split(':').collect();
-    if split.len() != 2 {
-        return Err(Error::MissingColon);
-    }
-    let payload_type = split[1].parse::<u8>()?;
-
-    Ok(Codec {
-        payload_type,
-        fmtp,
-        ..Default::default()
-    })
-}
-
-pub(crate) fn parse_rtcp_fb(rtcp_fb: &str) -> Result<Codec> {
-    // a=ftcp-fb:<payload type> <RTCP feedback type> [<RTCP feedback parameter>]
-    let split: Vec<&str> = rtcp_fb.splitn(2, ' ').collect();
-    if split.len() != 2 {
-        return Err(Error::MissingWhitespace);
-    }
-
-    let pt_split: Vec<&str> = split[0].split(':').collect();
-    if pt_split.len() != 2 {
-        return Err(Error::MissingColon);
-    }
-
-    Ok(Codec {
-        payload_type: pt_split[1].parse::<u8>()?,
-        rtcp_feedback: vec![split[1].to_string()],
-        ..Default::default()
-    })
-}
-
 pub(crate) fn merge_codecs(mut codec: Codec, codecs: &mut HashMap<u8, Codec>) {
     if let Some(saved_codec) = codecs.get_mut(&codec.payload_type) {
         if saved_codec.payload_type == 0 {
@@ -211,6 +132,22 @@ pub(crate) fn merge_codecs(mut codec: Codec, codecs: &mut HashMap<u8, Codec>) {
     }
 }
 
+/// parse_rtpmap_without_clock_rate parses an rtpmap value missing the clock
+/// rate, like "96 VP8", into a codec with a zero clock rate.
+pub(crate) fn parse_rtpmap_without_clock_rate(value: &str) -> Option<Codec> {
+    let mut split = value.split_whitespace();
+    let (payload_type, name) = (split.next()?, split.next()?);
+    if split.next().is_some() || name.contains('/') {
+        return None;
+    }
+
+    Some(Codec {
+        payload_type: payload_type.parse().ok()?,
+        name: name.to_owned(),
+        ..Default::default()
+    })
+}
+
 fn equivalent_fmtp(want: &str, got: &str) -> bool {
     let mut want_split: Vec<&str> = want.split(';').collect();
     let mut got_split: Vec<&str> = got.split(';').collect();
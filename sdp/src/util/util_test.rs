@@ -2,6 +2,7 @@ use super::*;
 use crate::description::common::*;
 use crate::description::media::*;
 use crate::description::session::*;
+use crate::error::Result;
 
 fn get_test_session_description() -> SessionDescription {
     SessionDescription{
@@ -175,3 +176,52 @@ fn test_new_session_id() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_get_codec_for_payload_type_wildcard_rtcp_fb() -> Result<()> {
+    let mut sdp = get_test_session_description();
+    sdp.media_descriptions[0]
+        .attributes
+        .push(Attribute::new("rtcp-fb:* ack ccfb".to_string(), None));
+
+    let codec = sdp.get_codec_for_payload_type(120)?;
+    assert_eq!(codec.rtcp_feedback, vec!["ack ccfb".to_string()]);
+
+    let codec = sdp.get_codec_for_payload_type(97)?;
+    assert_eq!(
+        codec.rtcp_feedback,
+        vec![
+            "ccm fir".to_string(),
+            "nack".to_string(),
+            "nack pli".to_string(),
+            "ack ccfb".to_string(),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_get_codec_for_payload_type_without_clock_rate() -> Result<()> {
+    let mut sdp = get_test_session_description();
+    let md = &mut sdp.media_descriptions[0];
+    md.media_name.formats.push("96".to_string());
+    md.attributes
+        .push(Attribute::new("rtpmap:96 VP8".to_string(), None));
+    md.attributes
+        .push(Attribute::new("rtpmap:98 VP8/fast".to_string(), None));
+
+    let codec = sdp.get_codec_for_payload_type(96)?;
+    assert_eq!(
+        codec,
+        Codec {
+            payload_type: 96,
+            name: "VP8".to_string(),
+            ..Default::default()
+        }
+    );
+
+    assert!(sdp.get_codec_for_payload_type(98).is_err());
+
+    Ok(())
+}
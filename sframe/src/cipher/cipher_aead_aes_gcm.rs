@@ -0,0 +1,56 @@
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes128Gcm, Aes256Gcm, KeyInit, Nonce};
+
+use super::Cipher;
+use crate::cipher_suite::CipherSuite;
+use crate::error::{Error, Result};
+
+/// AEAD Cipher based on AES-GCM, for AES_128_GCM_SHA256_128 and AES_256_GCM_SHA512_128.
+pub(crate) enum CipherAeadAesGcm {
+    Aes128(Box<Aes128Gcm>),
+    Aes256(Box<Aes256Gcm>),
+}
+
+impl CipherAeadAesGcm {
+    pub(crate) fn new(cipher_suite: CipherSuite, key: &[u8]) -> Result<Self> {
+        let invalid_key = |_| Error::Other(format!("invalid {cipher_suite} key length"));
+        match cipher_suite {
+            CipherSuite::AesGcm256Sha512 => Ok(CipherAeadAesGcm::Aes256(Box::new(
+                Aes256Gcm::new_from_slice(key).map_err(invalid_key)?,
+            ))),
+            _ => Ok(CipherAeadAesGcm::Aes128(Box::new(
+                Aes128Gcm::new_from_slice(key).map_err(invalid_key)?,
+            ))),
+        }
+    }
+}
+
+impl Cipher for CipherAeadAesGcm {
+    fn encrypt(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+        let nonce = Nonce::from_slice(nonce);
+        let payload = Payload {
+            msg: plaintext,
+            aad,
+        };
+        let ciphertext = match self {
+            CipherAeadAesGcm::Aes128(cipher) => cipher.encrypt(nonce, payload)?,
+            CipherAeadAesGcm::Aes256(cipher) => cipher.encrypt(nonce, payload)?,
+        };
+
+        Ok(ciphertext)
+    }
+
+    fn decrypt(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let nonce = Nonce::from_slice(nonce);
+        let payload = Payload {
+            msg: ciphertext,
+            aad,
+        };
+        let plaintext = match self {
+            CipherAeadAesGcm::Aes128(cipher) => cipher.decrypt(nonce, payload),
+            CipherAeadAesGcm::Aes256(cipher) => cipher.decrypt(nonce, payload),
+        };
+
+        plaintext.map_err(|_| Error::ErrFailedToVerifyAuthTag)
+    }
+}
@@ -0,0 +1,92 @@
+use aes::cipher::{KeyIvInit, StreamCipher};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+
+use super::Cipher;
+use crate::cipher_suite::CipherSuite;
+use crate::error::{Error, Result};
+
+type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
+type HmacSha256 = Hmac<Sha256>;
+
+const AES_KEY_LEN: usize = 16;
+
+/// AEAD Cipher built from AES-CTR and HMAC-SHA256, for the AES_128_CTR_HMAC_SHA256_* suites.
+///
+/// <https://www.rfc-editor.org/rfc/rfc9605#section-4.5.1>
+pub(crate) struct CipherAesCtrHmacSha256 {
+    enc_key: Vec<u8>,
+    auth_key: Vec<u8>,
+    tag_len: usize,
+}
+
+impl CipherAesCtrHmacSha256 {
+    pub(crate) fn new(cipher_suite: CipherSuite, key: &[u8]) -> Result<Self> {
+        if key.len() != cipher_suite.key_len() {
+            return Err(Error::Other(format!("invalid {cipher_suite} key length")));
+        }
+
+        // The encryption key comes first, followed by the authentication key
+        let (enc_key, auth_key) = key.split_at(AES_KEY_LEN);
+
+        Ok(CipherAesCtrHmacSha256 {
+            enc_key: enc_key.to_vec(),
+            auth_key: auth_key.to_vec(),
+            tag_len: cipher_suite.tag_len(),
+        })
+    }
+
+    fn apply_keystream(&self, nonce: &[u8], data: &mut [u8]) {
+        // The initial counter block is the nonce followed by four zero bytes
+        let mut iv = [0u8; 16];
+        iv[..nonce.len()].copy_from_slice(nonce);
+
+        let mut stream = Aes128Ctr::new(self.enc_key.as_slice().into(), &iv.into());
+        stream.apply_keystream(data);
+    }
+
+    fn compute_tag(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let mut mac =
+            HmacSha256::new_from_slice(&self.auth_key).map_err(|e| Error::Other(e.to_string()))?;
+        mac.update(&(aad.len() as u64).to_be_bytes());
+        mac.update(&(ciphertext.len() as u64).to_be_bytes());
+        mac.update(&(self.tag_len as u64).to_be_bytes());
+        mac.update(nonce);
+        mac.update(aad);
+        mac.update(ciphertext);
+
+        let mut tag = mac.finalize().into_bytes().to_vec();
+        tag.truncate(self.tag_len);
+        Ok(tag)
+    }
+}
+
+impl Cipher for CipherAesCtrHmacSha256 {
+    fn encrypt(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+        let mut ciphertext = plaintext.to_vec();
+        self.apply_keystream(nonce, &mut ciphertext);
+
+        let tag = self.compute_tag(nonce, aad, &ciphertext)?;
+        ciphertext.extend_from_slice(&tag);
+
+        Ok(ciphertext)
+    }
+
+    fn decrypt(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        if ciphertext.len() < self.tag_len {
+            return Err(Error::ErrFailedToVerifyAuthTag);
+        }
+
+        let (ciphertext, actual_tag) = ciphertext.split_at(ciphertext.len() - self.tag_len);
+        let expected_tag = self.compute_tag(nonce, aad, ciphertext)?;
+        if actual_tag.ct_eq(&expected_tag).unwrap_u8() != 1 {
+            return Err(Error::ErrFailedToVerifyAuthTag);
+        }
+
+        let mut plaintext = ciphertext.to_vec();
+        self.apply_keystream(nonce, &mut plaintext);
+
+        Ok(plaintext)
+    }
+}
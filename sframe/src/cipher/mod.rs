@@ -0,0 +1,28 @@
+pub(crate) mod cipher_aead_aes_gcm;
+pub(crate) mod cipher_aes_ctr_hmac_sha256;
+
+use crate::cipher_suite::CipherSuite;
+use crate::error::Result;
+
+/// Cipher is the AEAD algorithm of a cipher suite, keyed with a SFrame key
+pub(crate) trait Cipher {
+    /// encrypt returns the ciphertext followed by the authentication tag
+    fn encrypt(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>>;
+
+    /// decrypt verifies the authentication tag at the end of `ciphertext` and returns the
+    /// plaintext
+    fn decrypt(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>>;
+}
+
+pub(crate) fn new_cipher(cipher_suite: CipherSuite, key: &[u8]) -> Result<Box<dyn Cipher + Send>> {
+    match cipher_suite {
+        CipherSuite::AesCtr128HmacSha256_80
+        | CipherSuite::AesCtr128HmacSha256_64
+        | CipherSuite::AesCtr128HmacSha256_32 => Ok(Box::new(
+            cipher_aes_ctr_hmac_sha256::CipherAesCtrHmacSha256::new(cipher_suite, key)?,
+        )),
+        CipherSuite::AesGcm128Sha256 | CipherSuite::AesGcm256Sha512 => Ok(Box::new(
+            cipher_aead_aes_gcm::CipherAeadAesGcm::new(cipher_suite, key)?,
+        )),
+    }
+}
@@ -0,0 +1,76 @@
+use std::fmt;
+
+use crate::error::{Error, Result};
+
+/// CipherSuite is the set of algorithms used to encrypt SFrame frames
+///
+/// <https://www.rfc-editor.org/rfc/rfc9605#section-4.5>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CipherSuite {
+    AesCtr128HmacSha256_80 = 0x0001,
+    AesCtr128HmacSha256_64 = 0x0002,
+    AesCtr128HmacSha256_32 = 0x0003,
+    AesGcm128Sha256 = 0x0004,
+    AesGcm256Sha512 = 0x0005,
+}
+
+impl CipherSuite {
+    /// Identifier of the cipher suite in the IANA registry
+    pub fn id(&self) -> u16 {
+        *self as u16
+    }
+
+    /// Size in bytes of the key for the AEAD algorithm, Nk
+    pub fn key_len(&self) -> usize {
+        match self {
+            CipherSuite::AesCtr128HmacSha256_80
+            | CipherSuite::AesCtr128HmacSha256_64
+            | CipherSuite::AesCtr128HmacSha256_32 => 48,
+            CipherSuite::AesGcm128Sha256 => 16,
+            CipherSuite::AesGcm256Sha512 => 32,
+        }
+    }
+
+    /// Size in bytes of the nonce for the AEAD algorithm, Nn
+    pub fn nonce_len(&self) -> usize {
+        12
+    }
+
+    /// Size in bytes of the authentication tag for the AEAD algorithm, Nt
+    pub fn tag_len(&self) -> usize {
+        match self {
+            CipherSuite::AesCtr128HmacSha256_80 => 10,
+            CipherSuite::AesCtr128HmacSha256_64 => 8,
+            CipherSuite::AesCtr128HmacSha256_32 => 4,
+            CipherSuite::AesGcm128Sha256 | CipherSuite::AesGcm256Sha512 => 16,
+        }
+    }
+}
+
+impl TryFrom<u16> for CipherSuite {
+    type Error = Error;
+
+    fn try_from(id: u16) -> Result<Self> {
+        match id {
+            0x0001 => Ok(CipherSuite::AesCtr128HmacSha256_80),
+            0x0002 => Ok(CipherSuite::AesCtr128HmacSha256_64),
+            0x0003 => Ok(CipherSuite::AesCtr128HmacSha256_32),
+            0x0004 => Ok(CipherSuite::AesGcm128Sha256),
+            0x0005 => Ok(CipherSuite::AesGcm256Sha512),
+            _ => Err(Error::ErrNoSuchCipherSuite(id)),
+        }
+    }
+}
+
+impl fmt::Display for CipherSuite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            CipherSuite::AesCtr128HmacSha256_80 => "AES_128_CTR_HMAC_SHA256_80",
+            CipherSuite::AesCtr128HmacSha256_64 => "AES_128_CTR_HMAC_SHA256_64",
+            CipherSuite::AesCtr128HmacSha256_32 => "AES_128_CTR_HMAC_SHA256_32",
+            CipherSuite::AesGcm128Sha256 => "AES_128_GCM_SHA256_128",
+            CipherSuite::AesGcm256Sha512 => "AES_256_GCM_SHA512_128",
+        };
+        write!(f, "{s}")
+    }
+}
@@ -0,0 +1,268 @@
+use super::*;
+
+const CIPHER_SUITES: [CipherSuite; 5] = [
+    CipherSuite::AesCtr128HmacSha256_80,
+    CipherSuite::AesCtr128HmacSha256_64,
+    CipherSuite::AesCtr128HmacSha256_32,
+    CipherSuite::AesGcm128Sha256,
+    CipherSuite::AesGcm256Sha512,
+];
+
+const BASE_KEY: &[u8] = b"sframe base key";
+const METADATA: &[u8] = &[0xDE, 0xAD];
+const PLAINTEXT: &[u8] = b"encoded video frame";
+
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn new_context(cipher_suite: CipherSuite, kid: u64) -> Result<Context> {
+    let mut c = Context::new(cipher_suite);
+    c.add_key(kid, BASE_KEY)?;
+    c.set_encryption_kid(kid)?;
+    Ok(c)
+}
+
+#[test]
+fn test_context_round_trip() -> Result<()> {
+    for cipher_suite in CIPHER_SUITES {
+        let mut sender = new_context(cipher_suite, 3)?;
+        let receiver = new_context(cipher_suite, 3)?;
+
+        for ctr in 0..3 {
+            let frame = sender.encrypt(METADATA, PLAINTEXT)?;
+            assert_eq!(
+                frame.len(),
+                1 + PLAINTEXT.len() + cipher_suite.tag_len(),
+                "{cipher_suite}"
+            );
+
+            let header = Header::unmarshal(&mut frame.as_ref())?;
+            assert_eq!(header, Header::new(3, ctr), "{cipher_suite}");
+
+            let decrypted = receiver.decrypt(METADATA, &frame)?;
+            assert_eq!(decrypted, Bytes::from_static(PLAINTEXT), "{cipher_suite}");
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_context_nonce_changes_per_frame() -> Result<()> {
+    for cipher_suite in CIPHER_SUITES {
+        let mut c = new_context(cipher_suite, 1)?;
+        let first = c.encrypt(&[], PLAINTEXT)?;
+        let second = c.encrypt(&[], PLAINTEXT)?;
+        assert_ne!(first[1..], second[1..], "{cipher_suite}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_context_tampering() -> Result<()> {
+    for cipher_suite in CIPHER_SUITES {
+        let mut c = new_context(cipher_suite, 1000)?;
+        let frame = c.encrypt(METADATA, PLAINTEXT)?;
+
+        // Both the ciphertext and the tag are authenticated
+        for i in [frame.len() - 1, frame.len() / 2, 3] {
+            let mut tampered = frame.to_vec();
+            tampered[i] ^= 0x01;
+            assert_eq!(
+                c.decrypt(METADATA, &tampered),
+                Err(Error::ErrFailedToVerifyAuthTag),
+                "{cipher_suite} byte {i}"
+            );
+        }
+
+        assert_eq!(
+            c.decrypt(&[0xDE, 0xAE], &frame),
+            Err(Error::ErrFailedToVerifyAuthTag),
+            "{cipher_suite}"
+        );
+        assert_eq!(
+            c.decrypt(METADATA, &frame[..3]),
+            Err(Error::ErrFailedToVerifyAuthTag),
+            "{cipher_suite}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_context_keys() -> Result<()> {
+    let cipher_suite = CipherSuite::AesGcm128Sha256;
+    let mut c = Context::new(cipher_suite);
+    assert_eq!(c.cipher_suite(), cipher_suite);
+
+    assert_eq!(c.add_key(1, &[]), Err(Error::ErrEmptyBaseKey));
+    assert_eq!(c.set_encryption_kid(1), Err(Error::ErrUnknownKid(1)));
+    assert_eq!(
+        c.encrypt(&[], PLAINTEXT).unwrap_err(),
+        Error::ErrNoEncryptionKey
+    );
+
+    c.add_key(1, BASE_KEY)?;
+    assert!(c.has_key(1));
+    c.set_encryption_kid(1)?;
+    assert_eq!(c.encryption_kid(), Some(1));
+    let frame = c.encrypt(&[], PLAINTEXT)?;
+
+    // A receiver without the KID cannot decrypt
+    let receiver = new_context(cipher_suite, 2)?;
+    assert_eq!(receiver.decrypt(&[], &frame), Err(Error::ErrUnknownKid(1)));
+
+    // Same KID with another base key
+    let mut receiver = Context::new(cipher_suite);
+    receiver.add_key(1, b"another base key")?;
+    assert_eq!(
+        receiver.decrypt(&[], &frame),
+        Err(Error::ErrFailedToVerifyAuthTag)
+    );
+
+    assert!(c.remove_key(1));
+    assert!(!c.remove_key(1));
+    assert_eq!(c.encryption_kid(), None);
+    assert_eq!(
+        c.encrypt(&[], PLAINTEXT).unwrap_err(),
+        Error::ErrNoEncryptionKey
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_context_key_rotation() -> Result<()> {
+    let cipher_suite = CipherSuite::AesCtr128HmacSha256_80;
+    let mut sender = new_context(cipher_suite, 1)?;
+    let mut receiver = new_context(cipher_suite, 1)?;
+
+    let old_frame = sender.encrypt(&[], PLAINTEXT)?;
+
+    sender.add_key(2, b"next base key")?;
+    receiver.add_key(2, b"next base key")?;
+    sender.set_encryption_kid(2)?;
+    let new_frame = sender.encrypt(&[], PLAINTEXT)?;
+    assert_eq!(
+        Header::unmarshal(&mut new_frame.as_ref())?,
+        Header::new(2, 0)
+    );
+
+    // Frames in flight with the old key are still decrypted until it is removed
+    assert_eq!(receiver.decrypt(&[], &old_frame)?, PLAINTEXT);
+    assert_eq!(receiver.decrypt(&[], &new_frame)?, PLAINTEXT);
+    receiver.remove_key(1);
+    assert_eq!(
+        receiver.decrypt(&[], &old_frame),
+        Err(Error::ErrUnknownKid(1))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_context_add_key_again() -> Result<()> {
+    let cipher_suite = CipherSuite::AesCtr128HmacSha256_80;
+    let mut c = new_context(cipher_suite, 1)?;
+    c.encrypt(&[], PLAINTEXT)?;
+    c.encrypt(&[], PLAINTEXT)?;
+
+    // Adding the key again must not restart the counter and reuse a nonce
+    c.add_key(1, BASE_KEY)?;
+    let frame = c.encrypt(&[], PLAINTEXT)?;
+    assert_eq!(Header::unmarshal(&mut frame.as_ref())?, Header::new(1, 2));
+    assert_eq!(c.decrypt(&[], &frame)?, PLAINTEXT);
+
+    Ok(())
+}
+
+#[test]
+fn test_context_counter_exhausted() -> Result<()> {
+    let mut c = new_context(CipherSuite::AesGcm128Sha256, 1)?;
+    c.keys.get_mut(&1).unwrap().counter = u64::MAX - 1;
+
+    let frame = c.encrypt(&[], PLAINTEXT)?;
+    assert_eq!(
+        Header::unmarshal(&mut frame.as_ref())?,
+        Header::new(1, u64::MAX - 1)
+    );
+    assert_eq!(c.decrypt(&[], &frame)?, PLAINTEXT);
+    assert_eq!(
+        c.encrypt(&[], PLAINTEXT).unwrap_err(),
+        Error::ErrCounterExhausted(1)
+    );
+
+    Ok(())
+}
+
+/// Encryption of RFC 9605 Appendix C.3: key and salt derivation, then the encrypted frame,
+/// for every cipher suite.
+#[test]
+fn test_context_rfc9605_vectors() -> Result<()> {
+    const KID: u64 = 0x123;
+    const CTR: u64 = 0x4567;
+    let base_key = hex("000102030405060708090a0b0c0d0e0f");
+    let metadata = b"IETF SFrame WG";
+    let plaintext = b"draft-ietf-sframe-enc";
+
+    let tests = vec![
+        (
+            CipherSuite::AesCtr128HmacSha256_80,
+            "3f7d9a7c83ae8e1c8a11ae695ab59314b367e359fadac7b9c46b2bc6f81f46e16b96f0811868d59402b7e870102720b3",
+            "50b29329a04dc0f184ac3168",
+            "9901234567449408b6f490086165b9d6f62b24ae1a59a56486b4ae8ed036b88912e24f11",
+        ),
+        (
+            CipherSuite::AesCtr128HmacSha256_64,
+            "e2ec5c797540310483b16bf6e7a570d2a27d192fe869c7ccd8584a8d9dab91549fbe553f5113461ec6aa83bf3865553e",
+            "e68ac8dd3d02fbcd368c5577",
+            "99012345673f31438db4d09434e43afa0f8a2f00867a2be085046a9f5cb4f101d607",
+        ),
+        (
+            CipherSuite::AesCtr128HmacSha256_32,
+            "2c5703089cbb8c583475e4fc461d97d18809df79b6d550f78eb6d50ffa80d89211d57909934f46f5405e38cd583c69fe",
+            "38c16e4f5159700c00c7f350",
+            "990123456717fc8af28a5a695afcfc6c8df6358a17e26b2fcb3bae32e443",
+        ),
+        (
+            CipherSuite::AesGcm128Sha256,
+            "d34f547f4ca4f9a7447006fe7fcbf768",
+            "75234edefe07819026751816",
+            "9901234567b7412c2513a1b66dbb48841bbaf17f598751176ad847681a69c6d0b091c07018ce4adb34eb",
+        ),
+        (
+            CipherSuite::AesGcm256Sha512,
+            "d3e27b0d4a5ae9e55df01a70e6d4d28d969b246e2936f4b7a5d9b494da6b9633",
+            "84991c167b8cd23c93708ec7",
+            "990123456794f509d36e9beacb0e261d99c7d1e972f1fed787d4049f17ca21353c1cc24d56ceabced279",
+        ),
+    ];
+
+    for (cipher_suite, key, salt, ciphertext) in tests {
+        let (derived_key, derived_salt) = derive_key_salt(cipher_suite, KID, &base_key)?;
+        assert_eq!(derived_key, hex(key), "{cipher_suite}");
+        assert_eq!(derived_salt, hex(salt), "{cipher_suite}");
+
+        let mut c = new_context(cipher_suite, KID)?;
+        c.add_key(KID, &base_key)?;
+        c.keys.get_mut(&KID).unwrap().counter = CTR;
+        let frame = c.encrypt(metadata, plaintext)?;
+        assert_eq!(frame, hex(ciphertext), "{cipher_suite}");
+
+        let mut receiver = Context::new(cipher_suite);
+        receiver.add_key(KID, &base_key)?;
+        assert_eq!(
+            receiver.decrypt(metadata, &hex(ciphertext))?,
+            &plaintext[..],
+            "{cipher_suite}"
+        );
+    }
+
+    Ok(())
+}
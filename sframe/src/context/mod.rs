@@ -0,0 +1,161 @@
+#[cfg(test)]
+mod context_test;
+
+use std::collections::HashMap;
+
+use bytes::{Bytes, BytesMut};
+use util::marshal::{Marshal, MarshalSize, Unmarshal};
+
+use crate::cipher::*;
+use crate::cipher_suite::CipherSuite;
+use crate::error::{Error, Result};
+use crate::header::Header;
+use crate::key_derivation::*;
+
+/// Key and salt derived from the base key of a KID, together with the counter of the next
+/// frame encrypted with it
+struct KeyContext {
+    salt: Vec<u8>,
+    cipher: Box<dyn Cipher + Send>,
+    counter: u64,
+}
+
+/// Context represents a SFrame encryption/decryption context. It holds the keys of a sender
+/// or receiver indexed by their Key ID (KID).
+///
+/// The same context can both encrypt and decrypt. On the send side, one of the keys is
+/// selected with `set_encryption_kid`; on the receive side, the KID found in the header of
+/// each frame selects the key. Keys can be rotated by adding the new key, switching the
+/// encryption KID, and removing the old key once it is no longer in use.
+///
+/// <https://www.rfc-editor.org/rfc/rfc9605#section-4.4>
+pub struct Context {
+    cipher_suite: CipherSuite,
+    keys: HashMap<u64, KeyContext>,
+    encryption_kid: Option<u64>,
+}
+
+impl Context {
+    /// Creates a new context without any key
+    pub fn new(cipher_suite: CipherSuite) -> Self {
+        Context {
+            cipher_suite,
+            keys: HashMap::new(),
+            encryption_kid: None,
+        }
+    }
+
+    /// Returns the cipher suite of the context
+    pub fn cipher_suite(&self) -> CipherSuite {
+        self.cipher_suite
+    }
+
+    /// Adds the base key for a KID, replacing any key previously added with the same KID.
+    /// Replacing a key keeps its counter, so the same base key never reuses a nonce.
+    pub fn add_key(&mut self, kid: u64, base_key: &[u8]) -> Result<()> {
+        if base_key.is_empty() {
+            return Err(Error::ErrEmptyBaseKey);
+        }
+
+        let (key, salt) = derive_key_salt(self.cipher_suite, kid, base_key)?;
+        let cipher = new_cipher(self.cipher_suite, &key)?;
+        let counter = self.keys.get(&kid).map_or(0, |key| key.counter);
+        self.keys.insert(
+            kid,
+            KeyContext {
+                salt,
+                cipher,
+                counter,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Removes the key of a KID. If the key is selected for encryption, frames can no longer
+    /// be encrypted until another key is selected. The counter of the KID is lost, so a
+    /// removed KID must not be added again with the same base key.
+    pub fn remove_key(&mut self, kid: u64) -> bool {
+        if self.encryption_kid == Some(kid) {
+            self.encryption_kid = None;
+        }
+        self.keys.remove(&kid).is_some()
+    }
+
+    /// Returns true if a key was added for the KID
+    pub fn has_key(&self, kid: u64) -> bool {
+        self.keys.contains_key(&kid)
+    }
+
+    /// Selects the key used by `encrypt`
+    pub fn set_encryption_kid(&mut self, kid: u64) -> Result<()> {
+        if !self.keys.contains_key(&kid) {
+            return Err(Error::ErrUnknownKid(kid));
+        }
+        self.encryption_kid = Some(kid);
+        Ok(())
+    }
+
+    /// Returns the KID of the key used by `encrypt`
+    pub fn encryption_kid(&self) -> Option<u64> {
+        self.encryption_kid
+    }
+
+    /// Encrypts a frame with the selected key and returns the SFrame header followed by the
+    /// ciphertext. `metadata` is authenticated but not included in the output; the receiver
+    /// has to pass the same metadata to `decrypt`.
+    pub fn encrypt(&mut self, metadata: &[u8], plaintext: &[u8]) -> Result<Bytes> {
+        let kid = self.encryption_kid.ok_or(Error::ErrNoEncryptionKey)?;
+        let key = self.keys.get_mut(&kid).ok_or(Error::ErrUnknownKid(kid))?;
+        if key.counter == u64::MAX {
+            return Err(Error::ErrCounterExhausted(kid));
+        }
+        let ctr = key.counter;
+        key.counter += 1;
+
+        let header = Header::new(kid, ctr);
+        let header_len = header.marshal_size();
+        let mut header_buf = BytesMut::with_capacity(header_len);
+        header_buf.resize(header_len, 0);
+        header.marshal_to(&mut header_buf)?;
+
+        let nonce = nonce(&key.salt, ctr);
+        let aad = [header_buf.as_ref(), metadata].concat();
+        let ciphertext = key.cipher.encrypt(&nonce, &aad, plaintext)?;
+
+        let mut frame = BytesMut::with_capacity(header_len + ciphertext.len());
+        frame.extend_from_slice(&header_buf);
+        frame.extend_from_slice(&ciphertext);
+
+        Ok(frame.freeze())
+    }
+
+    /// Decrypts a SFrame ciphertext with the key selected by the KID in its header.
+    /// `metadata` must be the metadata passed to `encrypt`.
+    pub fn decrypt(&self, metadata: &[u8], frame: &[u8]) -> Result<Bytes> {
+        let mut reader = frame;
+        let header = Header::unmarshal(&mut reader)?;
+        let header_len = frame.len() - reader.len();
+
+        let key = self
+            .keys
+            .get(&header.kid)
+            .ok_or(Error::ErrUnknownKid(header.kid))?;
+
+        let nonce = nonce(&key.salt, header.ctr);
+        let aad = [&frame[..header_len], metadata].concat();
+        let plaintext = key.cipher.decrypt(&nonce, &aad, reader)?;
+
+        Ok(Bytes::from(plaintext))
+    }
+}
+
+/// nonce XORs the counter, encoded big-endian on the size of the nonce, with the salt
+fn nonce(salt: &[u8], ctr: u64) -> Vec<u8> {
+    let mut nonce = salt.to_vec();
+    let offset = nonce.len() - 8;
+    for (n, c) in nonce[offset..].iter_mut().zip(ctr.to_be_bytes()) {
+        *n ^= c;
+    }
+    nonce
+}
@@ -0,0 +1,56 @@
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Error, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("buffer too short for SFrame header")]
+    ErrHeaderTooShort,
+    #[error("buffer too small")]
+    ErrBufferTooSmall,
+    #[error("no such SFrame cipher suite {0:#06x}")]
+    ErrNoSuchCipherSuite(u16),
+    #[error("SFrame base key must not be empty")]
+    ErrEmptyBaseKey,
+    #[error("no SFrame key with KID {0}")]
+    ErrUnknownKid(u64),
+    #[error("no SFrame key selected for encryption")]
+    ErrNoEncryptionKey,
+    #[error("SFrame counter exhausted for KID {0}")]
+    ErrCounterExhausted(u64),
+    #[error("failed to verify auth tag")]
+    ErrFailedToVerifyAuthTag,
+    #[error("invalid HKDF output length")]
+    ErrInvalidHkdfLength,
+
+    #[error("aes gcm: {0}")]
+    AesGcm(#[from] aes_gcm::Error),
+    #[error("{0}")]
+    Util(#[from] util::Error),
+
+    #[error("{0}")]
+    Other(String),
+}
+
+impl From<hkdf::InvalidLength> for Error {
+    fn from(_: hkdf::InvalidLength) -> Self {
+        Error::ErrInvalidHkdfLength
+    }
+}
+
+impl From<Error> for util::Error {
+    fn from(e: Error) -> Self {
+        util::Error::from_std(e)
+    }
+}
+
+impl PartialEq<util::Error> for Error {
+    fn eq(&self, other: &util::Error) -> bool {
+        if let Some(down) = other.downcast_ref::<Error>() {
+            self == down
+        } else {
+            false
+        }
+    }
+}
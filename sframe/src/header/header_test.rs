@@ -0,0 +1,99 @@
+use bytes::{Bytes, BytesMut};
+
+use super::*;
+use crate::error::Result;
+
+#[test]
+fn test_header_round_trip() -> Result<()> {
+    let tests = vec![
+        (Header::new(0, 0), vec![0x00]),
+        (Header::new(7, 1), vec![0x71]),
+        (Header::new(8, 7), vec![0x87, 0x08]),
+        (Header::new(1, 0x1234), vec![0x19, 0x12, 0x34]),
+        (
+            Header::new(0xFFFF, 0x0100_0000),
+            vec![0x9B, 0xFF, 0xFF, 0x01, 0x00, 0x00, 0x00],
+        ),
+        (
+            Header::new(u64::MAX, u64::MAX),
+            vec![
+                0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+                0xFF, 0xFF, 0xFF,
+            ],
+        ),
+    ];
+
+    for (header, raw) in tests {
+        assert_eq!(header.marshal_size(), raw.len(), "{header:?}");
+        assert!(header.marshal_size() <= MAX_HEADER_LEN);
+
+        let mut buf = BytesMut::with_capacity(header.marshal_size());
+        buf.resize(header.marshal_size(), 0);
+        header.marshal_to(&mut buf)?;
+        assert_eq!(buf.freeze(), Bytes::from(raw.clone()), "{header:?}");
+
+        // Trailing ciphertext is left in the buffer
+        let mut frame = Bytes::from([raw.as_slice(), &[0xAA, 0xBB]].concat());
+        assert_eq!(Header::unmarshal(&mut frame)?, header);
+        assert_eq!(frame, Bytes::from_static(&[0xAA, 0xBB]));
+    }
+
+    Ok(())
+}
+
+/// Header encodings of RFC 9605 Appendix C.1
+#[test]
+fn test_header_rfc9605_vectors() -> Result<()> {
+    let tests: Vec<(u64, u64, &[u8])> = vec![
+        (0, 0, &[0x00]),
+        (0, 7, &[0x07]),
+        (7, 0, &[0x70]),
+        (0, 8, &[0x08, 0x08]),
+        (8, 0, &[0x80, 0x08]),
+        (0xFF, 0xFF, &[0x88, 0xFF, 0xFF]),
+        (0x100, 0x100, &[0x99, 0x01, 0x00, 0x01, 0x00]),
+        (0x123, 0x4567, &[0x99, 0x01, 0x23, 0x45, 0x67]),
+        (0xFFFF, 0x10000, &[0x9A, 0xFF, 0xFF, 0x01, 0x00, 0x00]),
+        (0x10000, 0xFFFF, &[0xA9, 0x01, 0x00, 0x00, 0xFF, 0xFF]),
+        (
+            0,
+            u64::MAX,
+            &[0x0F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+        ),
+        (
+            u64::MAX,
+            0,
+            &[0xF0, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+        ),
+    ];
+
+    for (kid, ctr, raw) in tests {
+        let header = Header::new(kid, ctr);
+        let mut buf = BytesMut::with_capacity(header.marshal_size());
+        buf.resize(header.marshal_size(), 0);
+        header.marshal_to(&mut buf)?;
+        assert_eq!(&buf[..], raw, "kid {kid:#x} ctr {ctr:#x}");
+
+        assert_eq!(Header::unmarshal(&mut &raw[..])?, header);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_header_too_short() {
+    for raw in [
+        &[][..],
+        &[0x80],
+        &[0x09, 0x12],
+        &[0xF8, 0, 0, 0, 0, 0, 0, 0],
+    ] {
+        let mut buf = raw;
+        let result = Header::unmarshal(&mut buf);
+        assert!(result.is_err(), "{raw:?}");
+        assert_eq!(Error::ErrHeaderTooShort, result.unwrap_err());
+    }
+
+    let mut buf = [0u8; 2];
+    assert!(Header::new(0x1234, 0).marshal_to(&mut buf).is_err());
+}
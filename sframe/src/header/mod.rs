@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod header_test;
+
+use bytes::{Buf, BufMut};
+use util::marshal::{Marshal, MarshalSize, Unmarshal};
+
+use crate::error::Error;
+
+/// Maximum size of a SFrame header: the config byte, and 8 bytes each for KID and CTR
+pub const MAX_HEADER_LEN: usize = 17;
+
+const EXTENDED_FLAG: u8 = 0x08;
+const VALUE_MASK: u8 = 0x07;
+
+/// Header is the SFrame header preceding every encrypted frame. It is sent in the clear so
+/// that the receiver can select the key, and media servers can inspect it without decrypting.
+///
+/// <https://www.rfc-editor.org/rfc/rfc9605#section-4.3>
+///
+/// ```text
+///  0 1 2 3 4 5 6 7
+/// +-+-+-+-+-+-+-+-+---------------------------+---------------------------+
+/// |X|  K  |Y|  C  |   Key ID (if X=1)         |  Counter (if Y=1)         |
+/// +-+-+-+-+-+-+-+-+---------------------------+---------------------------+
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Header {
+    /// Key ID, selecting the key used to encrypt the frame
+    pub kid: u64,
+    /// Counter, used to build the nonce of the frame
+    pub ctr: u64,
+}
+
+impl Header {
+    pub fn new(kid: u64, ctr: u64) -> Self {
+        Header { kid, ctr }
+    }
+}
+
+/// value_len returns the number of bytes following the config byte for a KID or CTR value
+fn value_len(value: u64) -> usize {
+    if value <= VALUE_MASK as u64 {
+        0
+    } else {
+        8 - value.leading_zeros() as usize / 8
+    }
+}
+
+/// encode_config returns the 4 bits describing a KID or CTR value in the config byte
+fn encode_config(value: u64) -> u8 {
+    match value_len(value) {
+        0 => value as u8,
+        len => EXTENDED_FLAG | (len - 1) as u8,
+    }
+}
+
+impl MarshalSize for Header {
+    fn marshal_size(&self) -> usize {
+        1 + value_len(self.kid) + value_len(self.ctr)
+    }
+}
+
+impl Marshal for Header {
+    fn marshal_to(&self, mut buf: &mut [u8]) -> Result<usize, util::Error> {
+        let size = self.marshal_size();
+        if buf.remaining_mut() < size {
+            return Err(Error::ErrBufferTooSmall.into());
+        }
+
+        buf.put_u8(encode_config(self.kid) << 4 | encode_config(self.ctr));
+        for value in [self.kid, self.ctr] {
+            let len = value_len(value);
+            buf.put_slice(&value.to_be_bytes()[8 - len..]);
+        }
+
+        Ok(size)
+    }
+}
+
+impl Unmarshal for Header {
+    fn unmarshal<B>(buf: &mut B) -> Result<Self, util::Error>
+    where
+        Self: Sized,
+        B: Buf,
+    {
+        if buf.remaining() < 1 {
+            return Err(Error::ErrHeaderTooShort.into());
+        }
+
+        let config = buf.get_u8();
+        let mut values = [0u64; 2];
+        for (value, config) in values.iter_mut().zip([config >> 4, config & 0x0F]) {
+            *value = if config & EXTENDED_FLAG == 0 {
+                (config & VALUE_MASK) as u64
+            } else {
+                let len = (config & VALUE_MASK) as usize + 1;
+                if buf.remaining() < len {
+                    return Err(Error::ErrHeaderTooShort.into());
+                }
+                buf.get_uint(len)
+            };
+        }
+
+        Ok(Header {
+            kid: values[0],
+            ctr: values[1],
+        })
+    }
+}
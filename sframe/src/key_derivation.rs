@@ -0,0 +1,46 @@
+use hkdf::Hkdf;
+use sha2::{Sha256, Sha512};
+
+use crate::cipher_suite::CipherSuite;
+use crate::error::Result;
+
+const SFRAME_KEY_LABEL: &[u8] = b"SFrame 1.0 Secret key ";
+const SFRAME_SALT_LABEL: &[u8] = b"SFrame 1.0 Secret salt ";
+
+/// derive_key_salt derives the key and salt used to encrypt frames with the given KID from the
+/// base key of that KID.
+///
+/// <https://www.rfc-editor.org/rfc/rfc9605#section-4.4.2>
+pub(crate) fn derive_key_salt(
+    cipher_suite: CipherSuite,
+    kid: u64,
+    base_key: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    let mut key = vec![0u8; cipher_suite.key_len()];
+    let mut salt = vec![0u8; cipher_suite.nonce_len()];
+
+    let key_label = label(SFRAME_KEY_LABEL, cipher_suite, kid);
+    let salt_label = label(SFRAME_SALT_LABEL, cipher_suite, kid);
+
+    match cipher_suite {
+        CipherSuite::AesGcm256Sha512 => {
+            let hkdf = Hkdf::<Sha512>::new(Some(&[]), base_key);
+            hkdf.expand(&key_label, &mut key)?;
+            hkdf.expand(&salt_label, &mut salt)?;
+        }
+        _ => {
+            let hkdf = Hkdf::<Sha256>::new(Some(&[]), base_key);
+            hkdf.expand(&key_label, &mut key)?;
+            hkdf.expand(&salt_label, &mut salt)?;
+        }
+    }
+
+    Ok((key, salt))
+}
+
+fn label(prefix: &[u8], cipher_suite: CipherSuite, kid: u64) -> Vec<u8> {
+    let mut label = prefix.to_vec();
+    label.extend_from_slice(&kid.to_be_bytes());
+    label.extend_from_slice(&cipher_suite.id().to_be_bytes());
+    label
+}
@@ -0,0 +1,11 @@
+#![warn(rust_2018_idioms)]
+#![allow(dead_code)]
+
+mod cipher;
+pub mod cipher_suite;
+pub mod context;
+mod error;
+pub mod header;
+mod key_derivation;
+
+pub use error::Error;
@@ -150,12 +150,13 @@ impl CipherAeadAesGcm {
         profile: ProtectionProfile,
         master_key: &[u8],
         master_salt: &[u8],
+        index_over_kdr: usize,
     ) -> Result<CipherAeadAesGcm> {
         let srtp_session_key = aes_cm_key_derivation(
             LABEL_SRTP_ENCRYPTION,
             master_key,
             master_salt,
-            0,
+            index_over_kdr,
             master_key.len(),
         )?;
 
@@ -167,7 +168,7 @@ impl CipherAeadAesGcm {
             LABEL_SRTCP_ENCRYPTION,
             master_key,
             master_salt,
-            0,
+            index_over_kdr,
             master_key.len(),
         )?;
 
@@ -179,7 +180,7 @@ impl CipherAeadAesGcm {
             LABEL_SRTP_SALT,
             master_key,
             master_salt,
-            0,
+            index_over_kdr,
             master_salt.len(),
         )?;
 
@@ -187,7 +188,7 @@ impl CipherAeadAesGcm {
             LABEL_SRTCP_SALT,
             master_key,
             master_salt,
-            0,
+            index_over_kdr,
             master_salt.len(),
         )?;
 
@@ -19,21 +19,26 @@ pub(crate) struct CipherAesCmHmacSha1 {
 }
 
 impl CipherAesCmHmacSha1 {
-    pub fn new(profile: ProtectionProfile, master_key: &[u8], master_salt: &[u8]) -> Result<Self> {
-        let inner = CipherInner::new(profile, master_key, master_salt)?;
+    pub fn new(
+        profile: ProtectionProfile,
+        master_key: &[u8],
+        master_salt: &[u8],
+        index_over_kdr: usize,
+    ) -> Result<Self> {
+        let inner = CipherInner::new(profile, master_key, master_salt, index_over_kdr)?;
 
         let srtp_session_key = aes_cm_key_derivation(
             LABEL_SRTP_ENCRYPTION,
             master_key,
             master_salt,
-            0,
+            index_over_kdr,
             master_key.len(),
         )?;
         let srtcp_session_key = aes_cm_key_derivation(
             LABEL_SRTCP_ENCRYPTION,
             master_key,
             master_salt,
-            0,
+            index_over_kdr,
             master_key.len(),
         )?;
 
@@ -32,19 +32,24 @@ pub(crate) struct CipherInner {
 }
 
 impl CipherInner {
-    pub fn new(profile: ProtectionProfile, master_key: &[u8], master_salt: &[u8]) -> Result<Self> {
+    pub fn new(
+        profile: ProtectionProfile,
+        master_key: &[u8],
+        master_salt: &[u8],
+        index_over_kdr: usize,
+    ) -> Result<Self> {
         let srtp_session_salt = aes_cm_key_derivation(
             LABEL_SRTP_SALT,
             master_key,
             master_salt,
-            0,
+            index_over_kdr,
             master_salt.len(),
         )?;
         let srtcp_session_salt = aes_cm_key_derivation(
             LABEL_SRTCP_SALT,
             master_key,
             master_salt,
-            0,
+            index_over_kdr,
             master_salt.len(),
         )?;
 
@@ -54,14 +59,14 @@ impl CipherInner {
             LABEL_SRTP_AUTHENTICATION_TAG,
             master_key,
             master_salt,
-            0,
+            index_over_kdr,
             auth_key_len,
         )?;
         let srtcp_session_auth_tag = aes_cm_key_derivation(
             LABEL_SRTCP_AUTHENTICATION_TAG,
             master_key,
             master_salt,
-            0,
+            index_over_kdr,
             auth_key_len,
         )?;
 
@@ -13,6 +13,12 @@ pub struct SessionKeys {
     pub local_master_salt: Vec<u8>,
     pub remote_master_key: Vec<u8>,
     pub remote_master_salt: Vec<u8>,
+    /// Master Key Identifiers carried in outgoing/incoming packets, empty if no MKI is used.
+    pub local_mki: Vec<u8>,
+    pub remote_mki: Vec<u8>,
+    /// Key derivation rate of both directions, if it is not 0 the session keys are derived
+    /// again every `kdr` packets. It must be 0 or a power of 2 up to 2^24.
+    pub kdr: u64,
 }
 
 /// Config is used to configure a session.
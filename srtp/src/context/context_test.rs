@@ -1,5 +1,6 @@
 use bytes::Bytes;
 use lazy_static::lazy_static;
+use util::marshal::Marshal;
 
 use super::*;
 use crate::key_derivation::*;
@@ -385,3 +386,183 @@ fn test_decrypt_rtcp() {
 
     assert_eq!(gotten_decrypted_rtcp_packet, *DECRYPTED_RTCP_PACKET)
 }
+
+fn master_key(profile: ProtectionProfile, seed: u8, mki: &[u8]) -> MasterKey {
+    MasterKey {
+        key: vec![seed; profile.key_len()],
+        salt: vec![seed.wrapping_add(1); profile.salt_len()],
+        mki: mki.to_vec(),
+    }
+}
+
+fn rtp_packet(sequence_number: u16) -> Bytes {
+    let pkt = rtp::packet::Packet {
+        header: rtp::header::Header {
+            version: 2,
+            sequence_number,
+            ssrc: 0xcafebabe,
+            ..Default::default()
+        },
+        payload: Bytes::from_static(&[0xab; 16]),
+    };
+    pkt.marshal().expect("Error marshaling rtp packet")
+}
+
+#[test]
+fn test_master_key_validation() {
+    let profile = CIPHER_CONTEXT_ALGO;
+
+    let result = Context::new_with_master_keys(vec![], profile, 0, None, None);
+    assert_eq!(result.err(), Some(Error::ErrNoMasterKey));
+
+    let result = Context::new_with_master_keys(
+        vec![master_key(profile, 1, &[]), master_key(profile, 2, &[])],
+        profile,
+        0,
+        None,
+        None,
+    );
+    assert_eq!(result.err(), Some(Error::ErrMkiRequired));
+
+    let result = Context::new_with_master_keys(
+        vec![
+            master_key(profile, 1, &[1]),
+            master_key(profile, 2, &[0, 2]),
+        ],
+        profile,
+        0,
+        None,
+        None,
+    );
+    assert_eq!(result.err(), Some(Error::MkiLength(1, 2)));
+
+    let result = Context::new_with_master_keys(
+        vec![master_key(profile, 1, &[1]), master_key(profile, 2, &[1])],
+        profile,
+        0,
+        None,
+        None,
+    );
+    assert_eq!(result.err(), Some(Error::ErrDuplicatedMki));
+
+    for kdr in [3, (1 << 24) + 1, 1 << 25] {
+        let result = Context::new_with_master_keys(
+            vec![master_key(profile, 1, &[])],
+            profile,
+            kdr,
+            None,
+            None,
+        );
+        assert_eq!(result.err(), Some(Error::InvalidKdr(kdr)));
+    }
+}
+
+#[test]
+fn test_mki() -> Result<()> {
+    for profile in [
+        ProtectionProfile::Aes128CmHmacSha1_80,
+        ProtectionProfile::Aes128CmHmacSha1_32,
+        ProtectionProfile::AeadAes128Gcm,
+    ] {
+        let key1 = master_key(profile, 1, &[0, 0, 0, 1]);
+        let key2 = master_key(profile, 2, &[0, 0, 0, 2]);
+
+        let mut encrypt_context =
+            Context::new_with_master_keys(vec![key1.clone()], profile, 0, None, None)?;
+        encrypt_context.add_master_key(key2.clone())?;
+        let mut decrypt_context =
+            Context::new_with_master_keys(vec![key2, key1], profile, 0, None, None)?;
+
+        // the MKI is placed right before the authentication tag
+        let encrypted = encrypt_context.encrypt_rtp(&rtp_packet(1))?;
+        let mki_pos = encrypted.len() - profile.rtp_auth_tag_len() - 4;
+        assert_eq!(&encrypted[mki_pos..mki_pos + 4], &[0, 0, 0, 1]);
+        assert_eq!(decrypt_context.decrypt_rtp(&encrypted)?, rtp_packet(1));
+
+        let encrypted = encrypt_context.encrypt_rtcp(&DECRYPTED_RTCP_PACKET)?;
+        let mki_pos = encrypted.len() - profile.rtcp_auth_tag_len() - 4;
+        assert_eq!(&encrypted[mki_pos..mki_pos + 4], &[0, 0, 0, 1]);
+        assert_eq!(
+            decrypt_context.decrypt_rtcp(&encrypted)?,
+            *DECRYPTED_RTCP_PACKET
+        );
+
+        // switching the key is picked up by the receiver through the MKI
+        encrypt_context.set_active_master_key(&[0, 0, 0, 2])?;
+        let encrypted = encrypt_context.encrypt_rtp(&rtp_packet(2))?;
+        let mki_pos = encrypted.len() - profile.rtp_auth_tag_len() - 4;
+        assert_eq!(&encrypted[mki_pos..mki_pos + 4], &[0, 0, 0, 2]);
+        assert_eq!(decrypt_context.decrypt_rtp(&encrypted)?, rtp_packet(2));
+
+        let encrypted = encrypt_context.encrypt_rtcp(&DECRYPTED_RTCP_PACKET)?;
+        assert_eq!(
+            decrypt_context.decrypt_rtcp(&encrypted)?,
+            *DECRYPTED_RTCP_PACKET
+        );
+
+        assert_eq!(
+            encrypt_context.set_active_master_key(&[0, 0, 0, 3]),
+            Err(Error::ErrMkiNotFound)
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_mki_unknown() -> Result<()> {
+    let profile = CIPHER_CONTEXT_ALGO;
+    let mut encrypt_context =
+        Context::new_with_master_keys(vec![master_key(profile, 1, &[7])], profile, 0, None, None)?;
+    let mut decrypt_context =
+        Context::new_with_master_keys(vec![master_key(profile, 1, &[8])], profile, 0, None, None)?;
+
+    let encrypted = encrypt_context.encrypt_rtp(&rtp_packet(1))?;
+    assert_eq!(
+        decrypt_context.decrypt_rtp(&encrypted),
+        Err(Error::ErrMkiNotFound)
+    );
+
+    let encrypted = encrypt_context.encrypt_rtcp(&DECRYPTED_RTCP_PACKET)?;
+    assert_eq!(
+        decrypt_context.decrypt_rtcp(&encrypted),
+        Err(Error::ErrMkiNotFound)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_key_derivation_rate() -> Result<()> {
+    for profile in [
+        ProtectionProfile::Aes128CmHmacSha1_80,
+        ProtectionProfile::AeadAes128Gcm,
+    ] {
+        let key = master_key(profile, 1, &[]);
+        let mut encrypt_context =
+            Context::new_with_master_keys(vec![key.clone()], profile, 4, None, None)?;
+        let mut decrypt_context =
+            Context::new_with_master_keys(vec![key.clone()], profile, 4, None, None)?;
+        let mut no_kdr_context = Context::new_with_master_keys(vec![key], profile, 0, None, None)?;
+
+        for sequence_number in 0..12 {
+            let encrypted = encrypt_context.encrypt_rtp(&rtp_packet(sequence_number))?;
+            assert_eq!(
+                decrypt_context.decrypt_rtp(&encrypted)?,
+                rtp_packet(sequence_number)
+            );
+
+            // the session keys are only the same as without rekeying until index DIV kdr changes
+            let result = no_kdr_context.decrypt_rtp(&encrypted);
+            assert_eq!(result.is_ok(), sequence_number < 4, "{sequence_number}");
+
+            let encrypted = encrypt_context.encrypt_rtcp(&DECRYPTED_RTCP_PACKET)?;
+            assert_eq!(
+                decrypt_context.decrypt_rtcp(&encrypted)?,
+                *DECRYPTED_RTCP_PACKET
+            );
+        }
+    }
+
+    Ok(())
+}
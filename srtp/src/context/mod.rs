@@ -5,8 +5,10 @@ mod srtcp_test;
 #[cfg(test)]
 mod srtp_test;
 
+use std::borrow::Cow;
 use std::collections::HashMap;
 
+use bytes::{Bytes, BytesMut};
 use util::replay_detector::*;
 
 use crate::cipher::cipher_aead_aes_gcm::*;
@@ -23,6 +25,33 @@ const MAX_ROC: u32 = u32::MAX;
 const SEQ_NUM_MEDIAN: u16 = 1 << 15;
 const SEQ_NUM_MAX: u16 = u16::MAX;
 
+/// The largest key derivation rate allowed by RFC 3711 Section 4.3.1
+const MAX_KDR: u64 = 1 << 24;
+
+/// MasterKey is a SRTP master key and salt, together with the Master Key Identifier (MKI)
+/// that tags the packets protected with it.
+/// See <https://www.rfc-editor.org/rfc/rfc3711#section-3.1>
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct MasterKey {
+    pub key: Vec<u8>,
+    pub salt: Vec<u8>,
+    /// The MKI of the key, empty if packets are not tagged with an MKI.
+    pub mki: Vec<u8>,
+}
+
+struct ContextKey {
+    master_key: MasterKey,
+    /// Cipher with the session keys derived at index 0, used if the key derivation rate is 0
+    cipher: Box<dyn Cipher + Send>,
+}
+
+/// Cipher with session keys derived for a specific `index DIV kdr`
+struct DerivedCipher {
+    key_index: usize,
+    index_over_kdr: usize,
+    cipher: Box<dyn Cipher + Send>,
+}
+
 /// Encrypt/Decrypt state for a single SRTP SSRC
 #[derive(Default)]
 pub(crate) struct SrtpSsrcState {
@@ -30,6 +59,7 @@ pub(crate) struct SrtpSsrcState {
     index: u64,
     rollover_has_processed: bool,
     replay_detector: Option<Box<dyn ReplayDetector + Send + 'static>>,
+    derived_cipher: Option<DerivedCipher>,
 }
 
 /// Encrypt/Decrypt state for a single SRTCP SSRC
@@ -38,6 +68,7 @@ pub(crate) struct SrtcpSsrcState {
     srtcp_index: usize,
     ssrc: u32,
     replay_detector: Option<Box<dyn ReplayDetector + Send + 'static>>,
+    derived_cipher: Option<DerivedCipher>,
 }
 
 impl SrtpSsrcState {
@@ -90,8 +121,15 @@ impl SrtpSsrcState {
 /// Context represents a SRTP cryptographic context
 /// Context can only be used for one-way operations
 /// it must either used ONLY for encryption or ONLY for decryption
+///
+/// A context can hold several master keys told apart by their MKI. Packets are encrypted with
+/// the active master key and decrypted with the master key matching the MKI they carry.
 pub struct Context {
-    cipher: Box<dyn Cipher + Send>,
+    profile: ProtectionProfile,
+    keys: Vec<ContextKey>,
+    active_key: usize,
+    mki_len: usize,
+    kdr: u64,
 
     srtp_ssrc_states: HashMap<u32, SrtpSsrcState>,
     srtcp_ssrc_states: HashMap<u32, SrtcpSsrcState>,
@@ -100,6 +138,47 @@ pub struct Context {
     new_srtcp_replay_detector: ContextOption,
 }
 
+fn new_cipher(
+    profile: ProtectionProfile,
+    master_key: &MasterKey,
+    index_over_kdr: usize,
+) -> Result<Box<dyn Cipher + Send>> {
+    let cipher: Box<dyn Cipher + Send> = match profile {
+        ProtectionProfile::Aes128CmHmacSha1_32 | ProtectionProfile::Aes128CmHmacSha1_80 => {
+            Box::new(CipherAesCmHmacSha1::new(
+                profile,
+                &master_key.key,
+                &master_key.salt,
+                index_over_kdr,
+            )?)
+        }
+
+        ProtectionProfile::AeadAes128Gcm | ProtectionProfile::AeadAes256Gcm => Box::new(
+            CipherAeadAesGcm::new(profile, &master_key.key, &master_key.salt, index_over_kdr)?,
+        ),
+    };
+    Ok(cipher)
+}
+
+/// Returns the cipher for `index_over_kdr`, deriving new session keys if they changed.
+fn derived_cipher<'a>(
+    slot: &'a mut Option<DerivedCipher>,
+    profile: ProtectionProfile,
+    key_index: usize,
+    master_key: &MasterKey,
+    index_over_kdr: usize,
+) -> Result<&'a mut (dyn Cipher + Send)> {
+    let derived = match slot.take() {
+        Some(d) if d.key_index == key_index && d.index_over_kdr == index_over_kdr => d,
+        _ => DerivedCipher {
+            key_index,
+            index_over_kdr,
+            cipher: new_cipher(profile, master_key, index_over_kdr)?,
+        },
+    };
+    Ok(slot.insert(derived).cipher.as_mut())
+}
+
 impl Context {
     /// CreateContext creates a new SRTP Context
     pub fn new(
@@ -109,23 +188,38 @@ impl Context {
         srtp_ctx_opt: Option<ContextOption>,
         srtcp_ctx_opt: Option<ContextOption>,
     ) -> Result<Context> {
-        let key_len = profile.key_len();
-        let salt_len = profile.salt_len();
+        Context::new_with_master_keys(
+            vec![MasterKey {
+                key: master_key.to_vec(),
+                salt: master_salt.to_vec(),
+                mki: vec![],
+            }],
+            profile,
+            0,
+            srtp_ctx_opt,
+            srtcp_ctx_opt,
+        )
+    }
 
-        if master_key.len() != key_len {
-            return Err(Error::SrtpMasterKeyLength(key_len, master_key.len()));
-        } else if master_salt.len() != salt_len {
-            return Err(Error::SrtpSaltLength(salt_len, master_salt.len()));
+    /// new_with_master_keys creates a new SRTP Context with one or more master keys.
+    /// All master keys must have MKIs of the same length, which may only be empty if there is
+    /// a single master key. The first master key is used for encryption.
+    /// `kdr` is the key derivation rate, if it is not 0 the session keys are derived again
+    /// every `kdr` packets as described in RFC 3711 Section 4.3.1.
+    pub fn new_with_master_keys(
+        master_keys: Vec<MasterKey>,
+        profile: ProtectionProfile,
+        kdr: u64,
+        srtp_ctx_opt: Option<ContextOption>,
+        srtcp_ctx_opt: Option<ContextOption>,
+    ) -> Result<Context> {
+        if kdr > MAX_KDR || (kdr != 0 && !kdr.is_power_of_two()) {
+            return Err(Error::InvalidKdr(kdr));
         }
 
-        let cipher: Box<dyn Cipher + Send> = match profile {
-            ProtectionProfile::Aes128CmHmacSha1_32 | ProtectionProfile::Aes128CmHmacSha1_80 => {
-                Box::new(CipherAesCmHmacSha1::new(profile, master_key, master_salt)?)
-            }
-
-            ProtectionProfile::AeadAes128Gcm | ProtectionProfile::AeadAes256Gcm => {
-                Box::new(CipherAeadAesGcm::new(profile, master_key, master_salt)?)
-            }
+        let mki_len = match master_keys.first() {
+            Some(master_key) => master_key.mki.len(),
+            None => return Err(Error::ErrNoMasterKey),
         };
 
         let srtp_ctx_opt = if let Some(ctx_opt) = srtp_ctx_opt {
@@ -140,13 +234,146 @@ impl Context {
             srtcp_no_replay_protection()
         };
 
-        Ok(Context {
-            cipher,
+        let mut context = Context {
+            profile,
+            keys: vec![],
+            active_key: 0,
+            mki_len,
+            kdr,
             srtp_ssrc_states: HashMap::new(),
             srtcp_ssrc_states: HashMap::new(),
             new_srtp_replay_detector: srtp_ctx_opt,
             new_srtcp_replay_detector: srtcp_ctx_opt,
-        })
+        };
+        for master_key in master_keys {
+            context.add_master_key(master_key)?;
+        }
+
+        Ok(context)
+    }
+
+    /// add_master_key adds a master key to the context. Packets tagged with its MKI can be
+    /// decrypted right away, use set_active_master_key to encrypt with it.
+    pub fn add_master_key(&mut self, master_key: MasterKey) -> Result<()> {
+        let key_len = self.profile.key_len();
+        let salt_len = self.profile.salt_len();
+
+        if master_key.key.len() != key_len {
+            return Err(Error::SrtpMasterKeyLength(key_len, master_key.key.len()));
+        } else if master_key.salt.len() != salt_len {
+            return Err(Error::SrtpSaltLength(salt_len, master_key.salt.len()));
+        } else if master_key.mki.len() != self.mki_len {
+            return Err(Error::MkiLength(self.mki_len, master_key.mki.len()));
+        } else if !self.keys.is_empty() && self.mki_len == 0 {
+            return Err(Error::ErrMkiRequired);
+        } else if self.keys.iter().any(|k| k.master_key.mki == master_key.mki) {
+            return Err(Error::ErrDuplicatedMki);
+        }
+
+        let cipher = new_cipher(self.profile, &master_key, 0)?;
+        self.keys.push(ContextKey { master_key, cipher });
+
+        Ok(())
+    }
+
+    /// set_active_master_key selects the master key used for encryption by its MKI.
+    pub fn set_active_master_key(&mut self, mki: &[u8]) -> Result<()> {
+        self.active_key = self.key_index(mki)?;
+        Ok(())
+    }
+
+    fn key_index(&self, mki: &[u8]) -> Result<usize> {
+        self.keys
+            .iter()
+            .position(|k| k.master_key.mki == mki)
+            .ok_or(Error::ErrMkiNotFound)
+    }
+
+    /// Inserts the MKI of the given master key in front of the authentication tag.
+    fn insert_mki(&self, packet: Bytes, key_index: usize, auth_tag_len: usize) -> Bytes {
+        if self.mki_len == 0 {
+            return packet;
+        }
+
+        let pos = packet.len() - auth_tag_len;
+        let mut writer = BytesMut::with_capacity(packet.len() + self.mki_len);
+        writer.extend_from_slice(&packet[..pos]);
+        writer.extend_from_slice(&self.keys[key_index].master_key.mki);
+        writer.extend_from_slice(&packet[pos..]);
+        writer.freeze()
+    }
+
+    /// Removes the MKI in front of the authentication tag and returns the index of the
+    /// master key it identifies together with the packet without the MKI.
+    fn remove_mki<'a>(
+        &self,
+        packet: &'a [u8],
+        auth_tag_len: usize,
+    ) -> Result<(usize, Cow<'a, [u8]>)> {
+        if self.mki_len == 0 {
+            return Ok((self.active_key, Cow::Borrowed(packet)));
+        }
+        if packet.len() < self.mki_len + auth_tag_len {
+            return Err(Error::ErrMkiNotFound);
+        }
+
+        let pos = packet.len() - auth_tag_len - self.mki_len;
+        let key_index = self.key_index(&packet[pos..pos + self.mki_len])?;
+
+        let mut writer = Vec::with_capacity(packet.len() - self.mki_len);
+        writer.extend_from_slice(&packet[..pos]);
+        writer.extend_from_slice(&packet[pos + self.mki_len..]);
+        Ok((key_index, Cow::Owned(writer)))
+    }
+
+    /// Returns the cipher for a SRTP packet with the given index, get_srtp_ssrc_state must
+    /// have been called for the SSRC before.
+    fn srtp_cipher(
+        &mut self,
+        key_index: usize,
+        ssrc: u32,
+        index: u64,
+    ) -> Result<&mut (dyn Cipher + Send)> {
+        let key = &mut self.keys[key_index];
+        if self.kdr == 0 {
+            return Ok(key.cipher.as_mut());
+        }
+
+        match self.srtp_ssrc_states.get_mut(&ssrc) {
+            Some(state) => derived_cipher(
+                &mut state.derived_cipher,
+                self.profile,
+                key_index,
+                &key.master_key,
+                (index / self.kdr) as usize,
+            ),
+            None => Err(Error::SsrcMissingFromSrtp(ssrc)),
+        }
+    }
+
+    /// Returns the cipher for a SRTCP packet with the given index, get_srtcp_ssrc_state must
+    /// have been called for the SSRC before.
+    fn srtcp_cipher(
+        &mut self,
+        key_index: usize,
+        ssrc: u32,
+        index: usize,
+    ) -> Result<&mut (dyn Cipher + Send)> {
+        let key = &mut self.keys[key_index];
+        if self.kdr == 0 {
+            return Ok(key.cipher.as_mut());
+        }
+
+        match self.srtcp_ssrc_states.get_mut(&ssrc) {
+            Some(state) => derived_cipher(
+                &mut state.derived_cipher,
+                self.profile,
+                key_index,
+                &key.master_key,
+                index / self.kdr as usize,
+            ),
+            None => Err(Error::SsrcMissingFromSrtcp(ssrc)),
+        }
     }
 
     fn get_srtp_ssrc_state(&mut self, ssrc: u32) -> &mut SrtpSsrcState {
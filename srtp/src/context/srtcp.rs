@@ -3,6 +3,7 @@ use util::marshal::*;
 
 use super::*;
 use crate::error::Result;
+use crate::key_derivation::SRTCP_INDEX_SIZE;
 
 impl Context {
     /// DecryptRTCP decrypts a RTCP packet with an encrypted payload
@@ -10,7 +11,14 @@ impl Context {
         let mut buf = encrypted;
         rtcp::header::Header::unmarshal(&mut buf)?;
 
-        let index = self.cipher.get_rtcp_index(encrypted);
+        let (key_index, encrypted) =
+            self.remove_mki(encrypted, self.profile.rtcp_auth_tag_len())?;
+        let cipher = &self.keys[key_index].cipher;
+        if encrypted.len() < 8 + cipher.rtcp_auth_tag_len() + SRTCP_INDEX_SIZE {
+            return Err(Error::ErrTooShortRtcp);
+        }
+
+        let index = cipher.get_rtcp_index(&encrypted);
         let ssrc = u32::from_be_bytes([encrypted[4], encrypted[5], encrypted[6], encrypted[7]]);
 
         if let Some(replay_detector) = &mut self.get_srtcp_ssrc_state(ssrc).replay_detector {
@@ -19,7 +27,9 @@ impl Context {
             }
         }
 
-        let dst = self.cipher.decrypt_rtcp(encrypted, index, ssrc)?;
+        let dst = self
+            .srtcp_cipher(key_index, ssrc, index)?
+            .decrypt_rtcp(&encrypted, index, ssrc)?;
 
         if let Some(replay_detector) = &mut self.get_srtcp_ssrc_state(ssrc).replay_detector {
             replay_detector.accept();
@@ -49,6 +59,11 @@ impl Context {
             state.srtcp_index
         };
 
-        self.cipher.encrypt_rtcp(decrypted, index, ssrc)
+        let key_index = self.active_key;
+        let dst = self
+            .srtcp_cipher(key_index, ssrc, index)?
+            .encrypt_rtcp(decrypted, index, ssrc)?;
+
+        Ok(self.insert_mki(dst, key_index, self.profile.rtcp_auth_tag_len()))
     }
 }
@@ -10,7 +10,8 @@ impl Context {
         encrypted: &[u8],
         header: &rtp::header::Header,
     ) -> Result<Bytes> {
-        let auth_tag_len = self.cipher.rtp_auth_tag_len();
+        let auth_tag_len = self.profile.rtp_auth_tag_len();
+        let (key_index, encrypted) = self.remove_mki(encrypted, auth_tag_len)?;
         if encrypted.len() < header.marshal_size() + auth_tag_len {
             return Err(Error::ErrTooShortRtp);
         }
@@ -26,7 +27,10 @@ impl Context {
             }
         }
 
-        let dst = self.cipher.decrypt_rtp(encrypted, header, roc)?;
+        let index = ((roc as u64) << 16) | header.sequence_number as u64;
+        let dst = self
+            .srtp_cipher(key_index, header.ssrc, index)?
+            .decrypt_rtp(&encrypted, header, roc)?;
         {
             let state = self.get_srtp_ssrc_state(header.ssrc);
             if let Some(replay_detector) = &mut state.replay_detector {
@@ -61,12 +65,16 @@ impl Context {
             return Err(Error::ErrExceededMaxPackets);
         }
 
-        let dst = self.cipher.encrypt_rtp(payload, header, roc)?;
+        let key_index = self.active_key;
+        let index = ((roc as u64) << 16) | header.sequence_number as u64;
+        let dst = self
+            .srtp_cipher(key_index, header.ssrc, index)?
+            .encrypt_rtp(payload, header, roc)?;
 
         self.get_srtp_ssrc_state(header.ssrc)
             .update_rollover_count(header.sequence_number, diff);
 
-        Ok(dst)
+        Ok(self.insert_mki(dst, key_index, self.profile.rtp_auth_tag_len()))
     }
 
     /// EncryptRTP marshals and encrypts an RTP packet, writing to the dst buffer provided.
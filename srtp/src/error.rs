@@ -46,8 +46,21 @@ pub enum Error {
     #[error("failed to cast child")]
     ErrFailedTypeAssertion,
 
+    #[deprecated(note = "non-zero key derivation rates are supported, see `Error::InvalidKdr`")]
     #[error("index_over_kdr > 0 is not supported yet")]
     UnsupportedIndexOverKdr,
+    #[error("key derivation rate must be 0 or a power of 2 up to 2^24, got {0}")]
+    InvalidKdr(u64),
+    #[error("no master key provided")]
+    ErrNoMasterKey,
+    #[error("MKI is required when a context has more than one master key")]
+    ErrMkiRequired,
+    #[error("MKI must be len {0}, got {1}")]
+    MkiLength(usize, usize),
+    #[error("duplicated MKI")]
+    ErrDuplicatedMki,
+    #[error("no master key with the given MKI")]
+    ErrMkiNotFound,
     #[error("SRTP Master Key must be len {0}, got {1}")]
     SrtpMasterKeyLength(usize, usize),
     #[error("SRTP Salt must be len {0}, got {1}")]
@@ -58,6 +71,8 @@ pub enum Error {
     SrtpSsrcDuplicated(u32, u16),
     #[error("srtcp ssrc={0} index={1}: duplicated")]
     SrtcpSsrcDuplicated(u32, usize),
+    #[error("ssrc {0} not exist in srtp_ssrc_state")]
+    SsrcMissingFromSrtp(u32),
     #[error("ssrc {0} not exist in srtcp_ssrc_state")]
     SsrcMissingFromSrtcp(u32),
     #[error("Stream with ssrc {0} exists")]
@@ -3,7 +3,7 @@ use aes::cipher::BlockEncrypt;
 use aes::Aes128;
 use aes_gcm::KeyInit;
 
-use crate::error::{Error, Result};
+use crate::error::Result;
 
 pub const LABEL_SRTP_ENCRYPTION: u8 = 0x00;
 pub const LABEL_SRTP_AUTHENTICATION_TAG: u8 = 0x01;
@@ -21,15 +21,10 @@ pub(crate) fn aes_cm_key_derivation(
     index_over_kdr: usize,
     out_len: usize,
 ) -> Result<Vec<u8>> {
-    if index_over_kdr != 0 {
-        // 24-bit "index DIV kdr" must be xored to prf input.
-        return Err(Error::UnsupportedIndexOverKdr);
-    }
-
     // https://tools.ietf.org/html/rfc3711#appendix-B.3
     // The input block for AES-CM is generated by exclusive-oring the master salt with the
     // concatenation of the encryption key label 0x00 with (index DIV kdr),
-    // - index is 'rollover count' and DIV is 'divided by'
+    // - index is the 48-bit SRTP or 31-bit SRTCP packet index and DIV is 'divided by'
 
     let n_master_key = master_key.len();
     let n_master_salt = master_salt.len();
@@ -39,6 +34,13 @@ pub(crate) fn aes_cm_key_derivation(
 
     prf_in[7] ^= label;
 
+    // https://tools.ietf.org/html/rfc3711#section-4.3.1
+    // r = index DIV kdr is 48 bits long and follows the label
+    let r = (index_over_kdr as u64).to_be_bytes();
+    for (i, b) in r[2..].iter().enumerate() {
+        prf_in[8 + i] ^= b;
+    }
+
     //The resulting value is then AES encrypted using the master key to get the cipher key.
     let key = GenericArray::from_slice(master_key);
     let block = Aes128::new(key);
@@ -161,12 +163,47 @@ mod test {
         Ok(())
     }
 
-    // This test asserts that calling aesCmKeyDerivation with a non-zero indexOverKdr fails
-    // Currently this isn't supported, but the API makes sure we can add this in the future
+    // index DIV kdr is xored into the master salt right after the label
     #[test]
     fn test_index_over_kdr() -> Result<()> {
-        let result = aes_cm_key_derivation(LABEL_SRTP_AUTHENTICATION_TAG, &[], &[], 1, 0);
-        assert!(result.is_err());
+        let master_key = vec![
+            0xE1, 0xF9, 0x7A, 0x0D, 0x3E, 0x01, 0x8B, 0xE0, 0xD6, 0x4F, 0xA3, 0x2C, 0x06, 0xDE,
+            0x41, 0x39,
+        ];
+        let master_salt = vec![
+            0x0E, 0xC6, 0x75, 0xAD, 0x49, 0x8A, 0xFE, 0xEB, 0xB6, 0x96, 0x0B, 0x3A, 0xAB, 0xE6,
+        ];
+        let index_over_kdr = 0x0102_0304_0506;
+
+        let mut xored_salt = master_salt.clone();
+        for (i, b) in [0x01, 0x02, 0x03, 0x04, 0x05, 0x06].iter().enumerate() {
+            xored_salt[8 + i] ^= b;
+        }
+
+        let session_key = aes_cm_key_derivation(
+            LABEL_SRTP_ENCRYPTION,
+            &master_key,
+            &master_salt,
+            index_over_kdr,
+            master_key.len(),
+        )?;
+        let expected_session_key = aes_cm_key_derivation(
+            LABEL_SRTP_ENCRYPTION,
+            &master_key,
+            &xored_salt,
+            0,
+            master_key.len(),
+        )?;
+        assert_eq!(session_key, expected_session_key);
+
+        let first_session_key = aes_cm_key_derivation(
+            LABEL_SRTP_ENCRYPTION,
+            &master_key,
+            &master_salt,
+            0,
+            master_key.len(),
+        )?;
+        assert_ne!(session_key, first_session_key);
 
         Ok(())
     }
@@ -1,5 +1,5 @@
 /// ProtectionProfile specifies Cipher and AuthTag details, similar to TLS cipher suite
-#[derive(Default, Debug, Clone, Copy)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum ProtectionProfile {
     #[default]
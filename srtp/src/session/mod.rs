@@ -42,18 +42,26 @@ impl Session {
         config: Config,
         is_rtp: bool,
     ) -> Result<Self> {
-        let local_context = Context::new(
-            &config.keys.local_master_key,
-            &config.keys.local_master_salt,
+        let local_context = Context::new_with_master_keys(
+            vec![MasterKey {
+                key: config.keys.local_master_key,
+                salt: config.keys.local_master_salt,
+                mki: config.keys.local_mki,
+            }],
             config.profile,
+            config.keys.kdr,
             config.local_rtp_options,
             config.local_rtcp_options,
         )?;
 
-        let mut remote_context = Context::new(
-            &config.keys.remote_master_key,
-            &config.keys.remote_master_salt,
+        let mut remote_context = Context::new_with_master_keys(
+            vec![MasterKey {
+                key: config.keys.remote_master_key,
+                salt: config.keys.remote_master_salt,
+                mki: config.keys.remote_mki,
+            }],
             config.profile,
+            config.keys.kdr,
             if config.remote_rtp_options.is_none() {
                 Some(srtp_replay_protection(
                     DEFAULT_SESSION_SRTP_REPLAY_PROTECTION_WINDOW,
@@ -29,6 +29,7 @@ async fn build_session_srtcp_pair() -> Result<(Session, Session)> {
             remote_master_salt: vec![
                 0x0E, 0xC6, 0x75, 0xAD, 0x49, 0x8A, 0xFE, 0xEB, 0xB6, 0x96, 0x0B, 0x3A, 0xAB, 0xE6,
             ],
+            ..Default::default()
         },
 
         local_rtp_options: None,
@@ -55,6 +56,7 @@ async fn build_session_srtcp_pair() -> Result<(Session, Session)> {
             remote_master_salt: vec![
                 0x0E, 0xC6, 0x75, 0xAD, 0x49, 0x8A, 0xFE, 0xEB, 0xB6, 0x96, 0x0B, 0x3A, 0xAB, 0xE6,
             ],
+            ..Default::default()
         },
 
         local_rtp_options: None,
@@ -10,6 +10,10 @@ use crate::error::Result;
 use crate::protection_profile::*;
 
 async fn build_session_srtp_pair() -> Result<(Session, Session)> {
+    build_session_srtp_pair_with_kdr(0).await
+}
+
+async fn build_session_srtp_pair_with_kdr(kdr: u64) -> Result<(Session, Session)> {
     let ua = UdpSocket::bind("127.0.0.1:0").await?;
     let ub = UdpSocket::bind("127.0.0.1:0").await?;
 
@@ -33,6 +37,8 @@ async fn build_session_srtp_pair() -> Result<(Session, Session)> {
             remote_master_salt: vec![
                 0x0E, 0xC6, 0x75, 0xAD, 0x49, 0x8A, 0xFE, 0xEB, 0xB6, 0x96, 0x0B, 0x3A, 0xAB, 0xE6,
             ],
+            kdr,
+            ..Default::default()
         },
 
         local_rtp_options: None,
@@ -59,6 +65,8 @@ async fn build_session_srtp_pair() -> Result<(Session, Session)> {
             remote_master_salt: vec![
                 0x0E, 0xC6, 0x75, 0xAD, 0x49, 0x8A, 0xFE, 0xEB, 0xB6, 0x96, 0x0B, 0x3A, 0xAB, 0xE6,
             ],
+            kdr,
+            ..Default::default()
         },
 
         local_rtp_options: None,
@@ -119,6 +127,42 @@ async fn test_session_srtp_accept() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_session_srtp_kdr() -> Result<()> {
+    let test_payload = Bytes::from_static(&[0x00, 0x01, 0x03, 0x04]);
+    let mut read_buffer = BytesMut::with_capacity(RTP_HEADER_SIZE + test_payload.len());
+    read_buffer.resize(RTP_HEADER_SIZE + test_payload.len(), 0u8);
+    let (sa, sb) = build_session_srtp_pair_with_kdr(4).await?;
+
+    let read_stream = sb.open(TEST_SSRC).await;
+
+    // Across several key derivations
+    for sequence_number in 0..10 {
+        let packet = rtp::packet::Packet {
+            header: rtp::header::Header {
+                ssrc: TEST_SSRC,
+                sequence_number,
+                ..Default::default()
+            },
+            payload: test_payload.clone(),
+        };
+        sa.write_rtp(&packet).await?;
+
+        read_stream.read(&mut read_buffer).await?;
+        assert_eq!(&test_payload[..], &read_buffer[RTP_HEADER_SIZE..]);
+    }
+
+    sa.close().await?;
+    sb.close().await?;
+
+    assert_eq!(
+        build_session_srtp_pair_with_kdr(3).await.err(),
+        Some(Error::InvalidKdr(3))
+    );
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_session_srtp_listen() -> Result<()> {
     let test_payload = Bytes::from_static(&[0x00, 0x01, 0x03, 0x04]);
@@ -0,0 +1,56 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use clap::{App, Arg};
+use stun::behavior::*;
+use stun::Error;
+use tokio::net::{lookup_host, UdpSocket};
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    let mut app = App::new("STUN NAT Behavior")
+        .version("0.1.0")
+        .about("Discovers the NAT mapping and filtering behavior (RFC 5780)")
+        .arg(
+            Arg::with_name("FULLHELP")
+                .help("Prints more detailed help information")
+                .long("fullhelp"),
+        )
+        .arg(
+            Arg::with_name("server")
+                .required_unless("FULLHELP")
+                .takes_value(true)
+                .long("server")
+                .help("STUN Server supporting NAT behavior discovery"),
+        );
+
+    let matches = app.clone().get_matches();
+
+    if matches.is_present("FULLHELP") {
+        app.print_long_help().unwrap();
+        std::process::exit(0);
+    }
+
+    let server = matches.value_of("server").unwrap();
+    let server_addr: SocketAddr = lookup_host(server)
+        .await?
+        .find(|addr| addr.is_ipv4())
+        .ok_or_else(|| Error::Other(format!("failed to resolve {server}")))?;
+
+    let conn = UdpSocket::bind("0.0.0.0:0").await?;
+    println!("Local address: {}", conn.local_addr()?);
+
+    let discovery = BehaviorDiscovery::new(Arc::new(conn), server_addr);
+    let nat = discovery
+        .discover(Arc::new(UdpSocket::bind("0.0.0.0:0").await?))
+        .await?;
+    println!("Mapped address: {}", nat.mapped_address);
+    if nat.nat_detected {
+        println!("Mapping behavior: {}", nat.mapping);
+        println!("Filtering behavior: {}", nat.filtering);
+    } else {
+        println!("No NAT detected");
+    }
+
+    Ok(())
+}
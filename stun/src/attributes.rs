@@ -50,6 +50,7 @@ impl fmt::Display for AttrType {
             ATTR_USE_CANDIDATE => "USE-CANDIDATE",
             ATTR_ICE_CONTROLLED => "ICE-CONTROLLED",
             ATTR_ICE_CONTROLLING => "ICE-CONTROLLING",
+            ATTR_NOMINATION => "NOMINATION",
             ATTR_CHANNEL_NUMBER => "CHANNEL-NUMBER",
             ATTR_LIFETIME => "LIFETIME",
             ATTR_XOR_PEER_ADDRESS => "XOR-PEER-ADDRESS",
@@ -59,8 +60,16 @@ impl fmt::Display for AttrType {
             ATTR_REQUESTED_TRANSPORT => "REQUESTED-TRANSPORT",
             ATTR_DONT_FRAGMENT => "DONT-FRAGMENT",
             ATTR_RESERVATION_TOKEN => "RESERVATION-TOKEN",
+            ATTR_CHANGE_REQUEST => "CHANGE-REQUEST",
+            ATTR_PADDING => "PADDING",
+            ATTR_RESPONSE_PORT => "RESPONSE-PORT",
+            ATTR_CACHE_TIMEOUT => "CACHE-TIMEOUT",
+            ATTR_RESPONSE_ORIGIN => "RESPONSE-ORIGIN",
+            ATTR_OTHER_ADDRESS => "OTHER-ADDRESS",
             ATTR_CONNECTION_ID => "CONNECTION-ID",
             ATTR_REQUESTED_ADDRESS_FAMILY => "REQUESTED-ADDRESS-FAMILY",
+            ATTR_ADDITIONAL_ADDRESS_FAMILY => "ADDITIONAL-ADDRESS-FAMILY",
+            ATTR_ADDRESS_ERROR_CODE => "ADDRESS-ERROR-CODE",
             ATTR_MESSAGE_INTEGRITY_SHA256 => "MESSAGE-INTEGRITY-SHA256",
             ATTR_PASSWORD_ALGORITHM => "PASSWORD-ALGORITHM",
             ATTR_USER_HASH => "USERHASH",
@@ -111,6 +120,9 @@ pub const ATTR_USE_CANDIDATE: AttrType = AttrType(0x0025); // USE-CANDIDATE
 pub const ATTR_ICE_CONTROLLED: AttrType = AttrType(0x8029); // ICE-CONTROLLED
 pub const ATTR_ICE_CONTROLLING: AttrType = AttrType(0x802A); // ICE-CONTROLLING
 
+/// Attributes from the ICE renomination extension (draft-thatcher-ice-renomination).
+pub const ATTR_NOMINATION: AttrType = AttrType(0xC001); // NOMINATION
+
 /// Attributes from RFC 5766 TURN.
 pub const ATTR_CHANNEL_NUMBER: AttrType = AttrType(0x000C); // CHANNEL-NUMBER
 pub const ATTR_LIFETIME: AttrType = AttrType(0x000D); // LIFETIME
@@ -141,6 +153,10 @@ pub const ATTR_CONNECTION_ID: AttrType = AttrType(0x002a); // CONNECTION-ID
 /// Attributes from RFC 6156 TURN IPv6.
 pub const ATTR_REQUESTED_ADDRESS_FAMILY: AttrType = AttrType(0x0017); // REQUESTED-ADDRESS-FAMILY
 
+/// Attributes from RFC 8656 TURN.
+pub const ATTR_ADDITIONAL_ADDRESS_FAMILY: AttrType = AttrType(0x8000); // ADDITIONAL-ADDRESS-FAMILY
+pub const ATTR_ADDRESS_ERROR_CODE: AttrType = AttrType(0x8001); // ADDRESS-ERROR-CODE
+
 /// Attributes from An Origin Attribute for the STUN Protocol.
 pub const ATTR_ORIGIN: AttrType = AttrType(0x802F);
 
@@ -0,0 +1,384 @@
+#[cfg(test)]
+mod behavior_test;
+
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use tokio::time::{self, Duration, Instant};
+use util::Conn;
+
+use crate::addr::*;
+use crate::agent::*;
+use crate::attributes::*;
+use crate::checks::*;
+use crate::error::*;
+use crate::error_code::*;
+use crate::fingerprint::*;
+use crate::message::*;
+use crate::xoraddr::*;
+
+const CHANGE_REQUEST_SIZE: usize = 4;
+const CHANGE_IP: u8 = 0x04;
+const CHANGE_PORT: u8 = 0x02;
+
+/// ChangeRequest represents CHANGE-REQUEST attribute.
+///
+/// The client asks the server to send the response from a different IP
+/// address and/or port.
+///
+/// RFC 5780 Section 7.2
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChangeRequest {
+    pub change_ip: bool,
+    pub change_port: bool,
+}
+
+impl fmt::Display for ChangeRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "change ip: {}, change port: {}",
+            self.change_ip, self.change_port
+        )
+    }
+}
+
+impl Setter for ChangeRequest {
+    /// add_to adds CHANGE-REQUEST to message.
+    fn add_to(&self, m: &mut Message) -> Result<()> {
+        let mut v = vec![0; CHANGE_REQUEST_SIZE];
+        if self.change_ip {
+            v[3] |= CHANGE_IP;
+        }
+        if self.change_port {
+            v[3] |= CHANGE_PORT;
+        }
+        m.add(ATTR_CHANGE_REQUEST, &v);
+        Ok(())
+    }
+}
+
+impl Getter for ChangeRequest {
+    /// get_from decodes CHANGE-REQUEST from message.
+    fn get_from(&mut self, m: &Message) -> Result<()> {
+        let v = m.get(ATTR_CHANGE_REQUEST)?;
+        check_size(ATTR_CHANGE_REQUEST, v.len(), CHANGE_REQUEST_SIZE)?;
+        self.change_ip = v[3] & CHANGE_IP != 0;
+        self.change_port = v[3] & CHANGE_PORT != 0;
+        Ok(())
+    }
+}
+
+const RESPONSE_PORT_SIZE: usize = 4;
+
+/// ResponsePort represents RESPONSE-PORT attribute.
+///
+/// The client asks the server to send the response to this port, keeping
+/// the IP address the request came from.
+///
+/// RFC 5780 Section 7.5
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponsePort(pub u16);
+
+impl fmt::Display for ResponsePort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Setter for ResponsePort {
+    /// add_to adds RESPONSE-PORT to message.
+    fn add_to(&self, m: &mut Message) -> Result<()> {
+        let mut v = vec![0; RESPONSE_PORT_SIZE];
+        v[..2].copy_from_slice(&self.0.to_be_bytes());
+        // v[2..4] is padding.
+        m.add(ATTR_RESPONSE_PORT, &v);
+        Ok(())
+    }
+}
+
+impl Getter for ResponsePort {
+    /// get_from decodes RESPONSE-PORT from message.
+    fn get_from(&mut self, m: &Message) -> Result<()> {
+        let v = m.get(ATTR_RESPONSE_PORT)?;
+        check_size(ATTR_RESPONSE_PORT, v.len(), RESPONSE_PORT_SIZE)?;
+        self.0 = u16::from_be_bytes([v[0], v[1]]);
+        Ok(())
+    }
+}
+
+/// Behavior classifies how a NAT maps or filters the traffic of an endpoint.
+///
+/// RFC 4787 Sections 4.1 and 5
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Behavior {
+    /// The same mapping is used, or inbound traffic is accepted, regardless
+    /// of the remote endpoint.
+    EndpointIndependent,
+    /// Depends on the IP address of the remote endpoint.
+    AddressDependent,
+    /// Depends on both the IP address and the port of the remote endpoint.
+    AddressAndPortDependent,
+}
+
+impl fmt::Display for Behavior {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match *self {
+            Behavior::EndpointIndependent => "endpoint-independent",
+            Behavior::AddressDependent => "address-dependent",
+            Behavior::AddressAndPortDependent => "address and port-dependent",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// NatBehavior is the result of the NAT behavior discovery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NatBehavior {
+    /// The address the server saw the requests coming from.
+    pub mapped_address: SocketAddr,
+    /// False if `mapped_address` is the local address of the connection.
+    pub nat_detected: bool,
+    pub mapping: Behavior,
+    pub filtering: Behavior,
+}
+
+impl fmt::Display for NatBehavior {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.nat_detected {
+            write!(
+                f,
+                "mapped address: {}, mapping: {}, filtering: {}",
+                self.mapped_address, self.mapping, self.filtering
+            )
+        } else {
+            write!(f, "mapped address: {}, no NAT", self.mapped_address)
+        }
+    }
+}
+
+/// BindingResponse holds the addresses of a successful Binding response.
+struct BindingResponse {
+    mapped_address: SocketAddr,
+    other_address: Option<SocketAddr>,
+}
+
+const DEFAULT_DISCOVERY_RTO: Duration = Duration::from_millis(500);
+const DEFAULT_DISCOVERY_MAX_ATTEMPTS: u32 = 3;
+const DISCOVERY_MAX_MESSAGE_SIZE: usize = 1500;
+
+/// BehaviorDiscovery determines the mapping and filtering behavior of the NATs
+/// between `conn` and a STUN server supporting NAT behavior discovery, that is
+/// listening on two IP addresses and two ports.
+///
+/// `conn` must not be connected, responses come from several addresses.
+///
+/// RFC 5780 Section 4
+pub struct BehaviorDiscovery {
+    conn: Arc<dyn Conn + Send + Sync>,
+    server_addr: SocketAddr,
+    rto: Duration,
+    max_attempts: u32,
+}
+
+impl BehaviorDiscovery {
+    pub fn new(conn: Arc<dyn Conn + Send + Sync>, server_addr: SocketAddr) -> Self {
+        BehaviorDiscovery {
+            conn,
+            server_addr,
+            rto: DEFAULT_DISCOVERY_RTO,
+            max_attempts: DEFAULT_DISCOVERY_MAX_ATTEMPTS,
+        }
+    }
+
+    /// with_rto sets the time to wait for a response before retransmitting.
+    pub fn with_rto(mut self, rto: Duration) -> Self {
+        self.rto = rto;
+        self
+    }
+
+    /// with_max_attempts sets how many times a request is sent before giving
+    /// up on the response.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// discover runs the mapping behavior tests, then the filtering behavior
+    /// tests on `filtering_conn`. It must be another socket than the one of
+    /// this [`BehaviorDiscovery`] that hasn't been used yet: the mapping tests
+    /// open NAT bindings towards the alternate addresses of the server, which
+    /// would let the responses of the filtering tests through.
+    ///
+    /// RFC 5780 Section 4.4
+    pub async fn discover(
+        &self,
+        filtering_conn: Arc<dyn Conn + Send + Sync>,
+    ) -> Result<NatBehavior> {
+        let (mapped_address, nat_detected, mapping) = self.mapping_tests().await?;
+        let filtering = self.filtering_tests(&*filtering_conn).await?;
+        Ok(NatBehavior {
+            mapped_address,
+            nat_detected,
+            mapping,
+            filtering,
+        })
+    }
+
+    /// mapping_behavior determines if the NAT reuses the mapping of the local
+    /// address for different remote endpoints.
+    ///
+    /// RFC 5780 Section 4.3
+    pub async fn mapping_behavior(&self) -> Result<Behavior> {
+        let (_, _, mapping) = self.mapping_tests().await?;
+        Ok(mapping)
+    }
+
+    async fn mapping_tests(&self) -> Result<(SocketAddr, bool, Behavior)> {
+        let conn = &*self.conn;
+
+        // Test I: the mapped address as seen by the primary address.
+        let res1 = self.required_binding(conn, self.server_addr, None).await?;
+        let other_address = res1.other_address.ok_or(Error::ErrNoOtherAddress)?;
+        if res1.mapped_address == self.conn.local_addr()? {
+            return Ok((res1.mapped_address, false, Behavior::EndpointIndependent));
+        }
+
+        // Test II: the mapped address as seen by the alternate IP address.
+        let res2 = self
+            .required_binding(
+                conn,
+                SocketAddr::new(other_address.ip(), self.server_addr.port()),
+                None,
+            )
+            .await?;
+        if res2.mapped_address == res1.mapped_address {
+            return Ok((res1.mapped_address, true, Behavior::EndpointIndependent));
+        }
+
+        // Test III: the mapped address as seen by the alternate IP address
+        // and port.
+        let res3 = self.required_binding(conn, other_address, None).await?;
+        let mapping = if res3.mapped_address == res2.mapped_address {
+            Behavior::AddressDependent
+        } else {
+            Behavior::AddressAndPortDependent
+        };
+        Ok((res1.mapped_address, true, mapping))
+    }
+
+    /// filtering_behavior determines which remote endpoints the NAT lets send
+    /// to the mapped address. The socket of this [`BehaviorDiscovery`] must not
+    /// have been used for other tests, see [`BehaviorDiscovery::discover`].
+    ///
+    /// RFC 5780 Section 4.4
+    pub async fn filtering_behavior(&self) -> Result<Behavior> {
+        self.filtering_tests(&*self.conn).await
+    }
+
+    async fn filtering_tests(&self, conn: &(dyn Conn + Send + Sync)) -> Result<Behavior> {
+        // Test I: a response is received from the primary address.
+        let res1 = self.required_binding(conn, self.server_addr, None).await?;
+        if res1.other_address.is_none() {
+            return Err(Error::ErrNoOtherAddress);
+        }
+
+        // Test II: a response is received from the alternate IP address and port.
+        let change_ip_and_port = ChangeRequest {
+            change_ip: true,
+            change_port: true,
+        };
+        if self
+            .binding(conn, self.server_addr, Some(change_ip_and_port))
+            .await?
+            .is_some()
+        {
+            return Ok(Behavior::EndpointIndependent);
+        }
+
+        // Test III: a response is received from the alternate port.
+        let change_port = ChangeRequest {
+            change_ip: false,
+            change_port: true,
+        };
+        if self
+            .binding(conn, self.server_addr, Some(change_port))
+            .await?
+            .is_some()
+        {
+            Ok(Behavior::AddressDependent)
+        } else {
+            Ok(Behavior::AddressAndPortDependent)
+        }
+    }
+
+    async fn required_binding(
+        &self,
+        conn: &(dyn Conn + Send + Sync),
+        dst: SocketAddr,
+        change_request: Option<ChangeRequest>,
+    ) -> Result<BindingResponse> {
+        self.binding(conn, dst, change_request)
+            .await?
+            .ok_or(Error::ErrTransactionTimeOut)
+    }
+
+    /// binding performs a Binding transaction with `dst` on `conn`, returns
+    /// None if no response was received.
+    async fn binding(
+        &self,
+        conn: &(dyn Conn + Send + Sync),
+        dst: SocketAddr,
+        change_request: Option<ChangeRequest>,
+    ) -> Result<Option<BindingResponse>> {
+        let mut attrs: Vec<Box<dyn Setter>> =
+            vec![Box::<TransactionId>::default(), Box::new(BINDING_REQUEST)];
+        if let Some(change_request) = change_request {
+            attrs.push(Box::new(change_request));
+        }
+        attrs.push(Box::new(FINGERPRINT));
+        let mut req = Message::new();
+        req.build(&attrs)?;
+
+        let mut buf = vec![0u8; DISCOVERY_MAX_MESSAGE_SIZE];
+        for _ in 0..self.max_attempts {
+            conn.send_to(&req.raw, dst).await?;
+
+            let deadline = Instant::now() + self.rto;
+            while let Ok(result) = time::timeout_at(deadline, conn.recv_from(&mut buf)).await {
+                let (n, _) = result?;
+                let mut res = Message::new();
+                if res.unmarshal_binary(&buf[..n]).is_err()
+                    || res.transaction_id != req.transaction_id
+                {
+                    // Late responses of previous tests are dropped.
+                    continue;
+                }
+
+                if res.typ != BINDING_SUCCESS {
+                    let mut code = ErrorCodeAttribute::default();
+                    return Err(match code.get_from(&res) {
+                        Ok(()) => Error::Other(format!("{} ({})", res.typ, code)),
+                        Err(_) => Error::Other(res.typ.to_string()),
+                    });
+                }
+
+                let mut mapped = XorMappedAddress::default();
+                mapped.get_from(&res)?;
+                let mut other = OtherAddress::default();
+                let other_address = other
+                    .get_from_as(&res, ATTR_OTHER_ADDRESS)
+                    .ok()
+                    .map(|_| SocketAddr::new(other.ip, other.port));
+
+                return Ok(Some(BindingResponse {
+                    mapped_address: SocketAddr::new(mapped.ip, mapped.port),
+                    other_address,
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+}
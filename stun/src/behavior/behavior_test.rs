@@ -0,0 +1,209 @@
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::Mutex;
+
+use tokio::net::UdpSocket;
+
+use super::*;
+
+#[test]
+fn test_change_request() -> Result<()> {
+    let mut m = Message::new();
+    let c = ChangeRequest {
+        change_ip: true,
+        change_port: true,
+    };
+    c.add_to(&mut m)?;
+    assert_eq!(m.get(ATTR_CHANGE_REQUEST)?, vec![0, 0, 0, 0x06]);
+
+    let mut decoded = ChangeRequest::default();
+    decoded.get_from(&m)?;
+    assert_eq!(decoded, c);
+
+    let mut m = Message::new();
+    m.add(ATTR_CHANGE_REQUEST, &[0, 0, 0, 0x02]);
+    decoded.get_from(&m)?;
+    assert_eq!(
+        decoded,
+        ChangeRequest {
+            change_ip: false,
+            change_port: true,
+        }
+    );
+
+    let mut m = Message::new();
+    m.add(ATTR_CHANGE_REQUEST, &[0, 0]);
+    let result = decoded.get_from(&m);
+    assert!(is_attr_size_invalid(&result.unwrap_err()));
+
+    Ok(())
+}
+
+#[test]
+fn test_response_port() -> Result<()> {
+    let mut m = Message::new();
+    ResponsePort(5000).add_to(&mut m)?;
+    assert_eq!(m.get(ATTR_RESPONSE_PORT)?, vec![0x13, 0x88, 0, 0]);
+
+    let mut decoded = ResponsePort::default();
+    decoded.get_from(&m)?;
+    assert_eq!(decoded, ResponsePort(5000));
+
+    let result = decoded.get_from(&Message::new());
+    assert_eq!(result, Err(Error::ErrAttributeNotFound));
+
+    Ok(())
+}
+
+/// Starts a server listening on two loopback addresses and two ports that
+/// simulates a NAT with the given behaviors, returns its primary address.
+/// Like a NAT, it lets responses through depending on the addresses that the
+/// mapping they are sent to has sent requests to.
+async fn fake_server(mapping: Behavior, filtering: Behavior) -> Result<SocketAddr> {
+    let primary = UdpSocket::bind("127.0.0.1:0").await?;
+    let alternate_port = UdpSocket::bind("127.0.0.1:0").await?;
+    let (port1, port2) = (
+        primary.local_addr()?.port(),
+        alternate_port.local_addr()?.port(),
+    );
+    let sockets = Arc::new([
+        [Arc::new(primary), Arc::new(alternate_port)],
+        [
+            Arc::new(UdpSocket::bind(("127.0.0.2", port1)).await?),
+            Arc::new(UdpSocket::bind(("127.0.0.2", port2)).await?),
+        ],
+    ]);
+    // The server addresses, as indexes in `sockets`, each mapping sent to
+    let sent_to = Arc::new(Mutex::new(HashMap::<_, HashSet<_>>::new()));
+
+    for ip in 0..2 {
+        for port in 0..2 {
+            let sockets = Arc::clone(&sockets);
+            let sent_to = Arc::clone(&sent_to);
+            tokio::spawn(async move {
+                let mut buf = vec![0u8; 1500];
+                while let Ok((n, src)) = sockets[ip][port].recv_from(&mut buf).await {
+                    let mut req = Message::new();
+                    if req.unmarshal_binary(&buf[..n]).is_err() {
+                        continue;
+                    }
+                    let mut change = ChangeRequest::default();
+                    let _ = change.get_from(&req);
+                    let from_ip = ip ^ change.change_ip as usize;
+                    let from_port = port ^ change.change_port as usize;
+
+                    let mapped_port = match mapping {
+                        Behavior::EndpointIndependent => 0,
+                        Behavior::AddressDependent => ip,
+                        Behavior::AddressAndPortDependent => ip * 2 + port,
+                    };
+                    let allowed = {
+                        let mut sent_to = sent_to.lock().unwrap();
+                        let sent_to = sent_to.entry((src, mapped_port)).or_default();
+                        sent_to.insert((ip, port));
+                        match filtering {
+                            Behavior::EndpointIndependent => true,
+                            Behavior::AddressDependent => {
+                                sent_to.iter().any(|&(ip, _)| ip == from_ip)
+                            }
+                            Behavior::AddressAndPortDependent => {
+                                sent_to.contains(&(from_ip, from_port))
+                            }
+                        }
+                    };
+                    if !allowed {
+                        continue;
+                    }
+
+                    let other = sockets[1 - ip][1 - port].local_addr().unwrap();
+                    let mut res = Message::new();
+                    res.build(&[
+                        Box::new(req.clone()),
+                        Box::new(BINDING_SUCCESS),
+                        Box::new(XorMappedAddress {
+                            ip: IpAddr::V4(Ipv4Addr::new(203, 0, 113, 1)),
+                            port: 40000 + mapped_port as u16,
+                        }),
+                    ])
+                    .unwrap();
+                    OtherAddress {
+                        ip: other.ip(),
+                        port: other.port(),
+                    }
+                    .add_to_as(&mut res, ATTR_OTHER_ADDRESS)
+                    .unwrap();
+                    FINGERPRINT.add_to(&mut res).unwrap();
+
+                    let _ = sockets[from_ip][from_port].send_to(&res.raw, src).await;
+                }
+            });
+        }
+    }
+
+    Ok(SocketAddr::new(
+        IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+        port1,
+    ))
+}
+
+#[tokio::test]
+async fn test_behavior_discovery() -> Result<()> {
+    let behaviors = [
+        Behavior::EndpointIndependent,
+        Behavior::AddressDependent,
+        Behavior::AddressAndPortDependent,
+    ];
+    for mapping in behaviors {
+        for filtering in behaviors {
+            let server_addr = fake_server(mapping, filtering).await?;
+            let conn = Arc::new(UdpSocket::bind("127.0.0.1:0").await?);
+            let discovery = BehaviorDiscovery::new(conn, server_addr)
+                .with_rto(Duration::from_millis(50))
+                .with_max_attempts(2);
+
+            let filtering_conn = Arc::new(UdpSocket::bind("127.0.0.1:0").await?);
+            let nat = discovery.discover(filtering_conn).await?;
+            assert!(nat.nat_detected);
+            assert_eq!(nat.mapped_address.ip(), Ipv4Addr::new(203, 0, 113, 1));
+            assert_eq!(nat.mapping, mapping, "mapping behavior of {nat}");
+            assert_eq!(nat.filtering, filtering, "filtering behavior of {nat}");
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_behavior_discovery_no_other_address() -> Result<()> {
+    let server = UdpSocket::bind("127.0.0.1:0").await?;
+    let server_addr = server.local_addr()?;
+    tokio::spawn(async move {
+        let mut buf = vec![0u8; 1500];
+        while let Ok((n, src)) = server.recv_from(&mut buf).await {
+            let mut req = Message::new();
+            if req.unmarshal_binary(&buf[..n]).is_err() {
+                continue;
+            }
+            let mut res = Message::new();
+            res.build(&[
+                Box::new(req),
+                Box::new(BINDING_SUCCESS),
+                Box::new(XorMappedAddress {
+                    ip: src.ip(),
+                    port: src.port(),
+                }),
+            ])
+            .unwrap();
+            let _ = server.send_to(&res.raw, src).await;
+        }
+    });
+
+    let conn = Arc::new(UdpSocket::bind("127.0.0.1:0").await?);
+    let discovery = BehaviorDiscovery::new(conn, server_addr).with_rto(Duration::from_millis(50));
+    assert_eq!(
+        discovery.mapping_behavior().await,
+        Err(Error::ErrNoOtherAddress)
+    );
+
+    Ok(())
+}
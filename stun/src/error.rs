@@ -21,6 +21,8 @@ pub enum Error {
     ErrAgentClosed,
     #[error("transaction is timed out")]
     ErrTransactionTimeOut,
+    #[error("server does not support NAT behavior discovery, no OTHER-ADDRESS")]
+    ErrNoOtherAddress,
     #[error("no default reason for ErrorCode")]
     ErrNoDefaultReason,
     #[error("unexpected EOF")]
@@ -47,6 +49,8 @@ pub enum Error {
     ErrNoConnection,
     #[error("client is closed")]
     ErrClientClosed,
+    #[error("server is closed")]
+    ErrServerClosed,
     #[error("no agent is set")]
     ErrNoAgent,
     #[error("collector is closed")]
@@ -7,6 +7,7 @@ extern crate lazy_static;
 pub mod addr;
 pub mod agent;
 pub mod attributes;
+pub mod behavior;
 pub mod checks;
 pub mod client;
 mod error;
@@ -14,6 +15,7 @@ pub mod error_code;
 pub mod fingerprint;
 pub mod integrity;
 pub mod message;
+pub mod server;
 pub mod textattrs;
 pub mod uattrs;
 pub mod uri;
@@ -0,0 +1,373 @@
+#[cfg(test)]
+mod server_test;
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex as SyncMutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use base64::prelude::BASE64_STANDARD_NO_PAD;
+use base64::Engine;
+use ring::hmac;
+use subtle::ConstantTimeEq;
+use tokio::sync::{broadcast, mpsc, Mutex, Semaphore};
+use tokio::time::{Duration, Instant};
+use util::Conn;
+
+use crate::attributes::*;
+use crate::error::*;
+use crate::error_code::*;
+use crate::fingerprint::*;
+use crate::integrity::*;
+use crate::message::*;
+use crate::textattrs::*;
+use crate::xoraddr::*;
+
+const INBOUND_MTU: usize = 1500;
+const NONCE_LIFETIME: Duration = Duration::from_secs(3600);
+const RATE_LIMITER_PRUNE_INTERVAL: Duration = Duration::from_secs(10);
+/// Number of in-flight requests after which a listener stops reading until
+/// one of them is answered.
+const MAX_CONCURRENT_REQUESTS: usize = 64;
+
+/// AuthHandler returns the key used to check the MESSAGE-INTEGRITY of the
+/// requests of a user.
+///
+/// With short-term credentials, `realm` is empty and the key is the password.
+/// With long-term credentials, the key is MD5(username ":" realm ":" password),
+/// see [`MessageIntegrity::new_long_term_integrity`].
+#[async_trait]
+pub trait AuthHandler {
+    async fn auth_handle(
+        &self,
+        username: &str,
+        realm: &str,
+        src_addr: SocketAddr,
+    ) -> Result<Vec<u8>>;
+}
+
+/// ServerConfig configures the STUN [`Server`].
+#[derive(Default)]
+pub struct ServerConfig {
+    /// conns are the sockets the server answers Binding requests on.
+    pub conns: Vec<Arc<dyn Conn + Send + Sync>>,
+
+    /// auth_handler enables the authentication of the requests, which are
+    /// answered without checking credentials if unset.
+    pub auth_handler: Option<Arc<dyn AuthHandler + Send + Sync>>,
+
+    /// realm enables the long-term credential mechanism, the short-term one
+    /// is used if it is empty.
+    pub realm: String,
+
+    /// software is sent in the SOFTWARE attribute of the responses, if set.
+    pub software: String,
+
+    /// max_requests_per_second limits the rate of the requests answered for
+    /// each source IP address, allowing bursts of one second. Unlimited if 0.
+    pub max_requests_per_second: u32,
+}
+
+/// Server answers Binding requests over UDP.
+///
+/// RFC 5389 Section 7.3
+pub struct Server {
+    close_tx: Mutex<Option<broadcast::Sender<()>>>,
+    done_rx: Mutex<Option<mpsc::Receiver<()>>>,
+}
+
+impl Server {
+    /// new starts answering Binding requests on the configured sockets.
+    pub async fn new(config: ServerConfig) -> Result<Self> {
+        if config.conns.is_empty() {
+            return Err(Error::ErrNoConnection);
+        }
+
+        let (close_tx, _) = broadcast::channel(1);
+        let (done_tx, done_rx) = mpsc::channel(1);
+        let handler = Arc::new(Handler {
+            auth_handler: config.auth_handler,
+            realm: config.realm,
+            software: config.software,
+            nonce_key: hmac::Key::new(hmac::HMAC_SHA256, &rand::random::<[u8; 32]>()),
+            rate_limiter: SyncMutex::new(RateLimiter::new(config.max_requests_per_second)),
+        });
+
+        for conn in config.conns {
+            tokio::spawn(Server::read_loop(
+                conn,
+                Arc::clone(&handler),
+                close_tx.subscribe(),
+                done_tx.clone(),
+            ));
+        }
+
+        Ok(Server {
+            close_tx: Mutex::new(Some(close_tx)),
+            done_rx: Mutex::new(Some(done_rx)),
+        })
+    }
+
+    async fn read_loop(
+        conn: Arc<dyn Conn + Send + Sync>,
+        handler: Arc<Handler>,
+        mut close_rx: broadcast::Receiver<()>,
+        _done_tx: mpsc::Sender<()>,
+    ) {
+        let mut buf = vec![0u8; INBOUND_MTU];
+        let requests = Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS));
+
+        loop {
+            let (n, src_addr) = tokio::select! {
+                result = conn.recv_from(&mut buf) => {
+                    match result {
+                        Ok(v) => v,
+                        Err(_) => break,
+                    }
+                }
+                _ = close_rx.recv() => break,
+            };
+
+            // Each request is answered from its own task, since looking up its
+            // key may take a while.
+            let Ok(permit) = Arc::clone(&requests).acquire_owned().await else {
+                break;
+            };
+            let (conn, handler, req) = (Arc::clone(&conn), Arc::clone(&handler), buf[..n].to_vec());
+            tokio::spawn(async move {
+                if let Some(res) = handler.handle(&req, src_addr).await {
+                    let _ = conn.send_to(&res.raw, src_addr).await;
+                }
+                drop(permit);
+            });
+        }
+
+        let _ = conn.close().await;
+    }
+
+    /// close stops the server, closing all of its sockets.
+    pub async fn close(&self) -> Result<()> {
+        if self.close_tx.lock().await.take().is_none() {
+            return Err(Error::ErrServerClosed);
+        }
+
+        // Waits for all read loops to exit.
+        if let Some(mut done_rx) = self.done_rx.lock().await.take() {
+            let _ = done_rx.recv().await;
+        }
+
+        Ok(())
+    }
+}
+
+/// Handler answers the requests received on all the sockets of a [`Server`].
+struct Handler {
+    auth_handler: Option<Arc<dyn AuthHandler + Send + Sync>>,
+    realm: String,
+    software: String,
+    /// nonce_key authenticates the nonces, which carry their creation time so
+    /// that the server doesn't have to remember them.
+    nonce_key: hmac::Key,
+    rate_limiter: SyncMutex<RateLimiter>,
+}
+
+impl Handler {
+    /// handle returns the response to the datagram `buf`, if any.
+    async fn handle(&self, buf: &[u8], src_addr: SocketAddr) -> Option<Message> {
+        if !is_message(buf) {
+            return None;
+        }
+        let mut m = Message::new();
+        if m.unmarshal_binary(buf).is_err() || m.typ != BINDING_REQUEST {
+            return None;
+        }
+        if m.contains(ATTR_FINGERPRINT) && FINGERPRINT.check(&m).is_err() {
+            return None;
+        }
+        if !self.rate_limiter.lock().unwrap().allow(src_addr.ip()) {
+            return None;
+        }
+
+        let integrity = match self.authenticate(&m, src_addr).await {
+            Ok(integrity) => integrity,
+            Err(res) => return Some(res),
+        };
+
+        let mut attrs: Vec<Box<dyn Setter>> = vec![
+            Box::new(m),
+            Box::new(BINDING_SUCCESS),
+            Box::new(XorMappedAddress {
+                ip: src_addr.ip(),
+                port: src_addr.port(),
+            }),
+        ];
+        if !self.software.is_empty() {
+            attrs.push(Box::new(Software::new(
+                ATTR_SOFTWARE,
+                self.software.clone(),
+            )));
+        }
+        if let Some(integrity) = integrity {
+            attrs.push(Box::new(integrity));
+        }
+        attrs.push(Box::new(FINGERPRINT));
+        self.build(&attrs)
+    }
+
+    /// authenticate checks the credentials of the request, returns the error
+    /// response if they are missing or invalid.
+    ///
+    /// RFC 5389 Sections 10.1.2 and 10.2.2
+    async fn authenticate(
+        &self,
+        m: &Message,
+        src_addr: SocketAddr,
+    ) -> std::result::Result<Option<MessageIntegrity>, Message> {
+        let Some(auth_handler) = &self.auth_handler else {
+            return Ok(None);
+        };
+        let long_term = !self.realm.is_empty();
+
+        if !m.contains(ATTR_MESSAGE_INTEGRITY) {
+            return Err(if long_term {
+                self.error_response(m, CODE_UNAUTHORIZED, true)
+            } else {
+                self.error_response(m, CODE_BAD_REQUEST, false)
+            });
+        }
+
+        let username = TextAttribute::get_from_as(m, ATTR_USERNAME)
+            .map_err(|_| self.error_response(m, CODE_BAD_REQUEST, false))?;
+        let realm = if long_term {
+            let realm = TextAttribute::get_from_as(m, ATTR_REALM);
+            let nonce = TextAttribute::get_from_as(m, ATTR_NONCE);
+            let (Ok(realm), Ok(nonce)) = (realm, nonce) else {
+                return Err(self.error_response(m, CODE_BAD_REQUEST, false));
+            };
+            if !self.is_nonce_valid(&nonce.text) {
+                return Err(self.error_response(m, CODE_STALE_NONCE, true));
+            }
+            realm.text
+        } else {
+            String::new()
+        };
+
+        let key = auth_handler
+            .auth_handle(&username.text, &realm, src_addr)
+            .await
+            .map_err(|_| self.error_response(m, CODE_UNAUTHORIZED, long_term))?;
+        let integrity = MessageIntegrity(key);
+        if integrity.check(&mut m.clone()).is_err() {
+            return Err(self.error_response(m, CODE_UNAUTHORIZED, long_term));
+        }
+
+        Ok(Some(integrity))
+    }
+
+    /// error_response builds the error response to `m`, with a new nonce if
+    /// `with_nonce` is set.
+    fn error_response(&self, m: &Message, code: ErrorCode, with_nonce: bool) -> Message {
+        let mut attrs: Vec<Box<dyn Setter>> = vec![
+            Box::new(m.clone()),
+            Box::new(BINDING_ERROR),
+            Box::new(ErrorCodeAttribute {
+                code,
+                reason: vec![],
+            }),
+        ];
+        if with_nonce {
+            attrs.push(Box::new(Realm::new(ATTR_REALM, self.realm.clone())));
+            attrs.push(Box::new(Nonce::new(ATTR_NONCE, self.new_nonce())));
+        }
+        if !self.software.is_empty() {
+            attrs.push(Box::new(Software::new(
+                ATTR_SOFTWARE,
+                self.software.clone(),
+            )));
+        }
+        attrs.push(Box::new(FINGERPRINT));
+        self.build(&attrs).unwrap_or_default()
+    }
+
+    fn build(&self, attrs: &[Box<dyn Setter>]) -> Option<Message> {
+        let mut res = Message::new();
+        res.build(attrs).ok()?;
+        Some(res)
+    }
+
+    /// new_nonce returns a nonce made of the current time and its HMAC.
+    fn new_nonce(&self) -> String {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.nonce_at(now)
+    }
+
+    fn nonce_at(&self, timestamp: u64) -> String {
+        let tag = hmac::sign(&self.nonce_key, &timestamp.to_be_bytes());
+        format!(
+            "{timestamp:016x}{}",
+            BASE64_STANDARD_NO_PAD.encode(tag.as_ref())
+        )
+    }
+
+    fn is_nonce_valid(&self, nonce: &str) -> bool {
+        let Some(timestamp) = nonce
+            .get(..16)
+            .and_then(|timestamp| u64::from_str_radix(timestamp, 16).ok())
+        else {
+            return false;
+        };
+        let created = UNIX_EPOCH + Duration::from_secs(timestamp);
+        let fresh = SystemTime::now()
+            .duration_since(created)
+            .is_ok_and(|age| age < NONCE_LIFETIME);
+
+        fresh && bool::from(self.nonce_at(timestamp).as_bytes().ct_eq(nonce.as_bytes()))
+    }
+}
+
+/// RateLimiter limits the requests of each source IP address with a token
+/// bucket holding one second worth of requests.
+struct RateLimiter {
+    rate: u32,
+    buckets: HashMap<IpAddr, (f64, Instant)>,
+    last_prune: Instant,
+}
+
+impl RateLimiter {
+    fn new(rate: u32) -> Self {
+        RateLimiter {
+            rate,
+            buckets: HashMap::new(),
+            last_prune: Instant::now(),
+        }
+    }
+
+    fn allow(&mut self, ip: IpAddr) -> bool {
+        if self.rate == 0 {
+            return true;
+        }
+
+        let now = Instant::now();
+        let rate = self.rate as f64;
+        if now.duration_since(self.last_prune) >= RATE_LIMITER_PRUNE_INTERVAL {
+            // Full buckets are the same as missing ones.
+            self.buckets.retain(|_, (tokens, last)| {
+                *tokens + now.duration_since(*last).as_secs_f64() * rate < rate
+            });
+            self.last_prune = now;
+        }
+
+        let (tokens, last) = self.buckets.entry(ip).or_insert((rate, now));
+        *tokens = (*tokens + now.duration_since(*last).as_secs_f64() * rate).min(rate);
+        *last = now;
+        if *tokens < 1.0 {
+            return false;
+        }
+        *tokens -= 1.0;
+        true
+    }
+}
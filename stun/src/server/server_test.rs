@@ -0,0 +1,274 @@
+use tokio::net::UdpSocket;
+
+use super::*;
+use crate::agent::TransactionId;
+
+struct TestAuthHandler;
+
+#[async_trait]
+impl AuthHandler for TestAuthHandler {
+    async fn auth_handle(
+        &self,
+        username: &str,
+        realm: &str,
+        _src_addr: SocketAddr,
+    ) -> Result<Vec<u8>> {
+        if username != "user" {
+            return Err(Error::Other("no such user".to_owned()));
+        }
+        if realm.is_empty() {
+            Ok(MessageIntegrity::new_short_term_integrity("pass".to_owned()).0)
+        } else {
+            Ok(MessageIntegrity::new_long_term_integrity(
+                username.to_owned(),
+                realm.to_owned(),
+                "pass".to_owned(),
+            )
+            .0)
+        }
+    }
+}
+
+async fn new_server(
+    auth_handler: Option<Arc<dyn AuthHandler + Send + Sync>>,
+    realm: &str,
+    max_requests_per_second: u32,
+) -> Result<(Server, Vec<SocketAddr>)> {
+    let conns = vec![
+        Arc::new(UdpSocket::bind("127.0.0.1:0").await?),
+        Arc::new(UdpSocket::bind("127.0.0.1:0").await?),
+    ];
+    let addrs = conns
+        .iter()
+        .map(|conn| conn.local_addr())
+        .collect::<std::io::Result<Vec<_>>>()?;
+
+    let server = Server::new(ServerConfig {
+        conns: conns.into_iter().map(|conn| conn as _).collect(),
+        auth_handler,
+        realm: realm.to_owned(),
+        software: "test".to_owned(),
+        max_requests_per_second,
+    })
+    .await?;
+
+    Ok((server, addrs))
+}
+
+/// Sends a Binding request with `attrs` and returns the response, if any.
+async fn binding(
+    conn: &UdpSocket,
+    server_addr: SocketAddr,
+    attrs: Vec<Box<dyn Setter>>,
+) -> Result<Option<Message>> {
+    let mut setters: Vec<Box<dyn Setter>> =
+        vec![Box::<TransactionId>::default(), Box::new(BINDING_REQUEST)];
+    setters.extend(attrs);
+    let mut req = Message::new();
+    req.build(&setters)?;
+    conn.send_to(&req.raw, server_addr).await?;
+
+    let mut buf = vec![0u8; 1500];
+    let Ok(result) =
+        tokio::time::timeout(Duration::from_millis(200), conn.recv_from(&mut buf)).await
+    else {
+        return Ok(None);
+    };
+    let (n, _) = result?;
+    let mut res = Message::new();
+    res.unmarshal_binary(&buf[..n])?;
+    assert_eq!(res.transaction_id, req.transaction_id);
+    FINGERPRINT.check(&res)?;
+    Ok(Some(res))
+}
+
+fn error_code(res: &Message) -> Result<u16> {
+    assert_eq!(res.typ, BINDING_ERROR);
+    let mut code = ErrorCodeAttribute::default();
+    code.get_from(res)?;
+    Ok(code.code.0)
+}
+
+#[tokio::test]
+async fn test_server_binding() -> Result<()> {
+    let (server, addrs) = new_server(None, "", 0).await?;
+
+    let conn = UdpSocket::bind("127.0.0.1:0").await?;
+    for addr in addrs {
+        let res = binding(&conn, addr, vec![Box::new(FINGERPRINT)])
+            .await?
+            .expect("response must be received");
+        assert_eq!(res.typ, BINDING_SUCCESS);
+
+        let mut mapped = XorMappedAddress::default();
+        mapped.get_from(&res)?;
+        assert_eq!(SocketAddr::new(mapped.ip, mapped.port), conn.local_addr()?);
+        assert_eq!(
+            TextAttribute::get_from_as(&res, ATTR_SOFTWARE)?.text,
+            "test"
+        );
+    }
+
+    server.close().await?;
+    assert_eq!(server.close().await, Err(Error::ErrServerClosed));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_server_short_term_credentials() -> Result<()> {
+    let (server, addrs) = new_server(Some(Arc::new(TestAuthHandler)), "", 0).await?;
+    let conn = UdpSocket::bind("127.0.0.1:0").await?;
+
+    // Missing credentials.
+    let res = binding(&conn, addrs[0], vec![Box::new(FINGERPRINT)])
+        .await?
+        .unwrap();
+    assert_eq!(error_code(&res)?, CODE_BAD_REQUEST.0);
+
+    // Wrong password.
+    let res = binding(
+        &conn,
+        addrs[0],
+        vec![
+            Box::new(Username::new(ATTR_USERNAME, "user".to_owned())),
+            Box::new(MessageIntegrity::new_short_term_integrity(
+                "wrong".to_owned(),
+            )),
+            Box::new(FINGERPRINT),
+        ],
+    )
+    .await?
+    .unwrap();
+    assert_eq!(error_code(&res)?, CODE_UNAUTHORIZED.0);
+
+    let integrity = MessageIntegrity::new_short_term_integrity("pass".to_owned());
+    let res = binding(
+        &conn,
+        addrs[0],
+        vec![
+            Box::new(Username::new(ATTR_USERNAME, "user".to_owned())),
+            Box::new(integrity.clone()),
+            Box::new(FINGERPRINT),
+        ],
+    )
+    .await?
+    .unwrap();
+    assert_eq!(res.typ, BINDING_SUCCESS);
+    integrity.check(&mut res.clone())?;
+
+    server.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_server_long_term_credentials() -> Result<()> {
+    let (server, addrs) = new_server(Some(Arc::new(TestAuthHandler)), "webrtc.rs", 0).await?;
+    let conn = UdpSocket::bind("127.0.0.1:0").await?;
+
+    // The first request is challenged with a realm and a nonce.
+    let res = binding(&conn, addrs[0], vec![]).await?.unwrap();
+    assert_eq!(error_code(&res)?, CODE_UNAUTHORIZED.0);
+    let realm = TextAttribute::get_from_as(&res, ATTR_REALM)?;
+    assert_eq!(realm.text, "webrtc.rs");
+    let nonce = TextAttribute::get_from_as(&res, ATTR_NONCE)?;
+
+    let integrity = MessageIntegrity::new_long_term_integrity(
+        "user".to_owned(),
+        realm.text.clone(),
+        "pass".to_owned(),
+    );
+    let res = binding(
+        &conn,
+        addrs[1],
+        vec![
+            Box::new(Username::new(ATTR_USERNAME, "user".to_owned())),
+            Box::new(realm.clone()),
+            Box::new(nonce),
+            Box::new(integrity.clone()),
+            Box::new(FINGERPRINT),
+        ],
+    )
+    .await?
+    .unwrap();
+    assert_eq!(res.typ, BINDING_SUCCESS);
+    integrity.check(&mut res.clone())?;
+
+    // An unknown nonce is stale.
+    let res = binding(
+        &conn,
+        addrs[0],
+        vec![
+            Box::new(Username::new(ATTR_USERNAME, "user".to_owned())),
+            Box::new(realm),
+            Box::new(Nonce::new(ATTR_NONCE, "unknown".to_owned())),
+            Box::new(integrity),
+            Box::new(FINGERPRINT),
+        ],
+    )
+    .await?
+    .unwrap();
+    assert_eq!(error_code(&res)?, CODE_STALE_NONCE.0);
+    assert!(res.contains(ATTR_NONCE));
+
+    server.close().await?;
+
+    Ok(())
+}
+
+#[test]
+fn test_server_nonce() {
+    let new_handler = || Handler {
+        auth_handler: None,
+        realm: "webrtc.rs".to_owned(),
+        software: String::new(),
+        nonce_key: hmac::Key::new(hmac::HMAC_SHA256, &rand::random::<[u8; 32]>()),
+        rate_limiter: SyncMutex::new(RateLimiter::new(0)),
+    };
+    let handler = new_handler();
+
+    let nonce = handler.new_nonce();
+    assert!(handler.is_nonce_valid(&nonce));
+    assert!(
+        !new_handler().is_nonce_valid(&nonce),
+        "nonces of another server must be rejected"
+    );
+
+    // Tampered with, or truncated.
+    let mut tampered = nonce.clone().into_bytes();
+    let last = tampered.len() - 1;
+    tampered[last] = if tampered[last] == b'A' { b'B' } else { b'A' };
+    assert!(!handler.is_nonce_valid(std::str::from_utf8(&tampered).unwrap()));
+    assert!(!handler.is_nonce_valid(&nonce[..nonce.len() - 1]));
+    assert!(!handler.is_nonce_valid(""));
+    assert!(!handler.is_nonce_valid("unknown"));
+
+    // Expired.
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    assert!(handler.is_nonce_valid(&handler.nonce_at(now - NONCE_LIFETIME.as_secs() + 10)));
+    assert!(!handler.is_nonce_valid(&handler.nonce_at(now - NONCE_LIFETIME.as_secs())));
+    // Created in the future.
+    assert!(!handler.is_nonce_valid(&handler.nonce_at(now + 60)));
+}
+
+#[tokio::test]
+async fn test_server_rate_limit() -> Result<()> {
+    let (server, addrs) = new_server(None, "", 2).await?;
+    let conn = UdpSocket::bind("127.0.0.1:0").await?;
+
+    let mut answered = 0;
+    for _ in 0..4 {
+        if binding(&conn, addrs[0], vec![]).await?.is_some() {
+            answered += 1;
+        }
+    }
+    assert_eq!(answered, 2, "requests over the rate limit must be dropped");
+
+    server.close().await?;
+
+    Ok(())
+}
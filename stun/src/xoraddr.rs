@@ -135,6 +135,26 @@ impl XorMappedAddress {
     /// getting it as for t type.
     pub fn get_from_as(&mut self, m: &Message, t: AttrType) -> Result<()> {
         let v = m.get(t)?;
+        self.decode_as(m, &v, t)
+    }
+
+    /// get_all_from_as decodes every XOR-MAPPED-ADDRESS attribute value in
+    /// message getting them as for t type, e.g. the XOR-RELAYED-ADDRESS
+    /// attributes of a dual-stack TURN allocation.
+    pub fn get_all_from_as(m: &Message, t: AttrType) -> Result<Vec<Self>> {
+        let mut addrs = vec![];
+        for attr in m.attributes.0.iter().filter(|a| a.typ == t) {
+            let mut a = XorMappedAddress::default();
+            a.decode_as(m, &attr.value, t)?;
+            addrs.push(a);
+        }
+        if addrs.is_empty() {
+            return Err(Error::ErrAttributeNotFound);
+        }
+        Ok(addrs)
+    }
+
+    fn decode_as(&mut self, m: &Message, v: &[u8], t: AttrType) -> Result<()> {
         if v.len() <= 4 {
             return Err(Error::ErrUnexpectedEof);
         }
@@ -3,6 +3,7 @@ use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
 use std::sync::Arc;
 
+use async_trait::async_trait;
 use clap::{App, AppSettings, Arg};
 use tokio::net::UdpSocket;
 use tokio::signal;
@@ -24,8 +25,9 @@ impl MyAuthHandler {
     }
 }
 
+#[async_trait]
 impl AuthHandler for MyAuthHandler {
-    fn auth_handle(
+    async fn auth_handle(
         &self,
         username: &str,
         _realm: &str,
@@ -122,11 +124,17 @@ async fn main() -> Result<(), Error> {
                 address: "0.0.0.0".to_owned(),
                 net: Arc::new(Net::new(None)),
             }),
+            nat_discovery: None,
         }],
         realm: realm.to_owned(),
         auth_handler: Arc::new(MyAuthHandler::new(cred_map)),
         channel_bind_timeout: Duration::from_secs(0),
         alloc_close_notify: None,
+        peer_address_policy: PeerAddressPolicy::default(),
+        quota: QuotaConfig::default(),
+        relay_handler: None,
+        store: None,
+        drain_timeout: Duration::from_secs(0),
     })
     .await?;
 
@@ -2,28 +2,38 @@
 mod allocation_manager_test;
 
 use std::collections::HashMap;
+use std::time::SystemTime;
 
 use futures::future;
 use stun::textattrs::Username;
 use tokio::sync::mpsc;
 use util::Conn;
 
+use super::quota::QuotaTracker;
+use super::store::AllocationStore;
 use super::*;
 use crate::error::*;
 use crate::relay::*;
+use crate::server::config::Quota;
 
 /// `ManagerConfig` a bag of config params for `Manager`.
 pub struct ManagerConfig {
     pub relay_addr_generator: Box<dyn RelayAddressGenerator + Send + Sync>,
     pub alloc_close_notify: Option<mpsc::Sender<AllocationInfo>>,
+    pub quota: Arc<QuotaTracker>,
+    pub relay_handler: Option<Arc<dyn RelayHandler + Send + Sync>>,
+    pub store: Arc<dyn AllocationStore + Send + Sync>,
 }
 
 /// `Manager` is used to hold active allocations.
 pub struct Manager {
     allocations: Arc<Mutex<AllocationMap>>,
-    reservations: Arc<Mutex<HashMap<String, u16>>>,
+    store: Arc<dyn AllocationStore + Send + Sync>,
+    draining: AtomicBool,
     relay_addr_generator: Box<dyn RelayAddressGenerator + Send + Sync>,
     alloc_close_notify: Option<mpsc::Sender<AllocationInfo>>,
+    quota: Arc<QuotaTracker>,
+    relay_handler: Option<Arc<dyn RelayHandler + Send + Sync>>,
 }
 
 impl Manager {
@@ -31,12 +41,39 @@ impl Manager {
     pub fn new(config: ManagerConfig) -> Self {
         Manager {
             allocations: Arc::new(Mutex::new(HashMap::new())),
-            reservations: Arc::new(Mutex::new(HashMap::new())),
+            store: config.store,
+            draining: AtomicBool::new(false),
             relay_addr_generator: config.relay_addr_generator,
             alloc_close_notify: config.alloc_close_notify,
+            quota: config.quota,
+            relay_handler: config.relay_handler,
         }
     }
 
+    /// Returns the maximum lifetime of the [`Allocation`]s of `username`, with
+    /// `user_quota` overriding its quota.
+    pub fn max_lifetime(&self, username: &str, user_quota: Option<&Quota>) -> Duration {
+        self.quota.max_lifetime(username, user_quota)
+    }
+
+    /// Overrides the quota of the user `username` for its next [`Allocation`] and
+    /// while it has [`Allocation`]s. Unlike in the server configuration, a zero
+    /// `max_allocations` denies new [`Allocation`]s to the user.
+    pub fn set_user_quota(&self, username: &str, quota: Quota) {
+        self.quota.set_user_quota(username, quota);
+    }
+
+    /// Stops this [`Manager`] from creating new [`Allocation`]s, the existing
+    /// ones are kept until they expire or the [`Manager`] is closed.
+    pub fn drain(&self) {
+        self.draining.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns whether this [`Manager`] is draining.
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
     /// Closes this [`manager`] and closes all [`Allocation`]s it manages.
     pub async fn close(&self) -> Result<()> {
         let allocations = self.allocations.lock().await;
@@ -90,18 +127,107 @@ impl Manager {
         username: Username,
         use_ipv4: bool,
     ) -> Result<Arc<Allocation>> {
-        if lifetime == Duration::from_secs(0) {
-            return Err(Error::ErrLifetimeZero);
-        }
+        let a = self
+            .new_allocation(
+                five_tuple,
+                turn_socket,
+                requested_port,
+                lifetime,
+                username,
+                use_ipv4,
+            )
+            .await?;
+
+        Ok(self.start_allocation(a, lifetime).await)
+    }
+
+    /// Creates a new dual-stack [`Allocation`] with both an IPv4 and an IPv6
+    /// relayed transport address and starts relaying. If only the IPv4 one
+    /// could be allocated, the [`Allocation`] is returned along with the error
+    /// that prevented allocating the IPv6 one.
+    pub async fn create_dual_stack_allocation(
+        &self,
+        five_tuple: FiveTuple,
+        turn_socket: Arc<dyn Conn + Send + Sync>,
+        requested_port: u16,
+        lifetime: Duration,
+        username: Username,
+    ) -> Result<(Arc<Allocation>, Option<Error>)> {
+        let mut a = self
+            .new_allocation(
+                five_tuple,
+                turn_socket,
+                requested_port,
+                lifetime,
+                username,
+                true,
+            )
+            .await?;
+
+        let err = match self.relay_addr_generator.allocate_conn(false, 0).await {
+            Ok((relay_socket, relay_addr)) => {
+                a.additional_relay_socket = Some(relay_socket);
+                a.additional_relay_addr = Some(relay_addr);
+                None
+            }
+            Err(err) => {
+                log::debug!("failed to allocate additional IPv6 relay: {}", err);
+                Some(err)
+            }
+        };
+
+        Ok((self.start_allocation(a, lifetime).await, err))
+    }
 
-        if self.get_allocation(&five_tuple).await.is_some() {
-            return Err(Error::ErrDupeFiveTuple);
+    async fn new_allocation(
+        &self,
+        five_tuple: FiveTuple,
+        turn_socket: Arc<dyn Conn + Send + Sync>,
+        requested_port: u16,
+        lifetime: Duration,
+        username: Username,
+        use_ipv4: bool,
+    ) -> Result<Allocation> {
+        let err = if self.is_draining() {
+            Some(Error::ErrServerDraining)
+        } else if lifetime == Duration::from_secs(0) {
+            Some(Error::ErrLifetimeZero)
+        } else if self.get_allocation(&five_tuple).await.is_some() {
+            Some(Error::ErrDupeFiveTuple)
+        } else {
+            None
+        };
+        if let Some(err) = err {
+            // Don't keep a quota override set for this allocation.
+            self.quota.forget_idle_user(&username.text);
+            return Err(err);
         }
 
-        let (relay_socket, relay_addr) = self
+        self.quota.acquire_allocation(&username.text)?;
+        let (relay_socket, relay_addr) = match self
             .relay_addr_generator
             .allocate_conn(use_ipv4, requested_port)
-            .await?;
+            .await
+        {
+            Ok(v) => v,
+            Err(err) => {
+                self.quota.release_allocation(&username.text);
+                return Err(err);
+            }
+        };
+        let mut info = AllocationInfo::new(
+            five_tuple,
+            username.text.clone(),
+            relay_addr,
+            #[cfg(feature = "metrics")]
+            0,
+        );
+        info.expires = Some(SystemTime::now() + lifetime);
+        if let Err(err) = self.store.insert_allocation(info).await {
+            self.quota.release_allocation(&username.text);
+            let _ = relay_socket.close().await;
+            return Err(err);
+        }
         let mut a = Allocation::new(
             turn_socket,
             relay_socket,
@@ -111,7 +237,14 @@ impl Manager {
             Arc::downgrade(&self.allocations),
             self.alloc_close_notify.clone(),
         );
+        a.quota = Some(Arc::clone(&self.quota));
+        a.relay_handler = self.relay_handler.clone();
+        a.store = Some(Arc::clone(&self.store));
 
+        Ok(a)
+    }
+
+    async fn start_allocation(&self, mut a: Allocation, lifetime: Duration) -> Arc<Allocation> {
         log::debug!("listening on relay addr: {:?}", a.relay_addr);
         a.start(lifetime).await;
         a.packet_handler().await;
@@ -119,10 +252,10 @@ impl Manager {
         let a = Arc::new(a);
         {
             let mut allocations = self.allocations.lock().await;
-            allocations.insert(five_tuple, Arc::clone(&a));
+            allocations.insert(a.five_tuple, Arc::clone(&a));
         }
 
-        Ok(a)
+        a
     }
 
     /// Removes an [`Allocation`].
@@ -167,7 +300,7 @@ impl Manager {
 
     /// Stores the reservation for the token+port.
     pub async fn create_reservation(&self, reservation_token: String, port: u16) {
-        let reservations = Arc::clone(&self.reservations);
+        let store = Arc::clone(&self.store);
         let reservation_token2 = reservation_token.clone();
 
         tokio::spawn(async move {
@@ -175,20 +308,17 @@ impl Manager {
             tokio::pin!(sleep);
             tokio::select! {
                 _ = &mut sleep => {
-                    let mut reservations = reservations.lock().await;
-                    reservations.remove(&reservation_token2);
+                    store.remove_reservation(&reservation_token2).await;
                 },
             }
         });
 
-        let mut reservations = self.reservations.lock().await;
-        reservations.insert(reservation_token, port);
+        self.store.insert_reservation(reservation_token, port).await;
     }
 
     /// Returns the port for a given reservation if it exists.
     pub async fn get_reservation(&self, reservation_token: &str) -> Option<u16> {
-        let reservations = self.reservations.lock().await;
-        reservations.get(reservation_token).copied()
+        self.store.get_reservation(reservation_token).await
     }
 
     /// Returns a random un-allocated udp4 port.
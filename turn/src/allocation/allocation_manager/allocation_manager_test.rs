@@ -1,12 +1,15 @@
 use std::net::{IpAddr, Ipv4Addr};
 use std::str::FromStr;
 
+use async_trait::async_trait;
 use stun::attributes::ATTR_USERNAME;
 use stun::textattrs::TextAttribute;
 use tokio::net::UdpSocket;
 use tokio::sync::mpsc::Sender;
 use util::vnet::net::*;
 
+use super::quota::QuotaTracker;
+use super::store::{AllocationStore, MemoryAllocationStore};
 use super::*;
 use crate::auth::{generate_auth_key, AuthHandler};
 use crate::client::{Client, ClientConfig};
@@ -14,7 +17,8 @@ use crate::error::Result;
 use crate::proto::lifetime::DEFAULT_LIFETIME;
 use crate::relay::relay_none::*;
 use crate::relay::relay_static::RelayAddressGeneratorStatic;
-use crate::server::config::{ConnConfig, ServerConfig};
+use crate::server::config::{ConnConfig, PeerAddressPolicy, Quota, QuotaConfig, ServerConfig};
+use crate::server::request::MAXIMUM_ALLOCATION_LIFETIME;
 use crate::server::Server;
 
 fn new_test_manager() -> Manager {
@@ -24,6 +28,9 @@ fn new_test_manager() -> Manager {
             net: Arc::new(Net::new(None)),
         }),
         alloc_close_notify: None,
+        quota: Arc::new(QuotaTracker::default()),
+        relay_handler: None,
+        store: Arc::new(MemoryAllocationStore::default()),
     };
     Manager::new(config)
 }
@@ -397,8 +404,14 @@ async fn test_delete_allocation_by_username() -> Result<()> {
 }
 
 struct TestAuthHandler;
+#[async_trait]
 impl AuthHandler for TestAuthHandler {
-    fn auth_handle(&self, username: &str, realm: &str, _src_addr: SocketAddr) -> Result<Vec<u8>> {
+    async fn auth_handle(
+        &self,
+        username: &str,
+        realm: &str,
+        _src_addr: SocketAddr,
+    ) -> Result<Vec<u8>> {
         Ok(generate_auth_key(username, realm, "pass"))
     }
 }
@@ -417,11 +430,20 @@ async fn create_server(
                 address: "0.0.0.0".to_owned(),
                 net: Arc::new(Net::new(None)),
             }),
+            nat_discovery: None,
         }],
         realm: "webrtc.rs".to_owned(),
         auth_handler: Arc::new(TestAuthHandler {}),
         channel_bind_timeout: Duration::from_secs(0),
         alloc_close_notify,
+        peer_address_policy: PeerAddressPolicy {
+            allow_internal: true,
+            ..Default::default()
+        },
+        quota: QuotaConfig::default(),
+        relay_handler: None,
+        store: None,
+        drain_timeout: Duration::from_secs(0),
     })
     .await?;
 
@@ -611,3 +633,201 @@ async fn test_alloc_close_notify() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_manager_allocation_quota() -> Result<()> {
+    let m = Manager::new(ManagerConfig {
+        relay_addr_generator: Box::new(RelayAddressGeneratorNone {
+            address: "0.0.0.0".to_owned(),
+            net: Arc::new(Net::new(None)),
+        }),
+        alloc_close_notify: None,
+        quota: Arc::new(QuotaTracker::new(QuotaConfig {
+            user: Quota {
+                max_allocations: 1,
+                ..Default::default()
+            },
+            total: Quota {
+                max_allocations: 2,
+                ..Default::default()
+            },
+        })),
+        relay_handler: None,
+        store: Arc::new(MemoryAllocationStore::default()),
+    });
+
+    let turn_socket: Arc<dyn Conn + Send + Sync> = Arc::new(UdpSocket::bind("0.0.0.0:0").await?);
+    let create = |five_tuple: FiveTuple, username: &str| {
+        m.create_allocation(
+            five_tuple,
+            Arc::clone(&turn_socket),
+            0,
+            DEFAULT_LIFETIME,
+            TextAttribute::new(ATTR_USERNAME, username.into()),
+            true,
+        )
+    };
+
+    let five_tuple1 = random_five_tuple();
+    create(five_tuple1, "user1").await?;
+
+    let result = create(random_five_tuple(), "user1").await;
+    assert_eq!(
+        result.err(),
+        Some(Error::ErrAllocationQuotaReached),
+        "user quota must be enforced"
+    );
+
+    let five_tuple2 = random_five_tuple();
+    create(five_tuple2, "user2").await?;
+
+    let result = create(random_five_tuple(), "user3").await;
+    assert_eq!(
+        result.err(),
+        Some(Error::ErrAllocationQuotaReached),
+        "total quota must be enforced"
+    );
+
+    m.delete_allocation(&five_tuple1).await;
+    create(random_five_tuple(), "user1").await?;
+
+    m.set_user_quota(
+        "user1",
+        Quota {
+            max_allocations: 2,
+            ..Default::default()
+        },
+    );
+    let five_tuple3 = random_five_tuple();
+    let result = create(five_tuple3, "user1").await;
+    assert_eq!(
+        result.err(),
+        Some(Error::ErrAllocationQuotaReached),
+        "total quota must still be enforced"
+    );
+    m.delete_allocation(&five_tuple2).await;
+    create(five_tuple3, "user1").await?;
+
+    m.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_manager_shared_store_and_drain() -> Result<()> {
+    let store: Arc<dyn AllocationStore + Send + Sync> = Arc::new(MemoryAllocationStore::default());
+    let new_manager = || {
+        Manager::new(ManagerConfig {
+            relay_addr_generator: Box::new(RelayAddressGeneratorNone {
+                address: "0.0.0.0".to_owned(),
+                net: Arc::new(Net::new(None)),
+            }),
+            alloc_close_notify: None,
+            quota: Arc::new(QuotaTracker::default()),
+            relay_handler: None,
+            store: Arc::clone(&store),
+        })
+    };
+    let (m1, m2) = (new_manager(), new_manager());
+
+    let turn_socket: Arc<dyn Conn + Send + Sync> = Arc::new(UdpSocket::bind("0.0.0.0:0").await?);
+    let five_tuple = random_five_tuple();
+    let username = Username::new(ATTR_USERNAME, "user".into());
+
+    m1.create_allocation(
+        five_tuple,
+        Arc::clone(&turn_socket),
+        0,
+        DEFAULT_LIFETIME,
+        username.clone(),
+        true,
+    )
+    .await?;
+    assert!(store.get_allocation(&five_tuple).await.is_some());
+
+    // The 5-tuple is in use on another manager sharing the store.
+    let result = m2
+        .create_allocation(
+            five_tuple,
+            Arc::clone(&turn_socket),
+            0,
+            DEFAULT_LIFETIME,
+            username.clone(),
+            true,
+        )
+        .await;
+    assert_eq!(result.err(), Some(Error::ErrDupeFiveTuple));
+
+    // A draining manager keeps its allocations but refuses new ones.
+    m1.drain();
+    assert!(m1.is_draining());
+    assert!(m1.get_allocation(&five_tuple).await.is_some());
+    let result = m1
+        .create_allocation(
+            random_five_tuple(),
+            Arc::clone(&turn_socket),
+            0,
+            DEFAULT_LIFETIME,
+            username.clone(),
+            true,
+        )
+        .await;
+    assert_eq!(result.err(), Some(Error::ErrServerDraining));
+
+    m1.delete_allocation(&five_tuple).await;
+    assert!(
+        store.get_allocation(&five_tuple).await.is_none(),
+        "closed allocation must be removed from the store"
+    );
+
+    m2.create_allocation(
+        five_tuple,
+        Arc::clone(&turn_socket),
+        0,
+        DEFAULT_LIFETIME,
+        username,
+        true,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[test]
+fn test_quota_max_lifetime() {
+    let quota = QuotaTracker::new(QuotaConfig {
+        user: Quota {
+            max_lifetime: Duration::from_secs(600),
+            ..Default::default()
+        },
+        total: Quota {
+            max_lifetime: Duration::from_secs(1200),
+            ..Default::default()
+        },
+    });
+    assert_eq!(quota.max_lifetime("user1", None), Duration::from_secs(600));
+
+    let long = Quota {
+        max_lifetime: Duration::from_secs(7200),
+        ..Default::default()
+    };
+    assert_eq!(
+        quota.max_lifetime("user1", Some(&long)),
+        Duration::from_secs(1200)
+    );
+
+    quota.set_user_quota(
+        "user2",
+        Quota {
+            max_lifetime: Duration::from_secs(60),
+            ..Default::default()
+        },
+    );
+    assert_eq!(quota.max_lifetime("user2", None), Duration::from_secs(60));
+    assert_eq!(quota.max_lifetime("user1", None), Duration::from_secs(600));
+
+    assert_eq!(
+        QuotaTracker::default().max_lifetime("user1", Some(&long)),
+        MAXIMUM_ALLOCATION_LIFETIME
+    );
+}
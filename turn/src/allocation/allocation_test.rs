@@ -312,3 +312,55 @@ async fn test_allocation_close() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_allocation_report_relay() -> Result<()> {
+    #[derive(Default)]
+    struct TestRelayHandler {
+        events: SyncMutex<Vec<(String, SocketAddr, RelayDirection, usize)>>,
+    }
+
+    impl RelayHandler for TestRelayHandler {
+        fn on_relay(&self, event: &RelayEvent<'_>) {
+            self.events.lock().push((
+                event.username.to_owned(),
+                event.peer_addr,
+                event.direction,
+                event.bytes,
+            ));
+        }
+    }
+
+    let turn_socket = Arc::new(UdpSocket::bind("0.0.0.0:0").await?);
+    let relay_socket = Arc::clone(&turn_socket);
+    let relay_addr = relay_socket.local_addr()?;
+    let allocations = Arc::new(Mutex::new(AllocationMap::new()));
+    let mut a = Allocation::new(
+        turn_socket,
+        relay_socket,
+        relay_addr,
+        FiveTuple::default(),
+        TextAttribute::new(ATTR_USERNAME, "user".into()),
+        Arc::downgrade(&allocations),
+        None,
+    );
+
+    // reporting without a handler is a no-op
+    let peer = SocketAddr::from_str("127.0.0.1:3478")?;
+    a.report_relay(peer, RelayDirection::ClientToPeer, 10);
+
+    let handler = Arc::new(TestRelayHandler::default());
+    a.relay_handler = Some(Arc::clone(&handler) as Arc<dyn RelayHandler + Send + Sync>);
+    a.report_relay(peer, RelayDirection::ClientToPeer, 100);
+    a.report_relay(peer, RelayDirection::PeerToClient, 200);
+
+    assert_eq!(
+        *handler.events.lock(),
+        vec![
+            ("user".to_owned(), peer, RelayDirection::ClientToPeer, 100),
+            ("user".to_owned(), peer, RelayDirection::PeerToClient, 200),
+        ]
+    );
+
+    Ok(())
+}
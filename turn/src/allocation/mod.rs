@@ -5,17 +5,22 @@ pub mod allocation_manager;
 pub mod channel_bind;
 pub mod five_tuple;
 pub mod permission;
+pub mod quota;
+pub mod store;
 
 use std::collections::HashMap;
 use std::marker::{Send, Sync};
 use std::net::SocketAddr;
 use std::sync::atomic::Ordering;
 use std::sync::{Arc, Weak};
+use std::time::SystemTime;
 
 use channel_bind::*;
 use five_tuple::*;
 use permission::*;
 use portable_atomic::{AtomicBool, AtomicUsize};
+use quota::QuotaTracker;
+use store::AllocationStore;
 use stun::agent::*;
 use stun::message::*;
 use stun::textattrs::Username;
@@ -51,6 +56,11 @@ pub struct AllocationInfo {
     /// Relayed bytes with this [`Allocation`].
     #[cfg(feature = "metrics")]
     pub relayed_bytes: usize,
+
+    /// Wall clock time this [`Allocation`] expires at unless it is refreshed,
+    /// if known. [`AllocationStore`]s drop expired records, so that the
+    /// [`FiveTuple`]s of a node that went away are freed eventually.
+    pub expires: Option<SystemTime>,
 }
 
 impl AllocationInfo {
@@ -67,10 +77,50 @@ impl AllocationInfo {
             relay_addr,
             #[cfg(feature = "metrics")]
             relayed_bytes,
+            expires: None,
         }
     }
 }
 
+/// Direction of the datagram reported in a [`RelayEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelayDirection {
+    /// From the client to the peer.
+    ClientToPeer,
+
+    /// From the peer to the client.
+    PeerToClient,
+}
+
+/// A datagram relayed by an [`Allocation`].
+#[derive(Debug, Clone, Copy)]
+pub struct RelayEvent<'a> {
+    /// [`FiveTuple`] of the [`Allocation`].
+    pub five_tuple: FiveTuple,
+
+    /// Username of the [`Allocation`].
+    pub username: &'a str,
+
+    /// Relay address of the [`Allocation`].
+    pub relay_addr: SocketAddr,
+
+    /// Address of the peer the datagram was relayed to or from.
+    pub peer_addr: SocketAddr,
+
+    /// Direction of the datagram.
+    pub direction: RelayDirection,
+
+    /// Number of application data bytes relayed, excluding the TURN framing.
+    pub bytes: usize,
+}
+
+/// `RelayHandler` is notified of every datagram relayed by the [`Allocation`]s
+/// of a server as soon as it is relayed, e.g. to meter the traffic of each user.
+/// It is called from the relay loops, so it must not block.
+pub trait RelayHandler {
+    fn on_relay(&self, event: &RelayEvent<'_>);
+}
+
 /// `Allocation` is tied to a FiveTuple and relays traffic
 /// use create_allocation and get_allocation to operate.
 pub struct Allocation {
@@ -78,6 +128,8 @@ pub struct Allocation {
     turn_socket: Arc<dyn Conn + Send + Sync>,
     pub(crate) relay_addr: SocketAddr,
     pub(crate) relay_socket: Arc<dyn Conn + Send + Sync>,
+    pub(crate) additional_relay_addr: Option<SocketAddr>,
+    pub(crate) additional_relay_socket: Option<Arc<dyn Conn + Send + Sync>>,
     five_tuple: FiveTuple,
     username: Username,
     permissions: Arc<Mutex<HashMap<String, Permission>>>,
@@ -87,8 +139,11 @@ pub struct Allocation {
     timer_expired: Arc<AtomicBool>,
     closed: AtomicBool, // Option<mpsc::Receiver<()>>,
     pub(crate) relayed_bytes: AtomicUsize,
-    drop_tx: Option<Sender<u32>>,
+    drop_tx: Vec<Sender<u32>>,
     alloc_close_notify: Option<mpsc::Sender<AllocationInfo>>,
+    pub(crate) quota: Option<Arc<QuotaTracker>>,
+    pub(crate) relay_handler: Option<Arc<dyn RelayHandler + Send + Sync>>,
+    pub(crate) store: Option<Arc<dyn AllocationStore + Send + Sync>>,
 }
 
 fn addr2ipfingerprint(addr: &SocketAddr) -> String {
@@ -111,6 +166,8 @@ impl Allocation {
             turn_socket,
             relay_addr,
             relay_socket,
+            additional_relay_addr: None,
+            additional_relay_socket: None,
             five_tuple,
             username,
             permissions: Arc::new(Mutex::new(HashMap::new())),
@@ -120,8 +177,61 @@ impl Allocation {
             timer_expired: Arc::new(AtomicBool::new(false)),
             closed: AtomicBool::new(false),
             relayed_bytes: Default::default(),
-            drop_tx: None,
+            drop_tx: vec![],
             alloc_close_notify,
+            quota: None,
+            relay_handler: None,
+            store: None,
+        }
+    }
+
+    /// Accounts `n` bytes relayed by this [`Allocation`], returns false if they
+    /// exceed the bandwidth quota and must be dropped.
+    pub(crate) fn consume_bandwidth(&self, n: usize) -> bool {
+        match &self.quota {
+            Some(quota) => quota.consume_bandwidth(&self.username.text, n),
+            None => true,
+        }
+    }
+
+    /// Returns true if this [`Allocation`] has a relayed transport address of
+    /// the IPv4 address family if `ipv4` is set, of the IPv6 one otherwise.
+    pub(crate) fn has_relay_family(&self, ipv4: bool) -> bool {
+        self.relay_addr.is_ipv4() == ipv4
+            || self
+                .additional_relay_addr
+                .is_some_and(|addr| addr.is_ipv4() == ipv4)
+    }
+
+    /// Returns the relay socket and relayed transport address of this
+    /// [`Allocation`] of the address family of `peer`, if any.
+    pub(crate) fn relay_for(
+        &self,
+        peer: &SocketAddr,
+    ) -> Option<(&Arc<dyn Conn + Send + Sync>, SocketAddr)> {
+        if peer.is_ipv4() == self.relay_addr.is_ipv4() {
+            return Some((&self.relay_socket, self.relay_addr));
+        }
+        match (&self.additional_relay_socket, self.additional_relay_addr) {
+            (Some(socket), Some(addr)) if peer.is_ipv4() == addr.is_ipv4() => Some((socket, addr)),
+            _ => None,
+        }
+    }
+
+    /// Reports `n` bytes relayed between the client and `peer_addr` to the [`RelayHandler`].
+    pub(crate) fn report_relay(&self, peer_addr: SocketAddr, direction: RelayDirection, n: usize) {
+        if let Some(handler) = &self.relay_handler {
+            let relay_addr = self
+                .relay_for(&peer_addr)
+                .map_or(self.relay_addr, |(_, addr)| addr);
+            handler.on_relay(&RelayEvent {
+                five_tuple: self.five_tuple,
+                username: &self.username.text,
+                relay_addr,
+                peer_addr,
+                direction,
+                bytes: n,
+            });
         }
     }
 
@@ -254,16 +364,27 @@ impl Allocation {
 
         let _ = self.turn_socket.close().await;
         let _ = self.relay_socket.close().await;
+        if let Some(additional_relay_socket) = &self.additional_relay_socket {
+            let _ = additional_relay_socket.close().await;
+        }
+
+        if let Some(quota) = &self.quota {
+            quota.release_allocation(&self.username.text);
+        }
+
+        if let Some(store) = &self.store {
+            store.remove_allocation(&self.five_tuple).await;
+        }
 
         if let Some(notify_tx) = &self.alloc_close_notify {
             let _ = notify_tx
-                .send(AllocationInfo {
-                    five_tuple: self.five_tuple,
-                    username: self.username.text.clone(),
-                    relay_addr: self.relay_addr,
+                .send(AllocationInfo::new(
+                    self.five_tuple,
+                    self.username.text.clone(),
+                    self.relay_addr,
                     #[cfg(feature = "metrics")]
-                    relayed_bytes: self.relayed_bytes.load(Ordering::Acquire),
-                })
+                    self.relayed_bytes.load(Ordering::Acquire),
+                ))
                 .await;
         }
 
@@ -319,6 +440,12 @@ impl Allocation {
         if let Some(tx) = reset_tx {
             let _ = tx.send(lifetime).await;
         }
+
+        if let Some(store) = &self.store {
+            store
+                .refresh_allocation(&self.five_tuple, SystemTime::now() + lifetime)
+                .await;
+        }
     }
 
     //  https://tools.ietf.org/html/rfc5766#section-10.3
@@ -341,135 +468,174 @@ impl Allocation {
     //  transport address of the received UDP datagram.  The Data indication
     //  is then sent on the 5-tuple associated with the allocation.
     async fn packet_handler(&mut self) {
-        let five_tuple = self.five_tuple;
-        let relay_addr = self.relay_addr;
-        let relay_socket = Arc::clone(&self.relay_socket);
-        let turn_socket = Arc::clone(&self.turn_socket);
-        let allocations = self.allocations.clone();
-        let channel_bindings = Arc::clone(&self.channel_bindings);
-        let permissions = Arc::clone(&self.permissions);
-        let (drop_tx, drop_rx) = oneshot::channel::<u32>();
-        self.drop_tx = Some(drop_tx);
+        let mut relays = vec![(Arc::clone(&self.relay_socket), self.relay_addr)];
+        if let (Some(socket), Some(addr)) =
+            (&self.additional_relay_socket, self.additional_relay_addr)
+        {
+            relays.push((Arc::clone(socket), addr));
+        }
 
-        tokio::spawn(async move {
-            let mut buffer = vec![0u8; RTP_MTU];
-
-            tokio::pin!(drop_rx);
-
-            loop {
-                let (n, src_addr) = tokio::select! {
-                    result = relay_socket.recv_from(&mut buffer) => {
-                        match result {
-                            Ok((n, src_addr)) => (n, src_addr),
-                            Err(_) => {
-                                if let Some(allocs) = &allocations.upgrade() {
-                                    let mut allocs = allocs.lock().await;
-                                    allocs.remove(&five_tuple);
+        for (relay_socket, relay_addr) in relays {
+            let five_tuple = self.five_tuple;
+            let turn_socket = Arc::clone(&self.turn_socket);
+            let allocations = self.allocations.clone();
+            let channel_bindings = Arc::clone(&self.channel_bindings);
+            let permissions = Arc::clone(&self.permissions);
+            let quota = self.quota.clone();
+            let username = self.username.text.clone();
+            let relay_handler = self.relay_handler.clone();
+            let (drop_tx, drop_rx) = oneshot::channel::<u32>();
+            self.drop_tx.push(drop_tx);
+
+            tokio::spawn(async move {
+                let mut buffer = vec![0u8; RTP_MTU];
+
+                tokio::pin!(drop_rx);
+
+                loop {
+                    let (n, src_addr) = tokio::select! {
+                        result = relay_socket.recv_from(&mut buffer) => {
+                            match result {
+                                Ok((n, src_addr)) => (n, src_addr),
+                                Err(_) => {
+                                    if let Some(allocs) = &allocations.upgrade() {
+                                        let mut allocs = allocs.lock().await;
+                                        allocs.remove(&five_tuple);
+                                    }
+                                    break;
                                 }
-                                break;
                             }
                         }
-                    }
-                    _ = drop_rx.as_mut() => {
-                        log::trace!("allocation has stopped, stop packet_handler. five_tuple: {:?}", five_tuple);
-                        break;
-                    }
-                };
-
-                log::debug!(
-                    "relay socket {:?} received {} bytes from {}",
-                    relay_socket.local_addr(),
-                    n,
-                    src_addr
-                );
-
-                let cb_number = {
-                    let mut cb_number = None;
-                    let cbs = channel_bindings.lock().await;
-                    for cb in cbs.values() {
-                        if cb.peer == src_addr {
-                            cb_number = Some(cb.number);
+                        _ = drop_rx.as_mut() => {
+                            log::trace!("allocation has stopped, stop packet_handler. five_tuple: {:?}", five_tuple);
                             break;
                         }
-                    }
-                    cb_number
-                };
-
-                if let Some(number) = cb_number {
-                    let mut channel_data = ChannelData {
-                        data: buffer[..n].to_vec(),
-                        number,
-                        raw: vec![],
                     };
-                    channel_data.encode();
-
-                    if let Err(err) = turn_socket
-                        .send_to(&channel_data.raw, five_tuple.src_addr)
-                        .await
-                    {
-                        log::error!(
-                            "Failed to send ChannelData from allocation {} {}",
-                            src_addr,
-                            err
-                        );
+
+                    log::debug!(
+                        "relay socket {:?} received {} bytes from {}",
+                        relay_socket.local_addr(),
+                        n,
+                        src_addr
+                    );
+
+                    if let Some(quota) = &quota {
+                        if !quota.consume_bandwidth(&username, n) {
+                            log::debug!(
+                                "bandwidth quota exceeded, dropping {} bytes from {} on allocation {}",
+                                n,
+                                src_addr,
+                                relay_addr
+                            );
+                            continue;
+                        }
                     }
-                } else {
-                    let exist = {
-                        let ps = permissions.lock().await;
-                        ps.get(&addr2ipfingerprint(&src_addr)).is_some()
+
+                    let report_relay = || {
+                        if let Some(handler) = &relay_handler {
+                            handler.on_relay(&RelayEvent {
+                                five_tuple,
+                                username: &username,
+                                relay_addr,
+                                peer_addr: src_addr,
+                                direction: RelayDirection::PeerToClient,
+                                bytes: n,
+                            });
+                        }
                     };
 
-                    if exist {
-                        let msg = {
-                            let peer_address_attr = PeerAddress {
-                                ip: src_addr.ip(),
-                                port: src_addr.port(),
-                            };
-                            let data_attr = Data(buffer[..n].to_vec());
-
-                            let mut msg = Message::new();
-                            if let Err(err) = msg.build(&[
-                                Box::new(TransactionId::new()),
-                                Box::new(MessageType::new(METHOD_DATA, CLASS_INDICATION)),
-                                Box::new(peer_address_attr),
-                                Box::new(data_attr),
-                            ]) {
+                    let cb_number = {
+                        let mut cb_number = None;
+                        let cbs = channel_bindings.lock().await;
+                        for cb in cbs.values() {
+                            if cb.peer == src_addr {
+                                cb_number = Some(cb.number);
+                                break;
+                            }
+                        }
+                        cb_number
+                    };
+
+                    if let Some(number) = cb_number {
+                        let mut channel_data = ChannelData {
+                            data: buffer[..n].to_vec(),
+                            number,
+                            raw: vec![],
+                        };
+                        channel_data.encode();
+
+                        match turn_socket
+                            .send_to(&channel_data.raw, five_tuple.src_addr)
+                            .await
+                        {
+                            Ok(_) => report_relay(),
+                            Err(err) => {
                                 log::error!(
-                                    "Failed to send DataIndication from allocation {} {}",
+                                    "Failed to send ChannelData from allocation {} {}",
                                     src_addr,
                                     err
                                 );
-                                None
-                            } else {
-                                Some(msg)
                             }
+                        }
+                    } else {
+                        let exist = {
+                            let ps = permissions.lock().await;
+                            ps.get(&addr2ipfingerprint(&src_addr)).is_some()
                         };
 
-                        if let Some(msg) = msg {
-                            log::debug!(
-                                "relaying message from {} to client at {}",
-                                src_addr,
-                                five_tuple.src_addr
-                            );
-                            if let Err(err) =
-                                turn_socket.send_to(&msg.raw, five_tuple.src_addr).await
-                            {
-                                log::error!(
-                                    "Failed to send DataIndication from allocation {} {}",
+                        if exist {
+                            let msg = {
+                                let peer_address_attr = PeerAddress {
+                                    ip: src_addr.ip(),
+                                    port: src_addr.port(),
+                                };
+                                let data_attr = Data(buffer[..n].to_vec());
+
+                                let mut msg = Message::new();
+                                if let Err(err) = msg.build(&[
+                                    Box::new(TransactionId::new()),
+                                    Box::new(MessageType::new(METHOD_DATA, CLASS_INDICATION)),
+                                    Box::new(peer_address_attr),
+                                    Box::new(data_attr),
+                                ]) {
+                                    log::error!(
+                                        "Failed to send DataIndication from allocation {} {}",
+                                        src_addr,
+                                        err
+                                    );
+                                    None
+                                } else {
+                                    Some(msg)
+                                }
+                            };
+
+                            if let Some(msg) = msg {
+                                log::debug!(
+                                    "relaying message from {} to client at {}",
                                     src_addr,
-                                    err
+                                    five_tuple.src_addr
                                 );
+                                match turn_socket.send_to(&msg.raw, five_tuple.src_addr).await {
+                                    Ok(_) => report_relay(),
+                                    Err(err) => {
+                                        log::error!(
+                                            "Failed to send DataIndication from allocation {} {}",
+                                            src_addr,
+                                            err
+                                        );
+                                    }
+                                }
                             }
+                        } else {
+                            log::info!(
+                                "No Permission or Channel exists for {} on allocation {}",
+                                src_addr,
+                                relay_addr
+                            );
                         }
-                    } else {
-                        log::info!(
-                            "No Permission or Channel exists for {} on allocation {}",
-                            src_addr,
-                            relay_addr
-                        );
                     }
                 }
-            }
-        });
+            });
+        }
     }
 }
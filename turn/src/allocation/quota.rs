@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use tokio::time::{Duration, Instant};
+use util::sync::Mutex as SyncMutex;
+
+use crate::error::*;
+use crate::server::config::{Quota, QuotaConfig};
+use crate::server::request::MAXIMUM_ALLOCATION_LIFETIME;
+
+/// TokenBucket limits a byte rate, allowing bursts of up to one second worth of bytes.
+#[derive(Debug)]
+struct TokenBucket {
+    rate: usize,
+    tokens: f64,
+    last: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: usize) -> Self {
+        TokenBucket {
+            rate,
+            tokens: rate as f64,
+            last: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate as f64).min(self.rate as f64);
+        self.last = now;
+    }
+
+    fn has(&mut self, now: Instant, n: usize) -> bool {
+        if self.rate == 0 {
+            return true;
+        }
+        self.refill(now);
+        self.tokens >= n as f64
+    }
+
+    fn take(&mut self, n: usize) {
+        if self.rate != 0 {
+            self.tokens -= n as f64;
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Usage {
+    quota: Quota,
+    /// Whether `quota` overrides the configured one, in which case a zero
+    /// `max_allocations` denies allocations instead of not limiting them.
+    overridden: bool,
+    allocations: usize,
+    bandwidth: TokenBucket,
+}
+
+impl Usage {
+    fn new(quota: &Quota) -> Self {
+        Usage {
+            quota: *quota,
+            overridden: false,
+            allocations: 0,
+            bandwidth: TokenBucket::new(quota.max_bandwidth),
+        }
+    }
+}
+
+/// `QuotaTracker` accounts the allocations and relayed bytes of every user and
+/// of the whole server against a [`QuotaConfig`]. It is shared by the
+/// allocation [`Manager`]s of all listeners of a server.
+///
+/// [`Manager`]: crate::allocation::allocation_manager::Manager
+#[derive(Debug)]
+pub struct QuotaTracker {
+    config: QuotaConfig,
+    total: SyncMutex<Usage>,
+    users: SyncMutex<HashMap<String, Usage>>,
+}
+
+impl Default for QuotaTracker {
+    fn default() -> Self {
+        QuotaTracker::new(QuotaConfig::default())
+    }
+}
+
+impl QuotaTracker {
+    /// Creates a new [`QuotaTracker`] enforcing `config`.
+    pub fn new(config: QuotaConfig) -> Self {
+        QuotaTracker {
+            total: SyncMutex::new(Usage::new(&config.total)),
+            users: SyncMutex::new(HashMap::new()),
+            config,
+        }
+    }
+
+    /// Returns the maximum lifetime of an allocation of `username`. `user_quota`
+    /// overrides the quota of the user, like [`QuotaTracker::set_user_quota`].
+    pub fn max_lifetime(&self, username: &str, user_quota: Option<&Quota>) -> Duration {
+        let user = match user_quota {
+            Some(quota) => quota.max_lifetime,
+            None => self
+                .users
+                .lock()
+                .get(username)
+                .map_or(self.config.user.max_lifetime, |user| {
+                    user.quota.max_lifetime
+                }),
+        };
+
+        [user, self.config.total.max_lifetime]
+            .into_iter()
+            .filter(|lifetime| !lifetime.is_zero())
+            .fold(MAXIMUM_ALLOCATION_LIFETIME, Duration::min)
+    }
+
+    /// Accounts a new allocation of `username`, failing if either the user's or
+    /// the server's allocation quota is reached.
+    pub fn acquire_allocation(&self, username: &str) -> Result<()> {
+        let mut total = self.total.lock();
+        let mut users = self.users.lock();
+        let user = users
+            .entry(username.to_owned())
+            .or_insert_with(|| Usage::new(&self.config.user));
+
+        if (total.quota.max_allocations != 0 && total.allocations >= total.quota.max_allocations)
+            || ((user.overridden || user.quota.max_allocations != 0)
+                && user.allocations >= user.quota.max_allocations)
+        {
+            if user.allocations == 0 {
+                users.remove(username);
+            }
+            return Err(Error::ErrAllocationQuotaReached);
+        }
+
+        total.allocations += 1;
+        user.allocations += 1;
+        Ok(())
+    }
+
+    /// Overrides the quota of `username` for its next allocation and while it
+    /// has allocations. A zero `max_allocations` denies new allocations.
+    pub fn set_user_quota(&self, username: &str, quota: Quota) {
+        let mut users = self.users.lock();
+        let user = users
+            .entry(username.to_owned())
+            .or_insert_with(|| Usage::new(&self.config.user));
+        if user.quota.max_bandwidth != quota.max_bandwidth {
+            user.bandwidth = TokenBucket::new(quota.max_bandwidth);
+        }
+        user.quota = quota;
+        user.overridden = true;
+    }
+
+    /// Forgets the quota override of `username` if it has no allocations.
+    pub(crate) fn forget_idle_user(&self, username: &str) {
+        let mut users = self.users.lock();
+        if users
+            .get(username)
+            .is_some_and(|user| user.allocations == 0)
+        {
+            users.remove(username);
+        }
+    }
+
+    /// Releases an allocation of `username` acquired with [`QuotaTracker::acquire_allocation`].
+    pub fn release_allocation(&self, username: &str) {
+        let mut total = self.total.lock();
+        total.allocations = total.allocations.saturating_sub(1);
+
+        let mut users = self.users.lock();
+        if let Some(user) = users.get_mut(username) {
+            user.allocations = user.allocations.saturating_sub(1);
+            if user.allocations == 0 {
+                users.remove(username);
+            }
+        }
+    }
+
+    /// Accounts `n` relayed bytes of `username`, returns false if they exceed
+    /// the user's or the server's bandwidth quota and must be dropped.
+    pub fn consume_bandwidth(&self, username: &str, n: usize) -> bool {
+        let now = Instant::now();
+        let mut total = self.total.lock();
+        let mut users = self.users.lock();
+
+        if !total.bandwidth.has(now, n) {
+            return false;
+        }
+        if let Some(user) = users.get_mut(username) {
+            if !user.bandwidth.has(now, n) {
+                return false;
+            }
+            user.bandwidth.take(n);
+        }
+        total.bandwidth.take(n);
+        true
+    }
+}
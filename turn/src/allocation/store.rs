@@ -0,0 +1,141 @@
+#[cfg(test)]
+mod store_test;
+
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+
+use super::five_tuple::FiveTuple;
+use super::AllocationInfo;
+use crate::error::*;
+
+/// `AllocationStore` keeps the state of a TURN server that has to be shared by
+/// all the nodes serving the same clients: the allocations, the nonces and the
+/// reservation tokens.
+///
+/// Relayed sockets always stay on the node that allocated them, the store only
+/// records which [`FiveTuple`]s are in use, so several nodes behind one anycast
+/// address can back each other up. Nonce timestamps are wall clock times, so
+/// they can be checked by any node.
+#[async_trait]
+pub trait AllocationStore: Send + Sync {
+    /// Records a new allocation, fails with [`Error::ErrDupeFiveTuple`] if
+    /// its [`FiveTuple`] is already in use by an allocation that hasn't expired.
+    async fn insert_allocation(&self, info: AllocationInfo) -> Result<()>;
+
+    /// Returns the allocation recorded for the [`FiveTuple`], unless it expired.
+    async fn get_allocation(&self, five_tuple: &FiveTuple) -> Option<AllocationInfo>;
+
+    /// Updates the expiry of the allocation recorded for the [`FiveTuple`]
+    /// when it is refreshed. The default implementation does nothing, for
+    /// stores that don't expire allocations.
+    async fn refresh_allocation(&self, _five_tuple: &FiveTuple, _expires: SystemTime) {}
+
+    /// Removes the allocation recorded for the [`FiveTuple`].
+    async fn remove_allocation(&self, five_tuple: &FiveTuple);
+
+    /// Records a nonce issued at `created`, fails with
+    /// [`Error::ErrDuplicatedNonce`] if it has already been issued.
+    async fn insert_nonce(&self, nonce: String, created: SystemTime) -> Result<()>;
+
+    /// Returns the time the nonce was issued at.
+    async fn get_nonce(&self, nonce: &str) -> Option<SystemTime>;
+
+    /// Removes the nonce.
+    async fn remove_nonce(&self, nonce: &str);
+
+    /// Records the relayed port reserved by the token.
+    async fn insert_reservation(&self, token: String, port: u16);
+
+    /// Returns the relayed port reserved by the token.
+    async fn get_reservation(&self, token: &str) -> Option<u16>;
+
+    /// Removes the reservation.
+    async fn remove_reservation(&self, token: &str);
+}
+
+/// `MemoryAllocationStore` is the default [`AllocationStore`], keeping the
+/// state in the memory of a single server.
+#[derive(Default)]
+pub struct MemoryAllocationStore {
+    allocations: Mutex<HashMap<FiveTuple, AllocationInfo>>,
+    pub(crate) nonces: Mutex<HashMap<String, SystemTime>>,
+    reservations: Mutex<HashMap<String, u16>>,
+}
+
+#[async_trait]
+impl AllocationStore for MemoryAllocationStore {
+    async fn insert_allocation(&self, info: AllocationInfo) -> Result<()> {
+        let now = SystemTime::now();
+        let mut allocations = self.allocations.lock().await;
+        allocations.retain(|_, info| !is_expired(info, now));
+        if allocations.contains_key(&info.five_tuple) {
+            return Err(Error::ErrDupeFiveTuple);
+        }
+        allocations.insert(info.five_tuple, info);
+        Ok(())
+    }
+
+    async fn get_allocation(&self, five_tuple: &FiveTuple) -> Option<AllocationInfo> {
+        let mut allocations = self.allocations.lock().await;
+        if allocations
+            .get(five_tuple)
+            .is_some_and(|info| is_expired(info, SystemTime::now()))
+        {
+            allocations.remove(five_tuple);
+        }
+        allocations.get(five_tuple).cloned()
+    }
+
+    async fn refresh_allocation(&self, five_tuple: &FiveTuple, expires: SystemTime) {
+        let mut allocations = self.allocations.lock().await;
+        if let Some(info) = allocations.get_mut(five_tuple) {
+            info.expires = Some(expires);
+        }
+    }
+
+    async fn remove_allocation(&self, five_tuple: &FiveTuple) {
+        let mut allocations = self.allocations.lock().await;
+        allocations.remove(five_tuple);
+    }
+
+    async fn insert_nonce(&self, nonce: String, created: SystemTime) -> Result<()> {
+        let mut nonces = self.nonces.lock().await;
+        if nonces.contains_key(&nonce) {
+            return Err(Error::ErrDuplicatedNonce);
+        }
+        nonces.insert(nonce, created);
+        Ok(())
+    }
+
+    async fn get_nonce(&self, nonce: &str) -> Option<SystemTime> {
+        let nonces = self.nonces.lock().await;
+        nonces.get(nonce).copied()
+    }
+
+    async fn remove_nonce(&self, nonce: &str) {
+        let mut nonces = self.nonces.lock().await;
+        nonces.remove(nonce);
+    }
+
+    async fn insert_reservation(&self, token: String, port: u16) {
+        let mut reservations = self.reservations.lock().await;
+        reservations.insert(token, port);
+    }
+
+    async fn get_reservation(&self, token: &str) -> Option<u16> {
+        let reservations = self.reservations.lock().await;
+        reservations.get(token).copied()
+    }
+
+    async fn remove_reservation(&self, token: &str) {
+        let mut reservations = self.reservations.lock().await;
+        reservations.remove(token);
+    }
+}
+
+fn is_expired(info: &AllocationInfo, now: SystemTime) -> bool {
+    info.expires.is_some_and(|expires| expires <= now)
+}
@@ -0,0 +1,94 @@
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::time::Duration;
+
+use super::*;
+use crate::proto::PROTO_UDP;
+
+#[tokio::test]
+async fn test_memory_allocation_store() -> Result<()> {
+    let store = MemoryAllocationStore::default();
+
+    let five_tuple = FiveTuple {
+        src_addr: SocketAddr::from_str("127.0.0.1:5000")?,
+        dst_addr: SocketAddr::from_str("127.0.0.1:3478")?,
+        protocol: PROTO_UDP,
+    };
+    let info = AllocationInfo::new(
+        five_tuple,
+        "user".to_owned(),
+        SocketAddr::from_str("127.0.0.1:6000")?,
+        #[cfg(feature = "metrics")]
+        0,
+    );
+
+    store.insert_allocation(info.clone()).await?;
+    assert_eq!(
+        store.insert_allocation(info).await,
+        Err(Error::ErrDupeFiveTuple),
+        "the five tuple must not be allocated twice"
+    );
+    let got = store.get_allocation(&five_tuple).await.unwrap();
+    assert_eq!(got.username, "user");
+    store.remove_allocation(&five_tuple).await;
+    assert!(store.get_allocation(&five_tuple).await.is_none());
+
+    let created = SystemTime::now() - Duration::from_secs(10);
+    store.insert_nonce("nonce".to_owned(), created).await?;
+    assert_eq!(
+        store
+            .insert_nonce("nonce".to_owned(), SystemTime::now())
+            .await,
+        Err(Error::ErrDuplicatedNonce),
+        "the nonce must not be issued twice"
+    );
+    assert_eq!(store.get_nonce("nonce").await, Some(created));
+    store.remove_nonce("nonce").await;
+    assert_eq!(store.get_nonce("nonce").await, None);
+
+    store.insert_reservation("token".to_owned(), 6001).await;
+    assert_eq!(store.get_reservation("token").await, Some(6001));
+    store.remove_reservation("token").await;
+    assert_eq!(store.get_reservation("token").await, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_memory_allocation_store_expiry() -> Result<()> {
+    let store = MemoryAllocationStore::default();
+
+    let five_tuple = FiveTuple {
+        src_addr: SocketAddr::from_str("127.0.0.1:5000")?,
+        dst_addr: SocketAddr::from_str("127.0.0.1:3478")?,
+        protocol: PROTO_UDP,
+    };
+    let mut info = AllocationInfo::new(
+        five_tuple,
+        "user".to_owned(),
+        SocketAddr::from_str("127.0.0.1:6000")?,
+        #[cfg(feature = "metrics")]
+        0,
+    );
+    info.expires = Some(SystemTime::now() + Duration::from_secs(60));
+    store.insert_allocation(info.clone()).await?;
+    assert!(store.get_allocation(&five_tuple).await.is_some());
+
+    // An expired record is dropped, freeing its five tuple.
+    store
+        .refresh_allocation(&five_tuple, SystemTime::now() - Duration::from_secs(1))
+        .await;
+    assert!(store.get_allocation(&five_tuple).await.is_none());
+    store.insert_allocation(info.clone()).await?;
+
+    store
+        .refresh_allocation(&five_tuple, SystemTime::now() - Duration::from_secs(1))
+        .await;
+    store.insert_allocation(info).await?;
+    assert_eq!(
+        store.get_allocation(&five_tuple).await.unwrap().username,
+        "user"
+    );
+
+    Ok(())
+}
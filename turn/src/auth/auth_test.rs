@@ -66,11 +66,20 @@ async fn test_new_long_term_auth_handler() -> Result<()> {
                 address: "0.0.0.0".to_owned(),
                 net: Arc::new(Net::new(None)),
             }),
+            nat_discovery: None,
         }],
         realm: "webrtc.rs".to_owned(),
         auth_handler: Arc::new(LongTermAuthHandler::new(SHARED_SECRET.to_string())),
         channel_bind_timeout: Duration::from_secs(0),
         alloc_close_notify: None,
+        peer_address_policy: PeerAddressPolicy {
+            allow_internal: true,
+            ..Default::default()
+        },
+        quota: QuotaConfig::default(),
+        relay_handler: None,
+        store: None,
+        drain_timeout: Duration::from_secs(0),
     })
     .await?;
 
@@ -101,3 +110,29 @@ async fn test_new_long_term_auth_handler() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_long_term_auth_handler_policy() -> Result<()> {
+    use std::net::SocketAddr;
+    use std::str::FromStr;
+
+    const SHARED_SECRET: &str = "HELLO_WORLD";
+
+    let (username, password) =
+        generate_long_term_credentials(SHARED_SECRET, Duration::from_secs(60))?;
+    let src_addr = SocketAddr::from_str("127.0.0.1:5000")?;
+
+    let handler = LongTermAuthHandler::new(SHARED_SECRET.to_string());
+    let (key, policy) = handler
+        .auth_policy(&username, "webrtc.rs", src_addr)
+        .await?;
+    assert_eq!(key, generate_auth_key(&username, "webrtc.rs", &password));
+    assert_eq!(
+        policy.expires,
+        Some(UNIX_EPOCH + Duration::from_secs(username.parse::<u64>()?))
+    );
+    assert!(policy.quota.is_none());
+    assert!(policy.realms.is_empty());
+
+    Ok(())
+}
@@ -4,15 +4,57 @@ mod auth_test;
 use std::net::SocketAddr;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use async_trait::async_trait;
 use base64::prelude::BASE64_STANDARD;
 use base64::Engine;
 use md5::{Digest, Md5};
 use ring::hmac;
 
 use crate::error::*;
+use crate::server::config::Quota;
 
-pub trait AuthHandler {
-    fn auth_handle(&self, username: &str, realm: &str, src_addr: SocketAddr) -> Result<Vec<u8>>;
+/// `UserPolicy` is the policy an [`AuthHandler`] applies to an authenticated user.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct UserPolicy {
+    /// `quota` overrides the per-user quota of the server for this user. Unlike
+    /// in the server configuration, a zero `max_allocations` denies allocations.
+    pub quota: Option<Quota>,
+
+    /// `realms` lists the realms the user may authenticate in, any realm if empty.
+    pub realms: Vec<String>,
+
+    /// `expires` is the time the credentials expire at. Requests authenticated
+    /// after it are rejected and allocations are not granted a lifetime past it.
+    pub expires: Option<SystemTime>,
+}
+
+/// `AuthHandler` looks up the key of the users authenticating with the server.
+///
+/// It is called for every authenticated request, so credentials revoked in the
+/// backing store stop being accepted on the next Refresh, CreatePermission or
+/// ChannelBind request.
+#[async_trait]
+pub trait AuthHandler: Send + Sync {
+    /// Returns the long-term credential key of `username`, see [`generate_auth_key`].
+    async fn auth_handle(
+        &self,
+        username: &str,
+        realm: &str,
+        src_addr: SocketAddr,
+    ) -> Result<Vec<u8>>;
+
+    /// Returns the long-term credential key of `username` along with its
+    /// [`UserPolicy`]. The default implementation calls [`AuthHandler::auth_handle`]
+    /// and applies no policy.
+    async fn auth_policy(
+        &self,
+        username: &str,
+        realm: &str,
+        src_addr: SocketAddr,
+    ) -> Result<(Vec<u8>, UserPolicy)> {
+        let key = self.auth_handle(username, realm, src_addr).await?;
+        Ok((key, UserPolicy::default()))
+    }
 }
 
 /// `generate_long_term_credentials()` can be used to create credentials valid for `duration` time/
@@ -48,8 +90,14 @@ pub struct LongTermAuthHandler {
     shared_secret: String,
 }
 
+#[async_trait]
 impl AuthHandler for LongTermAuthHandler {
-    fn auth_handle(&self, username: &str, realm: &str, src_addr: SocketAddr) -> Result<Vec<u8>> {
+    async fn auth_handle(
+        &self,
+        username: &str,
+        realm: &str,
+        src_addr: SocketAddr,
+    ) -> Result<Vec<u8>> {
         log::trace!(
             "Authentication username={} realm={} src_addr={}",
             username,
@@ -67,6 +115,23 @@ impl AuthHandler for LongTermAuthHandler {
         let password = long_term_credentials(username, &self.shared_secret);
         Ok(generate_auth_key(username, realm, &password))
     }
+
+    async fn auth_policy(
+        &self,
+        username: &str,
+        realm: &str,
+        src_addr: SocketAddr,
+    ) -> Result<(Vec<u8>, UserPolicy)> {
+        let key = self.auth_handle(username, realm, src_addr).await?;
+        let expires = UNIX_EPOCH + Duration::from_secs(username.parse::<u64>()?);
+        Ok((
+            key,
+            UserPolicy {
+                expires: Some(expires),
+                ..Default::default()
+            },
+        ))
+    }
 }
 
 impl LongTermAuthHandler {
@@ -5,6 +5,7 @@ use tokio::time::Duration;
 use util::vnet::net::*;
 
 use super::*;
+use crate::allocation::store::MemoryAllocationStore;
 use crate::auth::*;
 use crate::relay::relay_static::*;
 use crate::server::config::*;
@@ -120,8 +121,14 @@ async fn test_client_with_stun_send_binding_request_to_timeout() -> Result<()> {
 }
 
 struct TestAuthHandler;
+#[async_trait]
 impl AuthHandler for TestAuthHandler {
-    fn auth_handle(&self, username: &str, realm: &str, _src_addr: SocketAddr) -> Result<Vec<u8>> {
+    async fn auth_handle(
+        &self,
+        username: &str,
+        realm: &str,
+        _src_addr: SocketAddr,
+    ) -> Result<Vec<u8>> {
         Ok(generate_auth_key(username, realm, "pass"))
     }
 }
@@ -138,6 +145,7 @@ async fn test_client_nonce_expiration() -> Result<()> {
     // to auto assign a "static" port
     let conn = Arc::new(UdpSocket::bind("0.0.0.0:0").await?);
     let server_port = conn.local_addr()?.port();
+    let store = Arc::new(MemoryAllocationStore::default());
 
     let server = Server::new(ServerConfig {
         conn_configs: vec![ConnConfig {
@@ -147,11 +155,20 @@ async fn test_client_nonce_expiration() -> Result<()> {
                 address: "0.0.0.0".to_owned(),
                 net: Arc::new(Net::new(None)),
             }),
+            nat_discovery: None,
         }],
         realm: "webrtc.rs".to_owned(),
         auth_handler: Arc::new(TestAuthHandler {}),
         channel_bind_timeout: Duration::from_secs(0),
         alloc_close_notify: None,
+        peer_address_policy: PeerAddressPolicy {
+            allow_internal: true,
+            ..Default::default()
+        },
+        quota: QuotaConfig::default(),
+        relay_handler: None,
+        store: Some(Arc::clone(&store) as _),
+        drain_timeout: Duration::from_secs(0),
     })
     .await?;
 
@@ -175,7 +192,7 @@ async fn test_client_nonce_expiration() -> Result<()> {
     let allocation = client.allocate().await?;
 
     {
-        let mut nonces = server.nonces.lock().await;
+        let mut nonces = store.nonces.lock().await;
         nonces.clear();
     }
 
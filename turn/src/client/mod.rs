@@ -33,6 +33,8 @@ use util::conn::*;
 use util::vnet::net::*;
 
 use crate::error::*;
+use crate::proto::addfamily::*;
+use crate::proto::addrerror::*;
 use crate::proto::chandata::*;
 use crate::proto::data::*;
 use crate::proto::lifetime::*;
@@ -511,8 +513,12 @@ impl ClientInternal {
         bm.find_by_number(ch_num).map(|b| b.addr)
     }
 
-    /// Sends a TURN allocation request to the given transport address.
-    async fn allocate(&mut self) -> Result<RelayConnConfig> {
+    /// Sends a TURN allocation request to the given transport address, for
+    /// both an IPv4 and an IPv6 relayed transport address if `dual_stack` is set.
+    async fn allocate(
+        &mut self,
+        dual_stack: bool,
+    ) -> Result<(RelayConnConfig, Option<SocketAddr>)> {
         {
             let read_ch_tx = self.read_ch_tx.lock().await;
             log::debug!("allocate check: read_ch_tx_opt = {}", read_ch_tx.is_some());
@@ -522,14 +528,20 @@ impl ClientInternal {
         }
 
         let mut msg = Message::new();
-        msg.build(&[
-            Box::new(TransactionId::new()),
-            Box::new(MessageType::new(METHOD_ALLOCATE, CLASS_REQUEST)),
-            Box::new(RequestedTransport {
-                protocol: PROTO_UDP,
-            }),
-            Box::new(FINGERPRINT),
-        ])?;
+        {
+            let mut attrs: Vec<Box<dyn Setter>> = vec![
+                Box::new(TransactionId::new()),
+                Box::new(MessageType::new(METHOD_ALLOCATE, CLASS_REQUEST)),
+                Box::new(RequestedTransport {
+                    protocol: PROTO_UDP,
+                }),
+            ];
+            if dual_stack {
+                attrs.push(Box::new(ADDITIONAL_FAMILY_IPV6));
+            }
+            attrs.push(Box::new(FINGERPRINT));
+            msg.build(&attrs)?;
+        }
 
         log::debug!("client.Allocate call PerformTransaction 1");
         let tr_res = self
@@ -548,18 +560,26 @@ impl ClientInternal {
         );
 
         // Trying to authorize.
-        msg.build(&[
-            Box::new(TransactionId::new()),
-            Box::new(MessageType::new(METHOD_ALLOCATE, CLASS_REQUEST)),
-            Box::new(RequestedTransport {
-                protocol: PROTO_UDP,
-            }),
-            Box::new(self.username.clone()),
-            Box::new(self.realm.clone()),
-            Box::new(nonce.clone()),
-            Box::new(self.integrity.clone()),
-            Box::new(FINGERPRINT),
-        ])?;
+        {
+            let mut attrs: Vec<Box<dyn Setter>> = vec![
+                Box::new(TransactionId::new()),
+                Box::new(MessageType::new(METHOD_ALLOCATE, CLASS_REQUEST)),
+                Box::new(RequestedTransport {
+                    protocol: PROTO_UDP,
+                }),
+            ];
+            if dual_stack {
+                attrs.push(Box::new(ADDITIONAL_FAMILY_IPV6));
+            }
+            attrs.extend([
+                Box::new(self.username.clone()) as Box<dyn Setter>,
+                Box::new(self.realm.clone()),
+                Box::new(nonce.clone()),
+                Box::new(self.integrity.clone()),
+                Box::new(FINGERPRINT),
+            ]);
+            msg.build(&attrs)?;
+        }
 
         log::debug!("client.Allocate call PerformTransaction 2");
         let tr_res = self
@@ -578,9 +598,16 @@ impl ClientInternal {
         }
 
         // Getting relayed addresses from response.
-        let mut relayed = RelayedAddress::default();
-        relayed.get_from(&res)?;
-        let relayed_addr = SocketAddr::new(relayed.ip, relayed.port);
+        let relayed = RelayedAddress::get_all_from(&res)?;
+        let relayed_addr = SocketAddr::new(relayed[0].ip, relayed[0].port);
+        let additional_relayed_addr = relayed
+            .get(1)
+            .map(|relayed| SocketAddr::new(relayed.ip, relayed.port));
+
+        let mut address_error = AddressErrorCode::default();
+        if address_error.get_from(&res).is_ok() {
+            log::warn!("server couldn't allocate relay ({})", address_error);
+        }
 
         // Getting lifetime from response
         let mut lifetime = Lifetime::default();
@@ -593,14 +620,17 @@ impl ClientInternal {
             log::debug!("allocate: read_ch_tx_opt = {}", read_ch_tx_opt.is_some());
         }
 
-        Ok(RelayConnConfig {
-            relayed_addr,
-            integrity: self.integrity.clone(),
-            nonce,
-            lifetime: lifetime.0,
-            binding_mgr: Arc::clone(&self.binding_mgr),
-            read_ch_rx: Arc::new(Mutex::new(read_ch_rx)),
-        })
+        Ok((
+            RelayConnConfig {
+                relayed_addr,
+                integrity: self.integrity.clone(),
+                nonce,
+                lifetime: lifetime.0,
+                binding_mgr: Arc::clone(&self.binding_mgr),
+                read_ch_rx: Arc::new(Mutex::new(read_ch_rx)),
+            },
+            additional_relayed_addr,
+        ))
     }
 }
 
@@ -624,14 +654,31 @@ impl Client {
     }
 
     pub async fn allocate(&self) -> Result<impl Conn> {
-        let config = {
+        let (config, _) = {
             let mut ci = self.client_internal.lock().await;
-            ci.allocate().await?
+            ci.allocate(false).await?
         };
 
         Ok(RelayConn::new(Arc::clone(&self.client_internal), config).await)
     }
 
+    /// Allocates both an IPv4 and an IPv6 relayed transport address, see
+    /// [RFC 8656 Section 7.1](https://www.rfc-editor.org/rfc/rfc8656#section-7.1).
+    /// The returned connection relays to peers of both address families and its
+    /// local address is the IPv4 relayed address. The IPv6 relayed address is
+    /// returned along with it, or `None` if the server couldn't allocate it.
+    pub async fn allocate_dual_stack(&self) -> Result<(impl Conn, Option<SocketAddr>)> {
+        let (config, additional_relayed_addr) = {
+            let mut ci = self.client_internal.lock().await;
+            ci.allocate(true).await?
+        };
+
+        Ok((
+            RelayConn::new(Arc::clone(&self.client_internal), config).await,
+            additional_relayed_addr,
+        ))
+    }
+
     pub async fn close(&self) -> Result<()> {
         let mut ci = self.client_internal.lock().await;
         ci.close().await;
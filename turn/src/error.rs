@@ -63,6 +63,8 @@ pub enum Error {
     ErrUnexpectedEof,
     #[error("invalid value for requested family attribute")]
     ErrInvalidRequestedFamilyValue,
+    #[error("invalid value for additional family attribute")]
+    ErrInvalidAdditionalFamilyValue,
     #[error("error code 443: peer address family mismatch")]
     ErrPeerAddressFamilyMismatch,
     #[error("fake error")]
@@ -121,6 +123,10 @@ pub enum Error {
     ErrDuplicatedNonce,
     #[error("no such user exists")]
     ErrNoSuchUser,
+    #[error("realm is not allowed for the user")]
+    ErrRealmNotAllowed,
+    #[error("user credentials have expired")]
+    ErrCredentialsExpired,
     #[error("unexpected class")]
     ErrUnexpectedClass,
     #[error("unexpected method")]
@@ -145,6 +151,10 @@ pub enum Error {
     ErrRequestWithReservationTokenAndEvenPort,
     #[error("Request must not contain RESERVATION-TOKEN and REQUESTED-ADDRESS-FAMILY")]
     ErrRequestWithReservationTokenAndReqAddressFamily,
+    #[error("Request must not contain RESERVATION-TOKEN and ADDITIONAL-ADDRESS-FAMILY")]
+    ErrRequestWithReservationTokenAndAdditionalAddressFamily,
+    #[error("Request must not contain REQUESTED-ADDRESS-FAMILY and ADDITIONAL-ADDRESS-FAMILY")]
+    ErrRequestWithReqAndAdditionalAddressFamily,
     #[error("no allocation found")]
     ErrNoAllocationFound,
     #[error("unable to handle send-indication, no permission added")]
@@ -155,6 +165,18 @@ pub enum Error {
     ErrNoSuchChannelBind,
     #[error("failed writing to socket")]
     ErrFailedWriteSocket,
+    #[error("peer address is not allowed by the peer address policy")]
+    ErrPeerAddressForbidden,
+    #[error("allocation quota reached")]
+    ErrAllocationQuotaReached,
+    #[error("server is draining, no new allocations are accepted")]
+    ErrServerDraining,
+    #[error("CHANGE-REQUEST is not supported without a secondary address")]
+    ErrChangeRequestNotSupported,
+    #[error("RESPONSE-PORT is not supported without a secondary address")]
+    ErrResponsePortNotSupported,
+    #[error("request received on a listener not used for NAT behavior discovery")]
+    ErrNatDiscoveryListenerNotFound,
     #[error("parse int: {0}")]
     ParseInt(#[from] ParseIntError),
     #[error("parse addr: {0}")]
@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod addfamily_test;
+
+use std::fmt;
+
+use stun::attributes::*;
+use stun::checks::*;
+use stun::message::*;
+
+use super::reqfamily::REQUESTED_FAMILY_IPV6;
+
+/// `ADDITIONAL_FAMILY_IPV6` is the only value allowed in `ADDITIONAL-ADDRESS-FAMILY`.
+pub const ADDITIONAL_FAMILY_IPV6: AdditionalAddressFamily =
+    AdditionalAddressFamily(REQUESTED_FAMILY_IPV6.0);
+
+/// `AdditionalAddressFamily` represents the `ADDITIONAL-ADDRESS-FAMILY` Attribute as
+/// defined in [RFC 8656 Section 18.11](https://www.rfc-editor.org/rfc/rfc8656#section-18.11).
+///
+/// It is used by clients to request an IPv6 relayed transport address in
+/// addition to the IPv4 one.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct AdditionalAddressFamily(pub u8);
+
+impl fmt::Display for AdditionalAddressFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match *self {
+            ADDITIONAL_FAMILY_IPV6 => "IPv6",
+            _ => "unknown",
+        };
+        write!(f, "{s}")
+    }
+}
+
+const ADDITIONAL_FAMILY_SIZE: usize = 4;
+
+impl Setter for AdditionalAddressFamily {
+    /// Adds `ADDITIONAL-ADDRESS-FAMILY` to message.
+    fn add_to(&self, m: &mut Message) -> Result<(), stun::Error> {
+        let mut v = vec![0; ADDITIONAL_FAMILY_SIZE];
+        v[0] = self.0;
+        // b[1:4] is RFFU = 0.
+        m.add(ATTR_ADDITIONAL_ADDRESS_FAMILY, &v);
+        Ok(())
+    }
+}
+
+impl Getter for AdditionalAddressFamily {
+    /// Decodes `ADDITIONAL-ADDRESS-FAMILY` from message.
+    fn get_from(&mut self, m: &Message) -> Result<(), stun::Error> {
+        let v = m.get(ATTR_ADDITIONAL_ADDRESS_FAMILY)?;
+        check_size(
+            ATTR_ADDITIONAL_ADDRESS_FAMILY,
+            v.len(),
+            ADDITIONAL_FAMILY_SIZE,
+        )?;
+
+        // The IPv4 address family MUST NOT be used, the relayed transport
+        // address of that family is always allocated.
+        if v[0] != ADDITIONAL_FAMILY_IPV6.0 {
+            return Err(stun::Error::Other("ErrInvalidAdditionalFamilyValue".into()));
+        }
+        self.0 = v[0];
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use super::*;
+
+#[test]
+fn test_additional_address_family_string() -> Result<(), stun::Error> {
+    assert_eq!(ADDITIONAL_FAMILY_IPV6.to_string(), "IPv6");
+    assert_eq!(
+        AdditionalAddressFamily(0x01).to_string(),
+        "unknown",
+        "should be unknown"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_additional_address_family_add_to() -> Result<(), stun::Error> {
+    let mut m = Message::new();
+    let a = ADDITIONAL_FAMILY_IPV6;
+    a.add_to(&mut m)?;
+    m.write_header();
+
+    //"GetFrom"
+    {
+        let mut decoded = Message::new();
+        decoded.write(&m.raw)?;
+        let mut add = AdditionalAddressFamily::default();
+        add.get_from(&decoded)?;
+        assert_eq!(add, a, "Decoded {add}, expected {a}");
+
+        //"HandleErr"
+        {
+            let mut m = Message::new();
+            let mut handle = AdditionalAddressFamily::default();
+            if let Err(err) = handle.get_from(&m) {
+                assert_eq!(
+                    stun::Error::ErrAttributeNotFound,
+                    err,
+                    "{err} should be not found"
+                );
+            } else {
+                panic!("expected error, but got ok");
+            }
+            m.add(ATTR_ADDITIONAL_ADDRESS_FAMILY, &[2, 0, 0]);
+            if let Err(err) = handle.get_from(&m) {
+                assert!(
+                    is_attr_size_invalid(&err),
+                    "IsAttrSizeInvalid should be true"
+                );
+            } else {
+                panic!("expected error, but got ok");
+            }
+            m.reset();
+            m.add(ATTR_ADDITIONAL_ADDRESS_FAMILY, &[1, 0, 0, 0]);
+            assert!(handle.get_from(&m).is_err(), "should error on IPv4 value");
+        }
+    }
+
+    Ok(())
+}
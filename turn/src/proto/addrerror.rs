@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod addrerror_test;
+
+use std::fmt;
+
+use stun::attributes::*;
+use stun::checks::*;
+use stun::error_code::ErrorCode;
+use stun::message::*;
+
+use super::reqfamily::RequestedAddressFamily;
+
+// constants for ADDRESS-ERROR-CODE encoding.
+const ADDRESS_ERROR_CODE_FAMILY_BYTE: usize = 0;
+const ADDRESS_ERROR_CODE_CLASS_BYTE: usize = 2;
+const ADDRESS_ERROR_CODE_NUMBER_BYTE: usize = 3;
+const ADDRESS_ERROR_CODE_REASON_START: usize = 4;
+const ADDRESS_ERROR_CODE_REASON_MAX_B: usize = 763;
+const ADDRESS_ERROR_CODE_MODULO: u16 = 100;
+
+/// `AddressErrorCode` represents the `ADDRESS-ERROR-CODE` Attribute as defined in
+/// [RFC 8656 Section 18.12](https://www.rfc-editor.org/rfc/rfc8656#section-18.12).
+///
+/// It is used by servers to report why the relayed transport address of
+/// `family` couldn't be allocated in a dual-stack allocation.
+#[derive(Default, PartialEq, Eq)]
+pub struct AddressErrorCode {
+    pub family: RequestedAddressFamily,
+    pub code: ErrorCode,
+    pub reason: Vec<u8>,
+}
+
+impl fmt::Display for AddressErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}: {}",
+            self.family,
+            self.code.0,
+            String::from_utf8_lossy(&self.reason)
+        )
+    }
+}
+
+impl Setter for AddressErrorCode {
+    /// Adds `ADDRESS-ERROR-CODE` to message.
+    fn add_to(&self, m: &mut Message) -> Result<(), stun::Error> {
+        check_overflow(
+            ATTR_ADDRESS_ERROR_CODE,
+            self.reason.len() + ADDRESS_ERROR_CODE_REASON_START,
+            ADDRESS_ERROR_CODE_REASON_MAX_B + ADDRESS_ERROR_CODE_REASON_START,
+        )?;
+
+        let mut value = Vec::with_capacity(ADDRESS_ERROR_CODE_REASON_START + self.reason.len());
+        value.push(self.family.0); // [ADDRESS_ERROR_CODE_FAMILY_BYTE]
+        value.push(0); // reserved
+        value.push((self.code.0 / ADDRESS_ERROR_CODE_MODULO) as u8); // [ADDRESS_ERROR_CODE_CLASS_BYTE]
+        value.push((self.code.0 % ADDRESS_ERROR_CODE_MODULO) as u8); // [ADDRESS_ERROR_CODE_NUMBER_BYTE]
+        value.extend_from_slice(&self.reason); // [ADDRESS_ERROR_CODE_REASON_START:]
+
+        m.add(ATTR_ADDRESS_ERROR_CODE, &value);
+        Ok(())
+    }
+}
+
+impl Getter for AddressErrorCode {
+    /// Decodes `ADDRESS-ERROR-CODE` from message.
+    fn get_from(&mut self, m: &Message) -> Result<(), stun::Error> {
+        let v = m.get(ATTR_ADDRESS_ERROR_CODE)?;
+        if v.len() < ADDRESS_ERROR_CODE_REASON_START {
+            return Err(stun::Error::ErrUnexpectedEof);
+        }
+
+        let class = (v[ADDRESS_ERROR_CODE_CLASS_BYTE] & 0x07) as u16;
+        let number = v[ADDRESS_ERROR_CODE_NUMBER_BYTE] as u16;
+        self.family = RequestedAddressFamily(v[ADDRESS_ERROR_CODE_FAMILY_BYTE]);
+        self.code = ErrorCode(class * ADDRESS_ERROR_CODE_MODULO + number);
+        self.reason = v[ADDRESS_ERROR_CODE_REASON_START..].to_vec();
+        Ok(())
+    }
+}
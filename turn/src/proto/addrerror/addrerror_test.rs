@@ -0,0 +1,52 @@
+use stun::error_code::CODE_ADDR_FAMILY_NOT_SUPPORTED;
+
+use super::*;
+use crate::proto::reqfamily::REQUESTED_FAMILY_IPV6;
+
+#[test]
+fn test_address_error_code_add_to() -> Result<(), stun::Error> {
+    let mut m = Message::new();
+    let a = AddressErrorCode {
+        family: REQUESTED_FAMILY_IPV6,
+        code: CODE_ADDR_FAMILY_NOT_SUPPORTED,
+        reason: b"Address Family not Supported".to_vec(),
+    };
+    a.add_to(&mut m)?;
+    m.write_header();
+
+    let raw = m.get(ATTR_ADDRESS_ERROR_CODE)?;
+    assert_eq!(&raw[..4], &[0x02, 0x00, 0x04, 40]);
+
+    //"GetFrom"
+    {
+        let mut decoded = Message::new();
+        decoded.write(&m.raw)?;
+        let mut code = AddressErrorCode::default();
+        code.get_from(&decoded)?;
+        assert!(code == a, "Decoded {code}, expected {a}");
+        assert_eq!(code.to_string(), "IPv6: 440: Address Family not Supported");
+
+        //"HandleErr"
+        {
+            let mut m = Message::new();
+            let mut handle = AddressErrorCode::default();
+            if let Err(err) = handle.get_from(&m) {
+                assert_eq!(
+                    stun::Error::ErrAttributeNotFound,
+                    err,
+                    "{err} should be not found"
+                );
+            } else {
+                panic!("expected error, but got ok");
+            }
+            m.add(ATTR_ADDRESS_ERROR_CODE, &[2, 0, 4]);
+            assert_eq!(
+                handle.get_from(&m),
+                Err(stun::Error::ErrUnexpectedEof),
+                "should error on short value"
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -1,7 +1,9 @@
 #[cfg(test)]
 mod proto_test;
 
+pub mod addfamily;
 pub mod addr;
+pub mod addrerror;
 pub mod chandata;
 pub mod channum;
 pub mod data;
@@ -60,6 +60,22 @@ impl Getter for RelayedAddress {
     }
 }
 
+impl RelayedAddress {
+    /// Decodes every `XOR-RELAYED-ADDRESS` from message, a dual-stack
+    /// allocation has one per address family.
+    pub fn get_all_from(m: &Message) -> Result<Vec<Self>, stun::Error> {
+        Ok(
+            XorMappedAddress::get_all_from_as(m, ATTR_XOR_RELAYED_ADDRESS)?
+                .into_iter()
+                .map(|a| RelayedAddress {
+                    ip: a.ip,
+                    port: a.port,
+                })
+                .collect(),
+        )
+    }
+}
+
 /// `XorRelayedAddress` implements `XOR-RELAYED-ADDRESS` attribute.
 ///
 /// It specifies the address and port that the server allocated to the
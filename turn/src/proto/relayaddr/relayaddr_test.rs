@@ -1,4 +1,4 @@
-use std::net::Ipv4Addr;
+use std::net::{Ipv4Addr, Ipv6Addr};
 
 use super::*;
 
@@ -24,3 +24,32 @@ fn test_relayed_address() -> Result<(), stun::Error> {
 
     Ok(())
 }
+
+#[test]
+fn test_relayed_address_get_all_from() -> Result<(), stun::Error> {
+    let a4 = RelayedAddress {
+        ip: IpAddr::V4(Ipv4Addr::new(111, 11, 1, 2)),
+        port: 333,
+    };
+    let a6 = RelayedAddress {
+        ip: IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)),
+        port: 444,
+    };
+
+    let mut m = Message::new();
+    assert_eq!(
+        RelayedAddress::get_all_from(&m),
+        Err(stun::Error::ErrAttributeNotFound)
+    );
+
+    a4.add_to(&mut m)?;
+    a6.add_to(&mut m)?;
+    m.write_header();
+
+    let mut decoded = Message::new();
+    decoded.write(&m.raw)?;
+
+    assert_eq!(RelayedAddress::get_all_from(&decoded)?, vec![a4, a6]);
+
+    Ok(())
+}
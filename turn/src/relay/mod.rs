@@ -1,3 +1,4 @@
+pub mod relay_dual_stack;
 pub mod relay_none;
 pub mod relay_range;
 pub mod relay_static;
@@ -0,0 +1,35 @@
+use async_trait::async_trait;
+
+use super::*;
+use crate::error::*;
+
+/// `RelayAddressGeneratorDualStack` allocates the relays of each address family
+/// with a dedicated generator. It can be used to serve dual-stack allocations
+/// requested with the `ADDITIONAL-ADDRESS-FAMILY` attribute.
+pub struct RelayAddressGeneratorDualStack {
+    /// `ipv4` allocates the IPv4 relays.
+    pub ipv4: Box<dyn RelayAddressGenerator + Send + Sync>,
+
+    /// `ipv6` allocates the IPv6 relays.
+    pub ipv6: Box<dyn RelayAddressGenerator + Send + Sync>,
+}
+
+#[async_trait]
+impl RelayAddressGenerator for RelayAddressGeneratorDualStack {
+    fn validate(&self) -> Result<()> {
+        self.ipv4.validate()?;
+        self.ipv6.validate()
+    }
+
+    async fn allocate_conn(
+        &self,
+        use_ipv4: bool,
+        requested_port: u16,
+    ) -> Result<(Arc<dyn Conn + Send + Sync>, SocketAddr)> {
+        if use_ipv4 {
+            self.ipv4.allocate_conn(use_ipv4, requested_port).await
+        } else {
+            self.ipv6.allocate_conn(use_ipv4, requested_port).await
+        }
+    }
+}
@@ -1,9 +1,12 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::sync::Arc;
 
+use ipnet::IpNet;
 use tokio::sync::mpsc;
 use tokio::time::Duration;
 use util::Conn;
 
+use crate::allocation::store::AllocationStore;
 use crate::allocation::*;
 use crate::auth::*;
 use crate::error::*;
@@ -16,6 +19,10 @@ pub struct ConnConfig {
     // When an allocation is generated the RelayAddressGenerator
     // creates the net.PacketConn and returns the IP/Port it is available at
     pub relay_addr_generator: Box<dyn RelayAddressGenerator + Send + Sync>,
+
+    /// `nat_discovery` makes the listener answer NAT behavior discovery
+    /// requests, as defined in RFC 5780.
+    pub nat_discovery: Option<NatDiscoveryConfig>,
 }
 
 impl ConnConfig {
@@ -24,6 +31,135 @@ impl ConnConfig {
     }
 }
 
+/// NatDiscoveryConfig configures the listeners on a secondary IP address and
+/// port, that together with the primary listener let clients discover the
+/// behavior of their NATs. The listeners must be bound to specific addresses,
+/// which are reported to the clients.
+pub struct NatDiscoveryConfig {
+    /// `alternate_port_conn` listens on the primary IP address and the secondary port.
+    pub alternate_port_conn: Arc<dyn Conn + Send + Sync>,
+
+    /// `alternate_ip_conn` listens on the secondary IP address and the primary port.
+    pub alternate_ip_conn: Arc<dyn Conn + Send + Sync>,
+
+    /// `alternate_conn` listens on the secondary IP address and port.
+    pub alternate_conn: Arc<dyn Conn + Send + Sync>,
+}
+
+/// PeerAddressPolicy decides which peer addresses clients may create permissions
+/// and channel bindings for.
+///
+/// By default peers in loopback, private, link-local (including cloud metadata
+/// services), shared, multicast and otherwise reserved ranges are denied so the
+/// server cannot be used to reach the network it is deployed in.
+#[derive(Default, Debug, Clone)]
+pub struct PeerAddressPolicy {
+    /// `allow` lists networks peers are allowed in, taking precedence over `deny`
+    /// and the default denied ranges.
+    pub allow: Vec<IpNet>,
+
+    /// `deny` lists networks peers are not allowed in.
+    pub deny: Vec<IpNet>,
+
+    /// `allow_internal` disables denying the non-public ranges by default.
+    pub allow_internal: bool,
+}
+
+impl PeerAddressPolicy {
+    /// Returns true if clients may relay to and from `ip`.
+    pub fn is_allowed(&self, ip: IpAddr) -> bool {
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+            IpAddr::V4(_) => ip,
+        };
+
+        if self.allow.iter().any(|net| net.contains(&ip)) {
+            return true;
+        }
+        if self.deny.iter().any(|net| net.contains(&ip)) {
+            return false;
+        }
+
+        self.allow_internal || !is_internal(ip)
+    }
+}
+
+fn is_internal(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            v4.is_unspecified()
+                || v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_multicast()
+                || v4.is_broadcast()
+                // "This network" (RFC 791)
+                || a == 0
+                // Shared address space (RFC 6598)
+                || (a == 100 && (b & 0xc0) == 64)
+                // IETF protocol assignments (RFC 6890)
+                || v4.octets()[..3] == [192, 0, 0]
+                // Benchmarking (RFC 2544)
+                || (a == 198 && (b & 0xfe) == 18)
+                // Reserved (RFC 1112)
+                || a >= 240
+        }
+        IpAddr::V6(v6) => {
+            let first = v6.segments()[0];
+            v6.is_unspecified()
+                || v6.is_loopback()
+                || v6.is_multicast()
+                // Unique local (RFC 4193)
+                || (first & 0xfe00) == 0xfc00
+                // Link-local unicast
+                || (first & 0xffc0) == 0xfe80
+                // Reaching an internal IPv4 address through a transition mechanism
+                || embedded_ipv4(v6).is_some_and(|v4| is_internal(IpAddr::V4(v4)))
+        }
+    }
+}
+
+/// Returns the IPv4 address packets to `v6` are delivered to by IPv4-compatible
+/// (RFC 4291), NAT64 (RFC 6052), 6to4 (RFC 3056) and Teredo (RFC 4380) addresses.
+fn embedded_ipv4(v6: Ipv6Addr) -> Option<Ipv4Addr> {
+    let low = u128::from(v6) as u32;
+    match v6.segments() {
+        [0, 0, 0, 0, 0, 0, _, _] | [0x64, 0xff9b, 0, 0, 0, 0, _, _] => Some(Ipv4Addr::from(low)),
+        [0x2002, high, low, ..] => Some(Ipv4Addr::from((high as u32) << 16 | low as u32)),
+        // The mapped address of the Teredo client is stored inverted
+        [0x2001, 0, ..] => Some(Ipv4Addr::from(!low)),
+        _ => None,
+    }
+}
+
+/// Quota limits the allocations, their lifetime and the relayed bandwidth of a
+/// user or the whole server. Zero values mean no limit.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quota {
+    /// `max_allocations` is the maximum number of concurrent allocations.
+    pub max_allocations: usize,
+
+    /// `max_bandwidth` is the maximum relayed bandwidth in bytes per second,
+    /// counting both directions. Packets exceeding it are dropped.
+    pub max_bandwidth: usize,
+
+    /// `max_lifetime` caps the lifetime clients may request for allocations,
+    /// which can't exceed one hour anyway.
+    pub max_lifetime: Duration,
+}
+
+/// QuotaConfig configures the quotas enforced by the TURN server.
+/// The default config doesn't limit anything besides the allocation lifetime.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuotaConfig {
+    /// `user` is the quota of each username.
+    pub user: Quota,
+
+    /// `total` is the quota of the whole server, shared by all listeners.
+    pub total: Quota,
+}
+
 /// ServerConfig configures the TURN Server
 pub struct ServerConfig {
     /// `conn_configs` are a list of all the turn listeners.
@@ -42,6 +178,28 @@ pub struct ServerConfig {
 
     /// To receive notify on allocation close event, with metrics data.
     pub alloc_close_notify: Option<mpsc::Sender<AllocationInfo>>,
+
+    /// `peer_address_policy` decides which peers clients may relay to.
+    pub peer_address_policy: PeerAddressPolicy,
+
+    /// `quota` limits the allocations, their lifetime and the relayed bandwidth.
+    pub quota: QuotaConfig,
+
+    /// `relay_handler` is notified of every datagram relayed, with its size.
+    pub relay_handler: Option<Arc<dyn RelayHandler + Send + Sync>>,
+
+    /// `store` keeps the allocations, nonces and reservations, it can be shared
+    /// by several servers. Defaults to a [`MemoryAllocationStore`].
+    ///
+    /// [`MemoryAllocationStore`]: crate::allocation::store::MemoryAllocationStore
+    pub store: Option<Arc<dyn AllocationStore + Send + Sync>>,
+
+    /// `drain_timeout` is how long [`Server::close`] waits for the existing
+    /// allocations to expire, while refusing new ones, before closing them.
+    /// Defaults to 0, closing them right away.
+    ///
+    /// [`Server::close`]: super::Server::close
+    pub drain_timeout: Duration,
 }
 
 impl ServerConfig {
@@ -0,0 +1,147 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use stun::addr::{OtherAddress, ResponseOrigin};
+use stun::attributes::*;
+use stun::behavior::{ChangeRequest, ResponsePort};
+use stun::error_code::*;
+use stun::fingerprint::FINGERPRINT;
+use stun::message::*;
+use stun::uattrs::UnknownAttributes;
+use stun::xoraddr::XorMappedAddress;
+use util::Conn;
+
+use super::config::NatDiscoveryConfig;
+use super::request::{build_and_send, build_and_send_err, build_msg};
+use crate::error::*;
+
+/// `NatDiscovery` holds the listeners on the primary and the secondary IP
+/// address and port, used to answer NAT behavior discovery requests.
+///
+/// [RFC 5780 Section 6](https://www.rfc-editor.org/rfc/rfc5780#section-6)
+pub struct NatDiscovery {
+    // conns[ip][port], the primary IP address and port are at index 0.
+    conns: [[Arc<dyn Conn + Send + Sync>; 2]; 2],
+}
+
+impl NatDiscovery {
+    pub(crate) fn new(primary: Arc<dyn Conn + Send + Sync>, config: NatDiscoveryConfig) -> Self {
+        NatDiscovery {
+            conns: [
+                [primary, config.alternate_port_conn],
+                [config.alternate_ip_conn, config.alternate_conn],
+            ],
+        }
+    }
+
+    /// Returns the listeners other than the primary one.
+    pub(crate) fn alternate_conns(&self) -> Vec<Arc<dyn Conn + Send + Sync>> {
+        vec![
+            Arc::clone(&self.conns[0][1]),
+            Arc::clone(&self.conns[1][0]),
+            Arc::clone(&self.conns[1][1]),
+        ]
+    }
+
+    /// Returns the listener the response to a request received on
+    /// `local_addr` is sent from, and the OTHER-ADDRESS of that request.
+    fn select(
+        &self,
+        local_addr: SocketAddr,
+        change: ChangeRequest,
+    ) -> Result<(Arc<dyn Conn + Send + Sync>, SocketAddr)> {
+        for ip in 0..2 {
+            for port in 0..2 {
+                if self.conns[ip][port].local_addr()? != local_addr {
+                    continue;
+                }
+
+                let from_ip = ip ^ usize::from(change.change_ip);
+                let from_port = port ^ usize::from(change.change_port);
+                return Ok((
+                    Arc::clone(&self.conns[from_ip][from_port]),
+                    self.conns[1 - ip][1 - port].local_addr()?,
+                ));
+            }
+        }
+
+        Err(Error::ErrNatDiscoveryListenerNotFound)
+    }
+}
+
+/// Answers the Binding request `m` received on `conn`, honoring the
+/// CHANGE-REQUEST and RESPONSE-PORT attributes.
+///
+/// [RFC 5780 Section 6.1](https://www.rfc-editor.org/rfc/rfc5780#section-6.1)
+pub(crate) async fn handle_binding_request(
+    conn: &Arc<dyn Conn + Send + Sync>,
+    src_addr: SocketAddr,
+    m: &Message,
+    nat_discovery: Option<&NatDiscovery>,
+) -> Result<()> {
+    let mut change = ChangeRequest::default();
+    let has_change = change.get_from(m).is_ok();
+    let mut response_port = ResponsePort::default();
+    let has_response_port = response_port.get_from(m).is_ok();
+    if nat_discovery.is_none() && (has_change || has_response_port) {
+        // Without a secondary address the request can't be honored, and
+        // RESPONSE-PORT is only for NAT behavior discovery.
+        let mut unknown = vec![];
+        if has_change {
+            unknown.push(ATTR_CHANGE_REQUEST);
+        }
+        if has_response_port {
+            unknown.push(ATTR_RESPONSE_PORT);
+        }
+        let msg = build_msg(
+            m.transaction_id,
+            BINDING_ERROR,
+            vec![
+                Box::new(ErrorCodeAttribute {
+                    code: CODE_UNKNOWN_ATTRIBUTE,
+                    reason: vec![],
+                }),
+                Box::new(UnknownAttributes(unknown)),
+            ],
+        )?;
+        let err = if has_change {
+            Error::ErrChangeRequestNotSupported
+        } else {
+            Error::ErrResponsePortNotSupported
+        };
+        return build_and_send_err(conn, src_addr, msg, err).await;
+    }
+
+    let mut dst = src_addr;
+    if has_response_port {
+        dst.set_port(response_port.0);
+    }
+
+    let (ip, port) = (src_addr.ip(), src_addr.port());
+    let mut msg = build_msg(
+        m.transaction_id,
+        BINDING_SUCCESS,
+        vec![Box::new(XorMappedAddress { ip, port })],
+    )?;
+
+    let from = if let Some(nat_discovery) = nat_discovery {
+        let (from, other_addr) = nat_discovery.select(conn.local_addr()?, change)?;
+        let origin_addr = from.local_addr()?;
+        ResponseOrigin {
+            ip: origin_addr.ip(),
+            port: origin_addr.port(),
+        }
+        .add_to_as(&mut msg, ATTR_RESPONSE_ORIGIN)?;
+        OtherAddress {
+            ip: other_addr.ip(),
+            port: other_addr.port(),
+        }
+        .add_to_as(&mut msg, ATTR_OTHER_ADDRESS)?;
+        from
+    } else {
+        Arc::clone(conn)
+    };
+    FINGERPRINT.add_to(&mut msg)?;
+
+    build_and_send(&from, dst, msg).await
+}
@@ -2,34 +2,49 @@
 mod server_test;
 
 pub mod config;
+pub mod discovery;
 pub mod request;
 
 use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::sync::Arc;
 
 use config::*;
+use discovery::NatDiscovery;
 use request::*;
+use stun::message::{is_message, Message, MessageType, BINDING_REQUEST, CLASS_REQUEST};
 use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::broadcast::{self};
-use tokio::sync::{mpsc, oneshot, Mutex};
+use tokio::sync::mpsc::error::{TryRecvError, TrySendError};
+use tokio::sync::{mpsc, oneshot, Mutex, OwnedSemaphorePermit, Semaphore};
 use tokio::time::{Duration, Instant};
 use util::Conn;
 
 use crate::allocation::allocation_manager::*;
 use crate::allocation::five_tuple::FiveTuple;
+use crate::allocation::quota::QuotaTracker;
+use crate::allocation::store::{AllocationStore, MemoryAllocationStore};
 use crate::allocation::AllocationInfo;
 use crate::auth::AuthHandler;
 use crate::error::*;
 use crate::proto::lifetime::DEFAULT_LIFETIME;
 
 const INBOUND_MTU: usize = 1500;
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Maximum number of clients of a listener with a request in progress.
+const MAX_BUSY_CLIENTS: usize = 64;
+/// Maximum number of datagrams queued behind the request in progress of a
+/// client, more are dropped.
+const MAX_QUEUED_DATAGRAMS: usize = 32;
 
 /// Server is an instance of the TURN Server
 pub struct Server {
     auth_handler: Arc<dyn AuthHandler + Send + Sync>,
     realm: String,
     channel_bind_timeout: Duration,
-    pub(crate) nonces: Arc<Mutex<HashMap<String, Instant>>>,
+    peer_address_policy: Arc<PeerAddressPolicy>,
+    pub(crate) store: Arc<dyn AllocationStore + Send + Sync>,
+    drain_timeout: Duration,
     command_tx: Mutex<Option<broadcast::Sender<Command>>>,
 }
 
@@ -43,7 +58,11 @@ impl Server {
             auth_handler: config.auth_handler,
             realm: config.realm,
             channel_bind_timeout: config.channel_bind_timeout,
-            nonces: Arc::new(Mutex::new(HashMap::new())),
+            peer_address_policy: Arc::new(config.peer_address_policy),
+            store: config
+                .store
+                .unwrap_or_else(|| Arc::new(MemoryAllocationStore::default())),
+            drain_timeout: config.drain_timeout,
             command_tx: Mutex::new(Some(command_tx.clone())),
         };
 
@@ -51,25 +70,44 @@ impl Server {
             s.channel_bind_timeout = DEFAULT_LIFETIME;
         }
 
+        let quota = Arc::new(QuotaTracker::new(config.quota));
         for p in config.conn_configs.into_iter() {
-            let nonces = Arc::clone(&s.nonces);
+            let store = Arc::clone(&s.store);
             let auth_handler = Arc::clone(&s.auth_handler);
             let realm = s.realm.clone();
             let channel_bind_timeout = s.channel_bind_timeout;
+            let peer_address_policy = Arc::clone(&s.peer_address_policy);
             let handle_rx = command_tx.subscribe();
             let conn = p.conn;
+            let nat_discovery = p
+                .nat_discovery
+                .map(|c| Arc::new(NatDiscovery::new(Arc::clone(&conn), c)));
+            if let Some(nat_discovery) = &nat_discovery {
+                for alternate_conn in nat_discovery.alternate_conns() {
+                    tokio::spawn(Server::discovery_loop(
+                        alternate_conn,
+                        Arc::clone(nat_discovery),
+                        command_tx.subscribe(),
+                    ));
+                }
+            }
             let allocation_manager = Arc::new(Manager::new(ManagerConfig {
                 relay_addr_generator: p.relay_addr_generator,
                 alloc_close_notify: config.alloc_close_notify.clone(),
+                quota: Arc::clone(&quota),
+                relay_handler: config.relay_handler.clone(),
+                store: Arc::clone(&store),
             }));
 
             tokio::spawn(Server::read_loop(
                 conn,
                 allocation_manager,
-                nonces,
+                store,
                 auth_handler,
                 realm,
                 channel_bind_timeout,
+                peer_address_policy,
+                nat_discovery,
                 handle_rx,
             ));
         }
@@ -140,16 +178,21 @@ impl Server {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn read_loop(
         conn: Arc<dyn Conn + Send + Sync>,
         allocation_manager: Arc<Manager>,
-        nonces: Arc<Mutex<HashMap<String, Instant>>>,
+        store: Arc<dyn AllocationStore + Send + Sync>,
         auth_handler: Arc<dyn AuthHandler + Send + Sync>,
         realm: String,
         channel_bind_timeout: Duration,
+        peer_address_policy: Arc<PeerAddressPolicy>,
+        nat_discovery: Option<Arc<NatDiscovery>>,
         mut handle_rx: broadcast::Receiver<Command>,
     ) {
         let mut buf = vec![0u8; INBOUND_MTU];
+        let busy_clients = Arc::new(Semaphore::new(MAX_BUSY_CLIENTS));
+        let mut clients: HashMap<SocketAddr, mpsc::Sender<Request>> = HashMap::new();
 
         let (mut close_tx, mut close_rx) = oneshot::channel::<()>();
 
@@ -165,6 +208,10 @@ impl Server {
                                 .await;
                             continue;
                         }
+                        Ok(Command::Drain) => {
+                            allocation_manager.drain();
+                            continue;
+                        }
                         Ok(Command::GetAllocationsInfo(five_tuples, tx)) => {
                             let infos = allocation_manager.get_allocations_info(five_tuples).await;
                             let _ = tx.send(infos).await;
@@ -198,28 +245,169 @@ impl Server {
                 _ = close_tx.closed() => break
             };
 
-            let mut r = Request {
+            let r = Request {
                 conn: Arc::clone(&conn),
                 src_addr: addr,
                 buff: buf[..n].to_vec(),
                 allocation_manager: Arc::clone(&allocation_manager),
-                nonces: Arc::clone(&nonces),
+                store: Arc::clone(&store),
                 auth_handler: Arc::clone(&auth_handler),
                 realm: realm.clone(),
                 channel_bind_timeout,
+                peer_address_policy: Arc::clone(&peer_address_policy),
+                nat_discovery: nat_discovery.clone(),
+            };
+
+            // Each client is served by its own task while it has a request in
+            // progress, its later datagrams queue up behind the request so
+            // that they are handled in order. Other datagrams are handled
+            // right away.
+            let mut r = match clients.get(&addr) {
+                Some(tx) => match tx.try_send(r) {
+                    Ok(()) => continue,
+                    Err(TrySendError::Full(_)) => {
+                        log::debug!("dropping datagram from {}: too many queued", addr);
+                        continue;
+                    }
+                    Err(TrySendError::Closed(r)) => r,
+                },
+                None => r,
             };
+            if !is_request(&r.buff) {
+                if let Err(err) = r.handle_request().await {
+                    log::error!("error when handling datagram: {}", err);
+                }
+                continue;
+            }
 
+            let permit = match Arc::clone(&busy_clients).acquire_owned().await {
+                Ok(permit) => permit,
+                Err(_) => break,
+            };
+            clients.retain(|_, tx| !tx.is_closed());
+            let (tx, rx) = mpsc::channel(MAX_QUEUED_DATAGRAMS);
+            clients.insert(addr, tx);
+            tokio::spawn(Self::serve_client(r, rx, permit));
+        }
+
+        let _ = allocation_manager.close().await;
+        let _ = conn.close().await;
+    }
+
+    /// Handles the request `r` of a client, then the datagrams of the client
+    /// queued meanwhile, until there are none left.
+    async fn serve_client(
+        mut r: Request,
+        mut rx: mpsc::Receiver<Request>,
+        _permit: OwnedSemaphorePermit,
+    ) {
+        loop {
             if let Err(err) = r.handle_request().await {
                 log::error!("error when handling datagram: {}", err);
             }
+
+            r = match rx.try_recv() {
+                Ok(r) => r,
+                Err(TryRecvError::Empty) => {
+                    // Datagrams queued before closing are still handled.
+                    rx.close();
+                    match rx.try_recv() {
+                        Ok(r) => r,
+                        Err(_) => break,
+                    }
+                }
+                Err(TryRecvError::Disconnected) => break,
+            };
+        }
+    }
+
+    /// Answers the Binding requests received on a listener used only for NAT
+    /// behavior discovery.
+    async fn discovery_loop(
+        conn: Arc<dyn Conn + Send + Sync>,
+        nat_discovery: Arc<NatDiscovery>,
+        mut handle_rx: broadcast::Receiver<Command>,
+    ) {
+        let mut buf = vec![0u8; INBOUND_MTU];
+
+        loop {
+            let (n, addr) = tokio::select! {
+                v = conn.recv_from(&mut buf) => {
+                    match v {
+                        Ok(v) => v,
+                        Err(err) => {
+                            log::debug!("exit discovery loop on error: {}", err);
+                            break;
+                        }
+                    }
+                },
+                command = handle_rx.recv() => {
+                    match command {
+                        Ok(Command::GetAllocationsInfo(_, tx)) => {
+                            // There are no allocations on this listener.
+                            let _ = tx.send(HashMap::new()).await;
+                            continue;
+                        }
+                        Err(RecvError::Closed) | Ok(Command::Close(_)) => break,
+                        Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    }
+                }
+            };
+
+            let mut m = Message {
+                raw: buf[..n].to_vec(),
+                ..Default::default()
+            };
+            if m.decode().is_err() || m.typ != BINDING_REQUEST {
+                continue;
+            }
+
+            if let Err(err) =
+                discovery::handle_binding_request(&conn, addr, &m, Some(&nat_discovery)).await
+            {
+                log::error!("error when handling binding request: {}", err);
+            }
         }
 
-        let _ = allocation_manager.close().await;
         let _ = conn.close().await;
     }
 
+    /// Refuses new [`Allocation`][`Allocation`]s and waits up to `drain_timeout`
+    /// for the existing ones to expire.
+    ///
+    /// [`Allocation`]: crate::allocation::Allocation
+    async fn drain(&self) -> Result<()> {
+        let tx = {
+            let command_tx = self.command_tx.lock().await;
+            command_tx.clone()
+        };
+        let Some(tx) = tx else {
+            return Err(Error::ErrClosed);
+        };
+        tx.send(Command::Drain).map_err(|_| Error::ErrClosed)?;
+
+        let deadline = Instant::now() + self.drain_timeout;
+        while Instant::now() < deadline {
+            if self.get_allocations_info(None).await?.is_empty() {
+                break;
+            }
+            tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+        }
+
+        Ok(())
+    }
+
     /// Close stops the TURN Server. It cleans up any associated state and closes all connections it is managing.
+    ///
+    /// If `drain_timeout` is configured, new allocations are refused while the
+    /// existing ones are given that long to expire.
     pub async fn close(&self) -> Result<()> {
+        if self.drain_timeout > Duration::from_secs(0) {
+            if let Err(err) = self.drain().await {
+                log::debug!("failed to drain server: {}", err);
+            }
+        }
+
         let tx = {
             let mut command_tx = self.command_tx.lock().await;
             command_tx.take()
@@ -256,6 +444,21 @@ enum Command {
         mpsc::Sender<HashMap<FiveTuple, AllocationInfo>>,
     ),
 
+    /// Command to stop creating new [`Allocation`][`Allocation`]s.
+    ///
+    /// [`Allocation`]: `crate::allocation::Allocation`
+    Drain,
+
     /// Command to close the [`Server`].
     Close(Arc<mpsc::Receiver<()>>),
 }
+
+/// Returns whether `buf` is a STUN request, as opposed to relayed data.
+fn is_request(buf: &[u8]) -> bool {
+    if !is_message(buf) {
+        return false;
+    }
+    let mut typ = MessageType::default();
+    typ.read_value(u16::from_be_bytes([buf[0], buf[1]]));
+    typ.class == CLASS_REQUEST
+}
@@ -1,7 +1,6 @@
 #[cfg(test)]
 mod request_test;
 
-use std::collections::HashMap;
 use std::marker::{Send, Sync};
 use std::net::SocketAddr;
 #[cfg(feature = "metrics")]
@@ -13,22 +12,24 @@ use md5::{Digest, Md5};
 use stun::agent::*;
 use stun::attributes::*;
 use stun::error_code::*;
-use stun::fingerprint::*;
 use stun::integrity::*;
 use stun::message::*;
 use stun::textattrs::*;
 use stun::uattrs::*;
 use stun::xoraddr::*;
-use tokio::sync::Mutex;
-use tokio::time::{Duration, Instant};
+use tokio::time::Duration;
 use util::Conn;
 
 use crate::allocation::allocation_manager::*;
 use crate::allocation::channel_bind::ChannelBind;
 use crate::allocation::five_tuple::*;
 use crate::allocation::permission::Permission;
+use crate::allocation::store::{AllocationStore, MemoryAllocationStore};
+use crate::allocation::RelayDirection;
 use crate::auth::*;
 use crate::error::*;
+use crate::proto::addfamily::AdditionalAddressFamily;
+use crate::proto::addrerror::AddressErrorCode;
 use crate::proto::chandata::ChannelData;
 use crate::proto::channum::ChannelNumber;
 use crate::proto::data::Data;
@@ -42,6 +43,8 @@ use crate::proto::reqfamily::{
 use crate::proto::reqtrans::RequestedTransport;
 use crate::proto::rsrvtoken::ReservationToken;
 use crate::proto::*;
+use crate::server::config::PeerAddressPolicy;
+use crate::server::discovery::{self, NatDiscovery};
 
 pub(crate) const MAXIMUM_ALLOCATION_LIFETIME: Duration = Duration::from_secs(3600); // https://tools.ietf.org/html/rfc5766#section-6.2 defines 3600 seconds recommendation
 pub(crate) const NONCE_LIFETIME: Duration = Duration::from_secs(3600); // https://tools.ietf.org/html/rfc5766#section-4
@@ -55,12 +58,14 @@ pub struct Request {
 
     // Server State
     pub allocation_manager: Arc<Manager>,
-    pub nonces: Arc<Mutex<HashMap<String, Instant>>>,
+    pub store: Arc<dyn AllocationStore + Send + Sync>,
 
     // User Configuration
     pub auth_handler: Arc<dyn AuthHandler + Send + Sync>,
     pub realm: String,
     pub channel_bind_timeout: Duration,
+    pub peer_address_policy: Arc<PeerAddressPolicy>,
+    pub nat_discovery: Option<Arc<NatDiscovery>>,
 }
 
 impl Request {
@@ -75,10 +80,12 @@ impl Request {
             src_addr,
             buff: vec![],
             allocation_manager,
-            nonces: Arc::new(Mutex::new(HashMap::new())),
+            store: Arc::new(MemoryAllocationStore::default()),
             auth_handler,
             realm: String::new(),
             channel_bind_timeout: Duration::from_secs(0),
+            peer_address_policy: Arc::new(PeerAddressPolicy::default()),
+            nat_discovery: None,
         }
     }
 
@@ -143,7 +150,7 @@ impl Request {
         &mut self,
         m: &Message,
         calling_method: Method,
-    ) -> Result<Option<(Username, MessageIntegrity)>> {
+    ) -> Result<Option<(Username, MessageIntegrity, UserPolicy)>> {
         if !m.contains(ATTR_MESSAGE_INTEGRITY) {
             self.respond_with_nonce(m, calling_method, CODE_UNAUTHORIZED)
                 .await?;
@@ -167,24 +174,19 @@ impl Request {
             return Ok(None);
         }
 
-        let to_be_deleted = {
-            // Assert Nonce exists and is not expired
-            let mut nonces = self.nonces.lock().await;
-
-            let to_be_deleted = if let Some(nonce_creation_time) = nonces.get(&nonce_attr.text) {
-                Instant::now()
-                    .checked_duration_since(*nonce_creation_time)
-                    .unwrap_or_else(|| Duration::from_secs(0))
+        // Assert Nonce exists and is not expired
+        let to_be_deleted = match self.store.get_nonce(&nonce_attr.text).await {
+            Some(nonce_creation_time) => {
+                SystemTime::now()
+                    .duration_since(nonce_creation_time)
+                    .unwrap_or_default()
                     >= NONCE_LIFETIME
-            } else {
-                true
-            };
-
-            if to_be_deleted {
-                nonces.remove(&nonce_attr.text);
             }
-            to_be_deleted
+            None => true,
         };
+        if to_be_deleted {
+            self.store.remove_nonce(&nonce_attr.text).await;
+        }
 
         if to_be_deleted {
             self.respond_with_nonce(m, calling_method, CODE_STALE_NONCE)
@@ -201,12 +203,16 @@ impl Request {
             return Ok(None);
         }
 
-        let our_key = match self.auth_handler.auth_handle(
-            &username_attr.to_string(),
-            &realm_attr.to_string(),
-            self.src_addr,
-        ) {
-            Ok(key) => key,
+        let (our_key, policy) = match self
+            .auth_handler
+            .auth_policy(
+                &username_attr.to_string(),
+                &realm_attr.to_string(),
+                self.src_addr,
+            )
+            .await
+        {
+            Ok(v) => v,
             Err(_) => {
                 build_and_send_err(
                     &self.conn,
@@ -222,10 +228,49 @@ impl Request {
         let mi = MessageIntegrity(our_key);
         if let Err(err) = mi.check(&mut m.clone()) {
             build_and_send_err(&self.conn, self.src_addr, bad_request_msg, err.into()).await?;
-            Ok(None)
+            return Ok(None);
+        }
+
+        let err = if !policy.realms.is_empty() && !policy.realms.contains(&realm_attr.text) {
+            Some(Error::ErrRealmNotAllowed)
+        } else if policy
+            .expires
+            .is_some_and(|expires| expires <= SystemTime::now())
+        {
+            Some(Error::ErrCredentialsExpired)
         } else {
-            Ok(Some((username_attr, mi)))
+            None
+        };
+        if let Some(err) = err {
+            let unauthorized_msg = build_msg(
+                m.transaction_id,
+                MessageType::new(calling_method, CLASS_ERROR_RESPONSE),
+                vec![Box::new(ErrorCodeAttribute {
+                    code: CODE_UNAUTHORIZED,
+                    reason: vec![],
+                })],
+            )?;
+            build_and_send_err(&self.conn, self.src_addr, unauthorized_msg, err).await?;
+            return Ok(None);
         }
+
+        Ok(Some((username_attr, mi, policy)))
+    }
+
+    /// Returns the allocation lifetime requested by `m`, capped by the server
+    /// quotas and the expiry of the user's credentials.
+    fn requested_lifetime(&self, m: &Message, username: &str, policy: &UserPolicy) -> Duration {
+        let max_lifetime = self
+            .allocation_manager
+            .max_lifetime(username, policy.quota.as_ref());
+        let mut lifetime = allocation_lifetime(m).min(max_lifetime);
+        if let Some(expires) = policy.expires {
+            let remaining = expires
+                .duration_since(SystemTime::now())
+                .unwrap_or_default();
+            lifetime = lifetime.min(remaining);
+        }
+        lifetime
     }
 
     async fn respond_with_nonce(
@@ -236,14 +281,10 @@ impl Request {
     ) -> Result<()> {
         let nonce = build_nonce()?;
 
-        {
-            // Nonce has already been taken
-            let mut nonces = self.nonces.lock().await;
-            if nonces.contains_key(&nonce) {
-                return Err(Error::ErrDuplicatedNonce);
-            }
-            nonces.insert(nonce.clone(), Instant::now());
-        }
+        // Fails if the Nonce has already been taken
+        self.store
+            .insert_nonce(nonce.clone(), SystemTime::now())
+            .await?;
 
         let msg = build_msg(
             m.transaction_id,
@@ -264,18 +305,13 @@ impl Request {
     pub(crate) async fn handle_binding_request(&mut self, m: &Message) -> Result<()> {
         log::debug!("received BindingRequest from {}", self.src_addr);
 
-        let (ip, port) = (self.src_addr.ip(), self.src_addr.port());
-
-        let msg = build_msg(
-            m.transaction_id,
-            BINDING_SUCCESS,
-            vec![
-                Box::new(XorMappedAddress { ip, port }),
-                Box::new(FINGERPRINT),
-            ],
-        )?;
-
-        build_and_send(&self.conn, self.src_addr, msg).await
+        discovery::handle_binding_request(
+            &self.conn,
+            self.src_addr,
+            m,
+            self.nat_discovery.as_deref(),
+        )
+        .await
     }
 
     /// https://tools.ietf.org/html/rfc5766#section-6.2
@@ -287,7 +323,7 @@ impl Request {
         //    mechanism of [https://tools.ietf.org/html/rfc5389#section-10.2.2]
         //    unless the client and server agree to use another mechanism through
         //    some procedure outside the scope of this document.
-        let (username, message_integrity) =
+        let (username, message_integrity, policy) =
             if let Some(mi) = self.authenticate_request(m, METHOD_ALLOCATE).await? {
                 mi
             } else {
@@ -482,6 +518,45 @@ impl Request {
             }
         }
 
+        // RFC 8656, Section 7.2:
+        //
+        // If the request contains an ADDITIONAL-ADDRESS-FAMILY attribute along
+        // with a REQUESTED-ADDRESS-FAMILY or a RESERVATION-TOKEN attribute, or
+        // if the attribute specifies the IPv4 address family, the server
+        // replies with a 400 (Bad Request) Allocate error response. Otherwise
+        // it allocates both an IPv4 and an IPv6 relayed transport address.
+        let mut dual_stack = false;
+        let mut additional_family = AdditionalAddressFamily::default();
+        match additional_family.get_from(m) {
+            Err(stun::Error::ErrAttributeNotFound) => {}
+            result => {
+                let err = if result.is_err() {
+                    Some(Error::ErrInvalidAdditionalFamilyValue)
+                } else if m.contains(ATTR_REQUESTED_ADDRESS_FAMILY) {
+                    Some(Error::ErrRequestWithReqAndAdditionalAddressFamily)
+                } else if reservation_token_attr_result.is_ok() {
+                    Some(Error::ErrRequestWithReservationTokenAndAdditionalAddressFamily)
+                } else {
+                    None
+                };
+
+                if let Some(err) = err {
+                    let bad_request_msg = build_msg(
+                        m.transaction_id,
+                        MessageType::new(METHOD_ALLOCATE, CLASS_ERROR_RESPONSE),
+                        vec![Box::new(ErrorCodeAttribute {
+                            code: CODE_BAD_REQUEST,
+                            reason: vec![],
+                        })],
+                    )?;
+                    return build_and_send_err(&self.conn, self.src_addr, bad_request_msg, err)
+                        .await;
+                }
+
+                dual_stack = true;
+            }
+        }
+
         // 6. The server checks if the request contains an EVEN-PORT attribute.
         //    If yes, then the server checks that it can satisfy the request
         //    (i.e., can allocate a relayed transport address as described
@@ -530,36 +605,53 @@ impl Request {
         //    with a 300 (Try Alternate) error if it wishes to redirect the
         //    client to a different server.  The use of this error code and
         //    attribute follow the specification in [RFC5389].
-        let lifetime_duration = allocation_lifetime(m);
-        let a = match self
-            .allocation_manager
-            .create_allocation(
-                five_tuple,
-                Arc::clone(&self.conn),
-                requested_port,
-                lifetime_duration,
-                username,
-                use_ipv4,
-            )
-            .await
-        {
-            Ok(a) => a,
+        if let Some(quota) = policy.quota {
+            self.allocation_manager
+                .set_user_quota(&username.text, quota);
+        }
+
+        let lifetime_duration = self.requested_lifetime(m, &username.text, &policy);
+        let result = if dual_stack {
+            self.allocation_manager
+                .create_dual_stack_allocation(
+                    five_tuple,
+                    Arc::clone(&self.conn),
+                    requested_port,
+                    lifetime_duration,
+                    username.clone(),
+                )
+                .await
+        } else {
+            self.allocation_manager
+                .create_allocation(
+                    five_tuple,
+                    Arc::clone(&self.conn),
+                    requested_port,
+                    lifetime_duration,
+                    username.clone(),
+                    use_ipv4,
+                )
+                .await
+                .map(|a| (a, None))
+        };
+        let (a, additional_err) = match result {
+            Ok(v) => v,
             Err(err) => {
-                let insufficient_capacity_msg = build_msg(
+                let code = match err {
+                    Error::ErrAllocationQuotaReached => CODE_ALLOC_QUOTA_REACHED,
+                    // Another server sharing the store holds the 5-tuple.
+                    Error::ErrDupeFiveTuple => CODE_ALLOC_MISMATCH,
+                    _ => CODE_INSUFFICIENT_CAPACITY,
+                };
+                let err_msg = build_msg(
                     m.transaction_id,
                     MessageType::new(METHOD_ALLOCATE, CLASS_ERROR_RESPONSE),
                     vec![Box::new(ErrorCodeAttribute {
-                        code: CODE_INSUFFICIENT_CAPACITY,
+                        code,
                         reason: vec![],
                     })],
                 )?;
-                return build_and_send_err(
-                    &self.conn,
-                    self.src_addr,
-                    insufficient_capacity_msg,
-                    err,
-                )
-                .await;
+                return build_and_send_err(&self.conn, self.src_addr, err_msg, err).await;
             }
         };
 
@@ -603,6 +695,26 @@ impl Request {
                 )));
             }
 
+            // A dual-stack allocation carries the IPv6 relayed transport address
+            // too, or the reason it couldn't be allocated. [RFC 8656, Section 7.2]
+            if let Some(additional_relay_addr) = a.additional_relay_addr {
+                response_attrs.push(Box::new(RelayedAddress {
+                    ip: additional_relay_addr.ip(),
+                    port: additional_relay_addr.port(),
+                }));
+            } else if let Some(err) = additional_err {
+                let code = if err == Error::ErrMaxRetriesExceeded {
+                    CODE_INSUFFICIENT_CAPACITY
+                } else {
+                    CODE_ADDR_FAMILY_NOT_SUPPORTED
+                };
+                response_attrs.push(Box::new(AddressErrorCode {
+                    family: REQUESTED_FAMILY_IPV6,
+                    code,
+                    reason: vec![],
+                }));
+            }
+
             response_attrs.push(Box::new(message_integrity));
             build_msg(
                 m.transaction_id,
@@ -617,7 +729,7 @@ impl Request {
     pub(crate) async fn handle_refresh_request(&mut self, m: &Message) -> Result<()> {
         log::debug!("received RefreshRequest from {}", self.src_addr);
 
-        let (_, message_integrity) =
+        let (username, message_integrity, policy) =
             if let Some(mi) = self.authenticate_request(m, METHOD_REFRESH).await? {
                 mi
             } else {
@@ -625,7 +737,7 @@ impl Request {
                 return Ok(());
             };
 
-        let lifetime_duration = allocation_lifetime(m);
+        let lifetime_duration = self.requested_lifetime(m, &username.text, &policy);
         let five_tuple = FiveTuple {
             src_addr: self.src_addr,
             dst_addr: self.conn.local_addr()?,
@@ -641,8 +753,8 @@ impl Request {
                 // Address Family Mismatch) Refresh error response. [RFC 6156, Section 5.2]
                 let mut req_family = RequestedAddressFamily::default();
                 if req_family.get_from(m).is_ok()
-                    && ((req_family == REQUESTED_FAMILY_IPV6 && !a.relay_addr.is_ipv6())
-                        || (req_family == REQUESTED_FAMILY_IPV4 && !a.relay_addr.is_ipv4()))
+                    && ((req_family == REQUESTED_FAMILY_IPV6 && !a.has_relay_family(false))
+                        || (req_family == REQUESTED_FAMILY_IPV4 && !a.has_relay_family(true)))
                 {
                     let peer_address_family_mismatch_msg = build_msg(
                         m.transaction_id,
@@ -661,6 +773,11 @@ impl Request {
                     .await;
                 }
                 a.refresh(lifetime_duration).await;
+
+                if let Some(quota) = policy.quota {
+                    self.allocation_manager
+                        .set_user_quota(&username.text, quota);
+                }
             } else {
                 return Err(Error::ErrNoAllocationFound);
             }
@@ -693,7 +810,7 @@ impl Request {
             .await;
 
         if let Some(a) = a {
-            let (_, message_integrity) = if let Some(mi) = self
+            let (_, message_integrity, _) = if let Some(mi) = self
                 .authenticate_request(m, METHOD_CREATE_PERMISSION)
                 .await?
             {
@@ -720,9 +837,7 @@ impl Request {
                     // family different than that of the relayed transport address for the
                     // allocation, the server MUST generate an error response with the 443
                     // (Peer Address Family Mismatch) response code. [RFC 6156, Section 6.2]
-                    if (peer_address.ip.is_ipv4() && !a.relay_addr.is_ipv4())
-                        || (peer_address.ip.is_ipv6() && !a.relay_addr.is_ipv6())
-                    {
+                    if !a.has_relay_family(peer_address.ip.is_ipv4()) {
                         let peer_address_family_mismatch_msg = build_msg(
                             m.transaction_id,
                             MessageType::new(METHOD_CREATE_PERMISSION, CLASS_ERROR_RESPONSE),
@@ -740,6 +855,24 @@ impl Request {
                         .await;
                     }
 
+                    if !self.peer_address_policy.is_allowed(peer_address.ip) {
+                        let forbidden_msg = build_msg(
+                            m.transaction_id,
+                            MessageType::new(METHOD_CREATE_PERMISSION, CLASS_ERROR_RESPONSE),
+                            vec![Box::new(ErrorCodeAttribute {
+                                code: CODE_FORBIDDEN,
+                                reason: vec![],
+                            })],
+                        )?;
+                        return build_and_send_err(
+                            &self.conn,
+                            self.src_addr,
+                            forbidden_msg,
+                            Error::ErrPeerAddressForbidden,
+                        )
+                        .await;
+                    }
+
                     log::debug!(
                         "adding permission for {}",
                         format!("{}:{}", peer_address.ip, peer_address.port)
@@ -797,10 +930,25 @@ impl Request {
                 return Err(Error::ErrNoPermission);
             }
 
-            let l = a.relay_socket.send_to(&data_attr.0, msg_dst).await?;
+            if !a.consume_bandwidth(data_attr.0.len()) {
+                log::debug!(
+                    "bandwidth quota exceeded, dropping {} bytes to {}",
+                    data_attr.0.len(),
+                    msg_dst
+                );
+                return Ok(());
+            }
+
+            let relay_socket = match a.relay_for(&msg_dst) {
+                Some((relay_socket, _)) => relay_socket,
+                None => return Err(Error::ErrPeerAddressFamilyMismatch),
+            };
+            let l = relay_socket.send_to(&data_attr.0, msg_dst).await?;
             if l != data_attr.0.len() {
                 Err(Error::ErrShortWrite)
             } else {
+                a.report_relay(msg_dst, RelayDirection::ClientToPeer, l);
+
                 #[cfg(feature = "metrics")]
                 a.relayed_bytes
                     .fetch_add(data_attr.0.len(), Ordering::AcqRel);
@@ -834,7 +982,7 @@ impl Request {
                 })],
             )?;
 
-            let (_, message_integrity) =
+            let (_, message_integrity, _) =
                 if let Some(mi) = self.authenticate_request(m, METHOD_CHANNEL_BIND).await? {
                     mi
                 } else {
@@ -863,9 +1011,7 @@ impl Request {
                     // family different than that of the relayed transport address for the
                     // allocation, the server MUST generate an error response with the 443
                     // (Peer Address Family Mismatch) response code. [RFC 6156, Section 7.2]
-                    if (peer_addr.ip.is_ipv4() && !a.relay_addr.is_ipv4())
-                        || (peer_addr.ip.is_ipv6() && !a.relay_addr.is_ipv6())
-                    {
+                    if !a.has_relay_family(peer_addr.ip.is_ipv4()) {
                         let peer_address_family_mismatch_msg = build_msg(
                             m.transaction_id,
                             MessageType::new(METHOD_CHANNEL_BIND, CLASS_ERROR_RESPONSE),
@@ -882,6 +1028,24 @@ impl Request {
                         )
                         .await;
                     }
+
+                    if !self.peer_address_policy.is_allowed(peer_addr.ip) {
+                        let forbidden_msg = build_msg(
+                            m.transaction_id,
+                            MessageType::new(METHOD_CHANNEL_BIND, CLASS_ERROR_RESPONSE),
+                            vec![Box::new(ErrorCodeAttribute {
+                                code: CODE_FORBIDDEN,
+                                reason: vec![],
+                            })],
+                        )?;
+                        return build_and_send_err(
+                            &self.conn,
+                            self.src_addr,
+                            forbidden_msg,
+                            Error::ErrPeerAddressForbidden,
+                        )
+                        .await;
+                    }
                 }
             }
 
@@ -928,10 +1092,25 @@ impl Request {
         if let Some(a) = a {
             let channel = a.get_channel_addr(&c.number).await;
             if let Some(peer) = channel {
-                let l = a.relay_socket.send_to(&c.data, peer).await?;
+                if !a.consume_bandwidth(c.data.len()) {
+                    log::debug!(
+                        "bandwidth quota exceeded, dropping {} bytes to {}",
+                        c.data.len(),
+                        peer
+                    );
+                    return Ok(());
+                }
+
+                let relay_socket = match a.relay_for(&peer) {
+                    Some((relay_socket, _)) => relay_socket,
+                    None => return Err(Error::ErrPeerAddressFamilyMismatch),
+                };
+                let l = relay_socket.send_to(&c.data, peer).await?;
                 if l != c.data.len() {
                     Err(Error::ErrShortWrite)
                 } else {
+                    a.report_relay(peer, RelayDirection::ClientToPeer, l);
+
                     #[cfg(feature = "metrics")]
                     a.relayed_bytes.fetch_add(c.data.len(), Ordering::AcqRel);
 
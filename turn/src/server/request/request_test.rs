@@ -1,12 +1,15 @@
 use std::net::IpAddr;
 use std::str::FromStr;
 
+use async_trait::async_trait;
 use tokio::net::UdpSocket;
-use tokio::time::{Duration, Instant};
+use tokio::time::Duration;
 use util::vnet::net::*;
 
 use super::*;
+use crate::allocation::quota::QuotaTracker;
 use crate::relay::relay_none::*;
+use crate::server::config::Quota;
 
 const STATIC_KEY: &str = "ABC";
 
@@ -50,8 +53,14 @@ async fn test_allocation_lifetime_overflow() -> Result<()> {
 }
 
 struct TestAuthHandler;
+#[async_trait]
 impl AuthHandler for TestAuthHandler {
-    fn auth_handle(&self, _username: &str, _realm: &str, _src_addr: SocketAddr) -> Result<Vec<u8>> {
+    async fn auth_handle(
+        &self,
+        _username: &str,
+        _realm: &str,
+        _src_addr: SocketAddr,
+    ) -> Result<Vec<u8>> {
         Ok(STATIC_KEY.as_bytes().to_vec())
     }
 }
@@ -68,16 +77,18 @@ async fn test_allocation_lifetime_deletion_zero_lifetime() -> Result<()> {
             net: Arc::new(Net::new(None)),
         }),
         alloc_close_notify: None,
+        quota: Arc::new(QuotaTracker::default()),
+        relay_handler: None,
+        store: Arc::new(MemoryAllocationStore::default()),
     }));
 
     let socket = SocketAddr::new(IpAddr::from_str("127.0.0.1")?, 5000);
 
     let mut r = Request::new(l, socket, allocation_manager, Arc::new(TestAuthHandler {}));
 
-    {
-        let mut nonces = r.nonces.lock().await;
-        nonces.insert(STATIC_KEY.to_owned(), Instant::now());
-    }
+    r.store
+        .insert_nonce(STATIC_KEY.to_owned(), SystemTime::now())
+        .await?;
 
     let five_tuple = FiveTuple {
         src_addr: r.src_addr,
@@ -117,3 +128,228 @@ async fn test_allocation_lifetime_deletion_zero_lifetime() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_create_permission_forbidden_peer() -> Result<()> {
+    let l = Arc::new(UdpSocket::bind("0.0.0.0:0").await?);
+
+    let allocation_manager = Arc::new(Manager::new(ManagerConfig {
+        relay_addr_generator: Box::new(RelayAddressGeneratorNone {
+            address: "0.0.0.0".to_owned(),
+            net: Arc::new(Net::new(None)),
+        }),
+        alloc_close_notify: None,
+        quota: Arc::new(QuotaTracker::default()),
+        relay_handler: None,
+        store: Arc::new(MemoryAllocationStore::default()),
+    }));
+
+    let socket = SocketAddr::new(IpAddr::from_str("127.0.0.1")?, 5000);
+
+    let mut r = Request::new(l, socket, allocation_manager, Arc::new(TestAuthHandler {}));
+
+    r.store
+        .insert_nonce(STATIC_KEY.to_owned(), SystemTime::now())
+        .await?;
+
+    let five_tuple = FiveTuple {
+        src_addr: r.src_addr,
+        dst_addr: r.conn.local_addr()?,
+        protocol: PROTO_UDP,
+    };
+
+    let a = r
+        .allocation_manager
+        .create_allocation(
+            five_tuple,
+            Arc::clone(&r.conn),
+            0,
+            Duration::from_secs(3600),
+            TextAttribute::new(ATTR_USERNAME, "user".into()),
+            true,
+        )
+        .await?;
+
+    let peer = SocketAddr::new(IpAddr::from_str("169.254.169.254")?, 80);
+    let mut m = Message::new();
+    PeerAddress {
+        ip: peer.ip(),
+        port: peer.port(),
+    }
+    .add_to(&mut m)?;
+    MessageIntegrity(STATIC_KEY.as_bytes().to_vec()).add_to(&mut m)?;
+    Nonce::new(ATTR_NONCE, STATIC_KEY.to_owned()).add_to(&mut m)?;
+    Realm::new(ATTR_REALM, STATIC_KEY.to_owned()).add_to(&mut m)?;
+    Username::new(ATTR_USERNAME, STATIC_KEY.to_owned()).add_to(&mut m)?;
+
+    let result = r.handle_create_permission_request(&m).await;
+    assert_eq!(result, Err(Error::ErrPeerAddressForbidden));
+    assert!(!a.has_permission(&peer).await);
+
+    r.peer_address_policy = Arc::new(PeerAddressPolicy {
+        allow: vec!["169.254.169.0/24".parse().unwrap()],
+        ..Default::default()
+    });
+    r.handle_create_permission_request(&m).await?;
+    assert!(a.has_permission(&peer).await);
+
+    Ok(())
+}
+
+struct PolicyAuthHandler(UserPolicy);
+#[async_trait]
+impl AuthHandler for PolicyAuthHandler {
+    async fn auth_handle(
+        &self,
+        _username: &str,
+        _realm: &str,
+        _src_addr: SocketAddr,
+    ) -> Result<Vec<u8>> {
+        Ok(STATIC_KEY.as_bytes().to_vec())
+    }
+
+    async fn auth_policy(
+        &self,
+        username: &str,
+        realm: &str,
+        src_addr: SocketAddr,
+    ) -> Result<(Vec<u8>, UserPolicy)> {
+        let key = self.auth_handle(username, realm, src_addr).await?;
+        Ok((key, self.0.clone()))
+    }
+}
+
+#[tokio::test]
+async fn test_authenticate_request_user_policy() -> Result<()> {
+    let l = Arc::new(UdpSocket::bind("0.0.0.0:0").await?);
+
+    let allocation_manager = Arc::new(Manager::new(ManagerConfig {
+        relay_addr_generator: Box::new(RelayAddressGeneratorNone {
+            address: "0.0.0.0".to_owned(),
+            net: Arc::new(Net::new(None)),
+        }),
+        alloc_close_notify: None,
+        quota: Arc::new(QuotaTracker::default()),
+        relay_handler: None,
+        store: Arc::new(MemoryAllocationStore::default()),
+    }));
+
+    let socket = SocketAddr::new(IpAddr::from_str("127.0.0.1")?, 5000);
+
+    let mut m = Message::new();
+    Lifetime(Duration::from_secs(600)).add_to(&mut m)?;
+    MessageIntegrity(STATIC_KEY.as_bytes().to_vec()).add_to(&mut m)?;
+    Nonce::new(ATTR_NONCE, STATIC_KEY.to_owned()).add_to(&mut m)?;
+    Realm::new(ATTR_REALM, STATIC_KEY.to_owned()).add_to(&mut m)?;
+    Username::new(ATTR_USERNAME, STATIC_KEY.to_owned()).add_to(&mut m)?;
+
+    let in_30s = SystemTime::now() + Duration::from_secs(30);
+    for (policy, expected) in [
+        (
+            UserPolicy {
+                realms: vec!["other".to_owned()],
+                ..Default::default()
+            },
+            Err(Error::ErrRealmNotAllowed),
+        ),
+        (
+            UserPolicy {
+                expires: Some(SystemTime::now() - Duration::from_secs(1)),
+                ..Default::default()
+            },
+            Err(Error::ErrCredentialsExpired),
+        ),
+        (
+            UserPolicy {
+                realms: vec![STATIC_KEY.to_owned()],
+                expires: Some(in_30s),
+                ..Default::default()
+            },
+            Ok(()),
+        ),
+    ] {
+        let mut r = Request::new(
+            Arc::clone(&l) as Arc<dyn Conn + Send + Sync>,
+            socket,
+            Arc::clone(&allocation_manager),
+            Arc::new(PolicyAuthHandler(policy.clone())),
+        );
+        r.store
+            .insert_nonce(STATIC_KEY.to_owned(), SystemTime::now())
+            .await?;
+
+        let result = r.authenticate_request(&m, METHOD_REFRESH).await.map(|_| ());
+        assert_eq!(result, expected);
+
+        if result.is_ok() {
+            let lifetime = r.requested_lifetime(&m, STATIC_KEY, &policy);
+            assert!(
+                lifetime <= Duration::from_secs(30),
+                "lifetime must not exceed the credentials expiry, got {lifetime:?}"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_allocate_request_user_quota() -> Result<()> {
+    let l = Arc::new(UdpSocket::bind("127.0.0.1:0").await?);
+
+    let allocation_manager = Arc::new(Manager::new(ManagerConfig {
+        relay_addr_generator: Box::new(RelayAddressGeneratorNone {
+            address: "127.0.0.1".to_owned(),
+            net: Arc::new(Net::new(None)),
+        }),
+        alloc_close_notify: None,
+        quota: Arc::new(QuotaTracker::default()),
+        relay_handler: None,
+        store: Arc::new(MemoryAllocationStore::default()),
+    }));
+
+    let socket = SocketAddr::new(IpAddr::from_str("127.0.0.1")?, 5000);
+
+    let mut m = Message::new();
+    m.typ = MessageType::new(METHOD_ALLOCATE, CLASS_REQUEST);
+    RequestedTransport {
+        protocol: PROTO_UDP,
+    }
+    .add_to(&mut m)?;
+    Nonce::new(ATTR_NONCE, STATIC_KEY.to_owned()).add_to(&mut m)?;
+    Realm::new(ATTR_REALM, STATIC_KEY.to_owned()).add_to(&mut m)?;
+    Username::new(ATTR_USERNAME, STATIC_KEY.to_owned()).add_to(&mut m)?;
+    MessageIntegrity(STATIC_KEY.as_bytes().to_vec()).add_to(&mut m)?;
+
+    for (quota, expected) in [
+        (
+            Some(Quota {
+                max_allocations: 0,
+                ..Default::default()
+            }),
+            Err(Error::ErrAllocationQuotaReached),
+        ),
+        // The override doesn't outlive the rejected allocation.
+        (None, Ok(())),
+    ] {
+        let mut r = Request::new(
+            Arc::clone(&l) as Arc<dyn Conn + Send + Sync>,
+            socket,
+            Arc::clone(&allocation_manager),
+            Arc::new(PolicyAuthHandler(UserPolicy {
+                quota,
+                ..Default::default()
+            })),
+        );
+        r.store
+            .insert_nonce(STATIC_KEY.to_owned(), SystemTime::now())
+            .await?;
+
+        let result = r.handle_allocate_request(&m).await;
+        assert_eq!(result, expected, "quota {quota:?}");
+    }
+
+    allocation_manager.close().await?;
+
+    Ok(())
+}
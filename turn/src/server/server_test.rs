@@ -1,6 +1,19 @@
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::str::FromStr;
-
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use async_trait::async_trait;
+use stun::addr::{OtherAddress, ResponseOrigin};
+use stun::agent::TransactionId;
+use stun::attributes::*;
+use stun::behavior::*;
+use stun::error_code::*;
+use stun::fingerprint::FINGERPRINT;
+use stun::integrity::MessageIntegrity;
+use stun::message::*;
+use stun::textattrs::{Nonce, Realm, Username};
+use stun::uattrs::UnknownAttributes;
+use stun::xoraddr::XorMappedAddress;
 use tokio::net::UdpSocket;
 use tokio::sync::mpsc;
 use util::vnet::router::Nic;
@@ -11,8 +24,12 @@ use super::*;
 use crate::auth::generate_auth_key;
 use crate::client::*;
 use crate::error::*;
+use crate::proto::reqtrans::RequestedTransport;
+use crate::proto::PROTO_UDP;
+use crate::relay::relay_dual_stack::RelayAddressGeneratorDualStack;
 use crate::relay::relay_none::RelayAddressGeneratorNone;
 use crate::relay::relay_static::*;
+use crate::relay::RelayAddressGenerator;
 
 struct TestAuthHandler {
     cred_map: HashMap<String, Vec<u8>>,
@@ -30,8 +47,14 @@ impl TestAuthHandler {
     }
 }
 
+#[async_trait]
 impl AuthHandler for TestAuthHandler {
-    fn auth_handle(&self, username: &str, _realm: &str, _src_addr: SocketAddr) -> Result<Vec<u8>> {
+    async fn auth_handle(
+        &self,
+        username: &str,
+        _realm: &str,
+        _src_addr: SocketAddr,
+    ) -> Result<Vec<u8>> {
         if let Some(pw) = self.cred_map.get(username) {
             Ok(pw.to_vec())
         } else {
@@ -56,11 +79,20 @@ async fn test_server_simple() -> Result<()> {
                 address: "0.0.0.0".to_owned(),
                 net: Arc::new(net::Net::new(None)),
             }),
+            nat_discovery: None,
         }],
         realm: "webrtc.rs".to_owned(),
         auth_handler: Arc::new(TestAuthHandler::new()),
         channel_bind_timeout: Duration::from_secs(0),
         alloc_close_notify: None,
+        peer_address_policy: PeerAddressPolicy {
+            allow_internal: true,
+            ..Default::default()
+        },
+        quota: QuotaConfig::default(),
+        relay_handler: None,
+        store: None,
+        drain_timeout: Duration::from_secs(0),
     })
     .await?;
 
@@ -191,11 +223,20 @@ async fn build_vnet() -> Result<VNet> {
                 address: "1.2.3.4".to_owned(),
                 net: Arc::clone(&net0),
             }),
+            nat_discovery: None,
         }],
         realm: "webrtc.rs".to_owned(),
         auth_handler: Arc::new(TestAuthHandler::new()),
         channel_bind_timeout: Duration::from_secs(0),
         alloc_close_notify: None,
+        peer_address_policy: PeerAddressPolicy {
+            allow_internal: true,
+            ..Default::default()
+        },
+        quota: QuotaConfig::default(),
+        relay_handler: None,
+        store: None,
+        drain_timeout: Duration::from_secs(0),
     })
     .await?;
 
@@ -336,3 +377,521 @@ async fn test_server_vnet_echo_via_relay() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_peer_address_policy() -> Result<()> {
+    let policy = PeerAddressPolicy::default();
+    for (ip, allowed) in [
+        ("8.8.8.8", true),
+        ("2001:4860:4860::8888", true),
+        ("127.0.0.1", false),
+        ("10.1.2.3", false),
+        ("172.16.0.1", false),
+        ("192.168.1.1", false),
+        ("169.254.169.254", false),
+        ("100.64.0.1", false),
+        ("0.0.0.0", false),
+        ("255.255.255.255", false),
+        ("::1", false),
+        ("fd00::1", false),
+        ("fe80::1", false),
+        ("::ffff:127.0.0.1", false),
+        ("::ffff:8.8.8.8", true),
+        ("64:ff9b::a00:1", false),
+        ("64:ff9b::808:808", true),
+        ("::10.0.0.1", false),
+        ("::8.8.8.8", true),
+        ("2002:a00:1::1", false),
+        ("2002:808:808::1", true),
+        ("2001:0:4136:e378:8000:63bf:80ff:fffe", false),
+        ("2001:0:4136:e378:8000:63bf:f7f7:f7f7", true),
+    ] {
+        assert_eq!(
+            policy.is_allowed(IpAddr::from_str(ip)?),
+            allowed,
+            "unexpected policy for {ip}"
+        );
+    }
+
+    let policy = PeerAddressPolicy {
+        allow: vec!["10.0.0.0/24".parse().unwrap()],
+        deny: vec!["8.8.0.0/16".parse().unwrap(), "10.0.0.0/8".parse().unwrap()],
+        allow_internal: false,
+    };
+    assert!(policy.is_allowed(IpAddr::from_str("10.0.0.5")?));
+    assert!(!policy.is_allowed(IpAddr::from_str("10.0.1.5")?));
+    assert!(!policy.is_allowed(IpAddr::from_str("8.8.8.8")?));
+    assert!(policy.is_allowed(IpAddr::from_str("1.1.1.1")?));
+
+    let policy = PeerAddressPolicy {
+        allow_internal: true,
+        ..Default::default()
+    };
+    assert!(policy.is_allowed(IpAddr::from_str("192.168.1.1")?));
+
+    Ok(())
+}
+
+async fn build_dual_stack_server(
+    relay_addr_generator: Box<dyn RelayAddressGenerator + Send + Sync>,
+) -> Result<(Server, Client)> {
+    let conn = Arc::new(UdpSocket::bind("127.0.0.1:0").await?);
+    let server_port = conn.local_addr()?.port();
+
+    let server = Server::new(ServerConfig {
+        conn_configs: vec![ConnConfig {
+            conn,
+            relay_addr_generator,
+            nat_discovery: None,
+        }],
+        realm: "webrtc.rs".to_owned(),
+        auth_handler: Arc::new(TestAuthHandler::new()),
+        channel_bind_timeout: Duration::from_secs(0),
+        alloc_close_notify: None,
+        peer_address_policy: PeerAddressPolicy {
+            allow_internal: true,
+            ..Default::default()
+        },
+        quota: QuotaConfig::default(),
+        relay_handler: None,
+        store: None,
+        drain_timeout: Duration::from_secs(0),
+    })
+    .await?;
+
+    let client = Client::new(ClientConfig {
+        stun_serv_addr: format!("127.0.0.1:{server_port}"),
+        turn_serv_addr: format!("127.0.0.1:{server_port}"),
+        username: "user".to_owned(),
+        password: "pass".to_owned(),
+        realm: String::new(),
+        software: String::new(),
+        rto_in_ms: 0,
+        conn: Arc::new(UdpSocket::bind("127.0.0.1:0").await?),
+        vnet: None,
+    })
+    .await?;
+    client.listen().await?;
+
+    Ok((server, client))
+}
+
+#[tokio::test]
+async fn test_server_dual_stack_allocation() -> Result<()> {
+    let (server, client) = build_dual_stack_server(Box::new(RelayAddressGeneratorDualStack {
+        ipv4: Box::new(RelayAddressGeneratorNone {
+            address: "127.0.0.1".to_owned(),
+            net: Arc::new(net::Net::new(None)),
+        }),
+        ipv6: Box::new(RelayAddressGeneratorNone {
+            address: "[::1]".to_owned(),
+            net: Arc::new(net::Net::new(None)),
+        }),
+    }))
+    .await?;
+
+    let (relay_conn, ipv6_relayed_addr) = client.allocate_dual_stack().await?;
+    assert!(relay_conn.local_addr()?.is_ipv4());
+    let ipv6_relayed_addr = ipv6_relayed_addr.expect("IPv6 relayed address must be allocated");
+    assert!(ipv6_relayed_addr.is_ipv6());
+
+    // Both address families are reachable through the same allocation.
+    for (peer_addr, relayed_addr) in [
+        ("[::1]:0", ipv6_relayed_addr),
+        ("127.0.0.1:0", relay_conn.local_addr()?),
+    ] {
+        let peer = UdpSocket::bind(peer_addr).await?;
+        let peer_addr = peer.local_addr()?;
+
+        relay_conn.send_to(b"hello", peer_addr).await?;
+        let mut buf = [0u8; 64];
+        let (n, from) = peer.recv_from(&mut buf).await?;
+        assert_eq!(&buf[..n], b"hello");
+        assert_eq!(from, relayed_addr);
+
+        peer.send_to(b"world", relayed_addr).await?;
+        let (n, from) = relay_conn.recv_from(&mut buf).await?;
+        assert_eq!(&buf[..n], b"world");
+        assert_eq!(from, peer_addr);
+    }
+
+    client.close().await?;
+    server.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_server_dual_stack_allocation_ipv4_only() -> Result<()> {
+    let (server, client) = build_dual_stack_server(Box::new(RelayAddressGeneratorNone {
+        address: "127.0.0.1".to_owned(),
+        net: Arc::new(net::Net::new(None)),
+    }))
+    .await?;
+
+    let (relay_conn, ipv6_relayed_addr) = client.allocate_dual_stack().await?;
+    assert!(relay_conn.local_addr()?.is_ipv4());
+    assert_eq!(ipv6_relayed_addr, None);
+
+    client.close().await?;
+    server.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_server_close_drains_allocations() -> Result<()> {
+    let conn = Arc::new(UdpSocket::bind("127.0.0.1:0").await?);
+    let server_port = conn.local_addr()?.port();
+
+    let server = Arc::new(
+        Server::new(ServerConfig {
+            conn_configs: vec![ConnConfig {
+                conn,
+                relay_addr_generator: Box::new(RelayAddressGeneratorNone {
+                    address: "127.0.0.1".to_owned(),
+                    net: Arc::new(net::Net::new(None)),
+                }),
+                nat_discovery: None,
+            }],
+            realm: "webrtc.rs".to_owned(),
+            auth_handler: Arc::new(TestAuthHandler::new()),
+            channel_bind_timeout: Duration::from_secs(0),
+            alloc_close_notify: None,
+            peer_address_policy: PeerAddressPolicy::default(),
+            quota: QuotaConfig::default(),
+            relay_handler: None,
+            store: None,
+            drain_timeout: Duration::from_millis(500),
+        })
+        .await?,
+    );
+
+    let new_client = || async {
+        let client = Client::new(ClientConfig {
+            stun_serv_addr: format!("127.0.0.1:{server_port}"),
+            turn_serv_addr: format!("127.0.0.1:{server_port}"),
+            username: "user".to_owned(),
+            password: "pass".to_owned(),
+            realm: String::new(),
+            software: String::new(),
+            rto_in_ms: 0,
+            conn: Arc::new(UdpSocket::bind("127.0.0.1:0").await?),
+            vnet: None,
+        })
+        .await?;
+        client.listen().await?;
+        Result::<Client>::Ok(client)
+    };
+
+    let client1 = new_client().await?;
+    let _relay_conn = client1.allocate().await?;
+
+    let started = Instant::now();
+    let closing = tokio::spawn({
+        let server = Arc::clone(&server);
+        async move { server.close().await }
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // The existing allocation is kept while draining, new ones are refused.
+    assert_eq!(server.get_allocations_info(None).await?.len(), 1);
+    let client2 = new_client().await?;
+    assert!(
+        client2.allocate().await.is_err(),
+        "draining server must refuse new allocations"
+    );
+
+    closing.await.unwrap()?;
+    assert!(started.elapsed() >= Duration::from_millis(500));
+
+    client1.close().await?;
+    client2.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_server_nat_behavior_discovery() -> Result<()> {
+    let conn = Arc::new(UdpSocket::bind("127.0.0.1:0").await?);
+    let server_addr = conn.local_addr()?;
+    let alternate_port_conn = Arc::new(UdpSocket::bind("127.0.0.1:0").await?);
+    let alternate_port = alternate_port_conn.local_addr()?.port();
+
+    let server = Server::new(ServerConfig {
+        conn_configs: vec![ConnConfig {
+            conn,
+            relay_addr_generator: Box::new(RelayAddressGeneratorNone {
+                address: "127.0.0.1".to_owned(),
+                net: Arc::new(net::Net::new(None)),
+            }),
+            nat_discovery: Some(NatDiscoveryConfig {
+                alternate_port_conn,
+                alternate_ip_conn: Arc::new(
+                    UdpSocket::bind(("127.0.0.2", server_addr.port())).await?,
+                ),
+                alternate_conn: Arc::new(UdpSocket::bind(("127.0.0.2", alternate_port)).await?),
+            }),
+        }],
+        realm: "webrtc.rs".to_owned(),
+        auth_handler: Arc::new(TestAuthHandler::new()),
+        channel_bind_timeout: Duration::from_secs(0),
+        alloc_close_notify: None,
+        peer_address_policy: PeerAddressPolicy::default(),
+        quota: QuotaConfig::default(),
+        relay_handler: None,
+        store: None,
+        drain_timeout: Duration::from_secs(0),
+    })
+    .await?;
+
+    let conn = Arc::new(UdpSocket::bind("127.0.0.1:0").await?);
+    let discovery = BehaviorDiscovery::new(Arc::clone(&conn) as _, server_addr)
+        .with_rto(Duration::from_millis(100));
+    let nat = discovery
+        .discover(Arc::new(UdpSocket::bind("127.0.0.1:0").await?))
+        .await?;
+    assert!(!nat.nat_detected, "{nat}");
+    assert_eq!(nat.mapped_address, conn.local_addr()?);
+    assert_eq!(nat.filtering, Behavior::EndpointIndependent);
+
+    // The response is sent to RESPONSE-PORT, from the secondary IP address
+    // and port as CHANGE-REQUEST asks.
+    let response_conn = UdpSocket::bind("127.0.0.1:0").await?;
+    let mut req = Message::new();
+    req.build(&[
+        Box::<TransactionId>::default(),
+        Box::new(BINDING_REQUEST),
+        Box::new(ChangeRequest {
+            change_ip: true,
+            change_port: true,
+        }),
+        Box::new(ResponsePort(response_conn.local_addr()?.port())),
+    ])?;
+    conn.send_to(&req.raw, server_addr).await?;
+
+    let mut buf = vec![0u8; 1500];
+    let (n, from) = response_conn.recv_from(&mut buf).await?;
+    let mut res = Message::new();
+    res.unmarshal_binary(&buf[..n])?;
+    assert_eq!(res.typ, BINDING_SUCCESS);
+    assert_eq!(res.transaction_id, req.transaction_id);
+    assert_eq!(
+        from,
+        SocketAddr::from_str(&format!("127.0.0.2:{alternate_port}"))?
+    );
+
+    let mut mapped = XorMappedAddress::default();
+    mapped.get_from(&res)?;
+    assert_eq!(SocketAddr::new(mapped.ip, mapped.port), conn.local_addr()?);
+    let mut origin = ResponseOrigin::default();
+    origin.get_from_as(&res, ATTR_RESPONSE_ORIGIN)?;
+    assert_eq!(SocketAddr::new(origin.ip, origin.port), from);
+    let mut other = OtherAddress::default();
+    other.get_from_as(&res, ATTR_OTHER_ADDRESS)?;
+    assert_eq!(SocketAddr::new(other.ip, other.port), from);
+
+    server.close().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_server_change_request_not_supported() -> Result<()> {
+    let conn = Arc::new(UdpSocket::bind("127.0.0.1:0").await?);
+    let server_addr = conn.local_addr()?;
+
+    let server = Server::new(ServerConfig {
+        conn_configs: vec![ConnConfig {
+            conn,
+            relay_addr_generator: Box::new(RelayAddressGeneratorNone {
+                address: "127.0.0.1".to_owned(),
+                net: Arc::new(net::Net::new(None)),
+            }),
+            nat_discovery: None,
+        }],
+        realm: "webrtc.rs".to_owned(),
+        auth_handler: Arc::new(TestAuthHandler::new()),
+        channel_bind_timeout: Duration::from_secs(0),
+        alloc_close_notify: None,
+        peer_address_policy: PeerAddressPolicy::default(),
+        quota: QuotaConfig::default(),
+        relay_handler: None,
+        store: None,
+        drain_timeout: Duration::from_secs(0),
+    })
+    .await?;
+
+    let conn = UdpSocket::bind("127.0.0.1:0").await?;
+    let tests = [
+        (true, false, vec![ATTR_CHANGE_REQUEST]),
+        (false, true, vec![ATTR_RESPONSE_PORT]),
+        (true, true, vec![ATTR_CHANGE_REQUEST, ATTR_RESPONSE_PORT]),
+    ];
+    for (change_request, response_port, expected) in tests {
+        let mut setters: Vec<Box<dyn Setter>> =
+            vec![Box::<TransactionId>::default(), Box::new(BINDING_REQUEST)];
+        if change_request {
+            setters.push(Box::new(ChangeRequest {
+                change_ip: true,
+                change_port: false,
+            }));
+        }
+        if response_port {
+            setters.push(Box::new(ResponsePort(conn.local_addr()?.port())));
+        }
+        let mut req = Message::new();
+        req.build(&setters)?;
+        conn.send_to(&req.raw, server_addr).await?;
+
+        let mut buf = vec![0u8; 1500];
+        let (n, _) = conn.recv_from(&mut buf).await?;
+        let mut res = Message::new();
+        res.unmarshal_binary(&buf[..n])?;
+        assert_eq!(res.typ, BINDING_ERROR);
+        let mut code = ErrorCodeAttribute::default();
+        code.get_from(&res)?;
+        assert!(
+            code.code == CODE_UNKNOWN_ATTRIBUTE,
+            "unexpected error {code}"
+        );
+        let mut unknown = UnknownAttributes(vec![]);
+        unknown.get_from(&res)?;
+        assert_eq!(unknown.0, expected);
+    }
+
+    server.close().await?;
+
+    Ok(())
+}
+
+struct SlowAuthHandler(TestAuthHandler);
+
+#[async_trait]
+impl AuthHandler for SlowAuthHandler {
+    async fn auth_handle(
+        &self,
+        username: &str,
+        realm: &str,
+        src_addr: SocketAddr,
+    ) -> Result<Vec<u8>> {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        self.0.auth_handle(username, realm, src_addr).await
+    }
+}
+
+struct CountingRelayAddressGenerator {
+    relay_addr_generator: RelayAddressGeneratorNone,
+    allocated: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl RelayAddressGenerator for CountingRelayAddressGenerator {
+    fn validate(&self) -> Result<()> {
+        self.relay_addr_generator.validate()
+    }
+
+    async fn allocate_conn(
+        &self,
+        use_ipv4: bool,
+        requested_port: u16,
+    ) -> Result<(Arc<dyn Conn + Send + Sync>, SocketAddr)> {
+        self.allocated.fetch_add(1, Ordering::SeqCst);
+        // Let other requests run meanwhile, like a real relay would
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        self.relay_addr_generator
+            .allocate_conn(use_ipv4, requested_port)
+            .await
+    }
+}
+
+#[tokio::test]
+async fn test_server_concurrent_allocate_requests() -> Result<()> {
+    let conn = Arc::new(UdpSocket::bind("127.0.0.1:0").await?);
+    let server_addr = conn.local_addr()?;
+    let allocated = Arc::new(AtomicUsize::new(0));
+
+    let server = Server::new(ServerConfig {
+        conn_configs: vec![ConnConfig {
+            conn,
+            relay_addr_generator: Box::new(CountingRelayAddressGenerator {
+                relay_addr_generator: RelayAddressGeneratorNone {
+                    address: "127.0.0.1".to_owned(),
+                    net: Arc::new(net::Net::new(None)),
+                },
+                allocated: Arc::clone(&allocated),
+            }),
+            nat_discovery: None,
+        }],
+        realm: "webrtc.rs".to_owned(),
+        auth_handler: Arc::new(SlowAuthHandler(TestAuthHandler::new())),
+        channel_bind_timeout: Duration::from_secs(0),
+        alloc_close_notify: None,
+        peer_address_policy: PeerAddressPolicy::default(),
+        quota: QuotaConfig::default(),
+        relay_handler: None,
+        store: None,
+        drain_timeout: Duration::from_secs(0),
+    })
+    .await?;
+
+    let conn = UdpSocket::bind("127.0.0.1:0").await?;
+    let recv = || async {
+        let mut buf = vec![0u8; 1500];
+        let (n, _) = conn.recv_from(&mut buf).await?;
+        let mut res = Message::new();
+        res.unmarshal_binary(&buf[..n])?;
+        Result::<Message>::Ok(res)
+    };
+
+    // Get a nonce
+    let mut req = Message::new();
+    req.build(&[
+        Box::<TransactionId>::default(),
+        Box::new(MessageType::new(METHOD_ALLOCATE, CLASS_REQUEST)),
+        Box::new(RequestedTransport {
+            protocol: PROTO_UDP,
+        }),
+        Box::new(FINGERPRINT),
+    ])?;
+    conn.send_to(&req.raw, server_addr).await?;
+    let res = recv().await?;
+    let nonce = Nonce::get_from_as(&res, ATTR_NONCE)?;
+    let realm = Realm::get_from_as(&res, ATTR_REALM)?;
+
+    // An Allocate retransmitted while the first one is authenticated must
+    // not allocate twice.
+    let mut req = Message::new();
+    req.build(&[
+        Box::<TransactionId>::default(),
+        Box::new(MessageType::new(METHOD_ALLOCATE, CLASS_REQUEST)),
+        Box::new(RequestedTransport {
+            protocol: PROTO_UDP,
+        }),
+        Box::new(Username::new(ATTR_USERNAME, "user".to_owned())),
+        Box::new(realm),
+        Box::new(nonce),
+        Box::new(MessageIntegrity::new_long_term_integrity(
+            "user".to_owned(),
+            "webrtc.rs".to_owned(),
+            "pass".to_owned(),
+        )),
+        Box::new(FINGERPRINT),
+    ])?;
+    conn.send_to(&req.raw, server_addr).await?;
+    conn.send_to(&req.raw, server_addr).await?;
+
+    // Both are handled in order, the second one finds the allocation.
+    assert_eq!(recv().await?.typ.class, CLASS_SUCCESS_RESPONSE);
+    let res = recv().await?;
+    assert_eq!(res.typ.class, CLASS_ERROR_RESPONSE);
+    let mut code = ErrorCodeAttribute::default();
+    code.get_from(&res)?;
+    assert!(code.code == CODE_ALLOC_MISMATCH, "{code}");
+    assert_eq!(allocated.load(Ordering::SeqCst), 1);
+    assert_eq!(server.get_allocations_info(None).await?.len(), 1);
+
+    server.close().await?;
+
+    Ok(())
+}
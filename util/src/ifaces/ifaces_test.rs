@@ -0,0 +1,35 @@
+use super::*;
+
+#[test]
+fn test_adapter_type_from_name() {
+    let tests = vec![
+        ("lo", AdapterType::Loopback),
+        ("lo0", AdapterType::Loopback),
+        ("eth0", AdapterType::Ethernet),
+        ("enp0s3", AdapterType::Ethernet),
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        ("en0", AdapterType::Unknown),
+        #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+        ("en0", AdapterType::Ethernet),
+        ("wlan0", AdapterType::Wifi),
+        ("wlp2s0", AdapterType::Wifi),
+        ("rmnet_data0", AdapterType::Cellular),
+        ("pdp_ip0", AdapterType::Cellular),
+        ("tun0", AdapterType::Vpn),
+        ("utun3", AdapterType::Vpn),
+        ("wg0", AdapterType::Vpn),
+        ("docker0", AdapterType::Unknown),
+        ("", AdapterType::Unknown),
+    ];
+
+    for (name, expected) in tests {
+        assert_eq!(AdapterType::from_name(name), expected, "{name}");
+    }
+}
+
+#[test]
+fn test_adapter_type_network_cost() {
+    assert!(AdapterType::Ethernet.network_cost() < AdapterType::Wifi.network_cost());
+    assert!(AdapterType::Wifi.network_cost() < AdapterType::Vpn.network_cost());
+    assert!(AdapterType::Vpn.network_cost() < AdapterType::Cellular.network_cost());
+}
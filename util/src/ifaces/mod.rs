@@ -1,3 +1,6 @@
+#[cfg(test)]
+mod ifaces_test;
+
 pub mod ffi;
 pub use ffi::ifaces;
 
@@ -24,3 +27,72 @@ pub struct Interface {
     pub mask: Option<::std::net::SocketAddr>,
     pub hop: Option<NextHop>,
 }
+
+/// The kind of network adapter an interface belongs to.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash, Default)]
+pub enum AdapterType {
+    #[default]
+    Unknown,
+    Ethernet,
+    Wifi,
+    Cellular,
+    Vpn,
+    Loopback,
+}
+
+impl AdapterType {
+    /// Guesses the adapter type from the name the OS gives to the interface,
+    /// as the interface list doesn't tell it otherwise.
+    pub fn from_name(name: &str) -> Self {
+        const PREFIXES: &[(&str, AdapterType)] = &[
+            ("lo", AdapterType::Loopback),
+            // Linux, Android and Apple tunnels, WireGuard and IPsec.
+            ("tun", AdapterType::Vpn),
+            ("tap", AdapterType::Vpn),
+            ("utun", AdapterType::Vpn),
+            ("ipsec", AdapterType::Vpn),
+            ("ppp", AdapterType::Vpn),
+            ("wg", AdapterType::Vpn),
+            // Android and iOS cellular data.
+            ("rmnet", AdapterType::Cellular),
+            ("v4-rmnet", AdapterType::Cellular),
+            ("ccmni", AdapterType::Cellular),
+            ("pdp_ip", AdapterType::Cellular),
+            ("wwan", AdapterType::Cellular),
+            ("wlan", AdapterType::Wifi),
+            ("wl", AdapterType::Wifi),
+            ("eth", AdapterType::Ethernet),
+            // Apple names both Ethernet and Wi-Fi adapters `enN`.
+            #[cfg(any(target_os = "macos", target_os = "ios"))]
+            ("en", AdapterType::Unknown),
+            #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+            ("en", AdapterType::Ethernet),
+        ];
+
+        let name = name.to_ascii_lowercase();
+        PREFIXES
+            .iter()
+            .find(|(prefix, _)| name.starts_with(prefix))
+            .map(|(_, adapter_type)| *adapter_type)
+            .unwrap_or_default()
+    }
+
+    /// Returns the network cost of the adapter type, using the values of the
+    /// `network-cost` ICE candidate extension: the higher, the more expensive
+    /// the network is to use.
+    pub const fn network_cost(self) -> u16 {
+        match self {
+            AdapterType::Ethernet | AdapterType::Loopback => 0,
+            AdapterType::Wifi => 10,
+            AdapterType::Vpn | AdapterType::Unknown => 50,
+            AdapterType::Cellular => 900,
+        }
+    }
+}
+
+impl Interface {
+    /// Returns the adapter type of the interface.
+    pub fn adapter_type(&self) -> AdapterType {
+        AdapterType::from_name(&self.name)
+    }
+}
@@ -12,7 +12,9 @@ use interceptor::twcc::sender::Sender;
 use crate::api::media_engine::MediaEngine;
 use crate::error::Result;
 use crate::rtp_transceiver::rtp_codec::{RTCRtpHeaderExtensionCapability, RTPCodecType};
-use crate::rtp_transceiver::{RTCPFeedback, TYPE_RTCP_FB_TRANSPORT_CC};
+use crate::rtp_transceiver::{
+    RTCPFeedback, RTCP_FB_PARAMETER_CCFB, TYPE_RTCP_FB_ACK, TYPE_RTCP_FB_TRANSPORT_CC,
+};
 
 /// register_default_interceptors will register some useful interceptors.
 /// If you want to customize which interceptors are loaded, you should copy the
@@ -169,3 +171,22 @@ pub fn configure_twcc_receiver_only(
     registry.add(receiver);
     Ok(registry)
 }
+
+/// configure_ccfb will setup everything necessary for negotiating and generating
+/// RFC 8888 congestion control feedback reports. To consume the reports sent by the
+/// remote peer, add an [`interceptor::ccfb::sender::Sender`] with a feedback handler.
+pub fn configure_ccfb(mut registry: Registry, media_engine: &mut MediaEngine) -> Registry {
+    for typ in [RTPCodecType::Video, RTPCodecType::Audio] {
+        media_engine.register_feedback(
+            RTCPFeedback {
+                typ: TYPE_RTCP_FB_ACK.to_owned(),
+                parameter: RTCP_FB_PARAMETER_CCFB.to_owned(),
+            },
+            typ,
+        );
+    }
+
+    let receiver = Box::new(interceptor::ccfb::receiver::Receiver::builder());
+    registry.add(receiver);
+    registry
+}
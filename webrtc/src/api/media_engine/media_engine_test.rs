@@ -523,6 +523,64 @@ a=fmtp:97 apt=96
         }
     }
 
+    //"Matches H.265 by profile regardless of the level"
+    {
+        const PROFILE_LEVELS: &str = "v=0
+o=- 4596489990601351948 2 IN IP4 127.0.0.1
+s=-
+t=0 0
+m=video 60323 UDP/TLS/RTP/SAVPF 96 98
+a=rtpmap:96 H265/90000
+a=fmtp:96 profile-id=2;tier-flag=0;level-id=93;tx-mode=SRST
+a=rtpmap:98 H265/90000
+a=fmtp:98 profile-id=1;tier-flag=0;level-id=156;tx-mode=SRST
+";
+        let mut m = MediaEngine::default();
+        m.register_codec(
+            RTCRtpCodecParameters {
+                capability: RTCRtpCodecCapability {
+                    mime_type: MIME_TYPE_HEVC.to_owned(),
+                    clock_rate: 90000,
+                    channels: 0,
+                    sdp_fmtp_line: "profile-id=1;level-id=93".to_string(),
+                    rtcp_feedback: vec![],
+                },
+                payload_type: 45,
+                ..Default::default()
+            },
+            RTPCodecType::Video,
+        )?;
+
+        m.update_from_remote_description(&must_parse(PROFILE_LEVELS)?)
+            .await?;
+
+        assert!(m.negotiated_video.load(Ordering::SeqCst));
+
+        m.get_codec_by_payload(98).await?;
+        assert!(m.get_codec_by_payload(96).await.is_err());
+    }
+
+    //"Exposes the Opus parameters of the remote"
+    {
+        const OPUS: &str = "v=0
+o=- 4596489990601351948 2 IN IP4 127.0.0.1
+s=-
+t=0 0
+m=audio 9 UDP/TLS/RTP/SAVPF 109
+a=rtpmap:109 opus/48000/2
+a=fmtp:109 stereo=1;useinbandfec=0;maxaveragebitrate=128000
+";
+        let mut m = MediaEngine::default();
+        m.register_default_codecs()?;
+        m.update_from_remote_description(&must_parse(OPUS)?).await?;
+
+        let (codec, _) = m.get_codec_by_payload(109).await?;
+        let opus = codec.capability.opus_parameters().unwrap();
+        assert!(opus.stereo);
+        assert!(!opus.use_inband_fec);
+        assert_eq!(opus.max_average_bitrate, Some(128000));
+    }
+
     Ok(())
 }
 
@@ -20,7 +20,7 @@ use crate::rtp_transceiver::rtp_codec::{
     RTPCodecType,
 };
 use crate::rtp_transceiver::rtp_transceiver_direction::RTCRtpTransceiverDirection;
-use crate::rtp_transceiver::{fmtp, PayloadType, RTCPFeedback};
+use crate::rtp_transceiver::{fmtp, PayloadType, RTCPFeedback, RTCRtpCapabilities};
 use crate::stats::stats_collector::StatsCollector;
 use crate::stats::CodecStats;
 use crate::stats::StatsReportType::Codec;
@@ -288,7 +288,7 @@ impl MediaEngine {
                     mime_type: MIME_TYPE_AV1.to_owned(),
                     clock_rate: 90000,
                     channels: 0,
-                    sdp_fmtp_line: "profile-id=0".to_owned(),
+                    sdp_fmtp_line: "profile=0".to_owned(),
                     rtcp_feedback: video_rtcp_feedback.clone(),
                 },
                 payload_type: 41,
@@ -723,6 +723,43 @@ impl MediaEngine {
         }
     }
 
+    /// get_header_extensions_by_kind returns the registered header extensions of a kind that
+    /// are usable in the given direction, negotiated or not
+    pub(crate) fn get_header_extensions_by_kind(
+        &self,
+        typ: RTPCodecType,
+        direction: RTCRtpTransceiverDirection,
+    ) -> Vec<RTCRtpHeaderExtensionCapability> {
+        self.header_extensions
+            .iter()
+            .filter(|e| {
+                e.is_matching_direction(direction)
+                    && (e.is_audio && typ == RTPCodecType::Audio
+                        || e.is_video && typ == RTPCodecType::Video)
+            })
+            .map(|e| RTCRtpHeaderExtensionCapability { uri: e.uri.clone() })
+            .collect()
+    }
+
+    /// get_capabilities returns the registered codecs and header extensions of a kind that
+    /// are usable in the given direction
+    pub fn get_capabilities(
+        &self,
+        typ: RTPCodecType,
+        direction: RTCRtpTransceiverDirection,
+    ) -> RTCRtpCapabilities {
+        let codecs = match typ {
+            RTPCodecType::Audio => &self.audio_codecs,
+            RTPCodecType::Video => &self.video_codecs,
+            RTPCodecType::Unspecified => return RTCRtpCapabilities::default(),
+        };
+
+        RTCRtpCapabilities {
+            codecs: codecs.iter().map(|c| c.capability.clone()).collect(),
+            header_extensions: self.get_header_extensions_by_kind(typ, direction),
+        }
+    }
+
     pub(crate) fn get_rtp_parameters_by_kind(
         &self,
         typ: RTPCodecType,
@@ -26,6 +26,8 @@ use crate::peer_connection::RTCPeerConnection;
 use crate::rtp_transceiver::rtp_codec::RTPCodecType;
 use crate::rtp_transceiver::rtp_receiver::RTCRtpReceiver;
 use crate::rtp_transceiver::rtp_sender::RTCRtpSender;
+use crate::rtp_transceiver::rtp_transceiver_direction::RTCRtpTransceiverDirection;
+use crate::rtp_transceiver::RTCRtpCapabilities;
 use crate::sctp_transport::RTCSctpTransport;
 use crate::track::track_local::TrackLocal;
 
@@ -170,6 +172,17 @@ impl API {
         .await
     }
 
+    /// get_capabilities returns the codecs and header extensions of the given kind of media
+    /// that senders (`Sendonly`) or receivers (`Recvonly`) created with this API support,
+    /// without creating one.
+    pub fn get_capabilities(
+        &self,
+        kind: RTPCodecType,
+        direction: RTCRtpTransceiverDirection,
+    ) -> RTCRtpCapabilities {
+        self.media_engine.get_capabilities(kind, direction)
+    }
+
     /// Returns the internal [`SettingEngine`].
     pub fn setting_engine(&self) -> Arc<SettingEngine> {
         Arc::clone(&self.setting_engine)
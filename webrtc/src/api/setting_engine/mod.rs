@@ -45,6 +45,7 @@ pub struct Candidates {
     pub username_fragment: String,
     pub password: String,
     pub include_loopback_candidate: bool,
+    pub ice_renomination: bool,
 }
 
 #[derive(Default, Clone)]
@@ -79,6 +80,8 @@ pub struct SettingEngine {
     pub(crate) receive_mtu: usize,
     pub(crate) mid_generator: Option<Arc<dyn Fn(isize) -> String + Send + Sync>>,
     pub(crate) enable_sender_rtx: bool,
+    pub(crate) sdes_srtp: bool,
+    pub(crate) sender_keyframe_request_interval: Option<Duration>,
 }
 
 impl SettingEngine {
@@ -150,6 +153,13 @@ impl SettingEngine {
         self.candidates.ice_lite = lite;
     }
 
+    /// set_ice_renomination enables the renomination ICE option, letting the controlling agent
+    /// switch to a better candidate pair after one has been selected. It is advertised in the
+    /// ice-options of the local description and only used if the remote one advertises it too.
+    pub fn set_ice_renomination(&mut self, enabled: bool) {
+        self.candidates.ice_renomination = enabled;
+    }
+
     /// set_network_types configures what types of candidate networks are supported
     /// during local and server reflexive gathering.
     pub fn set_network_types(&mut self, candidate_types: Vec<NetworkType>) {
@@ -342,4 +352,20 @@ impl SettingEngine {
     pub fn enable_sender_rtx(&mut self, is_enabled: bool) {
         self.enable_sender_rtx = is_enabled;
     }
+
+    /// set_sender_keyframe_request_interval sets the minimum time between two keyframe requests
+    /// reported by RTPSender::encoder_events for the same encoding. PLI and FIR received
+    /// within the interval are merged into a single event at its end. Default is 300ms.
+    pub fn set_sender_keyframe_request_interval(&mut self, interval: Duration) {
+        self.sender_keyframe_request_interval = Some(interval);
+    }
+
+    /// enable_sdes_srtp keys SRTP with SDP Security Descriptions (RFC 4568 a=crypto attributes)
+    /// instead of a DTLS handshake, for interoperating with SIP endpoints that don't support
+    /// DTLS-SRTP. The protection profiles offered are the ones set with set_srtp_protection_profiles.
+    /// The keys are sent in the clear in the SDP, so the signaling channel must be secured.
+    /// Data channels require DTLS and are not available in this mode.
+    pub fn enable_sdes_srtp(&mut self, is_enabled: bool) {
+        self.sdes_srtp = is_enabled;
+    }
 }
@@ -23,6 +23,7 @@ use util::Conn;
 use crate::api::setting_engine::SettingEngine;
 use crate::dtls_transport::dtls_parameters::DTLSParameters;
 use crate::dtls_transport::dtls_transport_state::RTCDtlsTransportState;
+use crate::dtls_transport::sdes_crypto::RTCSdesCrypto;
 use crate::error::{flatten_errs, Error, Result};
 use crate::ice_transport::ice_role::RTCIceRole;
 use crate::ice_transport::ice_transport_state::RTCIceTransportState;
@@ -40,6 +41,7 @@ pub mod dtls_fingerprint;
 pub mod dtls_parameters;
 pub mod dtls_role;
 pub mod dtls_transport_state;
+pub mod sdes_crypto;
 
 pub(crate) fn default_srtp_protection_profiles() -> Vec<SrtpProtectionProfile> {
     vec![
@@ -50,6 +52,22 @@ pub(crate) fn default_srtp_protection_profiles() -> Vec<SrtpProtectionProfile> {
     ]
 }
 
+/// protection_profile_from_dtls returns the SRTP protection profile matching a DTLS-SRTP profile,
+/// None if the profile is not supported.
+fn protection_profile_from_dtls(profile: SrtpProtectionProfile) -> Option<ProtectionProfile> {
+    match profile {
+        SrtpProtectionProfile::Srtp_Aead_Aes_128_Gcm => Some(ProtectionProfile::AeadAes128Gcm),
+        SrtpProtectionProfile::Srtp_Aead_Aes_256_Gcm => Some(ProtectionProfile::AeadAes256Gcm),
+        SrtpProtectionProfile::Srtp_Aes128_Cm_Hmac_Sha1_80 => {
+            Some(ProtectionProfile::Aes128CmHmacSha1_80)
+        }
+        SrtpProtectionProfile::Srtp_Aes128_Cm_Hmac_Sha1_32 => {
+            Some(ProtectionProfile::Aes128CmHmacSha1_32)
+        }
+        _ => None,
+    }
+}
+
 pub type OnDTLSTransportStateChangeHdlrFn = Box<
     dyn (FnMut(RTCDtlsTransportState) -> Pin<Box<dyn Future<Output = ()> + Send + 'static>>)
         + Send
@@ -85,6 +103,11 @@ pub struct RTCDtlsTransport {
     pub(crate) srtp_ready_rx: Mutex<Option<mpsc::Receiver<()>>>,
 
     pub(crate) dtls_matcher: Option<MatchFunc>,
+
+    /// Locally generated SDES-SRTP keys, one per supported protection profile
+    pub(crate) sdes_local_cryptos: Mutex<Vec<RTCSdesCrypto>>,
+    /// Negotiated (local, remote) SDES-SRTP keys
+    pub(crate) sdes_cryptos: Mutex<Option<(RTCSdesCrypto, RTCSdesCrypto)>>,
 }
 
 impl RTCDtlsTransport {
@@ -181,8 +204,30 @@ impl RTCDtlsTransport {
             *srtp_protection_profile
         };
 
+        let mut config = srtp::config::Config {
+            profile,
+            ..Default::default()
+        };
+        if let Some(conn) = self.conn().await {
+            let conn_state = conn.connection_state().await;
+            config
+                .extract_session_keys_from_dtls(conn_state, self.role().await == DTLSRole::Client)
+                .await?;
+        } else {
+            return Err(Error::ErrDtlsTransportNotStarted);
+        }
+
+        self.start_srtp_sessions(profile, config.keys).await
+    }
+
+    async fn start_srtp_sessions(
+        &self,
+        profile: ProtectionProfile,
+        keys: srtp::config::SessionKeys,
+    ) -> Result<()> {
         let mut srtp_config = srtp::config::Config {
             profile,
+            keys: keys.clone(),
             ..Default::default()
         };
 
@@ -194,15 +239,6 @@ impl RTCDtlsTransport {
             srtp_config.remote_rtp_options = Some(srtp::option::srtp_no_replay_protection());
         }
 
-        if let Some(conn) = self.conn().await {
-            let conn_state = conn.connection_state().await;
-            srtp_config
-                .extract_session_keys_from_dtls(conn_state, self.role().await == DTLSRole::Client)
-                .await?;
-        } else {
-            return Err(Error::ErrDtlsTransportNotStarted);
-        }
-
         {
             let mut srtp_session = self.srtp_session.lock().await;
             *srtp_session = {
@@ -224,6 +260,7 @@ impl RTCDtlsTransport {
 
         let mut srtcp_config = srtp::config::Config {
             profile,
+            keys,
             ..Default::default()
         };
         if self.setting_engine.replay_protection.srtcp != 0 {
@@ -234,15 +271,6 @@ impl RTCDtlsTransport {
             srtcp_config.remote_rtcp_options = Some(srtp::option::srtcp_no_replay_protection());
         }
 
-        if let Some(conn) = self.conn().await {
-            let conn_state = conn.connection_state().await;
-            srtcp_config
-                .extract_session_keys_from_dtls(conn_state, self.role().await == DTLSRole::Client)
-                .await?;
-        } else {
-            return Err(Error::ErrDtlsTransportNotStarted);
-        }
-
         {
             let mut srtcp_session = self.srtcp_session.lock().await;
             *srtcp_session = {
@@ -273,6 +301,118 @@ impl RTCDtlsTransport {
         Ok(())
     }
 
+    /// sdes_local_cryptos returns the SDES-SRTP keys to put in a local description: the
+    /// negotiated one once the remote description was applied, otherwise one per supported
+    /// protection profile.
+    pub(crate) async fn sdes_local_cryptos(&self) -> Vec<RTCSdesCrypto> {
+        if let Some((local, _)) = &*self.sdes_cryptos.lock().await {
+            return vec![local.clone()];
+        }
+
+        let mut local_cryptos = self.sdes_local_cryptos.lock().await;
+        if local_cryptos.is_empty() {
+            *local_cryptos = self
+                .srtp_protection_profiles()
+                .into_iter()
+                .enumerate()
+                .map(|(i, profile)| RTCSdesCrypto::generate(i as u32 + 1, profile))
+                .collect();
+        }
+        local_cryptos.clone()
+    }
+
+    /// negotiate_sdes picks the SDES-SRTP keys from the crypto attributes of a remote description.
+    /// The first supported attribute of an offer is accepted with its key derivation rate, an
+    /// answer must accept one of the attributes we offered unchanged.
+    pub(crate) async fn negotiate_sdes(
+        &self,
+        remote_cryptos: &[RTCSdesCrypto],
+        remote_is_offer: bool,
+    ) -> Result<()> {
+        let local_cryptos = self.sdes_local_cryptos().await;
+        let mut sdes_cryptos = self.sdes_cryptos.lock().await;
+
+        for remote in remote_cryptos {
+            let local = local_cryptos.iter().find(|local| {
+                local.profile == remote.profile
+                    && (remote_is_offer || (local.tag == remote.tag && local.kdr == remote.kdr))
+            });
+            if let Some(local) = local {
+                *sdes_cryptos = Some((
+                    RTCSdesCrypto {
+                        tag: remote.tag,
+                        kdr: remote.kdr,
+                        ..local.clone()
+                    },
+                    remote.clone(),
+                ));
+                return Ok(());
+            }
+        }
+
+        Err(Error::ErrSessionDescriptionNoCrypto)
+    }
+
+    /// start_sdes starts SRTP with the negotiated SDES-SRTP keys instead of
+    /// running a DTLS handshake.
+    pub(crate) async fn start_sdes(&self) -> Result<()> {
+        self.ensure_ice_conn()?;
+
+        if self.state() != RTCDtlsTransportState::New {
+            return Err(Error::ErrInvalidDTLSStart);
+        }
+
+        let (local, remote) = self
+            .sdes_cryptos
+            .lock()
+            .await
+            .clone()
+            .ok_or(Error::ErrSdesNotNegotiated)?;
+
+        {
+            let mut srtp_endpoint = self.srtp_endpoint.lock().await;
+            *srtp_endpoint = self.ice_transport.new_endpoint(Box::new(match_srtp)).await;
+        }
+        {
+            let mut srtcp_endpoint = self.srtcp_endpoint.lock().await;
+            *srtcp_endpoint = self.ice_transport.new_endpoint(Box::new(match_srtcp)).await;
+        }
+        {
+            let mut srtp_protection_profile = self.srtp_protection_profile.lock().await;
+            *srtp_protection_profile = local.profile;
+        }
+        self.state_change(RTCDtlsTransportState::Connecting).await;
+
+        let keys = srtp::config::SessionKeys {
+            local_master_key: local.master_key,
+            local_master_salt: local.master_salt,
+            remote_master_key: remote.master_key,
+            remote_master_salt: remote.master_salt,
+            local_mki: local.mki,
+            remote_mki: remote.mki,
+            kdr: local.kdr,
+        };
+        if let Err(err) = self.start_srtp_sessions(local.profile, keys).await {
+            self.state_change(RTCDtlsTransportState::Failed).await;
+            return Err(err);
+        }
+
+        self.state_change(RTCDtlsTransportState::Connected).await;
+        Ok(())
+    }
+
+    fn srtp_protection_profiles(&self) -> Vec<ProtectionProfile> {
+        let profiles = if !self.setting_engine.srtp_protection_profiles.is_empty() {
+            self.setting_engine.srtp_protection_profiles.clone()
+        } else {
+            default_srtp_protection_profiles()
+        };
+        profiles
+            .into_iter()
+            .filter_map(protection_profile_from_dtls)
+            .collect()
+    }
+
     pub(crate) async fn get_srtp_session(&self) -> Option<Arc<Session>> {
         let srtp_session = self.srtp_session.lock().await;
         srtp_session.clone()
@@ -412,20 +552,9 @@ impl RTCDtlsTransport {
         let srtp_profile = dtls_conn.selected_srtpprotection_profile();
         {
             let mut srtp_protection_profile = self.srtp_protection_profile.lock().await;
-            *srtp_protection_profile = match srtp_profile {
-                dtls::extension::extension_use_srtp::SrtpProtectionProfile::Srtp_Aead_Aes_128_Gcm => {
-                    srtp::protection_profile::ProtectionProfile::AeadAes128Gcm
-                }
-                dtls::extension::extension_use_srtp::SrtpProtectionProfile::Srtp_Aead_Aes_256_Gcm => {
-                    srtp::protection_profile::ProtectionProfile::AeadAes256Gcm
-                }
-                dtls::extension::extension_use_srtp::SrtpProtectionProfile::Srtp_Aes128_Cm_Hmac_Sha1_80 => {
-                    srtp::protection_profile::ProtectionProfile::Aes128CmHmacSha1_80
-                }
-                dtls::extension::extension_use_srtp::SrtpProtectionProfile::Srtp_Aes128_Cm_Hmac_Sha1_32 => {
-                    srtp::protection_profile::ProtectionProfile::Aes128CmHmacSha1_32
-                }
-                _ => {
+            *srtp_protection_profile = match protection_profile_from_dtls(srtp_profile) {
+                Some(profile) => profile,
+                None => {
                     if let Err(err) = dtls_conn.close().await {
                         log::error!("{}", err);
                     }
@@ -0,0 +1,178 @@
+use std::fmt;
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use rand::Rng;
+use srtp::protection_profile::ProtectionProfile;
+
+use crate::error::{Error, Result};
+
+const KEY_METHOD_INLINE: &str = "inline:";
+
+/// RTCSdesCrypto holds the keying material of an SDP Security Descriptions
+/// `a=crypto` attribute as described in [RFC 4568]. It is only used when
+/// SDES-SRTP is enabled in the SettingEngine.
+///
+/// [RFC 4568]: https://tools.ietf.org/html/rfc4568#section-9.1
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct RTCSdesCrypto {
+    /// Tag identifies the attribute within a media section, the answer
+    /// echoes the tag of the accepted offered attribute.
+    pub tag: u32,
+    pub profile: ProtectionProfile,
+    pub master_key: Vec<u8>,
+    pub master_salt: Vec<u8>,
+    /// Master Key Identifier carried in each SRTP/SRTCP packet, empty if
+    /// no MKI is used.
+    pub mki: Vec<u8>,
+    /// Key derivation rate from the `KDR` session parameter, the session keys
+    /// are derived again every `kdr` packets. 0 if the parameter is absent.
+    pub kdr: u64,
+}
+
+impl RTCSdesCrypto {
+    /// generate creates an attribute with a random master key and salt.
+    pub(crate) fn generate(tag: u32, profile: ProtectionProfile) -> Self {
+        let mut rng = rand::thread_rng();
+        let mut master_key = vec![0u8; profile.key_len()];
+        rng.fill(master_key.as_mut_slice());
+        let mut master_salt = vec![0u8; profile.salt_len()];
+        rng.fill(master_salt.as_mut_slice());
+
+        RTCSdesCrypto {
+            tag,
+            profile,
+            master_key,
+            master_salt,
+            mki: vec![],
+            kdr: 0,
+        }
+    }
+
+    /// unmarshal parses the value of an `a=crypto` attribute. Only the first
+    /// key parameter is used. The attribute is rejected if it carries a session
+    /// parameter that we can't honor, see RFC 4568 section 6.3.
+    pub(crate) fn unmarshal(value: &str) -> Result<Self> {
+        let mut fields = value.split_whitespace();
+        let (tag, suite, key_params) = match (fields.next(), fields.next(), fields.next()) {
+            (Some(tag), Some(suite), Some(key_params)) => (tag, suite, key_params),
+            _ => return Err(Error::ErrSessionDescriptionInvalidCrypto),
+        };
+
+        let tag = tag
+            .parse::<u32>()
+            .map_err(|_| Error::ErrSessionDescriptionInvalidCrypto)?;
+        let profile =
+            profile_from_crypto_suite(suite).ok_or(Error::ErrSessionDescriptionInvalidCrypto)?;
+
+        let key_param = key_params
+            .split(';')
+            .next()
+            .and_then(|p| p.strip_prefix(KEY_METHOD_INLINE))
+            .ok_or(Error::ErrSessionDescriptionInvalidCrypto)?;
+        let mut key_info = key_param.split('|');
+
+        let key_salt = BASE64_STANDARD
+            .decode(key_info.next().unwrap_or_default())
+            .map_err(|_| Error::ErrSessionDescriptionInvalidCrypto)?;
+        if key_salt.len() != profile.key_len() + profile.salt_len() {
+            return Err(Error::ErrSessionDescriptionInvalidCrypto);
+        }
+        let (master_key, master_salt) = key_salt.split_at(profile.key_len());
+
+        // The optional lifetime is followed by the optional "MKI:length" pair
+        let mut mki = vec![];
+        for info in key_info {
+            if let Some((value, length)) = info.split_once(':') {
+                let value = value
+                    .parse::<u128>()
+                    .map_err(|_| Error::ErrSessionDescriptionInvalidCrypto)?;
+                let length = length
+                    .parse::<usize>()
+                    .map_err(|_| Error::ErrSessionDescriptionInvalidCrypto)?;
+                if length == 0 || length > 16 || (length < 16 && value >> (length * 8) != 0) {
+                    return Err(Error::ErrSessionDescriptionInvalidCrypto);
+                }
+                mki = value.to_be_bytes()[16 - length..].to_vec();
+            }
+        }
+
+        let mut kdr = 0;
+        for param in fields {
+            if let Some(rate) = param.strip_prefix("KDR=") {
+                kdr = kdr_from_param(rate).ok_or(Error::ErrSessionDescriptionInvalidCrypto)?;
+            } else if param.starts_with("WSH=") || param == "FEC_ORDER=FEC_SRTP" {
+                // The replay window size is only a hint and FEC_SRTP is the default order
+            } else {
+                return Err(Error::ErrSessionDescriptionInvalidCrypto);
+            }
+        }
+
+        Ok(RTCSdesCrypto {
+            tag,
+            profile,
+            master_key: master_key.to_vec(),
+            master_salt: master_salt.to_vec(),
+            mki,
+            kdr,
+        })
+    }
+}
+
+impl fmt::Display for RTCSdesCrypto {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let key_salt =
+            BASE64_STANDARD.encode([&self.master_key[..], &self.master_salt[..]].concat());
+        write!(
+            f,
+            "{} {} {}{}",
+            self.tag,
+            crypto_suite(self.profile),
+            KEY_METHOD_INLINE,
+            key_salt
+        )?;
+        if !self.mki.is_empty() {
+            let value = self
+                .mki
+                .iter()
+                .fold(0u128, |value, b| (value << 8) | *b as u128);
+            write!(f, "|{}:{}", value, self.mki.len())?;
+        }
+        if self.kdr != 0 {
+            write!(f, " KDR={}", self.kdr.trailing_zeros())?;
+        }
+        Ok(())
+    }
+}
+
+/// crypto_suite returns the SDES crypto-suite name of an SRTP protection profile.
+fn crypto_suite(profile: ProtectionProfile) -> &'static str {
+    match profile {
+        ProtectionProfile::Aes128CmHmacSha1_80 => "AES_CM_128_HMAC_SHA1_80",
+        ProtectionProfile::Aes128CmHmacSha1_32 => "AES_CM_128_HMAC_SHA1_32",
+        ProtectionProfile::AeadAes128Gcm => "AEAD_AES_128_GCM",
+        ProtectionProfile::AeadAes256Gcm => "AEAD_AES_256_GCM",
+    }
+}
+
+/// kdr_from_param returns the key derivation rate of a `KDR` session parameter,
+/// the parameter is the base 2 logarithm of the rate in the range 1 to 24.
+fn kdr_from_param(param: &str) -> Option<u64> {
+    if param.starts_with('0') || !param.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    match param.parse::<u32>().ok()? {
+        exp @ 1..=24 => Some(1 << exp),
+        _ => None,
+    }
+}
+
+fn profile_from_crypto_suite(suite: &str) -> Option<ProtectionProfile> {
+    match suite {
+        "AES_CM_128_HMAC_SHA1_80" => Some(ProtectionProfile::Aes128CmHmacSha1_80),
+        "AES_CM_128_HMAC_SHA1_32" => Some(ProtectionProfile::Aes128CmHmacSha1_32),
+        "AEAD_AES_128_GCM" => Some(ProtectionProfile::AeadAes128Gcm),
+        "AEAD_AES_256_GCM" => Some(ProtectionProfile::AeadAes256Gcm),
+        _ => None,
+    }
+}
@@ -169,6 +169,19 @@ pub enum Error {
     #[error("set_remote_description called with multiple conflicting ice-pwd values")]
     ErrSessionDescriptionConflictingIcePwd,
 
+    /// ErrSessionDescriptionNoCrypto indicates set_remote_description was called with a SessionDescription that
+    /// has no a=crypto attribute with a supported crypto-suite while SDES-SRTP is enabled
+    #[error("set_remote_description called with no supported crypto attribute")]
+    ErrSessionDescriptionNoCrypto,
+
+    /// ErrSessionDescriptionInvalidCrypto indicates an a=crypto attribute could not be parsed
+    #[error("invalid crypto attribute")]
+    ErrSessionDescriptionInvalidCrypto,
+
+    /// ErrSdesNotNegotiated indicates SRTP was started with SDES before crypto attributes were negotiated
+    #[error("SDES-SRTP keys have not been negotiated")]
+    ErrSdesNotNegotiated,
+
     /// ErrNoSRTPProtectionProfile indicates that the DTLS handshake completed and no SRTP Protection Profile was chosen
     #[error("DTLS Handshake completed and no SRTP Protection Profile was chosen")]
     ErrNoSRTPProtectionProfile,
@@ -254,6 +267,12 @@ pub enum Error {
     ErrICEConnectionNotStarted,
     #[error("unknown candidate type")]
     ErrICECandidateTypeUnknown,
+    #[error("no media section with the sdpMid of the candidate")]
+    ErrICECandidateMidNotFound,
+    #[error("sdpMLineIndex of the candidate is out of range")]
+    ErrICECandidateMLineIndexOutOfRange,
+    #[error("usernameFragment of the candidate does not match the remote description")]
+    ErrICECandidateUfragMismatch,
     #[error("cannot convert ice.CandidateType into webrtc.ICECandidateType, invalid type")]
     ErrICEInvalidConvertCandidateType,
     #[error("ICEAgent does not exist")]
@@ -355,6 +374,12 @@ pub enum Error {
     ErrRTPTransceiverSetSendingInvalidState,
     #[error("unsupported codec type by this transceiver")]
     ErrRTPTransceiverCodecUnsupported,
+    #[error("unsupported header extension by this transceiver")]
+    ErrRTPTransceiverHeaderExtensionUnsupported,
+    #[error("unsupported codec for encoded frame transform")]
+    ErrRTPTransformCodecUnsupported,
+    #[error("neither PLI nor FIR was negotiated for the codec")]
+    ErrKeyframeRequestNotNegotiated,
     #[error("DTLS not established")]
     ErrSCTPTransportDTLS,
     #[error("add_transceiver_sdp() called with 0 transceivers")]
@@ -406,6 +431,8 @@ pub enum Error {
     #[error("{0}")]
     Srtp(#[from] srtp::Error),
     #[error("{0}")]
+    SFrame(#[from] sframe::Error),
+    #[error("{0}")]
     Dtls(#[from] dtls::Error),
     #[error("{0}")]
     Data(#[from] data::Error),
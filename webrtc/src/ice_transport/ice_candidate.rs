@@ -35,6 +35,8 @@ pub struct RTCIceCandidate {
     pub related_address: String,
     pub related_port: u16,
     pub tcp_type: String,
+    pub network_id: u16,
+    pub network_cost: u16,
 }
 
 /// Conversion for ice_candidates
@@ -66,6 +68,8 @@ impl From<&Arc<dyn Candidate + Send + Sync>> for RTCIceCandidate {
             tcp_type: c.tcp_type().to_string(),
             related_address,
             related_port,
+            network_id: c.network_id(),
+            network_cost: c.network_cost(),
         }
     }
 }
@@ -82,6 +86,8 @@ impl RTCIceCandidate {
             //tcp_type: ice.NewTCPType(c.TCPType),
             foundation: self.foundation.clone(),
             priority: self.priority,
+            network_id: self.network_id,
+            network_cost: self.network_cost,
             ..Default::default()
         };
 
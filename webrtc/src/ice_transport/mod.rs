@@ -275,7 +275,8 @@ impl RTCIceTransport {
         }
     }
 
-    /// adds a candidate associated with the remote ICETransport.
+    /// adds a candidate associated with the remote ICETransport, None signals
+    /// that the remote ICETransport has no more candidates.
     pub async fn add_remote_candidate(
         &self,
         remote_candidate: Option<RTCIceCandidate>,
@@ -286,6 +287,9 @@ impl RTCIceTransport {
             if let Some(r) = remote_candidate {
                 let c: Arc<dyn Candidate + Send + Sync> = Arc::new(r.to_ice()?);
                 agent.add_remote_candidate(&c)?;
+            } else {
+                // No more candidates will be trickled, RFC 8838 S13
+                agent.add_remote_end_of_candidates();
             }
 
             Ok(())
@@ -1,7 +1,9 @@
 #![warn(rust_2018_idioms)]
 #![allow(dead_code)]
 
-pub use {data, dtls, ice, interceptor, mdns, media, rtcp, rtp, sctp, sdp, srtp, stun, turn, util};
+pub use {
+    data, dtls, ice, interceptor, mdns, media, rtcp, rtp, sctp, sdp, sframe, srtp, stun, turn, util,
+};
 
 /// [`peer_connection::RTCPeerConnection`] allows to establish connection between two peers given RTC configuration. Its API is similar to one in JavaScript.
 pub mod peer_connection;
@@ -1538,6 +1538,13 @@ impl RTCPeerConnection {
                     .await?;
             }
 
+            if have_end_of_candidates(parsed) {
+                self.internal
+                    .ice_transport
+                    .add_remote_candidate(None)
+                    .await?;
+            }
+
             if is_renegotiation {
                 if we_offer {
                     self.start_rtp_senders().await?;
@@ -1564,7 +1571,15 @@ impl RTCPeerConnection {
 
             let remote_is_lite = Self::is_lite_set(parsed);
 
-            let (fingerprint, fingerprint_hash) = extract_fingerprint(parsed)?;
+            let (fingerprint, fingerprint_hash) = if self.internal.setting_engine.sdes_srtp {
+                self.internal
+                    .dtls_transport
+                    .negotiate_sdes(&extract_sdes_cryptos(parsed), !we_offer)
+                    .await?;
+                (String::new(), String::new())
+            } else {
+                extract_fingerprint(parsed)?
+            };
 
             // If one of the agents is lite and the other one is not, the lite agent must be the controlling agent.
             // If both or neither agents are lite the offering agent is controlling.
@@ -1578,6 +1593,16 @@ impl RTCPeerConnection {
                 RTCIceRole::Controlled
             };
 
+            // Renomination is only used if both agents advertise it
+            if self.internal.setting_engine.candidates.ice_renomination
+                && !have_ice_option(parsed, ICE_OPTION_RENOMINATION)
+            {
+                self.internal.ice_gatherer.create_agent().await?;
+                if let Some(agent) = self.internal.ice_gatherer.get_agent().await {
+                    agent.disable_renomination();
+                }
+            }
+
             // Start the networking in a new routine since it will block until
             // the connection is actually established.
             if we_offer {
@@ -1647,10 +1672,38 @@ impl RTCPeerConnection {
     }
 
     /// add_ice_candidate accepts an ICE candidate string and adds it
-    /// to the existing set of candidates.
+    /// to the existing set of candidates. An empty candidate string signals
+    /// that the remote peer has no more candidates to trickle.
+    ///
+    /// When set, sdp_mid or else sdp_mline_index must refer to a media section
+    /// of the remote description, and username_fragment must match its
+    /// ufrag, so candidates of a previous ICE generation are rejected after
+    /// an ICE restart.
     pub async fn add_ice_candidate(&self, candidate: RTCIceCandidateInit) -> Result<()> {
-        if self.remote_description().await.is_none() {
-            return Err(Error::ErrNoRemoteDescription);
+        let remote_description = match self.remote_description().await {
+            Some(remote_description) => remote_description,
+            None => return Err(Error::ErrNoRemoteDescription),
+        };
+
+        if let Some(parsed) = &remote_description.parsed {
+            let media = get_media_for_candidate(
+                parsed,
+                candidate.sdp_mid.as_deref(),
+                candidate.sdp_mline_index,
+            )?;
+
+            if let Some(ufrag) = candidate
+                .username_fragment
+                .as_deref()
+                .filter(|ufrag| !ufrag.is_empty())
+            {
+                let remote_ufrag = media
+                    .and_then(|m| m.attribute("ice-ufrag").flatten())
+                    .or_else(|| parsed.attribute("ice-ufrag").map(|s| s.as_str()));
+                if remote_ufrag != Some(ufrag) {
+                    return Err(Error::ErrICECandidateUfragMismatch);
+                }
+            }
         }
 
         let candidate_value = match candidate.candidate.strip_prefix("candidate:") {
@@ -1673,6 +1726,16 @@ impl RTCPeerConnection {
             .await
     }
 
+    /// can_trickle_ice_candidates returns whether the remote peer supports
+    /// trickled candidates, as advertised with ice-options in its description.
+    /// It is None until a remote description is set.
+    pub async fn can_trickle_ice_candidates(&self) -> Option<bool> {
+        self.remote_description()
+            .await
+            .and_then(|d| d.parsed)
+            .map(|parsed| have_ice_option(&parsed, ICE_OPTION_TRICKLE))
+    }
+
     /// ice_connection_state returns the ICE connection state of the
     /// PeerConnection instance.
     pub fn ice_connection_state(&self) -> RTCIceConnectionState {
@@ -282,7 +282,7 @@ impl PeerConnectionInternal {
         self.start_rtp_receivers(&mut track_details, &current_transceivers)
             .await?;
         if let Some(parsed) = &remote_desc.parsed {
-            if have_application_media_section(parsed) {
+            if have_application_media_section(parsed) && !self.setting_engine.sdes_srtp {
                 self.start_sctp().await;
             }
         }
@@ -699,17 +699,20 @@ impl PeerConnectionInternal {
             return;
         }
 
-        // Start the dtls_transport transport
-        let result = self
-            .dtls_transport
-            .start(DTLSParameters {
-                role: dtls_role,
-                fingerprints: vec![RTCDtlsFingerprint {
-                    algorithm: fingerprint_hash,
-                    value: fingerprint,
-                }],
-            })
-            .await;
+        // Start the dtls_transport transport, or key SRTP directly with SDES
+        let result = if self.setting_engine.sdes_srtp {
+            self.dtls_transport.start_sdes().await
+        } else {
+            self.dtls_transport
+                .start(DTLSParameters {
+                    role: dtls_role,
+                    fingerprints: vec![RTCDtlsFingerprint {
+                        algorithm: fingerprint_hash,
+                        value: fingerprint,
+                    }],
+                })
+                .await
+        };
         RTCPeerConnection::update_connection_state(
             &self.on_peer_connection_state_change_handler,
             &self.is_closed,
@@ -760,6 +763,7 @@ impl PeerConnectionInternal {
             .data_channels_requested
             .load(Ordering::SeqCst)
             != 0
+            && !self.setting_engine.sdes_srtp
         {
             media_sections.push(MediaSection {
                 id: format!("{}", media_sections.len()),
@@ -768,8 +772,10 @@ impl PeerConnectionInternal {
             });
         }
 
-        let dtls_fingerprints = if let Some(cert) = self.dtls_transport.certificates.first() {
-            cert.get_fingerprints()
+        let (dtls_fingerprints, sdes_cryptos) = if self.setting_engine.sdes_srtp {
+            (vec![], self.dtls_transport.sdes_local_cryptos().await)
+        } else if let Some(cert) = self.dtls_transport.certificates.first() {
+            (cert.get_fingerprints(), vec![])
         } else {
             return Err(Error::ErrNonCertificate);
         };
@@ -777,10 +783,12 @@ impl PeerConnectionInternal {
         let params = PopulateSdpParams {
             media_description_fingerprint: self.setting_engine.sdp_media_level_fingerprints,
             is_icelite: self.setting_engine.candidates.ice_lite,
+            ice_renomination: self.setting_engine.candidates.ice_renomination,
             extmap_allow_mixed: true,
             connection_role: DEFAULT_DTLS_ROLE_OFFER.to_connection_role(),
             ice_gathering_state: self.ice_gathering_state(),
             match_bundle_group: None,
+            sdes_cryptos,
         };
         populate_sdp(
             d,
@@ -884,6 +892,7 @@ impl PeerConnectionInternal {
                 .load(Ordering::SeqCst)
                 != 0
                 && !already_have_application_media_section
+                && !self.setting_engine.sdes_srtp
             {
                 media_sections.push(MediaSection {
                     id: format!("{}", media_sections.len()),
@@ -901,8 +910,10 @@ impl PeerConnectionInternal {
                 .or(Some(String::new()))
         };
 
-        let dtls_fingerprints = if let Some(cert) = self.dtls_transport.certificates.first() {
-            cert.get_fingerprints()
+        let (dtls_fingerprints, sdes_cryptos) = if self.setting_engine.sdes_srtp {
+            (vec![], self.dtls_transport.sdes_local_cryptos().await)
+        } else if let Some(cert) = self.dtls_transport.certificates.first() {
+            (cert.get_fingerprints(), vec![])
         } else {
             return Err(Error::ErrNonCertificate);
         };
@@ -910,10 +921,12 @@ impl PeerConnectionInternal {
         let params = PopulateSdpParams {
             media_description_fingerprint: self.setting_engine.sdp_media_level_fingerprints,
             is_icelite: self.setting_engine.candidates.ice_lite,
+            ice_renomination: self.setting_engine.candidates.ice_renomination,
             extmap_allow_mixed,
             connection_role,
             ice_gathering_state: self.ice_gathering_state(),
             match_bundle_group,
+            sdes_cryptos,
         };
         populate_sdp(
             d,
@@ -1113,6 +1126,7 @@ impl PeerConnectionInternal {
                                 rtp_interceptor: Some(rtp_interceptor),
                                 rtcp_read_stream: Some(rtcp_read_stream),
                                 rtcp_interceptor: Some(rtcp_interceptor),
+                                rtcp_reads: None,
                             },
                         )
                         .await;
@@ -1128,6 +1142,7 @@ impl PeerConnectionInternal {
                             rtp_interceptor: Some(rtp_interceptor),
                             rtcp_read_stream: Some(rtcp_read_stream),
                             rtcp_interceptor: Some(rtcp_interceptor),
+                            rtcp_reads: None,
                         },
                     )
                     .await?;
@@ -636,6 +636,139 @@ async fn test_peer_connection_simulcast_no_data_channel() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_peer_connection_sdes_srtp() -> Result<()> {
+    let mut s = SettingEngine::default();
+    s.enable_sdes_srtp(true);
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    let api = APIBuilder::new()
+        .with_setting_engine(s)
+        .with_media_engine(m)
+        .build();
+
+    let (mut pc_offer, mut pc_answer) = new_pair(&api).await?;
+    let (offer_notifier, mut offer_connected) = on_connected();
+    let (answer_notifier, mut answer_connected) = on_connected();
+    pc_offer.on_peer_connection_state_change(offer_notifier);
+    pc_answer.on_peer_connection_state_change(answer_notifier);
+
+    let (done_tx, done_rx) = mpsc::channel(1);
+    pc_answer.on_track(Box::new(move |_, _, _| {
+        let _ = done_tx.try_send(());
+        Box::pin(async move {})
+    }));
+
+    let track = Arc::new(TrackLocalStaticSample::new(
+        RTCRtpCodecCapability {
+            mime_type: MIME_TYPE_VP8.to_owned(),
+            ..Default::default()
+        },
+        "video".to_owned(),
+        "webrtc-rs".to_owned(),
+    ));
+    pc_offer
+        .add_track(Arc::clone(&track) as Arc<dyn TrackLocal + Send + Sync>)
+        .await?;
+
+    signal_pair(&mut pc_offer, &mut pc_answer).await?;
+
+    let offer = pc_offer.local_description().await.unwrap();
+    assert!(offer.sdp.contains("m=video 9 RTP/SAVPF"));
+    assert!(offer.sdp.contains("a=crypto:1 AEAD_AES_128_GCM inline:"));
+    assert!(offer
+        .sdp
+        .contains("a=crypto:3 AES_CM_128_HMAC_SHA1_80 inline:"));
+    assert!(!offer.sdp.contains("a=fingerprint"));
+    assert!(!offer.sdp.contains("m=application"));
+
+    let answer = pc_answer.local_description().await.unwrap();
+    assert_eq!(answer.sdp.matches("a=crypto:1 AEAD_AES_128_GCM").count(), 1);
+    assert!(!answer.sdp.contains("a=crypto:3"));
+
+    let _ = offer_connected.recv().await;
+    let _ = answer_connected.recv().await;
+
+    send_video_until_done(
+        done_rx,
+        vec![track],
+        Bytes::from_static(&[0, 1, 2, 3]),
+        None,
+    )
+    .await;
+
+    close_pair_now(&pc_offer, &pc_answer).await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_add_ice_candidate_scoping() -> Result<()> {
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    let api = APIBuilder::new().with_media_engine(m).build();
+    let (pc_offer, pc_answer) = new_pair(&api).await?;
+
+    pc_offer.create_data_channel("data", None).await?;
+    let offer = pc_offer.create_offer(None).await?;
+    pc_offer.set_local_description(offer.clone()).await?;
+
+    assert_eq!(pc_answer.can_trickle_ice_candidates().await, None);
+    pc_answer.set_remote_description(offer).await?;
+    assert_eq!(pc_answer.can_trickle_ice_candidates().await, Some(true));
+
+    let ufrag = pc_offer
+        .internal
+        .ice_gatherer
+        .get_local_parameters()
+        .await?
+        .username_fragment;
+    let candidate =
+        |sdp_mid: Option<&str>, sdp_mline_index: Option<u16>, ufrag: &str| RTCIceCandidateInit {
+            candidate: "candidate:1 1 udp 2130706431 10.0.75.1 53634 typ host".to_owned(),
+            sdp_mid: sdp_mid.map(|mid| mid.to_owned()),
+            sdp_mline_index,
+            username_fragment: Some(ufrag.to_owned()),
+        };
+
+    assert_eq!(
+        pc_answer
+            .add_ice_candidate(candidate(Some("1"), None, &ufrag))
+            .await,
+        Err(Error::ErrICECandidateMidNotFound)
+    );
+    assert_eq!(
+        pc_answer
+            .add_ice_candidate(candidate(None, Some(1), &ufrag))
+            .await,
+        Err(Error::ErrICECandidateMLineIndexOutOfRange)
+    );
+    assert_eq!(
+        pc_answer
+            .add_ice_candidate(candidate(Some("0"), None, "previous"))
+            .await,
+        Err(Error::ErrICECandidateUfragMismatch)
+    );
+    pc_answer
+        .add_ice_candidate(candidate(Some("0"), None, &ufrag))
+        .await?;
+    pc_answer
+        .add_ice_candidate(candidate(None, Some(0), &ufrag))
+        .await?;
+
+    // End of candidates
+    pc_answer
+        .add_ice_candidate(RTCIceCandidateInit {
+            sdp_mid: Some("0".to_owned()),
+            ..Default::default()
+        })
+        .await?;
+
+    close_pair_now(&pc_offer, &pc_answer).await;
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_peer_connection_state() -> Result<()> {
     let mut m = MediaEngine::default();
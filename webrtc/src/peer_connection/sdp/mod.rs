@@ -3,6 +3,7 @@ mod sdp_test;
 
 use crate::api::media_engine::MediaEngine;
 use crate::dtls_transport::dtls_fingerprint::RTCDtlsFingerprint;
+use crate::dtls_transport::sdes_crypto::RTCSdesCrypto;
 use crate::error::{Error, Result};
 use crate::ice_transport::ice_candidate::RTCIceCandidate;
 use crate::ice_transport::ice_gatherer::RTCIceGatherer;
@@ -12,7 +13,9 @@ use crate::rtp_transceiver::rtp_codec::{
     RTCRtpCodecCapability, RTCRtpCodecParameters, RTPCodecType,
 };
 use crate::rtp_transceiver::rtp_transceiver_direction::RTCRtpTransceiverDirection;
-use crate::rtp_transceiver::{PayloadType, RTCPFeedback, RTCRtpTransceiver, SSRC};
+use crate::rtp_transceiver::{
+    PayloadType, RTCPFeedback, RTCRtpTransceiver, RTCP_FB_PARAMETER_CCFB, SSRC, TYPE_RTCP_FB_ACK,
+};
 
 pub mod sdp_type;
 pub mod session_description;
@@ -316,6 +319,12 @@ pub(crate) async fn add_candidates_to_media_descriptions(
     Ok(m.with_property_attribute("end-of-candidates".to_owned()))
 }
 
+/// The ice-options value advertising trickle ICE support, RFC 8840 S4.1.1
+pub(crate) const ICE_OPTION_TRICKLE: &str = "trickle";
+
+/// The ice-options value advertising support for renominating the selected candidate pair
+pub(crate) const ICE_OPTION_RENOMINATION: &str = "renomination";
+
 pub(crate) struct AddDataMediaSectionParams {
     should_add_candidates: bool,
     mid_value: String,
@@ -425,6 +434,7 @@ pub(crate) struct AddTransceiverSdpParams {
     dtls_role: ConnectionRole,
     ice_gathering_state: RTCIceGatheringState,
     offered_direction: Option<RTCRtpTransceiverDirection>,
+    sdes_cryptos: Vec<RTCSdesCrypto>,
 }
 
 pub(crate) async fn add_transceiver_sdp(
@@ -449,8 +459,14 @@ pub(crate) async fn add_transceiver_sdp(
     let transceivers = &media_section.transceivers;
     // Use the first transceiver to generate the section attributes
     let t = &transceivers[0];
-    let mut media = MediaDescription::new_jsep_media_description(t.kind.to_string(), vec![])
-        .with_value_attribute(ATTR_KEY_CONNECTION_SETUP.to_owned(), dtls_role.to_string())
+    let mut media = MediaDescription::new_jsep_media_description(t.kind.to_string(), vec![]);
+    if params.sdes_cryptos.is_empty() {
+        media =
+            media.with_value_attribute(ATTR_KEY_CONNECTION_SETUP.to_owned(), dtls_role.to_string());
+    } else {
+        media.media_name.protos = sdes_media_protos();
+    }
+    media = media
         .with_value_attribute(ATTR_KEY_MID.to_owned(), mid_value.clone())
         .with_ice_credentials(
             ice_params.username_fragment.clone(),
@@ -464,6 +480,15 @@ pub(crate) async fn add_transceiver_sdp(
     }
 
     let codecs = t.get_codecs().await;
+    // RFC 8888 feedback is negotiated for the whole media section rather than per payload type
+    let ccfb = RTCPFeedback {
+        typ: TYPE_RTCP_FB_ACK.to_owned(),
+        parameter: RTCP_FB_PARAMETER_CCFB.to_owned(),
+    };
+    let wildcard_ccfb = !codecs.is_empty()
+        && codecs
+            .iter()
+            .all(|c| c.capability.rtcp_feedback.contains(&ccfb));
     for codec in &codecs {
         let name = codec
             .capability
@@ -480,6 +505,9 @@ pub(crate) async fn add_transceiver_sdp(
         );
 
         for feedback in &codec.capability.rtcp_feedback {
+            if wildcard_ccfb && *feedback == ccfb {
+                continue;
+            }
             media = media.with_value_attribute(
                 "rtcp-fb".to_owned(),
                 format!(
@@ -489,6 +517,12 @@ pub(crate) async fn add_transceiver_sdp(
             );
         }
     }
+    if wildcard_ccfb {
+        media = media.with_value_attribute(
+            "rtcp-fb".to_owned(),
+            format!("* {} {}", ccfb.typ, ccfb.parameter),
+        );
+    }
     if codecs.is_empty() {
         // If we are sender and we have no codecs throw an error early
         if t.sender().await.track().await.is_some() {
@@ -503,12 +537,16 @@ pub(crate) async fn add_transceiver_sdp(
                     value: 0,
                     range: None,
                 },
-                protos: vec![
-                    "UDP".to_owned(),
-                    "TLS".to_owned(),
-                    "RTP".to_owned(),
-                    "SAVPF".to_owned(),
-                ],
+                protos: if params.sdes_cryptos.is_empty() {
+                    vec![
+                        "UDP".to_owned(),
+                        "TLS".to_owned(),
+                        "RTP".to_owned(),
+                        "SAVPF".to_owned(),
+                    ]
+                } else {
+                    sdes_media_protos()
+                },
                 formats: vec!["0".to_owned()],
             },
             media_title: None,
@@ -533,7 +571,7 @@ pub(crate) async fn add_transceiver_sdp(
     }
 
     let parameters = media_engine.get_rtp_parameters_by_kind(t.kind, t.direction());
-    for rtp_extension in &parameters.header_extensions {
+    for rtp_extension in &t.filter_header_extensions(parameters.header_extensions) {
         let ext_url = Url::parse(rtp_extension.uri.as_str())?;
         media = media.with_extmap(sdp::extmap::ExtMap {
             value: rtp_extension.id,
@@ -709,6 +747,10 @@ pub(crate) async fn add_transceiver_sdp(
         );
     }
 
+    for crypto in &params.sdes_cryptos {
+        media = media.with_value_attribute(ATTR_KEY_CRYPTO.to_owned(), crypto.to_string());
+    }
+
     if should_add_candidates {
         media =
             add_candidates_to_media_descriptions(candidates, media, ice_gathering_state).await?;
@@ -796,10 +838,13 @@ pub(crate) struct MediaSection {
 pub(crate) struct PopulateSdpParams {
     pub(crate) media_description_fingerprint: bool,
     pub(crate) is_icelite: bool,
+    pub(crate) ice_renomination: bool,
     pub(crate) extmap_allow_mixed: bool,
     pub(crate) connection_role: ConnectionRole,
     pub(crate) ice_gathering_state: RTCIceGatheringState,
     pub(crate) match_bundle_group: Option<String>,
+    /// SDES-SRTP keys to offer or answer with, empty when SRTP is keyed with DTLS
+    pub(crate) sdes_cryptos: Vec<RTCSdesCrypto>,
 }
 
 /// populate_sdp serializes a PeerConnections state into an SDP
@@ -818,6 +863,8 @@ pub(crate) async fn populate_sdp(
         vec![]
     };
 
+    let sdes = !params.sdes_cryptos.is_empty();
+
     let mut bundle_value = "BUNDLE".to_owned();
     let mut bundle_count = 0;
     let append_bundle = |mid_value: &str, value: &mut String, count: &mut i32| {
@@ -843,7 +890,16 @@ pub(crate) async fn populate_sdp(
                 ice_gathering_state: params.ice_gathering_state,
             };
             d = add_data_media_section(d, &media_dtls_fingerprints, candidates, params).await?;
-            true
+            if sdes {
+                // Data channels run over DTLS, reject them when SRTP is keyed with SDES
+                if let Some(desc) = d.media_descriptions.last_mut() {
+                    desc.media_name.port = RangedPort {
+                        value: 0,
+                        range: None,
+                    }
+                }
+            }
+            !sdes
         } else {
             let params = AddTransceiverSdpParams {
                 should_add_candidates,
@@ -851,6 +907,7 @@ pub(crate) async fn populate_sdp(
                 dtls_role: params.connection_role,
                 ice_gathering_state: params.ice_gathering_state,
                 offered_direction: m.offered_direction,
+                sdes_cryptos: params.sdes_cryptos.clone(),
             };
             let (d1, should_add_id) = add_transceiver_sdp(
                 d,
@@ -892,6 +949,14 @@ pub(crate) async fn populate_sdp(
         d = d.with_value_attribute(ATTR_KEY_ICELITE.to_owned(), ATTR_KEY_ICELITE.to_owned());
     }
 
+    // Candidates are trickled with on_ice_candidate, RFC 8840 S4.1.1
+    let ice_options = if params.ice_renomination {
+        format!("{ICE_OPTION_TRICKLE} {ICE_OPTION_RENOMINATION}")
+    } else {
+        ICE_OPTION_TRICKLE.to_owned()
+    };
+    d = d.with_value_attribute(ATTR_KEY_ICE_OPTIONS.to_owned(), ice_options);
+
     if bundle_count > 0 {
         d = d.with_value_attribute(ATTR_KEY_GROUP.to_owned(), bundle_value);
     }
@@ -954,6 +1019,35 @@ pub(crate) fn extract_fingerprint(desc: &SessionDescription) -> Result<(String,
     Ok((parts[1].to_owned(), parts[0].to_owned()))
 }
 
+/// extract_sdes_cryptos returns the supported SDES-SRTP crypto attributes of the first media
+/// section that has any. All media sections are bundled on a single transport.
+pub(crate) fn extract_sdes_cryptos(desc: &SessionDescription) -> Vec<RTCSdesCrypto> {
+    for m in &desc.media_descriptions {
+        let cryptos: Vec<RTCSdesCrypto> = m
+            .attributes
+            .iter()
+            .filter(|a| a.key == ATTR_KEY_CRYPTO)
+            .filter_map(|a| a.value.as_ref())
+            .filter_map(|value| match RTCSdesCrypto::unmarshal(value) {
+                Ok(crypto) => Some(crypto),
+                Err(err) => {
+                    log::debug!("skipping crypto attribute {}: {}", value, err);
+                    None
+                }
+            })
+            .collect();
+        if !cryptos.is_empty() {
+            return cryptos;
+        }
+    }
+
+    vec![]
+}
+
+fn sdes_media_protos() -> Vec<String> {
+    vec!["RTP".to_owned(), "SAVPF".to_owned()]
+}
+
 pub(crate) async fn extract_ice_details(
     desc: &SessionDescription,
 ) -> Result<(String, String, Vec<RTCIceCandidate>)> {
@@ -1019,6 +1113,55 @@ pub(crate) async fn extract_ice_details(
     Ok((remote_ufrag.to_owned(), remote_pwd.to_owned(), candidates))
 }
 
+/// have_ice_option returns whether the session or any of its media sections list
+/// the ICE option in ice-options.
+pub(crate) fn have_ice_option(desc: &SessionDescription, option: &str) -> bool {
+    let has_option = |value: &str| value.split_whitespace().any(|o| o == option);
+
+    desc.attribute(ATTR_KEY_ICE_OPTIONS)
+        .is_some_and(|value| has_option(value))
+        || desc.media_descriptions.iter().any(|m| {
+            m.attribute(ATTR_KEY_ICE_OPTIONS)
+                .flatten()
+                .is_some_and(has_option)
+        })
+}
+
+/// have_end_of_candidates returns whether the remote agent signalled in the
+/// description that it won't trickle any more candidates.
+pub(crate) fn have_end_of_candidates(desc: &SessionDescription) -> bool {
+    desc.media_descriptions
+        .iter()
+        .any(|m| m.attribute(ATTR_KEY_END_OF_CANDIDATES).is_some())
+}
+
+/// get_media_for_candidate returns the media section a trickled candidate is
+/// scoped to, by sdpMid or else by sdpMLineIndex. None if it isn't scoped.
+pub(crate) fn get_media_for_candidate<'a>(
+    desc: &'a SessionDescription,
+    sdp_mid: Option<&str>,
+    sdp_mline_index: Option<u16>,
+) -> Result<Option<&'a MediaDescription>> {
+    if let Some(mid) = sdp_mid.filter(|mid| !mid.is_empty()) {
+        return desc
+            .media_descriptions
+            .iter()
+            .find(|m| get_mid_value(m).map(|v| v.as_str()) == Some(mid))
+            .map(Some)
+            .ok_or(Error::ErrICECandidateMidNotFound);
+    }
+
+    if let Some(index) = sdp_mline_index {
+        return desc
+            .media_descriptions
+            .get(index as usize)
+            .map(Some)
+            .ok_or(Error::ErrICECandidateMLineIndexOutOfRange);
+    }
+
+    Ok(None)
+}
+
 pub(crate) fn have_application_media_section(desc: &SessionDescription) -> bool {
     for m in &desc.media_descriptions {
         if m.media_name.media == MEDIA_SECTION_APPLICATION {
@@ -568,6 +568,111 @@ fn test_have_application_media_section() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_trickle_ice_attributes() -> Result<()> {
+    let media = |mid: &str, attributes: Vec<Attribute>| MediaDescription {
+        media_name: MediaName {
+            media: "audio".to_owned(),
+            ..Default::default()
+        },
+        attributes: [
+            vec![Attribute {
+                key: ATTR_KEY_MID.to_owned(),
+                value: Some(mid.to_owned()),
+            }],
+            attributes,
+        ]
+        .concat(),
+        ..Default::default()
+    };
+
+    let s = SessionDescription {
+        media_descriptions: vec![
+            media("audio", vec![]),
+            media(
+                "video",
+                vec![
+                    Attribute {
+                        key: ATTR_KEY_ICE_OPTIONS.to_owned(),
+                        value: Some("ice2 trickle renomination".to_owned()),
+                    },
+                    Attribute {
+                        key: ATTR_KEY_END_OF_CANDIDATES.to_owned(),
+                        value: None,
+                    },
+                ],
+            ),
+        ],
+        ..Default::default()
+    };
+
+    assert!(have_ice_option(&s, ICE_OPTION_TRICKLE));
+    assert!(have_ice_option(&s, ICE_OPTION_RENOMINATION));
+    assert!(!have_ice_option(&s, "trick"));
+    assert!(!have_ice_option(
+        &SessionDescription::default(),
+        ICE_OPTION_RENOMINATION
+    ));
+    assert!(have_end_of_candidates(&s));
+    assert!(!have_ice_option(
+        &SessionDescription::default(),
+        ICE_OPTION_TRICKLE
+    ));
+    assert!(!have_end_of_candidates(&SessionDescription::default()));
+
+    let m = get_media_for_candidate(&s, Some("video"), Some(0))?;
+    assert_eq!(m.and_then(get_mid_value).map(|v| v.as_str()), Some("video"));
+    let m = get_media_for_candidate(&s, Some(""), Some(0))?;
+    assert_eq!(m.and_then(get_mid_value).map(|v| v.as_str()), Some("audio"));
+    assert!(get_media_for_candidate(&s, None, None)?.is_none());
+    assert_eq!(
+        get_media_for_candidate(&s, Some("data"), None).unwrap_err(),
+        Error::ErrICECandidateMidNotFound
+    );
+    assert_eq!(
+        get_media_for_candidate(&s, None, Some(2)).unwrap_err(),
+        Error::ErrICECandidateMLineIndexOutOfRange
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_populate_sdp_ice_options() -> Result<()> {
+    let engine = Arc::new(MediaEngine::default());
+
+    for ice_renomination in [false, true] {
+        let params = PopulateSdpParams {
+            media_description_fingerprint: false,
+            is_icelite: false,
+            ice_renomination,
+            extmap_allow_mixed: false,
+            connection_role: ConnectionRole::Active,
+            ice_gathering_state: RTCIceGatheringState::New,
+            match_bundle_group: None,
+            sdes_cryptos: vec![],
+        };
+        let s = populate_sdp(
+            SessionDescription::default(),
+            &[],
+            &engine,
+            &[],
+            &RTCIceParameters::default(),
+            &[],
+            params,
+        )
+        .await?;
+
+        assert!(have_ice_option(&s, ICE_OPTION_TRICKLE));
+        assert_eq!(
+            have_ice_option(&s, ICE_OPTION_RENOMINATION),
+            ice_renomination
+        );
+    }
+
+    Ok(())
+}
+
 async fn fingerprint_test(
     certificate: &RTCCertificate,
     engine: &Arc<MediaEngine>,
@@ -582,10 +687,12 @@ async fn fingerprint_test(
     let params = PopulateSdpParams {
         media_description_fingerprint: sdpmedia_description_fingerprints,
         is_icelite: false,
+        ice_renomination: false,
         extmap_allow_mixed: false,
         connection_role: ConnectionRole::Active,
         ice_gathering_state: RTCIceGatheringState::New,
         match_bundle_group: None,
+        sdes_cryptos: vec![],
     };
 
     let s = populate_sdp(
@@ -779,10 +886,12 @@ async fn test_populate_sdp() -> Result<()> {
         let params = PopulateSdpParams {
             media_description_fingerprint: se.sdp_media_level_fingerprints,
             is_icelite: se.candidates.ice_lite,
+            ice_renomination: se.candidates.ice_renomination,
             extmap_allow_mixed: true,
             connection_role: DEFAULT_DTLS_ROLE_OFFER.to_connection_role(),
             ice_gathering_state: RTCIceGatheringState::Complete,
             match_bundle_group: None,
+            sdes_cryptos: vec![],
         };
         let offer_sdp = populate_sdp(
             d,
@@ -885,10 +994,12 @@ async fn test_populate_sdp() -> Result<()> {
         let params = PopulateSdpParams {
             media_description_fingerprint: se.sdp_media_level_fingerprints,
             is_icelite: se.candidates.ice_lite,
+            ice_renomination: se.candidates.ice_renomination,
             extmap_allow_mixed: true,
             connection_role: DEFAULT_DTLS_ROLE_OFFER.to_connection_role(),
             ice_gathering_state: RTCIceGatheringState::Complete,
             match_bundle_group: None,
+            sdes_cryptos: vec![],
         };
         let offer_sdp = populate_sdp(
             d,
@@ -966,10 +1077,12 @@ async fn test_populate_sdp() -> Result<()> {
         let params = PopulateSdpParams {
             media_description_fingerprint: se.sdp_media_level_fingerprints,
             is_icelite: se.candidates.ice_lite,
+            ice_renomination: se.candidates.ice_renomination,
             extmap_allow_mixed: true,
             connection_role: DEFAULT_DTLS_ROLE_OFFER.to_connection_role(),
             ice_gathering_state: RTCIceGatheringState::Complete,
             match_bundle_group: None,
+            sdes_cryptos: vec![],
         };
         let offer_sdp = populate_sdp(
             d,
@@ -1064,10 +1177,12 @@ async fn test_populate_sdp() -> Result<()> {
         let params = PopulateSdpParams {
             media_description_fingerprint: se.sdp_media_level_fingerprints,
             is_icelite: se.candidates.ice_lite,
+            ice_renomination: se.candidates.ice_renomination,
             extmap_allow_mixed: true,
             connection_role: DEFAULT_DTLS_ROLE_OFFER.to_connection_role(),
             ice_gathering_state: RTCIceGatheringState::Complete,
             match_bundle_group: Some("audio".to_owned()),
+            sdes_cryptos: vec![],
         };
         let offer_sdp = populate_sdp(
             d,
@@ -1130,10 +1245,12 @@ async fn test_populate_sdp() -> Result<()> {
         let params = PopulateSdpParams {
             media_description_fingerprint: se.sdp_media_level_fingerprints,
             is_icelite: se.candidates.ice_lite,
+            ice_renomination: se.candidates.ice_renomination,
             extmap_allow_mixed: true,
             connection_role: DEFAULT_DTLS_ROLE_OFFER.to_connection_role(),
             ice_gathering_state: RTCIceGatheringState::Complete,
             match_bundle_group: Some("".to_owned()),
+            sdes_cryptos: vec![],
         };
         let offer_sdp = populate_sdp(
             d,
@@ -1240,10 +1357,12 @@ async fn test_populate_sdp() -> Result<()> {
         let params = PopulateSdpParams {
             media_description_fingerprint: se.sdp_media_level_fingerprints,
             is_icelite: se.candidates.ice_lite,
+            ice_renomination: se.candidates.ice_renomination,
             extmap_allow_mixed: true,
             connection_role: DEFAULT_DTLS_ROLE_OFFER.to_connection_role(),
             ice_gathering_state: RTCIceGatheringState::Complete,
             match_bundle_group: None,
+            sdes_cryptos: vec![],
         };
         let offer_sdp = populate_sdp(
             d,
@@ -1395,10 +1514,12 @@ async fn test_populate_sdp_reject() -> Result<()> {
     let params = PopulateSdpParams {
         media_description_fingerprint: se.sdp_media_level_fingerprints,
         is_icelite: se.candidates.ice_lite,
+        ice_renomination: se.candidates.ice_renomination,
         extmap_allow_mixed: true,
         connection_role: DEFAULT_DTLS_ROLE_OFFER.to_connection_role(),
         ice_gathering_state: RTCIceGatheringState::Complete,
         match_bundle_group: None,
+        sdes_cryptos: vec![],
     };
     let offer_sdp = populate_sdp(
         d,
@@ -1557,6 +1678,36 @@ fn test_codecs_from_media_description() -> Result<()> {
         );
     }
 
+    //"Codec with wildcard rtcp-fb"
+    {
+        let codecs = codecs_from_media_description(&MediaDescription {
+            media_name: MediaName {
+                media: "audio".to_owned(),
+                formats: vec!["111".to_owned()],
+                ..Default::default()
+            },
+            attributes: vec![
+                Attribute {
+                    key: "rtpmap".to_owned(),
+                    value: Some("111 opus/48000/2".to_owned()),
+                },
+                Attribute {
+                    key: "rtcp-fb".to_owned(),
+                    value: Some("* ack ccfb".to_owned()),
+                },
+            ],
+            ..Default::default()
+        })?;
+
+        assert_eq!(
+            codecs[0].capability.rtcp_feedback,
+            vec![RTCPFeedback {
+                typ: "ack".to_owned(),
+                parameter: "ccfb".to_owned()
+            }]
+        );
+    }
+
     Ok(())
 }
 
@@ -1586,3 +1737,69 @@ fn test_rtp_extensions_from_media_description() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_extract_sdes_cryptos() -> Result<()> {
+    let crypto = |value: &str| Attribute {
+        key: ATTR_KEY_CRYPTO.to_owned(),
+        value: Some(value.to_owned()),
+    };
+    let s = SessionDescription {
+        media_descriptions: vec![
+            MediaDescription {
+                attributes: vec![Attribute::new(ATTR_KEY_MID.to_owned(), Some("0".to_owned()))],
+                ..Default::default()
+            },
+            MediaDescription {
+                attributes: vec![
+                    crypto("1 AES_256_CM_HMAC_SHA1_80 inline:d0RmdmcmVCspeEc3QGZiNWpVLFJhQX1cfHAwJSoj"),
+                    crypto("2 AES_CM_128_HMAC_SHA1_80 inline:PS1uQCVeeCFCanVmcjkpPywjNWhcYD0mXXtxaVBR|2^20|1:4"),
+                    crypto("3 AES_CM_128_HMAC_SHA1_32 inline:PS1uQCVeeCFCanVmcjkpPywjNWhcYD0mXXtxaVBR"),
+                    crypto("4 AES_CM_128_HMAC_SHA1_32 inline:tooshort"),
+                    crypto("5 AES_CM_128_HMAC_SHA1_80 inline:PS1uQCVeeCFCanVmcjkpPywjNWhcYD0mXXtxaVBR KDR=10 WSH=128"),
+                    crypto("6 AES_CM_128_HMAC_SHA1_80 inline:PS1uQCVeeCFCanVmcjkpPywjNWhcYD0mXXtxaVBR UNENCRYPTED_SRTP"),
+                    crypto("7 AES_CM_128_HMAC_SHA1_80 inline:PS1uQCVeeCFCanVmcjkpPywjNWhcYD0mXXtxaVBR FOO=1"),
+                    crypto("8 AES_CM_128_HMAC_SHA1_80 inline:PS1uQCVeeCFCanVmcjkpPywjNWhcYD0mXXtxaVBR KDR=25"),
+                ],
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+
+    let cryptos = extract_sdes_cryptos(&s);
+    assert_eq!(cryptos.len(), 3);
+
+    assert_eq!(cryptos[0].tag, 2);
+    assert_eq!(
+        cryptos[0].profile,
+        srtp::protection_profile::ProtectionProfile::Aes128CmHmacSha1_80
+    );
+    assert_eq!(cryptos[0].master_key, b"=-n@%^x!Bjufr9)?".to_vec());
+    assert_eq!(cryptos[0].master_salt, b",#5h\\`=&]{qiPQ".to_vec());
+    assert_eq!(cryptos[0].mki, vec![0, 0, 0, 1]);
+    assert_eq!(cryptos[0].kdr, 0);
+    assert_eq!(
+        cryptos[0].to_string(),
+        "2 AES_CM_128_HMAC_SHA1_80 inline:PS1uQCVeeCFCanVmcjkpPywjNWhcYD0mXXtxaVBR|1:4"
+    );
+
+    assert_eq!(cryptos[1].tag, 3);
+    assert!(cryptos[1].mki.is_empty());
+    assert_eq!(
+        RTCSdesCrypto::unmarshal(&cryptos[1].to_string())?,
+        cryptos[1]
+    );
+
+    assert_eq!(cryptos[2].tag, 5);
+    assert_eq!(cryptos[2].kdr, 1 << 10);
+    assert_eq!(
+        cryptos[2].to_string(),
+        "5 AES_CM_128_HMAC_SHA1_80 inline:PS1uQCVeeCFCanVmcjkpPywjNWhcYD0mXXtxaVBR KDR=10"
+    );
+    assert!(RTCSdesCrypto::unmarshal(&cryptos[2].to_string().replace("=10", "=010")).is_err());
+
+    assert!(extract_sdes_cryptos(&SessionDescription::default()).is_empty());
+
+    Ok(())
+}
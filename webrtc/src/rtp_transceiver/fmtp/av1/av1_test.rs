@@ -0,0 +1,29 @@
+use super::*;
+
+#[test]
+fn test_av1_fmtp_compare() {
+    let tests = vec![
+        (
+            "SameProfile",
+            "profile=0;level-idx=5;tier=0",
+            "profile=0;level-idx=5;tier=0",
+            true,
+        ),
+        ("DefaultProfile", "", "level-idx=5;profile=0;tier=0", true),
+        (
+            "LevelAsymmetry",
+            "profile=0;level-idx=8;tier=1",
+            "profile=0;level-idx=5;tier=0",
+            true,
+        ),
+        ("DifferentProfile", "profile=0", "profile=1", false),
+        ("DefaultDifferentProfile", "level-idx=5", "profile=2", false),
+    ];
+
+    for (name, a, b, expected) in tests {
+        let aa = parse("video/AV1", a);
+        let bb = parse("video/av1", b);
+        assert_eq!(aa.match_fmtp(&*bb), expected, "{name} failed");
+        assert_eq!(bb.match_fmtp(&*aa), expected, "{name} failed (reverse)");
+    }
+}
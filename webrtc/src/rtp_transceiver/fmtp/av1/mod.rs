@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod av1_test;
+
+use super::*;
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Av1Fmtp {
+    pub(crate) parameters: HashMap<String, String>,
+}
+
+impl Av1Fmtp {
+    /// profile returns the AV1 profile, 0 (Main) when the parameter is absent
+    fn profile(&self) -> Option<u8> {
+        parameter_or(&self.parameters, "profile", 0)
+    }
+}
+
+impl Fmtp for Av1Fmtp {
+    fn mime_type(&self) -> &str {
+        "video/av1"
+    }
+
+    /// Match returns true if a and b are compatible fmtp descriptions
+    /// Based on the AV1 RTP Payload Format Section 7.2:
+    ///   The profile parameter MUST be used symmetrically, a missing profile
+    ///   means profile 0. level-idx and tier describe the highest level the
+    ///   receiver is able to decode and may differ between offer and answer.
+    fn match_fmtp(&self, f: &dyn Fmtp) -> bool {
+        if let Some(c) = f.as_any().downcast_ref::<Av1Fmtp>() {
+            match (self.profile(), c.profile()) {
+                (Some(a), Some(b)) => a == b,
+                _ => false,
+            }
+        } else {
+            false
+        }
+    }
+
+    fn parameter(&self, key: &str) -> Option<&String> {
+        self.parameters.get(key)
+    }
+
+    fn equal(&self, other: &dyn Fmtp) -> bool {
+        other.as_any().downcast_ref::<Av1Fmtp>() == Some(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -0,0 +1,49 @@
+use super::*;
+
+#[test]
+fn test_h265_fmtp_compare() {
+    let tests = vec![
+        (
+            "Equal",
+            "profile-id=1;tier-flag=0;level-id=93;tx-mode=SRST",
+            "profile-id=1;tier-flag=0;level-id=93;tx-mode=SRST",
+            true,
+        ),
+        (
+            "Defaults",
+            "",
+            "profile-space=0;profile-id=1;tier-flag=0;tx-mode=srst",
+            true,
+        ),
+        (
+            "LevelAsymmetry",
+            "profile-id=1;level-id=180",
+            "profile-id=1;level-id=93",
+            true,
+        ),
+        ("DifferentProfile", "profile-id=1", "profile-id=2", false),
+        ("DefaultDifferentProfile", "", "profile-id=2", false),
+        ("DifferentProfileSpace", "profile-space=1", "", false),
+        ("DifferentTier", "tier-flag=1", "tier-flag=0", false),
+        ("DifferentTxMode", "tx-mode=MRST", "", false),
+        (
+            "DifferentInteropConstraints",
+            "interop-constraints=B00000000000",
+            "interop-constraints=900000000000",
+            false,
+        ),
+        (
+            "InteropConstraintsCase",
+            "interop-constraints=b00000000000",
+            "interop-constraints=B00000000000",
+            true,
+        ),
+    ];
+
+    for (name, a, b, expected) in tests {
+        let aa = parse("video/H265", a);
+        let bb = parse("video/HEVC", b);
+        assert_eq!(aa.match_fmtp(&*bb), expected, "{name} failed");
+        assert_eq!(bb.match_fmtp(&*aa), expected, "{name} failed (reverse)");
+    }
+}
@@ -0,0 +1,86 @@
+#[cfg(test)]
+mod h265_test;
+
+use super::*;
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct H265Fmtp {
+    pub(crate) parameters: HashMap<String, String>,
+}
+
+impl H265Fmtp {
+    fn profile_space(&self) -> Option<u8> {
+        parameter_or(&self.parameters, "profile-space", 0)
+    }
+
+    /// profile_id returns the HEVC profile, 1 (Main) when the parameter is absent
+    fn profile_id(&self) -> Option<u8> {
+        parameter_or(&self.parameters, "profile-id", 1)
+    }
+
+    fn tier_flag(&self) -> Option<u8> {
+        parameter_or(&self.parameters, "tier-flag", 0)
+    }
+
+    fn interop_constraints(&self) -> Option<String> {
+        self.parameters
+            .get("interop-constraints")
+            .map(|c| c.to_uppercase())
+    }
+
+    /// tx_mode returns the transmission mode, SRST when the parameter is absent
+    fn tx_mode(&self) -> String {
+        self.parameters
+            .get("tx-mode")
+            .map(|m| m.to_uppercase())
+            .unwrap_or_else(|| "SRST".to_owned())
+    }
+}
+
+impl Fmtp for H265Fmtp {
+    fn mime_type(&self) -> &str {
+        "video/h265"
+    }
+
+    /// Match returns true if h and b are compatible fmtp descriptions
+    /// Based on RFC7798 Section 7.2.2:
+    ///   The parameters identifying a media format configuration for HEVC
+    ///   are profile-space, tier-flag, profile-id, interop-constraints, and
+    ///   tx-mode.  These media format configuration parameters (except
+    ///   level-id) MUST be used symmetrically.
+    /// The level may differ, each side declaring the highest level it is
+    /// able to receive.
+    fn match_fmtp(&self, f: &dyn Fmtp) -> bool {
+        if let Some(c) = f.as_any().downcast_ref::<H265Fmtp>() {
+            let (Some(hps), Some(cps)) = (self.profile_space(), c.profile_space()) else {
+                return false;
+            };
+            let (Some(hpid), Some(cpid)) = (self.profile_id(), c.profile_id()) else {
+                return false;
+            };
+            let (Some(htier), Some(ctier)) = (self.tier_flag(), c.tier_flag()) else {
+                return false;
+            };
+
+            hps == cps
+                && hpid == cpid
+                && htier == ctier
+                && self.interop_constraints() == c.interop_constraints()
+                && self.tx_mode() == c.tx_mode()
+        } else {
+            false
+        }
+    }
+
+    fn parameter(&self, key: &str) -> Option<&String> {
+        self.parameters.get(key)
+    }
+
+    fn equal(&self, other: &dyn Fmtp) -> bool {
+        other.as_any().downcast_ref::<H265Fmtp>() == Some(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
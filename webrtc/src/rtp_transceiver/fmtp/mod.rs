@@ -1,12 +1,20 @@
+pub(crate) mod av1;
 pub(crate) mod generic;
 pub(crate) mod h264;
+pub(crate) mod h265;
+pub(crate) mod opus;
+pub(crate) mod vp9;
 
 use std::any::Any;
 use std::collections::HashMap;
 use std::fmt;
 
+use crate::rtp_transceiver::fmtp::av1::Av1Fmtp;
 use crate::rtp_transceiver::fmtp::generic::GenericFmtp;
 use crate::rtp_transceiver::fmtp::h264::H264Fmtp;
+use crate::rtp_transceiver::fmtp::h265::H265Fmtp;
+use crate::rtp_transceiver::fmtp::opus::OpusFmtp;
+use crate::rtp_transceiver::fmtp::vp9::Vp9Fmtp;
 
 /// Fmtp interface for implementing custom
 /// Fmtp parsers based on mime_type
@@ -33,8 +41,9 @@ impl PartialEq for dyn Fmtp {
     }
 }
 
-/// parse parses an fmtp string based on the MimeType
-pub fn parse(mime_type: &str, line: &str) -> Box<dyn Fmtp> {
+/// parse_parameters parses the `key=value` parameters of an fmtp string,
+/// keys are lowercased
+fn parse_parameters(line: &str) -> HashMap<String, String> {
     let mut parameters = HashMap::new();
     for p in line.split(';').collect::<Vec<&str>>() {
         let pp: Vec<&str> = p.trim().splitn(2, '=').collect();
@@ -46,13 +55,31 @@ pub fn parse(mime_type: &str, line: &str) -> Box<dyn Fmtp> {
         };
         parameters.insert(key, value);
     }
+    parameters
+}
+
+/// parameter_or returns the numeric value of a parameter, the default when
+/// it is absent or None when it is malformed
+fn parameter_or(parameters: &HashMap<String, String>, key: &str, default: u8) -> Option<u8> {
+    match parameters.get(key) {
+        Some(v) => v.trim().parse().ok(),
+        None => Some(default),
+    }
+}
+
+/// parse parses an fmtp string based on the MimeType
+pub fn parse(mime_type: &str, line: &str) -> Box<dyn Fmtp> {
+    let parameters = parse_parameters(line);
 
-    if mime_type.to_uppercase() == "video/h264".to_uppercase() {
-        Box::new(H264Fmtp { parameters })
-    } else {
-        Box::new(GenericFmtp {
+    match mime_type.to_lowercase().as_str() {
+        "video/h264" => Box::new(H264Fmtp { parameters }),
+        "video/h265" | "video/hevc" => Box::new(H265Fmtp { parameters }),
+        "video/vp9" => Box::new(Vp9Fmtp { parameters }),
+        "video/av1" => Box::new(Av1Fmtp { parameters }),
+        "audio/opus" => Box::new(OpusFmtp { parameters }),
+        _ => Box::new(GenericFmtp {
             mime_type: mime_type.to_owned(),
             parameters,
-        })
+        }),
     }
 }
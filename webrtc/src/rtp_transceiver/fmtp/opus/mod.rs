@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod opus_test;
+
+use super::*;
+
+/// OpusParameters are the Opus format parameters of RFC7587 Section 6.1.
+/// They describe the preferences of the receiver of the parameters and the
+/// properties of what its sender produces, they never prevent two Opus
+/// codecs from being compatible.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct OpusParameters {
+    /// maxplaybackrate is the maximum output sampling rate the receiver is
+    /// able to render, in Hz.
+    pub max_playback_rate: u32,
+    /// sprop-maxcapturerate is the maximum input sampling rate of the sender, in Hz.
+    pub sprop_max_capture_rate: u32,
+    /// maxptime is the maximum duration of media in a packet, in milliseconds.
+    pub max_ptime: Option<u32>,
+    /// ptime is the preferred duration of media in a packet, in milliseconds.
+    pub ptime: Option<u32>,
+    /// minptime is the minimum duration of media in a packet, in milliseconds.
+    pub min_ptime: Option<u32>,
+    /// maxaveragebitrate is the maximum average bitrate the receiver wants
+    /// to receive, in bits per second.
+    pub max_average_bitrate: Option<u32>,
+    /// stereo tells whether the receiver prefers receiving stereo.
+    pub stereo: bool,
+    /// sprop-stereo tells whether the sender is likely to produce stereo.
+    pub sprop_stereo: bool,
+    /// cbr tells whether the receiver prefers constant bitrate.
+    pub cbr: bool,
+    /// useinbandfec tells whether the receiver is able to use in-band FEC.
+    pub use_inband_fec: bool,
+    /// usedtx tells whether the receiver prefers discontinuous transmission.
+    pub use_dtx: bool,
+}
+
+impl Default for OpusParameters {
+    fn default() -> Self {
+        OpusParameters {
+            max_playback_rate: 48000,
+            sprop_max_capture_rate: 48000,
+            max_ptime: None,
+            ptime: None,
+            min_ptime: None,
+            max_average_bitrate: None,
+            stereo: false,
+            sprop_stereo: false,
+            cbr: false,
+            use_inband_fec: false,
+            use_dtx: false,
+        }
+    }
+}
+
+impl OpusParameters {
+    /// parse parses the Opus parameters of an fmtp line, malformed or out of
+    /// range values are replaced by their default.
+    pub fn parse(line: &str) -> Self {
+        let parameters = parse_parameters(line);
+        let flag = |key: &str| parameters.get(key).is_some_and(|v| v.trim() == "1");
+        let value = |key: &str| {
+            parameters
+                .get(key)
+                .and_then(|v| v.trim().parse::<u32>().ok())
+        };
+        let rate = |key: &str| {
+            value(key)
+                .filter(|r| (8000..=48000).contains(r))
+                .unwrap_or(48000)
+        };
+
+        OpusParameters {
+            max_playback_rate: rate("maxplaybackrate"),
+            sprop_max_capture_rate: rate("sprop-maxcapturerate"),
+            max_ptime: value("maxptime"),
+            ptime: value("ptime"),
+            min_ptime: value("minptime"),
+            max_average_bitrate: value("maxaveragebitrate").filter(|b| (6000..=510000).contains(b)),
+            stereo: flag("stereo"),
+            sprop_stereo: flag("sprop-stereo"),
+            cbr: flag("cbr"),
+            use_inband_fec: flag("useinbandfec"),
+            use_dtx: flag("usedtx"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct OpusFmtp {
+    pub(crate) parameters: HashMap<String, String>,
+}
+
+impl Fmtp for OpusFmtp {
+    fn mime_type(&self) -> &str {
+        "audio/opus"
+    }
+
+    /// Match returns true if o and b are compatible fmtp descriptions
+    /// Based on RFC7587 Section 7:
+    ///   All the Opus parameters are declarative, they express the
+    ///   preferences of the receiver and the properties of the sender and
+    ///   the two sides need not agree on them.
+    fn match_fmtp(&self, f: &dyn Fmtp) -> bool {
+        f.as_any().downcast_ref::<OpusFmtp>().is_some()
+    }
+
+    fn parameter(&self, key: &str) -> Option<&String> {
+        self.parameters.get(key)
+    }
+
+    fn equal(&self, other: &dyn Fmtp) -> bool {
+        other.as_any().downcast_ref::<OpusFmtp>() == Some(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
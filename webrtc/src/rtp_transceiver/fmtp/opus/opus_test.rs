@@ -0,0 +1,39 @@
+use super::*;
+
+#[test]
+fn test_opus_fmtp_compare() {
+    let a = parse("audio/opus", "minptime=10;useinbandfec=1");
+    let b = parse("audio/OPUS", "minptime=20;useinbandfec=0;stereo=1");
+    assert!(a.match_fmtp(&*b));
+    assert!(b.match_fmtp(&*a));
+
+    let generic = parse("audio/PCMU", "minptime=10;useinbandfec=1");
+    assert!(!a.match_fmtp(&*generic));
+}
+
+#[test]
+fn test_opus_parameters_parse() {
+    assert_eq!(OpusParameters::parse(""), OpusParameters::default());
+
+    let parameters = OpusParameters::parse(
+        "minptime=10; useinbandfec=1;stereo=1;sprop-stereo=0;usedtx=1;maxaveragebitrate=64000;maxplaybackrate=16000;ptime=20",
+    );
+    assert_eq!(
+        parameters,
+        OpusParameters {
+            max_playback_rate: 16000,
+            min_ptime: Some(10),
+            ptime: Some(20),
+            max_average_bitrate: Some(64000),
+            stereo: true,
+            use_inband_fec: true,
+            use_dtx: true,
+            ..Default::default()
+        }
+    );
+
+    // Out of range and malformed values fall back to the defaults.
+    let parameters =
+        OpusParameters::parse("maxaveragebitrate=1000000;maxplaybackrate=4000;cbr=yes;ptime=x");
+    assert_eq!(parameters, OpusParameters::default());
+}
@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod vp9_test;
+
+use super::*;
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Vp9Fmtp {
+    pub(crate) parameters: HashMap<String, String>,
+}
+
+impl Vp9Fmtp {
+    /// profile_id returns the VP9 profile, 0 when the parameter is absent
+    fn profile_id(&self) -> Option<u8> {
+        parameter_or(&self.parameters, "profile-id", 0)
+    }
+}
+
+impl Fmtp for Vp9Fmtp {
+    fn mime_type(&self) -> &str {
+        "video/vp9"
+    }
+
+    /// Match returns true if v and b are compatible fmtp descriptions
+    /// Based on RFC9628 Section 6.1:
+    ///   The profile-id parameter identifies the VP9 profile of the stream
+    ///   and MUST be used symmetrically, a missing profile-id means profile 0.
+    ///   max-fr and max-fs are receiver capabilities that do not affect
+    ///   compatibility.
+    fn match_fmtp(&self, f: &dyn Fmtp) -> bool {
+        if let Some(c) = f.as_any().downcast_ref::<Vp9Fmtp>() {
+            match (self.profile_id(), c.profile_id()) {
+                (Some(a), Some(b)) => a == b,
+                _ => false,
+            }
+        } else {
+            false
+        }
+    }
+
+    fn parameter(&self, key: &str) -> Option<&String> {
+        self.parameters.get(key)
+    }
+
+    fn equal(&self, other: &dyn Fmtp) -> bool {
+        other.as_any().downcast_ref::<Vp9Fmtp>() == Some(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
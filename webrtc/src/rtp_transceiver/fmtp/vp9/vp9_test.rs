@@ -0,0 +1,28 @@
+use super::*;
+
+#[test]
+fn test_vp9_fmtp_compare() {
+    let tests = vec![
+        ("SameProfile", "profile-id=2", "profile-id=2", true),
+        ("DefaultProfile", "", "profile-id=0", true),
+        (
+            "ReceiverCapabilities",
+            "profile-id=0;max-fr=30",
+            "max-fs=8160",
+            true,
+        ),
+        ("DifferentProfile", "profile-id=0", "profile-id=1", false),
+        ("DefaultDifferentProfile", "", "profile-id=2", false),
+        ("MalformedProfile", "profile-id=x", "profile-id=0", false),
+    ];
+
+    for (name, a, b, expected) in tests {
+        let aa = parse("video/VP9", a);
+        let bb = parse("video/vp9", b);
+        assert_eq!(aa.match_fmtp(&*bb), expected, "{name} failed");
+        assert_eq!(bb.match_fmtp(&*aa), expected, "{name} failed (reverse)");
+    }
+
+    let generic = parse("video/VP8", "profile-id=0");
+    assert!(!parse("video/VP9", "profile-id=0").match_fmtp(&*generic));
+}
@@ -14,6 +14,7 @@ use portable_atomic::{AtomicBool, AtomicU8};
 use serde::{Deserialize, Serialize};
 use smol_str::SmolStr;
 use tokio::sync::{Mutex, OnceCell};
+use util::sync::Mutex as SyncMutex;
 
 use crate::api::media_engine::MediaEngine;
 use crate::error::{Error, Result};
@@ -28,6 +29,7 @@ pub mod rtp_codec;
 pub mod rtp_receiver;
 pub mod rtp_sender;
 pub mod rtp_transceiver_direction;
+pub mod rtp_transform;
 pub(crate) mod srtp_writer_future;
 
 /// SSRC represents a synchronization source
@@ -59,6 +61,10 @@ pub const TYPE_RTCP_FB_CCM: &str = "ccm";
 /// TYPE_RTCP_FB_NACK ..
 pub const TYPE_RTCP_FB_NACK: &str = "nack";
 
+/// RTCP_FB_PARAMETER_CCFB is the parameter of the "ack" feedback type for
+/// RFC 8888 congestion control feedback.
+pub const RTCP_FB_PARAMETER_CCFB: &str = "ccfb";
+
 /// rtcpfeedback signals the connection to use additional RTCP packet types.
 /// <https://draft.ortc.org/#dom-rtcrtcpfeedback>
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
@@ -182,6 +188,7 @@ pub struct RTCRtpTransceiver {
     current_direction: AtomicU8, //RTPTransceiverDirection
 
     codecs: Arc<Mutex<Vec<RTCRtpCodecParameters>>>, // User provided codecs via set_codec_preferences
+    header_extensions: SyncMutex<Option<Vec<String>>>, // User selected extensions via set_header_extensions_to_negotiate
 
     pub(crate) stopped: AtomicBool,
     pub(crate) kind: RTPCodecType,
@@ -213,6 +220,7 @@ impl RTCRtpTransceiver {
             current_direction: AtomicU8::new(RTCRtpTransceiverDirection::Unspecified as u8),
 
             codecs,
+            header_extensions: SyncMutex::new(None),
             stopped: AtomicBool::new(false),
             kind,
             media_engine,
@@ -249,6 +257,57 @@ impl RTCRtpTransceiver {
         RTPReceiverInternal::get_codecs(&mut codecs, self.kind, &self.media_engine)
     }
 
+    /// header_extensions_to_negotiate returns the header extensions this transceiver offers,
+    /// all the ones registered in the MediaEngine for its kind unless a subset has been
+    /// selected with set_header_extensions_to_negotiate
+    pub fn header_extensions_to_negotiate(&self) -> Vec<RTCRtpHeaderExtensionCapability> {
+        let extensions = self
+            .media_engine
+            .get_header_extensions_by_kind(self.kind, RTCRtpTransceiverDirection::Sendrecv);
+
+        match &*self.header_extensions.lock() {
+            Some(selected) => extensions
+                .into_iter()
+                .filter(|e| selected.contains(&e.uri))
+                .collect(),
+            None => extensions,
+        }
+    }
+
+    /// set_header_extensions_to_negotiate selects the header extensions this transceiver
+    /// offers from the ones registered in the MediaEngine for its kind, the others are left
+    /// out of the next offers and answers. An empty list disables all of them.
+    pub fn set_header_extensions_to_negotiate(
+        &self,
+        extensions: Vec<RTCRtpHeaderExtensionCapability>,
+    ) -> Result<()> {
+        let registered = self
+            .media_engine
+            .get_header_extensions_by_kind(self.kind, RTCRtpTransceiverDirection::Sendrecv);
+        for extension in &extensions {
+            if !registered.iter().any(|e| e.uri == extension.uri) {
+                return Err(Error::ErrRTPTransceiverHeaderExtensionUnsupported);
+            }
+        }
+
+        *self.header_extensions.lock() = Some(extensions.into_iter().map(|e| e.uri).collect());
+        Ok(())
+    }
+
+    /// filter_header_extensions keeps the header extensions selected for this transceiver
+    pub(crate) fn filter_header_extensions(
+        &self,
+        extensions: Vec<RTCRtpHeaderExtensionParameters>,
+    ) -> Vec<RTCRtpHeaderExtensionParameters> {
+        match &*self.header_extensions.lock() {
+            Some(selected) => extensions
+                .into_iter()
+                .filter(|e| selected.contains(&e.uri))
+                .collect(),
+            None => extensions,
+        }
+    }
+
     /// sender returns the RTPTransceiver's RTPSender if it has one
     pub async fn sender(&self) -> Arc<RTCRtpSender> {
         let sender = self.sender.lock().await;
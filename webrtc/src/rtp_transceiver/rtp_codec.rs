@@ -4,6 +4,7 @@ use super::*;
 use crate::api::media_engine::*;
 use crate::error::{Error, Result};
 use crate::rtp_transceiver::fmtp;
+pub use crate::rtp_transceiver::fmtp::opus::OpusParameters;
 
 /// RTPCodecType determines the type of a codec
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
@@ -93,6 +94,16 @@ impl RTCRtpCodecCapability {
             Err(Error::ErrNoPayloaderForCodec)
         }
     }
+
+    /// opus_parameters returns the parsed Opus parameters of the sdp_fmtp_line,
+    /// None if this is not an Opus codec
+    pub fn opus_parameters(&self) -> Option<OpusParameters> {
+        if self.mime_type.to_lowercase() == MIME_TYPE_OPUS.to_lowercase() {
+            Some(OpusParameters::parse(&self.sdp_fmtp_line))
+        } else {
+            None
+        }
+    }
 }
 
 /// RTPHeaderExtensionCapability is used to define a RFC5285 RTP header extension supported by the codec.
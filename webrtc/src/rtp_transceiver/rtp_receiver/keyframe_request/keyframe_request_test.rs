@@ -0,0 +1,276 @@
+use super::*;
+use crate::api::media_engine::{MIME_TYPE_OPUS, MIME_TYPE_VP8};
+use crate::rtp_transceiver::rtp_codec::RTCRtpCodecCapability;
+use crate::rtp_transceiver::RTCPFeedback;
+
+// VP8 payload descriptors with a 7 bit picture id, followed by the start of the frame
+const VP8_KEYFRAME: &[u8] = &[0x90, 0x80, 0x01, 0x10, 0x02];
+const VP8_DELTA_FRAME: &[u8] = &[0x90, 0x80, 0x02, 0x11, 0x02];
+const VP8_CONTINUATION: &[u8] = &[0x80, 0x80, 0x01, 0x00, 0x02];
+
+fn packet(sequence_number: u16, payload: &'static [u8]) -> rtp::packet::Packet {
+    rtp::packet::Packet {
+        header: rtp::header::Header {
+            version: 2,
+            sequence_number,
+            ssrc: 1234,
+            ..Default::default()
+        },
+        payload: Bytes::from_static(payload),
+    }
+}
+
+fn codec(feedback: &[(&str, &str)]) -> RTCRtpCodecParameters {
+    RTCRtpCodecParameters {
+        capability: RTCRtpCodecCapability {
+            mime_type: MIME_TYPE_VP8.to_owned(),
+            rtcp_feedback: feedback
+                .iter()
+                .map(|(typ, parameter)| RTCPFeedback {
+                    typ: typ.to_string(),
+                    parameter: parameter.to_string(),
+                })
+                .collect(),
+            ..Default::default()
+        },
+        payload_type: 96,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_packet_starts_keyframe() {
+    let tests: Vec<(&str, &[u8], Option<bool>)> = vec![
+        (MIME_TYPE_VP8, VP8_KEYFRAME, Some(true)),
+        (MIME_TYPE_VP8, VP8_DELTA_FRAME, Some(false)),
+        (MIME_TYPE_VP8, VP8_CONTINUATION, Some(false)),
+        ("video/VP9", &[0x08, 0x80], Some(true)),
+        ("video/VP9", &[0x48, 0x80], Some(false)),
+        ("video/VP9", &[0x08, 0x84], Some(false)),
+        // Single NALU, STAP-A with SPS and IDR, FU-A start and middle fragments of an IDR
+        ("video/H264", &[0x65, 0x88, 0x84], Some(true)),
+        ("video/H264", &[0x41, 0x9A, 0x02], Some(false)),
+        (
+            "video/H264",
+            &[0x78, 0x00, 0x02, 0x67, 0x42, 0x00, 0x02, 0x65, 0x88],
+            Some(true),
+        ),
+        ("video/H264", &[0x7C, 0x85, 0x88], Some(true)),
+        ("video/H264", &[0x7C, 0x05, 0x88], Some(false)),
+        // Single CRA NALU, AP with VPS and IDR, FU start fragment of an IDR
+        ("video/H265", &[0x2A, 0x01, 0xAF], Some(true)),
+        ("video/H265", &[0x02, 0x01, 0xD0], Some(false)),
+        (
+            "video/H265",
+            &[0x60, 0x01, 0x00, 0x02, 0x40, 0x01, 0x00, 0x02, 0x26, 0x01],
+            Some(true),
+        ),
+        ("video/H265", &[0x62, 0x01, 0x93, 0xAF], Some(true)),
+        ("video/H265", &[0x62, 0x01, 0x13, 0xAF], Some(false)),
+        ("video/AV1", &[0x18, 0x0A, 0x00], None),
+        (MIME_TYPE_OPUS, &[0xFC], None),
+    ];
+
+    for (mime_type, payload, expected) in tests {
+        assert_eq!(
+            packet_starts_keyframe(mime_type, &Bytes::from_static(payload)),
+            expected,
+            "{mime_type} {payload:?}"
+        );
+    }
+}
+
+#[test]
+fn test_keyframe_request_kind() {
+    assert_eq!(
+        keyframe_request_kind(&codec(&[("nack", ""), ("ccm", "fir"), ("nack", "pli")])),
+        Some(RTCKeyframeRequestKind::Pli)
+    );
+    assert_eq!(
+        keyframe_request_kind(&codec(&[("nack", ""), ("ccm", "fir")])),
+        Some(RTCKeyframeRequestKind::Fir)
+    );
+    assert_eq!(
+        keyframe_request_kind(&codec(&[("nack", ""), ("goog-remb", "")])),
+        None
+    );
+}
+
+#[test]
+fn test_keyframe_requester_start() {
+    let policy = RTCKeyframeRequestPolicy::default();
+    let now = Instant::now();
+
+    // Starting with a keyframe
+    let mut requester = KeyframeRequester::default();
+    assert!(!requester.on_packet(&packet(10, VP8_KEYFRAME), MIME_TYPE_VP8, &policy, now));
+    assert!(!requester.on_packet(&packet(11, VP8_CONTINUATION), MIME_TYPE_VP8, &policy, now));
+
+    // Joining in the middle of the stream
+    let mut requester = KeyframeRequester::default();
+    assert!(requester.on_packet(&packet(10, VP8_DELTA_FRAME), MIME_TYPE_VP8, &policy, now));
+
+    let policy = RTCKeyframeRequestPolicy {
+        request_on_start: false,
+        ..Default::default()
+    };
+    let mut requester = KeyframeRequester::default();
+    assert!(!requester.on_packet(&packet(10, VP8_DELTA_FRAME), MIME_TYPE_VP8, &policy, now));
+}
+
+#[test]
+fn test_keyframe_requester_loss() {
+    let policy = RTCKeyframeRequestPolicy::default();
+    let start = Instant::now();
+    let mut requester = KeyframeRequester::default();
+    let mut on_packet = |sequence_number, payload, elapsed_ms| {
+        requester.on_packet(
+            &packet(sequence_number, payload),
+            MIME_TYPE_VP8,
+            &policy,
+            start + Duration::from_millis(elapsed_ms),
+        )
+    };
+
+    assert!(!on_packet(65534, VP8_KEYFRAME, 0));
+
+    // 65535 arrives late, within the reorder window, across the wrap
+    assert!(!on_packet(0, VP8_CONTINUATION, 0));
+    assert!(!on_packet(65535, VP8_CONTINUATION, 10));
+    assert!(!on_packet(1, VP8_DELTA_FRAME, 100));
+
+    // 2 never arrives
+    assert!(!on_packet(3, VP8_CONTINUATION, 110));
+    assert!(!on_packet(4, VP8_CONTINUATION, 150));
+    assert!(on_packet(5, VP8_DELTA_FRAME, 170));
+}
+
+#[test]
+fn test_keyframe_requester_sequence_jump() {
+    let policy = RTCKeyframeRequestPolicy {
+        min_interval: Duration::ZERO,
+        ..Default::default()
+    };
+    let start = Instant::now();
+    let mut requester = KeyframeRequester::default();
+    let mut on_packet = |sequence_number, payload, elapsed_ms| {
+        requester.on_packet(
+            &packet(sequence_number, payload),
+            MIME_TYPE_VP8,
+            &policy,
+            start + Duration::from_millis(elapsed_ms),
+        )
+    };
+
+    assert!(!on_packet(100, VP8_KEYFRAME, 0));
+    assert!(!on_packet(101, VP8_CONTINUATION, 0));
+
+    // A single stray old packet is ignored
+    assert!(!on_packet(40000, VP8_CONTINUATION, 10));
+    assert!(!on_packet(102, VP8_CONTINUATION, 20));
+
+    // A jump by more than half the sequence number space makes the packets look old, the
+    // sequence is tracked from there after a few of them and a keyframe is requested
+    let mut requested = vec![];
+    for i in 0..MAX_OUT_OF_WINDOW_PACKETS {
+        if on_packet(40000 + i, VP8_CONTINUATION, 30) {
+            requested.push(i);
+        }
+    }
+    assert_eq!(requested, vec![MAX_OUT_OF_WINDOW_PACKETS - 1]);
+
+    // Tracked again: the following packets are in order, a gap is detected as usual
+    let next = 40000 + MAX_OUT_OF_WINDOW_PACKETS;
+    assert!(!on_packet(next, VP8_KEYFRAME, 40));
+    assert!(!on_packet(next + 1, VP8_CONTINUATION, 40));
+    assert!(!on_packet(next + 3, VP8_CONTINUATION, 50));
+    assert!(on_packet(next + 4, VP8_CONTINUATION, 110));
+}
+
+#[test]
+fn test_keyframe_requester_throttle() {
+    let policy = RTCKeyframeRequestPolicy::default();
+    let start = Instant::now();
+    let mut requester = KeyframeRequester::default();
+
+    let mut sequence_number = 0u16;
+    let mut requests = vec![];
+    for elapsed_ms in (0..1200).step_by(20) {
+        let now = start + Duration::from_millis(elapsed_ms);
+        if requester.on_packet(
+            &packet(sequence_number, VP8_DELTA_FRAME),
+            MIME_TYPE_VP8,
+            &policy,
+            now,
+        ) {
+            requester.request(RTCKeyframeRequestKind::Pli, 1234, now);
+            requests.push(elapsed_ms);
+        }
+        sequence_number += 1;
+    }
+    // Repeated at min_interval while no keyframe arrives
+    assert_eq!(requests, vec![0, 500, 1000]);
+
+    // The keyframe stops the requests
+    let now = start + Duration::from_millis(1600);
+    assert!(!requester.on_packet(
+        &packet(sequence_number, VP8_KEYFRAME),
+        MIME_TYPE_VP8,
+        &policy,
+        now
+    ));
+    assert!(!requester.on_packet(
+        &packet(sequence_number + 1, VP8_DELTA_FRAME),
+        MIME_TYPE_VP8,
+        &policy,
+        now + Duration::from_millis(600)
+    ));
+}
+
+#[test]
+fn test_keyframe_requester_undetected_codec() {
+    let policy = RTCKeyframeRequestPolicy::default();
+    let start = Instant::now();
+    let mut requester = KeyframeRequester::default();
+    let mime_type = "video/AV1";
+
+    assert!(!requester.on_packet(&packet(1, &[0x10, 0x00]), mime_type, &policy, start));
+    assert!(!requester.on_packet(&packet(3, &[0x10, 0x00]), mime_type, &policy, start));
+    // Requested once for the loss of 2
+    let now = start + Duration::from_millis(60);
+    assert!(requester.on_packet(&packet(4, &[0x10, 0x00]), mime_type, &policy, now));
+    requester.request(RTCKeyframeRequestKind::Pli, 1234, now);
+    let now = start + Duration::from_millis(600);
+    assert!(!requester.on_packet(&packet(5, &[0x10, 0x00]), mime_type, &policy, now));
+}
+
+#[test]
+fn test_keyframe_requester_request() -> Result<(), util::Error> {
+    let mut requester = KeyframeRequester::default();
+    let now = Instant::now();
+
+    let pli = requester.request(RTCKeyframeRequestKind::Pli, 1234, now);
+    let pli = pli
+        .as_any()
+        .downcast_ref::<PictureLossIndication>()
+        .expect("PLI");
+    assert_eq!(pli.media_ssrc, 1234);
+
+    for expected in 0..2u8 {
+        let fir = requester.request(RTCKeyframeRequestKind::Fir, 1234, now);
+        fir.marshal()?;
+        let fir = fir
+            .as_any()
+            .downcast_ref::<FullIntraRequest>()
+            .expect("FIR");
+        assert_eq!(
+            fir.fir,
+            vec![FirEntry {
+                ssrc: 1234,
+                sequence_number: expected,
+            }]
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,277 @@
+#[cfg(test)]
+mod keyframe_request_test;
+
+use std::collections::VecDeque;
+
+use bytes::Bytes;
+use rtcp::payload_feedbacks::full_intra_request::{FirEntry, FullIntraRequest};
+use rtcp::payload_feedbacks::picture_loss_indication::PictureLossIndication;
+use rtp::codecs::h264::H264Packet;
+use rtp::codecs::vp8::Vp8Packet;
+use rtp::codecs::vp9::Vp9Packet;
+use rtp::packetizer::Depacketizer;
+use tokio::time::{Duration, Instant};
+
+use crate::rtp_transceiver::rtp_codec::RTCRtpCodecParameters;
+use crate::rtp_transceiver::rtp_sender::encoder_control::RTCKeyframeRequestKind;
+use crate::rtp_transceiver::rtp_transform::is_keyframe;
+use crate::rtp_transceiver::{SSRC, TYPE_RTCP_FB_CCM, TYPE_RTCP_FB_NACK};
+
+/// Missing packets tracked at once; a larger gap is treated as lost right away
+const MAX_MISSING_PACKETS: usize = 512;
+
+/// Consecutive packets far behind the last sequence number after which the sequence is assumed
+/// to have jumped, e.g. by more than half the sequence number space, and is tracked again from
+/// there
+const MAX_OUT_OF_WINDOW_PACKETS: u16 = 8;
+
+/// RTCKeyframeRequestPolicy controls the keyframe requests sent automatically by a RTCRtpReceiver
+/// for its video tracks, see RTCRtpReceiver::set_keyframe_request_policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RTCKeyframeRequestPolicy {
+    /// Minimum time between two keyframe requests for the same track. Requests are repeated
+    /// at this interval until a keyframe is received.
+    pub min_interval: Duration,
+    /// How long a missing packet is waited for before it is considered lost. Packets arriving
+    /// out of order within this window don't trigger a request.
+    pub reorder_window: Duration,
+    /// Request a keyframe if the track doesn't start with one, e.g. when joining a stream that
+    /// is already running.
+    pub request_on_start: bool,
+}
+
+impl Default for RTCKeyframeRequestPolicy {
+    fn default() -> Self {
+        RTCKeyframeRequestPolicy {
+            min_interval: Duration::from_millis(500),
+            reorder_window: Duration::from_millis(50),
+            request_on_start: true,
+        }
+    }
+}
+
+/// keyframe_request_kind returns the message to request a keyframe with, as negotiated in the
+/// rtcp-fb of the codec: PLI if possible, FIR otherwise.
+pub(crate) fn keyframe_request_kind(
+    codec: &RTCRtpCodecParameters,
+) -> Option<RTCKeyframeRequestKind> {
+    let feedback = &codec.capability.rtcp_feedback;
+    if feedback
+        .iter()
+        .any(|fb| fb.typ == TYPE_RTCP_FB_NACK && fb.parameter == "pli")
+    {
+        Some(RTCKeyframeRequestKind::Pli)
+    } else if feedback
+        .iter()
+        .any(|fb| fb.typ == TYPE_RTCP_FB_CCM && fb.parameter == "fir")
+    {
+        Some(RTCKeyframeRequestKind::Fir)
+    } else {
+        None
+    }
+}
+
+/// packet_starts_keyframe reports whether a RTP payload is the first packet of a keyframe, or
+/// None if keyframes of the codec can't be detected.
+pub(crate) fn packet_starts_keyframe(mime_type: &str, payload: &Bytes) -> Option<bool> {
+    let mime_type = mime_type.to_lowercase();
+    let starts_keyframe = match mime_type.as_str() {
+        "video/vp8" => {
+            let mut vp8 = Vp8Packet::default();
+            match vp8.depacketize(payload) {
+                Ok(data) => vp8.s == 1 && vp8.pid == 0 && is_keyframe(&mime_type, &data),
+                Err(_) => false,
+            }
+        }
+        "video/vp9" => {
+            let mut vp9 = Vp9Packet::default();
+            match vp9.depacketize(payload) {
+                Ok(data) => vp9.b && !vp9.p && is_keyframe(&mime_type, &data),
+                Err(_) => false,
+            }
+        }
+        "video/h264" => {
+            const NALU_TYPE_FU_A: u8 = 28;
+            match payload.first() {
+                Some(&b) if b & 0x1F == NALU_TYPE_FU_A => match payload.get(1) {
+                    // Only the start fragment carries the type of the fragmented NALU
+                    Some(&fu) if fu & 0x80 != 0 => {
+                        is_keyframe(&mime_type, &[0, 0, 1, (b & 0xE0) | (fu & 0x1F)])
+                    }
+                    _ => false,
+                },
+                Some(_) => match H264Packet::default().depacketize(payload) {
+                    Ok(data) => is_keyframe(&mime_type, &data),
+                    Err(_) => false,
+                },
+                None => false,
+            }
+        }
+        "video/h265" | "video/hevc" => h265_starts_keyframe(payload),
+        _ => return None,
+    };
+
+    Some(starts_keyframe)
+}
+
+fn h265_starts_keyframe(payload: &[u8]) -> bool {
+    const NALU_TYPE_AP: u8 = 48;
+    const NALU_TYPE_FU: u8 = 49;
+    let is_irap = |nalu_type: u8| (16..=23).contains(&nalu_type);
+
+    if payload.len() < 3 {
+        return false;
+    }
+    match (payload[0] >> 1) & 0x3F {
+        NALU_TYPE_AP => {
+            // Aggregated NALUs, each preceded by its 16 bit size
+            let mut aggregated = &payload[2..];
+            while aggregated.len() > 2 {
+                let size = u16::from_be_bytes([aggregated[0], aggregated[1]]) as usize;
+                if size == 0 || aggregated.len() < 2 + size {
+                    break;
+                }
+                if is_irap((aggregated[2] >> 1) & 0x3F) {
+                    return true;
+                }
+                aggregated = &aggregated[2 + size..];
+            }
+            false
+        }
+        NALU_TYPE_FU => payload[2] & 0x80 != 0 && is_irap(payload[2] & 0x3F),
+        nalu_type => is_irap(nalu_type),
+    }
+}
+
+/// KeyframeRequester tracks the packets of a video track to detect when the decoder can't make
+/// progress any more, and decides when to request a keyframe.
+#[derive(Debug, Default)]
+pub(crate) struct KeyframeRequester {
+    last_sequence_number: Option<u16>,
+    /// Sequence numbers that were skipped, with the time the gap was detected
+    missing: VecDeque<(u16, Instant)>,
+    /// Consecutive packets more than MAX_MISSING_PACKETS behind the last sequence number
+    out_of_window: u16,
+    waiting_for_keyframe: bool,
+    last_request: Option<Instant>,
+    /// Sequence number of the next FIR
+    fir_sequence_number: u8,
+}
+
+impl KeyframeRequester {
+    /// on_packet updates the state with a received packet, and returns true if a keyframe
+    /// should be requested now. The caller then sends the packet built by `request`.
+    pub(crate) fn on_packet(
+        &mut self,
+        pkt: &rtp::packet::Packet,
+        mime_type: &str,
+        policy: &RTCKeyframeRequestPolicy,
+        now: Instant,
+    ) -> bool {
+        let sequence_number = pkt.header.sequence_number;
+        let starts_keyframe = if pkt.payload.is_empty() {
+            // Padding
+            Some(false)
+        } else {
+            packet_starts_keyframe(mime_type, &pkt.payload)
+        };
+
+        let mut lost = false;
+        match self.last_sequence_number {
+            None => {
+                self.last_sequence_number = Some(sequence_number);
+                if policy.request_on_start && starts_keyframe == Some(false) {
+                    self.waiting_for_keyframe = true;
+                }
+            }
+            Some(last) => {
+                let diff = sequence_number.wrapping_sub(last) as i16;
+                if diff > 0 {
+                    self.out_of_window = 0;
+                    if diff as usize - 1 > MAX_MISSING_PACKETS {
+                        lost = true;
+                        self.missing.clear();
+                    } else {
+                        for i in 1..diff as u16 {
+                            self.missing.push_back((last.wrapping_add(i), now));
+                        }
+                    }
+                    self.last_sequence_number = Some(sequence_number);
+                } else if diff.unsigned_abs() as usize <= MAX_MISSING_PACKETS {
+                    // Reordered or duplicated packet
+                    self.out_of_window = 0;
+                    self.missing.retain(|(s, _)| *s != sequence_number);
+                } else {
+                    // A stray old packet, or the sequence jumped and every packet now looks
+                    // older than the last one: resynchronize if it keeps happening
+                    self.out_of_window += 1;
+                    if self.out_of_window >= MAX_OUT_OF_WINDOW_PACKETS {
+                        self.out_of_window = 0;
+                        self.missing.clear();
+                        self.last_sequence_number = Some(sequence_number);
+                        lost = true;
+                    }
+                }
+            }
+        }
+
+        while let Some((_, detected)) = self.missing.front() {
+            if now.duration_since(*detected) < policy.reorder_window {
+                break;
+            }
+            self.missing.pop_front();
+            lost = true;
+        }
+
+        if starts_keyframe == Some(true) && self.last_sequence_number == Some(sequence_number) {
+            // Nothing before the keyframe is needed any more
+            self.waiting_for_keyframe = false;
+            self.missing.clear();
+            lost = false;
+        }
+
+        let should_request = match starts_keyframe {
+            // Keep requesting until the keyframe arrives
+            Some(_) => {
+                self.waiting_for_keyframe |= lost;
+                self.waiting_for_keyframe
+            }
+            // Without keyframe detection, request once per loss
+            None => lost,
+        };
+
+        should_request
+            && self
+                .last_request
+                .map(|last| now.duration_since(last) >= policy.min_interval)
+                .unwrap_or(true)
+    }
+
+    /// request builds the RTCP packet requesting a keyframe for the track
+    pub(crate) fn request(
+        &mut self,
+        kind: RTCKeyframeRequestKind,
+        media_ssrc: SSRC,
+        now: Instant,
+    ) -> Box<dyn rtcp::packet::Packet + Send + Sync> {
+        self.last_request = Some(now);
+        match kind {
+            RTCKeyframeRequestKind::Pli => Box::new(PictureLossIndication {
+                sender_ssrc: 0,
+                media_ssrc,
+            }),
+            RTCKeyframeRequestKind::Fir => {
+                let sequence_number = self.fir_sequence_number;
+                self.fir_sequence_number = self.fir_sequence_number.wrapping_add(1);
+                Box::new(FullIntraRequest {
+                    sender_ssrc: 0,
+                    media_ssrc: 0,
+                    fir: vec![FirEntry {
+                        ssrc: media_ssrc,
+                        sequence_number,
+                    }],
+                })
+            }
+        }
+    }
+}
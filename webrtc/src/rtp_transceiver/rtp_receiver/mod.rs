@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod rtp_receiver_test;
 
+pub mod keyframe_request;
+
 use std::fmt;
 use std::sync::Arc;
 
@@ -9,7 +11,11 @@ use interceptor::stream_info::{AssociatedStreamInfo, RTPHeaderExtension};
 use interceptor::{Attributes, Interceptor};
 use log::trace;
 use smol_str::SmolStr;
-use tokio::sync::{watch, Mutex, RwLock};
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{mpsc, watch, Mutex, RwLock};
+use util::sync::Mutex as SyncMutex;
+
+use self::keyframe_request::RTCKeyframeRequestPolicy;
 
 use crate::api::media_engine::MediaEngine;
 use crate::dtls_transport::RTCDtlsTransport;
@@ -20,8 +26,10 @@ use crate::rtp_transceiver::rtp_codec::{
     RTPCodecType,
 };
 use crate::rtp_transceiver::rtp_transceiver_direction::RTCRtpTransceiverDirection;
+use crate::rtp_transceiver::rtp_transform::{RTCRtpTransform, TransformSlot};
 use crate::rtp_transceiver::{
-    codec_rtx_search, create_stream_info, RTCRtpDecodingParameters, RTCRtpReceiveParameters, SSRC,
+    codec_rtx_search, create_stream_info, RTCRtpCapabilities, RTCRtpDecodingParameters,
+    RTCRtpReceiveParameters, SSRC,
 };
 use crate::track::track_remote::TrackRemote;
 use crate::track::{TrackStream, TrackStreams};
@@ -154,12 +162,67 @@ pub struct RTPReceiverInternal {
     tracks: RwLock<Vec<TrackStreams>>,
 
     transceiver_codecs: ArcSwapOption<Mutex<Vec<RTCRtpCodecParameters>>>,
+    pub(crate) transform: TransformSlot,
+    keyframe_request_policy: SyncMutex<Option<RTCKeyframeRequestPolicy>>,
 
     transport: Arc<RTCDtlsTransport>,
     media_engine: Arc<MediaEngine>,
     interceptor: Arc<dyn Interceptor + Send + Sync>,
 }
 
+/// RTCPRead is the result of reading RTCP from the interceptors of a track
+pub(crate) type RTCPRead = Result<(Vec<Box<dyn rtcp::packet::Packet + Send + Sync>>, Attributes)>;
+
+/// RTCP_READ_QUEUE_SIZE is the number of RTCP reads of a track kept until the application reads
+/// them, further reads are dropped.
+const RTCP_READ_QUEUE_SIZE: usize = 64;
+
+/// process_sender_reports hands the RTCP sender reports describing a track to it, so that its
+/// RTP timestamps can be mapped to the wall clock of the sender
+fn process_sender_reports(
+    track: &TrackRemote,
+    pkts: &[Box<dyn rtcp::packet::Packet + Send + Sync>],
+) {
+    for sr in pkts.iter().filter_map(|p| {
+        p.as_any()
+            .downcast_ref::<rtcp::sender_report::SenderReport>()
+    }) {
+        if sr.ssrc == track.ssrc() {
+            track.on_sender_report(sr);
+        }
+    }
+}
+
+/// start_rtcp_reader starts a routine that reads the RTCP of a track stream, so that sender
+/// reports are processed even if the application never reads RTCP. The reads are queued for
+/// RTPReceiver::read and read_simulcast.
+fn start_rtcp_reader(track: Arc<TrackRemote>, stream: &mut TrackStream, receive_mtu: usize) {
+    let Some(rtcp_interceptor) = stream.rtcp_interceptor.clone() else {
+        return;
+    };
+    let (reads_tx, reads_rx) = mpsc::channel(RTCP_READ_QUEUE_SIZE);
+    stream.rtcp_reads = Some(Arc::new(Mutex::new(reads_rx)));
+
+    tokio::spawn(async move {
+        let a = Attributes::new();
+        let mut b = vec![0u8; receive_mtu];
+        loop {
+            match rtcp_interceptor.read(&mut b, &a).await {
+                Ok((pkts, attributes)) => {
+                    process_sender_reports(&track, &pkts);
+                    if let Err(TrySendError::Full(_)) = reads_tx.try_send(Ok((pkts, attributes))) {
+                        trace!("RTCP of ssrc {} not read, dropping it", track.ssrc());
+                    }
+                }
+                Err(err) => {
+                    let _ = reads_tx.try_send(Err(err.into()));
+                    break;
+                }
+            }
+        }
+    });
+}
+
 impl RTPReceiverInternal {
     /// read reads incoming RTCP for this RTPReceiver
     async fn read(
@@ -171,26 +234,16 @@ impl RTPReceiverInternal {
         // isn't flowing.
         State::wait_for(&mut state_watch_rx, &[State::Started, State::Paused]).await?;
 
-        let tracks = self.tracks.read().await;
-        if let Some(t) = tracks.first() {
-            if let Some(rtcp_interceptor) = &t.stream.rtcp_interceptor {
-                let a = Attributes::new();
-                loop {
-                    tokio::select! {
-                        res = State::error_on_close(&mut state_watch_rx) => {
-                            res?
-                        }
-                        result = rtcp_interceptor.read(b, &a) => {
-                            return Ok(result?)
-                        }
-                    }
-                }
-            } else {
-                Err(Error::ErrInterceptorNotBind)
-            }
-        } else {
-            Err(Error::ErrExistingTrack)
-        }
+        let rtcp_reads = {
+            let tracks = self.tracks.read().await;
+            let t = tracks.first().ok_or(Error::ErrExistingTrack)?;
+            t.stream
+                .rtcp_reads
+                .clone()
+                .ok_or(Error::ErrInterceptorNotBind)?
+        };
+
+        Self::read_queued_rtcp(&rtcp_reads, b, &mut state_watch_rx).await
     }
 
     /// read_simulcast reads incoming RTCP for this RTPReceiver for given rid
@@ -205,28 +258,44 @@ impl RTPReceiverInternal {
         // isn't flowing.
         State::wait_for(&mut state_watch_rx, &[State::Started, State::Paused]).await?;
 
-        let tracks = self.tracks.read().await;
-        for t in &*tracks {
-            if t.track.rid() == rid {
-                if let Some(rtcp_interceptor) = &t.stream.rtcp_interceptor {
-                    let a = Attributes::new();
+        let rtcp_reads = {
+            let tracks = self.tracks.read().await;
+            let t = tracks
+                .iter()
+                .find(|t| t.track.rid() == rid)
+                .ok_or(Error::ErrRTPReceiverForRIDTrackStreamNotFound)?;
+            t.stream
+                .rtcp_reads
+                .clone()
+                .ok_or(Error::ErrInterceptorNotBind)?
+        };
 
-                    loop {
-                        tokio::select! {
-                            res = State::error_on_close(&mut state_watch_rx) => {
-                                res?
-                            }
-                            result = rtcp_interceptor.read(b, &a) => {
-                                return Ok(result?);
-                            }
-                        }
+        Self::read_queued_rtcp(&rtcp_reads, b, &mut state_watch_rx).await
+    }
+
+    /// read_queued_rtcp returns the next RTCP read in the background from the stream of a track
+    async fn read_queued_rtcp(
+        rtcp_reads: &Mutex<mpsc::Receiver<RTCPRead>>,
+        b: &mut [u8],
+        state_watch_rx: &mut watch::Receiver<State>,
+    ) -> RTCPRead {
+        let mut rtcp_reads = rtcp_reads.lock().await;
+        loop {
+            tokio::select! {
+                res = State::error_on_close(state_watch_rx) => {
+                    res?
+                }
+                result = rtcp_reads.recv() => {
+                    let (pkts, attributes) = result.unwrap_or(Err(Error::ErrClosedPipe))?;
+                    // The RTCP was read into another buffer, callers still get it in b
+                    if let Ok(raw) = rtcp::packet::marshal(&pkts) {
+                        let n = raw.len().min(b.len());
+                        b[..n].copy_from_slice(&raw[..n]);
                     }
-                } else {
-                    return Err(Error::ErrInterceptorNotBind);
+                    return Ok((pkts, attributes));
                 }
             }
         }
-        Err(Error::ErrRTPReceiverForRIDTrackStreamNotFound)
     }
 
     /// read_rtcp is a convenience method that wraps Read and unmarshal for you.
@@ -316,6 +385,18 @@ impl RTPReceiverInternal {
         }
     }
 
+    pub(crate) fn keyframe_request_policy(&self) -> Option<RTCKeyframeRequestPolicy> {
+        self.keyframe_request_policy.lock().clone()
+    }
+
+    /// write_rtcp sends RTCP feedback for the tracks of this RTPReceiver
+    pub(crate) async fn write_rtcp(
+        &self,
+        pkts: &[Box<dyn rtcp::packet::Packet + Send + Sync>],
+    ) -> Result<usize> {
+        self.transport.write_rtcp(pkts).await
+    }
+
     async fn get_parameters(&self) -> RTCRtpParameters {
         let mut parameters = self
             .media_engine
@@ -438,6 +519,8 @@ impl RTCRtpReceiver {
                 state_rx,
 
                 transceiver_codecs: ArcSwapOption::new(None),
+                transform: TransformSlot::default(),
+                keyframe_request_policy: SyncMutex::new(None),
             }),
         }
     }
@@ -459,6 +542,44 @@ impl RTCRtpReceiver {
         Arc::clone(&self.internal.transport)
     }
 
+    /// get_capabilities returns the codecs and header extensions this receiver is able to
+    /// receive for the given kind of media, the same as [`API::get_capabilities`] with
+    /// `Recvonly`.
+    ///
+    /// [`API::get_capabilities`]: crate::api::API::get_capabilities
+    pub fn get_capabilities(&self, kind: RTPCodecType) -> RTCRtpCapabilities {
+        self.internal
+            .media_engine
+            .get_capabilities(kind, RTCRtpTransceiverDirection::Recvonly)
+    }
+
+    /// set_transform installs a transform that is given every encoded frame read with
+    /// TrackRemote::read_frame after the frame is depacketized, or removes it when `None`.
+    pub fn set_transform(&self, transform: Option<Arc<dyn RTCRtpTransform>>) {
+        self.internal.transform.set(transform);
+    }
+
+    /// transform returns the transform installed with set_transform
+    pub fn transform(&self) -> Option<Arc<dyn RTCRtpTransform>> {
+        self.internal.transform.get()
+    }
+
+    /// set_keyframe_request_policy enables requesting keyframes automatically for the video
+    /// tracks of this receiver, or disables it when `None`.
+    ///
+    /// A keyframe is requested when packets are lost, or when the track doesn't start with a
+    /// keyframe, and the request is repeated until one is received. Requests are sent as PLI,
+    /// or as FIR if only `ccm fir` was negotiated for the codec, and are only sent while the
+    /// track is read.
+    pub fn set_keyframe_request_policy(&self, policy: Option<RTCKeyframeRequestPolicy>) {
+        *self.internal.keyframe_request_policy.lock() = policy;
+    }
+
+    /// keyframe_request_policy returns the policy set with set_keyframe_request_policy
+    pub fn keyframe_request_policy(&self) -> Option<RTCKeyframeRequestPolicy> {
+        self.internal.keyframe_request_policy()
+    }
+
     /// get_parameters describes the current configuration for the encoding and
     /// transmission of media on the receiver's track.
     pub async fn get_parameters(&self) -> RTCRtpParameters {
@@ -552,7 +673,7 @@ impl RTCRtpReceiver {
                     (None, None, None, None, None)
                 };
 
-            let t = TrackStreams {
+            let mut t = TrackStreams {
                 track: Arc::new(TrackRemote::new(
                     self.receive_mtu,
                     self.internal.kind,
@@ -568,6 +689,7 @@ impl RTCRtpReceiver {
                     rtp_interceptor,
                     rtcp_read_stream,
                     rtcp_interceptor,
+                    rtcp_reads: None,
                 },
 
                 repair_stream: TrackStream {
@@ -576,8 +698,10 @@ impl RTCRtpReceiver {
                     rtp_interceptor: None,
                     rtcp_read_stream: None,
                     rtcp_interceptor: None,
+                    rtcp_reads: None,
                 },
             };
+            start_rtcp_reader(Arc::clone(&t.track), &mut t.stream, self.receive_mtu);
 
             {
                 let mut tracks = self.internal.tracks.write().await;
@@ -617,6 +741,7 @@ impl RTCRtpReceiver {
                         rtp_interceptor: Some(rtp_interceptor),
                         rtcp_read_stream: Some(rtcp_read_stream),
                         rtcp_interceptor: Some(rtcp_interceptor),
+                        rtcp_reads: None,
                     },
                 )
                 .await?;
@@ -626,7 +751,8 @@ impl RTCRtpReceiver {
         Ok(())
     }
 
-    /// read reads incoming RTCP for this RTPReceiver
+    /// read reads incoming RTCP for this RTPReceiver. RTCP is read in the background as it is
+    /// received, and up to 64 reads are kept until the application reads them.
     pub async fn read(
         &self,
         b: &mut [u8],
@@ -769,7 +895,7 @@ impl RTCRtpReceiver {
         &self,
         rid: SmolStr,
         params: RTCRtpParameters,
-        stream: TrackStream,
+        mut stream: TrackStream,
     ) -> Result<Arc<TrackRemote>> {
         let mut tracks = self.internal.tracks.write().await;
         for t in &mut *tracks {
@@ -781,6 +907,7 @@ impl RTCRtpReceiver {
                 t.track.set_params(params.clone());
                 t.track
                     .set_ssrc(stream.stream_info.as_ref().map_or(0, |s| s.ssrc));
+                start_rtcp_reader(Arc::clone(&t.track), &mut stream, self.receive_mtu);
                 t.stream = stream;
                 return Ok(Arc::clone(&t.track));
             }
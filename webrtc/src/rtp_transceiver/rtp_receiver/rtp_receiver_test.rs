@@ -1,20 +1,29 @@
 use bytes::Bytes;
+use interceptor::registry::Registry;
 use media::Sample;
 use tokio::sync::mpsc;
 use tokio::time::Duration;
 use waitgroup::WaitGroup;
 
 use super::*;
+use crate::api::interceptor_registry::configure_rtcp_reports;
 use crate::api::media_engine::{MIME_TYPE_OPUS, MIME_TYPE_VP8};
+use crate::api::APIBuilder;
 use crate::error::Result;
 use crate::peer_connection::peer_connection_state::RTCPeerConnectionState;
 use crate::peer_connection::peer_connection_test::{
-    close_pair_now, create_vnet_pair, signal_pair, until_connection_state,
+    close_pair_now, create_vnet_pair, new_pair, send_video_until_done, signal_pair,
+    until_connection_state,
 };
 use crate::rtp_transceiver::rtp_codec::RTCRtpHeaderExtensionParameters;
+use crate::rtp_transceiver::rtp_receiver::keyframe_request::RTCKeyframeRequestPolicy;
+use crate::rtp_transceiver::rtp_sender::encoder_control::{
+    RTCEncoderEvent, RTCKeyframeRequestKind,
+};
 use crate::rtp_transceiver::{RTCPFeedback, RTCRtpCodecCapability};
 use crate::track::track_local::track_local_static_sample::TrackLocalStaticSample;
 use crate::track::track_local::TrackLocal;
+use crate::track::track_remote::av_sync::av_sync_offset;
 
 lazy_static! {
     static ref P: RTCRtpParameters = RTCRtpParameters {
@@ -231,3 +240,140 @@ async fn test_rtp_receiver_set_read_deadline() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_rtp_receiver_keyframe_request_policy() -> Result<()> {
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    let api = APIBuilder::new().with_media_engine(m).build();
+
+    let (mut pc_offer, mut pc_answer) = new_pair(&api).await?;
+
+    let track = Arc::new(TrackLocalStaticSample::new(
+        RTCRtpCodecCapability {
+            mime_type: MIME_TYPE_VP8.to_owned(),
+            ..Default::default()
+        },
+        "video".to_owned(),
+        "webrtc-rs".to_owned(),
+    ));
+    let rtp_sender = pc_offer
+        .add_track(Arc::clone(&track) as Arc<dyn TrackLocal + Send + Sync>)
+        .await?;
+    let mut events_rx = rtp_sender.encoder_events().await;
+
+    pc_answer.on_track(Box::new(move |track, receiver, _| {
+        assert_eq!(receiver.keyframe_request_policy(), None);
+        receiver.set_keyframe_request_policy(Some(RTCKeyframeRequestPolicy::default()));
+        Box::pin(async move { while track.read_rtp().await.is_ok() {} })
+    }));
+
+    signal_pair(&mut pc_offer, &mut pc_answer).await?;
+
+    // Only delta frames are sent, so the receiver asks for a keyframe
+    let (done_tx, done_rx) = mpsc::channel::<()>(1);
+    let send_video = tokio::spawn(send_video_until_done(
+        done_rx,
+        vec![track],
+        Bytes::from_static(&[0x01, 0x02, 0x03]),
+        None,
+    ));
+
+    loop {
+        let event = events_rx.recv().await.unwrap();
+        if let RTCEncoderEvent::KeyframeRequested { kind, .. } = event {
+            assert_eq!(kind, RTCKeyframeRequestKind::Pli);
+            break;
+        }
+    }
+    let _ = done_tx.send(()).await;
+    let _ = send_video.await;
+
+    close_pair_now(&pc_offer, &pc_answer).await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rtp_receiver_av_sync() -> Result<()> {
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    let registry = configure_rtcp_reports(Registry::new());
+    let api = APIBuilder::new()
+        .with_media_engine(m)
+        .with_interceptor_registry(registry)
+        .build();
+
+    let (mut pc_offer, mut pc_answer) = new_pair(&api).await?;
+
+    let mut tracks = vec![];
+    for mime_type in [MIME_TYPE_OPUS, MIME_TYPE_VP8] {
+        let track = Arc::new(TrackLocalStaticSample::new(
+            RTCRtpCodecCapability {
+                mime_type: mime_type.to_owned(),
+                ..Default::default()
+            },
+            mime_type.to_owned(),
+            "webrtc-rs".to_owned(),
+        ));
+        pc_offer
+            .add_track(Arc::clone(&track) as Arc<dyn TrackLocal + Send + Sync>)
+            .await?;
+        tracks.push(track);
+    }
+
+    let (track_tx, mut track_rx) = mpsc::channel::<Arc<TrackRemote>>(2);
+    // Sender reports are processed without reading RTCP
+    pc_answer.on_track(Box::new(move |track, _, _| {
+        let track_tx = track_tx.clone();
+        Box::pin(async move {
+            let _ = track_tx.send(Arc::clone(&track)).await;
+            tokio::spawn(async move { while track.read_rtp().await.is_ok() {} });
+        })
+    }));
+
+    signal_pair(&mut pc_offer, &mut pc_answer).await?;
+
+    let send_samples = tokio::spawn(async move {
+        loop {
+            for track in &tracks {
+                let result = track
+                    .write_sample(&Sample {
+                        data: Bytes::from_static(&[0xAA, 0xBB, 0xCC]),
+                        duration: Duration::from_millis(20),
+                        ..Default::default()
+                    })
+                    .await;
+                assert!(result.is_ok());
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    });
+
+    let mut audio = track_rx.recv().await.unwrap();
+    let mut video = track_rx.recv().await.unwrap();
+    if audio.kind() == RTPCodecType::Video {
+        std::mem::swap(&mut audio, &mut video);
+    }
+    assert_eq!(audio.rtp_to_ntp(0), None);
+
+    let offset = loop {
+        if let Some(offset) = av_sync_offset(&audio, &video) {
+            break offset;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    };
+    // Both tracks are sent and received together
+    assert!(offset.abs() < 0.1, "{offset}");
+
+    let sender_report = audio.sender_report().unwrap();
+    assert_eq!(
+        audio.rtp_to_ntp(sender_report.rtp_time),
+        Some(sender_report.ntp_time)
+    );
+
+    send_samples.abort();
+    close_pair_now(&pc_offer, &pc_answer).await;
+
+    Ok(())
+}
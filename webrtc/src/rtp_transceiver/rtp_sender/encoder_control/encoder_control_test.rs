@@ -0,0 +1,221 @@
+use rtcp::payload_feedbacks::full_intra_request::FirEntry;
+use rtcp::payload_feedbacks::layer_refresh_request::{LayerId, LrrEntry};
+use rtcp::transport_feedbacks::temporary_maximum_media_stream_bitrate::TmmbEntry;
+
+use super::*;
+
+const SSRC_LOW: SSRC = 1000;
+const SSRC_HIGH: SSRC = 2000;
+
+fn new_encoder_control(
+    keyframe_request_interval: Duration,
+) -> (
+    Arc<EncoderControl>,
+    mpsc::UnboundedReceiver<RTCEncoderEvent>,
+) {
+    let encoder_control = Arc::new(EncoderControl::new(keyframe_request_interval, true));
+    encoder_control.set_encodings(vec![
+        EncoderEncoding {
+            ssrc: SSRC_LOW,
+            rid: Some("q".to_owned()),
+        },
+        EncoderEncoding {
+            ssrc: SSRC_HIGH,
+            rid: Some("f".to_owned()),
+        },
+    ]);
+    let events_rx = encoder_control.subscribe();
+    (encoder_control, events_rx)
+}
+
+fn pli(media_ssrc: SSRC) -> Box<dyn rtcp::packet::Packet + Send + Sync> {
+    Box::new(PictureLossIndication {
+        sender_ssrc: 1,
+        media_ssrc,
+    })
+}
+
+fn fir(ssrc: SSRC, sequence_number: u8) -> Box<dyn rtcp::packet::Packet + Send + Sync> {
+    Box::new(FullIntraRequest {
+        sender_ssrc: 1,
+        media_ssrc: 0,
+        fir: vec![FirEntry {
+            ssrc,
+            sequence_number,
+        }],
+    })
+}
+
+fn keyframe_requested(ssrc: SSRC, kind: RTCKeyframeRequestKind) -> RTCEncoderEvent {
+    RTCEncoderEvent::KeyframeRequested {
+        ssrc,
+        rid: Some(if ssrc == SSRC_LOW { "q" } else { "f" }.to_owned()),
+        kind,
+    }
+}
+
+#[tokio::test]
+async fn test_encoder_control_keyframe_request_debounce() {
+    let (encoder_control, mut events_rx) = new_encoder_control(Duration::from_millis(100));
+
+    encoder_control.handle_rtcp(&[pli(SSRC_LOW), pli(SSRC_HIGH)]);
+    assert_eq!(
+        events_rx.try_recv(),
+        Ok(keyframe_requested(SSRC_LOW, RTCKeyframeRequestKind::Pli))
+    );
+    assert_eq!(
+        events_rx.try_recv(),
+        Ok(keyframe_requested(SSRC_HIGH, RTCKeyframeRequestKind::Pli))
+    );
+
+    // A storm of requests is reported once at the end of the interval, as a FIR if any
+    // request was one
+    encoder_control.handle_rtcp(&[pli(SSRC_LOW), fir(SSRC_LOW, 1), pli(SSRC_LOW)]);
+    assert!(events_rx.try_recv().is_err());
+
+    let event = tokio::time::timeout(Duration::from_secs(1), events_rx.recv()).await;
+    assert_eq!(
+        event,
+        Ok(Some(keyframe_requested(
+            SSRC_LOW,
+            RTCKeyframeRequestKind::Fir
+        )))
+    );
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert!(events_rx.try_recv().is_err());
+
+    // Requests for other SSRCs are ignored
+    encoder_control.handle_rtcp(&[pli(3000)]);
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert!(events_rx.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_encoder_control_fir_retransmission() {
+    let (encoder_control, mut events_rx) = new_encoder_control(Duration::ZERO);
+
+    encoder_control.handle_rtcp(&[fir(SSRC_HIGH, 7), fir(SSRC_HIGH, 7)]);
+    assert_eq!(
+        events_rx.try_recv(),
+        Ok(keyframe_requested(SSRC_HIGH, RTCKeyframeRequestKind::Fir))
+    );
+    assert!(events_rx.try_recv().is_err());
+
+    encoder_control.handle_rtcp(&[fir(SSRC_HIGH, 8)]);
+    assert_eq!(
+        events_rx.try_recv(),
+        Ok(keyframe_requested(SSRC_HIGH, RTCKeyframeRequestKind::Fir))
+    );
+}
+
+#[tokio::test]
+async fn test_encoder_control_layer_refresh_request() {
+    let (encoder_control, mut events_rx) = new_encoder_control(Duration::ZERO);
+
+    let lrr = LayerRefreshRequest {
+        sender_ssrc: 1,
+        media_ssrc: 0,
+        entries: vec![LrrEntry {
+            ssrc: SSRC_LOW,
+            sequence_number: 3,
+            payload_type: 98,
+            target_layer: LayerId {
+                temporal_id: 1,
+                layer_id: 2,
+            },
+            current_layer: None,
+        }],
+    };
+    encoder_control.handle_rtcp(&[Box::new(lrr.clone()), Box::new(lrr)]);
+
+    assert_eq!(
+        events_rx.try_recv(),
+        Ok(RTCEncoderEvent::LayerRefreshRequested {
+            ssrc: SSRC_LOW,
+            rid: Some("q".to_owned()),
+            temporal_id: 1,
+            layer_id: 2,
+        })
+    );
+    assert!(events_rx.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_encoder_control_target_bitrate() {
+    let (encoder_control, mut events_rx) = new_encoder_control(Duration::ZERO);
+
+    let remb = |bitrate: f32, ssrcs: Vec<SSRC>| -> Box<dyn rtcp::packet::Packet + Send + Sync> {
+        Box::new(ReceiverEstimatedMaximumBitrate {
+            sender_ssrc: 1,
+            bitrate,
+            ssrcs,
+        })
+    };
+
+    encoder_control.handle_rtcp(&[
+        remb(1_000_000.0, vec![SSRC_LOW, SSRC_HIGH]),
+        remb(1_000_000.0, vec![SSRC_LOW, SSRC_HIGH]),
+        remb(500_000.0, vec![3000]),
+    ]);
+    assert_eq!(
+        events_rx.try_recv(),
+        Ok(RTCEncoderEvent::TargetBitrateChanged { bitrate: 1_000_000 })
+    );
+    assert!(events_rx.try_recv().is_err());
+
+    let tmmbr = TemporaryMaximumMediaStreamBitrateRequest {
+        sender_ssrc: 1,
+        media_ssrc: 0,
+        entries: vec![
+            TmmbEntry {
+                ssrc: SSRC_LOW,
+                bitrate: 150_000,
+                overhead: 40,
+            },
+            TmmbEntry {
+                ssrc: SSRC_HIGH,
+                bitrate: 600_000,
+                overhead: 40,
+            },
+        ],
+    };
+    encoder_control.handle_rtcp(&[Box::new(tmmbr)]);
+    assert_eq!(
+        events_rx.try_recv(),
+        Ok(RTCEncoderEvent::TargetBitrateChanged { bitrate: 750_000 })
+    );
+}
+
+#[tokio::test]
+async fn test_encoder_control_active() {
+    let (encoder_control, mut events_rx) = new_encoder_control(Duration::ZERO);
+
+    encoder_control.set_active(true);
+    assert!(events_rx.try_recv().is_err());
+
+    encoder_control.set_active(false);
+    for (ssrc, rid) in [(SSRC_LOW, "q"), (SSRC_HIGH, "f")] {
+        assert_eq!(
+            events_rx.try_recv(),
+            Ok(RTCEncoderEvent::LayerDeactivated {
+                ssrc,
+                rid: Some(rid.to_owned()),
+            })
+        );
+    }
+
+    encoder_control.set_active(true);
+    for ssrc in [SSRC_LOW, SSRC_HIGH] {
+        assert!(matches!(
+            events_rx.try_recv(),
+            Ok(RTCEncoderEvent::LayerActivated { ssrc: s, .. }) if s == ssrc
+        ));
+    }
+
+    // A new subscription closes the previous channel
+    let _events_rx = encoder_control.subscribe();
+    assert!(events_rx.recv().await.is_none());
+
+    encoder_control.close();
+    assert!(!encoder_control.is_subscribed());
+}
@@ -0,0 +1,294 @@
+#[cfg(test)]
+mod encoder_control_test;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use rtcp::payload_feedbacks::full_intra_request::FullIntraRequest;
+use rtcp::payload_feedbacks::layer_refresh_request::LayerRefreshRequest;
+use rtcp::payload_feedbacks::picture_loss_indication::PictureLossIndication;
+use rtcp::payload_feedbacks::receiver_estimated_maximum_bitrate::ReceiverEstimatedMaximumBitrate;
+use rtcp::transport_feedbacks::temporary_maximum_media_stream_bitrate::TemporaryMaximumMediaStreamBitrateRequest;
+use tokio::sync::mpsc;
+use tokio::time::{Duration, Instant};
+use util::sync::Mutex as SyncMutex;
+
+use crate::rtp_transceiver::SSRC;
+
+/// The default minimum time between two keyframe requests reported for the same encoding.
+pub(crate) const DEFAULT_KEYFRAME_REQUEST_INTERVAL: Duration = Duration::from_millis(300);
+
+/// RTCKeyframeRequestKind is the RTCP feedback message a keyframe was requested with
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RTCKeyframeRequestKind {
+    /// Picture Loss Indication, RFC 4585 Section 6.3.1
+    Pli,
+    /// Full Intra Request, RFC 5104 Section 4.3.1
+    Fir,
+}
+
+/// RTCEncoderEvent is an instruction for the encoder feeding a RTPSender, derived from the
+/// RTCP feedback of the remote peer or from the state of the sender.
+///
+/// Encodings are identified by their SSRC, and by their RID for simulcast senders.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RTCEncoderEvent {
+    /// The remote peer can't decode the encoding any more and needs a keyframe. Requests
+    /// received less than the keyframe request interval apart are merged into one event.
+    KeyframeRequested {
+        ssrc: SSRC,
+        rid: Option<String>,
+        kind: RTCKeyframeRequestKind,
+    },
+    /// The remote peer requests a refresh of a single layer of a scalable encoding, see
+    /// RFC 9627.
+    LayerRefreshRequested {
+        ssrc: SSRC,
+        rid: Option<String>,
+        temporal_id: u8,
+        layer_id: u8,
+    },
+    /// The bitrate in bits per second the remote peer is willing to receive for all encodings
+    /// of the sender, from REMB or TMMBR feedback.
+    TargetBitrateChanged { bitrate: u64 },
+    /// The encoding is sent again, after the sender was paused.
+    LayerActivated { ssrc: SSRC, rid: Option<String> },
+    /// The encoding is no longer sent, e.g. because the transceiver direction no longer
+    /// includes send. The encoder can stop producing it.
+    LayerDeactivated { ssrc: SSRC, rid: Option<String> },
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct EncoderEncoding {
+    pub(crate) ssrc: SSRC,
+    pub(crate) rid: Option<String>,
+}
+
+#[derive(Default)]
+struct KeyframeRequestState {
+    last_reported: Option<Instant>,
+    pending: Option<RTCKeyframeRequestKind>,
+}
+
+#[derive(Default)]
+struct EncoderControlState {
+    encodings: Vec<EncoderEncoding>,
+    active: bool,
+    keyframe_requests: HashMap<SSRC, KeyframeRequestState>,
+    /// Last FIR and LRR sequence numbers per requester and media SSRC, to ignore retransmitted
+    /// requests
+    fir_sequence_numbers: HashMap<(u32, SSRC), u8>,
+    lrr_sequence_numbers: HashMap<(u32, SSRC), u8>,
+    target_bitrate: Option<u64>,
+}
+
+/// EncoderControl turns the RTCP feedback received by a RTPSender into RTCEncoderEvents
+pub(crate) struct EncoderControl {
+    keyframe_request_interval: Duration,
+    events_tx: SyncMutex<Option<mpsc::UnboundedSender<RTCEncoderEvent>>>,
+    state: SyncMutex<EncoderControlState>,
+}
+
+impl EncoderControl {
+    pub(crate) fn new(keyframe_request_interval: Duration, active: bool) -> Self {
+        EncoderControl {
+            keyframe_request_interval,
+            events_tx: SyncMutex::new(None),
+            state: SyncMutex::new(EncoderControlState {
+                active,
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// subscribe returns a new channel of events, closing the previous one
+    pub(crate) fn subscribe(&self) -> mpsc::UnboundedReceiver<RTCEncoderEvent> {
+        let (events_tx, events_rx) = mpsc::unbounded_channel();
+        *self.events_tx.lock() = Some(events_tx);
+        events_rx
+    }
+
+    pub(crate) fn is_subscribed(&self) -> bool {
+        self.events_tx.lock().is_some()
+    }
+
+    /// close closes the channel of events
+    pub(crate) fn close(&self) {
+        self.events_tx.lock().take();
+    }
+
+    pub(crate) fn set_encodings(&self, encodings: Vec<EncoderEncoding>) {
+        self.state.lock().encodings = encodings;
+    }
+
+    /// set_active reports every encoding as activated or deactivated, if the state changed
+    pub(crate) fn set_active(&self, active: bool) {
+        let encodings = {
+            let mut state = self.state.lock();
+            if state.active == active {
+                return;
+            }
+            state.active = active;
+            state.encodings.clone()
+        };
+
+        for e in encodings {
+            self.emit(if active {
+                RTCEncoderEvent::LayerActivated {
+                    ssrc: e.ssrc,
+                    rid: e.rid,
+                }
+            } else {
+                RTCEncoderEvent::LayerDeactivated {
+                    ssrc: e.ssrc,
+                    rid: e.rid,
+                }
+            });
+        }
+    }
+
+    /// handle_rtcp emits the events for the RTCP packets received for the sender
+    pub(crate) fn handle_rtcp(
+        self: &Arc<Self>,
+        pkts: &[Box<dyn rtcp::packet::Packet + Send + Sync>],
+    ) {
+        for pkt in pkts {
+            let pkt = pkt.as_any();
+            if let Some(pli) = pkt.downcast_ref::<PictureLossIndication>() {
+                self.request_keyframe(pli.media_ssrc, RTCKeyframeRequestKind::Pli);
+            } else if let Some(fir) = pkt.downcast_ref::<FullIntraRequest>() {
+                for entry in &fir.fir {
+                    if self.is_new_request(fir.sender_ssrc, entry.ssrc, entry.sequence_number, true)
+                    {
+                        self.request_keyframe(entry.ssrc, RTCKeyframeRequestKind::Fir);
+                    }
+                }
+            } else if let Some(lrr) = pkt.downcast_ref::<LayerRefreshRequest>() {
+                for entry in &lrr.entries {
+                    if !self.is_new_request(
+                        lrr.sender_ssrc,
+                        entry.ssrc,
+                        entry.sequence_number,
+                        false,
+                    ) {
+                        continue;
+                    }
+                    if let Some(e) = self.encoding(entry.ssrc) {
+                        self.emit(RTCEncoderEvent::LayerRefreshRequested {
+                            ssrc: e.ssrc,
+                            rid: e.rid,
+                            temporal_id: entry.target_layer.temporal_id,
+                            layer_id: entry.target_layer.layer_id,
+                        });
+                    }
+                }
+            } else if let Some(remb) = pkt.downcast_ref::<ReceiverEstimatedMaximumBitrate>() {
+                if remb.ssrcs.iter().any(|ssrc| self.encoding(*ssrc).is_some()) {
+                    self.set_target_bitrate(remb.bitrate as u64);
+                }
+            } else if let Some(tmmbr) =
+                pkt.downcast_ref::<TemporaryMaximumMediaStreamBitrateRequest>()
+            {
+                let entries: Vec<_> = tmmbr
+                    .entries
+                    .iter()
+                    .filter(|entry| self.encoding(entry.ssrc).is_some())
+                    .collect();
+                if !entries.is_empty() {
+                    self.set_target_bitrate(entries.iter().map(|entry| entry.bitrate).sum());
+                }
+            }
+        }
+    }
+
+    fn encoding(&self, ssrc: SSRC) -> Option<EncoderEncoding> {
+        let state = self.state.lock();
+        state.encodings.iter().find(|e| e.ssrc == ssrc).cloned()
+    }
+
+    /// is_new_request returns false for FIR and LRR entries that repeat the sequence number of
+    /// the previous request, which are retransmissions of the same request.
+    fn is_new_request(&self, sender_ssrc: u32, ssrc: SSRC, sequence_number: u8, fir: bool) -> bool {
+        let mut state = self.state.lock();
+        let sequence_numbers = if fir {
+            &mut state.fir_sequence_numbers
+        } else {
+            &mut state.lrr_sequence_numbers
+        };
+        sequence_numbers.insert((sender_ssrc, ssrc), sequence_number) != Some(sequence_number)
+    }
+
+    fn request_keyframe(self: &Arc<Self>, ssrc: SSRC, kind: RTCKeyframeRequestKind) {
+        let Some(encoding) = self.encoding(ssrc) else {
+            return;
+        };
+
+        let now = Instant::now();
+        let deadline = {
+            let mut state = self.state.lock();
+            let request = state.keyframe_requests.entry(ssrc).or_default();
+            match request.last_reported {
+                Some(last) if now < last + self.keyframe_request_interval => {
+                    // Merge into the request reported at the end of the interval
+                    let already_pending = request.pending.is_some();
+                    if request.pending != Some(RTCKeyframeRequestKind::Fir) {
+                        request.pending = Some(kind);
+                    }
+                    if already_pending {
+                        return;
+                    }
+                    last + self.keyframe_request_interval
+                }
+                _ => {
+                    request.last_reported = Some(now);
+                    drop(state);
+                    self.emit(RTCEncoderEvent::KeyframeRequested {
+                        ssrc,
+                        rid: encoding.rid,
+                        kind,
+                    });
+                    return;
+                }
+            }
+        };
+
+        let encoder_control = Arc::downgrade(self);
+        tokio::spawn(async move {
+            tokio::time::sleep_until(deadline).await;
+            let Some(encoder_control) = encoder_control.upgrade() else {
+                return;
+            };
+            let kind = {
+                let mut state = encoder_control.state.lock();
+                let request = state.keyframe_requests.entry(ssrc).or_default();
+                request.last_reported = Some(Instant::now());
+                request.pending.take()
+            };
+            if let Some(kind) = kind {
+                encoder_control.emit(RTCEncoderEvent::KeyframeRequested {
+                    ssrc,
+                    rid: encoding.rid,
+                    kind,
+                });
+            }
+        });
+    }
+
+    fn set_target_bitrate(&self, bitrate: u64) {
+        {
+            let mut state = self.state.lock();
+            if state.target_bitrate == Some(bitrate) {
+                return;
+            }
+            state.target_bitrate = Some(bitrate);
+        }
+        self.emit(RTCEncoderEvent::TargetBitrateChanged { bitrate });
+    }
+
+    fn emit(&self, event: RTCEncoderEvent) {
+        let events_tx = self.events_tx.lock();
+        if let Some(events_tx) = &*events_tx {
+            let _ = events_tx.send(event);
+        }
+    }
+}
@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod rtp_sender_test;
 
+pub mod encoder_control;
+
 use std::sync::atomic::Ordering;
 use std::sync::{Arc, Weak};
 
@@ -9,21 +11,25 @@ use interceptor::stream_info::{AssociatedStreamInfo, StreamInfo};
 use interceptor::{Attributes, Interceptor, RTCPReader, RTPWriter};
 use portable_atomic::AtomicBool;
 use tokio::select;
-use tokio::sync::{watch, Mutex, Notify};
+use tokio::sync::{mpsc, watch, Mutex, Notify};
 use util::sync::Mutex as SyncMutex;
 
+use self::encoder_control::{
+    EncoderControl, EncoderEncoding, RTCEncoderEvent, DEFAULT_KEYFRAME_REQUEST_INTERVAL,
+};
 use super::srtp_writer_future::SequenceTransformer;
 use super::RTCRtpRtxParameters;
 use crate::api::media_engine::MediaEngine;
 use crate::api::setting_engine::SettingEngine;
 use crate::dtls_transport::RTCDtlsTransport;
 use crate::error::{Error, Result};
-use crate::rtp_transceiver::rtp_codec::{codec_rtx_search, RTPCodecType};
+use crate::rtp_transceiver::rtp_codec::{codec_rtx_search, RTCRtpParameters, RTPCodecType};
 use crate::rtp_transceiver::rtp_transceiver_direction::RTCRtpTransceiverDirection;
+use crate::rtp_transceiver::rtp_transform::{RTCRtpTransform, TransformSlot};
 use crate::rtp_transceiver::srtp_writer_future::SrtpWriterFuture;
 use crate::rtp_transceiver::{
-    create_stream_info, PayloadType, RTCRtpEncodingParameters, RTCRtpSendParameters,
-    RTCRtpTransceiver, SSRC,
+    create_stream_info, PayloadType, RTCRtpCapabilities, RTCRtpEncodingParameters,
+    RTCRtpSendParameters, RTCRtpTransceiver, SSRC,
 };
 use crate::track::track_local::{InterceptorToTrackLocalWriter, TrackLocal, TrackLocalContext};
 
@@ -96,6 +102,10 @@ pub struct RTCRtpSender {
     stop_called_signal: Arc<AtomicBool>,
 
     pub(crate) paused: Arc<AtomicBool>,
+    transform: TransformSlot,
+
+    encoder_control: Arc<EncoderControl>,
+    encoder_control_started: AtomicBool,
 
     internal: Arc<RTPSenderInternal>,
 }
@@ -168,6 +178,15 @@ impl RTCRtpSender {
             stop_called_signal,
 
             paused: Arc::new(AtomicBool::new(start_paused)),
+            transform: TransformSlot::default(),
+
+            encoder_control: Arc::new(EncoderControl::new(
+                setting_engine
+                    .sender_keyframe_request_interval
+                    .unwrap_or(DEFAULT_KEYFRAME_REQUEST_INTERVAL),
+                !start_paused,
+            )),
+            encoder_control_started: AtomicBool::new(false),
 
             internal,
         };
@@ -281,6 +300,7 @@ impl RTCRtpSender {
             write_stream,
             paused: self.paused.clone(),
             mid: None,
+            transform: self.transform.clone(),
         };
         let encoding = TrackEncoding {
             track,
@@ -315,6 +335,7 @@ impl RTCRtpSender {
 
     pub(crate) fn set_paused(&self, paused: bool) {
         self.paused.store(paused, Ordering::SeqCst);
+        self.encoder_control.set_active(!paused);
     }
 
     /// transport returns the currently-configured DTLSTransport
@@ -323,6 +344,46 @@ impl RTCRtpSender {
         Arc::clone(&self.transport)
     }
 
+    /// get_capabilities returns the codecs and header extensions this sender is able to send
+    /// for the given kind of media, the same as [`API::get_capabilities`] with `Sendonly`.
+    ///
+    /// [`API::get_capabilities`]: crate::api::API::get_capabilities
+    pub fn get_capabilities(&self, kind: RTPCodecType) -> RTCRtpCapabilities {
+        self.media_engine
+            .get_capabilities(kind, RTCRtpTransceiverDirection::Sendonly)
+    }
+
+    /// set_transform installs a transform that is given every encoded frame written to the
+    /// sender's track before the frame is packetized, or removes it when `None`.
+    ///
+    /// Frames are only available to the transform for tracks that packetize themselves, such as
+    /// TrackLocalStaticSample. Packets written to a TrackLocalStaticRTP are sent as-is.
+    pub fn set_transform(&self, transform: Option<Arc<dyn RTCRtpTransform>>) {
+        self.transform.set(transform);
+    }
+
+    /// transform returns the transform installed with set_transform
+    pub fn transform(&self) -> Option<Arc<dyn RTCRtpTransform>> {
+        self.transform.get()
+    }
+
+    /// get_rtp_parameters_by_kind returns the parameters of the MediaEngine, restricted to
+    /// the header extensions selected on the transceiver
+    fn get_rtp_parameters_by_kind(&self, kind: RTPCodecType) -> RTCRtpParameters {
+        let mut params = self
+            .media_engine
+            .get_rtp_parameters_by_kind(kind, RTCRtpTransceiverDirection::Sendonly);
+        if let Some(t) = self
+            .rtp_transceiver
+            .lock()
+            .as_ref()
+            .and_then(|t| t.upgrade())
+        {
+            params.header_extensions = t.filter_header_extensions(params.header_extensions);
+        }
+        params
+    }
+
     /// get_parameters describes the current configuration for the encoding and
     /// transmission of media on the sender's track.
     pub async fn get_parameters(&self) -> RTCRtpSendParameters {
@@ -343,9 +404,7 @@ impl RTCRtpSender {
             encodings
         };
 
-        let mut rtp_parameters = self
-            .media_engine
-            .get_rtp_parameters_by_kind(self.kind, RTCRtpTransceiverDirection::Sendonly);
+        let mut rtp_parameters = self.get_rtp_parameters_by_kind(self.kind);
         rtp_parameters.codecs = {
             let tr = self
                 .rtp_transceiver
@@ -413,13 +472,12 @@ impl RTCRtpSender {
 
             let new_context = TrackLocalContext {
                 id: encoding.context.id.clone(),
-                params: self
-                    .media_engine
-                    .get_rtp_parameters_by_kind(t.kind(), RTCRtpTransceiverDirection::Sendonly),
+                params: self.get_rtp_parameters_by_kind(t.kind()),
                 ssrc: encoding.context.ssrc,
                 write_stream: encoding.context.write_stream.clone(),
                 paused: self.paused.clone(),
                 mid,
+                transform: self.transform.clone(),
             };
 
             match t.bind(&new_context).await {
@@ -468,10 +526,7 @@ impl RTCRtpSender {
 
         for (idx, encoding) in track_encodings.iter_mut().enumerate() {
             let write_stream = Arc::new(InterceptorToTrackLocalWriter::new(self.paused.clone()));
-            encoding.context.params = self.media_engine.get_rtp_parameters_by_kind(
-                encoding.track.kind(),
-                RTCRtpTransceiverDirection::Sendonly,
-            );
+            encoding.context.params = self.get_rtp_parameters_by_kind(encoding.track.kind());
             encoding.context.ssrc = parameters.encodings[idx].ssrc;
             encoding.context.write_stream = Arc::clone(&write_stream) as _;
             encoding.context.mid = mid.to_owned();
@@ -526,10 +581,74 @@ impl RTCRtpSender {
             }
         }
 
+        if self.encoder_control.is_subscribed() {
+            self.start_encoder_control(&track_encodings);
+        }
+
         self.send_called.send_replace(true);
         Ok(())
     }
 
+    /// encoder_events returns a channel of the events the encoder feeding this sender has to
+    /// act on: keyframe and layer refresh requests, target bitrate changes, and encodings
+    /// being paused or resumed. Keyframe requests are debounced, see
+    /// SettingEngine::set_sender_keyframe_request_interval.
+    ///
+    /// From the first call on, the sender reads the incoming RTCP of all its encodings itself,
+    /// so read_rtcp and read_rtcp_simulcast must not be used any more. Calling encoder_events
+    /// again closes the previously returned channel. The channel is closed when the sender is
+    /// stopped.
+    pub async fn encoder_events(&self) -> mpsc::UnboundedReceiver<RTCEncoderEvent> {
+        let track_encodings = self.track_encodings.lock().await;
+        let events_rx = self.encoder_control.subscribe();
+        if self.has_sent() {
+            self.start_encoder_control(&track_encodings);
+        }
+
+        events_rx
+    }
+
+    /// starts a routine per encoding that reads its rtcp stream and feeds the encoder control
+    fn start_encoder_control(&self, track_encodings: &[TrackEncoding]) {
+        if self.encoder_control_started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        self.encoder_control.set_encodings(
+            track_encodings
+                .iter()
+                .map(|e| EncoderEncoding {
+                    ssrc: e.ssrc,
+                    rid: e.track.rid().map(|rid| rid.to_owned()),
+                })
+                .collect(),
+        );
+
+        for encoding in track_encodings {
+            let rtcp_reader = Arc::clone(&encoding.rtcp_interceptor);
+            let encoder_control = Arc::clone(&self.encoder_control);
+            let receive_mtu = self.receive_mtu;
+            let stop_called_signal = self.internal.stop_called_signal.clone();
+            let stop_called_rx = self.internal.stop_called_rx.clone();
+
+            tokio::spawn(async move {
+                let attrs = Attributes::new();
+                let mut b = vec![0u8; receive_mtu];
+                while !stop_called_signal.load(Ordering::SeqCst) {
+                    select! {
+                        r = rtcp_reader.read(&mut b, &attrs) => {
+                            match r {
+                                Ok((pkts, _)) => encoder_control.handle_rtcp(&pkts),
+                                Err(_) => break,
+                            }
+                        },
+                        _ = stop_called_rx.notified() => break,
+                    }
+                }
+            });
+        }
+    }
+
     /// starts a routine that reads the rtx rtcp stream
     /// These packets aren't exposed to the user, but we need to process them
     /// for TWCC
@@ -561,6 +680,7 @@ impl RTCRtpSender {
         }
         self.stop_called_signal.store(true, Ordering::SeqCst);
         self.stop_called_tx.notify_waiters();
+        self.encoder_control.close();
 
         if !self.has_sent() {
             return Ok(());
@@ -796,3 +796,78 @@ async fn test_rtp_sender_rtx() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_rtp_sender_encoder_events() -> Result<()> {
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    let api = APIBuilder::new().with_media_engine(m).build();
+
+    let (mut pc_offer, mut pc_answer) = new_pair(&api).await?;
+
+    let track = Arc::new(TrackLocalStaticSample::new(
+        RTCRtpCodecCapability {
+            mime_type: MIME_TYPE_VP8.to_owned(),
+            ..Default::default()
+        },
+        "video".to_owned(),
+        "webrtc-rs".to_owned(),
+    ));
+    let rtp_sender = pc_offer
+        .add_track(Arc::clone(&track) as Arc<dyn TrackLocal + Send + Sync>)
+        .await?;
+    let mut events_rx = rtp_sender.encoder_events().await;
+
+    let (ssrc_tx, mut ssrc_rx) = mpsc::channel::<u32>(1);
+    pc_answer.on_track(Box::new(move |track, _, _| {
+        let ssrc_tx = ssrc_tx.clone();
+        Box::pin(async move {
+            let _ = ssrc_tx.send(track.ssrc()).await;
+        })
+    }));
+
+    signal_pair(&mut pc_offer, &mut pc_answer).await?;
+
+    let (done_tx, done_rx) = mpsc::channel::<()>(1);
+    let send_video = tokio::spawn(send_video_until_done(
+        done_rx,
+        vec![track],
+        Bytes::from_static(&[0xAA, 0xBB, 0xCC]),
+        None,
+    ));
+
+    let ssrc = ssrc_rx.recv().await.unwrap();
+    assert_eq!(ssrc, rtp_sender.get_parameters().await.encodings[0].ssrc);
+    pc_answer
+        .write_rtcp(&[Box::new(
+            rtcp::payload_feedbacks::picture_loss_indication::PictureLossIndication {
+                sender_ssrc: 0,
+                media_ssrc: ssrc,
+            },
+        )])
+        .await?;
+
+    loop {
+        let event = events_rx.recv().await.unwrap();
+        if let RTCEncoderEvent::KeyframeRequested {
+            ssrc: event_ssrc,
+            rid,
+            kind,
+        } = event
+        {
+            assert_eq!(event_ssrc, ssrc);
+            assert_eq!(rid, None);
+            assert_eq!(kind, encoder_control::RTCKeyframeRequestKind::Pli);
+            break;
+        }
+    }
+    let _ = done_tx.send(()).await;
+    let _ = send_video.await;
+
+    close_pair_now(&pc_offer, &pc_answer).await;
+
+    // The channel is closed when the sender stops
+    assert!(events_rx.recv().await.is_none());
+
+    Ok(())
+}
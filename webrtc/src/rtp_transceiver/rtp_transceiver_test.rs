@@ -212,6 +212,118 @@ async fn test_rtp_transceiver_set_codec_preferences_payload_type() -> Result<()>
     Ok(())
 }
 
+#[tokio::test]
+async fn test_rtp_transceiver_header_extensions_to_negotiate() -> Result<()> {
+    const VIDEO_ORIENTATION_URI: &str = "urn:3gpp:video-orientation";
+
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    for typ in [RTPCodecType::Audio, RTPCodecType::Video] {
+        m.register_header_extension(
+            RTCRtpHeaderExtensionCapability {
+                uri: sdp::extmap::SDES_MID_URI.to_owned(),
+            },
+            typ,
+            None,
+        )?;
+    }
+    m.register_header_extension(
+        RTCRtpHeaderExtensionCapability {
+            uri: VIDEO_ORIENTATION_URI.to_owned(),
+        },
+        RTPCodecType::Video,
+        Some(RTCRtpTransceiverDirection::Sendonly),
+    )?;
+    let api = APIBuilder::new().with_media_engine(m).build();
+    let pc = api.new_peer_connection(RTCConfiguration::default()).await?;
+
+    let first = pc
+        .add_transceiver_from_kind(RTPCodecType::Video, None)
+        .await?;
+    let second = pc
+        .add_transceiver_from_kind(RTPCodecType::Video, None)
+        .await?;
+
+    // Capabilities depend on the direction the extension is registered for
+    let uris = |c: RTCRtpCapabilities| -> Vec<String> {
+        c.header_extensions.into_iter().map(|e| e.uri).collect()
+    };
+    let sender = first.sender().await;
+    assert_eq!(
+        uris(sender.get_capabilities(RTPCodecType::Video)),
+        vec![sdp::extmap::SDES_MID_URI, VIDEO_ORIENTATION_URI]
+    );
+    assert_eq!(
+        uris(first.receiver().await.get_capabilities(RTPCodecType::Video)),
+        vec![sdp::extmap::SDES_MID_URI]
+    );
+    assert_eq!(
+        uris(sender.get_capabilities(RTPCodecType::Audio)),
+        vec![sdp::extmap::SDES_MID_URI]
+    );
+    assert!(sender
+        .get_capabilities(RTPCodecType::Video)
+        .codecs
+        .iter()
+        .any(|c| c.mime_type == MIME_TYPE_VP8));
+
+    // The same capabilities are available without a sender or receiver
+    assert_eq!(
+        uris(api.get_capabilities(RTPCodecType::Video, RTCRtpTransceiverDirection::Sendonly)),
+        vec![sdp::extmap::SDES_MID_URI, VIDEO_ORIENTATION_URI]
+    );
+    assert_eq!(
+        uris(api.get_capabilities(RTPCodecType::Video, RTCRtpTransceiverDirection::Recvonly)),
+        vec![sdp::extmap::SDES_MID_URI]
+    );
+    assert_eq!(
+        api.get_capabilities(RTPCodecType::Video, RTCRtpTransceiverDirection::Sendonly)
+            .codecs
+            .len(),
+        sender.get_capabilities(RTPCodecType::Video).codecs.len()
+    );
+
+    assert_eq!(first.header_extensions_to_negotiate().len(), 2);
+    second.set_header_extensions_to_negotiate(vec![RTCRtpHeaderExtensionCapability {
+        uri: sdp::extmap::SDES_MID_URI.to_owned(),
+    }])?;
+    assert_eq!(
+        uris(RTCRtpCapabilities {
+            header_extensions: second.header_extensions_to_negotiate(),
+            ..Default::default()
+        }),
+        vec![sdp::extmap::SDES_MID_URI]
+    );
+    assert_eq!(
+        second.set_header_extensions_to_negotiate(vec![RTCRtpHeaderExtensionCapability {
+            uri: sdp::extmap::ABS_SEND_TIME_URI.to_owned(),
+        }]),
+        Err(Error::ErrRTPTransceiverHeaderExtensionUnsupported)
+    );
+
+    let offer = pc.create_offer(None).await?;
+    let sections: Vec<&str> = offer.sdp.split("m=video").skip(1).collect();
+    assert_eq!(sections.len(), 2);
+    assert!(sections[0].contains(VIDEO_ORIENTATION_URI), "{}", offer.sdp);
+    assert!(sections[0].contains(sdp::extmap::SDES_MID_URI));
+    assert!(
+        !sections[1].contains(VIDEO_ORIENTATION_URI),
+        "{}",
+        offer.sdp
+    );
+    assert!(sections[1].contains(sdp::extmap::SDES_MID_URI));
+
+    // An empty list disables all of them
+    second.set_header_extensions_to_negotiate(vec![])?;
+    let offer = pc.create_offer(None).await?;
+    let sections: Vec<&str> = offer.sdp.split("m=video").skip(1).collect();
+    assert!(!sections[1].contains("a=extmap"), "{}", offer.sdp);
+
+    pc.close().await?;
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_rtp_transceiver_direction_change() -> Result<()> {
     let (offer_pc, answer_pc, _) = create_vnet_pair().await?;
@@ -0,0 +1,368 @@
+#[cfg(test)]
+mod rtp_transform_test;
+
+pub mod sframe_transform;
+
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::{Bytes, BytesMut};
+use rtp::codecs::h264::H264Packet;
+use rtp::codecs::opus::OpusPacket;
+use rtp::codecs::vp8::Vp8Packet;
+use rtp::codecs::vp9::Vp9Packet;
+use rtp::packetizer::Depacketizer;
+use util::sync::Mutex as SyncMutex;
+
+use crate::error::{Error, Result};
+use crate::rtp_transceiver::rtp_codec::RTPCodecType;
+use crate::rtp_transceiver::{PayloadType, SSRC};
+
+/// RTCEncodedFrameMetadata describes an encoded frame handed to a [`RTCRtpTransform`].
+///
+/// ## Specifications
+///
+/// * [W3C]
+///
+/// [W3C]: https://w3c.github.io/webrtc-encoded-transform/#dictdef-rtcencodedvideoframemetadata
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct RTCEncodedFrameMetadata {
+    pub kind: RTPCodecType,
+    pub mime_type: String,
+    pub ssrc: SSRC,
+    pub csrc: Vec<u32>,
+    pub payload_type: PayloadType,
+    /// RTP timestamp of the frame
+    pub timestamp: u32,
+    /// Sequence number of the first packet of the frame, only known on receive
+    pub sequence_number: Option<u16>,
+    /// Whether the frame can be decoded without any other frame. Always false for audio.
+    pub is_keyframe: bool,
+    /// Picture ID of the frame, when the payload descriptor carries one
+    pub frame_id: Option<u64>,
+    /// Frame ids this frame references, when the payload descriptor carries them
+    pub dependencies: Vec<u64>,
+    pub spatial_index: Option<u8>,
+    pub temporal_index: Option<u8>,
+}
+
+/// RTCEncodedFrame is a whole encoded audio or video frame, i.e. the payload before it is
+/// packetized on send or after it was depacketized on receive.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct RTCEncodedFrame {
+    pub data: Bytes,
+    pub metadata: RTCEncodedFrameMetadata,
+}
+
+/// RTCRtpTransform processes encoded frames between the encoder and the packetizer of a
+/// [`RTCRtpSender`], or between the depacketizer and the application of a [`RTCRtpReceiver`].
+/// It is the building block for end-to-end encryption or watermarking of media.
+///
+/// ## Specifications
+///
+/// * [W3C]
+///
+/// [W3C]: https://w3c.github.io/webrtc-encoded-transform/#rtcrtpscripttransform
+/// [`RTCRtpSender`]: crate::rtp_transceiver::rtp_sender::RTCRtpSender
+/// [`RTCRtpReceiver`]: crate::rtp_transceiver::rtp_receiver::RTCRtpReceiver
+#[async_trait]
+pub trait RTCRtpTransform: Send + Sync {
+    /// transform returns the frame to send or deliver in place of `frame`, or `None` to drop it.
+    /// Only `data` of the returned frame is used.
+    async fn transform(&self, frame: RTCEncodedFrame) -> Result<Option<RTCEncodedFrame>>;
+}
+
+/// TransformSlot holds the transform installed on a sender or receiver, shared with the
+/// tracks that read or write its frames.
+#[derive(Default, Clone)]
+pub(crate) struct TransformSlot(Arc<SyncMutex<Option<Arc<dyn RTCRtpTransform>>>>);
+
+impl TransformSlot {
+    pub(crate) fn get(&self) -> Option<Arc<dyn RTCRtpTransform>> {
+        self.0.lock().clone()
+    }
+
+    pub(crate) fn set(&self, transform: Option<Arc<dyn RTCRtpTransform>>) {
+        *self.0.lock() = transform;
+    }
+}
+
+impl fmt::Debug for TransformSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TransformSlot")
+            .field(&self.0.lock().is_some())
+            .finish()
+    }
+}
+
+/// is_keyframe inspects the start of an encoded video frame and reports whether it can be
+/// decoded independently. H.264 and H.265 frames are expected in Annex B format.
+pub(crate) fn is_keyframe(mime_type: &str, data: &[u8]) -> bool {
+    match mime_type.to_lowercase().as_str() {
+        "video/vp8" => data.first().map(|b| b & 0x01 == 0).unwrap_or(false),
+        "video/vp9" => vp9_is_keyframe(data),
+        "video/h264" => annexb_nalus(data).any(|nalu| nalu[0] & 0x1F == 5),
+        "video/h265" | "video/hevc" => annexb_nalus(data).any(|nalu| {
+            let nalu_type = (nalu[0] >> 1) & 0x3F;
+            (16..=23).contains(&nalu_type)
+        }),
+        "video/av1" => av1_has_sequence_header(data),
+        _ => false,
+    }
+}
+
+fn vp9_is_keyframe(data: &[u8]) -> bool {
+    // frame_marker(2) profile_low_bit(1) profile_high_bit(1) [reserved_zero(1)]
+    // show_existing_frame(1) frame_type(1)
+    let Some(&b) = data.first() else {
+        return false;
+    };
+    if b >> 6 != 0b10 {
+        return false;
+    }
+    let profile = ((b >> 5) & 0x01) | ((b >> 3) & 0x02);
+    let shift = if profile == 3 { 1 } else { 0 };
+    let show_existing_frame = (b >> (3 - shift)) & 0x01;
+    let frame_type = (b >> (2 - shift)) & 0x01;
+
+    show_existing_frame == 0 && frame_type == 0
+}
+
+fn av1_has_sequence_header(mut data: &[u8]) -> bool {
+    const OBU_SEQUENCE_HEADER: u8 = 1;
+
+    while let Some(&header) = data.first() {
+        let obu_type = (header >> 3) & 0x0F;
+        if obu_type == OBU_SEQUENCE_HEADER {
+            return true;
+        }
+        let has_extension = header & 0x04 != 0;
+        let has_size = header & 0x02 != 0;
+        if !has_size {
+            return false;
+        }
+
+        let mut offset = 1 + has_extension as usize;
+        let mut size = 0usize;
+        for i in 0..8 {
+            let Some(&b) = data.get(offset) else {
+                return false;
+            };
+            offset += 1;
+            size |= ((b & 0x7F) as usize) << (i * 7);
+            if b & 0x80 == 0 {
+                break;
+            }
+        }
+        match data.get(offset + size..) {
+            Some(rest) => data = rest,
+            None => return false,
+        }
+    }
+
+    false
+}
+
+/// annexb_nalus iterates the NAL units of an Annex B byte stream, without start codes.
+fn annexb_nalus(data: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut starts = vec![];
+    let mut i = 0;
+    while i + 3 <= data.len() {
+        if data[i] == 0 && data[i + 1] == 0 && data[i + 2] == 1 {
+            starts.push(i + 3);
+            i += 3;
+        } else {
+            i += 1;
+        }
+    }
+
+    let ends: Vec<usize> = starts
+        .iter()
+        .skip(1)
+        .map(|&start| {
+            let mut end = start - 3;
+            while end > 0 && data[end - 1] == 0 {
+                end -= 1;
+            }
+            end
+        })
+        .chain(std::iter::once(data.len()))
+        .collect();
+
+    starts
+        .into_iter()
+        .zip(ends)
+        .filter(|(start, end)| start < end)
+        .map(move |(start, end)| &data[start..end])
+}
+
+/// FrameAssembler collects the RTP packets of a TrackRemote into whole encoded frames.
+///
+/// Audio packets carry one frame each. Video packets sharing a RTP timestamp are buffered
+/// until all packets up to the one with the marker bit arrived; frames with missing packets
+/// are dropped once packets of the next frame arrive.
+#[derive(Default, Debug)]
+pub(crate) struct FrameAssembler {
+    packets: Vec<rtp::packet::Packet>,
+}
+
+impl FrameAssembler {
+    pub(crate) fn push(
+        &mut self,
+        pkt: rtp::packet::Packet,
+        kind: RTPCodecType,
+        mime_type: &str,
+    ) -> Result<Option<RTCEncodedFrame>> {
+        if pkt.payload.is_empty() {
+            return Ok(None);
+        }
+
+        if kind == RTPCodecType::Audio {
+            return Self::depacketize(&[pkt], kind, mime_type);
+        }
+
+        if self
+            .packets
+            .first()
+            .map(|p| p.header.timestamp != pkt.header.timestamp)
+            .unwrap_or(false)
+        {
+            self.packets.clear();
+        }
+
+        self.packets.push(pkt);
+        if !self.packets.iter().any(|p| p.header.marker) {
+            return Ok(None);
+        }
+
+        let base = self.packets[0].header.sequence_number.wrapping_sub(0x8000);
+        self.packets
+            .sort_by_key(|p| p.header.sequence_number.wrapping_sub(base));
+
+        // Packets may still arrive out of order, so keep them until the timestamp changes
+        let complete = is_frame_head(mime_type, &self.packets[0].payload)
+            && self.packets.windows(2).all(|w| {
+                w[1].header.sequence_number == w[0].header.sequence_number.wrapping_add(1)
+            })
+            && self.packets.last().map(|p| p.header.marker) == Some(true);
+        if !complete {
+            return Ok(None);
+        }
+
+        let packets = std::mem::take(&mut self.packets);
+        Self::depacketize(&packets, kind, mime_type)
+    }
+
+    fn depacketize(
+        packets: &[rtp::packet::Packet],
+        kind: RTPCodecType,
+        mime_type: &str,
+    ) -> Result<Option<RTCEncodedFrame>> {
+        match depacketize(packets, kind, mime_type) {
+            Ok(frame) => Ok(Some(frame)),
+            // Malformed payloads are dropped like lost packets
+            Err(Error::Rtp(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// is_frame_head checks if a video payload starts a new frame, so that frames whose first
+/// packets were lost are not delivered.
+fn is_frame_head(mime_type: &str, payload: &Bytes) -> bool {
+    match mime_type.to_lowercase().as_str() {
+        // S bit set and partition index 0
+        "video/vp8" => payload[0] & 0x1F == 0x10,
+        // B bit
+        "video/vp9" => payload[0] & 0x08 != 0,
+        // Anything but a FU-A without the start bit
+        "video/h264" => payload[0] & 0x1F != 28 || payload.get(1).is_some_and(|b| b & 0x80 != 0),
+        _ => true,
+    }
+}
+
+fn depacketize(
+    packets: &[rtp::packet::Packet],
+    kind: RTPCodecType,
+    mime_type: &str,
+) -> Result<RTCEncodedFrame> {
+    let first = &packets[0];
+    let mut metadata = RTCEncodedFrameMetadata {
+        kind,
+        mime_type: mime_type.to_owned(),
+        ssrc: first.header.ssrc,
+        csrc: first.header.csrc.clone(),
+        payload_type: first.header.payload_type,
+        timestamp: first.header.timestamp,
+        sequence_number: Some(first.header.sequence_number),
+        ..Default::default()
+    };
+
+    let mut data = BytesMut::new();
+    match mime_type.to_lowercase().as_str() {
+        "video/vp8" => {
+            for (i, pkt) in packets.iter().enumerate() {
+                let mut vp8 = Vp8Packet::default();
+                data.extend_from_slice(&vp8.depacketize(&pkt.payload)?);
+                if i == 0 {
+                    if vp8.i == 1 {
+                        metadata.frame_id = Some(vp8.picture_id as u64);
+                    }
+                    if vp8.t == 1 {
+                        metadata.temporal_index = Some(vp8.tid);
+                    }
+                }
+            }
+        }
+        "video/vp9" => {
+            for (i, pkt) in packets.iter().enumerate() {
+                let mut vp9 = Vp9Packet::default();
+                data.extend_from_slice(&vp9.depacketize(&pkt.payload)?);
+                if i == 0 && vp9.i {
+                    metadata.frame_id = Some(vp9.picture_id as u64);
+                    if vp9.f {
+                        metadata.dependencies = vp9
+                            .pdiff
+                            .iter()
+                            .map(|&pdiff| {
+                                (vp9.picture_id.wrapping_sub(pdiff as u16) & 0x7FFF) as u64
+                            })
+                            .collect();
+                    }
+                }
+                if vp9.l {
+                    metadata.temporal_index = Some(vp9.tid);
+                    metadata.spatial_index = Some(vp9.sid);
+                }
+            }
+        }
+        "video/h264" => {
+            let mut h264 = H264Packet::default();
+            for pkt in packets {
+                data.extend_from_slice(&h264.depacketize(&pkt.payload)?);
+            }
+        }
+        "audio/opus" => {
+            let mut opus = OpusPacket;
+            for pkt in packets {
+                data.extend_from_slice(&opus.depacketize(&pkt.payload)?);
+            }
+        }
+        _ if kind == RTPCodecType::Audio => {
+            for pkt in packets {
+                data.extend_from_slice(&pkt.payload);
+            }
+        }
+        _ => return Err(Error::ErrRTPTransformCodecUnsupported),
+    }
+
+    if kind == RTPCodecType::Video {
+        metadata.is_keyframe = is_keyframe(mime_type, &data);
+    }
+
+    Ok(RTCEncodedFrame {
+        data: data.freeze(),
+        metadata,
+    })
+}
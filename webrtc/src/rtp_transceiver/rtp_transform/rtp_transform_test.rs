@@ -0,0 +1,396 @@
+use portable_atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use tokio::sync::mpsc;
+use tokio::time::Duration;
+
+use super::*;
+use crate::api::media_engine::{MediaEngine, MIME_TYPE_AV1, MIME_TYPE_OPUS, MIME_TYPE_VP8};
+use crate::api::APIBuilder;
+use crate::peer_connection::peer_connection_test::{
+    close_pair_now, new_pair, send_video_until_done, signal_pair,
+};
+use crate::rtp_transceiver::rtp_codec::RTCRtpCodecCapability;
+use crate::track::track_local::track_local_static_sample::TrackLocalStaticSample;
+use crate::track::track_local::TrackLocal;
+
+fn packet(
+    sequence_number: u16,
+    timestamp: u32,
+    marker: bool,
+    payload: &[u8],
+) -> rtp::packet::Packet {
+    rtp::packet::Packet {
+        header: rtp::header::Header {
+            version: 2,
+            marker,
+            payload_type: 96,
+            sequence_number,
+            timestamp,
+            ssrc: 1234,
+            ..Default::default()
+        },
+        payload: Bytes::copy_from_slice(payload),
+    }
+}
+
+#[test]
+fn test_is_keyframe() {
+    let tests: Vec<(&str, &[u8], bool)> = vec![
+        ("video/VP8", &[0x10, 0x02], true),
+        ("video/VP8", &[0x11, 0x02], false),
+        ("video/VP9", &[0x80], true),
+        ("video/VP9", &[0x84], false),
+        // Profile 3 has an extra reserved bit
+        ("video/VP9", &[0xB0], true),
+        ("video/VP9", &[0xB2], false),
+        (
+            "video/H264",
+            &[0, 0, 0, 1, 0x67, 0x42, 0, 0, 1, 0x65, 0x88],
+            true,
+        ),
+        ("video/H264", &[0, 0, 0, 1, 0x41, 0x9A], false),
+        ("video/H265", &[0, 0, 1, 0x26, 0x01, 0xAF], true),
+        ("video/H265", &[0, 0, 1, 0x02, 0x01, 0xD0], false),
+        ("video/AV1", &[0x12, 0x00, 0x0A, 0x01, 0x00], true),
+        ("video/AV1", &[0x12, 0x00, 0x32, 0x01, 0x00], false),
+        ("audio/opus", &[0xFC], false),
+    ];
+
+    for (mime_type, data, expected) in tests {
+        assert_eq!(
+            is_keyframe(mime_type, data),
+            expected,
+            "{mime_type} {data:?}"
+        );
+    }
+}
+
+#[test]
+fn test_frame_assembler_vp8() -> Result<()> {
+    let mut assembler = FrameAssembler::default();
+    let kind = RTPCodecType::Video;
+
+    // In order, with a 7 bit picture id
+    assert!(assembler
+        .push(
+            packet(10, 100, false, &[0x90, 0x80, 0x05, 0x10, 0x01]),
+            kind,
+            MIME_TYPE_VP8
+        )?
+        .is_none());
+    let frame = assembler
+        .push(
+            packet(11, 100, true, &[0x80, 0x80, 0x05, 0x02, 0x03]),
+            kind,
+            MIME_TYPE_VP8,
+        )?
+        .expect("frame");
+    assert_eq!(frame.data, Bytes::from_static(&[0x10, 0x01, 0x02, 0x03]));
+    assert_eq!(frame.metadata.ssrc, 1234);
+    assert_eq!(frame.metadata.payload_type, 96);
+    assert_eq!(frame.metadata.timestamp, 100);
+    assert_eq!(frame.metadata.sequence_number, Some(10));
+    assert_eq!(frame.metadata.frame_id, Some(5));
+    assert!(frame.metadata.is_keyframe);
+
+    // Out of order, across the sequence number wrap
+    assert!(assembler
+        .push(
+            packet(0, 200, true, &[0x00, 0x03, 0x04, 0x05]),
+            kind,
+            MIME_TYPE_VP8
+        )?
+        .is_none());
+    let frame = assembler
+        .push(
+            packet(65535, 200, false, &[0x10, 0x01, 0x02, 0x03]),
+            kind,
+            MIME_TYPE_VP8,
+        )?
+        .expect("frame");
+    assert_eq!(
+        frame.data,
+        Bytes::from_static(&[0x01, 0x02, 0x03, 0x03, 0x04, 0x05])
+    );
+    assert_eq!(frame.metadata.sequence_number, Some(65535));
+    assert!(!frame.metadata.is_keyframe);
+
+    // Missing middle packet
+    assert!(assembler
+        .push(
+            packet(1, 300, false, &[0x10, 0x01, 0x02, 0x03]),
+            kind,
+            MIME_TYPE_VP8
+        )?
+        .is_none());
+    assert!(assembler
+        .push(
+            packet(3, 300, true, &[0x00, 0x01, 0x02, 0x03]),
+            kind,
+            MIME_TYPE_VP8
+        )?
+        .is_none());
+
+    // Missing first packet
+    assert!(assembler
+        .push(
+            packet(5, 400, true, &[0x00, 0x01, 0x02, 0x03]),
+            kind,
+            MIME_TYPE_VP8
+        )?
+        .is_none());
+
+    // The next frame is delivered after the incomplete ones
+    assert!(assembler
+        .push(
+            packet(6, 500, true, &[0x10, 0x01, 0x02, 0x03]),
+            kind,
+            MIME_TYPE_VP8
+        )?
+        .is_some());
+
+    Ok(())
+}
+
+#[test]
+fn test_frame_assembler_vp9_h264() -> Result<()> {
+    let mut assembler = FrameAssembler::default();
+    let kind = RTPCodecType::Video;
+
+    // Flexible mode with picture id 10 referencing picture 9
+    let frame = assembler
+        .push(
+            packet(1, 100, true, &[0xDC, 0x0A, 0x02, 0x84, 0x01]),
+            kind,
+            "video/VP9",
+        )?
+        .expect("frame");
+    assert_eq!(frame.data, Bytes::from_static(&[0x84, 0x01]));
+    assert_eq!(frame.metadata.frame_id, Some(10));
+    assert_eq!(frame.metadata.dependencies, vec![9]);
+    assert!(!frame.metadata.is_keyframe);
+
+    let frame = assembler
+        .push(
+            packet(2, 200, true, &[0x65, 0xAA, 0xBB]),
+            kind,
+            "video/H264",
+        )?
+        .expect("frame");
+    assert_eq!(
+        frame.data,
+        Bytes::from_static(&[0, 0, 0, 1, 0x65, 0xAA, 0xBB])
+    );
+    assert!(frame.metadata.is_keyframe);
+
+    // FU-A without its start fragment
+    assert!(assembler
+        .push(
+            packet(4, 300, true, &[0x7C, 0x45, 0xAA]),
+            kind,
+            "video/H264"
+        )?
+        .is_none());
+
+    assert!(matches!(
+        assembler.push(packet(5, 400, true, &[0x10, 0x00]), kind, MIME_TYPE_AV1),
+        Err(Error::ErrRTPTransformCodecUnsupported)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_frame_assembler_audio() -> Result<()> {
+    let mut assembler = FrameAssembler::default();
+    let kind = RTPCodecType::Audio;
+
+    for (sequence_number, payload) in [(1, [0xFC, 0x01]), (2, [0xFC, 0x02])] {
+        let frame = assembler
+            .push(
+                packet(sequence_number, 960, false, &payload),
+                kind,
+                MIME_TYPE_OPUS,
+            )?
+            .expect("frame");
+        assert_eq!(frame.data, Bytes::copy_from_slice(&payload));
+        assert_eq!(frame.metadata.kind, RTPCodecType::Audio);
+        assert!(!frame.metadata.is_keyframe);
+    }
+
+    let frame = assembler
+        .push(packet(3, 160, false, &[0xFF; 4]), kind, "audio/PCMU")?
+        .expect("frame");
+    assert_eq!(frame.data.len(), 4);
+
+    // Padding only
+    assert!(assembler
+        .push(packet(4, 160, false, &[]), kind, MIME_TYPE_OPUS)?
+        .is_none());
+
+    Ok(())
+}
+
+struct XorTransform {
+    key: u8,
+    frames: AtomicU64,
+    metadata: SyncMutex<Option<RTCEncodedFrameMetadata>>,
+}
+
+impl XorTransform {
+    fn new(key: u8) -> Self {
+        XorTransform {
+            key,
+            frames: AtomicU64::new(0),
+            metadata: SyncMutex::new(None),
+        }
+    }
+}
+
+#[async_trait]
+impl RTCRtpTransform for XorTransform {
+    async fn transform(&self, mut frame: RTCEncodedFrame) -> Result<Option<RTCEncodedFrame>> {
+        self.frames.fetch_add(1, Ordering::SeqCst);
+        *self.metadata.lock() = Some(frame.metadata.clone());
+        frame.data = frame.data.iter().map(|b| b ^ self.key).collect();
+        Ok(Some(frame))
+    }
+}
+
+#[tokio::test]
+async fn test_rtp_transform_sender_receiver() -> Result<()> {
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    let api = APIBuilder::new().with_media_engine(m).build();
+
+    let (mut pc_offer, mut pc_answer) = new_pair(&api).await?;
+
+    let track = Arc::new(TrackLocalStaticSample::new(
+        RTCRtpCodecCapability {
+            mime_type: MIME_TYPE_VP8.to_owned(),
+            ..Default::default()
+        },
+        "video".to_owned(),
+        "webrtc-rs".to_owned(),
+    ));
+    let sender = pc_offer
+        .add_track(Arc::clone(&track) as Arc<dyn TrackLocal + Send + Sync>)
+        .await?;
+    let send_transform = Arc::new(XorTransform::new(0x55));
+    sender.set_transform(Some(Arc::clone(&send_transform) as Arc<dyn RTCRtpTransform>));
+    assert!(sender.transform().is_some());
+
+    let receive_transform = Arc::new(XorTransform::new(0x55));
+    let (seen_frame_tx, seen_frame_rx) = mpsc::channel::<()>(1);
+    let seen_frame_tx = Arc::new(seen_frame_tx);
+    let on_track_transform = Arc::clone(&receive_transform);
+    pc_answer.on_track(Box::new(move |track, receiver, _| {
+        receiver.set_transform(Some(
+            Arc::clone(&on_track_transform) as Arc<dyn RTCRtpTransform>
+        ));
+        let seen_frame_tx = Arc::clone(&seen_frame_tx);
+        Box::pin(async move {
+            // The payload on the wire is transformed
+            let (pkt, _) = track.read_rtp().await.unwrap();
+            assert_eq!(pkt.payload.last(), Some(&(0xCC ^ 0x55)));
+
+            let frame = track.read_frame().await.unwrap();
+            assert_eq!(frame.data, Bytes::from_static(&[0xAA, 0xBB, 0xCC]));
+            assert_eq!(frame.metadata.ssrc, track.ssrc());
+            let _ = seen_frame_tx.send(()).await;
+        })
+    }));
+
+    signal_pair(&mut pc_offer, &mut pc_answer).await?;
+
+    send_video_until_done(
+        seen_frame_rx,
+        vec![track],
+        Bytes::from_static(&[0xAA, 0xBB, 0xCC]),
+        None,
+    )
+    .await;
+
+    assert!(send_transform.frames.load(Ordering::SeqCst) > 0);
+    let metadata = send_transform.metadata.lock().clone().unwrap();
+    assert_eq!(metadata.kind, RTPCodecType::Video);
+    assert_eq!(metadata.mime_type, MIME_TYPE_VP8);
+    assert_ne!(metadata.ssrc, 0);
+    assert!(receive_transform.frames.load(Ordering::SeqCst) > 0);
+
+    close_pair_now(&pc_offer, &pc_answer).await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rtp_transform_mixed_bindings_sequence_numbers() -> Result<()> {
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    let api = APIBuilder::new().with_media_engine(m).build();
+
+    let track = Arc::new(TrackLocalStaticSample::new(
+        RTCRtpCodecCapability {
+            mime_type: MIME_TYPE_VP8.to_owned(),
+            ..Default::default()
+        },
+        "video".to_owned(),
+        "webrtc-rs".to_owned(),
+    ));
+
+    // The same track is sent with a transform to one peer and without to the other
+    let (seq_tx, mut seq_rx) = mpsc::channel::<(usize, u16)>(64);
+    let mut pairs = vec![];
+    for i in 0..2 {
+        let (mut pc_offer, mut pc_answer) = new_pair(&api).await?;
+        let sender = pc_offer
+            .add_track(Arc::clone(&track) as Arc<dyn TrackLocal + Send + Sync>)
+            .await?;
+        if i == 0 {
+            sender.set_transform(Some(Arc::new(XorTransform::new(0x55))));
+        }
+
+        let seq_tx = seq_tx.clone();
+        pc_answer.on_track(Box::new(move |track, _, _| {
+            let seq_tx = seq_tx.clone();
+            Box::pin(async move {
+                while let Ok((pkt, _)) = track.read_rtp().await {
+                    if seq_tx.send((i, pkt.header.sequence_number)).await.is_err() {
+                        break;
+                    }
+                }
+            })
+        }));
+
+        signal_pair(&mut pc_offer, &mut pc_answer).await?;
+        pairs.push((pc_offer, pc_answer));
+    }
+
+    let mut received: [Vec<u16>; 2] = [vec![], vec![]];
+    while received.iter().any(|seqs| seqs.len() < 5) {
+        track
+            .write_sample(&media::Sample {
+                data: Bytes::from_static(&[0xAA, 0xBB, 0xCC]),
+                duration: Duration::from_millis(20),
+                ..Default::default()
+            })
+            .await?;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        while let Ok((i, seq)) = seq_rx.try_recv() {
+            received[i].push(seq);
+        }
+    }
+
+    // Each peer receives consecutive sequence numbers, whatever the other one is sent
+    for seqs in received {
+        for w in seqs.windows(2) {
+            assert_eq!(w[1], w[0].wrapping_add(1), "{seqs:?}");
+        }
+    }
+
+    for (pc_offer, pc_answer) in pairs {
+        close_pair_now(&pc_offer, &pc_answer).await;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod sframe_transform_test;
+
+use async_trait::async_trait;
+use sframe::cipher_suite::CipherSuite;
+use sframe::context::Context;
+use util::sync::Mutex as SyncMutex;
+
+use super::{RTCEncodedFrame, RTCRtpTransform};
+use crate::error::{Error, Result};
+
+/// SFrameTransformRole selects whether a [`SFrameTransform`] encrypts or decrypts frames.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SFrameTransformRole {
+    #[default]
+    Encrypt,
+    Decrypt,
+}
+
+/// SFrameTransform is a [`RTCRtpTransform`] that encrypts frames end-to-end with SFrame
+/// ([RFC 9605]). Install it with `set_transform` on an RTPSender to encrypt, and on the
+/// RTPReceiver of the remote participant to decrypt.
+///
+/// Frames are encrypted before packetization, so the RTP headers and payload descriptors stay
+/// in the clear. A media server forwarding the packets with `TrackRemote::read_rtp` and
+/// `TrackLocalStaticRTP` does not need any key; it can still read the KID and counter of a
+/// frame from the payload of its first packet with [`sframe::header::Header`].
+///
+/// When decrypting, frames that fail to decrypt (unknown KID, invalid authentication tag) are
+/// dropped.
+///
+/// Only codecs whose packetizer treats the frame as opaque can be encrypted: VP8, VP9 and
+/// audio codecs. Encrypting H.264, H.265 or AV1 frames fails with
+/// ErrRTPTransformCodecUnsupported.
+///
+/// ## Specifications
+///
+/// * [W3C]
+///
+/// [RFC 9605]: https://www.rfc-editor.org/rfc/rfc9605
+/// [W3C]: https://w3c.github.io/webrtc-encoded-transform/#sframe
+pub struct SFrameTransform {
+    role: SFrameTransformRole,
+    context: SyncMutex<Context>,
+}
+
+impl SFrameTransform {
+    pub fn new(role: SFrameTransformRole, cipher_suite: CipherSuite) -> Self {
+        SFrameTransform {
+            role,
+            context: SyncMutex::new(Context::new(cipher_suite)),
+        }
+    }
+
+    /// role returns whether the transform encrypts or decrypts frames
+    pub fn role(&self) -> SFrameTransformRole {
+        self.role
+    }
+
+    /// cipher_suite returns the cipher suite used to protect frames
+    pub fn cipher_suite(&self) -> CipherSuite {
+        self.context.lock().cipher_suite()
+    }
+
+    /// set_key adds the base key for a KID. When encrypting, frames are encrypted with the
+    /// last key set. When decrypting, previous keys stay usable until they are removed, so
+    /// that frames in flight during a key rotation can still be decrypted.
+    pub fn set_key(&self, kid: u64, key: &[u8]) -> Result<()> {
+        let mut context = self.context.lock();
+        context.add_key(kid, key)?;
+        if self.role == SFrameTransformRole::Encrypt {
+            context.set_encryption_kid(kid)?;
+        }
+        Ok(())
+    }
+
+    /// remove_key removes the key of a KID. Returns false if there was no such key.
+    pub fn remove_key(&self, kid: u64) -> bool {
+        self.context.lock().remove_key(kid)
+    }
+}
+
+#[async_trait]
+impl RTCRtpTransform for SFrameTransform {
+    async fn transform(&self, mut frame: RTCEncodedFrame) -> Result<Option<RTCEncodedFrame>> {
+        frame.data = match self.role {
+            SFrameTransformRole::Encrypt => {
+                // These packetizers parse the frame, which fails on ciphertext
+                if matches!(
+                    frame.metadata.mime_type.to_lowercase().as_str(),
+                    "video/h264" | "video/h265" | "video/hevc" | "video/av1"
+                ) {
+                    return Err(Error::ErrRTPTransformCodecUnsupported);
+                }
+
+                self.context.lock().encrypt(&[], &frame.data)?
+            }
+            SFrameTransformRole::Decrypt => {
+                // Frames that can't be decrypted, e.g. before the key of their KID is set,
+                // are dropped like lost ones rather than ending the track.
+                match self.context.lock().decrypt(&[], &frame.data) {
+                    Ok(data) => data,
+                    Err(err) => {
+                        log::debug!("dropping frame that failed to decrypt: {err}");
+                        return Ok(None);
+                    }
+                }
+            }
+        };
+
+        Ok(Some(frame))
+    }
+}
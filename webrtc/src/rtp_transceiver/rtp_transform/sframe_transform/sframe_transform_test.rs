@@ -0,0 +1,188 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use rtp::codecs::vp8::Vp8Packet;
+use rtp::packetizer::Depacketizer;
+use sframe::header::Header;
+use tokio::sync::mpsc;
+use util::Unmarshal;
+
+use super::*;
+use crate::api::media_engine::{MediaEngine, MIME_TYPE_H264, MIME_TYPE_OPUS, MIME_TYPE_VP8};
+use crate::api::APIBuilder;
+use crate::peer_connection::peer_connection_test::{
+    close_pair_now, new_pair, send_video_until_done, signal_pair,
+};
+use crate::rtp_transceiver::rtp_codec::{RTCRtpCodecCapability, RTPCodecType};
+use crate::rtp_transceiver::rtp_transform::RTCEncodedFrameMetadata;
+use crate::track::track_local::track_local_static_sample::TrackLocalStaticSample;
+use crate::track::track_local::TrackLocal;
+
+const CIPHER_SUITE: CipherSuite = CipherSuite::AesGcm128Sha256;
+
+fn frame(mime_type: &str, data: &'static [u8]) -> RTCEncodedFrame {
+    RTCEncodedFrame {
+        data: Bytes::from_static(data),
+        metadata: RTCEncodedFrameMetadata {
+            kind: if mime_type.starts_with("audio/") {
+                RTPCodecType::Audio
+            } else {
+                RTPCodecType::Video
+            },
+            mime_type: mime_type.to_owned(),
+            ..Default::default()
+        },
+    }
+}
+
+#[tokio::test]
+async fn test_sframe_transform_round_trip() -> Result<()> {
+    let encrypt = SFrameTransform::new(SFrameTransformRole::Encrypt, CIPHER_SUITE);
+    let decrypt = SFrameTransform::new(SFrameTransformRole::Decrypt, CIPHER_SUITE);
+    assert_eq!(encrypt.role(), SFrameTransformRole::Encrypt);
+    assert_eq!(decrypt.cipher_suite(), CIPHER_SUITE);
+
+    // No key yet
+    assert!(matches!(
+        encrypt.transform(frame(MIME_TYPE_OPUS, &[0xFC])).await,
+        Err(Error::SFrame(sframe::Error::ErrNoEncryptionKey))
+    ));
+
+    encrypt.set_key(1, b"first key")?;
+    decrypt.set_key(1, b"first key")?;
+    decrypt.set_key(2, b"second key")?;
+
+    for mime_type in [MIME_TYPE_VP8, MIME_TYPE_OPUS] {
+        let encrypted = encrypt
+            .transform(frame(mime_type, &[1, 2, 3]))
+            .await?
+            .expect("frame");
+        assert_eq!(Header::unmarshal(&mut encrypted.data.clone())?.kid, 1);
+
+        let decrypted = decrypt.transform(encrypted).await?.expect("frame");
+        assert_eq!(decrypted.data, Bytes::from_static(&[1, 2, 3]));
+    }
+
+    // Frames encrypted with the old key are still decrypted after a rotation
+    let old = encrypt
+        .transform(frame(MIME_TYPE_VP8, &[4]))
+        .await?
+        .expect("frame");
+    encrypt.set_key(2, b"second key")?;
+    let new = encrypt
+        .transform(frame(MIME_TYPE_VP8, &[5]))
+        .await?
+        .expect("frame");
+    assert_eq!(Header::unmarshal(&mut new.data.clone())?.kid, 2);
+    assert_eq!(
+        decrypt.transform(old.clone()).await?.expect("frame").data[..],
+        [4]
+    );
+    assert_eq!(decrypt.transform(new).await?.expect("frame").data[..], [5]);
+
+    assert!(decrypt.remove_key(1));
+    assert!(!decrypt.remove_key(1));
+    // Frames with an unknown KID or an invalid tag are dropped
+    assert_eq!(decrypt.transform(old.clone()).await?, None);
+    let mut corrupted = old.data.to_vec();
+    *corrupted.last_mut().unwrap() ^= 1;
+    decrypt.set_key(1, b"first key")?;
+    assert_eq!(
+        decrypt
+            .transform(RTCEncodedFrame {
+                data: corrupted.into(),
+                ..old
+            })
+            .await?,
+        None
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sframe_transform_codec_unsupported() -> Result<()> {
+    let encrypt = SFrameTransform::new(SFrameTransformRole::Encrypt, CIPHER_SUITE);
+    encrypt.set_key(1, b"key")?;
+
+    for mime_type in [MIME_TYPE_H264, "video/H265", "video/AV1"] {
+        assert!(
+            matches!(
+                encrypt
+                    .transform(frame(mime_type, &[0, 0, 0, 1, 0x65]))
+                    .await,
+                Err(Error::ErrRTPTransformCodecUnsupported)
+            ),
+            "{mime_type}"
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sframe_transform_sender_receiver() -> Result<()> {
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    let api = APIBuilder::new().with_media_engine(m).build();
+
+    let (mut pc_offer, mut pc_answer) = new_pair(&api).await?;
+
+    let track = Arc::new(TrackLocalStaticSample::new(
+        RTCRtpCodecCapability {
+            mime_type: MIME_TYPE_VP8.to_owned(),
+            ..Default::default()
+        },
+        "video".to_owned(),
+        "webrtc-rs".to_owned(),
+    ));
+    let sender = pc_offer
+        .add_track(Arc::clone(&track) as Arc<dyn TrackLocal + Send + Sync>)
+        .await?;
+    let encrypt = Arc::new(SFrameTransform::new(
+        SFrameTransformRole::Encrypt,
+        CIPHER_SUITE,
+    ));
+    encrypt.set_key(7, b"end-to-end key")?;
+    sender.set_transform(Some(encrypt));
+
+    let decrypt = Arc::new(SFrameTransform::new(
+        SFrameTransformRole::Decrypt,
+        CIPHER_SUITE,
+    ));
+    decrypt.set_key(7, b"end-to-end key")?;
+
+    let (seen_frame_tx, seen_frame_rx) = mpsc::channel::<()>(1);
+    let seen_frame_tx = Arc::new(seen_frame_tx);
+    pc_answer.on_track(Box::new(move |track, receiver, _| {
+        receiver.set_transform(Some(Arc::clone(&decrypt) as Arc<dyn RTCRtpTransform>));
+        let seen_frame_tx = Arc::clone(&seen_frame_tx);
+        Box::pin(async move {
+            // Without any key, the SFrame header follows the VP8 payload descriptor
+            let (pkt, _) = track.read_rtp().await.unwrap();
+            let mut encrypted = Vp8Packet::default().depacketize(&pkt.payload).unwrap();
+            let header = Header::unmarshal(&mut encrypted).unwrap();
+            assert_eq!(header.kid, 7);
+
+            let frame = track.read_frame().await.unwrap();
+            assert_eq!(frame.data, Bytes::from_static(&[0xAA, 0xBB, 0xCC]));
+            // Detected from the decrypted frame, not from the ciphertext
+            assert!(frame.metadata.is_keyframe);
+            let _ = seen_frame_tx.send(()).await;
+        })
+    }));
+
+    signal_pair(&mut pc_offer, &mut pc_answer).await?;
+
+    send_video_until_done(
+        seen_frame_rx,
+        vec![track],
+        Bytes::from_static(&[0xAA, 0xBB, 0xCC]),
+        None,
+    )
+    .await;
+
+    close_pair_now(&pc_offer, &pc_answer).await;
+
+    Ok(())
+}
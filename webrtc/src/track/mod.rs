@@ -5,8 +5,11 @@ use std::sync::Arc;
 
 use interceptor::stream_info::StreamInfo;
 use interceptor::{RTCPReader, RTPReader};
+use tokio::sync::{mpsc, Mutex};
 use track_remote::*;
 
+use crate::rtp_transceiver::rtp_receiver::RTCPRead;
+
 pub(crate) const RTP_OUTBOUND_MTU: usize = 1200;
 pub(crate) const RTP_PAYLOAD_TYPE_BITMASK: u8 = 0x7F;
 
@@ -17,6 +20,8 @@ pub(crate) struct TrackStream {
     pub(crate) rtp_interceptor: Option<Arc<dyn RTPReader + Send + Sync>>,
     pub(crate) rtcp_read_stream: Option<Arc<srtp::stream::Stream>>,
     pub(crate) rtcp_interceptor: Option<Arc<dyn RTCPReader + Send + Sync>>,
+    /// RTCP read from rtcp_interceptor in the background, waiting to be read by the application
+    pub(crate) rtcp_reads: Option<Arc<Mutex<mpsc::Receiver<RTCPRead>>>>,
 }
 
 /// TrackStreams maintains a mapping of RTP/RTCP streams to a specific track
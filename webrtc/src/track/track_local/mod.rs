@@ -18,6 +18,7 @@ use util::Unmarshal;
 
 use crate::error::{Error, Result};
 use crate::rtp_transceiver::rtp_codec::*;
+use crate::rtp_transceiver::rtp_transform::{RTCRtpTransform, TransformSlot};
 use crate::rtp_transceiver::*;
 
 /// TrackLocalWriter is the Writer for outbound RTP Packets
@@ -55,6 +56,7 @@ pub struct TrackLocalContext {
     pub(crate) write_stream: Arc<dyn TrackLocalWriter + Send + Sync>,
     pub(crate) paused: Arc<AtomicBool>,
     pub(crate) mid: Option<SmolStr>,
+    pub(crate) transform: TransformSlot,
 }
 
 impl TrackLocalContext {
@@ -96,6 +98,12 @@ impl TrackLocalContext {
     pub fn paused(&self) -> Arc<AtomicBool> {
         self.paused.clone()
     }
+
+    /// transform returns the transform installed on the RTPSender, which implementers
+    /// apply to each encoded frame before packetizing it
+    pub fn transform(&self) -> Option<Arc<dyn RTCRtpTransform>> {
+        self.transform.get()
+    }
 }
 /// TrackLocal is an interface that controls how the user can send media
 /// The user can provide their own TrackLocal implementations, or use
@@ -140,6 +148,7 @@ pub(crate) struct TrackBinding {
     write_stream: Arc<dyn TrackLocalWriter + Send + Sync>,
     sender_paused: Arc<AtomicBool>,
     hdr_ext_ids: Vec<rtp::header::Extension>,
+    transform: TransformSlot,
 }
 
 impl TrackBinding {
@@ -7,6 +7,9 @@ use util::{Marshal, MarshalSize};
 use super::*;
 use crate::error::flatten_errs;
 
+/// TransformGroup is the set of bindings sharing the same frame transform
+pub(crate) type TransformGroup = (Option<Arc<dyn RTCRtpTransform>>, Vec<Arc<TrackBinding>>);
+
 /// TrackLocalStaticRTP  is a TrackLocal that has a pre-set codec and accepts RTP Packets.
 /// If you wish to send a media.Sample use TrackLocalStaticSample
 #[derive(Debug)]
@@ -93,14 +96,49 @@ impl TrackLocalStaticRTP {
         extensions: &[rtp::extension::HeaderExtension],
         attr: &Attributes,
     ) -> Result<usize> {
-        let mut n = 0;
-        let mut write_errs = vec![];
-        let mut pkt = p.clone();
-
         let bindings = {
             let bindings = self.bindings.lock().await;
             bindings.clone()
         };
+
+        self.write_rtp_to_bindings(p, extensions, attr, bindings)
+            .await
+    }
+
+    /// bindings_by_transform groups the bindings that are not paused by the transform
+    /// installed on their RTPSender.
+    pub(crate) async fn bindings_by_transform(&self) -> Vec<TransformGroup> {
+        let bindings = self.bindings.lock().await;
+
+        let mut groups: Vec<TransformGroup> = vec![];
+        for b in bindings.iter().filter(|b| !b.is_sender_paused()) {
+            let transform = b.transform.get();
+            let group = groups.iter_mut().find(|(t, _)| match (t, &transform) {
+                (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            });
+            match group {
+                Some((_, group)) => group.push(Arc::clone(b)),
+                None => groups.push((transform, vec![Arc::clone(b)])),
+            }
+        }
+
+        groups
+    }
+
+    /// write_rtp_to_bindings writes a RTP Packet to the given bindings of this track
+    pub(crate) async fn write_rtp_to_bindings(
+        &self,
+        p: &rtp::packet::Packet,
+        extensions: &[rtp::extension::HeaderExtension],
+        attr: &Attributes,
+        bindings: Vec<Arc<TrackBinding>>,
+    ) -> Result<usize> {
+        let mut n = 0;
+        let mut write_errs = vec![];
+        let mut pkt = p.clone();
+
         // Prepare the extensions data
         let extension_data: HashMap<_, _> = extensions
             .iter()
@@ -214,6 +252,7 @@ impl TrackLocal for TrackLocalStaticRTP {
                     write_stream: t.write_stream(),
                     sender_paused: t.paused.clone(),
                     hdr_ext_ids,
+                    transform: t.transform.clone(),
                 }));
             }
 
@@ -1,18 +1,62 @@
+use std::sync::Weak;
+
 use log::warn;
+use media::audio::dtx::{opus_dtx_frame, DtxAction, DtxConfig, DtxController};
+use media::audio::level::VoiceActivityDetector;
 use media::Sample;
+use rtp::extension::audio_level_extension::AudioLevelExtension;
+use rtp::extension::HeaderExtension;
+use rtp::sequence::Sequencer;
 use tokio::sync::Mutex;
 
 use super::track_local_static_rtp::TrackLocalStaticRTP;
 use super::*;
+use crate::api::media_engine::MIME_TYPE_OPUS;
 use crate::error::flatten_errs;
+use crate::rtp_transceiver::rtp_transform::{
+    is_keyframe, RTCEncodedFrame, RTCEncodedFrameMetadata, RTCRtpTransform,
+};
 use crate::track::RTP_OUTBOUND_MTU;
 
 #[derive(Debug, Clone)]
 struct TrackLocalStaticSampleInternal {
     packetizer: Option<Box<dyn rtp::packetizer::Packetizer + Send + Sync>>,
-    sequencer: Option<Box<dyn rtp::sequence::Sequencer + Send + Sync>>,
+    sequencer: Option<Box<dyn Sequencer + Send + Sync>>,
+    /// Sequencers of the bindings whose RTPSender has a transform, each transform sends its
+    /// own frames so its packets are numbered separately.
+    transform_sequencers: Vec<(Weak<dyn RTCRtpTransform>, Box<dyn Sequencer + Send + Sync>)>,
     clock_rate: f64,
     did_warn_about_wonky_pause: bool,
+    vad: VoiceActivityDetector,
+    dtx: Option<DtxController>,
+}
+
+impl TrackLocalStaticSampleInternal {
+    /// group_sequencer returns the sequencer numbering the packets of the bindings with the
+    /// given transform.
+    fn group_sequencer(
+        &mut self,
+        transform: Option<&Arc<dyn RTCRtpTransform>>,
+    ) -> Option<Box<dyn Sequencer + Send + Sync>> {
+        let Some(transform) = transform else {
+            return self.sequencer.clone();
+        };
+
+        self.transform_sequencers
+            .retain(|(t, _)| t.strong_count() > 0);
+        let sequencer = self
+            .transform_sequencers
+            .iter()
+            .find(|(t, _)| t.upgrade().is_some_and(|t| Arc::ptr_eq(&t, transform)))
+            .map(|(_, sequencer)| sequencer.clone());
+        Some(sequencer.unwrap_or_else(|| {
+            let sequencer: Box<dyn Sequencer + Send + Sync> =
+                Box::new(rtp::sequence::new_random_sequencer());
+            self.transform_sequencers
+                .push((Arc::downgrade(transform), sequencer.clone()));
+            sequencer
+        }))
+    }
 }
 
 /// TrackLocalStaticSample is a TrackLocal that has a pre-set codec and accepts Samples.
@@ -33,8 +77,11 @@ impl TrackLocalStaticSample {
             internal: Mutex::new(TrackLocalStaticSampleInternal {
                 packetizer: None,
                 sequencer: None,
+                transform_sequencers: vec![],
                 clock_rate: 0.0f64,
                 did_warn_about_wonky_pause: false,
+                vad: VoiceActivityDetector::default(),
+                dtx: None,
             }),
         }
     }
@@ -53,8 +100,11 @@ impl TrackLocalStaticSample {
             internal: Mutex::new(TrackLocalStaticSampleInternal {
                 packetizer: None,
                 sequencer: None,
+                transform_sequencers: vec![],
                 clock_rate: 0.0f64,
                 did_warn_about_wonky_pause: false,
+                vad: VoiceActivityDetector::default(),
+                dtx: None,
             }),
         }
     }
@@ -64,6 +114,25 @@ impl TrackLocalStaticSample {
         self.rtp_track.codec()
     }
 
+    /// set_voice_activity_detector sets the detector deciding whether the samples written with
+    /// their PCM audio contain voice, see [`SampleWriter::with_pcm`].
+    pub async fn set_voice_activity_detector(&self, vad: VoiceActivityDetector) {
+        let mut internal = self.internal.lock().await;
+        internal.vad = vad;
+    }
+
+    /// set_dtx enables discontinuous transmission for the samples written with their PCM audio,
+    /// see [`SampleWriter::with_pcm`], or disables it when `None`.
+    ///
+    /// During silence, samples are not sent apart from the first one and one every keep alive
+    /// interval. For Opus, the keep alive is the frame an Opus encoder sends in DTX mode. For
+    /// other codecs nothing is sent during silence; comfort noise can be sent separately with
+    /// [`media::audio::comfort_noise::ComfortNoise`] payloads.
+    pub async fn set_dtx(&self, config: Option<DtxConfig>) {
+        let mut internal = self.internal.lock().await;
+        internal.dtx = config.map(DtxController::new);
+    }
+
     /// write_sample writes a Sample to the TrackLocalStaticSample
     /// If one PeerConnection fails the packets will still be sent to
     /// all PeerConnections. The error message will contain the ID of the failed
@@ -125,38 +194,155 @@ impl TrackLocalStaticSample {
         }
 
         // skip packets by the number of previously dropped packets
-        if let Some(sequencer) = &internal.sequencer {
+        let sequencers = internal
+            .sequencer
+            .iter()
+            .chain(internal.transform_sequencers.iter().map(|(_, s)| s));
+        for sequencer in sequencers {
             for _ in 0..sample.prev_dropped_packets {
                 sequencer.next_sequence_number();
             }
         }
 
-        let clock_rate = internal.clock_rate;
+        // Each group of bindings sharing a transform packetizes its own frame, so the packets
+        // of each group are numbered by its own sequencer.
+        let groups = self.rtp_track.bindings_by_transform().await;
+        let group_sequencers: Vec<_> = groups
+            .iter()
+            .map(|(transform, _)| internal.group_sequencer(transform.as_ref()))
+            .collect();
 
-        let packets = if let Some(packetizer) = &mut internal.packetizer {
-            let samples = (sample.duration.as_secs_f64() * clock_rate) as u32;
-            if sample.prev_dropped_packets > 0 {
-                packetizer.skip_samples(samples * sample.prev_dropped_packets as u32);
-            }
-            packetizer.packetize(&sample.data, samples)?
-        } else {
-            vec![]
+        let clock_rate = internal.clock_rate;
+        let Some(packetizer) = &mut internal.packetizer else {
+            return Ok(());
         };
 
+        let samples = (sample.duration.as_secs_f64() * clock_rate) as u32;
+        if sample.prev_dropped_packets > 0 {
+            packetizer.skip_samples(samples * sample.prev_dropped_packets as u32);
+        }
+
+        let kind = self.kind();
+        let mime_type = self.rtp_track.codec().mime_type;
+        // Packetizer state before this sample, for groups other than the first to send it
+        let initial_packetizer = (groups.len() > 1).then(|| packetizer.clone());
+        let mut packetized = false;
+
         let mut write_errs = vec![];
-        for p in packets {
-            if let Err(err) = self
-                .rtp_track
-                .write_rtp_with_extensions(&p, extensions)
-                .await
-            {
-                write_errs.push(err);
+        for ((transform, bindings), sequencer) in groups.into_iter().zip(group_sequencers) {
+            let data = if let Some(transform) = transform {
+                let frame = RTCEncodedFrame {
+                    data: sample.data.clone(),
+                    metadata: RTCEncodedFrameMetadata {
+                        kind,
+                        mime_type: mime_type.clone(),
+                        ssrc: bindings[0].ssrc,
+                        payload_type: bindings[0].payload_type,
+                        timestamp: packetizer.timestamp(),
+                        is_keyframe: kind == RTPCodecType::Video
+                            && is_keyframe(&mime_type, &sample.data),
+                        ..Default::default()
+                    },
+                };
+                match transform.transform(frame).await {
+                    Ok(Some(frame)) => frame.data,
+                    Ok(None) => continue,
+                    Err(err) => {
+                        write_errs.push(err);
+                        continue;
+                    }
+                }
+            } else {
+                sample.data.clone()
+            };
+
+            let packets = match (&initial_packetizer, packetized) {
+                (Some(initial_packetizer), true) => {
+                    initial_packetizer.clone().packetize(&data, samples)?
+                }
+                _ => {
+                    packetized = true;
+                    packetizer.packetize(&data, samples)?
+                }
+            };
+
+            for mut p in packets {
+                if let Some(sequencer) = &sequencer {
+                    p.header.sequence_number = sequencer.next_sequence_number();
+                }
+                if let Err(err) = self
+                    .rtp_track
+                    .write_rtp_to_bindings(&p, extensions, &Attributes::new(), bindings.clone())
+                    .await
+                {
+                    write_errs.push(err);
+                }
             }
         }
 
+        if !packetized {
+            packetizer.skip_samples(samples);
+        }
+
         flatten_errs(write_errs)
     }
 
+    /// write_sample_with_audio_level writes a Sample of audio with the given level, adding the
+    /// audio level extension and applying discontinuous transmission.
+    pub(crate) async fn write_sample_with_audio_level(
+        &self,
+        sample: &Sample,
+        mut extensions: Vec<HeaderExtension>,
+        level: u8,
+    ) -> Result<()> {
+        let (voice, action) = {
+            let mut internal = self.internal.lock().await;
+            let voice = internal.vad.process(level, sample.duration);
+            let action = internal
+                .dtx
+                .as_mut()
+                .map(|dtx| dtx.process(voice, sample.duration))
+                .unwrap_or(DtxAction::Send);
+            (voice, action)
+        };
+
+        let audio_level = HeaderExtension::AudioLevel(AudioLevelExtension { level, voice });
+        extensions.retain(|e| !e.is_same(&audio_level));
+        extensions.push(audio_level);
+
+        let is_opus = self.codec().mime_type.eq_ignore_ascii_case(MIME_TYPE_OPUS);
+        match action {
+            DtxAction::Send => self.write_sample_with_extensions(sample, &extensions).await,
+            DtxAction::SendSilence if is_opus => match opus_dtx_frame(&sample.data) {
+                Some(data) => {
+                    let sample = Sample {
+                        data,
+                        timestamp: sample.timestamp,
+                        duration: sample.duration,
+                        packet_timestamp: sample.packet_timestamp,
+                        prev_dropped_packets: sample.prev_dropped_packets,
+                        prev_padding_packets: sample.prev_padding_packets,
+                    };
+                    self.write_sample_with_extensions(&sample, &extensions)
+                        .await
+                }
+                None => self.skip_sample(sample).await,
+            },
+            DtxAction::SendSilence | DtxAction::Skip => self.skip_sample(sample).await,
+        }
+    }
+
+    /// skip_sample advances the RTP timestamp by the duration of a sample without sending it
+    async fn skip_sample(&self, sample: &Sample) -> Result<()> {
+        let mut internal = self.internal.lock().await;
+        let clock_rate = internal.clock_rate;
+        if let Some(packetizer) = &mut internal.packetizer {
+            packetizer.skip_samples((sample.duration.as_secs_f64() * clock_rate) as u32);
+        }
+
+        Ok(())
+    }
+
     /// Create a builder for writing samples with additional data.
     ///
     /// # Example
@@ -212,17 +398,16 @@ impl TrackLocal for TrackLocalStaticSample {
         }
 
         let payloader = codec.capability.payloader_for_codec()?;
-        let sequencer: Box<dyn rtp::sequence::Sequencer + Send + Sync> =
-            Box::new(rtp::sequence::new_random_sequencer());
         internal.packetizer = Some(Box::new(rtp::packetizer::new_packetizer(
             RTP_OUTBOUND_MTU,
             0, // Value is handled when writing
             0, // Value is handled when writing
             payloader,
-            sequencer.clone(),
+            // Sequence numbers are handled when writing, by the sequencer of each transform
+            Box::new(rtp::sequence::new_random_sequencer()),
             codec.capability.clock_rate,
         )));
-        internal.sequencer = Some(sequencer);
+        internal.sequencer = Some(Box::new(rtp::sequence::new_random_sequencer()));
         internal.clock_rate = codec.capability.clock_rate as f64;
 
         Ok(codec)
@@ -262,9 +447,16 @@ impl TrackLocal for TrackLocalStaticSample {
 }
 
 mod sample_writer {
+    use media::audio::level::audio_level;
     use media::Sample;
+    use rtp::extension::abs_capture_time_extension::AbsCaptureTimeExtension;
     use rtp::extension::audio_level_extension::AudioLevelExtension;
+    use rtp::extension::color_space_extension::ColorSpaceExtension;
+    use rtp::extension::inband_cn_extension::InbandCnExtension;
+    use rtp::extension::video_content_type_extension::VideoContentTypeExtension;
+    use rtp::extension::video_layers_allocation_extension::VideoLayersAllocationExtension;
     use rtp::extension::video_orientation_extension::VideoOrientationExtension;
+    use rtp::extension::video_timing_extension::VideoTimingExtension;
     use rtp::extension::HeaderExtension;
 
     use super::TrackLocalStaticSample;
@@ -276,6 +468,8 @@ mod sample_writer {
     pub struct SampleWriter<'track> {
         track: &'track TrackLocalStaticSample,
         extensions: Vec<HeaderExtension>,
+        /// Audio level computed from the PCM of the sample
+        pcm_level: Option<u8>,
     }
 
     impl<'track> SampleWriter<'track> {
@@ -283,6 +477,7 @@ mod sample_writer {
             Self {
                 track,
                 extensions: vec![],
+                pcm_level: None,
             }
         }
 
@@ -293,6 +488,24 @@ mod sample_writer {
             self.with_extension(HeaderExtension::AudioLevel(ext))
         }
 
+        /// Add a RTP audio level extension to all packets written for the sample, measured
+        /// from the PCM audio the sample was encoded from. Voice activity is determined by
+        /// the voice activity detector of the track, and samples without voice can be skipped
+        /// with discontinuous transmission, see [`TrackLocalStaticSample::set_dtx`].
+        ///
+        /// This overwrites any previously configured audio level extension.
+        pub fn with_pcm<T>(mut self, pcm: &[T]) -> Self
+        where
+            T: Copy,
+            media::audio::Sample<T>: From<T>,
+            media::audio::Sample<f32>: From<media::audio::Sample<T>>,
+        {
+            self.extensions
+                .retain(|e| !matches!(e, HeaderExtension::AudioLevel(_)));
+            self.pcm_level = Some(audio_level(pcm));
+            self
+        }
+
         /// Add a RTP video orientation extension to all packets written for the sample.
         ///
         /// This overwrites any previously configured video orientation extension.
@@ -300,8 +513,53 @@ mod sample_writer {
             self.with_extension(HeaderExtension::VideoOrientation(ext))
         }
 
+        /// Add a RTP absolute capture time extension to all packets written for the sample.
+        ///
+        /// This overwrites any previously configured absolute capture time extension.
+        pub fn with_abs_capture_time(self, ext: AbsCaptureTimeExtension) -> Self {
+            self.with_extension(HeaderExtension::AbsCaptureTime(ext))
+        }
+
+        /// Add a RTP video layers allocation extension to all packets written for the sample.
+        ///
+        /// This overwrites any previously configured video layers allocation extension.
+        pub fn with_video_layers_allocation(self, ext: VideoLayersAllocationExtension) -> Self {
+            self.with_extension(HeaderExtension::VideoLayersAllocation(ext))
+        }
+
+        /// Add a RTP color space extension to all packets written for the sample.
+        ///
+        /// This overwrites any previously configured color space extension.
+        pub fn with_color_space(self, ext: ColorSpaceExtension) -> Self {
+            self.with_extension(HeaderExtension::ColorSpace(ext))
+        }
+
+        /// Add a RTP video content type extension to all packets written for the sample.
+        ///
+        /// This overwrites any previously configured video content type extension.
+        pub fn with_video_content_type(self, ext: VideoContentTypeExtension) -> Self {
+            self.with_extension(HeaderExtension::VideoContentType(ext))
+        }
+
+        /// Add a RTP video timing extension to all packets written for the sample.
+        ///
+        /// This overwrites any previously configured video timing extension.
+        pub fn with_video_timing(self, ext: VideoTimingExtension) -> Self {
+            self.with_extension(HeaderExtension::VideoTiming(ext))
+        }
+
+        /// Add a RTP inband comfort noise extension to all packets written for the sample.
+        ///
+        /// This overwrites any previously configured inband comfort noise extension.
+        pub fn with_inband_cn(self, ext: InbandCnExtension) -> Self {
+            self.with_extension(HeaderExtension::InbandCn(ext))
+        }
+
         /// Add any RTP extension to all packets written for the sample.
         pub fn with_extension(mut self, ext: HeaderExtension) -> Self {
+            if matches!(ext, HeaderExtension::AudioLevel(_)) {
+                self.pcm_level = None;
+            }
             self.extensions.retain(|e| !e.is_same(&ext));
 
             self.extensions.push(ext);
@@ -314,6 +572,13 @@ mod sample_writer {
         /// Creates one or more RTP packets with any extensions specified for each packet and sends
         /// them.
         pub async fn write_sample(self, sample: &Sample) -> Result<()> {
+            if let Some(level) = self.pcm_level {
+                return self
+                    .track
+                    .write_sample_with_audio_level(sample, self.extensions, level)
+                    .await;
+            }
+
             self.track
                 .write_sample_with_extensions(sample, &self.extensions)
                 .await
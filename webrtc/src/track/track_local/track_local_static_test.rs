@@ -1,12 +1,15 @@
 use std::sync::Arc;
 
 use bytes::Bytes;
+use media::audio::dtx::DtxConfig;
+use media::Sample;
 use tokio::sync::{mpsc, Mutex};
+use tokio::time::Duration;
 
 use super::track_local_static_rtp::*;
 use super::track_local_static_sample::*;
 use super::*;
-use crate::api::media_engine::{MediaEngine, MIME_TYPE_VP8};
+use crate::api::media_engine::{MediaEngine, MIME_TYPE_OPUS, MIME_TYPE_VP8};
 use crate::api::APIBuilder;
 use crate::peer_connection::configuration::RTCConfiguration;
 use crate::peer_connection::peer_connection_test::*;
@@ -432,3 +435,120 @@ async fn test_track_local_static_binding_non_blocking() -> Result<()> {
     }
 }
 */
+
+async fn write_pcm_sample(
+    track: &TrackLocalStaticSample,
+    data: &'static [u8],
+    pcm: &[i16],
+) -> Result<()> {
+    track
+        .sample_writer()
+        .with_pcm(pcm)
+        .write_sample(&Sample {
+            data: Bytes::from_static(data),
+            duration: Duration::from_millis(20),
+            ..Default::default()
+        })
+        .await
+}
+
+#[tokio::test]
+async fn test_track_local_static_sample_dtx() -> Result<()> {
+    let mut m = MediaEngine::default();
+    m.register_default_codecs()?;
+    m.register_header_extension(
+        RTCRtpHeaderExtensionCapability {
+            uri: ::sdp::extmap::AUDIO_LEVEL_URI.to_owned(),
+        },
+        RTPCodecType::Audio,
+        None,
+    )?;
+    let api = APIBuilder::new().with_media_engine(m).build();
+
+    let (mut pc_offer, mut pc_answer) = new_pair(&api).await?;
+
+    let track = Arc::new(TrackLocalStaticSample::new(
+        RTCRtpCodecCapability {
+            mime_type: MIME_TYPE_OPUS.to_owned(),
+            ..Default::default()
+        },
+        "audio".to_owned(),
+        "webrtc-rs".to_owned(),
+    ));
+    track.set_dtx(Some(DtxConfig::default())).await;
+    pc_offer
+        .add_track(Arc::clone(&track) as Arc<dyn TrackLocal + Send + Sync>)
+        .await?;
+
+    let (pkt_tx, mut pkt_rx) = mpsc::channel::<(rtp::packet::Packet, Option<u8>)>(64);
+    pc_answer.on_track(Box::new(move |track, _, _| {
+        let pkt_tx = pkt_tx.clone();
+        Box::pin(async move {
+            let audio_level_id = track
+                .params()
+                .header_extensions
+                .iter()
+                .find(|ext| ext.uri == ::sdp::extmap::AUDIO_LEVEL_URI)
+                .map(|ext| ext.id as u8);
+            while let Ok((pkt, _)) = track.read_rtp().await {
+                let audio_level = audio_level_id
+                    .and_then(|id| pkt.header.get_extension(id))
+                    .map(|ext| ext[0]);
+                if pkt_tx.send((pkt, audio_level)).await.is_err() {
+                    break;
+                }
+            }
+        })
+    }));
+
+    signal_pair(&mut pc_offer, &mut pc_answer).await?;
+
+    let voice = [i16::MAX / 2, -i16::MAX / 2].repeat(480);
+    let silence = [0i16; 960];
+
+    // Until the track is received
+    loop {
+        write_pcm_sample(&track, &[0xFC, 0xAA], &voice).await?;
+        tokio::select! {
+            _ = pkt_rx.recv() => break,
+            _ = tokio::time::sleep(Duration::from_millis(20)) => {}
+        }
+    }
+
+    write_pcm_sample(&track, &[0xFC, 0xBB], &voice).await?;
+    for _ in 0..40 {
+        write_pcm_sample(&track, &[0xFC, 0xCC], &silence).await?;
+    }
+    write_pcm_sample(&track, &[0xFC, 0xDD], &voice).await?;
+
+    let mut received = vec![];
+    while received.len() < 14 {
+        let (pkt, audio_level) = pkt_rx.recv().await.unwrap();
+        if pkt.payload[..] == [0xFC, 0xAA] {
+            continue;
+        }
+        received.push((pkt, audio_level));
+    }
+
+    // The voice, the silence during the VAD hangover, the first silent frame, a keep alive
+    // frame and the voice again
+    let payloads: Vec<&[u8]> = received.iter().map(|(p, _)| &p.payload[..]).collect();
+    let mut expected: Vec<&[u8]> = vec![&[0xFC, 0xBB]];
+    expected.extend(std::iter::repeat_n([0xFC, 0xCC].as_slice(), 11));
+    expected.extend([[0xFC].as_slice(), [0xFC, 0xDD].as_slice()]);
+    assert_eq!(payloads, expected);
+
+    let first = &received[0].0.header;
+    let last = &received[13].0.header;
+    assert_eq!(last.timestamp.wrapping_sub(first.timestamp), 41 * 960);
+
+    // Voice flag and level
+    assert_eq!(received[0].1, Some(0x80 | 6));
+    assert_eq!(received[10].1, Some(0x80 | 127));
+    assert_eq!(received[11].1, Some(127));
+    assert_eq!(received[12].1, Some(127));
+
+    close_pair_now(&pc_offer, &pc_answer).await;
+
+    Ok(())
+}
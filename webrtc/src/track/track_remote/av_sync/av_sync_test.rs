@@ -0,0 +1,128 @@
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use interceptor::noop::NoOp;
+use rtp::extension::abs_send_time_extension::{ntp2unix, unix2ntp};
+
+use super::*;
+use crate::api::media_engine::MediaEngine;
+use crate::rtp_transceiver::rtp_codec::RTPCodecType;
+
+const NTP_SECOND: u64 = 1 << 32;
+
+fn sender_report(ntp_time: u64, rtp_time: u32) -> SenderReport {
+    SenderReport {
+        ssrc: 1234,
+        ntp_time,
+        rtp_time,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_rtp_to_ntp() {
+    let mapping = RTCRtpNtpMapping {
+        ntp_time: 1000 * NTP_SECOND,
+        rtp_time: 90000,
+    };
+
+    let tests = vec![
+        (90000, 90000, Some(1000 * NTP_SECOND)),
+        (180000, 90000, Some(1001 * NTP_SECOND)),
+        (45000, 90000, Some(1000 * NTP_SECOND - NTP_SECOND / 2)),
+        (
+            90000 + 480,
+            48000,
+            Some(1000 * NTP_SECOND + NTP_SECOND / 100),
+        ),
+        (90000, 0, None),
+    ];
+    for (rtp_time, clock_rate, expected) in tests {
+        assert_eq!(
+            mapping.rtp_to_ntp(rtp_time, clock_rate),
+            expected,
+            "{rtp_time} {clock_rate}"
+        );
+    }
+
+    // Across the RTP timestamp wrap, in both directions
+    let mapping = RTCRtpNtpMapping {
+        ntp_time: 1000 * NTP_SECOND,
+        rtp_time: u32::MAX - 44999,
+    };
+    assert_eq!(mapping.rtp_to_ntp(45000, 90000), Some(1001 * NTP_SECOND));
+    let mapping = RTCRtpNtpMapping {
+        ntp_time: 1000 * NTP_SECOND,
+        rtp_time: 45000,
+    };
+    assert_eq!(
+        mapping.rtp_to_ntp(u32::MAX - 44999, 90000),
+        Some(999 * NTP_SECOND)
+    );
+
+    // Before the NTP epoch
+    let mapping = RTCRtpNtpMapping {
+        ntp_time: 0,
+        rtp_time: 90000,
+    };
+    assert_eq!(mapping.rtp_to_ntp(0, 90000), None);
+}
+
+#[test]
+fn test_av_sync_sender_report() {
+    let mut av_sync = AvSync::default();
+    assert_eq!(av_sync.sender_report(), None);
+
+    av_sync.on_sender_report(&sender_report(1000 * NTP_SECOND, 3000));
+    av_sync.on_sender_report(&sender_report(1005 * NTP_SECOND, 453000));
+    assert_eq!(
+        av_sync.sender_report(),
+        Some(RTCRtpNtpMapping {
+            ntp_time: 1005 * NTP_SECOND,
+            rtp_time: 453000,
+        })
+    );
+}
+
+#[test]
+fn test_relative_delay() {
+    let now = SystemTime::now();
+    let ntp_now = unix2ntp(now);
+
+    // Audio and video captured at the same time by the sender
+    let mut audio = AvSync::default();
+    audio.on_sender_report(&sender_report(ntp_now, 48000));
+    let mut video = AvSync::default();
+    video.on_sender_report(&sender_report(ntp_now, 90000));
+    assert!(relative_delay(&audio, 48000, &video, 90000).is_none());
+
+    // Audio captured 1s after the reports, and received 100ms later.
+    audio.on_rtp(96000, ntp2unix(ntp_now) + Duration::from_millis(1100));
+    assert!(relative_delay(&audio, 48000, &video, 90000).is_none());
+
+    // Video captured 1.5s after the reports, and received 300ms later, so 200ms after the
+    // audio captured at the same time.
+    video.on_rtp(225000, ntp2unix(ntp_now) + Duration::from_millis(1800));
+    let delay = relative_delay(&audio, 48000, &video, 90000).unwrap();
+    assert!((delay - 0.2).abs() < 0.001, "{delay}");
+    let delay = relative_delay(&video, 90000, &audio, 48000).unwrap();
+    assert!((delay + 0.2).abs() < 0.001, "{delay}");
+}
+
+#[test]
+fn test_av_sync_offset_same_track() {
+    let track = TrackRemote::new(
+        1500,
+        RTPCodecType::Audio,
+        1234,
+        "".into(),
+        Weak::new(),
+        Arc::new(MediaEngine::default()),
+        Arc::new(NoOp),
+    );
+    track.av_sync.lock().on_sender_report(&sender_report(0, 0));
+    track.av_sync.lock().on_rtp(0, SystemTime::now());
+
+    // Must not lock the track twice
+    assert!(av_sync_offset(&track, &track).is_none());
+}
@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod av_sync_test;
+
+use std::time::SystemTime;
+
+use rtcp::sender_report::SenderReport;
+
+use super::TrackRemote;
+
+/// RTCRtpNtpMapping is the pair of NTP and RTP timestamps carried in a RTCP sender report. It
+/// maps the RTP clock of a track to the wall clock of the sender.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RTCRtpNtpMapping {
+    /// Wall clock time of the sender, in 64 bit NTP format
+    pub ntp_time: u64,
+    /// RTP timestamp corresponding to ntp_time
+    pub rtp_time: u32,
+}
+
+impl RTCRtpNtpMapping {
+    /// rtp_to_ntp converts a RTP timestamp of the track to the wall clock of the sender, using
+    /// the clock rate of the codec. RTP timestamps before and after the sender report are
+    /// supported, as long as they are less than half of the RTP timestamp range away.
+    pub fn rtp_to_ntp(&self, rtp_time: u32, clock_rate: u32) -> Option<u64> {
+        if clock_rate == 0 {
+            return None;
+        }
+
+        let diff = rtp_time.wrapping_sub(self.rtp_time) as i32 as i128;
+        let ntp_time = self.ntp_time as i128 + (diff << 32) / clock_rate as i128;
+        u64::try_from(ntp_time).ok()
+    }
+}
+
+/// AvSync tracks the timing of a remote track needed to synchronize it with the other tracks
+/// of its media stream.
+#[derive(Default, Debug, Copy, Clone)]
+pub(crate) struct AvSync {
+    /// The most recent sender report
+    sender_report: Option<RTCRtpNtpMapping>,
+    /// RTP timestamp of the most recent packet, and the local time it was read
+    last_packet: Option<(u32, SystemTime)>,
+}
+
+impl AvSync {
+    pub(crate) fn on_sender_report(&mut self, sr: &SenderReport) {
+        self.sender_report = Some(RTCRtpNtpMapping {
+            ntp_time: sr.ntp_time,
+            rtp_time: sr.rtp_time,
+        });
+    }
+
+    pub(crate) fn on_rtp(&mut self, rtp_time: u32, now: SystemTime) {
+        self.last_packet = Some((rtp_time, now));
+    }
+
+    pub(crate) fn sender_report(&self) -> Option<RTCRtpNtpMapping> {
+        self.sender_report
+    }
+
+    /// last_packet_ntp returns the sender wall clock time of the most recent packet, and the
+    /// local time it was read
+    fn last_packet_ntp(&self, clock_rate: u32) -> Option<(u64, SystemTime)> {
+        let (rtp_time, received_at) = self.last_packet?;
+        let ntp_time = self.sender_report?.rtp_to_ntp(rtp_time, clock_rate)?;
+        Some((ntp_time, received_at))
+    }
+}
+
+/// relative_delay returns, in seconds, how much later `b` is received than `a` for media
+/// captured at the same time.
+pub(crate) fn relative_delay(
+    a: &AvSync,
+    a_clock_rate: u32,
+    b: &AvSync,
+    b_clock_rate: u32,
+) -> Option<f64> {
+    let (a_ntp, a_received_at) = a.last_packet_ntp(a_clock_rate)?;
+    let (b_ntp, b_received_at) = b.last_packet_ntp(b_clock_rate)?;
+
+    let captured = (b_ntp as i128 - a_ntp as i128) as f64 / (1u64 << 32) as f64;
+    let received = match b_received_at.duration_since(a_received_at) {
+        Ok(d) => d.as_secs_f64(),
+        Err(err) => -err.duration().as_secs_f64(),
+    };
+    Some(received - captured)
+}
+
+/// av_sync_offset returns, in seconds, how much later the video track of a media stream is
+/// received than the audio track for media captured at the same time. A recorder or player
+/// delays the audio by this amount to keep it in sync with the video, or the video if the
+/// offset is negative.
+///
+/// The offset is computed from the RTCP sender reports of both tracks and the last packet read
+/// from each, so it is only available once both have received a sender report and a packet,
+/// and both tracks are read continuously. Returns None until then, or if the tracks don't
+/// belong to the same media stream.
+pub fn av_sync_offset(audio: &TrackRemote, video: &TrackRemote) -> Option<f64> {
+    if audio.stream_id() != video.stream_id() {
+        return None;
+    }
+
+    let audio_clock_rate = audio.codec().capability.clock_rate;
+    let video_clock_rate = video.codec().capability.clock_rate;
+    // Copied under their own lock, so that both locks are never held at once
+    let audio_sync = *audio.av_sync.lock();
+    let video_sync = *video.av_sync.lock();
+    relative_delay(&audio_sync, audio_clock_rate, &video_sync, video_clock_rate)
+}
@@ -1,20 +1,31 @@
+pub mod av_sync;
+
 use std::collections::VecDeque;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::atomic::Ordering;
 use std::sync::{Arc, Weak};
+use std::time::SystemTime;
 
 use arc_swap::ArcSwapOption;
+use av_sync::{AvSync, RTCRtpNtpMapping};
 use interceptor::{Attributes, Interceptor};
 use portable_atomic::{AtomicU32, AtomicU8, AtomicUsize};
+use rtp::extension::abs_capture_time_extension::AbsCaptureTimeExtension;
 use smol_str::SmolStr;
 use tokio::sync::Mutex;
+use tokio::time::Instant;
+use util::marshal::Unmarshal;
 use util::sync::Mutex as SyncMutex;
 
 use crate::api::media_engine::MediaEngine;
 use crate::error::{Error, Result};
 use crate::rtp_transceiver::rtp_codec::{RTCRtpCodecParameters, RTCRtpParameters, RTPCodecType};
+use crate::rtp_transceiver::rtp_receiver::keyframe_request::{
+    keyframe_request_kind, KeyframeRequester,
+};
 use crate::rtp_transceiver::rtp_receiver::RTPReceiverInternal;
+use crate::rtp_transceiver::rtp_transform::{is_keyframe, FrameAssembler, RTCEncodedFrame};
 use crate::rtp_transceiver::{PayloadType, SSRC};
 
 lazy_static! {
@@ -33,6 +44,8 @@ struct Handlers {
 #[derive(Default)]
 struct TrackRemoteInternal {
     peeked: VecDeque<(rtp::packet::Packet, Attributes)>,
+    frame_assembler: FrameAssembler,
+    keyframe_requester: KeyframeRequester,
 }
 
 /// TrackRemote represents a single inbound source of media
@@ -57,6 +70,7 @@ pub struct TrackRemote {
 
     receiver: Option<Weak<RTPReceiverInternal>>,
     internal: Mutex<TrackRemoteInternal>,
+    av_sync: SyncMutex<AvSync>,
 }
 
 impl std::fmt::Debug for TrackRemote {
@@ -101,6 +115,7 @@ impl TrackRemote {
             handlers: Default::default(),
 
             internal: Default::default(),
+            av_sync: Default::default(),
         }
     }
 
@@ -182,6 +197,26 @@ impl TrackRemote {
         *c = codec;
     }
 
+    /// sender_report returns the NTP and RTP timestamps of the most recent RTCP sender report
+    /// for the track. Sender reports are processed as they are received, whether or not the
+    /// application reads RTCP.
+    pub fn sender_report(&self) -> Option<RTCRtpNtpMapping> {
+        self.av_sync.lock().sender_report()
+    }
+
+    /// rtp_to_ntp converts a RTP timestamp of the track to the wall clock of the sender, in 64
+    /// bit NTP format, using the most recent RTCP sender report. The result can be compared
+    /// with the other tracks of the same sender, see av_sync::av_sync_offset. Returns None
+    /// until a sender report is received.
+    pub fn rtp_to_ntp(&self, rtp_time: u32) -> Option<u64> {
+        let clock_rate = self.codec().capability.clock_rate;
+        self.sender_report()?.rtp_to_ntp(rtp_time, clock_rate)
+    }
+
+    pub(crate) fn on_sender_report(&self, sr: &rtcp::sender_report::SenderReport) {
+        self.av_sync.lock().on_sender_report(sr);
+    }
+
     pub fn params(&self) -> RTCRtpParameters {
         let p = self.params.lock();
         p.clone()
@@ -232,9 +267,76 @@ impl TrackRemote {
 
         let (pkt, attributes) = receiver.read_rtp(b, self.tid).await?;
         self.check_and_update_track(&pkt).await?;
+        self.av_sync
+            .lock()
+            .on_rtp(pkt.header.timestamp, SystemTime::now());
+        self.handle_keyframe_request(&receiver, &pkt).await;
         Ok((pkt, attributes))
     }
 
+    /// handle_keyframe_request requests a keyframe if the packet shows the decoder can't make
+    /// progress, according to the keyframe request policy of the receiver
+    async fn handle_keyframe_request(
+        &self,
+        receiver: &RTPReceiverInternal,
+        pkt: &rtp::packet::Packet,
+    ) {
+        if self.kind() != RTPCodecType::Video {
+            return;
+        }
+        let Some(policy) = receiver.keyframe_request_policy() else {
+            return;
+        };
+
+        let codec = self.codec();
+        let request = {
+            let mut internal = self.internal.lock().await;
+            let now = Instant::now();
+            if !internal.keyframe_requester.on_packet(
+                pkt,
+                &codec.capability.mime_type,
+                &policy,
+                now,
+            ) {
+                return;
+            }
+            let Some(kind) = keyframe_request_kind(&codec) else {
+                return;
+            };
+            internal.keyframe_requester.request(kind, self.ssrc(), now)
+        };
+
+        if let Err(err) = receiver.write_rtcp(&[request]).await {
+            log::warn!(
+                "failed to request keyframe for ssrc {}: {}",
+                self.ssrc(),
+                err
+            );
+        }
+    }
+
+    /// request_keyframe asks the remote sender for a keyframe, with a PLI or a FIR depending on
+    /// the feedback negotiated for the codec of the track. Returns
+    /// ErrKeyframeRequestNotNegotiated if neither was negotiated.
+    pub async fn request_keyframe(&self) -> Result<()> {
+        let receiver = match self.receiver.as_ref().and_then(|r| r.upgrade()) {
+            Some(r) => r,
+            None => return Err(Error::ErrRTPReceiverNil),
+        };
+        let kind =
+            keyframe_request_kind(&self.codec()).ok_or(Error::ErrKeyframeRequestNotNegotiated)?;
+
+        let request = {
+            let mut internal = self.internal.lock().await;
+            internal
+                .keyframe_requester
+                .request(kind, self.ssrc(), Instant::now())
+        };
+        receiver.write_rtcp(&[request]).await?;
+
+        Ok(())
+    }
+
     /// check_and_update_track checks payloadType for every incoming packet
     /// once a different payloadType is detected the track will be updated
     pub(crate) async fn check_and_update_track(&self, pkt: &rtp::packet::Packet) -> Result<()> {
@@ -276,6 +378,74 @@ impl TrackRemote {
         Ok((pkt, attributes))
     }
 
+    /// read_frame reads RTP packets until a whole encoded frame has been received, and returns it
+    /// after applying the transform installed on the RTPReceiver. Frames with lost packets are
+    /// skipped.
+    ///
+    /// Returns ErrRTPTransformCodecUnsupported for video codecs other than VP8, VP9 and H264.
+    pub async fn read_frame(&self) -> Result<RTCEncodedFrame> {
+        let kind = self.kind();
+        loop {
+            let (pkt, _) = self.read_rtp().await?;
+            let mime_type = self.codec().capability.mime_type;
+
+            let frame = {
+                let mut internal = self.internal.lock().await;
+                internal.frame_assembler.push(pkt, kind, &mime_type)?
+            };
+            let Some(frame) = frame else {
+                continue;
+            };
+
+            let transform = self
+                .receiver
+                .as_ref()
+                .and_then(|r| r.upgrade())
+                .and_then(|r| r.transform.get());
+            let Some(transform) = transform else {
+                return Ok(frame);
+            };
+            let mut metadata = frame.metadata.clone();
+            if let Some(transformed) = transform.transform(frame).await? {
+                // The assembler saw the payload before the transform, e.g. SFrame ciphertext
+                if kind == RTPCodecType::Video {
+                    metadata.is_keyframe = is_keyframe(&mime_type, &transformed.data);
+                }
+                return Ok(RTCEncodedFrame {
+                    data: transformed.data,
+                    metadata,
+                });
+            }
+        }
+    }
+
+    /// header_extension unmarshals the RTP header extension negotiated for `uri` from a packet
+    /// read from this track. Returns `None` if the extension was not negotiated, is absent from
+    /// the packet or is malformed.
+    pub fn header_extension<T: Unmarshal>(
+        &self,
+        pkt: &rtp::packet::Packet,
+        uri: &str,
+    ) -> Option<T> {
+        let id = {
+            let params = self.params.lock();
+            params
+                .header_extensions
+                .iter()
+                .find(|ext| ext.uri == uri)
+                .map(|ext| ext.id)?
+        };
+        let mut payload = pkt.header.get_extension(id as u8)?;
+
+        T::unmarshal(&mut payload).ok()
+    }
+
+    /// abs_capture_time returns the absolute capture time extension of a packet read from this
+    /// track, if present.
+    pub fn abs_capture_time(&self, pkt: &rtp::packet::Packet) -> Option<AbsCaptureTimeExtension> {
+        self.header_extension(pkt, sdp::extmap::ABS_CAPTURE_TIME_URI)
+    }
+
     /// peek is like Read, but it doesn't discard the packet read
     pub(crate) async fn peek(&self, b: &mut [u8]) -> Result<(rtp::packet::Packet, Attributes)> {
         let (pkt, a) = self.read(b).await?;